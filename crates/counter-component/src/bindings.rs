@@ -98,560 +98,7770 @@ pub mod vello {
                     }
                 }
             }
-            #[allow(unused_unsafe, clippy::all)]
-            /// Clear the current scene background (call once per frame before drawing).
-            pub fn clear(c: Color) -> () {
-                unsafe {
-                    let super::super::super::vello::canvas::math::Color {
-                        r: r0,
-                        g: g0,
-                        b: b0,
-                        a: a0,
-                    } = c;
-                    #[cfg(target_arch = "wasm32")]
-                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
-                    unsafe extern "C" {
-                        #[link_name = "clear"]
-                        fn wit_import1(_: f32, _: f32, _: f32, _: f32);
-                    }
-                    #[cfg(not(target_arch = "wasm32"))]
-                    unsafe extern "C" fn wit_import1(_: f32, _: f32, _: f32, _: f32) {
-                        unreachable!()
+            #[repr(u8)]
+            #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+            pub enum WindowLevel {
+                Normal,
+                AlwaysOnTop,
+                AlwaysOnBottom,
+            }
+            impl ::core::fmt::Debug for WindowLevel {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        WindowLevel::Normal => {
+                            f.debug_tuple("WindowLevel::Normal").finish()
+                        }
+                        WindowLevel::AlwaysOnTop => {
+                            f.debug_tuple("WindowLevel::AlwaysOnTop").finish()
+                        }
+                        WindowLevel::AlwaysOnBottom => {
+                            f.debug_tuple("WindowLevel::AlwaysOnBottom").finish()
+                        }
                     }
-                    unsafe {
-                        wit_import1(
-                            _rt::as_f32(r0),
-                            _rt::as_f32(g0),
-                            _rt::as_f32(b0),
-                            _rt::as_f32(a0),
-                        )
-                    };
                 }
             }
-            #[allow(unused_unsafe, clippy::all)]
-            /// Fill axis-aligned rectangle specified by top-left + size (logical pixels).
-            pub fn fill_rect(origin: Vec2, size: Vec2, color: Color) -> () {
-                unsafe {
-                    let super::super::super::vello::canvas::math::Vec2 {
-                        x: x0,
-                        y: y0,
-                    } = origin;
-                    let super::super::super::vello::canvas::math::Vec2 {
-                        x: x1,
-                        y: y1,
-                    } = size;
-                    let super::super::super::vello::canvas::math::Color {
-                        r: r2,
-                        g: g2,
-                        b: b2,
-                        a: a2,
-                    } = color;
-                    #[cfg(target_arch = "wasm32")]
-                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
-                    unsafe extern "C" {
-                        #[link_name = "fill-rect"]
-                        fn wit_import3(
-                            _: f32,
-                            _: f32,
-                            _: f32,
-                            _: f32,
-                            _: f32,
-                            _: f32,
-                            _: f32,
-                            _: f32,
-                        );
+            impl WindowLevel {
+                #[doc(hidden)]
+                pub unsafe fn _lift(val: u8) -> WindowLevel {
+                    if !cfg!(debug_assertions) {
+                        return ::core::mem::transmute(val);
                     }
-                    #[cfg(not(target_arch = "wasm32"))]
-                    unsafe extern "C" fn wit_import3(
-                        _: f32,
-                        _: f32,
-                        _: f32,
-                        _: f32,
-                        _: f32,
-                        _: f32,
-                        _: f32,
-                        _: f32,
-                    ) {
-                        unreachable!()
+                    match val {
+                        0 => WindowLevel::Normal,
+                        1 => WindowLevel::AlwaysOnTop,
+                        2 => WindowLevel::AlwaysOnBottom,
+                        _ => panic!("invalid enum discriminant"),
                     }
-                    unsafe {
-                        wit_import3(
-                            _rt::as_f32(x0),
-                            _rt::as_f32(y0),
-                            _rt::as_f32(x1),
-                            _rt::as_f32(y1),
-                            _rt::as_f32(r2),
-                            _rt::as_f32(g2),
-                            _rt::as_f32(b2),
-                            _rt::as_f32(a2),
-                        )
-                    };
                 }
             }
-            #[allow(unused_unsafe, clippy::all)]
-            /// Draw text anchored at baseline origin using a bundled font.
-            pub fn draw_text(text: &str, origin: Vec2, size: f32, color: Color) -> () {
-                unsafe {
-                    let vec0 = text;
-                    let ptr0 = vec0.as_ptr().cast::<u8>();
-                    let len0 = vec0.len();
-                    let super::super::super::vello::canvas::math::Vec2 {
-                        x: x1,
-                        y: y1,
-                    } = origin;
-                    let super::super::super::vello::canvas::math::Color {
-                        r: r2,
-                        g: g2,
-                        b: b2,
-                        a: a2,
-                    } = color;
-                    #[cfg(target_arch = "wasm32")]
-                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
-                    unsafe extern "C" {
-                        #[link_name = "draw-text"]
-                        fn wit_import3(
-                            _: *mut u8,
-                            _: usize,
-                            _: f32,
-                            _: f32,
-                            _: f32,
-                            _: f32,
-                            _: f32,
-                            _: f32,
-                            _: f32,
-                        );
-                    }
-                    #[cfg(not(target_arch = "wasm32"))]
-                    unsafe extern "C" fn wit_import3(
-                        _: *mut u8,
-                        _: usize,
-                        _: f32,
-                        _: f32,
-                        _: f32,
-                        _: f32,
-                        _: f32,
-                        _: f32,
-                        _: f32,
-                    ) {
-                        unreachable!()
+            /// Bundled typefaces: `sans` is Cantarell Regular, `sans-bold` is DejaVu Sans Bold and
+            /// `monospace` is DejaVu Sans Mono.
+            #[repr(u8)]
+            #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+            pub enum FontFamily {
+                Sans,
+                SansBold,
+                Monospace,
+            }
+            impl ::core::fmt::Debug for FontFamily {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        FontFamily::Sans => f.debug_tuple("FontFamily::Sans").finish(),
+                        FontFamily::SansBold => {
+                            f.debug_tuple("FontFamily::SansBold").finish()
+                        }
+                        FontFamily::Monospace => {
+                            f.debug_tuple("FontFamily::Monospace").finish()
+                        }
                     }
-                    unsafe {
-                        wit_import3(
-                            ptr0.cast_mut(),
-                            len0,
-                            _rt::as_f32(x1),
-                            _rt::as_f32(y1),
-                            _rt::as_f32(&size),
-                            _rt::as_f32(r2),
-                            _rt::as_f32(g2),
-                            _rt::as_f32(b2),
-                            _rt::as_f32(a2),
-                        )
-                    };
                 }
             }
-            #[allow(unused_unsafe, clippy::all)]
-            /// Request another animation frame; host coalesces multiple calls.
-            pub fn request_frame() -> () {
-                unsafe {
-                    #[cfg(target_arch = "wasm32")]
-                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
-                    unsafe extern "C" {
-                        #[link_name = "request-frame"]
-                        fn wit_import0();
+            impl FontFamily {
+                #[doc(hidden)]
+                pub unsafe fn _lift(val: u8) -> FontFamily {
+                    if !cfg!(debug_assertions) {
+                        return ::core::mem::transmute(val);
                     }
-                    #[cfg(not(target_arch = "wasm32"))]
-                    unsafe extern "C" fn wit_import0() {
-                        unreachable!()
+                    match val {
+                        0 => FontFamily::Sans,
+                        1 => FontFamily::SansBold,
+                        2 => FontFamily::Monospace,
+                        _ => panic!("invalid enum discriminant"),
                     }
-                    unsafe { wit_import0() };
                 }
             }
-            #[allow(unused_unsafe, clippy::all)]
-            /// Debug logging surfaced via host console.
-            pub fn log(level: LogLevel, message: &str) -> () {
-                unsafe {
-                    let vec0 = message;
-                    let ptr0 = vec0.as_ptr().cast::<u8>();
-                    let len0 = vec0.len();
-                    #[cfg(target_arch = "wasm32")]
-                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
-                    unsafe extern "C" {
-                        #[link_name = "log"]
-                        fn wit_import1(_: i32, _: *mut u8, _: usize);
-                    }
-                    #[cfg(not(target_arch = "wasm32"))]
-                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8, _: usize) {
-                        unreachable!()
+            /// Paragraph direction for bidirectional text: `auto` takes the direction of the first
+            /// strong letter, left-to-right when there is none. Right-to-left runs inside either
+            /// direction are reordered for display whatever the paragraph direction.
+            #[repr(u8)]
+            #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+            pub enum TextDirection {
+                Auto,
+                Ltr,
+                Rtl,
+            }
+            impl ::core::fmt::Debug for TextDirection {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        TextDirection::Auto => {
+                            f.debug_tuple("TextDirection::Auto").finish()
+                        }
+                        TextDirection::Ltr => {
+                            f.debug_tuple("TextDirection::Ltr").finish()
+                        }
+                        TextDirection::Rtl => {
+                            f.debug_tuple("TextDirection::Rtl").finish()
+                        }
                     }
-                    unsafe { wit_import1(level.clone() as i32, ptr0.cast_mut(), len0) };
                 }
             }
-        }
-    }
-}
-#[rustfmt::skip]
-#[allow(dead_code, clippy::all)]
-pub mod exports {
-    pub mod vello {
-        pub mod canvas {
-            /// Events/lifecycle callbacks the guest exports.
-            #[allow(dead_code, async_fn_in_trait, unused_imports, clippy::all)]
-            pub mod app {
-                #[used]
+            impl TextDirection {
                 #[doc(hidden)]
-                static __FORCE_SECTION_REF: fn() = super::super::super::super::__link_custom_section_describing_imports;
-                use super::super::super::super::_rt;
-                pub type Vec2 = super::super::super::super::vello::canvas::math::Vec2;
-                #[repr(C)]
-                #[derive(Clone, Copy)]
-                pub struct LogicalSize {
-                    pub width: f32,
-                    pub height: f32,
-                    pub scale_factor: f32,
-                }
-                impl ::core::fmt::Debug for LogicalSize {
-                    fn fmt(
-                        &self,
-                        f: &mut ::core::fmt::Formatter<'_>,
-                    ) -> ::core::fmt::Result {
-                        f.debug_struct("LogicalSize")
-                            .field("width", &self.width)
-                            .field("height", &self.height)
-                            .field("scale-factor", &self.scale_factor)
-                            .finish()
+                pub unsafe fn _lift(val: u8) -> TextDirection {
+                    if !cfg!(debug_assertions) {
+                        return ::core::mem::transmute(val);
+                    }
+                    match val {
+                        0 => TextDirection::Auto,
+                        1 => TextDirection::Ltr,
+                        2 => TextDirection::Rtl,
+                        _ => panic!("invalid enum discriminant"),
                     }
                 }
-                #[repr(C)]
-                #[derive(Clone, Copy)]
-                pub struct PointerButton {
-                    pub primary: bool,
-                    pub secondary: bool,
+            }
+            /// `horizontal-tb` sets lines across, each below the last; `vertical-rl` stacks upright
+            /// characters top to bottom, each line a column to the left of the last, as in
+            /// vertical Chinese and Japanese.
+            #[repr(u8)]
+            #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+            pub enum WritingMode {
+                HorizontalTb,
+                VerticalRl,
+            }
+            impl ::core::fmt::Debug for WritingMode {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        WritingMode::HorizontalTb => {
+                            f.debug_tuple("WritingMode::HorizontalTb").finish()
+                        }
+                        WritingMode::VerticalRl => {
+                            f.debug_tuple("WritingMode::VerticalRl").finish()
+                        }
+                    }
                 }
-                impl ::core::fmt::Debug for PointerButton {
-                    fn fmt(
-                        &self,
-                        f: &mut ::core::fmt::Formatter<'_>,
-                    ) -> ::core::fmt::Result {
-                        f.debug_struct("PointerButton")
-                            .field("primary", &self.primary)
-                            .field("secondary", &self.secondary)
-                            .finish()
+            }
+            impl WritingMode {
+                #[doc(hidden)]
+                pub unsafe fn _lift(val: u8) -> WritingMode {
+                    if !cfg!(debug_assertions) {
+                        return ::core::mem::transmute(val);
+                    }
+                    match val {
+                        0 => WritingMode::HorizontalTb,
+                        1 => WritingMode::VerticalRl,
+                        _ => panic!("invalid enum discriminant"),
                     }
                 }
-                #[repr(C)]
-                #[derive(Clone, Copy)]
-                pub struct Modifiers {
-                    pub shift: bool,
-                    pub ctrl: bool,
-                    pub alt: bool,
-                    pub meta: bool,
+            }
+            /// Axis-aligned rectangle: top-left corner and size, in logical pixels.
+            #[repr(C)]
+            #[derive(Clone, Copy)]
+            pub struct Rect {
+                pub origin: Vec2,
+                pub size: Vec2,
+            }
+            impl ::core::fmt::Debug for Rect {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("Rect")
+                        .field("origin", &self.origin)
+                        .field("size", &self.size)
+                        .finish()
                 }
-                impl ::core::fmt::Debug for Modifiers {
-                    fn fmt(
-                        &self,
-                        f: &mut ::core::fmt::Formatter<'_>,
-                    ) -> ::core::fmt::Result {
-                        f.debug_struct("Modifiers")
-                            .field("shift", &self.shift)
-                            .field("ctrl", &self.ctrl)
-                            .field("alt", &self.alt)
-                            .field("meta", &self.meta)
-                            .finish()
-                    }
+            }
+            /// Part of a `draw-rich-text` paragraph; `sans-bold` gives bold text.
+            #[derive(Clone)]
+            pub struct StyledSpan {
+                pub text: _rt::String,
+                pub color: Color,
+                pub size: f32,
+                pub font: FontFamily,
+            }
+            impl ::core::fmt::Debug for StyledSpan {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("StyledSpan")
+                        .field("text", &self.text)
+                        .field("color", &self.color)
+                        .field("size", &self.size)
+                        .field("font", &self.font)
+                        .finish()
                 }
-                #[repr(u8)]
-                #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
-                pub enum PointerKind {
-                    Mouse,
-                    Touch,
-                    Pen,
+            }
+            /// Cells of a `draw-text-grid` sharing colours: each character of `text` fills one
+            /// cell, drawn in `fg` over `bg` when it is set.
+            #[derive(Clone)]
+            pub struct GridCell {
+                pub text: _rt::String,
+                pub fg: Color,
+                pub bg: Option<Color>,
+            }
+            impl ::core::fmt::Debug for GridCell {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("GridCell")
+                        .field("text", &self.text)
+                        .field("fg", &self.fg)
+                        .field("bg", &self.bg)
+                        .finish()
                 }
-                impl ::core::fmt::Debug for PointerKind {
-                    fn fmt(
-                        &self,
-                        f: &mut ::core::fmt::Formatter<'_>,
-                    ) -> ::core::fmt::Result {
-                        match self {
-                            PointerKind::Mouse => {
-                                f.debug_tuple("PointerKind::Mouse").finish()
-                            }
-                            PointerKind::Touch => {
-                                f.debug_tuple("PointerKind::Touch").finish()
-                            }
-                            PointerKind::Pen => {
-                                f.debug_tuple("PointerKind::Pen").finish()
-                            }
+            }
+            /// How `draw-bars` maps values to bars: `min` at the bottom of the rectangle and `max`
+            /// at the top, each bar growing from `baseline`, with `gap` (0 to 1) of every bar's
+            /// slot left empty.
+            #[repr(C)]
+            #[derive(Clone, Copy)]
+            pub struct BarStyle {
+                pub color: Color,
+                pub min: f32,
+                pub max: f32,
+                pub baseline: f32,
+                pub gap: f32,
+            }
+            impl ::core::fmt::Debug for BarStyle {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("BarStyle")
+                        .field("color", &self.color)
+                        .field("min", &self.min)
+                        .field("max", &self.max)
+                        .field("baseline", &self.baseline)
+                        .field("gap", &self.gap)
+                        .finish()
+                }
+            }
+            /// Cells of a `draw-heatmap`, `values` in rows of `columns`, coloured from `min` to `max`.
+            #[derive(Clone)]
+            pub struct Heatmap {
+                pub columns: u32,
+                pub values: _rt::Vec<f32>,
+                pub min: f32,
+                pub max: f32,
+            }
+            impl ::core::fmt::Debug for Heatmap {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("Heatmap")
+                        .field("columns", &self.columns)
+                        .field("values", &self.values)
+                        .field("min", &self.min)
+                        .field("max", &self.max)
+                        .finish()
+                }
+            }
+            /// Handle returned by `create-image`.
+            pub type ImageId = u32;
+            /// Handle returned by `load-svg`.
+            pub type PictureId = u32;
+            /// Handle returned by `load-lottie`.
+            pub type AnimationId = u32;
+            /// Handle returned by `decode-image`.
+            pub type AnimatedImageId = u32;
+            /// Pixel size, how long each frame shows in order, and how many times the animation
+            /// plays (0 means forever). Still images report a single frame.
+            #[derive(Clone)]
+            pub struct AnimatedImageMetadata {
+                pub width: u32,
+                pub height: u32,
+                pub frame_durations_ms: _rt::Vec<u32>,
+                pub plays: u32,
+            }
+            impl ::core::fmt::Debug for AnimatedImageMetadata {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("AnimatedImageMetadata")
+                        .field("width", &self.width)
+                        .field("height", &self.height)
+                        .field("frame-durations-ms", &self.frame_durations_ms)
+                        .field("plays", &self.plays)
+                        .finish()
+                }
+            }
+            /// Handle returned by `create-pixel-buffer`.
+            pub type PixelBufferId = u32;
+            /// Handle returned by `register-shader`.
+            pub type ShaderId = u32;
+            /// Handle returned by `create-emitter`.
+            pub type EmitterId = u32;
+            /// How an emitter spawns particles. Each starts at `position` moving `speed` logical
+            /// pixels per second (give or take `speed-jitter`) in a direction up to `spread`
+            /// radians either side of `angle`, where 0 points right and positive angles turn
+            /// clockwise. Particles accelerate by `gravity` (pixels per second squared) and shrink
+            /// or grow from `start-size` to `end-size` across while fading from `start-color` to
+            /// `end-color` over `lifetime-ms`. At most `max-particles` are alive at once.
+            #[repr(C)]
+            #[derive(Clone, Copy)]
+            pub struct EmitterConfig {
+                pub position: Vec2,
+                pub rate: f32,
+                pub max_particles: u32,
+                pub lifetime_ms: f32,
+                pub angle: f32,
+                pub spread: f32,
+                pub speed: f32,
+                pub speed_jitter: f32,
+                pub gravity: Vec2,
+                pub start_size: f32,
+                pub end_size: f32,
+                pub start_color: Color,
+                pub end_color: Color,
+            }
+            impl ::core::fmt::Debug for EmitterConfig {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("EmitterConfig")
+                        .field("position", &self.position)
+                        .field("rate", &self.rate)
+                        .field("max-particles", &self.max_particles)
+                        .field("lifetime-ms", &self.lifetime_ms)
+                        .field("angle", &self.angle)
+                        .field("spread", &self.spread)
+                        .field("speed", &self.speed)
+                        .field("speed-jitter", &self.speed_jitter)
+                        .field("gravity", &self.gravity)
+                        .field("start-size", &self.start_size)
+                        .field("end-size", &self.end_size)
+                        .field("start-color", &self.start_color)
+                        .field("end-color", &self.end_color)
+                        .finish()
+                }
+            }
+            /// Handle returned by `open-camera`.
+            pub type CameraId = u32;
+            /// `pending` waits for the user to answer the host's permission prompt; `failed` means
+            /// the device could not start or stopped delivering frames.
+            #[repr(u8)]
+            #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+            pub enum CameraState {
+                Pending,
+                Streaming,
+                Denied,
+                Failed,
+            }
+            impl ::core::fmt::Debug for CameraState {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        CameraState::Pending => {
+                            f.debug_tuple("CameraState::Pending").finish()
+                        }
+                        CameraState::Streaming => {
+                            f.debug_tuple("CameraState::Streaming").finish()
+                        }
+                        CameraState::Denied => {
+                            f.debug_tuple("CameraState::Denied").finish()
+                        }
+                        CameraState::Failed => {
+                            f.debug_tuple("CameraState::Failed").finish()
                         }
                     }
                 }
-                impl PointerKind {
-                    #[doc(hidden)]
-                    pub unsafe fn _lift(val: u8) -> PointerKind {
-                        if !cfg!(debug_assertions) {
-                            return ::core::mem::transmute(val);
+            }
+            impl CameraState {
+                #[doc(hidden)]
+                pub unsafe fn _lift(val: u8) -> CameraState {
+                    if !cfg!(debug_assertions) {
+                        return ::core::mem::transmute(val);
+                    }
+                    match val {
+                        0 => CameraState::Pending,
+                        1 => CameraState::Streaming,
+                        2 => CameraState::Denied,
+                        3 => CameraState::Failed,
+                        _ => panic!("invalid enum discriminant"),
+                    }
+                }
+            }
+            /// Pixel size of the newest frame; `sequence` grows whenever a new frame arrives.
+            #[repr(C)]
+            #[derive(Clone, Copy)]
+            pub struct CameraFrameMetadata {
+                pub width: u32,
+                pub height: u32,
+                pub sequence: u64,
+            }
+            impl ::core::fmt::Debug for CameraFrameMetadata {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("CameraFrameMetadata")
+                        .field("width", &self.width)
+                        .field("height", &self.height)
+                        .field("sequence", &self.sequence)
+                        .finish()
+                }
+            }
+            /// Intrinsic size in logical pixels, length of one loop and authored frame rate.
+            #[repr(C)]
+            #[derive(Clone, Copy)]
+            pub struct AnimationMetadata {
+                pub size: Vec2,
+                pub duration_ms: f64,
+                pub frame_rate: f32,
+            }
+            impl ::core::fmt::Debug for AnimationMetadata {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("AnimationMetadata")
+                        .field("size", &self.size)
+                        .field("duration-ms", &self.duration_ms)
+                        .field("frame-rate", &self.frame_rate)
+                        .finish()
+                }
+            }
+            /// How `fill-pattern` repeats its image: `repeat` tiles in both directions, `repeat-x`
+            /// tiles a single row along the top of the area, and `mirror` tiles with every other
+            /// copy flipped so edges meet seamlessly.
+            #[repr(u8)]
+            #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+            pub enum PatternRepeat {
+                Repeat,
+                RepeatX,
+                Mirror,
+            }
+            impl ::core::fmt::Debug for PatternRepeat {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        PatternRepeat::Repeat => {
+                            f.debug_tuple("PatternRepeat::Repeat").finish()
                         }
-                        match val {
-                            0 => PointerKind::Mouse,
-                            1 => PointerKind::Touch,
-                            2 => PointerKind::Pen,
-                            _ => panic!("invalid enum discriminant"),
+                        PatternRepeat::RepeatX => {
+                            f.debug_tuple("PatternRepeat::RepeatX").finish()
+                        }
+                        PatternRepeat::Mirror => {
+                            f.debug_tuple("PatternRepeat::Mirror").finish()
                         }
                     }
                 }
-                #[repr(C)]
-                #[derive(Clone, Copy)]
-                pub struct PointerEvent {
-                    pub kind: PointerKind,
-                    pub position: Vec2,
-                    pub buttons: PointerButton,
-                    pub modifiers: Modifiers,
-                    pub pointer_id: u64,
+            }
+            impl PatternRepeat {
+                #[doc(hidden)]
+                pub unsafe fn _lift(val: u8) -> PatternRepeat {
+                    if !cfg!(debug_assertions) {
+                        return ::core::mem::transmute(val);
+                    }
+                    match val {
+                        0 => PatternRepeat::Repeat,
+                        1 => PatternRepeat::RepeatX,
+                        2 => PatternRepeat::Mirror,
+                        _ => panic!("invalid enum discriminant"),
+                    }
                 }
-                impl ::core::fmt::Debug for PointerEvent {
-                    fn fmt(
-                        &self,
-                        f: &mut ::core::fmt::Formatter<'_>,
-                    ) -> ::core::fmt::Result {
-                        f.debug_struct("PointerEvent")
-                            .field("kind", &self.kind)
-                            .field("position", &self.position)
-                            .field("buttons", &self.buttons)
-                            .field("modifiers", &self.modifiers)
-                            .field("pointer-id", &self.pointer_id)
-                            .finish()
+            }
+            /// Vector formats `export-frame` can write.
+            #[repr(u8)]
+            #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+            pub enum ExportFormat {
+                Svg,
+                Pdf,
+            }
+            impl ::core::fmt::Debug for ExportFormat {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        ExportFormat::Svg => f.debug_tuple("ExportFormat::Svg").finish(),
+                        ExportFormat::Pdf => f.debug_tuple("ExportFormat::Pdf").finish(),
                     }
                 }
-                #[derive(Clone)]
-                pub struct KeyEvent {
-                    pub key: _rt::String,
-                    pub code: _rt::String,
-                    pub modifiers: Modifiers,
-                    pub is_repeat: bool,
+            }
+            impl ExportFormat {
+                #[doc(hidden)]
+                pub unsafe fn _lift(val: u8) -> ExportFormat {
+                    if !cfg!(debug_assertions) {
+                        return ::core::mem::transmute(val);
+                    }
+                    match val {
+                        0 => ExportFormat::Svg,
+                        1 => ExportFormat::Pdf,
+                        _ => panic!("invalid enum discriminant"),
+                    }
+                }
+            }
+            /// Colour processing applied to an image as it is drawn.
+            #[repr(u8)]
+            #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+            pub enum ImageFilter {
+                None,
+                Grayscale,
+                Sepia,
+            }
+            impl ::core::fmt::Debug for ImageFilter {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        ImageFilter::None => f.debug_tuple("ImageFilter::None").finish(),
+                        ImageFilter::Grayscale => {
+                            f.debug_tuple("ImageFilter::Grayscale").finish()
+                        }
+                        ImageFilter::Sepia => {
+                            f.debug_tuple("ImageFilter::Sepia").finish()
+                        }
+                    }
+                }
+            }
+            impl ImageFilter {
+                #[doc(hidden)]
+                pub unsafe fn _lift(val: u8) -> ImageFilter {
+                    if !cfg!(debug_assertions) {
+                        return ::core::mem::transmute(val);
+                    }
+                    match val {
+                        0 => ImageFilter::None,
+                        1 => ImageFilter::Grayscale,
+                        2 => ImageFilter::Sepia,
+                        _ => panic!("invalid enum discriminant"),
+                    }
+                }
+            }
+            /// Per-draw image attributes: `filter` runs first, then every channel (alpha too) is
+            /// multiplied by `tint`, and `opacity` in 0.0..=1.0 fades the result. A white tint,
+            /// no filter and opacity 1.0 draw the image unchanged.
+            #[repr(C)]
+            #[derive(Clone, Copy)]
+            pub struct ImageStyle {
+                pub tint: Color,
+                pub filter: ImageFilter,
+                pub opacity: f32,
+            }
+            impl ::core::fmt::Debug for ImageStyle {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("ImageStyle")
+                        .field("tint", &self.tint)
+                        .field("filter", &self.filter)
+                        .field("opacity", &self.opacity)
+                        .finish()
+                }
+            }
+            /// The machine's power supply. `on-battery` is true while a system battery discharges;
+            /// `battery-percent` is absent on machines without one; `power-saver` reflects the
+            /// platform's low-power profile.
+            #[repr(C)]
+            #[derive(Clone, Copy)]
+            pub struct PowerStatus {
+                pub on_battery: bool,
+                pub battery_percent: Option<u8>,
+                pub power_saver: bool,
+            }
+            impl ::core::fmt::Debug for PowerStatus {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("PowerStatus")
+                        .field("on-battery", &self.on_battery)
+                        .field("battery-percent", &self.battery_percent)
+                        .field("power-saver", &self.power_saver)
+                        .finish()
+                }
+            }
+            /// How the user asked content to be shown. `high-contrast` follows the OS
+            /// accessibility setting and is also set under `--force-colors`; `forced-colors` means
+            /// the host already maps every fill and text colour onto the user's palette.
+            #[repr(C)]
+            #[derive(Clone, Copy)]
+            pub struct Preferences {
+                pub high_contrast: bool,
+                pub forced_colors: bool,
+            }
+            impl ::core::fmt::Debug for Preferences {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("Preferences")
+                        .field("high-contrast", &self.high_contrast)
+                        .field("forced-colors", &self.forced_colors)
+                        .finish()
+                }
+            }
+            /// Feedback strengths for `haptic-feedback`, from a light tick to an error buzz.
+            #[repr(u8)]
+            #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+            pub enum HapticKind {
+                Light,
+                Medium,
+                Heavy,
+                Success,
+                Error,
+            }
+            impl ::core::fmt::Debug for HapticKind {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        HapticKind::Light => f.debug_tuple("HapticKind::Light").finish(),
+                        HapticKind::Medium => {
+                            f.debug_tuple("HapticKind::Medium").finish()
+                        }
+                        HapticKind::Heavy => f.debug_tuple("HapticKind::Heavy").finish(),
+                        HapticKind::Success => {
+                            f.debug_tuple("HapticKind::Success").finish()
+                        }
+                        HapticKind::Error => f.debug_tuple("HapticKind::Error").finish(),
+                    }
+                }
+            }
+            impl HapticKind {
+                #[doc(hidden)]
+                pub unsafe fn _lift(val: u8) -> HapticKind {
+                    if !cfg!(debug_assertions) {
+                        return ::core::mem::transmute(val);
+                    }
+                    match val {
+                        0 => HapticKind::Light,
+                        1 => HapticKind::Medium,
+                        2 => HapticKind::Heavy,
+                        3 => HapticKind::Success,
+                        4 => HapticKind::Error,
+                        _ => panic!("invalid enum discriminant"),
+                    }
+                }
+            }
+            /// Timing curve of an `animate` tween, matching the CSS keyword of the same name.
+            #[repr(u8)]
+            #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+            pub enum Easing {
+                Linear,
+                Ease,
+                EaseIn,
+                EaseOut,
+                EaseInOut,
+            }
+            impl ::core::fmt::Debug for Easing {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        Easing::Linear => f.debug_tuple("Easing::Linear").finish(),
+                        Easing::Ease => f.debug_tuple("Easing::Ease").finish(),
+                        Easing::EaseIn => f.debug_tuple("Easing::EaseIn").finish(),
+                        Easing::EaseOut => f.debug_tuple("Easing::EaseOut").finish(),
+                        Easing::EaseInOut => f.debug_tuple("Easing::EaseInOut").finish(),
+                    }
+                }
+            }
+            impl Easing {
+                #[doc(hidden)]
+                pub unsafe fn _lift(val: u8) -> Easing {
+                    if !cfg!(debug_assertions) {
+                        return ::core::mem::transmute(val);
+                    }
+                    match val {
+                        0 => Easing::Linear,
+                        1 => Easing::Ease,
+                        2 => Easing::EaseIn,
+                        3 => Easing::EaseOut,
+                        4 => Easing::EaseInOut,
+                        _ => panic!("invalid enum discriminant"),
+                    }
+                }
+            }
+            /// Identifies a task started with `spawn-task`.
+            pub type TaskId = u32;
+            /// Identifies a worker started with `spawn-worker`.
+            pub type WorkerId = u32;
+            /// Where `spawn-worker` loads a component from.
+            #[derive(Clone)]
+            pub enum WorkerSource {
+                /// The component itself, e.g. a plugin the user picked.
+                Bytes(_rt::Vec<u8>),
+                /// A local path or `file://` URL inside this component's directory; relative paths
+                /// start there.
+                Url(_rt::String),
+            }
+            impl ::core::fmt::Debug for WorkerSource {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        WorkerSource::Bytes(e) => {
+                            f.debug_tuple("WorkerSource::Bytes").field(e).finish()
+                        }
+                        WorkerSource::Url(e) => {
+                            f.debug_tuple("WorkerSource::Url").field(e).finish()
+                        }
+                    }
+                }
+            }
+            wit_bindgen_rt::bitflags::bitflags! {
+                #[doc =
+                " Host interfaces a worker may use; its calls into the others trap. A worker never"]
+                #[doc = " gets an interface the component spawning it lacks."]
+                #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)] pub
+                struct WorkerPermissions : u8 { const WASI = 1 << 0; const CANVAS = 1 <<
+                1; const DATABASE = 1 << 2; }
+            }
+            /// Which key-value namespace a storage call uses.
+            #[derive(Clone)]
+            pub enum StorageScope {
+                /// This component's own keys; no other component can reach them.
+                Private,
+                /// The shared namespace of the component with this id, or this component's own
+                /// when none. Another component's namespace is only reachable when its manifest
+                /// lists this component in `shares-storage-with`.
+                Shared(Option<_rt::String>),
+            }
+            impl ::core::fmt::Debug for StorageScope {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        StorageScope::Private => {
+                            f.debug_tuple("StorageScope::Private").finish()
+                        }
+                        StorageScope::Shared(e) => {
+                            f.debug_tuple("StorageScope::Shared").field(e).finish()
+                        }
+                    }
+                }
+            }
+            /// What `format-datetime` shows: the date, the time of day, or both.
+            #[repr(u8)]
+            #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+            pub enum DatetimeStyle {
+                Date,
+                Time,
+                DateTime,
+            }
+            impl ::core::fmt::Debug for DatetimeStyle {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        DatetimeStyle::Date => {
+                            f.debug_tuple("DatetimeStyle::Date").finish()
+                        }
+                        DatetimeStyle::Time => {
+                            f.debug_tuple("DatetimeStyle::Time").finish()
+                        }
+                        DatetimeStyle::DateTime => {
+                            f.debug_tuple("DatetimeStyle::DateTime").finish()
+                        }
+                    }
+                }
+            }
+            impl DatetimeStyle {
+                #[doc(hidden)]
+                pub unsafe fn _lift(val: u8) -> DatetimeStyle {
+                    if !cfg!(debug_assertions) {
+                        return ::core::mem::transmute(val);
+                    }
+                    match val {
+                        0 => DatetimeStyle::Date,
+                        1 => DatetimeStyle::Time,
+                        2 => DatetimeStyle::DateTime,
+                        _ => panic!("invalid enum discriminant"),
+                    }
+                }
+            }
+            /// Top-left corner of the window frame in physical desktop pixels; may be negative on
+            /// multi-monitor setups.
+            #[repr(C)]
+            #[derive(Clone, Copy)]
+            pub struct WindowPosition {
+                pub x: i32,
+                pub y: i32,
+            }
+            impl ::core::fmt::Debug for WindowPosition {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("WindowPosition")
+                        .field("x", &self.x)
+                        .field("y", &self.y)
+                        .finish()
+                }
+            }
+            /// The monitor the window is on. `width`/`height` are physical pixels; refresh rate
+            /// is in millihertz (59940 for 59.94 Hz) and absent when the platform does not say.
+            #[derive(Clone)]
+            pub struct DisplayInfo {
+                pub name: Option<_rt::String>,
+                pub width: u32,
+                pub height: u32,
+                pub scale_factor: f32,
+                pub refresh_rate_millihertz: Option<u32>,
+            }
+            impl ::core::fmt::Debug for DisplayInfo {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("DisplayInfo")
+                        .field("name", &self.name)
+                        .field("width", &self.width)
+                        .field("height", &self.height)
+                        .field("scale-factor", &self.scale_factor)
+                        .field("refresh-rate-millihertz", &self.refresh_rate_millihertz)
+                        .finish()
+                }
+            }
+            /// Build and environment details for diagnostics screens and bug reports. The GPU
+            /// fields name the wgpu adapter and its backend (`vulkan`, `metal`, `dx12`, `gl`) and
+            /// are absent until the renderer starts; `os` is a Rust target OS such as `linux`.
+            #[derive(Clone)]
+            pub struct HostEnvironment {
+                pub host_version: _rt::String,
+                pub vello_version: _rt::String,
+                pub gpu_name: Option<_rt::String>,
+                pub backend: Option<_rt::String>,
+                pub os: _rt::String,
+            }
+            impl ::core::fmt::Debug for HostEnvironment {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    f.debug_struct("HostEnvironment")
+                        .field("host-version", &self.host_version)
+                        .field("vello-version", &self.vello_version)
+                        .field("gpu-name", &self.gpu_name)
+                        .field("backend", &self.backend)
+                        .field("os", &self.os)
+                        .finish()
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Clear the current scene background (call once per frame before drawing).
+            pub fn clear(c: Color) -> () {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r0,
+                        g: g0,
+                        b: b0,
+                        a: a0,
+                    } = c;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "clear"]
+                        fn wit_import1(_: f32, _: f32, _: f32, _: f32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: f32, _: f32, _: f32, _: f32) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import1(
+                            _rt::as_f32(r0),
+                            _rt::as_f32(g0),
+                            _rt::as_f32(b0),
+                            _rt::as_f32(a0),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Fill axis-aligned rectangle specified by top-left + size (logical pixels).
+            pub fn fill_rect(origin: Vec2, size: Vec2, color: Color) -> () {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x0,
+                        y: y0,
+                    } = origin;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = size;
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r2,
+                        g: g2,
+                        b: b2,
+                        a: a2,
+                    } = color;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "fill-rect"]
+                        fn wit_import3(
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import3(
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import3(
+                            _rt::as_f32(x0),
+                            _rt::as_f32(y0),
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                            _rt::as_f32(r2),
+                            _rt::as_f32(g2),
+                            _rt::as_f32(b2),
+                            _rt::as_f32(a2),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw text anchored at baseline origin using a bundled font.
+            pub fn draw_text(text: &str, origin: Vec2, size: f32, color: Color) -> () {
+                unsafe {
+                    let vec0 = text;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = origin;
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r2,
+                        g: g2,
+                        b: b2,
+                        a: a2,
+                    } = color;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-text"]
+                        fn wit_import3(
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import3(
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import3(
+                            ptr0.cast_mut(),
+                            len0,
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                            _rt::as_f32(&size),
+                            _rt::as_f32(r2),
+                            _rt::as_f32(g2),
+                            _rt::as_f32(b2),
+                            _rt::as_f32(a2),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// `draw-text` in a specific bundled font; `draw-text` always uses `sans`.
+            pub fn draw_text_with_font(
+                text: &str,
+                origin: Vec2,
+                size: f32,
+                color: Color,
+                font: FontFamily,
+            ) -> () {
+                unsafe {
+                    let vec0 = text;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = origin;
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r2,
+                        g: g2,
+                        b: b2,
+                        a: a2,
+                    } = color;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-text-with-font"]
+                        fn wit_import3(
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: i32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import3(
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import3(
+                            ptr0.cast_mut(),
+                            len0,
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                            _rt::as_f32(&size),
+                            _rt::as_f32(r2),
+                            _rt::as_f32(g2),
+                            _rt::as_f32(b2),
+                            _rt::as_f32(a2),
+                            font.clone() as i32,
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// `draw-text-with-font` in a paragraph of `direction`. Right-to-left lines end at
+            /// `origin` and extend to the left; `draw-text` and `draw-text-with-font` lay text out
+            /// left-to-right.
+            pub fn draw_text_with_direction(
+                text: &str,
+                origin: Vec2,
+                size: f32,
+                color: Color,
+                font: FontFamily,
+                direction: TextDirection,
+            ) -> () {
+                unsafe {
+                    let vec0 = text;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = origin;
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r2,
+                        g: g2,
+                        b: b2,
+                        a: a2,
+                    } = color;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-text-with-direction"]
+                        fn wit_import3(
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: i32,
+                            _: i32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import3(
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                        _: i32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import3(
+                            ptr0.cast_mut(),
+                            len0,
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                            _rt::as_f32(&size),
+                            _rt::as_f32(r2),
+                            _rt::as_f32(g2),
+                            _rt::as_f32(b2),
+                            _rt::as_f32(a2),
+                            font.clone() as i32,
+                            direction.clone() as i32,
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// `draw-text-with-direction` in `mode`, turned `rotation` radians clockwise about
+            /// `origin`, for vertical layouts and rotated chart labels. In `vertical-rl` `origin`
+            /// is the top of the first column's centre line; characters step down by `size`,
+            /// columns step left by 1.2 times `size`, and `direction` does not reorder them.
+            pub fn draw_text_oriented(
+                text: &str,
+                origin: Vec2,
+                size: f32,
+                color: Color,
+                font: FontFamily,
+                direction: TextDirection,
+                mode: WritingMode,
+                rotation: f32,
+            ) -> () {
+                unsafe {
+                    let vec0 = text;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = origin;
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r2,
+                        g: g2,
+                        b: b2,
+                        a: a2,
+                    } = color;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-text-oriented"]
+                        fn wit_import3(
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: f32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import3(
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                        _: i32,
+                        _: i32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import3(
+                            ptr0.cast_mut(),
+                            len0,
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                            _rt::as_f32(&size),
+                            _rt::as_f32(r2),
+                            _rt::as_f32(g2),
+                            _rt::as_f32(b2),
+                            _rt::as_f32(a2),
+                            font.clone() as i32,
+                            direction.clone() as i32,
+                            mode.clone() as i32,
+                            _rt::as_f32(&rotation),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw `spans` as one paragraph with its first baseline at `origin`, wrapped at spaces
+            /// to `max-width` logical pixels (mid-word when a word is wider) and at `\n`. Each line
+            /// sits below the last by 1.2 times its largest text size. Spaces after a `\n` are kept,
+            /// so indented code stays indented. In a right-to-left paragraph lines end at
+            /// `max-width`, or at `origin` when it is infinite. Counts against the same text quota
+            /// as `draw-text`.
+            pub fn draw_rich_text(
+                spans: &[StyledSpan],
+                origin: Vec2,
+                max_width: f32,
+                direction: TextDirection,
+            ) -> () {
+                unsafe {
+                    let vec3 = spans;
+                    let len3 = vec3.len();
+                    let layout3 = _rt::alloc::Layout::from_size_align_unchecked(
+                        vec3.len() * (24 + 2 * ::core::mem::size_of::<*const u8>()),
+                        ::core::mem::size_of::<*const u8>(),
+                    );
+                    let result3 = if layout3.size() != 0 {
+                        let ptr = _rt::alloc::alloc(layout3).cast::<u8>();
+                        if ptr.is_null() {
+                            _rt::alloc::handle_alloc_error(layout3);
+                        }
+                        ptr
+                    } else {
+                        ::core::ptr::null_mut()
+                    };
+                    for (i, e) in vec3.into_iter().enumerate() {
+                        let base = result3
+                            .add(i * (24 + 2 * ::core::mem::size_of::<*const u8>()));
+                        {
+                            let StyledSpan {
+                                text: text0,
+                                color: color0,
+                                size: size0,
+                                font: font0,
+                            } = e;
+                            let vec1 = text0;
+                            let ptr1 = vec1.as_ptr().cast::<u8>();
+                            let len1 = vec1.len();
+                            *base
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<usize>() = len1;
+                            *base.add(0).cast::<*mut u8>() = ptr1.cast_mut();
+                            let super::super::super::vello::canvas::math::Color {
+                                r: r2,
+                                g: g2,
+                                b: b2,
+                                a: a2,
+                            } = color0;
+                            *base
+                                .add(2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<f32>() = _rt::as_f32(r2);
+                            *base
+                                .add(4 + 2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<f32>() = _rt::as_f32(g2);
+                            *base
+                                .add(8 + 2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<f32>() = _rt::as_f32(b2);
+                            *base
+                                .add(12 + 2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<f32>() = _rt::as_f32(a2);
+                            *base
+                                .add(16 + 2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<f32>() = _rt::as_f32(size0);
+                            *base
+                                .add(20 + 2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<u8>() = (font0.clone() as i32) as u8;
+                        }
+                    }
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x4,
+                        y: y4,
+                    } = origin;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-rich-text"]
+                        fn wit_import5(
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: i32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import5(
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import5(
+                            result3,
+                            len3,
+                            _rt::as_f32(x4),
+                            _rt::as_f32(y4),
+                            _rt::as_f32(&max_width),
+                            direction.clone() as i32,
+                        )
+                    };
+                    if layout3.size() != 0 {
+                        _rt::alloc::dealloc(result3.cast(), layout3);
+                    }
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw `cells` in order on a grid of fixed `monospace` cells, `text-grid-cell(size)`
+            /// large, with the grid's top-left corner at `origin`: one call per frame draws a whole
+            /// terminal screen. `\n` starts the next row and `\t` moves to the next multiple of
+            /// `tab-width` columns (1 to 64). Characters are not reordered for bidi or widened for
+            /// CJK; each keeps its own cell. Counts against the same text quota as `draw-text`.
+            pub fn draw_text_grid(
+                cells: &[GridCell],
+                origin: Vec2,
+                size: f32,
+                tab_width: u32,
+            ) -> () {
+                unsafe {
+                    let vec4 = cells;
+                    let len4 = vec4.len();
+                    let layout4 = _rt::alloc::Layout::from_size_align_unchecked(
+                        vec4.len() * (32 + 3 * ::core::mem::size_of::<*const u8>()),
+                        ::core::mem::size_of::<*const u8>(),
+                    );
+                    let result4 = if layout4.size() != 0 {
+                        let ptr = _rt::alloc::alloc(layout4).cast::<u8>();
+                        if ptr.is_null() {
+                            _rt::alloc::handle_alloc_error(layout4);
+                        }
+                        ptr
+                    } else {
+                        ::core::ptr::null_mut()
+                    };
+                    for (i, e) in vec4.into_iter().enumerate() {
+                        let base = result4
+                            .add(i * (32 + 3 * ::core::mem::size_of::<*const u8>()));
+                        {
+                            let GridCell { text: text0, fg: fg0, bg: bg0 } = e;
+                            let vec1 = text0;
+                            let ptr1 = vec1.as_ptr().cast::<u8>();
+                            let len1 = vec1.len();
+                            *base
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<usize>() = len1;
+                            *base.add(0).cast::<*mut u8>() = ptr1.cast_mut();
+                            let super::super::super::vello::canvas::math::Color {
+                                r: r2,
+                                g: g2,
+                                b: b2,
+                                a: a2,
+                            } = fg0;
+                            *base
+                                .add(2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<f32>() = _rt::as_f32(r2);
+                            *base
+                                .add(4 + 2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<f32>() = _rt::as_f32(g2);
+                            *base
+                                .add(8 + 2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<f32>() = _rt::as_f32(b2);
+                            *base
+                                .add(12 + 2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<f32>() = _rt::as_f32(a2);
+                            match bg0 {
+                                Some(e) => {
+                                    *base
+                                        .add(16 + 2 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<u8>() = (1i32) as u8;
+                                    let super::super::super::vello::canvas::math::Color {
+                                        r: r3,
+                                        g: g3,
+                                        b: b3,
+                                        a: a3,
+                                    } = e;
+                                    *base
+                                        .add(20 + 2 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<f32>() = _rt::as_f32(r3);
+                                    *base
+                                        .add(24 + 2 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<f32>() = _rt::as_f32(g3);
+                                    *base
+                                        .add(28 + 2 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<f32>() = _rt::as_f32(b3);
+                                    *base
+                                        .add(32 + 2 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<f32>() = _rt::as_f32(a3);
+                                }
+                                None => {
+                                    *base
+                                        .add(16 + 2 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<u8>() = (0i32) as u8;
+                                }
+                            };
+                        }
+                    }
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x5,
+                        y: y5,
+                    } = origin;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-text-grid"]
+                        fn wit_import6(
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: i32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import6(
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import6(
+                            result4,
+                            len4,
+                            _rt::as_f32(x5),
+                            _rt::as_f32(y5),
+                            _rt::as_f32(&size),
+                            _rt::as_i32(&tab_width),
+                        )
+                    };
+                    if layout4.size() != 0 {
+                        _rt::alloc::dealloc(result4.cast(), layout4);
+                    }
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Size of one `draw-text-grid` cell at `size`: the `monospace` advance wide and 1.2
+            /// times `size` tall.
+            pub fn text_grid_cell(size: f32) -> Vec2 {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 8]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 8]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "text-grid-cell"]
+                        fn wit_import1(_: f32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: f32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_f32(&size), ptr0) };
+                    let l2 = *ptr0.add(0).cast::<f32>();
+                    let l3 = *ptr0.add(4).cast::<f32>();
+                    let result4 = super::super::super::vello::canvas::math::Vec2 {
+                        x: l2,
+                        y: l3,
+                    };
+                    result4
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw CommonMark `source` top to bottom inside `rect` in `color`, parsed and laid out
+            /// by the host: headings in bold at up to twice `base-size`, strong and emphasised text
+            /// in bold, inline code and code blocks in `monospace` (blocks on a faint background),
+            /// list items and block quotes indented, and rules as thin lines. Paragraphs wrap to
+            /// the width of `rect`; lines below its bottom are left out. Links draw as their text,
+            /// images as their alt text, and HTML is skipped. Counts against the same text quota
+            /// as `draw-text`.
+            pub fn draw_markdown(
+                source: &str,
+                rect: Rect,
+                base_size: f32,
+                color: Color,
+            ) -> () {
+                unsafe {
+                    let vec0 = source;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let Rect { origin: origin1, size: size1 } = rect;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x2,
+                        y: y2,
+                    } = origin1;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x3,
+                        y: y3,
+                    } = size1;
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r4,
+                        g: g4,
+                        b: b4,
+                        a: a4,
+                    } = color;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-markdown"]
+                        fn wit_import5(
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import5(
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import5(
+                            ptr0.cast_mut(),
+                            len0,
+                            _rt::as_f32(x2),
+                            _rt::as_f32(y2),
+                            _rt::as_f32(x3),
+                            _rt::as_f32(y3),
+                            _rt::as_f32(&base_size),
+                            _rt::as_f32(r4),
+                            _rt::as_f32(g4),
+                            _rt::as_f32(b4),
+                            _rt::as_f32(a4),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw `data` as a QR code, encoded by the host: black modules on white with the
+            /// four-module quiet zone scanners need, as the largest square centred in `rect`, one
+            /// module per pixel scaled up without smoothing. The code is the smallest that holds
+            /// `data` as bytes with medium error correction, raised to the strongest level that
+            /// still fits. Data longer than 2331 bytes does not fit any code and is dropped as
+            /// invalid.
+            pub fn draw_qr(data: &str, rect: Rect) -> () {
+                unsafe {
+                    let vec0 = data;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let Rect { origin: origin1, size: size1 } = rect;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x2,
+                        y: y2,
+                    } = origin1;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x3,
+                        y: y3,
+                    } = size1;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-qr"]
+                        fn wit_import4(
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import4(
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import4(
+                            ptr0.cast_mut(),
+                            len0,
+                            _rt::as_f32(x2),
+                            _rt::as_f32(y2),
+                            _rt::as_f32(x3),
+                            _rt::as_f32(y3),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw one bar per value side by side across `rect`, in one call however long the
+            /// series. Bars outside the window are skipped, and bars narrower than a pixel are
+            /// merged into one per pixel that reaches their highest and lowest values.
+            /// Non-finite values leave a gap. A `style` with non-finite values or `min` equal to
+            /// `max` draws nothing.
+            pub fn draw_bars(values: &[f32], rect: Rect, style: BarStyle) -> () {
+                unsafe {
+                    let vec0 = values;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let Rect { origin: origin1, size: size1 } = rect;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x2,
+                        y: y2,
+                    } = origin1;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x3,
+                        y: y3,
+                    } = size1;
+                    let BarStyle {
+                        color: color4,
+                        min: min4,
+                        max: max4,
+                        baseline: baseline4,
+                        gap: gap4,
+                    } = style;
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r5,
+                        g: g5,
+                        b: b5,
+                        a: a5,
+                    } = color4;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-bars"]
+                        fn wit_import6(
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import6(
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import6(
+                            ptr0.cast_mut(),
+                            len0,
+                            _rt::as_f32(x2),
+                            _rt::as_f32(y2),
+                            _rt::as_f32(x3),
+                            _rt::as_f32(y3),
+                            _rt::as_f32(r5),
+                            _rt::as_f32(g5),
+                            _rt::as_f32(b5),
+                            _rt::as_f32(a5),
+                            _rt::as_f32(min4),
+                            _rt::as_f32(max4),
+                            _rt::as_f32(baseline4),
+                            _rt::as_f32(gap4),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw a filled circle of `radius` at each point, skipping those outside the window.
+            pub fn draw_points(points: &[Vec2], radius: f32, color: Color) -> () {
+                unsafe {
+                    let vec0 = points;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r1,
+                        g: g1,
+                        b: b1,
+                        a: a1,
+                    } = color;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-points"]
+                        fn wit_import2(
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import2(
+                            ptr0.cast_mut(),
+                            len0,
+                            _rt::as_f32(&radius),
+                            _rt::as_f32(r1),
+                            _rt::as_f32(g1),
+                            _rt::as_f32(b1),
+                            _rt::as_f32(a1),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw `grid` stretched over `rect`, each cell filled with the colour `colormap` gives
+            /// its value: the stops spread evenly from `min` to `max` and blended in between, and
+            /// values outside the range clamped. Only cells in the window are drawn, as one image
+            /// with hard cell edges. Non-finite values and a short last row are transparent.
+            pub fn draw_heatmap(grid: &Heatmap, rect: Rect, colormap: &[Color]) -> () {
+                unsafe {
+                    let Heatmap {
+                        columns: columns0,
+                        values: values0,
+                        min: min0,
+                        max: max0,
+                    } = grid;
+                    let vec1 = values0;
+                    let ptr1 = vec1.as_ptr().cast::<u8>();
+                    let len1 = vec1.len();
+                    let Rect { origin: origin2, size: size2 } = rect;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x3,
+                        y: y3,
+                    } = origin2;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x4,
+                        y: y4,
+                    } = size2;
+                    let vec5 = colormap;
+                    let ptr5 = vec5.as_ptr().cast::<u8>();
+                    let len5 = vec5.len();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-heatmap"]
+                        fn wit_import6(
+                            _: i32,
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: *mut u8,
+                            _: usize,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import6(
+                        _: i32,
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: *mut u8,
+                        _: usize,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import6(
+                            _rt::as_i32(columns0),
+                            ptr1.cast_mut(),
+                            len1,
+                            _rt::as_f32(min0),
+                            _rt::as_f32(max0),
+                            _rt::as_f32(x3),
+                            _rt::as_f32(y3),
+                            _rt::as_f32(x4),
+                            _rt::as_f32(y4),
+                            ptr5.cast_mut(),
+                            len5,
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Declare scroll region `id`, showing `content-size` logical pixels of content through
+            /// `viewport`. The host scrolls it with the mouse wheel and touch drags, lets a quick
+            /// drag fling on after the finger lifts, bounces it back when pulled or flung past an
+            /// end, and calls `scroll-changed` with each new offset. Defining an existing region
+            /// updates its sizes and keeps its offset within the new content. False when a size is
+            /// negative or not finite, or 256 regions are already defined.
+            pub fn define_scroll_region(
+                id: u32,
+                content_size: Vec2,
+                viewport: Rect,
+            ) -> bool {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x0,
+                        y: y0,
+                    } = content_size;
+                    let Rect { origin: origin1, size: size1 } = viewport;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x2,
+                        y: y2,
+                    } = origin1;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x3,
+                        y: y3,
+                    } = size1;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "define-scroll-region"]
+                        fn wit_import4(
+                            _: i32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                        ) -> i32;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import4(
+                        _: i32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                    ) -> i32 {
+                        unreachable!()
+                    }
+                    let ret = unsafe {
+                        wit_import4(
+                            _rt::as_i32(&id),
+                            _rt::as_f32(x0),
+                            _rt::as_f32(y0),
+                            _rt::as_f32(x2),
+                            _rt::as_f32(y2),
+                            _rt::as_f32(x3),
+                            _rt::as_f32(y3),
+                        )
+                    };
+                    _rt::bool_lift(ret as u8)
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Forget a scroll region.
+            pub fn remove_scroll_region(id: u32) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "remove-scroll-region"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(&id)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// How far a region is scrolled, from (0, 0) to its content size less its viewport
+            /// size, or briefly past either end while it bounces; none for unknown ids.
+            pub fn scroll_offset(id: u32) -> Option<Vec2> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 12]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 12]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "scroll-offset"]
+                        fn wit_import1(_: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(&id), ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result5 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0.add(4).cast::<f32>();
+                                let l4 = *ptr0.add(8).cast::<f32>();
+                                super::super::super::vello::canvas::math::Vec2 {
+                                    x: l3,
+                                    y: l4,
+                                }
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result5
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Scroll a region to `offset`, kept within its content, stopping any fling. Does not
+            /// call `scroll-changed`.
+            pub fn set_scroll_offset(id: u32, offset: Vec2) -> () {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x0,
+                        y: y0,
+                    } = offset;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "set-scroll-offset"]
+                        fn wit_import1(_: i32, _: f32, _: f32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: f32, _: f32) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import1(_rt::as_i32(&id), _rt::as_f32(x0), _rt::as_f32(y0))
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw everything up to the matching `pop-scroll-region` as the region's content:
+            /// moved so the content's top-left corner is at the viewport's, less the scroll offset,
+            /// and clipped to the viewport. Regions pushed inside another take their viewport in
+            /// its content's coordinates, and the innermost region under the pointer scrolls
+            /// first. Clips do not carry across `push-shader-layer` or `pop-shader-layer`.
+            pub fn push_scroll_region(id: u32) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "push-scroll-region"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(&id)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// End the innermost scroll region.
+            pub fn pop_scroll_region() -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "pop-scroll-region"]
+                        fn wit_import0();
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0() {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0() };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// A finger went down at `position` on content the guest scrolls itself, scroller
+            /// `id`, which the host then moves with the same drag, fling and bounce physics as
+            /// scroll regions. A scroller still flinging is caught where it is; otherwise it starts
+            /// at `offset`, free to move between `min` and `max`. Feed the drag to `kinetic-drag`
+            /// and the lift to `kinetic-release`, after which `kinetic-scrolled` reports each
+            /// frame's motion until it stops. False when a value is not finite or 64 scrollers are
+            /// already held or moving.
+            pub fn kinetic_press(
+                id: u32,
+                position: Vec2,
+                offset: Vec2,
+                min: Vec2,
+                max: Vec2,
+            ) -> bool {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x0,
+                        y: y0,
+                    } = position;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = offset;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x2,
+                        y: y2,
+                    } = min;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x3,
+                        y: y3,
+                    } = max;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "kinetic-press"]
+                        fn wit_import4(
+                            _: i32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                        ) -> i32;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import4(
+                        _: i32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                    ) -> i32 {
+                        unreachable!()
+                    }
+                    let ret = unsafe {
+                        wit_import4(
+                            _rt::as_i32(&id),
+                            _rt::as_f32(x0),
+                            _rt::as_f32(y0),
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                            _rt::as_f32(x2),
+                            _rt::as_f32(y2),
+                            _rt::as_f32(x3),
+                            _rt::as_f32(y3),
+                        )
+                    };
+                    _rt::bool_lift(ret as u8)
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// The finger pressed on scroller `id` moved to `position`. Returns the scroller's new
+            /// offset: the content follows the finger, with growing resistance past `min` and
+            /// `max`. None when `id` is not pressed.
+            pub fn kinetic_drag(id: u32, position: Vec2) -> Option<Vec2> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 12]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 12]);
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x0,
+                        y: y0,
+                    } = position;
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "kinetic-drag"]
+                        fn wit_import2(_: i32, _: f32, _: f32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(
+                        _: i32,
+                        _: f32,
+                        _: f32,
+                        _: *mut u8,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import2(
+                            _rt::as_i32(&id),
+                            _rt::as_f32(x0),
+                            _rt::as_f32(y0),
+                            ptr1,
+                        )
+                    };
+                    let l3 = i32::from(*ptr1.add(0).cast::<u8>());
+                    let result6 = match l3 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l4 = *ptr1.add(4).cast::<f32>();
+                                let l5 = *ptr1.add(8).cast::<f32>();
+                                super::super::super::vello::canvas::math::Vec2 {
+                                    x: l4,
+                                    y: l5,
+                                }
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result6
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// The finger lifted from scroller `id`: a quick drag flings on and a pull past an end
+            /// springs back, each reported through `kinetic-scrolled`.
+            pub fn kinetic_release(id: u32) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "kinetic-release"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(&id)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Stop scroller `id` where it is, returning its offset; none when it was neither
+            /// held nor moving.
+            pub fn kinetic_stop(id: u32) -> Option<Vec2> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 12]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 12]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "kinetic-stop"]
+                        fn wit_import1(_: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(&id), ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result5 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0.add(4).cast::<f32>();
+                                let l4 = *ptr0.add(8).cast::<f32>();
+                                super::super::super::vello::canvas::math::Vec2 {
+                                    x: l3,
+                                    y: l4,
+                                }
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result5
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Caret position nearest `point` in `text` as `draw-text-with-direction` lays it out
+            /// in `sans` at `size`, as a character (not byte) index from 0 to the length. `point` is
+            /// relative to the baseline origin; points above the first line or below the last
+            /// resolve on those lines.
+            pub fn hit_test_text(
+                text: &str,
+                size: f32,
+                point: Vec2,
+                direction: TextDirection,
+            ) -> u32 {
+                unsafe {
+                    let vec0 = text;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = point;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "hit-test-text"]
+                        fn wit_import2(
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: i32,
+                        ) -> i32;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                    ) -> i32 {
+                        unreachable!()
+                    }
+                    let ret = unsafe {
+                        wit_import2(
+                            ptr0.cast_mut(),
+                            len0,
+                            _rt::as_f32(&size),
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                            direction.clone() as i32,
+                        )
+                    };
+                    ret as u32
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Where the caret at character `index` of `text` sits as `draw-text-with-direction`
+            /// lays it out in `sans` at `size`: a zero-width rectangle spanning its line, relative
+            /// to the baseline origin, on the leading side of the character (its right side in
+            /// right-to-left runs). Indices past the end give the caret after the last character.
+            pub fn caret_rect(
+                text: &str,
+                size: f32,
+                index: u32,
+                direction: TextDirection,
+            ) -> Rect {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 16]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 16]);
+                    let vec0 = text;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "caret-rect"]
+                        fn wit_import2(
+                            _: *mut u8,
+                            _: usize,
+                            _: f32,
+                            _: i32,
+                            _: i32,
+                            _: *mut u8,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(
+                        _: *mut u8,
+                        _: usize,
+                        _: f32,
+                        _: i32,
+                        _: i32,
+                        _: *mut u8,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import2(
+                            ptr0.cast_mut(),
+                            len0,
+                            _rt::as_f32(&size),
+                            _rt::as_i32(&index),
+                            direction.clone() as i32,
+                            ptr1,
+                        )
+                    };
+                    let l3 = *ptr1.add(0).cast::<f32>();
+                    let l4 = *ptr1.add(4).cast::<f32>();
+                    let l5 = *ptr1.add(8).cast::<f32>();
+                    let l6 = *ptr1.add(12).cast::<f32>();
+                    let result7 = Rect {
+                        origin: super::super::super::vello::canvas::math::Vec2 {
+                            x: l3,
+                            y: l4,
+                        },
+                        size: super::super::super::vello::canvas::math::Vec2 {
+                            x: l5,
+                            y: l6,
+                        },
+                    };
+                    result7
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Upload `width` x `height` pixels of straight-alpha RGBA8 (row-major, 4 bytes per
+            /// pixel). None when the pixel count does not match, a side is 0 or over 4096, or the
+            /// guest's image memory budget or per-second upload rate is exhausted. Images live
+            /// until `drop-image`.
+            pub fn create_image(
+                width: u32,
+                height: u32,
+                rgba: &[u8],
+            ) -> Option<ImageId> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 8]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 8]);
+                    let vec0 = rgba;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "create-image"]
+                        fn wit_import2(_: i32, _: i32, _: *mut u8, _: usize, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(
+                        _: i32,
+                        _: i32,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import2(
+                            _rt::as_i32(&width),
+                            _rt::as_i32(&height),
+                            ptr0.cast_mut(),
+                            len0,
+                            ptr1,
+                        )
+                    };
+                    let l3 = i32::from(*ptr1.add(0).cast::<u8>());
+                    let result5 = match l3 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l4 = *ptr1.add(4).cast::<i32>();
+                                l4 as u32
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result5
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Free an image; fills that still reference it are dropped.
+            pub fn drop_image(image: ImageId) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "drop-image"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(image)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Parse an SVG document into a picture that stays crisp at any size. Paths, basic
+            /// shapes, solid fills and strokes, groups, transforms and `viewBox` are supported;
+            /// gradients, text, embedded images, `<use>` and filters are left out. Fails with a
+            /// message for malformed documents or when the image memory budget is exhausted.
+            pub fn load_svg(bytes: &[u8]) -> Result<PictureId, _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let vec0 = bytes;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "load-svg"]
+                        fn wit_import2(_: *mut u8, _: usize, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(_: *mut u8, _: usize, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import2(ptr0.cast_mut(), len0, ptr1) };
+                    let l3 = i32::from(*ptr1.add(0).cast::<u8>());
+                    let result8 = match l3 {
+                        0 => {
+                            let e = {
+                                let l4 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                l4 as u32
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l5 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l6 = *ptr1
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len7 = l6;
+                                let bytes7 = _rt::Vec::from_raw_parts(
+                                    l5.cast(),
+                                    len7,
+                                    len7,
+                                );
+                                _rt::string_lift(bytes7)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result8
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Intrinsic size of a picture in logical pixels; none for unknown ids.
+            pub fn picture_size(picture: PictureId) -> Option<Vec2> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 12]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 12]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "picture-size"]
+                        fn wit_import1(_: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(picture), ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result5 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0.add(4).cast::<f32>();
+                                let l4 = *ptr0.add(8).cast::<f32>();
+                                super::super::super::vello::canvas::math::Vec2 {
+                                    x: l3,
+                                    y: l4,
+                                }
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result5
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Free a picture; draws that still reference it are dropped.
+            pub fn drop_picture(picture: PictureId) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "drop-picture"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(picture)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw `picture` scaled to fill the rectangle at `origin`.
+            pub fn draw_picture(picture: PictureId, origin: Vec2, size: Vec2) -> () {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x0,
+                        y: y0,
+                    } = origin;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = size;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-picture"]
+                        fn wit_import2(_: i32, _: f32, _: f32, _: f32, _: f32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(
+                        _: i32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import2(
+                            _rt::as_i32(picture),
+                            _rt::as_f32(x0),
+                            _rt::as_f32(y0),
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Parse a Lottie (Bodymovin JSON) animation. Shape, solid and null layers with
+            /// animated transforms, rectangles, ellipses, paths, fills and strokes are supported;
+            /// precompositions, images, text, masks, mattes, effects and gradients are left out.
+            /// Fails with a message for malformed files or when the image memory budget is
+            /// exhausted.
+            pub fn load_lottie(bytes: &[u8]) -> Result<AnimationId, _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let vec0 = bytes;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "load-lottie"]
+                        fn wit_import2(_: *mut u8, _: usize, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(_: *mut u8, _: usize, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import2(ptr0.cast_mut(), len0, ptr1) };
+                    let l3 = i32::from(*ptr1.add(0).cast::<u8>());
+                    let result8 = match l3 {
+                        0 => {
+                            let e = {
+                                let l4 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                l4 as u32
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l5 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l6 = *ptr1
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len7 = l6;
+                                let bytes7 = _rt::Vec::from_raw_parts(
+                                    l5.cast(),
+                                    len7,
+                                    len7,
+                                );
+                                _rt::string_lift(bytes7)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result8
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Size, duration and frame rate of an animation; none for unknown ids.
+            pub fn animation_info(animation: AnimationId) -> Option<AnimationMetadata> {
+                unsafe {
+                    #[repr(align(8))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 32]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 32]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "animation-info"]
+                        fn wit_import1(_: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(animation), ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result7 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0.add(8).cast::<f32>();
+                                let l4 = *ptr0.add(12).cast::<f32>();
+                                let l5 = *ptr0.add(16).cast::<f64>();
+                                let l6 = *ptr0.add(24).cast::<f32>();
+                                AnimationMetadata {
+                                    size: super::super::super::vello::canvas::math::Vec2 {
+                                        x: l3,
+                                        y: l4,
+                                    },
+                                    duration_ms: l5,
+                                    frame_rate: l6,
+                                }
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result7
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Free an animation; draws that still reference it are dropped.
+            pub fn drop_animation(animation: AnimationId) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "drop-animation"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(animation)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw the frame `time-ms` into `animation`, scaled to fill the rectangle at `origin`.
+            /// Times past the end loop back to the start.
+            pub fn draw_animation(
+                animation: AnimationId,
+                time_ms: f64,
+                origin: Vec2,
+                size: Vec2,
+            ) -> () {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x0,
+                        y: y0,
+                    } = origin;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = size;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-animation"]
+                        fn wit_import2(_: i32, _: f64, _: f32, _: f32, _: f32, _: f32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(
+                        _: i32,
+                        _: f64,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import2(
+                            _rt::as_i32(animation),
+                            _rt::as_f64(&time_ms),
+                            _rt::as_f32(x0),
+                            _rt::as_f32(y0),
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Decode a PNG, APNG or GIF file. Every frame is composited once, here, and cached on
+            /// the host, so drawing any frame later costs the same as `draw-image`. Fails with a
+            /// message for other formats, malformed files, or when the decoded frames would exceed
+            /// the image memory budget or what is left of the per-second upload rate.
+            pub fn decode_image(bytes: &[u8]) -> Result<AnimatedImageId, _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let vec0 = bytes;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "decode-image"]
+                        fn wit_import2(_: *mut u8, _: usize, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(_: *mut u8, _: usize, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import2(ptr0.cast_mut(), len0, ptr1) };
+                    let l3 = i32::from(*ptr1.add(0).cast::<u8>());
+                    let result8 = match l3 {
+                        0 => {
+                            let e = {
+                                let l4 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                l4 as u32
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l5 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l6 = *ptr1
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len7 = l6;
+                                let bytes7 = _rt::Vec::from_raw_parts(
+                                    l5.cast(),
+                                    len7,
+                                    len7,
+                                );
+                                _rt::string_lift(bytes7)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result8
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Size, frame durations and play count; none for unknown ids.
+            pub fn animated_image_info(
+                image: AnimatedImageId,
+            ) -> Option<AnimatedImageMetadata> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 8 + 4 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 8
+                            + 4 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "animated-image-info"]
+                        fn wit_import1(_: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(image), ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result9 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                let l4 = *ptr0
+                                    .add(4 + 1 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                let l5 = *ptr0
+                                    .add(8 + 1 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l6 = *ptr0
+                                    .add(8 + 2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len7 = l6;
+                                let l8 = *ptr0
+                                    .add(8 + 3 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                AnimatedImageMetadata {
+                                    width: l3 as u32,
+                                    height: l4 as u32,
+                                    frame_durations_ms: _rt::Vec::from_raw_parts(
+                                        l5.cast(),
+                                        len7,
+                                        len7,
+                                    ),
+                                    plays: l8 as u32,
+                                }
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result9
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Free a decoded image and its cached frames.
+            pub fn drop_animated_image(image: AnimatedImageId) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "drop-animated-image"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(image)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw frame `frame` (modulo the frame count) stretched over the rectangle at `origin`.
+            pub fn draw_animated_image(
+                image: AnimatedImageId,
+                frame: u32,
+                origin: Vec2,
+                size: Vec2,
+                style: ImageStyle,
+            ) -> () {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x0,
+                        y: y0,
+                    } = origin;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = size;
+                    let ImageStyle { tint: tint2, filter: filter2, opacity: opacity2 } = style;
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r3,
+                        g: g3,
+                        b: b3,
+                        a: a3,
+                    } = tint2;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-animated-image"]
+                        fn wit_import4(
+                            _: i32,
+                            _: i32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: i32,
+                            _: f32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import4(
+                        _: i32,
+                        _: i32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import4(
+                            _rt::as_i32(image),
+                            _rt::as_i32(&frame),
+                            _rt::as_f32(x0),
+                            _rt::as_f32(y0),
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                            _rt::as_f32(r3),
+                            _rt::as_f32(g3),
+                            _rt::as_f32(b3),
+                            _rt::as_f32(a3),
+                            filter2.clone() as i32,
+                            _rt::as_f32(opacity2),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Start a host-simulated particle emitter that spawns `rate` particles per second.
+            /// Emitters advance by each frame's `dt`. None for non-finite or negative values, a
+            /// lifetime of zero, `max-particles` outside 1..=65536, or when the emitter would
+            /// exceed the image memory budget.
+            pub fn create_emitter(config: EmitterConfig) -> Option<EmitterId> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 84]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 84]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    let EmitterConfig {
+                        position: position1,
+                        rate: rate1,
+                        max_particles: max_particles1,
+                        lifetime_ms: lifetime_ms1,
+                        angle: angle1,
+                        spread: spread1,
+                        speed: speed1,
+                        speed_jitter: speed_jitter1,
+                        gravity: gravity1,
+                        start_size: start_size1,
+                        end_size: end_size1,
+                        start_color: start_color1,
+                        end_color: end_color1,
+                    } = config;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x2,
+                        y: y2,
+                    } = position1;
+                    *ptr0.add(0).cast::<f32>() = _rt::as_f32(x2);
+                    *ptr0.add(4).cast::<f32>() = _rt::as_f32(y2);
+                    *ptr0.add(8).cast::<f32>() = _rt::as_f32(rate1);
+                    *ptr0.add(12).cast::<i32>() = _rt::as_i32(max_particles1);
+                    *ptr0.add(16).cast::<f32>() = _rt::as_f32(lifetime_ms1);
+                    *ptr0.add(20).cast::<f32>() = _rt::as_f32(angle1);
+                    *ptr0.add(24).cast::<f32>() = _rt::as_f32(spread1);
+                    *ptr0.add(28).cast::<f32>() = _rt::as_f32(speed1);
+                    *ptr0.add(32).cast::<f32>() = _rt::as_f32(speed_jitter1);
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x3,
+                        y: y3,
+                    } = gravity1;
+                    *ptr0.add(36).cast::<f32>() = _rt::as_f32(x3);
+                    *ptr0.add(40).cast::<f32>() = _rt::as_f32(y3);
+                    *ptr0.add(44).cast::<f32>() = _rt::as_f32(start_size1);
+                    *ptr0.add(48).cast::<f32>() = _rt::as_f32(end_size1);
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r4,
+                        g: g4,
+                        b: b4,
+                        a: a4,
+                    } = start_color1;
+                    *ptr0.add(52).cast::<f32>() = _rt::as_f32(r4);
+                    *ptr0.add(56).cast::<f32>() = _rt::as_f32(g4);
+                    *ptr0.add(60).cast::<f32>() = _rt::as_f32(b4);
+                    *ptr0.add(64).cast::<f32>() = _rt::as_f32(a4);
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r5,
+                        g: g5,
+                        b: b5,
+                        a: a5,
+                    } = end_color1;
+                    *ptr0.add(68).cast::<f32>() = _rt::as_f32(r5);
+                    *ptr0.add(72).cast::<f32>() = _rt::as_f32(g5);
+                    *ptr0.add(76).cast::<f32>() = _rt::as_f32(b5);
+                    *ptr0.add(80).cast::<f32>() = _rt::as_f32(a5);
+                    let ptr6 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "create-emitter"]
+                        fn wit_import7(_: *mut u8, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import7(_: *mut u8, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import7(ptr0, ptr6) };
+                    let l8 = i32::from(*ptr6.add(0).cast::<u8>());
+                    let result10 = match l8 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l9 = *ptr6.add(4).cast::<i32>();
+                                l9 as u32
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result10
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Change the settings particles are spawned with from now on; live particles keep the
+            /// settings they were spawned with. `max-particles` keeps its original value. False for
+            /// unknown ids and invalid settings.
+            pub fn configure_emitter(emitter: EmitterId, config: EmitterConfig) -> bool {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 88]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 88]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    *ptr0.add(0).cast::<i32>() = _rt::as_i32(emitter);
+                    let EmitterConfig {
+                        position: position1,
+                        rate: rate1,
+                        max_particles: max_particles1,
+                        lifetime_ms: lifetime_ms1,
+                        angle: angle1,
+                        spread: spread1,
+                        speed: speed1,
+                        speed_jitter: speed_jitter1,
+                        gravity: gravity1,
+                        start_size: start_size1,
+                        end_size: end_size1,
+                        start_color: start_color1,
+                        end_color: end_color1,
+                    } = config;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x2,
+                        y: y2,
+                    } = position1;
+                    *ptr0.add(4).cast::<f32>() = _rt::as_f32(x2);
+                    *ptr0.add(8).cast::<f32>() = _rt::as_f32(y2);
+                    *ptr0.add(12).cast::<f32>() = _rt::as_f32(rate1);
+                    *ptr0.add(16).cast::<i32>() = _rt::as_i32(max_particles1);
+                    *ptr0.add(20).cast::<f32>() = _rt::as_f32(lifetime_ms1);
+                    *ptr0.add(24).cast::<f32>() = _rt::as_f32(angle1);
+                    *ptr0.add(28).cast::<f32>() = _rt::as_f32(spread1);
+                    *ptr0.add(32).cast::<f32>() = _rt::as_f32(speed1);
+                    *ptr0.add(36).cast::<f32>() = _rt::as_f32(speed_jitter1);
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x3,
+                        y: y3,
+                    } = gravity1;
+                    *ptr0.add(40).cast::<f32>() = _rt::as_f32(x3);
+                    *ptr0.add(44).cast::<f32>() = _rt::as_f32(y3);
+                    *ptr0.add(48).cast::<f32>() = _rt::as_f32(start_size1);
+                    *ptr0.add(52).cast::<f32>() = _rt::as_f32(end_size1);
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r4,
+                        g: g4,
+                        b: b4,
+                        a: a4,
+                    } = start_color1;
+                    *ptr0.add(56).cast::<f32>() = _rt::as_f32(r4);
+                    *ptr0.add(60).cast::<f32>() = _rt::as_f32(g4);
+                    *ptr0.add(64).cast::<f32>() = _rt::as_f32(b4);
+                    *ptr0.add(68).cast::<f32>() = _rt::as_f32(a4);
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r5,
+                        g: g5,
+                        b: b5,
+                        a: a5,
+                    } = end_color1;
+                    *ptr0.add(72).cast::<f32>() = _rt::as_f32(r5);
+                    *ptr0.add(76).cast::<f32>() = _rt::as_f32(g5);
+                    *ptr0.add(80).cast::<f32>() = _rt::as_f32(b5);
+                    *ptr0.add(84).cast::<f32>() = _rt::as_f32(a5);
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "configure-emitter"]
+                        fn wit_import6(_: *mut u8) -> i32;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import6(_: *mut u8) -> i32 {
+                        unreachable!()
+                    }
+                    let ret = unsafe { wit_import6(ptr0) };
+                    _rt::bool_lift(ret as u8)
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Spawn up to `count` particles at once, such as for an explosion with `rate` 0.
+            pub fn burst_emitter(emitter: EmitterId, count: u32) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "burst-emitter"]
+                        fn wit_import0(_: i32, _: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32, _: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(emitter), _rt::as_i32(&count)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Live particles; none for unknown ids.
+            pub fn emitter_particle_count(emitter: EmitterId) -> Option<u32> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 8]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 8]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "emitter-particle-count"]
+                        fn wit_import1(_: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(emitter), ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result4 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0.add(4).cast::<i32>();
+                                l3 as u32
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result4
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw the emitter's live particles as filled circles. The host works out where each
+            /// one is on the GPU.
+            pub fn draw_emitter(emitter: EmitterId) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-emitter"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(emitter)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Remove an emitter and its particles.
+            pub fn drop_emitter(emitter: EmitterId) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "drop-emitter"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(emitter)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Compile a WGSL post-process effect. `source` defines
+            /// `fn shade(uv: vec2<f32>) -> vec4<f32>`, returning the colour for a layer position in
+            /// 0..1. It can call `sample_layer(uv)` and read `effect.resolution` (the layer size in
+            /// physical pixels) and `effect.params`, an `array<vec4<f32>, 2>`. Declaring bindings,
+            /// entry points or override constants is an error, as is anything naga rejects;
+            /// messages give line numbers within `source`.
+            pub fn register_shader(source: &str) -> Result<ShaderId, _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let vec0 = source;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "register-shader"]
+                        fn wit_import2(_: *mut u8, _: usize, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(_: *mut u8, _: usize, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import2(ptr0.cast_mut(), len0, ptr1) };
+                    let l3 = i32::from(*ptr1.add(0).cast::<u8>());
+                    let result8 = match l3 {
+                        0 => {
+                            let e = {
+                                let l4 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                l4 as u32
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l5 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l6 = *ptr1
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len7 = l6;
+                                let bytes7 = _rt::Vec::from_raw_parts(
+                                    l5.cast(),
+                                    len7,
+                                    len7,
+                                );
+                                _rt::string_lift(bytes7)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result8
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Free a compiled effect.
+            pub fn drop_shader(shader: ShaderId) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "drop-shader"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(shader)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw everything up to the matching `pop-shader-layer` into an offscreen layer, then
+            /// composite it through `shader`. `params` fills `effect.params` in order (up to 8
+            /// floats, the rest are zero). Layers pushed inside another draw without their effect.
+            pub fn push_shader_layer(shader: ShaderId, params: &[f32]) -> () {
+                unsafe {
+                    let vec0 = params;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "push-shader-layer"]
+                        fn wit_import1(_: i32, _: *mut u8, _: usize);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8, _: usize) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(shader), ptr0.cast_mut(), len0) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// End the innermost shader layer.
+            pub fn pop_shader_layer() -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "pop-shader-layer"]
+                        fn wit_import0();
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0() {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0() };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Names of the capture devices on this machine, in `open-camera` order. Empty when
+            /// the host was built without camera support.
+            pub fn list_cameras() -> _rt::Vec<_rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 2 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 2
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "list-cameras"]
+                        fn wit_import1(_: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(ptr0) };
+                    let l2 = *ptr0.add(0).cast::<*mut u8>();
+                    let l3 = *ptr0
+                        .add(::core::mem::size_of::<*const u8>())
+                        .cast::<usize>();
+                    let base7 = l2;
+                    let len7 = l3;
+                    let mut result7 = _rt::Vec::with_capacity(len7);
+                    for i in 0..len7 {
+                        let base = base7
+                            .add(i * (2 * ::core::mem::size_of::<*const u8>()));
+                        let e7 = {
+                            let l4 = *base.add(0).cast::<*mut u8>();
+                            let l5 = *base
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<usize>();
+                            let len6 = l5;
+                            let bytes6 = _rt::Vec::from_raw_parts(l4.cast(), len6, len6);
+                            _rt::string_lift(bytes6)
+                        };
+                        result7.push(e7);
+                    }
+                    _rt::cabi_dealloc(
+                        base7,
+                        len7 * (2 * ::core::mem::size_of::<*const u8>()),
+                        ::core::mem::size_of::<*const u8>(),
+                    );
+                    let result8 = result7;
+                    result8
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Start capturing from the `device`th camera. Unless the user already answered, the
+            /// camera stays `pending` until they allow or deny it in the host's prompt.
+            pub fn open_camera(device: u32) -> Result<CameraId, _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "open-camera"]
+                        fn wit_import1(_: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(&device), ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result7 = match l2 {
+                        0 => {
+                            let e = {
+                                let l3 = *ptr0
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                l3 as u32
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l4 = *ptr0
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l5 = *ptr0
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len6 = l5;
+                                let bytes6 = _rt::Vec::from_raw_parts(
+                                    l4.cast(),
+                                    len6,
+                                    len6,
+                                );
+                                _rt::string_lift(bytes6)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result7
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Where the camera is in its life; none for unknown ids.
+            pub fn camera_status(camera: CameraId) -> Option<CameraState> {
+                unsafe {
+                    #[repr(align(1))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 2]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 2]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "camera-status"]
+                        fn wit_import1(_: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(camera), ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result4 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = i32::from(*ptr0.add(1).cast::<u8>());
+                                CameraState::_lift(l3 as u8)
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result4
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// The newest frame's size and sequence number; none until the first frame arrives.
+            pub fn camera_frame_info(camera: CameraId) -> Option<CameraFrameMetadata> {
+                unsafe {
+                    #[repr(align(8))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 24]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 24]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "camera-frame-info"]
+                        fn wit_import1(_: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(camera), ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result6 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0.add(8).cast::<i32>();
+                                let l4 = *ptr0.add(12).cast::<i32>();
+                                let l5 = *ptr0.add(16).cast::<i64>();
+                                CameraFrameMetadata {
+                                    width: l3 as u32,
+                                    height: l4 as u32,
+                                    sequence: l5 as u64,
+                                }
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result6
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw the newest frame stretched over the rectangle at `origin`; draws nothing until
+            /// a frame arrives.
+            pub fn draw_camera(
+                camera: CameraId,
+                origin: Vec2,
+                size: Vec2,
+                style: ImageStyle,
+            ) -> () {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x0,
+                        y: y0,
+                    } = origin;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = size;
+                    let ImageStyle { tint: tint2, filter: filter2, opacity: opacity2 } = style;
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r3,
+                        g: g3,
+                        b: b3,
+                        a: a3,
+                    } = tint2;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-camera"]
+                        fn wit_import4(
+                            _: i32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: i32,
+                            _: f32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import4(
+                        _: i32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import4(
+                            _rt::as_i32(camera),
+                            _rt::as_f32(x0),
+                            _rt::as_f32(y0),
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                            _rt::as_f32(r3),
+                            _rt::as_f32(g3),
+                            _rt::as_f32(b3),
+                            _rt::as_f32(a3),
+                            filter2.clone() as i32,
+                            _rt::as_f32(opacity2),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Stop capturing and release the device.
+            pub fn close_camera(camera: CameraId) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "close-camera"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(camera)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Allocate a transparent RGBA8 framebuffer on the host for software rendering. None
+            /// for sizes outside 1..=4096 or when it would exceed the image memory budget.
+            pub fn create_pixel_buffer(
+                width: u32,
+                height: u32,
+            ) -> Option<PixelBufferId> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 8]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 8]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "create-pixel-buffer"]
+                        fn wit_import1(_: i32, _: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import1(_rt::as_i32(&width), _rt::as_i32(&height), ptr0)
+                    };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result4 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0.add(4).cast::<i32>();
+                                l3 as u32
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result4
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Overwrite the rectangle at (`x`, `y`) that is `width` pixels wide with whole rows of
+            /// straight-alpha RGBA8. Only changed regions need sending; false if the rows do not
+            /// fit inside the buffer or the per-second upload rate is exhausted.
+            pub fn write_pixel_buffer(
+                buffer: PixelBufferId,
+                x: u32,
+                y: u32,
+                width: u32,
+                rgba: &[u8],
+            ) -> bool {
+                unsafe {
+                    let vec0 = rgba;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "write-pixel-buffer"]
+                        fn wit_import1(
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: *mut u8,
+                            _: usize,
+                        ) -> i32;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(
+                        _: i32,
+                        _: i32,
+                        _: i32,
+                        _: i32,
+                        _: *mut u8,
+                        _: usize,
+                    ) -> i32 {
+                        unreachable!()
+                    }
+                    let ret = unsafe {
+                        wit_import1(
+                            _rt::as_i32(buffer),
+                            _rt::as_i32(&x),
+                            _rt::as_i32(&y),
+                            _rt::as_i32(&width),
+                            ptr0.cast_mut(),
+                            len0,
+                        )
+                    };
+                    _rt::bool_lift(ret as u8)
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw the buffer's current contents stretched over the rectangle at `origin`.
+            pub fn present_pixel_buffer(
+                buffer: PixelBufferId,
+                origin: Vec2,
+                size: Vec2,
+            ) -> () {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x0,
+                        y: y0,
+                    } = origin;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = size;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "present-pixel-buffer"]
+                        fn wit_import2(_: i32, _: f32, _: f32, _: f32, _: f32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(
+                        _: i32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import2(
+                            _rt::as_i32(buffer),
+                            _rt::as_f32(x0),
+                            _rt::as_f32(y0),
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Free a pixel buffer.
+            pub fn drop_pixel_buffer(buffer: PixelBufferId) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "drop-pixel-buffer"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_i32(buffer)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Draw `image` stretched over the rectangle at `origin`.
+            pub fn draw_image(
+                image: ImageId,
+                origin: Vec2,
+                size: Vec2,
+                style: ImageStyle,
+            ) -> () {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x0,
+                        y: y0,
+                    } = origin;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = size;
+                    let ImageStyle { tint: tint2, filter: filter2, opacity: opacity2 } = style;
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r3,
+                        g: g3,
+                        b: b3,
+                        a: a3,
+                    } = tint2;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "draw-image"]
+                        fn wit_import4(
+                            _: i32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: i32,
+                            _: f32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import4(
+                        _: i32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import4(
+                            _rt::as_i32(image),
+                            _rt::as_f32(x0),
+                            _rt::as_f32(y0),
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                            _rt::as_f32(r3),
+                            _rt::as_f32(g3),
+                            _rt::as_f32(b3),
+                            _rt::as_f32(a3),
+                            filter2.clone() as i32,
+                            _rt::as_f32(opacity2),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Fill the rectangle at `origin` with `image` scaled to `tile-size` logical pixels
+            /// and repeated from the rectangle's top-left corner.
+            pub fn fill_pattern(
+                origin: Vec2,
+                size: Vec2,
+                image: ImageId,
+                tile_size: Vec2,
+                repeat: PatternRepeat,
+                style: ImageStyle,
+            ) -> () {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x0,
+                        y: y0,
+                    } = origin;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x1,
+                        y: y1,
+                    } = size;
+                    let super::super::super::vello::canvas::math::Vec2 {
+                        x: x2,
+                        y: y2,
+                    } = tile_size;
+                    let ImageStyle { tint: tint3, filter: filter3, opacity: opacity3 } = style;
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r4,
+                        g: g4,
+                        b: b4,
+                        a: a4,
+                    } = tint3;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "fill-pattern"]
+                        fn wit_import5(
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: i32,
+                            _: f32,
+                            _: f32,
+                            _: i32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: f32,
+                            _: i32,
+                            _: f32,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import5(
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                        _: f32,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import5(
+                            _rt::as_f32(x0),
+                            _rt::as_f32(y0),
+                            _rt::as_f32(x1),
+                            _rt::as_f32(y1),
+                            _rt::as_i32(image),
+                            _rt::as_f32(x2),
+                            _rt::as_f32(y2),
+                            repeat.clone() as i32,
+                            _rt::as_f32(r4),
+                            _rt::as_f32(g4),
+                            _rt::as_f32(b4),
+                            _rt::as_f32(a4),
+                            filter3.clone() as i32,
+                            _rt::as_f32(opacity3),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Save this frame as a vector file once `frame` returns. The host picks the file name
+            /// and directory (`--export-dir`); repeated requests for one format save one file.
+            /// Only valid during `frame`.
+            pub fn export_frame(format: ExportFormat) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "export-frame"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(format.clone() as i32) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Request another animation frame; host coalesces multiple calls.
+            pub fn request_frame() -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "request-frame"]
+                        fn wit_import0();
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0() {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0() };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Real milliseconds between the current frame and the previous one. `frame`'s `dt-ms`
+            /// is time-scaled and capped (100 ms by default) so a stall such as a laptop sleeping
+            /// does not arrive as one huge step; guests keeping wall-clock time resync from this.
+            pub fn frame_elapsed_ms() -> f64 {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "frame-elapsed-ms"]
+                        fn wit_import0() -> f64;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0() -> f64 {
+                        unreachable!()
+                    }
+                    let ret = unsafe { wit_import0() };
+                    ret
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Tween `id` from `from` to `to` over `duration-ms`, replacing any tween with that id.
+            /// The host steps tweens by each frame's `dt-ms` just before calling `frame`, so
+            /// `animated-value` reads one value for the whole frame, on time however the guest's
+            /// callbacks are scheduled, and frames keep coming until every tween finishes. Tweens
+            /// carry on across hot reloads. False when a value is not finite, the duration is
+            /// negative, or 256 tweens exist, finished ones included.
+            pub fn animate(
+                id: u32,
+                from: f32,
+                to: f32,
+                duration_ms: f32,
+                easing: Easing,
+            ) -> bool {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "animate"]
+                        fn wit_import0(_: i32, _: f32, _: f32, _: f32, _: i32) -> i32;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(
+                        _: i32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                        _: i32,
+                    ) -> i32 {
+                        unreachable!()
+                    }
+                    let ret = unsafe {
+                        wit_import0(
+                            _rt::as_i32(&id),
+                            _rt::as_f32(&from),
+                            _rt::as_f32(&to),
+                            _rt::as_f32(&duration_ms),
+                            easing.clone() as i32,
+                        )
+                    };
+                    _rt::bool_lift(ret as u8)
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Spring `id` towards `target` as a unit mass on a damped spring, stepped like tweens
+            /// and replacing any tween or spring with that id. It starts from the value and speed
+            /// that id's animation has reached, so a spring can take over a moving tween or be
+            /// retargeted mid-flight; a new id starts at rest on `target`. `damping` of
+            /// 2 × √`stiffness` is critically damped, settling fastest without overshoot; less
+            /// bounces and more creeps in. The spring comes to rest on `target` once within 0.001
+            /// of it. False when a value is not finite, `stiffness` or `damping` is not positive,
+            /// or 256 tweens and springs exist.
+            pub fn spring(id: u32, target: f32, stiffness: f32, damping: f32) -> bool {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "spring"]
+                        fn wit_import0(_: i32, _: f32, _: f32, _: f32) -> i32;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(
+                        _: i32,
+                        _: f32,
+                        _: f32,
+                        _: f32,
+                    ) -> i32 {
+                        unreachable!()
+                    }
+                    let ret = unsafe {
+                        wit_import0(
+                            _rt::as_i32(&id),
+                            _rt::as_f32(&target),
+                            _rt::as_f32(&stiffness),
+                            _rt::as_f32(&damping),
+                        )
+                    };
+                    _rt::bool_lift(ret as u8)
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Tween or spring `id`'s value this frame; a finished tween holds its `to`. None for
+            /// unknown ids.
+            pub fn animated_value(id: u32) -> Option<f32> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 8]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 8]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "animated-value"]
+                        fn wit_import1(_: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(&id), ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result4 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0.add(4).cast::<f32>();
+                                l3
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result4
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Forget tween or spring `id`, returning the value it reached; none for unknown ids.
+            pub fn stop_animating(id: u32) -> Option<f32> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 8]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 8]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "stop-animating"]
+                        fn wit_import1(_: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(&id), ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result4 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0.add(4).cast::<f32>();
+                                l3
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result4
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Debug logging surfaced via host console.
+            pub fn log(level: LogLevel, message: &str) -> () {
+                unsafe {
+                    let vec0 = message;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "log"]
+                        fn wit_import1(_: i32, _: *mut u8, _: usize);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: *mut u8, _: usize) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(level.clone() as i32, ptr0.cast_mut(), len0) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Stack the window above or below other windows.
+            pub fn set_window_level(level: WindowLevel) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "set-window-level"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(level.clone() as i32) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Overall window opacity in 0.0..=1.0 (1.0 is fully opaque).
+            pub fn set_window_opacity(opacity: f32) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "set-window-opacity"]
+                        fn wit_import0(_: f32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: f32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_f32(&opacity)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Colour the host shows where the guest draws nothing: behind frames that do not
+            /// `clear`, and before the first frame. Kept until changed, including across reloads.
+            pub fn set_background_color(color: Color) -> () {
+                unsafe {
+                    let super::super::super::vello::canvas::math::Color {
+                        r: r0,
+                        g: g0,
+                        b: b0,
+                        a: a0,
+                    } = color;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "set-background-color"]
+                        fn wit_import1(_: f32, _: f32, _: f32, _: f32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: f32, _: f32, _: f32, _: f32) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import1(
+                            _rt::as_f32(r0),
+                            _rt::as_f32(g0),
+                            _rt::as_f32(b0),
+                            _rt::as_f32(a0),
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Move the window frame. Ignored on platforms where windows cannot place
+            /// themselves (Wayland).
+            pub fn set_window_position(position: WindowPosition) -> () {
+                unsafe {
+                    let WindowPosition { x: x0, y: y0 } = position;
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "set-window-position"]
+                        fn wit_import1(_: i32, _: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i32, _: i32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i32(x0), _rt::as_i32(y0)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Where the window currently is; none on Wayland or when running headless.
+            pub fn get_window_position() -> Option<WindowPosition> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 12]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 12]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "get-window-position"]
+                        fn wit_import1(_: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result5 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0.add(4).cast::<i32>();
+                                let l4 = *ptr0.add(8).cast::<i32>();
+                                WindowPosition { x: l3, y: l4 }
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result5
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// When enabled, pointer input passes through the window to whatever is beneath it.
+            pub fn set_click_through(enabled: bool) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "set-click-through"]
+                        fn wit_import0(_: i32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import0(
+                            match &enabled {
+                                true => 1,
+                                false => 0,
+                            },
+                        )
+                    };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Ask the host to close the window and exit once the current callback returns.
+            pub fn request_quit() -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "request-quit"]
+                        fn wit_import0();
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0() {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0() };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Replace this component with the one at `target` once the current callback returns,
+            /// keeping the window open. `target` is a path, relative to this component's file, or
+            /// a `file://` URL. Fails unless the host runs with `--allow-navigation`.
+            pub fn navigate(target: &str) -> Result<(), _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let vec0 = target;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "navigate"]
+                        fn wit_import2(_: *mut u8, _: usize, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(_: *mut u8, _: usize, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import2(ptr0.cast_mut(), len0, ptr1) };
+                    let l3 = i32::from(*ptr1.add(0).cast::<u8>());
+                    let result7 = match l3 {
+                        0 => {
+                            let e = ();
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l4 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l5 = *ptr1
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len6 = l5;
+                                let bytes6 = _rt::Vec::from_raw_parts(
+                                    l4.cast(),
+                                    len6,
+                                    len6,
+                                );
+                                _rt::string_lift(bytes6)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result7
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// The monitor the window is currently on; none when running headless or the
+            /// platform cannot tell.
+            pub fn get_display_info() -> Option<DisplayInfo> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 16 + 5 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 16
+                            + 5 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "get-display-info"]
+                        fn wit_import1(_: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result12 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = i32::from(
+                                    *ptr0.add(::core::mem::size_of::<*const u8>()).cast::<u8>(),
+                                );
+                                let l7 = *ptr0
+                                    .add(4 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                let l8 = *ptr0
+                                    .add(4 + 4 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                let l9 = *ptr0
+                                    .add(8 + 4 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<f32>();
+                                let l10 = i32::from(
+                                    *ptr0
+                                        .add(12 + 4 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<u8>(),
+                                );
+                                DisplayInfo {
+                                    name: match l3 {
+                                        0 => None,
+                                        1 => {
+                                            let e = {
+                                                let l4 = *ptr0
+                                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                                    .cast::<*mut u8>();
+                                                let l5 = *ptr0
+                                                    .add(3 * ::core::mem::size_of::<*const u8>())
+                                                    .cast::<usize>();
+                                                let len6 = l5;
+                                                let bytes6 = _rt::Vec::from_raw_parts(
+                                                    l4.cast(),
+                                                    len6,
+                                                    len6,
+                                                );
+                                                _rt::string_lift(bytes6)
+                                            };
+                                            Some(e)
+                                        }
+                                        _ => _rt::invalid_enum_discriminant(),
+                                    },
+                                    width: l7 as u32,
+                                    height: l8 as u32,
+                                    scale_factor: l9,
+                                    refresh_rate_millihertz: match l10 {
+                                        0 => None,
+                                        1 => {
+                                            let e = {
+                                                let l11 = *ptr0
+                                                    .add(16 + 4 * ::core::mem::size_of::<*const u8>())
+                                                    .cast::<i32>();
+                                                l11 as u32
+                                            };
+                                            Some(e)
+                                        }
+                                        _ => _rt::invalid_enum_discriminant(),
+                                    },
+                                }
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result12
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Arguments given after `--` on the host command line (also visible via WASI argv).
+            pub fn get_launch_args() -> _rt::Vec<_rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 2 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 2
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "get-launch-args"]
+                        fn wit_import1(_: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(ptr0) };
+                    let l2 = *ptr0.add(0).cast::<*mut u8>();
+                    let l3 = *ptr0
+                        .add(::core::mem::size_of::<*const u8>())
+                        .cast::<usize>();
+                    let base7 = l2;
+                    let len7 = l3;
+                    let mut result7 = _rt::Vec::with_capacity(len7);
+                    for i in 0..len7 {
+                        let base = base7
+                            .add(i * (2 * ::core::mem::size_of::<*const u8>()));
+                        let e7 = {
+                            let l4 = *base.add(0).cast::<*mut u8>();
+                            let l5 = *base
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<usize>();
+                            let len6 = l5;
+                            let bytes6 = _rt::Vec::from_raw_parts(l4.cast(), len6, len6);
+                            _rt::string_lift(bytes6)
+                        };
+                        result7.push(e7);
+                    }
+                    _rt::cabi_dealloc(
+                        base7,
+                        len7 * (2 * ::core::mem::size_of::<*const u8>()),
+                        ::core::mem::size_of::<*const u8>(),
+                    );
+                    let result8 = result7;
+                    result8
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Optional features this host provides, so a guest can adapt its UI at `init`
+            /// instead of calling into a no-op. Names are `images`, `svg`, `lottie`, `particles`,
+            /// `shaders`, `pixel-buffers` and `export-frame` for drawing features, `camera` when
+            /// capture is built in and not denied, `navigation` under `--allow-navigation`,
+            /// `power-state` where the platform reports it, `haptics` when gamepad rumble is built
+            /// in, and `storage` when the host keeps data for components, which covers both
+            /// `storage-set` and the `database` interface. Unknown names should be ignored.
+            pub fn host_capabilities() -> _rt::Vec<_rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 2 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 2
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "host-capabilities"]
+                        fn wit_import1(_: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(ptr0) };
+                    let l2 = *ptr0.add(0).cast::<*mut u8>();
+                    let l3 = *ptr0
+                        .add(::core::mem::size_of::<*const u8>())
+                        .cast::<usize>();
+                    let base7 = l2;
+                    let len7 = l3;
+                    let mut result7 = _rt::Vec::with_capacity(len7);
+                    for i in 0..len7 {
+                        let base = base7
+                            .add(i * (2 * ::core::mem::size_of::<*const u8>()));
+                        let e7 = {
+                            let l4 = *base.add(0).cast::<*mut u8>();
+                            let l5 = *base
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<usize>();
+                            let len6 = l5;
+                            let bytes6 = _rt::Vec::from_raw_parts(l4.cast(), len6, len6);
+                            _rt::string_lift(bytes6)
+                        };
+                        result7.push(e7);
+                    }
+                    _rt::cabi_dealloc(
+                        base7,
+                        len7 * (2 * ::core::mem::size_of::<*const u8>()),
+                        ::core::mem::size_of::<*const u8>(),
+                    );
+                    let result8 = result7;
+                    result8
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Give up `capability` for the rest of this instance's life, e.g. `storage` once
+            /// settings are loaded, so code that runs later cannot use it. Calls that need it then
+            /// fail or do nothing as if the host lacked it, `host-capabilities` stops listing it,
+            /// and tasks and workers spawned afterwards lack it too. A reload or `navigate` starts
+            /// with everything back. Accepts `camera`, `export-frame`, `haptics`, `navigation`,
+            /// `power-state`, `storage` and `net`, which this host never grants; errors for
+            /// drawing features and unknown names so a typo is not mistaken for a sandbox.
+            pub fn drop_capability(capability: &str) -> Result<(), _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let vec0 = capability;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "drop-capability"]
+                        fn wit_import2(_: *mut u8, _: usize, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(_: *mut u8, _: usize, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import2(ptr0.cast_mut(), len0, ptr1) };
+                    let l3 = i32::from(*ptr1.add(0).cast::<u8>());
+                    let result7 = match l3 {
+                        0 => {
+                            let e = ();
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l4 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l5 = *ptr1
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len6 = l5;
+                                let bytes6 = _rt::Vec::from_raw_parts(
+                                    l4.cast(),
+                                    len6,
+                                    len6,
+                                );
+                                _rt::string_lift(bytes6)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result7
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Host and Vello versions, the GPU in use, and the operating system, so a guest can
+            /// show a diagnostics screen or work around a known driver issue.
+            pub fn host_info() -> HostEnvironment {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 12 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 12
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "host-info"]
+                        fn wit_import1(_: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(ptr0) };
+                    let l2 = *ptr0.add(0).cast::<*mut u8>();
+                    let l3 = *ptr0
+                        .add(::core::mem::size_of::<*const u8>())
+                        .cast::<usize>();
+                    let len4 = l3;
+                    let bytes4 = _rt::Vec::from_raw_parts(l2.cast(), len4, len4);
+                    let l5 = *ptr0
+                        .add(2 * ::core::mem::size_of::<*const u8>())
+                        .cast::<*mut u8>();
+                    let l6 = *ptr0
+                        .add(3 * ::core::mem::size_of::<*const u8>())
+                        .cast::<usize>();
+                    let len7 = l6;
+                    let bytes7 = _rt::Vec::from_raw_parts(l5.cast(), len7, len7);
+                    let l8 = i32::from(
+                        *ptr0.add(4 * ::core::mem::size_of::<*const u8>()).cast::<u8>(),
+                    );
+                    let l12 = i32::from(
+                        *ptr0.add(7 * ::core::mem::size_of::<*const u8>()).cast::<u8>(),
+                    );
+                    let l16 = *ptr0
+                        .add(10 * ::core::mem::size_of::<*const u8>())
+                        .cast::<*mut u8>();
+                    let l17 = *ptr0
+                        .add(11 * ::core::mem::size_of::<*const u8>())
+                        .cast::<usize>();
+                    let len18 = l17;
+                    let bytes18 = _rt::Vec::from_raw_parts(l16.cast(), len18, len18);
+                    let result19 = HostEnvironment {
+                        host_version: _rt::string_lift(bytes4),
+                        vello_version: _rt::string_lift(bytes7),
+                        gpu_name: match l8 {
+                            0 => None,
+                            1 => {
+                                let e = {
+                                    let l9 = *ptr0
+                                        .add(5 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<*mut u8>();
+                                    let l10 = *ptr0
+                                        .add(6 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<usize>();
+                                    let len11 = l10;
+                                    let bytes11 = _rt::Vec::from_raw_parts(
+                                        l9.cast(),
+                                        len11,
+                                        len11,
+                                    );
+                                    _rt::string_lift(bytes11)
+                                };
+                                Some(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        },
+                        backend: match l12 {
+                            0 => None,
+                            1 => {
+                                let e = {
+                                    let l13 = *ptr0
+                                        .add(8 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<*mut u8>();
+                                    let l14 = *ptr0
+                                        .add(9 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<usize>();
+                                    let len15 = l14;
+                                    let bytes15 = _rt::Vec::from_raw_parts(
+                                        l13.cast(),
+                                        len15,
+                                        len15,
+                                    );
+                                    _rt::string_lift(bytes15)
+                                };
+                                Some(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        },
+                        os: _rt::string_lift(bytes18),
+                    };
+                    result19
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// The user's locale as a BCP 47 tag such as `en-US`, from `--locale` or the host
+            /// environment.
+            pub fn get_locale() -> _rt::String {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 2 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 2
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "get-locale"]
+                        fn wit_import1(_: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(ptr0) };
+                    let l2 = *ptr0.add(0).cast::<*mut u8>();
+                    let l3 = *ptr0
+                        .add(::core::mem::size_of::<*const u8>())
+                        .cast::<usize>();
+                    let len4 = l3;
+                    let bytes4 = _rt::Vec::from_raw_parts(l2.cast(), len4, len4);
+                    let result5 = _rt::string_lift(bytes4);
+                    result5
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// `value` in the user's locale with exactly `fraction-digits` digits after the
+            /// decimal separator (at most 20), e.g. `1,234.50` or `1.234,50`.
+            pub fn format_number(value: f64, fraction_digits: u8) -> _rt::String {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 2 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 2
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "format-number"]
+                        fn wit_import1(_: f64, _: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: f64, _: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import1(
+                            _rt::as_f64(&value),
+                            _rt::as_i32(&fraction_digits),
+                            ptr0,
+                        )
+                    };
+                    let l2 = *ptr0.add(0).cast::<*mut u8>();
+                    let l3 = *ptr0
+                        .add(::core::mem::size_of::<*const u8>())
+                        .cast::<usize>();
+                    let len4 = l3;
+                    let bytes4 = _rt::Vec::from_raw_parts(l2.cast(), len4, len4);
+                    let result5 = _rt::string_lift(bytes4);
+                    result5
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// `value` as an amount of `currency`, an ISO 4217 code such as `EUR`, in the user's
+            /// locale; errors when the code is malformed.
+            pub fn format_currency(
+                value: f64,
+                currency: &str,
+            ) -> Result<_rt::String, _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let vec0 = currency;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "format-currency"]
+                        fn wit_import2(_: f64, _: *mut u8, _: usize, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(
+                        _: f64,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import2(_rt::as_f64(&value), ptr0.cast_mut(), len0, ptr1)
+                    };
+                    let l3 = i32::from(*ptr1.add(0).cast::<u8>());
+                    let result10 = match l3 {
+                        0 => {
+                            let e = {
+                                let l4 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l5 = *ptr1
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len6 = l5;
+                                let bytes6 = _rt::Vec::from_raw_parts(
+                                    l4.cast(),
+                                    len6,
+                                    len6,
+                                );
+                                _rt::string_lift(bytes6)
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l7 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l8 = *ptr1
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len9 = l8;
+                                let bytes9 = _rt::Vec::from_raw_parts(
+                                    l7.cast(),
+                                    len9,
+                                    len9,
+                                );
+                                _rt::string_lift(bytes9)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result10
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// A timestamp in milliseconds since the Unix epoch, in the host's local time zone and
+            /// the user's locale; none when it is outside the representable range.
+            pub fn format_datetime(
+                unix_ms: i64,
+                style: DatetimeStyle,
+            ) -> Option<_rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "format-datetime"]
+                        fn wit_import1(_: i64, _: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i64, _: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import1(_rt::as_i64(&unix_ms), style.clone() as i32, ptr0)
+                    };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result6 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l4 = *ptr0
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len5 = l4;
+                                let bytes5 = _rt::Vec::from_raw_parts(
+                                    l3.cast(),
+                                    len5,
+                                    len5,
+                                );
+                                _rt::string_lift(bytes5)
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result6
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// The host's IANA time zone name, such as `Europe/Berlin`; none when the platform
+            /// does not say.
+            pub fn local_timezone() -> Option<_rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "local-timezone"]
+                        fn wit_import1(_: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result6 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l4 = *ptr0
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len5 = l4;
+                                let bytes5 = _rt::Vec::from_raw_parts(
+                                    l3.cast(),
+                                    len5,
+                                    len5,
+                                );
+                                _rt::string_lift(bytes5)
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result6
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// The user's display preferences, read once at startup.
+            pub fn get_preferences() -> Preferences {
+                unsafe {
+                    #[repr(align(1))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 2]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 2]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "get-preferences"]
+                        fn wit_import1(_: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let l3 = i32::from(*ptr0.add(1).cast::<u8>());
+                    let result4 = Preferences {
+                        high_contrast: _rt::bool_lift(l2 as u8),
+                        forced_colors: _rt::bool_lift(l3 as u8),
+                    };
+                    result4
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Seconds to add to UTC for the host's local time at `unix-ms` (3600 for Berlin in
+            /// winter, 7200 in summer); none when the timestamp is out of range.
+            pub fn utc_offset_at(unix_ms: i64) -> Option<i32> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 8]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 8]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "utc-offset-at"]
+                        fn wit_import1(_: i64, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: i64, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(_rt::as_i64(&unix_ms), ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result4 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = *ptr0.add(4).cast::<i32>();
+                                l3
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result4
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Battery and power-saving state; none when the platform does not report it. Changes
+            /// arrive as `power-state-changed`.
+            pub fn power_state() -> Option<PowerStatus> {
+                unsafe {
+                    #[repr(align(1))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 5]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 5]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "power-state"]
+                        fn wit_import1(_: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(ptr0) };
+                    let l2 = i32::from(*ptr0.add(0).cast::<u8>());
+                    let result7 = match l2 {
+                        0 => None,
+                        1 => {
+                            let e = {
+                                let l3 = i32::from(*ptr0.add(1).cast::<u8>());
+                                let l4 = i32::from(*ptr0.add(2).cast::<u8>());
+                                let l6 = i32::from(*ptr0.add(4).cast::<u8>());
+                                PowerStatus {
+                                    on_battery: _rt::bool_lift(l3 as u8),
+                                    battery_percent: match l4 {
+                                        0 => None,
+                                        1 => {
+                                            let e = {
+                                                let l5 = i32::from(*ptr0.add(3).cast::<u8>());
+                                                l5 as u8
+                                            };
+                                            Some(e)
+                                        }
+                                        _ => _rt::invalid_enum_discriminant(),
+                                    },
+                                    power_saver: _rt::bool_lift(l6 as u8),
+                                }
+                            };
+                            Some(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result7
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Play tactile feedback on connected gamepads with rumble motors. Returns whether any
+            /// device played it; false on hosts without the `haptics` capability.
+            pub fn haptic_feedback(kind: HapticKind) -> bool {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "haptic-feedback"]
+                        fn wit_import0(_: i32) -> i32;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) -> i32 {
+                        unreachable!()
+                    }
+                    let ret = unsafe { wit_import0(kind.clone() as i32) };
+                    _rt::bool_lift(ret as u8)
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// The value stored under `key` in `scope`, persisted across runs; none when unset.
+            /// Errors when the namespace is not shared with this component or storage is
+            /// unavailable.
+            pub fn storage_get(
+                scope: &StorageScope,
+                key: &str,
+            ) -> Result<Option<_rt::Vec<u8>>, _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 4 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 4
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let (result2_0, result2_1, result2_2, result2_3) = match scope {
+                        StorageScope::Private => {
+                            (0i32, 0i32, ::core::ptr::null_mut(), 0usize)
+                        }
+                        StorageScope::Shared(e) => {
+                            let (result1_0, result1_1, result1_2) = match e {
+                                Some(e) => {
+                                    let vec0 = e;
+                                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                                    let len0 = vec0.len();
+                                    (1i32, ptr0.cast_mut(), len0)
+                                }
+                                None => (0i32, ::core::ptr::null_mut(), 0usize),
+                            };
+                            (1i32, result1_0, result1_1, result1_2)
+                        }
+                    };
+                    let vec3 = key;
+                    let ptr3 = vec3.as_ptr().cast::<u8>();
+                    let len3 = vec3.len();
+                    let ptr4 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "storage-get"]
+                        fn wit_import5(
+                            _: i32,
+                            _: i32,
+                            _: *mut u8,
+                            _: usize,
+                            _: *mut u8,
+                            _: usize,
+                            _: *mut u8,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import5(
+                        _: i32,
+                        _: i32,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import5(
+                            result2_0,
+                            result2_1,
+                            result2_2,
+                            result2_3,
+                            ptr3.cast_mut(),
+                            len3,
+                            ptr4,
+                        )
+                    };
+                    let l6 = i32::from(*ptr4.add(0).cast::<u8>());
+                    let result14 = match l6 {
+                        0 => {
+                            let e = {
+                                let l7 = i32::from(
+                                    *ptr4.add(::core::mem::size_of::<*const u8>()).cast::<u8>(),
+                                );
+                                match l7 {
+                                    0 => None,
+                                    1 => {
+                                        let e = {
+                                            let l8 = *ptr4
+                                                .add(2 * ::core::mem::size_of::<*const u8>())
+                                                .cast::<*mut u8>();
+                                            let l9 = *ptr4
+                                                .add(3 * ::core::mem::size_of::<*const u8>())
+                                                .cast::<usize>();
+                                            let len10 = l9;
+                                            _rt::Vec::from_raw_parts(l8.cast(), len10, len10)
+                                        };
+                                        Some(e)
+                                    }
+                                    _ => _rt::invalid_enum_discriminant(),
+                                }
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l11 = *ptr4
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l12 = *ptr4
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len13 = l12;
+                                let bytes13 = _rt::Vec::from_raw_parts(
+                                    l11.cast(),
+                                    len13,
+                                    len13,
+                                );
+                                _rt::string_lift(bytes13)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result14
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Store `value` under `key` (at most 1 KiB) in `scope`. Each namespace holds up to
+            /// 5 MiB of keys and values.
+            pub fn storage_set(
+                scope: &StorageScope,
+                key: &str,
+                value: &[u8],
+            ) -> Result<(), _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let (result2_0, result2_1, result2_2, result2_3) = match scope {
+                        StorageScope::Private => {
+                            (0i32, 0i32, ::core::ptr::null_mut(), 0usize)
+                        }
+                        StorageScope::Shared(e) => {
+                            let (result1_0, result1_1, result1_2) = match e {
+                                Some(e) => {
+                                    let vec0 = e;
+                                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                                    let len0 = vec0.len();
+                                    (1i32, ptr0.cast_mut(), len0)
+                                }
+                                None => (0i32, ::core::ptr::null_mut(), 0usize),
+                            };
+                            (1i32, result1_0, result1_1, result1_2)
+                        }
+                    };
+                    let vec3 = key;
+                    let ptr3 = vec3.as_ptr().cast::<u8>();
+                    let len3 = vec3.len();
+                    let vec4 = value;
+                    let ptr4 = vec4.as_ptr().cast::<u8>();
+                    let len4 = vec4.len();
+                    let ptr5 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "storage-set"]
+                        fn wit_import6(
+                            _: i32,
+                            _: i32,
+                            _: *mut u8,
+                            _: usize,
+                            _: *mut u8,
+                            _: usize,
+                            _: *mut u8,
+                            _: usize,
+                            _: *mut u8,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import6(
+                        _: i32,
+                        _: i32,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import6(
+                            result2_0,
+                            result2_1,
+                            result2_2,
+                            result2_3,
+                            ptr3.cast_mut(),
+                            len3,
+                            ptr4.cast_mut(),
+                            len4,
+                            ptr5,
+                        )
+                    };
+                    let l7 = i32::from(*ptr5.add(0).cast::<u8>());
+                    let result11 = match l7 {
+                        0 => {
+                            let e = ();
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l8 = *ptr5
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l9 = *ptr5
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len10 = l9;
+                                let bytes10 = _rt::Vec::from_raw_parts(
+                                    l8.cast(),
+                                    len10,
+                                    len10,
+                                );
+                                _rt::string_lift(bytes10)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result11
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Remove `key` from `scope`; removing a missing key succeeds.
+            pub fn storage_delete(
+                scope: &StorageScope,
+                key: &str,
+            ) -> Result<(), _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let (result2_0, result2_1, result2_2, result2_3) = match scope {
+                        StorageScope::Private => {
+                            (0i32, 0i32, ::core::ptr::null_mut(), 0usize)
+                        }
+                        StorageScope::Shared(e) => {
+                            let (result1_0, result1_1, result1_2) = match e {
+                                Some(e) => {
+                                    let vec0 = e;
+                                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                                    let len0 = vec0.len();
+                                    (1i32, ptr0.cast_mut(), len0)
+                                }
+                                None => (0i32, ::core::ptr::null_mut(), 0usize),
+                            };
+                            (1i32, result1_0, result1_1, result1_2)
+                        }
+                    };
+                    let vec3 = key;
+                    let ptr3 = vec3.as_ptr().cast::<u8>();
+                    let len3 = vec3.len();
+                    let ptr4 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "storage-delete"]
+                        fn wit_import5(
+                            _: i32,
+                            _: i32,
+                            _: *mut u8,
+                            _: usize,
+                            _: *mut u8,
+                            _: usize,
+                            _: *mut u8,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import5(
+                        _: i32,
+                        _: i32,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import5(
+                            result2_0,
+                            result2_1,
+                            result2_2,
+                            result2_3,
+                            ptr3.cast_mut(),
+                            len3,
+                            ptr4,
+                        )
+                    };
+                    let l6 = i32::from(*ptr4.add(0).cast::<u8>());
+                    let result10 = match l6 {
+                        0 => {
+                            let e = ();
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l7 = *ptr4
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l8 = *ptr4
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len9 = l8;
+                                let bytes9 = _rt::Vec::from_raw_parts(
+                                    l7.cast(),
+                                    len9,
+                                    len9,
+                                );
+                                _rt::string_lift(bytes9)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result10
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Start `run-task` with `name` and `input` in a second instance of this component, on
+            /// its own thread, for work that would block `frame`. The call timeout does not apply
+            /// to it. Progress and the result arrive as `task-progress` and `task-finished`. Errors
+            /// with 8 tasks already in flight.
+            pub fn spawn_task(name: &str, input: &[u8]) -> Result<TaskId, _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let vec0 = name;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let vec1 = input;
+                    let ptr1 = vec1.as_ptr().cast::<u8>();
+                    let len1 = vec1.len();
+                    let ptr2 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "spawn-task"]
+                        fn wit_import3(
+                            _: *mut u8,
+                            _: usize,
+                            _: *mut u8,
+                            _: usize,
+                            _: *mut u8,
+                        );
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import3(
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import3(ptr0.cast_mut(), len0, ptr1.cast_mut(), len1, ptr2)
+                    };
+                    let l4 = i32::from(*ptr2.add(0).cast::<u8>());
+                    let result9 = match l4 {
+                        0 => {
+                            let e = {
+                                let l5 = *ptr2
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                l5 as u32
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l6 = *ptr2
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l7 = *ptr2
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len8 = l7;
+                                let bytes8 = _rt::Vec::from_raw_parts(
+                                    l6.cast(),
+                                    len8,
+                                    len8,
+                                );
+                                _rt::string_lift(bytes8)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result9
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// From inside `run-task`: report how far along the task is, from 0 to 1. Ignored
+            /// anywhere else. Traps once the host has abandoned the task, e.g. on reload.
+            pub fn report_task_progress(progress: f32) -> () {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "report-task-progress"]
+                        fn wit_import0(_: f32);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: f32) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import0(_rt::as_f32(&progress)) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Instantiate a `canvas-worker` component on its own thread, limited to
+            /// `permissions`, e.g. to run user-provided plugins with less access than this
+            /// component. Messages go to it with `post-to-worker` and come back as
+            /// `worker-message`. Errors for URLs that do not name a component file in this
+            /// component's directory or with 8 workers already running; later failures arrive as
+            /// `worker-failed`.
+            pub fn spawn_worker(
+                source: &WorkerSource,
+                permissions: WorkerPermissions,
+            ) -> Result<WorkerId, _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let (result2_0, result2_1, result2_2) = match source {
+                        WorkerSource::Bytes(e) => {
+                            let vec0 = e;
+                            let ptr0 = vec0.as_ptr().cast::<u8>();
+                            let len0 = vec0.len();
+                            (0i32, ptr0.cast_mut(), len0)
+                        }
+                        WorkerSource::Url(e) => {
+                            let vec1 = e;
+                            let ptr1 = vec1.as_ptr().cast::<u8>();
+                            let len1 = vec1.len();
+                            (1i32, ptr1.cast_mut(), len1)
+                        }
+                    };
+                    let flags3 = permissions;
+                    let ptr4 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "spawn-worker"]
+                        fn wit_import5(_: i32, _: *mut u8, _: usize, _: i32, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import5(
+                        _: i32,
+                        _: *mut u8,
+                        _: usize,
+                        _: i32,
+                        _: *mut u8,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import5(
+                            result2_0,
+                            result2_1,
+                            result2_2,
+                            (flags3.bits() >> 0) as i32,
+                            ptr4,
+                        )
+                    };
+                    let l6 = i32::from(*ptr4.add(0).cast::<u8>());
+                    let result11 = match l6 {
+                        0 => {
+                            let e = {
+                                let l7 = *ptr4
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                l7 as u32
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l8 = *ptr4
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l9 = *ptr4
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len10 = l9;
+                                let bytes10 = _rt::Vec::from_raw_parts(
+                                    l8.cast(),
+                                    len10,
+                                    len10,
+                                );
+                                _rt::string_lift(bytes10)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result11
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Queue `message` (at most 1 MiB) for the worker's `on-message`. Errors for unknown
+            /// or failed workers and when 256 messages are already waiting.
+            pub fn post_to_worker(
+                worker: WorkerId,
+                message: &[u8],
+            ) -> Result<(), _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let vec0 = message;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "post-to-worker"]
+                        fn wit_import2(_: i32, _: *mut u8, _: usize, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(
+                        _: i32,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe {
+                        wit_import2(_rt::as_i32(worker), ptr0.cast_mut(), len0, ptr1)
+                    };
+                    let l3 = i32::from(*ptr1.add(0).cast::<u8>());
+                    let result7 = match l3 {
+                        0 => {
+                            let e = ();
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l4 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l5 = *ptr1
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len6 = l5;
+                                let bytes6 = _rt::Vec::from_raw_parts(
+                                    l4.cast(),
+                                    len6,
+                                    len6,
+                                );
+                                _rt::string_lift(bytes6)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result7
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Stop a worker once it has handled the message in progress; false for an unknown
+            /// id.
+            pub fn terminate_worker(worker: WorkerId) -> bool {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "terminate-worker"]
+                        fn wit_import0(_: i32) -> i32;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) -> i32 {
+                        unreachable!()
+                    }
+                    let ret = unsafe { wit_import0(_rt::as_i32(worker)) };
+                    _rt::bool_lift(ret as u8)
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Seconds since the user last moved the pointer, touched, scrolled or typed in the
+            /// window (or since the host started). Crossing `--idle-timeout` arrives as
+            /// `idle-changed`.
+            pub fn seconds_since_input() -> f64 {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "seconds-since-input"]
+                        fn wit_import0() -> f64;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0() -> f64 {
+                        unreachable!()
+                    }
+                    let ret = unsafe { wit_import0() };
+                    ret
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Record a named timestamp for later `perf-measure` calls (like `performance.mark`).
+            pub fn perf_mark(name: &str) -> () {
+                unsafe {
+                    let vec0 = name;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "perf-mark"]
+                        fn wit_import1(_: *mut u8, _: usize);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import1(_: *mut u8, _: usize) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import1(ptr0.cast_mut(), len0) };
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Record the time elapsed since `start-mark` as a named region in the host's trace
+            /// output and stats overlay (F3).
+            pub fn perf_measure(name: &str, start_mark: &str) -> () {
+                unsafe {
+                    let vec0 = name;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let vec1 = start_mark;
+                    let ptr1 = vec1.as_ptr().cast::<u8>();
+                    let len1 = vec1.len();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/host@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "perf-measure"]
+                        fn wit_import2(_: *mut u8, _: usize, _: *mut u8, _: usize);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                        _: usize,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import2(ptr0.cast_mut(), len0, ptr1.cast_mut(), len1) };
+                }
+            }
+        }
+        /// Structured storage: SQL against a SQLite database that belongs to the component alone,
+        /// kept beside its `storage-set` data.
+        #[allow(dead_code, async_fn_in_trait, unused_imports, clippy::all)]
+        pub mod database {
+            #[used]
+            #[doc(hidden)]
+            static __FORCE_SECTION_REF: fn() = super::super::super::__link_custom_section_describing_imports;
+            use super::super::super::_rt;
+            /// A statement parameter or result column.
+            #[derive(Clone)]
+            pub enum SqlValue {
+                Null,
+                Integer(i64),
+                Real(f64),
+                Text(_rt::String),
+                Blob(_rt::Vec<u8>),
+            }
+            impl ::core::fmt::Debug for SqlValue {
+                fn fmt(
+                    &self,
+                    f: &mut ::core::fmt::Formatter<'_>,
+                ) -> ::core::fmt::Result {
+                    match self {
+                        SqlValue::Null => f.debug_tuple("SqlValue::Null").finish(),
+                        SqlValue::Integer(e) => {
+                            f.debug_tuple("SqlValue::Integer").field(e).finish()
+                        }
+                        SqlValue::Real(e) => {
+                            f.debug_tuple("SqlValue::Real").field(e).finish()
+                        }
+                        SqlValue::Text(e) => {
+                            f.debug_tuple("SqlValue::Text").field(e).finish()
+                        }
+                        SqlValue::Blob(e) => {
+                            f.debug_tuple("SqlValue::Blob").field(e).finish()
+                        }
+                    }
+                }
+            }
+            pub type StatementId = u32;
+            #[allow(unused_unsafe, clippy::all)]
+            /// Compile one SQL statement against the component's database, creating the database
+            /// on first use. Errors on invalid SQL, when the host keeps no data for components,
+            /// or with 256 statements already prepared. ATTACH and pragmas other than
+            /// `foreign_keys`, `recursive_triggers` and `user_version` cannot be set.
+            pub fn prepare(sql: &str) -> Result<StatementId, _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let vec0 = sql;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/database@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "prepare"]
+                        fn wit_import2(_: *mut u8, _: usize, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import2(_: *mut u8, _: usize, _: *mut u8) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import2(ptr0.cast_mut(), len0, ptr1) };
+                    let l3 = i32::from(*ptr1.add(0).cast::<u8>());
+                    let result8 = match l3 {
+                        0 => {
+                            let e = {
+                                let l4 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<i32>();
+                                l4 as u32
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l5 = *ptr1
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l6 = *ptr1
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len7 = l6;
+                                let bytes7 = _rt::Vec::from_raw_parts(
+                                    l5.cast(),
+                                    len7,
+                                    len7,
+                                );
+                                _rt::string_lift(bytes7)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    result8
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Run a statement with `params` bound to `?1`, `?2`, ... for its side effects;
+            /// returns the number of rows changed. Writes that would take the database past the
+            /// host's quota (64 MiB unless `--max-database-bytes` says otherwise) fail, as do
+            /// statements still running at the call timeout and strings or blobs over 16 MiB.
+            pub fn execute(
+                statement: StatementId,
+                params: &[SqlValue],
+            ) -> Result<u64, _rt::String> {
+                unsafe {
+                    #[repr(align(8))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 8 + 2 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 8
+                            + 2 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let vec2 = params;
+                    let len2 = vec2.len();
+                    let layout2 = _rt::alloc::Layout::from_size_align_unchecked(
+                        vec2.len() * (8 + 2 * ::core::mem::size_of::<*const u8>()),
+                        8,
+                    );
+                    let result2 = if layout2.size() != 0 {
+                        let ptr = _rt::alloc::alloc(layout2).cast::<u8>();
+                        if ptr.is_null() {
+                            _rt::alloc::handle_alloc_error(layout2);
+                        }
+                        ptr
+                    } else {
+                        ::core::ptr::null_mut()
+                    };
+                    for (i, e) in vec2.into_iter().enumerate() {
+                        let base = result2
+                            .add(i * (8 + 2 * ::core::mem::size_of::<*const u8>()));
+                        {
+                            match e {
+                                SqlValue::Null => {
+                                    *base.add(0).cast::<u8>() = (0i32) as u8;
+                                }
+                                SqlValue::Integer(e) => {
+                                    *base.add(0).cast::<u8>() = (1i32) as u8;
+                                    *base.add(8).cast::<i64>() = _rt::as_i64(e);
+                                }
+                                SqlValue::Real(e) => {
+                                    *base.add(0).cast::<u8>() = (2i32) as u8;
+                                    *base.add(8).cast::<f64>() = _rt::as_f64(e);
+                                }
+                                SqlValue::Text(e) => {
+                                    *base.add(0).cast::<u8>() = (3i32) as u8;
+                                    let vec0 = e;
+                                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                                    let len0 = vec0.len();
+                                    *base
+                                        .add(8 + 1 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<usize>() = len0;
+                                    *base.add(8).cast::<*mut u8>() = ptr0.cast_mut();
+                                }
+                                SqlValue::Blob(e) => {
+                                    *base.add(0).cast::<u8>() = (4i32) as u8;
+                                    let vec1 = e;
+                                    let ptr1 = vec1.as_ptr().cast::<u8>();
+                                    let len1 = vec1.len();
+                                    *base
+                                        .add(8 + 1 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<usize>() = len1;
+                                    *base.add(8).cast::<*mut u8>() = ptr1.cast_mut();
+                                }
+                            }
+                        }
+                    }
+                    let ptr3 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/database@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "execute"]
+                        fn wit_import4(_: i32, _: *mut u8, _: usize, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import4(
+                        _: i32,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import4(_rt::as_i32(statement), result2, len2, ptr3) };
+                    let l5 = i32::from(*ptr3.add(0).cast::<u8>());
+                    let result10 = match l5 {
+                        0 => {
+                            let e = {
+                                let l6 = *ptr3.add(8).cast::<i64>();
+                                l6 as u64
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l7 = *ptr3.add(8).cast::<*mut u8>();
+                                let l8 = *ptr3
+                                    .add(8 + 1 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len9 = l8;
+                                let bytes9 = _rt::Vec::from_raw_parts(
+                                    l7.cast(),
+                                    len9,
+                                    len9,
+                                );
+                                _rt::string_lift(bytes9)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    if layout2.size() != 0 {
+                        _rt::alloc::dealloc(result2.cast(), layout2);
+                    }
+                    result10
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Run a statement and return the rows it produces; errors past 100000 rows or 64 MiB,
+            /// and like `execute` for slow statements and oversized values.
+            pub fn query(
+                statement: StatementId,
+                params: &[SqlValue],
+            ) -> Result<_rt::Vec<_rt::Vec<SqlValue>>, _rt::String> {
+                unsafe {
+                    #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                    #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                    struct RetArea(
+                        [::core::mem::MaybeUninit<
+                            u8,
+                        >; 3 * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let mut ret_area = RetArea(
+                        [::core::mem::MaybeUninit::uninit(); 3
+                            * ::core::mem::size_of::<*const u8>()],
+                    );
+                    let vec2 = params;
+                    let len2 = vec2.len();
+                    let layout2 = _rt::alloc::Layout::from_size_align_unchecked(
+                        vec2.len() * (8 + 2 * ::core::mem::size_of::<*const u8>()),
+                        8,
+                    );
+                    let result2 = if layout2.size() != 0 {
+                        let ptr = _rt::alloc::alloc(layout2).cast::<u8>();
+                        if ptr.is_null() {
+                            _rt::alloc::handle_alloc_error(layout2);
+                        }
+                        ptr
+                    } else {
+                        ::core::ptr::null_mut()
+                    };
+                    for (i, e) in vec2.into_iter().enumerate() {
+                        let base = result2
+                            .add(i * (8 + 2 * ::core::mem::size_of::<*const u8>()));
+                        {
+                            match e {
+                                SqlValue::Null => {
+                                    *base.add(0).cast::<u8>() = (0i32) as u8;
+                                }
+                                SqlValue::Integer(e) => {
+                                    *base.add(0).cast::<u8>() = (1i32) as u8;
+                                    *base.add(8).cast::<i64>() = _rt::as_i64(e);
+                                }
+                                SqlValue::Real(e) => {
+                                    *base.add(0).cast::<u8>() = (2i32) as u8;
+                                    *base.add(8).cast::<f64>() = _rt::as_f64(e);
+                                }
+                                SqlValue::Text(e) => {
+                                    *base.add(0).cast::<u8>() = (3i32) as u8;
+                                    let vec0 = e;
+                                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                                    let len0 = vec0.len();
+                                    *base
+                                        .add(8 + 1 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<usize>() = len0;
+                                    *base.add(8).cast::<*mut u8>() = ptr0.cast_mut();
+                                }
+                                SqlValue::Blob(e) => {
+                                    *base.add(0).cast::<u8>() = (4i32) as u8;
+                                    let vec1 = e;
+                                    let ptr1 = vec1.as_ptr().cast::<u8>();
+                                    let len1 = vec1.len();
+                                    *base
+                                        .add(8 + 1 * ::core::mem::size_of::<*const u8>())
+                                        .cast::<usize>() = len1;
+                                    *base.add(8).cast::<*mut u8>() = ptr1.cast_mut();
+                                }
+                            }
+                        }
+                    }
+                    let ptr3 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/database@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "query"]
+                        fn wit_import4(_: i32, _: *mut u8, _: usize, _: *mut u8);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import4(
+                        _: i32,
+                        _: *mut u8,
+                        _: usize,
+                        _: *mut u8,
+                    ) {
+                        unreachable!()
+                    }
+                    unsafe { wit_import4(_rt::as_i32(statement), result2, len2, ptr3) };
+                    let l5 = i32::from(*ptr3.add(0).cast::<u8>());
+                    let result25 = match l5 {
+                        0 => {
+                            let e = {
+                                let l6 = *ptr3
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l7 = *ptr3
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let base21 = l6;
+                                let len21 = l7;
+                                let mut result21 = _rt::Vec::with_capacity(len21);
+                                for i in 0..len21 {
+                                    let base = base21
+                                        .add(i * (2 * ::core::mem::size_of::<*const u8>()));
+                                    let e21 = {
+                                        let l8 = *base.add(0).cast::<*mut u8>();
+                                        let l9 = *base
+                                            .add(::core::mem::size_of::<*const u8>())
+                                            .cast::<usize>();
+                                        let base20 = l8;
+                                        let len20 = l9;
+                                        let mut result20 = _rt::Vec::with_capacity(len20);
+                                        for i in 0..len20 {
+                                            let base = base20
+                                                .add(i * (8 + 2 * ::core::mem::size_of::<*const u8>()));
+                                            let e20 = {
+                                                let l10 = i32::from(*base.add(0).cast::<u8>());
+                                                let v19 = match l10 {
+                                                    0 => SqlValue::Null,
+                                                    1 => {
+                                                        let e19 = {
+                                                            let l11 = *base.add(8).cast::<i64>();
+                                                            l11
+                                                        };
+                                                        SqlValue::Integer(e19)
+                                                    }
+                                                    2 => {
+                                                        let e19 = {
+                                                            let l12 = *base.add(8).cast::<f64>();
+                                                            l12
+                                                        };
+                                                        SqlValue::Real(e19)
+                                                    }
+                                                    3 => {
+                                                        let e19 = {
+                                                            let l13 = *base.add(8).cast::<*mut u8>();
+                                                            let l14 = *base
+                                                                .add(8 + 1 * ::core::mem::size_of::<*const u8>())
+                                                                .cast::<usize>();
+                                                            let len15 = l14;
+                                                            let bytes15 = _rt::Vec::from_raw_parts(
+                                                                l13.cast(),
+                                                                len15,
+                                                                len15,
+                                                            );
+                                                            _rt::string_lift(bytes15)
+                                                        };
+                                                        SqlValue::Text(e19)
+                                                    }
+                                                    n => {
+                                                        debug_assert_eq!(n, 4, "invalid enum discriminant");
+                                                        let e19 = {
+                                                            let l16 = *base.add(8).cast::<*mut u8>();
+                                                            let l17 = *base
+                                                                .add(8 + 1 * ::core::mem::size_of::<*const u8>())
+                                                                .cast::<usize>();
+                                                            let len18 = l17;
+                                                            _rt::Vec::from_raw_parts(l16.cast(), len18, len18)
+                                                        };
+                                                        SqlValue::Blob(e19)
+                                                    }
+                                                };
+                                                v19
+                                            };
+                                            result20.push(e20);
+                                        }
+                                        _rt::cabi_dealloc(
+                                            base20,
+                                            len20 * (8 + 2 * ::core::mem::size_of::<*const u8>()),
+                                            8,
+                                        );
+                                        result20
+                                    };
+                                    result21.push(e21);
+                                }
+                                _rt::cabi_dealloc(
+                                    base21,
+                                    len21 * (2 * ::core::mem::size_of::<*const u8>()),
+                                    ::core::mem::size_of::<*const u8>(),
+                                );
+                                result21
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l22 = *ptr3
+                                    .add(::core::mem::size_of::<*const u8>())
+                                    .cast::<*mut u8>();
+                                let l23 = *ptr3
+                                    .add(2 * ::core::mem::size_of::<*const u8>())
+                                    .cast::<usize>();
+                                let len24 = l23;
+                                let bytes24 = _rt::Vec::from_raw_parts(
+                                    l22.cast(),
+                                    len24,
+                                    len24,
+                                );
+                                _rt::string_lift(bytes24)
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    };
+                    if layout2.size() != 0 {
+                        _rt::alloc::dealloc(result2.cast(), layout2);
+                    }
+                    result25
+                }
+            }
+            #[allow(unused_unsafe, clippy::all)]
+            /// Release a prepared statement; false for an unknown id.
+            pub fn finalize(statement: StatementId) -> bool {
+                unsafe {
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "vello:canvas/database@0.1.0")]
+                    unsafe extern "C" {
+                        #[link_name = "finalize"]
+                        fn wit_import0(_: i32) -> i32;
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unsafe extern "C" fn wit_import0(_: i32) -> i32 {
+                        unreachable!()
+                    }
+                    let ret = unsafe { wit_import0(_rt::as_i32(statement)) };
+                    _rt::bool_lift(ret as u8)
+                }
+            }
+        }
+    }
+}
+#[rustfmt::skip]
+#[allow(dead_code, clippy::all)]
+pub mod exports {
+    pub mod vello {
+        pub mod canvas {
+            /// Events/lifecycle callbacks the guest exports.
+            #[allow(dead_code, async_fn_in_trait, unused_imports, clippy::all)]
+            pub mod app {
+                #[used]
+                #[doc(hidden)]
+                static __FORCE_SECTION_REF: fn() = super::super::super::super::__link_custom_section_describing_imports;
+                use super::super::super::super::_rt;
+                pub type Vec2 = super::super::super::super::vello::canvas::math::Vec2;
+                pub type DisplayInfo = super::super::super::super::vello::canvas::host::DisplayInfo;
+                pub type ImageId = super::super::super::super::vello::canvas::host::ImageId;
+                pub type PictureId = super::super::super::super::vello::canvas::host::PictureId;
+                pub type PowerStatus = super::super::super::super::vello::canvas::host::PowerStatus;
+                pub type ShaderId = super::super::super::super::vello::canvas::host::ShaderId;
+                pub type TaskId = super::super::super::super::vello::canvas::host::TaskId;
+                pub type WindowPosition = super::super::super::super::vello::canvas::host::WindowPosition;
+                pub type WorkerId = super::super::super::super::vello::canvas::host::WorkerId;
+                #[repr(C)]
+                #[derive(Clone, Copy)]
+                pub struct LogicalSize {
+                    pub width: f32,
+                    pub height: f32,
+                    pub scale_factor: f32,
+                }
+                impl ::core::fmt::Debug for LogicalSize {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        f.debug_struct("LogicalSize")
+                            .field("width", &self.width)
+                            .field("height", &self.height)
+                            .field("scale-factor", &self.scale_factor)
+                            .finish()
+                    }
+                }
+                /// How hard `trim-memory` asks: `moderate` to free what is cheap to recreate,
+                /// `critical` to free everything the next frame does not need.
+                #[repr(u8)]
+                #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+                pub enum MemoryPressure {
+                    Moderate,
+                    Critical,
+                }
+                impl ::core::fmt::Debug for MemoryPressure {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        match self {
+                            MemoryPressure::Moderate => {
+                                f.debug_tuple("MemoryPressure::Moderate").finish()
+                            }
+                            MemoryPressure::Critical => {
+                                f.debug_tuple("MemoryPressure::Critical").finish()
+                            }
+                        }
+                    }
+                }
+                impl MemoryPressure {
+                    #[doc(hidden)]
+                    pub unsafe fn _lift(val: u8) -> MemoryPressure {
+                        if !cfg!(debug_assertions) {
+                            return ::core::mem::transmute(val);
+                        }
+                        match val {
+                            0 => MemoryPressure::Moderate,
+                            1 => MemoryPressure::Critical,
+                            _ => panic!("invalid enum discriminant"),
+                        }
+                    }
+                }
+                #[repr(C)]
+                #[derive(Clone, Copy)]
+                pub struct PointerButton {
+                    pub primary: bool,
+                    pub secondary: bool,
+                }
+                impl ::core::fmt::Debug for PointerButton {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        f.debug_struct("PointerButton")
+                            .field("primary", &self.primary)
+                            .field("secondary", &self.secondary)
+                            .finish()
+                    }
+                }
+                wit_bindgen_rt::bitflags::bitflags! {
+                    #[doc =
+                    " Which physical modifier keys are held, for guests that treat left and right"]
+                    #[doc = " differently."] #[derive(PartialEq, Eq, PartialOrd, Ord,
+                    Hash, Debug, Clone, Copy)] pub struct ModifierKeys : u8 { const
+                    LEFT_SHIFT = 1 << 0; const RIGHT_SHIFT = 1 << 1; const LEFT_CTRL = 1
+                    << 2; const RIGHT_CTRL = 1 << 3; const LEFT_ALT = 1 << 4; const
+                    RIGHT_ALT = 1 << 5; const LEFT_META = 1 << 6; const RIGHT_META = 1 <<
+                    7; }
+                }
+                wit_bindgen_rt::bitflags::bitflags! {
+                    #[doc =
+                    " Lock keys that are toggled on. The host tracks these from key presses, so a lock"]
+                    #[doc =
+                    " enabled before the window gained focus reads as off until it is toggled. NumLock"]
+                    #[doc =
+                    " reads as off until `num-lock-known` says a keypad key has shown its state."]
+                    #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+                    pub struct LockKeys : u8 { const CAPS_LOCK = 1 << 0; const NUM_LOCK =
+                    1 << 1; const SCROLL_LOCK = 1 << 2; }
+                }
+                #[repr(C)]
+                #[derive(Clone, Copy)]
+                pub struct Modifiers {
+                    pub shift: bool,
+                    pub ctrl: bool,
+                    pub alt: bool,
+                    pub meta: bool,
+                    pub keys: ModifierKeys,
+                    pub locks: LockKeys,
+                    /// Whether `num-lock` in `locks` reflects the real state rather than a guess.
+                    pub num_lock_known: bool,
+                }
+                impl ::core::fmt::Debug for Modifiers {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        f.debug_struct("Modifiers")
+                            .field("shift", &self.shift)
+                            .field("ctrl", &self.ctrl)
+                            .field("alt", &self.alt)
+                            .field("meta", &self.meta)
+                            .field("keys", &self.keys)
+                            .field("locks", &self.locks)
+                            .field("num-lock-known", &self.num_lock_known)
+                            .finish()
+                    }
+                }
+                /// Where the key sits on the keyboard, like `KeyboardEvent.location`.
+                #[repr(u8)]
+                #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+                pub enum KeyLocation {
+                    Standard,
+                    Left,
+                    Right,
+                    Numpad,
+                }
+                impl ::core::fmt::Debug for KeyLocation {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        match self {
+                            KeyLocation::Standard => {
+                                f.debug_tuple("KeyLocation::Standard").finish()
+                            }
+                            KeyLocation::Left => {
+                                f.debug_tuple("KeyLocation::Left").finish()
+                            }
+                            KeyLocation::Right => {
+                                f.debug_tuple("KeyLocation::Right").finish()
+                            }
+                            KeyLocation::Numpad => {
+                                f.debug_tuple("KeyLocation::Numpad").finish()
+                            }
+                        }
+                    }
+                }
+                impl KeyLocation {
+                    #[doc(hidden)]
+                    pub unsafe fn _lift(val: u8) -> KeyLocation {
+                        if !cfg!(debug_assertions) {
+                            return ::core::mem::transmute(val);
+                        }
+                        match val {
+                            0 => KeyLocation::Standard,
+                            1 => KeyLocation::Left,
+                            2 => KeyLocation::Right,
+                            3 => KeyLocation::Numpad,
+                            _ => panic!("invalid enum discriminant"),
+                        }
+                    }
+                }
+                #[repr(u8)]
+                #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+                pub enum PointerKind {
+                    Mouse,
+                    Touch,
+                    Pen,
+                }
+                impl ::core::fmt::Debug for PointerKind {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        match self {
+                            PointerKind::Mouse => {
+                                f.debug_tuple("PointerKind::Mouse").finish()
+                            }
+                            PointerKind::Touch => {
+                                f.debug_tuple("PointerKind::Touch").finish()
+                            }
+                            PointerKind::Pen => {
+                                f.debug_tuple("PointerKind::Pen").finish()
+                            }
+                        }
+                    }
+                }
+                impl PointerKind {
+                    #[doc(hidden)]
+                    pub unsafe fn _lift(val: u8) -> PointerKind {
+                        if !cfg!(debug_assertions) {
+                            return ::core::mem::transmute(val);
+                        }
+                        match val {
+                            0 => PointerKind::Mouse,
+                            1 => PointerKind::Touch,
+                            2 => PointerKind::Pen,
+                            _ => panic!("invalid enum discriminant"),
+                        }
+                    }
+                }
+                /// One raw pointer position. `time-ms` is milliseconds since the host started, on a
+                /// monotonic clock shared by all samples.
+                #[repr(C)]
+                #[derive(Clone, Copy)]
+                pub struct PointerSample {
+                    pub position: Vec2,
+                    pub time_ms: f64,
+                }
+                impl ::core::fmt::Debug for PointerSample {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        f.debug_struct("PointerSample")
+                            .field("position", &self.position)
+                            .field("time-ms", &self.time_ms)
+                            .finish()
+                    }
+                }
+                #[derive(Clone)]
+                pub struct PointerEvent {
+                    pub kind: PointerKind,
+                    pub position: Vec2,
+                    pub buttons: PointerButton,
+                    pub modifiers: Modifiers,
+                    /// Stable for the lifetime of a device (each mouse) or touch contact; the first
+                    /// pointer the host sees is 0, and ids are never reused.
+                    pub pointer_id: u64,
+                    /// How many raw moves this event stands for. Always 1 unless the host coalesces
+                    /// pointer moves, in which case intermediate positions were skipped.
+                    pub coalesced_count: u32,
+                    /// The raw samples folded into a coalesced move, oldest first and ending with
+                    /// `position` (like the web's `getCoalescedEvents`). At most the latest 128 are kept,
+                    /// so it can be shorter than `coalesced-count`. Empty when nothing was coalesced.
+                    pub history: _rt::Vec<PointerSample>,
+                }
+                impl ::core::fmt::Debug for PointerEvent {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        f.debug_struct("PointerEvent")
+                            .field("kind", &self.kind)
+                            .field("position", &self.position)
+                            .field("buttons", &self.buttons)
+                            .field("modifiers", &self.modifiers)
+                            .field("pointer-id", &self.pointer_id)
+                            .field("coalesced-count", &self.coalesced_count)
+                            .field("history", &self.history)
+                            .finish()
+                    }
+                }
+                /// `key` and `code` use the W3C UI Events `KeyboardEvent` values, e.g. key `"a"`,
+                /// `" "` or `"ArrowLeft"` and code `"KeyA"`, `"Space"` or `"ArrowLeft"`.
+                #[derive(Clone)]
+                pub struct KeyEvent {
+                    pub key: _rt::String,
+                    pub code: _rt::String,
+                    pub modifiers: Modifiers,
+                    pub is_repeat: bool,
+                    pub location: KeyLocation,
+                }
+                impl ::core::fmt::Debug for KeyEvent {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        f.debug_struct("KeyEvent")
+                            .field("key", &self.key)
+                            .field("code", &self.code)
+                            .field("modifiers", &self.modifiers)
+                            .field("is-repeat", &self.is_repeat)
+                            .field("location", &self.location)
+                            .finish()
+                    }
+                }
+                #[repr(u8)]
+                #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+                pub enum GesturePhase {
+                    Started,
+                    Changed,
+                    Ended,
+                    Cancelled,
+                }
+                impl ::core::fmt::Debug for GesturePhase {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        match self {
+                            GesturePhase::Started => {
+                                f.debug_tuple("GesturePhase::Started").finish()
+                            }
+                            GesturePhase::Changed => {
+                                f.debug_tuple("GesturePhase::Changed").finish()
+                            }
+                            GesturePhase::Ended => {
+                                f.debug_tuple("GesturePhase::Ended").finish()
+                            }
+                            GesturePhase::Cancelled => {
+                                f.debug_tuple("GesturePhase::Cancelled").finish()
+                            }
+                        }
+                    }
+                }
+                impl GesturePhase {
+                    #[doc(hidden)]
+                    pub unsafe fn _lift(val: u8) -> GesturePhase {
+                        if !cfg!(debug_assertions) {
+                            return ::core::mem::transmute(val);
+                        }
+                        match val {
+                            0 => GesturePhase::Started,
+                            1 => GesturePhase::Changed,
+                            2 => GesturePhase::Ended,
+                            3 => GesturePhase::Cancelled,
+                            _ => panic!("invalid enum discriminant"),
+                        }
+                    }
+                }
+                /// A touchpad gesture in progress. `position` is the last known cursor position;
+                /// `delta` is the change since the previous event of the same gesture.
+                #[repr(C)]
+                #[derive(Clone, Copy)]
+                pub struct GestureEvent {
+                    pub phase: GesturePhase,
+                    pub position: Vec2,
+                    pub modifiers: Modifiers,
+                    pub delta: f32,
+                }
+                impl ::core::fmt::Debug for GestureEvent {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        f.debug_struct("GestureEvent")
+                            .field("phase", &self.phase)
+                            .field("position", &self.position)
+                            .field("modifiers", &self.modifiers)
+                            .field("delta", &self.delta)
+                            .finish()
+                    }
+                }
+                #[repr(u8)]
+                #[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+                pub enum CloseResponse {
+                    Allow,
+                    Deny,
+                }
+                impl ::core::fmt::Debug for CloseResponse {
+                    fn fmt(
+                        &self,
+                        f: &mut ::core::fmt::Formatter<'_>,
+                    ) -> ::core::fmt::Result {
+                        match self {
+                            CloseResponse::Allow => {
+                                f.debug_tuple("CloseResponse::Allow").finish()
+                            }
+                            CloseResponse::Deny => {
+                                f.debug_tuple("CloseResponse::Deny").finish()
+                            }
+                        }
+                    }
+                }
+                impl CloseResponse {
+                    #[doc(hidden)]
+                    pub unsafe fn _lift(val: u8) -> CloseResponse {
+                        if !cfg!(debug_assertions) {
+                            return ::core::mem::transmute(val);
+                        }
+                        match val {
+                            0 => CloseResponse::Allow,
+                            1 => CloseResponse::Deny,
+                            _ => panic!("invalid enum discriminant"),
+                        }
+                    }
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_init_cabi<T: Guest>(
+                    arg0: f32,
+                    arg1: f32,
+                    arg2: f32,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    T::init(LogicalSize {
+                        width: arg0,
+                        height: arg1,
+                        scale_factor: arg2,
+                    });
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_resize_cabi<T: Guest>(
+                    arg0: f32,
+                    arg1: f32,
+                    arg2: f32,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    T::resize(LogicalSize {
+                        width: arg0,
+                        height: arg1,
+                        scale_factor: arg2,
+                    });
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_minimized_changed_cabi<T: Guest>(arg0: i32) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    T::minimized_changed(_rt::bool_lift(arg0 as u8));
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_pointer_down_cabi<T: Guest>(
+                    arg0: i32,
+                    arg1: f32,
+                    arg2: f32,
+                    arg3: i32,
+                    arg4: i32,
+                    arg5: i32,
+                    arg6: i32,
+                    arg7: i32,
+                    arg8: i32,
+                    arg9: i32,
+                    arg10: i32,
+                    arg11: i32,
+                    arg12: i64,
+                    arg13: i32,
+                    arg14: *mut u8,
+                    arg15: usize,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let len0 = arg15;
+                    T::pointer_down(PointerEvent {
+                        kind: PointerKind::_lift(arg0 as u8),
+                        position: super::super::super::super::vello::canvas::math::Vec2 {
+                            x: arg1,
+                            y: arg2,
+                        },
+                        buttons: PointerButton {
+                            primary: _rt::bool_lift(arg3 as u8),
+                            secondary: _rt::bool_lift(arg4 as u8),
+                        },
+                        modifiers: Modifiers {
+                            shift: _rt::bool_lift(arg5 as u8),
+                            ctrl: _rt::bool_lift(arg6 as u8),
+                            alt: _rt::bool_lift(arg7 as u8),
+                            meta: _rt::bool_lift(arg8 as u8),
+                            keys: ModifierKeys::empty()
+                                | ModifierKeys::from_bits_retain(((arg9 as u8) << 0) as _),
+                            locks: LockKeys::empty()
+                                | LockKeys::from_bits_retain(((arg10 as u8) << 0) as _),
+                            num_lock_known: _rt::bool_lift(arg11 as u8),
+                        },
+                        pointer_id: arg12 as u64,
+                        coalesced_count: arg13 as u32,
+                        history: _rt::Vec::from_raw_parts(arg14.cast(), len0, len0),
+                    });
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_pointer_up_cabi<T: Guest>(
+                    arg0: i32,
+                    arg1: f32,
+                    arg2: f32,
+                    arg3: i32,
+                    arg4: i32,
+                    arg5: i32,
+                    arg6: i32,
+                    arg7: i32,
+                    arg8: i32,
+                    arg9: i32,
+                    arg10: i32,
+                    arg11: i32,
+                    arg12: i64,
+                    arg13: i32,
+                    arg14: *mut u8,
+                    arg15: usize,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let len0 = arg15;
+                    T::pointer_up(PointerEvent {
+                        kind: PointerKind::_lift(arg0 as u8),
+                        position: super::super::super::super::vello::canvas::math::Vec2 {
+                            x: arg1,
+                            y: arg2,
+                        },
+                        buttons: PointerButton {
+                            primary: _rt::bool_lift(arg3 as u8),
+                            secondary: _rt::bool_lift(arg4 as u8),
+                        },
+                        modifiers: Modifiers {
+                            shift: _rt::bool_lift(arg5 as u8),
+                            ctrl: _rt::bool_lift(arg6 as u8),
+                            alt: _rt::bool_lift(arg7 as u8),
+                            meta: _rt::bool_lift(arg8 as u8),
+                            keys: ModifierKeys::empty()
+                                | ModifierKeys::from_bits_retain(((arg9 as u8) << 0) as _),
+                            locks: LockKeys::empty()
+                                | LockKeys::from_bits_retain(((arg10 as u8) << 0) as _),
+                            num_lock_known: _rt::bool_lift(arg11 as u8),
+                        },
+                        pointer_id: arg12 as u64,
+                        coalesced_count: arg13 as u32,
+                        history: _rt::Vec::from_raw_parts(arg14.cast(), len0, len0),
+                    });
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_pointer_move_cabi<T: Guest>(
+                    arg0: i32,
+                    arg1: f32,
+                    arg2: f32,
+                    arg3: i32,
+                    arg4: i32,
+                    arg5: i32,
+                    arg6: i32,
+                    arg7: i32,
+                    arg8: i32,
+                    arg9: i32,
+                    arg10: i32,
+                    arg11: i32,
+                    arg12: i64,
+                    arg13: i32,
+                    arg14: *mut u8,
+                    arg15: usize,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let len0 = arg15;
+                    T::pointer_move(PointerEvent {
+                        kind: PointerKind::_lift(arg0 as u8),
+                        position: super::super::super::super::vello::canvas::math::Vec2 {
+                            x: arg1,
+                            y: arg2,
+                        },
+                        buttons: PointerButton {
+                            primary: _rt::bool_lift(arg3 as u8),
+                            secondary: _rt::bool_lift(arg4 as u8),
+                        },
+                        modifiers: Modifiers {
+                            shift: _rt::bool_lift(arg5 as u8),
+                            ctrl: _rt::bool_lift(arg6 as u8),
+                            alt: _rt::bool_lift(arg7 as u8),
+                            meta: _rt::bool_lift(arg8 as u8),
+                            keys: ModifierKeys::empty()
+                                | ModifierKeys::from_bits_retain(((arg9 as u8) << 0) as _),
+                            locks: LockKeys::empty()
+                                | LockKeys::from_bits_retain(((arg10 as u8) << 0) as _),
+                            num_lock_known: _rt::bool_lift(arg11 as u8),
+                        },
+                        pointer_id: arg12 as u64,
+                        coalesced_count: arg13 as u32,
+                        history: _rt::Vec::from_raw_parts(arg14.cast(), len0, len0),
+                    });
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_key_down_cabi<T: Guest>(
+                    arg0: *mut u8,
+                    arg1: usize,
+                    arg2: *mut u8,
+                    arg3: usize,
+                    arg4: i32,
+                    arg5: i32,
+                    arg6: i32,
+                    arg7: i32,
+                    arg8: i32,
+                    arg9: i32,
+                    arg10: i32,
+                    arg11: i32,
+                    arg12: i32,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let len0 = arg1;
+                    let bytes0 = _rt::Vec::from_raw_parts(arg0.cast(), len0, len0);
+                    let len1 = arg3;
+                    let bytes1 = _rt::Vec::from_raw_parts(arg2.cast(), len1, len1);
+                    T::key_down(KeyEvent {
+                        key: _rt::string_lift(bytes0),
+                        code: _rt::string_lift(bytes1),
+                        modifiers: Modifiers {
+                            shift: _rt::bool_lift(arg4 as u8),
+                            ctrl: _rt::bool_lift(arg5 as u8),
+                            alt: _rt::bool_lift(arg6 as u8),
+                            meta: _rt::bool_lift(arg7 as u8),
+                            keys: ModifierKeys::empty()
+                                | ModifierKeys::from_bits_retain(((arg8 as u8) << 0) as _),
+                            locks: LockKeys::empty()
+                                | LockKeys::from_bits_retain(((arg9 as u8) << 0) as _),
+                            num_lock_known: _rt::bool_lift(arg10 as u8),
+                        },
+                        is_repeat: _rt::bool_lift(arg11 as u8),
+                        location: KeyLocation::_lift(arg12 as u8),
+                    });
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_key_up_cabi<T: Guest>(
+                    arg0: *mut u8,
+                    arg1: usize,
+                    arg2: *mut u8,
+                    arg3: usize,
+                    arg4: i32,
+                    arg5: i32,
+                    arg6: i32,
+                    arg7: i32,
+                    arg8: i32,
+                    arg9: i32,
+                    arg10: i32,
+                    arg11: i32,
+                    arg12: i32,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let len0 = arg1;
+                    let bytes0 = _rt::Vec::from_raw_parts(arg0.cast(), len0, len0);
+                    let len1 = arg3;
+                    let bytes1 = _rt::Vec::from_raw_parts(arg2.cast(), len1, len1);
+                    T::key_up(KeyEvent {
+                        key: _rt::string_lift(bytes0),
+                        code: _rt::string_lift(bytes1),
+                        modifiers: Modifiers {
+                            shift: _rt::bool_lift(arg4 as u8),
+                            ctrl: _rt::bool_lift(arg5 as u8),
+                            alt: _rt::bool_lift(arg6 as u8),
+                            meta: _rt::bool_lift(arg7 as u8),
+                            keys: ModifierKeys::empty()
+                                | ModifierKeys::from_bits_retain(((arg8 as u8) << 0) as _),
+                            locks: LockKeys::empty()
+                                | LockKeys::from_bits_retain(((arg9 as u8) << 0) as _),
+                            num_lock_known: _rt::bool_lift(arg10 as u8),
+                        },
+                        is_repeat: _rt::bool_lift(arg11 as u8),
+                        location: KeyLocation::_lift(arg12 as u8),
+                    });
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_text_input_cabi<T: Guest>(
+                    arg0: *mut u8,
+                    arg1: usize,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let len0 = arg1;
+                    let bytes0 = _rt::Vec::from_raw_parts(arg0.cast(), len0, len0);
+                    T::text_input(_rt::string_lift(bytes0));
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_pinch_gesture_cabi<T: Guest>(
+                    arg0: i32,
+                    arg1: f32,
+                    arg2: f32,
+                    arg3: i32,
+                    arg4: i32,
+                    arg5: i32,
+                    arg6: i32,
+                    arg7: i32,
+                    arg8: i32,
+                    arg9: i32,
+                    arg10: f32,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    T::pinch_gesture(GestureEvent {
+                        phase: GesturePhase::_lift(arg0 as u8),
+                        position: super::super::super::super::vello::canvas::math::Vec2 {
+                            x: arg1,
+                            y: arg2,
+                        },
+                        modifiers: Modifiers {
+                            shift: _rt::bool_lift(arg3 as u8),
+                            ctrl: _rt::bool_lift(arg4 as u8),
+                            alt: _rt::bool_lift(arg5 as u8),
+                            meta: _rt::bool_lift(arg6 as u8),
+                            keys: ModifierKeys::empty()
+                                | ModifierKeys::from_bits_retain(((arg7 as u8) << 0) as _),
+                            locks: LockKeys::empty()
+                                | LockKeys::from_bits_retain(((arg8 as u8) << 0) as _),
+                            num_lock_known: _rt::bool_lift(arg9 as u8),
+                        },
+                        delta: arg10,
+                    });
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_rotation_gesture_cabi<T: Guest>(
+                    arg0: i32,
+                    arg1: f32,
+                    arg2: f32,
+                    arg3: i32,
+                    arg4: i32,
+                    arg5: i32,
+                    arg6: i32,
+                    arg7: i32,
+                    arg8: i32,
+                    arg9: i32,
+                    arg10: f32,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    T::rotation_gesture(GestureEvent {
+                        phase: GesturePhase::_lift(arg0 as u8),
+                        position: super::super::super::super::vello::canvas::math::Vec2 {
+                            x: arg1,
+                            y: arg2,
+                        },
+                        modifiers: Modifiers {
+                            shift: _rt::bool_lift(arg3 as u8),
+                            ctrl: _rt::bool_lift(arg4 as u8),
+                            alt: _rt::bool_lift(arg5 as u8),
+                            meta: _rt::bool_lift(arg6 as u8),
+                            keys: ModifierKeys::empty()
+                                | ModifierKeys::from_bits_retain(((arg7 as u8) << 0) as _),
+                            locks: LockKeys::empty()
+                                | LockKeys::from_bits_retain(((arg8 as u8) << 0) as _),
+                            num_lock_known: _rt::bool_lift(arg9 as u8),
+                        },
+                        delta: arg10,
+                    });
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_double_tap_gesture_cabi<T: Guest>(
+                    arg0: f32,
+                    arg1: f32,
+                    arg2: i32,
+                    arg3: i32,
+                    arg4: i32,
+                    arg5: i32,
+                    arg6: i32,
+                    arg7: i32,
+                    arg8: i32,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    T::double_tap_gesture(
+                        super::super::super::super::vello::canvas::math::Vec2 {
+                            x: arg0,
+                            y: arg1,
+                        },
+                        Modifiers {
+                            shift: _rt::bool_lift(arg2 as u8),
+                            ctrl: _rt::bool_lift(arg3 as u8),
+                            alt: _rt::bool_lift(arg4 as u8),
+                            meta: _rt::bool_lift(arg5 as u8),
+                            keys: ModifierKeys::empty()
+                                | ModifierKeys::from_bits_retain(((arg6 as u8) << 0) as _),
+                            locks: LockKeys::empty()
+                                | LockKeys::from_bits_retain(((arg7 as u8) << 0) as _),
+                            num_lock_known: _rt::bool_lift(arg8 as u8),
+                        },
+                    );
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_frame_cabi<T: Guest>(arg0: f32) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    T::frame(arg0);
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_frame_missed_cabi<T: Guest>(arg0: i32) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    T::frame_missed(arg0 as u32);
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_power_state_changed_cabi<T: Guest>(
+                    arg0: i32,
+                    arg1: i32,
+                    arg2: i32,
+                    arg3: i32,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    T::power_state_changed(super::super::super::super::vello::canvas::host::PowerStatus {
+                        on_battery: _rt::bool_lift(arg0 as u8),
+                        battery_percent: match arg1 {
+                            0 => None,
+                            1 => {
+                                let e = arg2 as u8;
+                                Some(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        },
+                        power_saver: _rt::bool_lift(arg3 as u8),
+                    });
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_idle_changed_cabi<T: Guest>(arg0: i32) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    T::idle_changed(_rt::bool_lift(arg0 as u8));
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_trim_memory_cabi<T: Guest>(arg0: i32) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    T::trim_memory(MemoryPressure::_lift(arg0 as u8));
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_assets_evicted_cabi<T: Guest>(
+                    arg0: *mut u8,
+                    arg1: usize,
+                    arg2: *mut u8,
+                    arg3: usize,
+                    arg4: *mut u8,
+                    arg5: usize,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let len0 = arg1;
+                    let len1 = arg3;
+                    let len2 = arg5;
+                    T::assets_evicted(
+                        _rt::Vec::from_raw_parts(arg0.cast(), len0, len0),
+                        _rt::Vec::from_raw_parts(arg2.cast(), len1, len1),
+                        _rt::Vec::from_raw_parts(arg4.cast(), len2, len2),
+                    );
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_run_task_cabi<T: Guest>(
+                    arg0: *mut u8,
+                    arg1: usize,
+                    arg2: *mut u8,
+                    arg3: usize,
+                ) -> *mut u8 {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let len0 = arg1;
+                    let bytes0 = _rt::Vec::from_raw_parts(arg0.cast(), len0, len0);
+                    let len1 = arg3;
+                    let result2 = T::run_task(
+                        _rt::string_lift(bytes0),
+                        _rt::Vec::from_raw_parts(arg2.cast(), len1, len1),
+                    );
+                    let ptr3 = (&raw mut _RET_AREA.0).cast::<u8>();
+                    match result2 {
+                        Ok(e) => {
+                            *ptr3.add(0).cast::<u8>() = (0i32) as u8;
+                            let vec4 = (e).into_boxed_slice();
+                            let ptr4 = vec4.as_ptr().cast::<u8>();
+                            let len4 = vec4.len();
+                            ::core::mem::forget(vec4);
+                            *ptr3
+                                .add(2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<usize>() = len4;
+                            *ptr3
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<*mut u8>() = ptr4.cast_mut();
+                        }
+                        Err(e) => {
+                            *ptr3.add(0).cast::<u8>() = (1i32) as u8;
+                            let vec5 = (e.into_bytes()).into_boxed_slice();
+                            let ptr5 = vec5.as_ptr().cast::<u8>();
+                            let len5 = vec5.len();
+                            ::core::mem::forget(vec5);
+                            *ptr3
+                                .add(2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<usize>() = len5;
+                            *ptr3
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<*mut u8>() = ptr5.cast_mut();
+                        }
+                    };
+                    ptr3
                 }
-                impl ::core::fmt::Debug for KeyEvent {
-                    fn fmt(
-                        &self,
-                        f: &mut ::core::fmt::Formatter<'_>,
-                    ) -> ::core::fmt::Result {
-                        f.debug_struct("KeyEvent")
-                            .field("key", &self.key)
-                            .field("code", &self.code)
-                            .field("modifiers", &self.modifiers)
-                            .field("is-repeat", &self.is_repeat)
-                            .finish()
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn __post_return_run_task<T: Guest>(arg0: *mut u8) {
+                    let l0 = i32::from(*arg0.add(0).cast::<u8>());
+                    match l0 {
+                        0 => {
+                            let l1 = *arg0
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<*mut u8>();
+                            let l2 = *arg0
+                                .add(2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<usize>();
+                            let base3 = l1;
+                            let len3 = l2;
+                            _rt::cabi_dealloc(base3, len3 * 1, 1);
+                        }
+                        _ => {
+                            let l4 = *arg0
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<*mut u8>();
+                            let l5 = *arg0
+                                .add(2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<usize>();
+                            _rt::cabi_dealloc(l4, l5, 1);
+                        }
                     }
                 }
                 #[doc(hidden)]
                 #[allow(non_snake_case)]
-                pub unsafe fn _export_init_cabi<T: Guest>(
-                    arg0: f32,
+                pub unsafe fn _export_task_progress_cabi<T: Guest>(
+                    arg0: i32,
                     arg1: f32,
-                    arg2: f32,
                 ) {
                     #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
-                    T::init(LogicalSize {
-                        width: arg0,
-                        height: arg1,
-                        scale_factor: arg2,
-                    });
+                    T::task_progress(arg0 as u32, arg1);
                 }
                 #[doc(hidden)]
                 #[allow(non_snake_case)]
-                pub unsafe fn _export_resize_cabi<T: Guest>(
-                    arg0: f32,
-                    arg1: f32,
-                    arg2: f32,
+                pub unsafe fn _export_task_finished_cabi<T: Guest>(
+                    arg0: i32,
+                    arg1: i32,
+                    arg2: *mut u8,
+                    arg3: usize,
                 ) {
                     #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
-                    T::resize(LogicalSize {
-                        width: arg0,
-                        height: arg1,
-                        scale_factor: arg2,
+                    T::task_finished(
+                        arg0 as u32,
+                        match arg1 {
+                            0 => {
+                                let e = {
+                                    let len0 = arg3;
+                                    _rt::Vec::from_raw_parts(arg2.cast(), len0, len0)
+                                };
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let len1 = arg3;
+                                    let bytes1 = _rt::Vec::from_raw_parts(
+                                        arg2.cast(),
+                                        len1,
+                                        len1,
+                                    );
+                                    _rt::string_lift(bytes1)
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        },
+                    );
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_worker_message_cabi<T: Guest>(
+                    arg0: i32,
+                    arg1: *mut u8,
+                    arg2: usize,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let len0 = arg2;
+                    T::worker_message(
+                        arg0 as u32,
+                        _rt::Vec::from_raw_parts(arg1.cast(), len0, len0),
+                    );
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_worker_failed_cabi<T: Guest>(
+                    arg0: i32,
+                    arg1: *mut u8,
+                    arg2: usize,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let len0 = arg2;
+                    let bytes0 = _rt::Vec::from_raw_parts(arg1.cast(), len0, len0);
+                    T::worker_failed(arg0 as u32, _rt::string_lift(bytes0));
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_window_moved_cabi<T: Guest>(arg0: i32, arg1: i32) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    T::window_moved(super::super::super::super::vello::canvas::host::WindowPosition {
+                        x: arg0,
+                        y: arg1,
                     });
                 }
                 #[doc(hidden)]
                 #[allow(non_snake_case)]
-                pub unsafe fn _export_pointer_down_cabi<T: Guest>(
+                pub unsafe fn _export_display_changed_cabi<T: Guest>(
                     arg0: i32,
-                    arg1: f32,
-                    arg2: f32,
+                    arg1: *mut u8,
+                    arg2: usize,
                     arg3: i32,
                     arg4: i32,
-                    arg5: i32,
+                    arg5: f32,
                     arg6: i32,
                     arg7: i32,
-                    arg8: i32,
-                    arg9: i64,
                 ) {
                     #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
-                    T::pointer_down(PointerEvent {
-                        kind: PointerKind::_lift(arg0 as u8),
-                        position: super::super::super::super::vello::canvas::math::Vec2 {
-                            x: arg1,
-                            y: arg2,
-                        },
-                        buttons: PointerButton {
-                            primary: _rt::bool_lift(arg3 as u8),
-                            secondary: _rt::bool_lift(arg4 as u8),
+                    T::display_changed(super::super::super::super::vello::canvas::host::DisplayInfo {
+                        name: match arg0 {
+                            0 => None,
+                            1 => {
+                                let e = {
+                                    let len0 = arg2;
+                                    let bytes0 = _rt::Vec::from_raw_parts(
+                                        arg1.cast(),
+                                        len0,
+                                        len0,
+                                    );
+                                    _rt::string_lift(bytes0)
+                                };
+                                Some(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
                         },
-                        modifiers: Modifiers {
-                            shift: _rt::bool_lift(arg5 as u8),
-                            ctrl: _rt::bool_lift(arg6 as u8),
-                            alt: _rt::bool_lift(arg7 as u8),
-                            meta: _rt::bool_lift(arg8 as u8),
+                        width: arg3 as u32,
+                        height: arg4 as u32,
+                        scale_factor: arg5,
+                        refresh_rate_millihertz: match arg6 {
+                            0 => None,
+                            1 => {
+                                let e = arg7 as u32;
+                                Some(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
                         },
-                        pointer_id: arg9 as u64,
                     });
                 }
                 #[doc(hidden)]
                 #[allow(non_snake_case)]
-                pub unsafe fn _export_pointer_up_cabi<T: Guest>(
+                pub unsafe fn _export_deep_link_opened_cabi<T: Guest>(
+                    arg0: *mut u8,
+                    arg1: usize,
+                ) {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let len0 = arg1;
+                    let bytes0 = _rt::Vec::from_raw_parts(arg0.cast(), len0, len0);
+                    T::deep_link_opened(_rt::string_lift(bytes0));
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_scroll_changed_cabi<T: Guest>(
                     arg0: i32,
                     arg1: f32,
                     arg2: f32,
-                    arg3: i32,
-                    arg4: i32,
-                    arg5: i32,
-                    arg6: i32,
-                    arg7: i32,
-                    arg8: i32,
-                    arg9: i64,
                 ) {
                     #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
-                    T::pointer_up(PointerEvent {
-                        kind: PointerKind::_lift(arg0 as u8),
-                        position: super::super::super::super::vello::canvas::math::Vec2 {
+                    T::scroll_changed(
+                        arg0 as u32,
+                        super::super::super::super::vello::canvas::math::Vec2 {
                             x: arg1,
                             y: arg2,
                         },
-                        buttons: PointerButton {
-                            primary: _rt::bool_lift(arg3 as u8),
-                            secondary: _rt::bool_lift(arg4 as u8),
-                        },
-                        modifiers: Modifiers {
-                            shift: _rt::bool_lift(arg5 as u8),
-                            ctrl: _rt::bool_lift(arg6 as u8),
-                            alt: _rt::bool_lift(arg7 as u8),
-                            meta: _rt::bool_lift(arg8 as u8),
-                        },
-                        pointer_id: arg9 as u64,
-                    });
+                    );
                 }
                 #[doc(hidden)]
                 #[allow(non_snake_case)]
-                pub unsafe fn _export_pointer_move_cabi<T: Guest>(
+                pub unsafe fn _export_kinetic_scrolled_cabi<T: Guest>(
                     arg0: i32,
                     arg1: f32,
                     arg2: f32,
-                    arg3: i32,
-                    arg4: i32,
-                    arg5: i32,
-                    arg6: i32,
-                    arg7: i32,
-                    arg8: i32,
-                    arg9: i64,
+                    arg3: f32,
+                    arg4: f32,
                 ) {
                     #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
-                    T::pointer_move(PointerEvent {
-                        kind: PointerKind::_lift(arg0 as u8),
-                        position: super::super::super::super::vello::canvas::math::Vec2 {
+                    T::kinetic_scrolled(
+                        arg0 as u32,
+                        super::super::super::super::vello::canvas::math::Vec2 {
                             x: arg1,
                             y: arg2,
                         },
-                        buttons: PointerButton {
-                            primary: _rt::bool_lift(arg3 as u8),
-                            secondary: _rt::bool_lift(arg4 as u8),
-                        },
-                        modifiers: Modifiers {
-                            shift: _rt::bool_lift(arg5 as u8),
-                            ctrl: _rt::bool_lift(arg6 as u8),
-                            alt: _rt::bool_lift(arg7 as u8),
-                            meta: _rt::bool_lift(arg8 as u8),
+                        super::super::super::super::vello::canvas::math::Vec2 {
+                            x: arg3,
+                            y: arg4,
                         },
-                        pointer_id: arg9 as u64,
-                    });
+                    );
                 }
                 #[doc(hidden)]
                 #[allow(non_snake_case)]
-                pub unsafe fn _export_key_down_cabi<T: Guest>(
+                pub unsafe fn _export_instance_activated_cabi<T: Guest>(
                     arg0: *mut u8,
                     arg1: usize,
-                    arg2: *mut u8,
-                    arg3: usize,
-                    arg4: i32,
-                    arg5: i32,
-                    arg6: i32,
-                    arg7: i32,
-                    arg8: i32,
                 ) {
                     #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
-                    let len0 = arg1;
-                    let bytes0 = _rt::Vec::from_raw_parts(arg0.cast(), len0, len0);
-                    let len1 = arg3;
-                    let bytes1 = _rt::Vec::from_raw_parts(arg2.cast(), len1, len1);
-                    T::key_down(KeyEvent {
-                        key: _rt::string_lift(bytes0),
-                        code: _rt::string_lift(bytes1),
-                        modifiers: Modifiers {
-                            shift: _rt::bool_lift(arg4 as u8),
-                            ctrl: _rt::bool_lift(arg5 as u8),
-                            alt: _rt::bool_lift(arg6 as u8),
-                            meta: _rt::bool_lift(arg7 as u8),
-                        },
-                        is_repeat: _rt::bool_lift(arg8 as u8),
-                    });
+                    let base3 = arg0;
+                    let len3 = arg1;
+                    let mut result3 = _rt::Vec::with_capacity(len3);
+                    for i in 0..len3 {
+                        let base = base3
+                            .add(i * (2 * ::core::mem::size_of::<*const u8>()));
+                        let e3 = {
+                            let l0 = *base.add(0).cast::<*mut u8>();
+                            let l1 = *base
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<usize>();
+                            let len2 = l1;
+                            let bytes2 = _rt::Vec::from_raw_parts(l0.cast(), len2, len2);
+                            _rt::string_lift(bytes2)
+                        };
+                        result3.push(e3);
+                    }
+                    _rt::cabi_dealloc(
+                        base3,
+                        len3 * (2 * ::core::mem::size_of::<*const u8>()),
+                        ::core::mem::size_of::<*const u8>(),
+                    );
+                    T::instance_activated(result3);
                 }
                 #[doc(hidden)]
                 #[allow(non_snake_case)]
-                pub unsafe fn _export_key_up_cabi<T: Guest>(
+                pub unsafe fn _export_close_requested_cabi<T: Guest>() -> i32 {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let result0 = T::close_requested();
+                    result0.clone() as i32
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_save_state_cabi<T: Guest>() -> *mut u8 {
+                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
+                    let result0 = T::save_state();
+                    let ptr1 = (&raw mut _RET_AREA.0).cast::<u8>();
+                    match result0 {
+                        Some(e) => {
+                            *ptr1.add(0).cast::<u8>() = (1i32) as u8;
+                            let vec2 = (e).into_boxed_slice();
+                            let ptr2 = vec2.as_ptr().cast::<u8>();
+                            let len2 = vec2.len();
+                            ::core::mem::forget(vec2);
+                            *ptr1
+                                .add(2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<usize>() = len2;
+                            *ptr1
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<*mut u8>() = ptr2.cast_mut();
+                        }
+                        None => {
+                            *ptr1.add(0).cast::<u8>() = (0i32) as u8;
+                        }
+                    };
+                    ptr1
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn __post_return_save_state<T: Guest>(arg0: *mut u8) {
+                    let l0 = i32::from(*arg0.add(0).cast::<u8>());
+                    match l0 {
+                        0 => {}
+                        _ => {
+                            let l1 = *arg0
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<*mut u8>();
+                            let l2 = *arg0
+                                .add(2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<usize>();
+                            let base3 = l1;
+                            let len3 = l2;
+                            _rt::cabi_dealloc(base3, len3 * 1, 1);
+                        }
+                    }
+                }
+                #[doc(hidden)]
+                #[allow(non_snake_case)]
+                pub unsafe fn _export_restore_state_cabi<T: Guest>(
                     arg0: *mut u8,
                     arg1: usize,
-                    arg2: *mut u8,
-                    arg3: usize,
-                    arg4: i32,
-                    arg5: i32,
-                    arg6: i32,
-                    arg7: i32,
-                    arg8: i32,
-                ) {
+                ) -> *mut u8 {
                     #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
                     let len0 = arg1;
-                    let bytes0 = _rt::Vec::from_raw_parts(arg0.cast(), len0, len0);
-                    let len1 = arg3;
-                    let bytes1 = _rt::Vec::from_raw_parts(arg2.cast(), len1, len1);
-                    T::key_up(KeyEvent {
-                        key: _rt::string_lift(bytes0),
-                        code: _rt::string_lift(bytes1),
-                        modifiers: Modifiers {
-                            shift: _rt::bool_lift(arg4 as u8),
-                            ctrl: _rt::bool_lift(arg5 as u8),
-                            alt: _rt::bool_lift(arg6 as u8),
-                            meta: _rt::bool_lift(arg7 as u8),
-                        },
-                        is_repeat: _rt::bool_lift(arg8 as u8),
-                    });
+                    let result1 = T::restore_state(
+                        _rt::Vec::from_raw_parts(arg0.cast(), len0, len0),
+                    );
+                    let ptr2 = (&raw mut _RET_AREA.0).cast::<u8>();
+                    match result1 {
+                        Ok(_) => {
+                            *ptr2.add(0).cast::<u8>() = (0i32) as u8;
+                        }
+                        Err(e) => {
+                            *ptr2.add(0).cast::<u8>() = (1i32) as u8;
+                            let vec3 = (e.into_bytes()).into_boxed_slice();
+                            let ptr3 = vec3.as_ptr().cast::<u8>();
+                            let len3 = vec3.len();
+                            ::core::mem::forget(vec3);
+                            *ptr2
+                                .add(2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<usize>() = len3;
+                            *ptr2
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<*mut u8>() = ptr3.cast_mut();
+                        }
+                    };
+                    ptr2
                 }
                 #[doc(hidden)]
                 #[allow(non_snake_case)]
-                pub unsafe fn _export_frame_cabi<T: Guest>(arg0: f32) {
-                    #[cfg(target_arch = "wasm32")] _rt::run_ctors_once();
-                    T::frame(arg0);
+                pub unsafe fn __post_return_restore_state<T: Guest>(arg0: *mut u8) {
+                    let l0 = i32::from(*arg0.add(0).cast::<u8>());
+                    match l0 {
+                        0 => {}
+                        _ => {
+                            let l1 = *arg0
+                                .add(::core::mem::size_of::<*const u8>())
+                                .cast::<*mut u8>();
+                            let l2 = *arg0
+                                .add(2 * ::core::mem::size_of::<*const u8>())
+                                .cast::<usize>();
+                            _rt::cabi_dealloc(l1, l2, 1);
+                        }
+                    }
                 }
                 pub trait Guest {
                     /// Called once after component instantiation.
                     fn init(initial: LogicalSize) -> ();
-                    /// Called when window logical size or scale factor changes.
+                    /// Called when window logical size or scale factor changes. Never called with a zero
+                    /// area; a window shrunk to nothing is reported through `minimized-changed` instead.
                     fn resize(new: LogicalSize) -> ();
+                    /// The window was minimized (or shrunk to zero area) or restored. No frames are
+                    /// delivered while minimized; `resize` follows a restore if the size changed.
+                    fn minimized_changed(minimized: bool) -> ();
                     /// Pointer events targeting the canvas.
+                    ///
+                    /// Event handlers may draw as well as `frame`. If a handler clears or draws anything,
+                    /// the host presents it right away in place of the window's contents, so draw the whole
+                    /// scene, as in `frame`. Handlers that draw nothing leave the last frame on screen.
                     fn pointer_down(evt: PointerEvent) -> ();
                     fn pointer_up(evt: PointerEvent) -> ();
                     fn pointer_move(evt: PointerEvent) -> ();
                     /// Keyboard focus is owned by the host; key events delivered when focused.
                     fn key_down(evt: KeyEvent) -> ();
                     fn key_up(evt: KeyEvent) -> ();
+                    /// Text the user typed, after keyboard layout, dead-key and IME composition. Sent
+                    /// after the `key-down` that produced it (or on IME commit); never contains control
+                    /// characters, so editors should insert it verbatim and use key events for Enter,
+                    /// Backspace and navigation.
+                    fn text_input(text: _rt::String) -> ();
+                    /// Touchpad pinch; `delta` is the change in scale, positive when zooming in
+                    /// (e.g. 0.1 means 10% larger).
+                    fn pinch_gesture(evt: GestureEvent) -> ();
+                    /// Touchpad two-finger rotation; `delta` is in degrees, counterclockwise positive.
+                    fn rotation_gesture(evt: GestureEvent) -> ();
+                    /// Touchpad two-finger double tap ("smart magnify" on macOS).
+                    fn double_tap_gesture(position: Vec2, modifiers: Modifiers) -> ();
                     /// Frame callback. Host only invokes when guest requested redraw.
                     fn frame(dt_ms: f32) -> ();
+                    /// `count` display refreshes went by without a new frame since the previous `frame`,
+                    /// which asked for this one; called just before this `frame`. Guests can shed work
+                    /// here, e.g. lower effect quality, when it keeps happening.
+                    fn frame_missed(count: u32) -> ();
+                    /// The battery or power-saver state changed; guests can lower animation rates while
+                    /// on battery.
+                    fn power_state_changed(state: PowerStatus) -> ();
+                    /// The user has not touched the window for `--idle-timeout` seconds (five minutes by
+                    /// default), or came back after that. Screensavers and kiosks can dim or lock here.
+                    fn idle_changed(idle: bool) -> ();
+                    /// Memory is running low: the system's, or this component's `--memory-budget` for its
+                    /// assets and the host's caches. The host has already emptied its
+                    /// own caches; free assets you can recreate, least recently used first. Sent once each
+                    /// time pressure rises.
+                    fn trim_memory(level: MemoryPressure) -> ();
+                    /// Right after a `critical` `trim-memory`, the host dropped these assets, least recently
+                    /// drawn first: enough to get back under 80% of `--memory-budget`, or all it could when
+                    /// the system is short. Anything drawn in the last two frames is kept. The ids are now
+                    /// unknown, as if the guest had dropped them; recreate them before drawing again.
+                    fn assets_evicted(
+                        images: _rt::Vec<ImageId>,
+                        pictures: _rt::Vec<PictureId>,
+                        shaders: _rt::Vec<ShaderId>,
+                    ) -> ();
+                    /// Entry point of a task instance started by `spawn-task`, called once with the task's
+                    /// name and input. No other export is called on that instance, and it cannot draw.
+                    fn run_task(
+                        name: _rt::String,
+                        input: _rt::Vec<u8>,
+                    ) -> Result<_rt::Vec<u8>, _rt::String>;
+                    /// A task reported progress with `report-task-progress`. Reports are coalesced, so
+                    /// guests see at most one per task between frames.
+                    fn task_progress(task: TaskId, progress: f32) -> ();
+                    /// A task's `run-task` returned, or trapped (reported as an error).
+                    fn task_finished(
+                        task: TaskId,
+                        outcome: Result<_rt::Vec<u8>, _rt::String>,
+                    ) -> ();
+                    /// A worker called `post-message`.
+                    fn worker_message(worker: WorkerId, message: _rt::Vec<u8>) -> ();
+                    /// A worker failed to load or trapped, and has stopped.
+                    fn worker_failed(worker: WorkerId, error: _rt::String) -> ();
+                    /// The window was moved, by the user or by `set-window-position`.
+                    fn window_moved(position: WindowPosition) -> ();
+                    /// The window moved to another monitor, or the monitor's scale or mode changed.
+                    fn display_changed(info: DisplayInfo) -> ();
+                    /// The host was opened with a `frontier://` link, at launch (after `init`) or while
+                    /// running. `url` is the whole link, for the guest to route on.
+                    fn deep_link_opened(url: _rt::String) -> ();
+                    /// The user scrolled region `id` to `offset`, by wheel, drag or fling. A frame is
+                    /// requested, so content drawn in `push-scroll-region` follows on its own.
+                    fn scroll_changed(id: u32, offset: Vec2) -> ();
+                    /// Released kinetic scroller `id` moved by `delta` to `offset` since the last frame. A
+                    /// frame is requested; the scroller is forgotten once it stops.
+                    fn kinetic_scrolled(id: u32, offset: Vec2, delta: Vec2) -> ();
+                    /// The host runs with `--single-instance` and was launched again for this component;
+                    /// `args` are that launch's arguments after `--`. The window has been raised.
+                    fn instance_activated(args: _rt::Vec<_rt::String>) -> ();
+                    /// The user asked to close the window. Return `deny` to keep it open (e.g. to
+                    /// prompt about unsaved changes) and call `request-quit` later to exit.
+                    fn close_requested() -> CloseResponse;
+                    /// Serialize what the guest needs to resume exactly where it is, for host snapshots
+                    /// (crash recovery, checkpoints). Return none if the guest does not support them.
+                    fn save_state() -> Option<_rt::Vec<u8>>;
+                    /// Resume from a `save-state` result. Called on a fresh instance right after `init`;
+                    /// an error leaves the guest in its freshly initialised state.
+                    fn restore_state(state: _rt::Vec<u8>) -> Result<(), _rt::String>;
                 }
                 #[doc(hidden)]
                 macro_rules! __export_vello_canvas_app_0_1_0_cabi {
@@ -664,43 +7874,177 @@ pub mod exports {
                         unsafe extern "C" fn export_resize(arg0 : f32, arg1 : f32, arg2 :
                         f32,) { unsafe { $($path_to_types)*:: _export_resize_cabi::<$ty >
                         (arg0, arg1, arg2) } } #[unsafe (export_name =
+                        "vello:canvas/app@0.1.0#minimized-changed")] unsafe extern "C" fn
+                        export_minimized_changed(arg0 : i32,) { unsafe {
+                        $($path_to_types)*:: _export_minimized_changed_cabi::<$ty >
+                        (arg0) } } #[unsafe (export_name =
                         "vello:canvas/app@0.1.0#pointer-down")] unsafe extern "C" fn
                         export_pointer_down(arg0 : i32, arg1 : f32, arg2 : f32, arg3 :
                         i32, arg4 : i32, arg5 : i32, arg6 : i32, arg7 : i32, arg8 : i32,
-                        arg9 : i64,) { unsafe { $($path_to_types)*::
+                        arg9 : i32, arg10 : i32, arg11 : i32, arg12 : i64, arg13 : i32,
+                        arg14 : * mut u8, arg15 : usize,) { unsafe { $($path_to_types)*::
                         _export_pointer_down_cabi::<$ty > (arg0, arg1, arg2, arg3, arg4,
-                        arg5, arg6, arg7, arg8, arg9) } } #[unsafe (export_name =
+                        arg5, arg6, arg7, arg8, arg9, arg10, arg11, arg12, arg13, arg14,
+                        arg15) } } #[unsafe (export_name =
                         "vello:canvas/app@0.1.0#pointer-up")] unsafe extern "C" fn
                         export_pointer_up(arg0 : i32, arg1 : f32, arg2 : f32, arg3 : i32,
                         arg4 : i32, arg5 : i32, arg6 : i32, arg7 : i32, arg8 : i32, arg9
-                        : i64,) { unsafe { $($path_to_types)*::
+                        : i32, arg10 : i32, arg11 : i32, arg12 : i64, arg13 : i32, arg14
+                        : * mut u8, arg15 : usize,) { unsafe { $($path_to_types)*::
                         _export_pointer_up_cabi::<$ty > (arg0, arg1, arg2, arg3, arg4,
-                        arg5, arg6, arg7, arg8, arg9) } } #[unsafe (export_name =
+                        arg5, arg6, arg7, arg8, arg9, arg10, arg11, arg12, arg13, arg14,
+                        arg15) } } #[unsafe (export_name =
                         "vello:canvas/app@0.1.0#pointer-move")] unsafe extern "C" fn
                         export_pointer_move(arg0 : i32, arg1 : f32, arg2 : f32, arg3 :
                         i32, arg4 : i32, arg5 : i32, arg6 : i32, arg7 : i32, arg8 : i32,
-                        arg9 : i64,) { unsafe { $($path_to_types)*::
+                        arg9 : i32, arg10 : i32, arg11 : i32, arg12 : i64, arg13 : i32,
+                        arg14 : * mut u8, arg15 : usize,) { unsafe { $($path_to_types)*::
                         _export_pointer_move_cabi::<$ty > (arg0, arg1, arg2, arg3, arg4,
-                        arg5, arg6, arg7, arg8, arg9) } } #[unsafe (export_name =
+                        arg5, arg6, arg7, arg8, arg9, arg10, arg11, arg12, arg13, arg14,
+                        arg15) } } #[unsafe (export_name =
                         "vello:canvas/app@0.1.0#key-down")] unsafe extern "C" fn
                         export_key_down(arg0 : * mut u8, arg1 : usize, arg2 : * mut u8,
                         arg3 : usize, arg4 : i32, arg5 : i32, arg6 : i32, arg7 : i32,
-                        arg8 : i32,) { unsafe { $($path_to_types)*::
-                        _export_key_down_cabi::<$ty > (arg0, arg1, arg2, arg3, arg4,
-                        arg5, arg6, arg7, arg8) } } #[unsafe (export_name =
+                        arg8 : i32, arg9 : i32, arg10 : i32, arg11 : i32, arg12 : i32,) {
+                        unsafe { $($path_to_types)*:: _export_key_down_cabi::<$ty >
+                        (arg0, arg1, arg2, arg3, arg4, arg5, arg6, arg7, arg8, arg9,
+                        arg10, arg11, arg12) } } #[unsafe (export_name =
                         "vello:canvas/app@0.1.0#key-up")] unsafe extern "C" fn
                         export_key_up(arg0 : * mut u8, arg1 : usize, arg2 : * mut u8,
                         arg3 : usize, arg4 : i32, arg5 : i32, arg6 : i32, arg7 : i32,
-                        arg8 : i32,) { unsafe { $($path_to_types)*::
-                        _export_key_up_cabi::<$ty > (arg0, arg1, arg2, arg3, arg4, arg5,
-                        arg6, arg7, arg8) } } #[unsafe (export_name =
+                        arg8 : i32, arg9 : i32, arg10 : i32, arg11 : i32, arg12 : i32,) {
+                        unsafe { $($path_to_types)*:: _export_key_up_cabi::<$ty > (arg0,
+                        arg1, arg2, arg3, arg4, arg5, arg6, arg7, arg8, arg9, arg10,
+                        arg11, arg12) } } #[unsafe (export_name =
+                        "vello:canvas/app@0.1.0#text-input")] unsafe extern "C" fn
+                        export_text_input(arg0 : * mut u8, arg1 : usize,) { unsafe {
+                        $($path_to_types)*:: _export_text_input_cabi::<$ty > (arg0, arg1)
+                        } } #[unsafe (export_name =
+                        "vello:canvas/app@0.1.0#pinch-gesture")] unsafe extern "C" fn
+                        export_pinch_gesture(arg0 : i32, arg1 : f32, arg2 : f32, arg3 :
+                        i32, arg4 : i32, arg5 : i32, arg6 : i32, arg7 : i32, arg8 : i32,
+                        arg9 : i32, arg10 : f32,) { unsafe { $($path_to_types)*::
+                        _export_pinch_gesture_cabi::<$ty > (arg0, arg1, arg2, arg3, arg4,
+                        arg5, arg6, arg7, arg8, arg9, arg10) } } #[unsafe (export_name =
+                        "vello:canvas/app@0.1.0#rotation-gesture")] unsafe extern "C" fn
+                        export_rotation_gesture(arg0 : i32, arg1 : f32, arg2 : f32, arg3
+                        : i32, arg4 : i32, arg5 : i32, arg6 : i32, arg7 : i32, arg8 :
+                        i32, arg9 : i32, arg10 : f32,) { unsafe { $($path_to_types)*::
+                        _export_rotation_gesture_cabi::<$ty > (arg0, arg1, arg2, arg3,
+                        arg4, arg5, arg6, arg7, arg8, arg9, arg10) } } #[unsafe
+                        (export_name = "vello:canvas/app@0.1.0#double-tap-gesture")]
+                        unsafe extern "C" fn export_double_tap_gesture(arg0 : f32, arg1 :
+                        f32, arg2 : i32, arg3 : i32, arg4 : i32, arg5 : i32, arg6 : i32,
+                        arg7 : i32, arg8 : i32,) { unsafe { $($path_to_types)*::
+                        _export_double_tap_gesture_cabi::<$ty > (arg0, arg1, arg2, arg3,
+                        arg4, arg5, arg6, arg7, arg8) } } #[unsafe (export_name =
                         "vello:canvas/app@0.1.0#frame")] unsafe extern "C" fn
                         export_frame(arg0 : f32,) { unsafe { $($path_to_types)*::
-                        _export_frame_cabi::<$ty > (arg0) } } };
+                        _export_frame_cabi::<$ty > (arg0) } } #[unsafe (export_name =
+                        "vello:canvas/app@0.1.0#frame-missed")] unsafe extern "C" fn
+                        export_frame_missed(arg0 : i32,) { unsafe { $($path_to_types)*::
+                        _export_frame_missed_cabi::<$ty > (arg0) } } #[unsafe
+                        (export_name = "vello:canvas/app@0.1.0#power-state-changed")]
+                        unsafe extern "C" fn export_power_state_changed(arg0 : i32, arg1
+                        : i32, arg2 : i32, arg3 : i32,) { unsafe { $($path_to_types)*::
+                        _export_power_state_changed_cabi::<$ty > (arg0, arg1, arg2, arg3)
+                        } } #[unsafe (export_name =
+                        "vello:canvas/app@0.1.0#idle-changed")] unsafe extern "C" fn
+                        export_idle_changed(arg0 : i32,) { unsafe { $($path_to_types)*::
+                        _export_idle_changed_cabi::<$ty > (arg0) } } #[unsafe
+                        (export_name = "vello:canvas/app@0.1.0#trim-memory")] unsafe
+                        extern "C" fn export_trim_memory(arg0 : i32,) { unsafe {
+                        $($path_to_types)*:: _export_trim_memory_cabi::<$ty > (arg0) } }
+                        #[unsafe (export_name = "vello:canvas/app@0.1.0#assets-evicted")]
+                        unsafe extern "C" fn export_assets_evicted(arg0 : * mut u8, arg1
+                        : usize, arg2 : * mut u8, arg3 : usize, arg4 : * mut u8, arg5 :
+                        usize,) { unsafe { $($path_to_types)*::
+                        _export_assets_evicted_cabi::<$ty > (arg0, arg1, arg2, arg3,
+                        arg4, arg5) } } #[unsafe (export_name =
+                        "vello:canvas/app@0.1.0#run-task")] unsafe extern "C" fn
+                        export_run_task(arg0 : * mut u8, arg1 : usize, arg2 : * mut u8,
+                        arg3 : usize,) -> * mut u8 { unsafe { $($path_to_types)*::
+                        _export_run_task_cabi::<$ty > (arg0, arg1, arg2, arg3) } }
+                        #[unsafe (export_name =
+                        "cabi_post_vello:canvas/app@0.1.0#run-task")] unsafe extern "C"
+                        fn _post_return_run_task(arg0 : * mut u8,) { unsafe {
+                        $($path_to_types)*:: __post_return_run_task::<$ty > (arg0) } }
+                        #[unsafe (export_name = "vello:canvas/app@0.1.0#task-progress")]
+                        unsafe extern "C" fn export_task_progress(arg0 : i32, arg1 :
+                        f32,) { unsafe { $($path_to_types)*::
+                        _export_task_progress_cabi::<$ty > (arg0, arg1) } } #[unsafe
+                        (export_name = "vello:canvas/app@0.1.0#task-finished")] unsafe
+                        extern "C" fn export_task_finished(arg0 : i32, arg1 : i32, arg2 :
+                        * mut u8, arg3 : usize,) { unsafe { $($path_to_types)*::
+                        _export_task_finished_cabi::<$ty > (arg0, arg1, arg2, arg3) } }
+                        #[unsafe (export_name = "vello:canvas/app@0.1.0#worker-message")]
+                        unsafe extern "C" fn export_worker_message(arg0 : i32, arg1 : *
+                        mut u8, arg2 : usize,) { unsafe { $($path_to_types)*::
+                        _export_worker_message_cabi::<$ty > (arg0, arg1, arg2) } }
+                        #[unsafe (export_name = "vello:canvas/app@0.1.0#worker-failed")]
+                        unsafe extern "C" fn export_worker_failed(arg0 : i32, arg1 : *
+                        mut u8, arg2 : usize,) { unsafe { $($path_to_types)*::
+                        _export_worker_failed_cabi::<$ty > (arg0, arg1, arg2) } }
+                        #[unsafe (export_name = "vello:canvas/app@0.1.0#window-moved")]
+                        unsafe extern "C" fn export_window_moved(arg0 : i32, arg1 : i32,)
+                        { unsafe { $($path_to_types)*:: _export_window_moved_cabi::<$ty >
+                        (arg0, arg1) } } #[unsafe (export_name =
+                        "vello:canvas/app@0.1.0#display-changed")] unsafe extern "C" fn
+                        export_display_changed(arg0 : i32, arg1 : * mut u8, arg2 : usize,
+                        arg3 : i32, arg4 : i32, arg5 : f32, arg6 : i32, arg7 : i32,) {
+                        unsafe { $($path_to_types)*:: _export_display_changed_cabi::<$ty
+                        > (arg0, arg1, arg2, arg3, arg4, arg5, arg6, arg7) } } #[unsafe
+                        (export_name = "vello:canvas/app@0.1.0#deep-link-opened")] unsafe
+                        extern "C" fn export_deep_link_opened(arg0 : * mut u8, arg1 :
+                        usize,) { unsafe { $($path_to_types)*::
+                        _export_deep_link_opened_cabi::<$ty > (arg0, arg1) } } #[unsafe
+                        (export_name = "vello:canvas/app@0.1.0#scroll-changed")] unsafe
+                        extern "C" fn export_scroll_changed(arg0 : i32, arg1 : f32, arg2
+                        : f32,) { unsafe { $($path_to_types)*::
+                        _export_scroll_changed_cabi::<$ty > (arg0, arg1, arg2) } }
+                        #[unsafe (export_name =
+                        "vello:canvas/app@0.1.0#kinetic-scrolled")] unsafe extern "C" fn
+                        export_kinetic_scrolled(arg0 : i32, arg1 : f32, arg2 : f32, arg3
+                        : f32, arg4 : f32,) { unsafe { $($path_to_types)*::
+                        _export_kinetic_scrolled_cabi::<$ty > (arg0, arg1, arg2, arg3,
+                        arg4) } } #[unsafe (export_name =
+                        "vello:canvas/app@0.1.0#instance-activated")] unsafe extern "C"
+                        fn export_instance_activated(arg0 : * mut u8, arg1 : usize,) {
+                        unsafe { $($path_to_types)*::
+                        _export_instance_activated_cabi::<$ty > (arg0, arg1) } } #[unsafe
+                        (export_name = "vello:canvas/app@0.1.0#close-requested")] unsafe
+                        extern "C" fn export_close_requested() -> i32 { unsafe {
+                        $($path_to_types)*:: _export_close_requested_cabi::<$ty > () } }
+                        #[unsafe (export_name = "vello:canvas/app@0.1.0#save-state")]
+                        unsafe extern "C" fn export_save_state() -> * mut u8 { unsafe {
+                        $($path_to_types)*:: _export_save_state_cabi::<$ty > () } }
+                        #[unsafe (export_name =
+                        "cabi_post_vello:canvas/app@0.1.0#save-state")] unsafe extern "C"
+                        fn _post_return_save_state(arg0 : * mut u8,) { unsafe {
+                        $($path_to_types)*:: __post_return_save_state::<$ty > (arg0) } }
+                        #[unsafe (export_name = "vello:canvas/app@0.1.0#restore-state")]
+                        unsafe extern "C" fn export_restore_state(arg0 : * mut u8, arg1 :
+                        usize,) -> * mut u8 { unsafe { $($path_to_types)*::
+                        _export_restore_state_cabi::<$ty > (arg0, arg1) } } #[unsafe
+                        (export_name = "cabi_post_vello:canvas/app@0.1.0#restore-state")]
+                        unsafe extern "C" fn _post_return_restore_state(arg0 : * mut u8,)
+                        { unsafe { $($path_to_types)*:: __post_return_restore_state::<$ty
+                        > (arg0) } } };
                     };
                 }
                 #[doc(hidden)]
                 pub(crate) use __export_vello_canvas_app_0_1_0_cabi;
+                #[cfg_attr(target_pointer_width = "64", repr(align(8)))]
+                #[cfg_attr(target_pointer_width = "32", repr(align(4)))]
+                struct _RetArea(
+                    [::core::mem::MaybeUninit<
+                        u8,
+                    >; 3 * ::core::mem::size_of::<*const u8>()],
+                );
+                static mut _RET_AREA: _RetArea = _RetArea(
+                    [::core::mem::MaybeUninit::uninit(); 3
+                        * ::core::mem::size_of::<*const u8>()],
+                );
             }
         }
     }
@@ -708,6 +8052,8 @@ pub mod exports {
 #[rustfmt::skip]
 mod _rt {
     #![allow(dead_code, clippy::all)]
+    pub use alloc_crate::string::String;
+    pub use alloc_crate::vec::Vec;
     pub fn as_f32<T: AsF32>(t: T) -> f32 {
         t.as_f32()
     }
@@ -725,10 +8071,65 @@ mod _rt {
             self as f32
         }
     }
-    pub use alloc_crate::string::String;
-    #[cfg(target_arch = "wasm32")]
-    pub fn run_ctors_once() {
-        wit_bindgen_rt::run_ctors_once();
+    pub use alloc_crate::alloc;
+    pub fn as_i32<T: AsI32>(t: T) -> i32 {
+        t.as_i32()
+    }
+    pub trait AsI32 {
+        fn as_i32(self) -> i32;
+    }
+    impl<'a, T: Copy + AsI32> AsI32 for &'a T {
+        fn as_i32(self) -> i32 {
+            (*self).as_i32()
+        }
+    }
+    impl AsI32 for i32 {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for u32 {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for i16 {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for u16 {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for i8 {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for u8 {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for char {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
+    }
+    impl AsI32 for usize {
+        #[inline]
+        fn as_i32(self) -> i32 {
+            self as i32
+        }
     }
     pub unsafe fn bool_lift(val: u8) -> bool {
         if cfg!(debug_assertions) {
@@ -741,7 +8142,13 @@ mod _rt {
             val != 0
         }
     }
-    pub use alloc_crate::vec::Vec;
+    pub unsafe fn invalid_enum_discriminant<T>() -> T {
+        if cfg!(debug_assertions) {
+            panic!("invalid enum discriminant")
+        } else {
+            unsafe { core::hint::unreachable_unchecked() }
+        }
+    }
     pub unsafe fn string_lift(bytes: Vec<u8>) -> String {
         if cfg!(debug_assertions) {
             String::from_utf8(bytes).unwrap()
@@ -749,6 +8156,57 @@ mod _rt {
             String::from_utf8_unchecked(bytes)
         }
     }
+    pub fn as_f64<T: AsF64>(t: T) -> f64 {
+        t.as_f64()
+    }
+    pub trait AsF64 {
+        fn as_f64(self) -> f64;
+    }
+    impl<'a, T: Copy + AsF64> AsF64 for &'a T {
+        fn as_f64(self) -> f64 {
+            (*self).as_f64()
+        }
+    }
+    impl AsF64 for f64 {
+        #[inline]
+        fn as_f64(self) -> f64 {
+            self as f64
+        }
+    }
+    pub unsafe fn cabi_dealloc(ptr: *mut u8, size: usize, align: usize) {
+        if size == 0 {
+            return;
+        }
+        let layout = alloc::Layout::from_size_align_unchecked(size, align);
+        alloc::dealloc(ptr, layout);
+    }
+    pub fn as_i64<T: AsI64>(t: T) -> i64 {
+        t.as_i64()
+    }
+    pub trait AsI64 {
+        fn as_i64(self) -> i64;
+    }
+    impl<'a, T: Copy + AsI64> AsI64 for &'a T {
+        fn as_i64(self) -> i64 {
+            (*self).as_i64()
+        }
+    }
+    impl AsI64 for i64 {
+        #[inline]
+        fn as_i64(self) -> i64 {
+            self as i64
+        }
+    }
+    impl AsI64 for u64 {
+        #[inline]
+        fn as_i64(self) -> i64 {
+            self as i64
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    pub fn run_ctors_once() {
+        wit_bindgen_rt::run_ctors_once();
+    }
     extern crate alloc as alloc_crate;
 }
 /// Generates `#[unsafe(no_mangle)]` functions to export the specified type as
@@ -787,31 +8245,197 @@ pub(crate) use __export_canvas_app_impl as export;
 )]
 #[doc(hidden)]
 #[allow(clippy::octal_escapes)]
-pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 1025] = *b"\
-\0asm\x0d\0\x01\0\0\x19\x16wit-component-encoding\x04\0\x07\x80\x07\x01A\x02\x01\
-A\x08\x01B\x04\x01r\x02\x01xv\x01yv\x04\0\x04vec2\x03\0\0\x01r\x04\x01rv\x01gv\x01\
-bv\x01av\x04\0\x05color\x03\0\x02\x03\0\x17vello:canvas/math@0.1.0\x05\0\x02\x03\
-\0\0\x05color\x02\x03\0\0\x04vec2\x01B\x10\x02\x03\x02\x01\x01\x04\0\x05color\x03\
-\0\0\x02\x03\x02\x01\x02\x04\0\x04vec2\x03\0\x02\x01m\x05\x05trace\x05debug\x04i\
-nfo\x04warn\x05error\x04\0\x09log-level\x03\0\x04\x01@\x01\x01c\x01\x01\0\x04\0\x05\
-clear\x01\x06\x01@\x03\x06origin\x03\x04size\x03\x05color\x01\x01\0\x04\0\x09fil\
-l-rect\x01\x07\x01@\x04\x04texts\x06origin\x03\x04sizev\x05color\x01\x01\0\x04\0\
-\x09draw-text\x01\x08\x01@\0\x01\0\x04\0\x0drequest-frame\x01\x09\x01@\x02\x05le\
-vel\x05\x07messages\x01\0\x04\0\x03log\x01\x0a\x03\0\x17vello:canvas/host@0.1.0\x05\
-\x03\x01B\x1b\x02\x03\x02\x01\x02\x04\0\x04vec2\x03\0\0\x01r\x03\x05widthv\x06he\
-ightv\x0cscale-factorv\x04\0\x0clogical-size\x03\0\x02\x01r\x02\x07primary\x7f\x09\
-secondary\x7f\x04\0\x0epointer-button\x03\0\x04\x01r\x04\x05shift\x7f\x04ctrl\x7f\
-\x03alt\x7f\x04meta\x7f\x04\0\x09modifiers\x03\0\x06\x01m\x03\x05mouse\x05touch\x03\
-pen\x04\0\x0cpointer-kind\x03\0\x08\x01r\x05\x04kind\x09\x08position\x01\x07butt\
-ons\x05\x09modifiers\x07\x0apointer-idw\x04\0\x0dpointer-event\x03\0\x0a\x01r\x04\
-\x03keys\x04codes\x09modifiers\x07\x09is-repeat\x7f\x04\0\x09key-event\x03\0\x0c\
-\x01@\x01\x07initial\x03\x01\0\x04\0\x04init\x01\x0e\x01@\x01\x03new\x03\x01\0\x04\
-\0\x06resize\x01\x0f\x01@\x01\x03evt\x0b\x01\0\x04\0\x0cpointer-down\x01\x10\x04\
-\0\x0apointer-up\x01\x10\x04\0\x0cpointer-move\x01\x10\x01@\x01\x03evt\x0d\x01\0\
-\x04\0\x08key-down\x01\x11\x04\0\x06key-up\x01\x11\x01@\x01\x05dt-msv\x01\0\x04\0\
-\x05frame\x01\x12\x04\0\x16vello:canvas/app@0.1.0\x05\x04\x04\0\x1dvello:canvas/\
-canvas-app@0.1.0\x04\0\x0b\x10\x01\0\x0acanvas-app\x03\0\0\0G\x09producers\x01\x0c\
-processed-by\x02\x0dwit-component\x070.227.1\x10wit-bindgen-rust\x060.41.0";
+pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 8537] = *b"\
+\0asm\x0d\0\x01\0\0\x19\x16wit-component-encoding\x04\0\x07\xd8A\x01A\x02\x01A\x12\
+\x01B\x04\x01r\x02\x01xv\x01yv\x04\0\x04vec2\x03\0\0\x01r\x04\x01rv\x01gv\x01bv\x01\
+av\x04\0\x05color\x03\0\x02\x03\0\x17vello:canvas/math@0.1.0\x05\0\x02\x03\0\0\x05\
+color\x02\x03\0\0\x04vec2\x01B\xbb\x02\x02\x03\x02\x01\x01\x04\0\x05color\x03\0\0\
+\x02\x03\x02\x01\x02\x04\0\x04vec2\x03\0\x02\x01m\x05\x05trace\x05debug\x04info\x04\
+warn\x05error\x04\0\x09log-level\x03\0\x04\x01m\x03\x06normal\x0dalways-on-top\x10\
+always-on-bottom\x04\0\x0cwindow-level\x03\0\x06\x01m\x03\x04sans\x09sans-bold\x09\
+monospace\x04\0\x0bfont-family\x03\0\x08\x01m\x03\x04auto\x03ltr\x03rtl\x04\0\x0e\
+text-direction\x03\0\x0a\x01m\x02\x0dhorizontal-tb\x0bvertical-rl\x04\0\x0cwriti\
+ng-mode\x03\0\x0c\x01r\x02\x06origin\x03\x04size\x03\x04\0\x04rect\x03\0\x0e\x01\
+r\x04\x04texts\x05color\x01\x04sizev\x04font\x09\x04\0\x0bstyled-span\x03\0\x10\x01\
+k\x01\x01r\x03\x04texts\x02fg\x01\x02bg\x12\x04\0\x09grid-cell\x03\0\x13\x01r\x05\
+\x05color\x01\x03minv\x03maxv\x08baselinev\x03gapv\x04\0\x09bar-style\x03\0\x15\x01\
+pv\x01r\x04\x07columnsy\x06values\x17\x03minv\x03maxv\x04\0\x07heatmap\x03\0\x18\
+\x01y\x04\0\x08image-id\x03\0\x1a\x01y\x04\0\x0apicture-id\x03\0\x1c\x01y\x04\0\x0c\
+animation-id\x03\0\x1e\x01y\x04\0\x11animated-image-id\x03\0\x20\x01py\x01r\x04\x05\
+widthy\x06heighty\x12frame-durations-ms\"\x05playsy\x04\0\x17animated-image-meta\
+data\x03\0#\x01y\x04\0\x0fpixel-buffer-id\x03\0%\x01y\x04\0\x09shader-id\x03\0'\x01\
+y\x04\0\x0aemitter-id\x03\0)\x01r\x0d\x08position\x03\x04ratev\x0dmax-particlesy\
+\x0blifetime-msv\x05anglev\x06spreadv\x05speedv\x0cspeed-jitterv\x07gravity\x03\x0a\
+start-sizev\x08end-sizev\x0bstart-color\x01\x09end-color\x01\x04\0\x0eemitter-co\
+nfig\x03\0+\x01y\x04\0\x09camera-id\x03\0-\x01m\x04\x07pending\x09streaming\x06d\
+enied\x06failed\x04\0\x0ccamera-state\x03\0/\x01r\x03\x05widthy\x06heighty\x08se\
+quencew\x04\0\x15camera-frame-metadata\x03\01\x01r\x03\x04size\x03\x0bduration-m\
+su\x0aframe-ratev\x04\0\x12animation-metadata\x03\03\x01m\x03\x06repeat\x08repea\
+t-x\x06mirror\x04\0\x0epattern-repeat\x03\05\x01m\x02\x03svg\x03pdf\x04\0\x0dexp\
+ort-format\x03\07\x01m\x03\x04none\x09grayscale\x05sepia\x04\0\x0cimage-filter\x03\
+\09\x01r\x03\x04tint\x01\x06filter:\x07opacityv\x04\0\x0bimage-style\x03\0;\x01k\
+}\x01r\x03\x0aon-battery\x7f\x0fbattery-percent=\x0bpower-saver\x7f\x04\0\x0cpow\
+er-status\x03\0>\x01r\x02\x0dhigh-contrast\x7f\x0dforced-colors\x7f\x04\0\x0bpre\
+ferences\x03\0@\x01m\x05\x05light\x06medium\x05heavy\x07success\x05error\x04\0\x0b\
+haptic-kind\x03\0B\x01m\x05\x06linear\x04ease\x07ease-in\x08ease-out\x0bease-in-\
+out\x04\0\x06easing\x03\0D\x01y\x04\0\x07task-id\x03\0F\x01y\x04\0\x09worker-id\x03\
+\0H\x01p}\x01q\x02\x05bytes\x01\xca\0\0\x03url\x01s\0\x04\0\x0dworker-source\x03\
+\0K\x01n\x03\x04wasi\x06canvas\x08database\x04\0\x12worker-permissions\x03\0M\x01\
+ks\x01q\x02\x07private\0\0\x06shared\x01\xcf\0\0\x04\0\x0dstorage-scope\x03\0P\x01\
+m\x03\x04date\x04time\x09date-time\x04\0\x0edatetime-style\x03\0R\x01r\x02\x01xz\
+\x01yz\x04\0\x0fwindow-position\x03\0T\x01ky\x01r\x05\x04name\xcf\0\x05widthy\x06\
+heighty\x0cscale-factorv\x17refresh-rate-millihertz\xd6\0\x04\0\x0cdisplay-info\x03\
+\0W\x01r\x05\x0chost-versions\x0dvello-versions\x08gpu-name\xcf\0\x07backend\xcf\
+\0\x02oss\x04\0\x10host-environment\x03\0Y\x01@\x01\x01c\x01\x01\0\x04\0\x05clea\
+r\x01[\x01@\x03\x06origin\x03\x04size\x03\x05color\x01\x01\0\x04\0\x09fill-rect\x01\
+\\\x01@\x04\x04texts\x06origin\x03\x04sizev\x05color\x01\x01\0\x04\0\x09draw-tex\
+t\x01]\x01@\x05\x04texts\x06origin\x03\x04sizev\x05color\x01\x04font\x09\x01\0\x04\
+\0\x13draw-text-with-font\x01^\x01@\x06\x04texts\x06origin\x03\x04sizev\x05color\
+\x01\x04font\x09\x09direction\x0b\x01\0\x04\0\x18draw-text-with-direction\x01_\x01\
+@\x08\x04texts\x06origin\x03\x04sizev\x05color\x01\x04font\x09\x09direction\x0b\x04\
+mode\x0d\x08rotationv\x01\0\x04\0\x12draw-text-oriented\x01`\x01p\x11\x01@\x04\x05\
+spans\xe1\0\x06origin\x03\x09max-widthv\x09direction\x0b\x01\0\x04\0\x0edraw-ric\
+h-text\x01b\x01p\x14\x01@\x04\x05cells\xe3\0\x06origin\x03\x04sizev\x09tab-width\
+y\x01\0\x04\0\x0edraw-text-grid\x01d\x01@\x01\x04sizev\0\x03\x04\0\x0etext-grid-\
+cell\x01e\x01@\x04\x06sources\x04rect\x0f\x09base-sizev\x05color\x01\x01\0\x04\0\
+\x0ddraw-markdown\x01f\x01@\x02\x04datas\x04rect\x0f\x01\0\x04\0\x07draw-qr\x01g\
+\x01@\x03\x06values\x17\x04rect\x0f\x05style\x16\x01\0\x04\0\x09draw-bars\x01h\x01\
+p\x03\x01@\x03\x06points\xe9\0\x06radiusv\x05color\x01\x01\0\x04\0\x0bdraw-point\
+s\x01j\x01p\x01\x01@\x03\x04grid\x19\x04rect\x0f\x08colormap\xeb\0\x01\0\x04\0\x0c\
+draw-heatmap\x01l\x01@\x03\x02idy\x0ccontent-size\x03\x08viewport\x0f\0\x7f\x04\0\
+\x14define-scroll-region\x01m\x01@\x01\x02idy\x01\0\x04\0\x14remove-scroll-regio\
+n\x01n\x01k\x03\x01@\x01\x02idy\0\xef\0\x04\0\x0dscroll-offset\x01p\x01@\x02\x02\
+idy\x06offset\x03\x01\0\x04\0\x11set-scroll-offset\x01q\x04\0\x12push-scroll-reg\
+ion\x01n\x01@\0\x01\0\x04\0\x11pop-scroll-region\x01r\x01@\x05\x02idy\x08positio\
+n\x03\x06offset\x03\x03min\x03\x03max\x03\0\x7f\x04\0\x0dkinetic-press\x01s\x01@\
+\x02\x02idy\x08position\x03\0\xef\0\x04\0\x0ckinetic-drag\x01t\x04\0\x0fkinetic-\
+release\x01n\x04\0\x0ckinetic-stop\x01p\x01@\x04\x04texts\x04sizev\x05point\x03\x09\
+direction\x0b\0y\x04\0\x0dhit-test-text\x01u\x01@\x04\x04texts\x04sizev\x05index\
+y\x09direction\x0b\0\x0f\x04\0\x0acaret-rect\x01v\x01k\x1b\x01@\x03\x05widthy\x06\
+heighty\x04rgba\xca\0\0\xf7\0\x04\0\x0ccreate-image\x01x\x01@\x01\x05image\x1b\x01\
+\0\x04\0\x0adrop-image\x01y\x01j\x01\x1d\x01s\x01@\x01\x05bytes\xca\0\0\xfa\0\x04\
+\0\x08load-svg\x01{\x01@\x01\x07picture\x1d\0\xef\0\x04\0\x0cpicture-size\x01|\x01\
+@\x01\x07picture\x1d\x01\0\x04\0\x0cdrop-picture\x01}\x01@\x03\x07picture\x1d\x06\
+origin\x03\x04size\x03\x01\0\x04\0\x0cdraw-picture\x01~\x01j\x01\x1f\x01s\x01@\x01\
+\x05bytes\xca\0\0\xff\0\x04\0\x0bload-lottie\x01\x80\x01\x01k4\x01@\x01\x09anima\
+tion\x1f\0\x81\x01\x04\0\x0eanimation-info\x01\x82\x01\x01@\x01\x09animation\x1f\
+\x01\0\x04\0\x0edrop-animation\x01\x83\x01\x01@\x04\x09animation\x1f\x07time-msu\
+\x06origin\x03\x04size\x03\x01\0\x04\0\x0edraw-animation\x01\x84\x01\x01j\x01!\x01\
+s\x01@\x01\x05bytes\xca\0\0\x85\x01\x04\0\x0cdecode-image\x01\x86\x01\x01k$\x01@\
+\x01\x05image!\0\x87\x01\x04\0\x13animated-image-info\x01\x88\x01\x01@\x01\x05im\
+age!\x01\0\x04\0\x13drop-animated-image\x01\x89\x01\x01@\x05\x05image!\x05framey\
+\x06origin\x03\x04size\x03\x05style<\x01\0\x04\0\x13draw-animated-image\x01\x8a\x01\
+\x01k*\x01@\x01\x06config,\0\x8b\x01\x04\0\x0ecreate-emitter\x01\x8c\x01\x01@\x02\
+\x07emitter*\x06config,\0\x7f\x04\0\x11configure-emitter\x01\x8d\x01\x01@\x02\x07\
+emitter*\x05county\x01\0\x04\0\x0dburst-emitter\x01\x8e\x01\x01@\x01\x07emitter*\
+\0\xd6\0\x04\0\x16emitter-particle-count\x01\x8f\x01\x01@\x01\x07emitter*\x01\0\x04\
+\0\x0cdraw-emitter\x01\x90\x01\x04\0\x0cdrop-emitter\x01\x90\x01\x01j\x01(\x01s\x01\
+@\x01\x06sources\0\x91\x01\x04\0\x0fregister-shader\x01\x92\x01\x01@\x01\x06shad\
+er(\x01\0\x04\0\x0bdrop-shader\x01\x93\x01\x01@\x02\x06shader(\x06params\x17\x01\
+\0\x04\0\x11push-shader-layer\x01\x94\x01\x04\0\x10pop-shader-layer\x01r\x01ps\x01\
+@\0\0\x95\x01\x04\0\x0clist-cameras\x01\x96\x01\x01j\x01.\x01s\x01@\x01\x06devic\
+ey\0\x97\x01\x04\0\x0bopen-camera\x01\x98\x01\x01k0\x01@\x01\x06camera.\0\x99\x01\
+\x04\0\x0dcamera-status\x01\x9a\x01\x01k2\x01@\x01\x06camera.\0\x9b\x01\x04\0\x11\
+camera-frame-info\x01\x9c\x01\x01@\x04\x06camera.\x06origin\x03\x04size\x03\x05s\
+tyle<\x01\0\x04\0\x0bdraw-camera\x01\x9d\x01\x01@\x01\x06camera.\x01\0\x04\0\x0c\
+close-camera\x01\x9e\x01\x01k&\x01@\x02\x05widthy\x06heighty\0\x9f\x01\x04\0\x13\
+create-pixel-buffer\x01\xa0\x01\x01@\x05\x06buffer&\x01xy\x01yy\x05widthy\x04rgb\
+a\xca\0\0\x7f\x04\0\x12write-pixel-buffer\x01\xa1\x01\x01@\x03\x06buffer&\x06ori\
+gin\x03\x04size\x03\x01\0\x04\0\x14present-pixel-buffer\x01\xa2\x01\x01@\x01\x06\
+buffer&\x01\0\x04\0\x11drop-pixel-buffer\x01\xa3\x01\x01@\x04\x05image\x1b\x06or\
+igin\x03\x04size\x03\x05style<\x01\0\x04\0\x0adraw-image\x01\xa4\x01\x01@\x06\x06\
+origin\x03\x04size\x03\x05image\x1b\x09tile-size\x03\x06repeat6\x05style<\x01\0\x04\
+\0\x0cfill-pattern\x01\xa5\x01\x01@\x01\x06format8\x01\0\x04\0\x0cexport-frame\x01\
+\xa6\x01\x04\0\x0drequest-frame\x01r\x01@\0\0u\x04\0\x10frame-elapsed-ms\x01\xa7\
+\x01\x01@\x05\x02idy\x04fromv\x02tov\x0bduration-msv\x06easing\xc5\0\0\x7f\x04\0\
+\x07animate\x01\xa8\x01\x01@\x04\x02idy\x06targetv\x09stiffnessv\x07dampingv\0\x7f\
+\x04\0\x06spring\x01\xa9\x01\x01kv\x01@\x01\x02idy\0\xaa\x01\x04\0\x0eanimated-v\
+alue\x01\xab\x01\x04\0\x0estop-animating\x01\xab\x01\x01@\x02\x05level\x05\x07me\
+ssages\x01\0\x04\0\x03log\x01\xac\x01\x01@\x01\x05level\x07\x01\0\x04\0\x10set-w\
+indow-level\x01\xad\x01\x01@\x01\x07opacityv\x01\0\x04\0\x12set-window-opacity\x01\
+\xae\x01\x01@\x01\x05color\x01\x01\0\x04\0\x14set-background-color\x01\xaf\x01\x01\
+@\x01\x08position\xd5\0\x01\0\x04\0\x13set-window-position\x01\xb0\x01\x01k\xd5\0\
+\x01@\0\0\xb1\x01\x04\0\x13get-window-position\x01\xb2\x01\x01@\x01\x07enabled\x7f\
+\x01\0\x04\0\x11set-click-through\x01\xb3\x01\x04\0\x0crequest-quit\x01r\x01j\0\x01\
+s\x01@\x01\x06targets\0\xb4\x01\x04\0\x08navigate\x01\xb5\x01\x01k\xd8\0\x01@\0\0\
+\xb6\x01\x04\0\x10get-display-info\x01\xb7\x01\x04\0\x0fget-launch-args\x01\x96\x01\
+\x04\0\x11host-capabilities\x01\x96\x01\x01@\x01\x0acapabilitys\0\xb4\x01\x04\0\x0f\
+drop-capability\x01\xb8\x01\x01@\0\0\xda\0\x04\0\x09host-info\x01\xb9\x01\x01@\0\
+\0s\x04\0\x0aget-locale\x01\xba\x01\x01@\x02\x05valueu\x0ffraction-digits}\0s\x04\
+\0\x0dformat-number\x01\xbb\x01\x01j\x01s\x01s\x01@\x02\x05valueu\x08currencys\0\
+\xbc\x01\x04\0\x0fformat-currency\x01\xbd\x01\x01@\x02\x07unix-msx\x05style\xd3\0\
+\0\xcf\0\x04\0\x0fformat-datetime\x01\xbe\x01\x01@\0\0\xcf\0\x04\0\x0elocal-time\
+zone\x01\xbf\x01\x01@\0\0\xc1\0\x04\0\x0fget-preferences\x01\xc0\x01\x01kz\x01@\x01\
+\x07unix-msx\0\xc1\x01\x04\0\x0dutc-offset-at\x01\xc2\x01\x01k?\x01@\0\0\xc3\x01\
+\x04\0\x0bpower-state\x01\xc4\x01\x01@\x01\x04kind\xc3\0\0\x7f\x04\0\x0fhaptic-f\
+eedback\x01\xc5\x01\x01k\xca\0\x01j\x01\xc6\x01\x01s\x01@\x02\x05scope\xd1\0\x03\
+keys\0\xc7\x01\x04\0\x0bstorage-get\x01\xc8\x01\x01@\x03\x05scope\xd1\0\x03keys\x05\
+value\xca\0\0\xb4\x01\x04\0\x0bstorage-set\x01\xc9\x01\x01@\x02\x05scope\xd1\0\x03\
+keys\0\xb4\x01\x04\0\x0estorage-delete\x01\xca\x01\x01j\x01\xc7\0\x01s\x01@\x02\x04\
+names\x05input\xca\0\0\xcb\x01\x04\0\x0aspawn-task\x01\xcc\x01\x01@\x01\x08progr\
+essv\x01\0\x04\0\x14report-task-progress\x01\xcd\x01\x01j\x01\xc9\0\x01s\x01@\x02\
+\x06source\xcc\0\x0bpermissions\xce\0\0\xce\x01\x04\0\x0cspawn-worker\x01\xcf\x01\
+\x01@\x02\x06worker\xc9\0\x07message\xca\0\0\xb4\x01\x04\0\x0epost-to-worker\x01\
+\xd0\x01\x01@\x01\x06worker\xc9\0\0\x7f\x04\0\x10terminate-worker\x01\xd1\x01\x04\
+\0\x13seconds-since-input\x01\xa7\x01\x01@\x01\x04names\x01\0\x04\0\x09perf-mark\
+\x01\xd2\x01\x01@\x02\x04names\x0astart-marks\x01\0\x04\0\x0cperf-measure\x01\xd3\
+\x01\x03\0\x17vello:canvas/host@0.1.0\x05\x03\x01B\x12\x01p}\x01q\x05\x04null\0\0\
+\x07integer\x01x\0\x04real\x01u\0\x04text\x01s\0\x04blob\x01\0\0\x04\0\x09sql-va\
+lue\x03\0\x01\x01y\x04\0\x0cstatement-id\x03\0\x03\x01j\x01\x04\x01s\x01@\x01\x03\
+sqls\0\x05\x04\0\x07prepare\x01\x06\x01p\x02\x01j\x01w\x01s\x01@\x02\x09statemen\
+t\x04\x06params\x07\0\x08\x04\0\x07execute\x01\x09\x01p\x07\x01j\x01\x0a\x01s\x01\
+@\x02\x09statement\x04\x06params\x07\0\x0b\x04\0\x05query\x01\x0c\x01@\x01\x09st\
+atement\x04\0\x7f\x04\0\x08finalize\x01\x0d\x03\0\x1bvello:canvas/database@0.1.0\
+\x05\x04\x02\x03\0\x01\x0cdisplay-info\x02\x03\0\x01\x08image-id\x02\x03\0\x01\x0a\
+picture-id\x02\x03\0\x01\x0cpower-status\x02\x03\0\x01\x09shader-id\x02\x03\0\x01\
+\x07task-id\x02\x03\0\x01\x0fwindow-position\x02\x03\0\x01\x09worker-id\x01Bs\x02\
+\x03\x02\x01\x02\x04\0\x04vec2\x03\0\0\x02\x03\x02\x01\x05\x04\0\x0cdisplay-info\
+\x03\0\x02\x02\x03\x02\x01\x06\x04\0\x08image-id\x03\0\x04\x02\x03\x02\x01\x07\x04\
+\0\x0apicture-id\x03\0\x06\x02\x03\x02\x01\x08\x04\0\x0cpower-status\x03\0\x08\x02\
+\x03\x02\x01\x09\x04\0\x09shader-id\x03\0\x0a\x02\x03\x02\x01\x0a\x04\0\x07task-\
+id\x03\0\x0c\x02\x03\x02\x01\x0b\x04\0\x0fwindow-position\x03\0\x0e\x02\x03\x02\x01\
+\x0c\x04\0\x09worker-id\x03\0\x10\x01r\x03\x05widthv\x06heightv\x0cscale-factorv\
+\x04\0\x0clogical-size\x03\0\x12\x01m\x02\x08moderate\x08critical\x04\0\x0fmemor\
+y-pressure\x03\0\x14\x01r\x02\x07primary\x7f\x09secondary\x7f\x04\0\x0epointer-b\
+utton\x03\0\x16\x01n\x08\x0aleft-shift\x0bright-shift\x09left-ctrl\x0aright-ctrl\
+\x08left-alt\x09right-alt\x09left-meta\x0aright-meta\x04\0\x0dmodifier-keys\x03\0\
+\x18\x01n\x03\x09caps-lock\x08num-lock\x0bscroll-lock\x04\0\x09lock-keys\x03\0\x1a\
+\x01r\x07\x05shift\x7f\x04ctrl\x7f\x03alt\x7f\x04meta\x7f\x04keys\x19\x05locks\x1b\
+\x0enum-lock-known\x7f\x04\0\x09modifiers\x03\0\x1c\x01m\x04\x08standard\x04left\
+\x05right\x06numpad\x04\0\x0ckey-location\x03\0\x1e\x01m\x03\x05mouse\x05touch\x03\
+pen\x04\0\x0cpointer-kind\x03\0\x20\x01r\x02\x08position\x01\x07time-msu\x04\0\x0e\
+pointer-sample\x03\0\"\x01p#\x01r\x07\x04kind!\x08position\x01\x07buttons\x17\x09\
+modifiers\x1d\x0apointer-idw\x0fcoalesced-county\x07history$\x04\0\x0dpointer-ev\
+ent\x03\0%\x01r\x05\x03keys\x04codes\x09modifiers\x1d\x09is-repeat\x7f\x08locati\
+on\x1f\x04\0\x09key-event\x03\0'\x01m\x04\x07started\x07changed\x05ended\x09canc\
+elled\x04\0\x0dgesture-phase\x03\0)\x01r\x04\x05phase*\x08position\x01\x09modifi\
+ers\x1d\x05deltav\x04\0\x0dgesture-event\x03\0+\x01m\x02\x05allow\x04deny\x04\0\x0e\
+close-response\x03\0-\x01@\x01\x07initial\x13\x01\0\x04\0\x04init\x01/\x01@\x01\x03\
+new\x13\x01\0\x04\0\x06resize\x010\x01@\x01\x09minimized\x7f\x01\0\x04\0\x11mini\
+mized-changed\x011\x01@\x01\x03evt&\x01\0\x04\0\x0cpointer-down\x012\x04\0\x0apo\
+inter-up\x012\x04\0\x0cpointer-move\x012\x01@\x01\x03evt(\x01\0\x04\0\x08key-dow\
+n\x013\x04\0\x06key-up\x013\x01@\x01\x04texts\x01\0\x04\0\x0atext-input\x014\x01\
+@\x01\x03evt,\x01\0\x04\0\x0dpinch-gesture\x015\x04\0\x10rotation-gesture\x015\x01\
+@\x02\x08position\x01\x09modifiers\x1d\x01\0\x04\0\x12double-tap-gesture\x016\x01\
+@\x01\x05dt-msv\x01\0\x04\0\x05frame\x017\x01@\x01\x05county\x01\0\x04\0\x0cfram\
+e-missed\x018\x01@\x01\x05state\x09\x01\0\x04\0\x13power-state-changed\x019\x01@\
+\x01\x04idle\x7f\x01\0\x04\0\x0cidle-changed\x01:\x01@\x01\x05level\x15\x01\0\x04\
+\0\x0btrim-memory\x01;\x01p\x05\x01p\x07\x01p\x0b\x01@\x03\x06images<\x08picture\
+s=\x07shaders>\x01\0\x04\0\x0eassets-evicted\x01?\x01p}\x01j\x01\xc0\0\x01s\x01@\
+\x02\x04names\x05input\xc0\0\0\xc1\0\x04\0\x08run-task\x01B\x01@\x02\x04task\x0d\
+\x08progressv\x01\0\x04\0\x0dtask-progress\x01C\x01@\x02\x04task\x0d\x07outcome\xc1\
+\0\x01\0\x04\0\x0dtask-finished\x01D\x01@\x02\x06worker\x11\x07message\xc0\0\x01\
+\0\x04\0\x0eworker-message\x01E\x01@\x02\x06worker\x11\x05errors\x01\0\x04\0\x0d\
+worker-failed\x01F\x01@\x01\x08position\x0f\x01\0\x04\0\x0cwindow-moved\x01G\x01\
+@\x01\x04info\x03\x01\0\x04\0\x0fdisplay-changed\x01H\x01@\x01\x03urls\x01\0\x04\
+\0\x10deep-link-opened\x01I\x01@\x02\x02idy\x06offset\x01\x01\0\x04\0\x0escroll-\
+changed\x01J\x01@\x03\x02idy\x06offset\x01\x05delta\x01\x01\0\x04\0\x10kinetic-s\
+crolled\x01K\x01ps\x01@\x01\x04args\xcc\0\x01\0\x04\0\x12instance-activated\x01M\
+\x01@\0\0.\x04\0\x0fclose-requested\x01N\x01k\xc0\0\x01@\0\0\xcf\0\x04\0\x0asave\
+-state\x01P\x01j\0\x01s\x01@\x01\x05state\xc0\0\0\xd1\0\x04\0\x0drestore-state\x01\
+R\x04\0\x16vello:canvas/app@0.1.0\x05\x0d\x04\0\x1dvello:canvas/canvas-app@0.1.0\
+\x04\0\x0b\x10\x01\0\x0acanvas-app\x03\0\0\0G\x09producers\x01\x0cprocessed-by\x02\
+\x0dwit-component\x070.227.1\x10wit-bindgen-rust\x060.41.0";
 #[inline(never)]
 #[doc(hidden)]
 pub fn __link_custom_section_describing_imports() {
@@ -48,6 +48,8 @@ struct CounterApp {
     active_pointer: Option<(u64, Button)>,
     hover: Option<Button>,
     cursor: [f32; 2],
+    /// Progress of the background doubling task, while one is running.
+    doubling: Option<f32>,
 }
 
 impl CounterApp {
@@ -62,6 +64,7 @@ impl CounterApp {
             active_pointer: None,
             hover: None,
             cursor: [0.0, 0.0],
+            doubling: None,
         }
     }
 
@@ -106,6 +109,20 @@ impl CounterApp {
         }
     }
 
+    /// Double the count on a task instance, as a stand-in for slow work.
+    fn start_doubling(&mut self) {
+        if self.doubling.is_some() {
+            return;
+        }
+        match host::spawn_task("double", &self.count.to_le_bytes()) {
+            Ok(_) => {
+                self.doubling = Some(0.0);
+                self.request_redraw();
+            }
+            Err(err) => host::log(host::LogLevel::Warn, &err),
+        }
+    }
+
     fn button_at(&self, point: [f32; 2]) -> Option<Button> {
         let layout = self.layout();
         if layout.minus.contains(point) {
@@ -170,18 +187,22 @@ impl CounterApp {
 
     fn draw_label(&self, layout: &Layout) {
         let text = format!("{}", self.count);
-        host::draw_text(
+        host::draw_text_with_font(
             &text,
             to_vec2(layout.count_label_origin()),
             layout.count_text_size,
             host_color(0.92, 0.94, 0.98, 1.0),
+            host::FontFamily::SansBold,
         );
     }
 
     fn draw_hint(&self, layout: &Layout) {
-        let hint = "Use +/- keys or Space/Enter";
+        let hint = match self.doubling {
+            Some(progress) => format!("Doubling... {}%", (progress * 100.0) as u32),
+            None => "Use +/- keys or Space/Enter".to_string(),
+        };
         host::draw_text(
-            hint,
+            &hint,
             to_vec2(layout.hint_origin),
             layout.count_text_size * 0.4,
             host_color(0.6, 0.68, 0.78, 1.0),
@@ -230,8 +251,9 @@ impl CounterApp {
         match evt.key.as_str() {
             "+" | "=" => self.adjust_count(1),
             "-" => self.adjust_count(-1),
-            "Space" => self.adjust_count(1),
+            " " => self.adjust_count(1),
             "Enter" => self.reset_count(),
+            "d" => self.start_doubling(),
             other if other.trim() == "+" => self.adjust_count(1),
             other if other.trim() == "-" => self.adjust_count(-1),
             _ => {}
@@ -256,6 +278,8 @@ impl Guest for Component {
         with_state(|state| state.handle_resize(new));
     }
 
+    fn minimized_changed(_minimized: bool) {}
+
     fn pointer_down(evt: app::PointerEvent) {
         with_state(|state| state.handle_pointer_down(evt));
     }
@@ -276,9 +300,107 @@ impl Guest for Component {
         with_state(|state| state.handle_key_up(evt));
     }
 
+    fn text_input(_text: String) {}
+
+    fn pinch_gesture(_evt: app::GestureEvent) {}
+
+    fn rotation_gesture(_evt: app::GestureEvent) {}
+
+    fn double_tap_gesture(_position: HostVec2, _modifiers: app::Modifiers) {}
+
     fn frame(dt_ms: f32) {
         with_state(|state| state.handle_frame(dt_ms));
     }
+
+    fn frame_missed(_count: u32) {}
+
+    fn window_moved(_position: host::WindowPosition) {}
+
+    fn display_changed(_info: app::DisplayInfo) {}
+
+    fn power_state_changed(_state: app::PowerStatus) {}
+
+    fn idle_changed(_idle: bool) {}
+
+    fn trim_memory(_level: app::MemoryPressure) {}
+
+    fn assets_evicted(_images: Vec<u32>, _pictures: Vec<u32>, _shaders: Vec<u32>) {}
+
+    fn run_task(name: String, input: Vec<u8>) -> Result<Vec<u8>, String> {
+        if name != "double" {
+            return Err(format!("unknown task '{name}'"));
+        }
+        let count = <[u8; 4]>::try_from(input.as_slice())
+            .map_err(|_| format!("expected 4 bytes of input, got {}", input.len()))?;
+        host::report_task_progress(0.5);
+        let doubled = i32::from_le_bytes(count).saturating_mul(2);
+        host::report_task_progress(1.0);
+        Ok(doubled.to_le_bytes().to_vec())
+    }
+
+    fn task_progress(_task: host::TaskId, progress: f32) {
+        with_state(|state| {
+            if state.doubling.is_some() {
+                state.doubling = Some(progress);
+                state.request_redraw();
+            }
+        });
+    }
+
+    fn task_finished(_task: host::TaskId, outcome: Result<Vec<u8>, String>) {
+        with_state(|state| {
+            state.doubling = None;
+            let count = outcome.and_then(|bytes| {
+                <[u8; 4]>::try_from(bytes.as_slice())
+                    .map_err(|_| format!("expected 4 bytes from the task, got {}", bytes.len()))
+            });
+            match count {
+                Ok(count) => state.count = i32::from_le_bytes(count),
+                Err(err) => host::log(host::LogLevel::Warn, &err),
+            }
+            state.request_redraw();
+        });
+    }
+
+    fn worker_message(_worker: host::WorkerId, message: Vec<u8>) {
+        // Workers that send a count set it, so plugins can drive the demo.
+        if let Ok(count) = <[u8; 4]>::try_from(message.as_slice()) {
+            with_state(|state| {
+                state.count = i32::from_le_bytes(count);
+                state.request_redraw();
+            });
+        }
+    }
+
+    fn worker_failed(_worker: host::WorkerId, error: String) {
+        host::log(host::LogLevel::Warn, &error);
+    }
+
+    fn deep_link_opened(_url: String) {}
+
+    fn scroll_changed(_id: u32, _offset: HostVec2) {}
+
+    fn kinetic_scrolled(_id: u32, _offset: HostVec2, _delta: HostVec2) {}
+
+    fn instance_activated(_args: Vec<String>) {}
+
+    fn close_requested() -> app::CloseResponse {
+        app::CloseResponse::Allow
+    }
+
+    fn save_state() -> Option<Vec<u8>> {
+        Some(with_state(|state| state.count.to_le_bytes().to_vec()))
+    }
+
+    fn restore_state(saved: Vec<u8>) -> Result<(), String> {
+        let count = <[u8; 4]>::try_from(saved.as_slice())
+            .map_err(|_| format!("expected 4 bytes of state, got {}", saved.len()))?;
+        with_state(|state| {
+            state.count = i32::from_le_bytes(count);
+            state.request_redraw();
+        });
+        Ok(())
+    }
 }
 
 struct Layout {
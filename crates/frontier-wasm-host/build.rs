@@ -1,3 +1,24 @@
+use std::fs;
+
 fn main() {
     println!("cargo:rerun-if-changed=../../wit/vello/canvas.wit");
+    println!("cargo:rerun-if-changed=../../Cargo.lock");
+    println!("cargo:rustc-env=VELLO_VERSION={}", locked_version("vello"));
+}
+
+/// Version of `package` pinned in the workspace lockfile, reported by `host-info`.
+fn locked_version(package: &str) -> String {
+    let lock = fs::read_to_string("../../Cargo.lock").unwrap_or_default();
+    let mut lines = lock.lines();
+    while let Some(line) = lines.next() {
+        if line.strip_prefix("name = ") == Some(&format!("\"{package}\"")) {
+            if let Some(version) = lines
+                .next()
+                .and_then(|line| line.strip_prefix("version = "))
+            {
+                return version.trim_matches('"').to_string();
+            }
+        }
+    }
+    "unknown".to_string()
 }
@@ -0,0 +1,317 @@
+//! Semantics for the UI the host draws itself (the error overlay, the console and the
+//! camera prompt), described independently of whatever the guest exposes so the recovery
+//! flow stays usable with a screen reader. [`HostUi::tree_update`] turns them into the
+//! AccessKit tree the window's adapter publishes, and [`HostUi::action`] maps the screen
+//! reader's requests back to what the host should do.
+//!
+//! Buttons carry the [`Action`] they perform so the host can move focus between them with
+//! Tab, the arrow keys or a gamepad, and trigger the focused one with Enter or A.
+
+use accesskit::{Live, NodeId, Tree, TreeId, TreeUpdate};
+
+/// The window node every host UI node hangs from.
+const ROOT_ID: NodeId = NodeId(0);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// A failure that needs the user's attention before the guest runs again.
+    Alert,
+    /// A question the guest is blocked on until it is answered.
+    AlertDialog,
+    /// Read-only text, such as the error report.
+    Document,
+    /// A keyboard-triggered action.
+    Button,
+    /// Lines that grow over time, such as guest logs.
+    Log,
+}
+
+/// What a host UI button does when activated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Restart,
+    RestoreLastGoodState,
+    CopyError,
+    AllowCamera,
+    DenyCamera,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Node {
+    pub role: Role,
+    pub label: String,
+    /// Keys that trigger the node's action, as shown in the overlay (`"R"`, `"F5"`).
+    pub shortcut: Option<String>,
+    pub action: Option<Action>,
+    /// Set on the button Enter or the gamepad's A would activate.
+    pub focused: bool,
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    fn new(role: Role, label: impl Into<String>) -> Self {
+        Self {
+            role,
+            label: label.into(),
+            shortcut: None,
+            action: None,
+            focused: false,
+            children: Vec::new(),
+        }
+    }
+
+    fn button(label: &str, shortcut: impl Into<String>, action: Action) -> Self {
+        Self {
+            shortcut: Some(shortcut.into()),
+            action: Some(action),
+            ..Self::new(Role::Button, label)
+        }
+    }
+
+    pub fn is_modal(&self) -> bool {
+        matches!(self.role, Role::Alert | Role::AlertDialog)
+    }
+
+    /// Actions of the child buttons, in focus order.
+    pub fn actions(&self) -> Vec<Action> {
+        self.children
+            .iter()
+            .filter_map(|node| node.action)
+            .collect()
+    }
+
+    /// Mark the child button performing `action` as focused, and no other.
+    pub fn set_focus(&mut self, action: Action) {
+        for child in &mut self.children {
+            child.focused = child.action == Some(action);
+        }
+    }
+
+    /// Add this node and its children to `nodes`, numbered in tree order from `next`, and
+    /// return its id. `focus` becomes the focused button's id.
+    fn encode(
+        &self,
+        next: &mut u64,
+        nodes: &mut Vec<(NodeId, accesskit::Node)>,
+        focus: &mut NodeId,
+    ) -> NodeId {
+        let id = NodeId(*next);
+        *next += 1;
+        let mut node = accesskit::Node::new(match self.role {
+            Role::Alert => accesskit::Role::Alert,
+            Role::AlertDialog => accesskit::Role::AlertDialog,
+            Role::Document => accesskit::Role::Document,
+            Role::Button => accesskit::Role::Button,
+            Role::Log => accesskit::Role::Log,
+        });
+        match self.role {
+            Role::Document => node.set_value(self.label.as_str()),
+            _ => node.set_label(self.label.as_str()),
+        }
+        if self.is_modal() {
+            node.set_modal();
+        }
+        if self.role == Role::Log {
+            node.set_live(Live::Polite);
+        }
+        if let Some(shortcut) = &self.shortcut {
+            node.set_keyboard_shortcut(shortcut.as_str());
+        }
+        if self.action.is_some() {
+            node.add_action(accesskit::Action::Click);
+            node.add_action(accesskit::Action::Focus);
+        }
+        if self.focused {
+            *focus = id;
+        }
+        let children: Vec<NodeId> = self
+            .children
+            .iter()
+            .map(|child| child.encode(next, nodes, focus))
+            .collect();
+        node.set_children(children);
+        nodes.push((id, node));
+        id
+    }
+
+    /// This node and its descendants in tree order.
+    fn flatten<'a>(&'a self, out: &mut Vec<&'a Node>) {
+        out.push(self);
+        for child in &self.children {
+            child.flatten(out);
+        }
+    }
+}
+
+/// The error overlay: the failure, its report and the keys that recover from it.
+pub fn error_overlay(title: &str, report: &str, restart: &str, can_restore: bool) -> Node {
+    let mut children = vec![
+        Node::new(Role::Document, report),
+        Node::button("Restart", format!("R or {restart}"), Action::Restart),
+    ];
+    if can_restore {
+        children.push(Node::button(
+            "Restore last good state",
+            "S",
+            Action::RestoreLastGoodState,
+        ));
+    }
+    children.push(Node::button("Copy error", "C", Action::CopyError));
+    Node {
+        children,
+        ..Node::new(Role::Alert, title)
+    }
+}
+
+pub fn camera_prompt(camera: &str) -> Node {
+    Node {
+        children: vec![
+            Node::button("Allow", "Y", Action::AllowCamera),
+            Node::button("Deny", "N", Action::DenyCamera),
+        ],
+        ..Node::new(
+            Role::AlertDialog,
+            format!("Allow this component to use the camera \"{camera}\"?"),
+        )
+    }
+}
+
+pub fn console(lines: &[String]) -> Node {
+    Node {
+        children: lines
+            .iter()
+            .map(|line| Node::new(Role::Document, line.trim()))
+            .collect(),
+        ..Node::new(Role::Log, "Console")
+    }
+}
+
+/// The host UI currently on screen, topmost first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HostUi {
+    pub nodes: Vec<Node>,
+}
+
+impl HostUi {
+    /// The whole AccessKit tree: a window labelled `title` holding the host UI. Nodes are
+    /// numbered in tree order, so ids stay put while the UI does. Focus is on the modal
+    /// node's focused button, or on the window.
+    pub fn tree_update(&self, title: &str) -> TreeUpdate {
+        let mut nodes = Vec::new();
+        let mut focus = ROOT_ID;
+        let mut next = ROOT_ID.0 + 1;
+        let children: Vec<NodeId> = self
+            .nodes
+            .iter()
+            .map(|node| node.encode(&mut next, &mut nodes, &mut focus))
+            .collect();
+        let mut window = accesskit::Node::new(accesskit::Role::Window);
+        window.set_label(title);
+        window.set_children(children);
+        nodes.push((ROOT_ID, window));
+        TreeUpdate {
+            nodes,
+            tree: Some(Tree::new(ROOT_ID)),
+            tree_id: TreeId::ROOT,
+            focus,
+        }
+    }
+
+    /// What the button numbered `id` by [`tree_update`](Self::tree_update) does.
+    pub fn action(&self, id: NodeId) -> Option<Action> {
+        let mut nodes = Vec::new();
+        for node in &self.nodes {
+            node.flatten(&mut nodes);
+        }
+        let index = id.0.checked_sub(ROOT_ID.0 + 1)?;
+        nodes.get(usize::try_from(index).ok()?)?.action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use accesskit::NodeId;
+
+    use super::{camera_prompt, console, error_overlay, Action, HostUi, Role, ROOT_ID};
+
+    #[test]
+    fn error_overlay_lists_recovery_keys() {
+        let overlay = error_overlay("Component init failed", "Reason: trap", "F5", false);
+        assert_eq!(overlay.role, Role::Alert);
+        assert_eq!(overlay.children[0].role, Role::Document);
+        let shortcuts: Vec<_> = overlay
+            .children
+            .iter()
+            .filter_map(|node| node.shortcut.as_deref())
+            .collect();
+        assert_eq!(shortcuts, ["R or F5", "C"]);
+
+        let restorable = error_overlay("Frame failed", "", "F5", true);
+        assert!(restorable
+            .children
+            .iter()
+            .any(|node| node.shortcut.as_deref() == Some("S")));
+    }
+
+    #[test]
+    fn tree_hangs_the_host_ui_from_the_window() {
+        let mut ui = HostUi::default();
+        let empty = ui.tree_update("Frontier Wasm Host");
+        assert_eq!(empty.nodes.len(), 1);
+        assert_eq!(empty.focus, ROOT_ID);
+        assert_eq!(empty.nodes[0].1.label(), Some("Frontier Wasm Host"));
+
+        ui.nodes.push(console(&["  hello ".to_string()]));
+        assert_eq!(ui.nodes[0].children[0].label, "hello");
+        ui.nodes.insert(0, camera_prompt("webcam"));
+        let update = ui.tree_update("Frontier Wasm Host");
+        let node = |id| {
+            &update
+                .nodes
+                .iter()
+                .find(|(node, _)| *node == NodeId(id))
+                .unwrap()
+                .1
+        };
+        assert_eq!(node(0).children(), [NodeId(1), NodeId(4)]);
+        assert_eq!(node(1).role(), accesskit::Role::AlertDialog);
+        assert!(node(1).is_modal());
+        assert_eq!(node(2).label(), Some("Allow"));
+        assert_eq!(node(2).keyboard_shortcut(), Some("Y"));
+        assert_eq!(node(5).value(), Some("hello"));
+        assert_eq!(update.nodes.len(), 6);
+
+        assert_eq!(ui.action(NodeId(3)), Some(Action::DenyCamera));
+        assert_eq!(ui.action(NodeId(1)), None);
+        assert_eq!(ui.action(NodeId(6)), None);
+        assert_eq!(ui.action(ROOT_ID), None);
+    }
+
+    #[test]
+    fn focus_follows_the_action_and_is_announced() {
+        let mut overlay = error_overlay("Frame failed", "", "F5", true);
+        assert_eq!(
+            overlay.actions(),
+            [
+                Action::Restart,
+                Action::RestoreLastGoodState,
+                Action::CopyError
+            ]
+        );
+
+        overlay.set_focus(Action::CopyError);
+        assert_eq!(
+            overlay.children.iter().filter(|node| node.focused).count(),
+            1
+        );
+        let update = HostUi {
+            nodes: vec![overlay],
+        }
+        .tree_update("Frontier Wasm Host");
+        assert_eq!(
+            update.focus,
+            NodeId(5),
+            "the alert, its report, then three buttons"
+        );
+    }
+}
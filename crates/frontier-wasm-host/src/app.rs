@@ -1,35 +1,195 @@
 use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, TryRecvError};
 use std::sync::Arc;
-use std::time::Instant;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{json, Value};
 use tracing::error;
 use winit::application::ApplicationHandler;
 use winit::dpi::{PhysicalPosition, PhysicalSize};
-use winit::event::{ElementState, KeyEvent, MouseButton, WindowEvent};
-use winit::event_loop::{ActiveEventLoop, ControlFlow};
-use winit::keyboard::{Key, PhysicalKey};
-use winit::window::{Window, WindowAttributes};
+use winit::event::{
+    ElementState, Ime, KeyEvent, MouseButton, MouseScrollDelta, Touch, TouchPhase, WindowEvent,
+};
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoopProxy};
+use winit::keyboard::{Key, KeyLocation, NamedKey, PhysicalKey};
+use winit::window::Window;
 
-use crate::graphics::{GraphicsState, OverlayContent};
+use crate::accessibility::{self, Action, HostUi, Node};
+use crate::camera::CameraAccess;
+use crate::clock::VirtualClock;
+use crate::control::{ControlCommand, ControlRequest};
+use crate::deeplink::DeepLink;
+use crate::export::{self, ExportFormat};
+use crate::frame_file;
+use crate::gamepad::{self, Gamepads, NavInput};
+use crate::geometry::Vec2;
+use crate::graphics::{GraphicsState, OverlayButton, OverlayContent, RenderOptions};
+use crate::host::{DrawCommand, FrameOutput};
+use crate::hotkeys::{HostAction, HotkeyDispatcher, KeyRoute, Keybinds};
+use crate::identity::DataDirs;
+use crate::idle::{self, IdleTracker};
+use crate::keys;
+use crate::launch::LaunchConfig;
+use crate::limits::ResourceLimits;
+use crate::logging::FRAME_TARGET;
+use crate::memory::{self, MemoryMonitor, MemoryPressure};
+use crate::metrics::Metrics;
 use crate::model::{
-    KeyEvent as GuestKeyEvent, LogicalSize, Modifiers, PointerButtons, PointerEvent, PointerKind,
+    GestureEvent, GesturePhase, KeyEvent as GuestKeyEvent, LogicalSize, Modifiers, PointerEvent,
+    PointerSample,
+};
+use crate::pacing::{self, FramePacing};
+use crate::panes::{self, PanePress, Panes};
+use crate::pointers::{PendingMoves, PointerSource, PointerTracker};
+use crate::power::{self, PowerSave, PowerState};
+use crate::runtime::{CallResult, CloseResponse, ComponentRuntime, ComponentSource, FrameResult};
+use crate::scroll::{ScrollEvent, ScrollInput, WHEEL_LINE};
+use crate::stats::FrameStats;
+use crate::tasks;
+use crate::transition::{self, TransitionKind};
+use crate::window::{
+    self as host_window, DisplayInfo, WindowOptions, WindowRequest, MIN_WINDOW_SIZE,
 };
-use crate::runtime::{CallResult, ComponentRuntime, ComponentSource, FrameResult};
+
+/// How often the event loop checks on a component still loading in the background.
+const LOADING_POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+/// How often a running guest's state is snapshotted for "restore last good state".
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Trailing draw commands of the last frame listed in the error overlay.
+const OVERLAY_DRAW_COMMANDS: usize = 8;
+/// Longest draw command description shown in the overlay, in characters.
+const OVERLAY_COMMAND_CHARS: usize = 120;
+
+/// Zoom levels the zoom hotkeys step through, as in a browser.
+const ZOOM_LEVELS: [f32; 13] = [
+    0.5, 0.67, 0.75, 0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0,
+];
+
+/// A loaded component, with the other panes when its manifest lays out several.
+type Loaded = (ComponentRuntime, Option<Panes>);
+
+/// What other threads hand the event loop.
+#[derive(Debug)]
+pub enum AppEvent {
+    /// A command from the control socket or a later launch.
+    Control(ControlRequest),
+    /// The screen reader wants the host UI tree or acts on it.
+    Accessibility(accesskit_winit::Event),
+}
+
+impl From<ControlRequest> for AppEvent {
+    fn from(request: ControlRequest) -> Self {
+        Self::Control(request)
+    }
+}
+
+impl From<accesskit_winit::Event> for AppEvent {
+    fn from(event: accesskit_winit::Event) -> Self {
+        Self::Accessibility(event)
+    }
+}
 
 pub struct App {
     component: ComponentSource,
+    launch: LaunchConfig,
+    window_options: WindowOptions,
+    render_options: RenderOptions,
+    hotkeys: HotkeyDispatcher,
     window: Option<Arc<Window>>,
+    /// The component, or with a manifest layout the focused pane's.
     runtime: Option<ComponentRuntime>,
+    /// The other panes when the manifest lays out several components.
+    panes: Option<Panes>,
+    /// Compilation and instantiation running on a background thread; the window shows a
+    /// loading screen until it reports back.
+    loading: Option<mpsc::Receiver<Result<Loaded>>>,
     graphics: Option<GraphicsState>,
     logical_size: LogicalSize,
     scale_factor: f32,
-    pointer_buttons: PointerButtons,
+    /// Host zoom on top of the display's scale factor; the guest sees the product as its
+    /// scale factor and a correspondingly smaller logical size.
+    zoom: f32,
+    pointers: PointerTracker,
+    /// Where the mouse cursor was last seen; touchpad gestures are anchored here.
+    cursor_position: PhysicalPosition<f64>,
     modifiers: Modifiers,
-    last_frame_instant: Option<Instant>,
+    clock: VirtualClock,
+    limits: ResourceLimits,
+    /// How camera requests are answered; updated once the user answers the prompt so
+    /// restarts keep the answer.
+    camera_access: CameraAccess,
+    /// Where frames the guest exports with `export-frame` are written.
+    export_dir: PathBuf,
+    /// `--dump-frames`: every guest frame is also saved here as a `.fframe` file.
+    dump_dir: Option<PathBuf>,
+    /// Frames written to `dump_dir` so far; numbers the files.
+    dumped_frames: u64,
+    /// Last rendered guest frame, re-presented while guest time is paused.
+    last_frame: Option<FrameOutput>,
     needs_redraw: bool,
+    /// A `RedrawRequested` is on its way; coalesced moves wait for it.
+    redraw_pending: bool,
+    coalesce_pointer_moves: bool,
+    pending_moves: PendingMoves,
+    /// The window has zero area; frames and `resize` calls are held until it is restored.
+    minimized: bool,
+    display: Option<DisplayInfo>,
+    window_position: Option<PhysicalPosition<i32>>,
+    power: Option<PowerState>,
+    /// When `power` was last read; `None` until the first poll.
+    power_polled_at: Option<Instant>,
+    power_save: PowerSave,
+    memory: MemoryMonitor,
+    /// When memory pressure was last checked; `None` until the first poll.
+    memory_polled_at: Option<Instant>,
+    /// Last user input and whether the guest was told the user is idle.
+    idle: IdleTracker,
+    /// `--open`: a deep link handed to the guest once it has initialised.
+    deep_link: Option<String>,
+    /// `--allow-navigation`: guests may replace themselves with `navigate`.
+    allow_navigation: bool,
+    /// `--transition`: how the old frame gives way to the new one on restart.
+    transition: TransitionKind,
+    transition_duration: Duration,
+    /// `--metrics`: frame, guest call and restart figures are recorded here.
+    metrics: Option<Metrics>,
+    /// When the last guest frame ran, for the `--power-save` frame cap.
+    last_frame_at: Option<Instant>,
+    /// Missed refreshes and the 1% low frame time, for `frame-missed` and the stats
+    /// overlay.
+    pacing: FramePacing,
+    /// Zero point for pointer sample timestamps.
+    input_epoch: Instant,
     overlay: Option<OverlayState>,
-    cursor_position: PhysicalPosition<f64>,
+    /// Snapshot taken after a recent successful frame, offered on the error overlay.
+    last_good_state: Option<Vec<u8>>,
+    last_checkpoint_at: Option<Instant>,
+    /// Cleared when the guest cannot be snapshotted, so it is not asked every interval.
+    checkpoints_enabled: bool,
+    exit_requested: bool,
+    stats: FrameStats,
+    show_stats: bool,
+    show_console: bool,
+    /// Delivers the accessibility adapter's requests; without it no adapter is created.
+    event_proxy: Option<EventLoopProxy<AppEvent>>,
+    /// Publishes the host UI to screen readers once the window exists.
+    accessibility: Option<accesskit_winit::Adapter>,
+    /// Host UI last sent to the adapter.
+    published_ui: Option<HostUi>,
+    /// Button the user moved focus to in the modal host UI; its first button when unset
+    /// or no longer offered.
+    ui_focus: Option<Action>,
+    /// Opened only while modal host UI is up, so the user can answer it from a controller.
+    gamepads: Gamepads,
+    gamepads_polled_at: Option<Instant>,
+    /// Opened on first copy and kept alive: on X11 the copied text is served by this
+    /// process and disappears when the clipboard handle is dropped.
+    clipboard: Option<arboard::Clipboard>,
 }
 
 #[derive(Clone, Debug)]
@@ -37,6 +197,16 @@ struct OverlayState {
     title: String,
     body: String,
     footer: String,
+    scroll: usize,
+    /// Result of the last action taken on the overlay, shown before the footer.
+    notice: Option<String>,
+}
+
+enum Gesture {
+    Pinch(GestureEvent),
+    Rotation(GestureEvent),
+    /// Logical cursor position at the time of the tap.
+    DoubleTap(Vec2),
 }
 
 impl OverlayState {
@@ -44,255 +214,2181 @@ impl OverlayState {
         OverlayContent {
             title: self.title.clone(),
             body: self.body.lines().map(|s| s.to_string()).collect(),
-            footer: self.footer.clone(),
+            footer: match &self.notice {
+                Some(notice) => format!("{notice} {}", self.footer),
+                None => self.footer.clone(),
+            },
+            scroll: self.scroll,
+            buttons: Vec::new(),
         }
     }
 }
 
 impl App {
-    pub fn new(component: ComponentSource) -> Self {
+    pub fn new(
+        component: ComponentSource,
+        launch: LaunchConfig,
+        window_options: WindowOptions,
+    ) -> Self {
         Self {
             component,
+            launch,
+            window_options,
+            render_options: RenderOptions::default(),
+            hotkeys: HotkeyDispatcher::default(),
             window: None,
             runtime: None,
+            panes: None,
+            loading: None,
             graphics: None,
             logical_size: LogicalSize::default(),
             scale_factor: 1.0,
-            pointer_buttons: PointerButtons::default(),
+            zoom: 1.0,
+            pointers: PointerTracker::default(),
+            cursor_position: PhysicalPosition::new(0.0, 0.0),
             modifiers: Modifiers::default(),
-            last_frame_instant: None,
+            clock: VirtualClock::default(),
+            limits: ResourceLimits::default(),
+            camera_access: CameraAccess::default(),
+            export_dir: PathBuf::from("."),
+            dump_dir: None,
+            dumped_frames: 0,
+            last_frame: None,
             needs_redraw: false,
+            redraw_pending: false,
+            coalesce_pointer_moves: false,
+            pending_moves: PendingMoves::default(),
+            minimized: false,
+            display: None,
+            window_position: None,
+            power: None,
+            power_polled_at: None,
+            power_save: PowerSave::default(),
+            memory: MemoryMonitor::default(),
+            memory_polled_at: None,
+            idle: IdleTracker::new(Some(idle::DEFAULT_TIMEOUT), Instant::now()),
+            deep_link: None,
+            allow_navigation: false,
+            transition: TransitionKind::default(),
+            transition_duration: transition::DEFAULT_DURATION,
+            metrics: None,
+            last_frame_at: None,
+            pacing: FramePacing::default(),
+            input_epoch: Instant::now(),
             overlay: None,
-            cursor_position: PhysicalPosition::new(0.0, 0.0),
+            last_good_state: None,
+            last_checkpoint_at: None,
+            checkpoints_enabled: true,
+            exit_requested: false,
+            stats: FrameStats::default(),
+            show_stats: false,
+            show_console: false,
+            event_proxy: None,
+            accessibility: None,
+            published_ui: None,
+            ui_focus: None,
+            gamepads: Gamepads::default(),
+            gamepads_polled_at: None,
+            clipboard: None,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: VirtualClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_render_options(mut self, options: RenderOptions) -> Self {
+        self.render_options = options;
+        self
+    }
+
+    pub fn with_keybinds(mut self, keybinds: Keybinds) -> Self {
+        self.hotkeys = HotkeyDispatcher::new(keybinds);
+        self
+    }
+
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_camera_access(mut self, access: CameraAccess) -> Self {
+        self.camera_access = access;
+        self
+    }
+
+    pub fn with_export_dir(mut self, dir: PathBuf) -> Self {
+        self.export_dir = dir;
+        self
+    }
+
+    pub fn with_power_save(mut self, power_save: PowerSave) -> Self {
+        self.power_save = power_save;
+        self
+    }
+
+    /// Bytes of guest assets and host caches above which the guest is asked to trim.
+    pub fn with_memory_budget(mut self, budget: Option<usize>) -> Self {
+        self.memory = MemoryMonitor::new(budget);
+        self
+    }
+
+    /// How long without input before `idle-changed(true)`; `None` never sends it.
+    pub fn with_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.idle = IdleTracker::new(timeout, self.idle.last_input());
+        self
+    }
+
+    pub fn with_deep_link(mut self, url: String) -> Self {
+        self.deep_link = Some(url);
+        self
+    }
+
+    pub fn with_navigation(mut self, allowed: bool) -> Self {
+        self.allow_navigation = allowed;
+        self
+    }
+
+    pub fn with_transition(mut self, kind: TransitionKind, duration: Duration) -> Self {
+        self.transition = kind;
+        self.transition_duration = duration;
+        self
+    }
+
+    pub fn with_event_proxy(mut self, proxy: EventLoopProxy<AppEvent>) -> Self {
+        self.event_proxy = Some(proxy);
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn with_frame_dump(mut self, dir: PathBuf) -> Self {
+        self.dump_dir = Some(dir);
+        self
+    }
+
+    /// Hold pointer moves back and deliver at most one per pointer per frame, at the latest
+    /// position, instead of one guest call per raw `CursorMoved`.
+    pub fn with_pointer_coalescing(mut self, enabled: bool) -> Self {
+        self.coalesce_pointer_moves = enabled;
+        self
+    }
+
+    fn request_redraw(&mut self) {
+        if let Some(window) = &self.window {
+            window.request_redraw();
+            self.redraw_pending = true;
+        }
+    }
+
+    /// Recreate the window surface dropped by `suspended` and pick frames back up.
+    fn resume_graphics(&mut self, window: Arc<Window>) {
+        let Some(graphics) = self.graphics.as_mut() else {
+            return;
+        };
+        if !graphics.is_suspended() {
+            return;
+        }
+        if let Err(err) = graphics.resume(window.clone()) {
+            self.set_overlay_error("Graphics initialisation failed", &err);
+            return;
+        }
+        tracing::info!("resumed; window surface recreated");
+        self.clock.resync();
+        self.handle_window_resize(window.inner_size());
+        self.request_redraw();
+    }
+
+    /// Compile and instantiate the component off the event loop thread so the window can
+    /// show a loading screen meanwhile; `poll_loading` picks up the result.
+    fn start_loading(&mut self) {
+        if self.runtime.is_some() || self.loading.is_some() {
+            return;
+        }
+        self.pacing.interrupt();
+        let (sender, receiver) = mpsc::channel();
+        let component = self.component.clone();
+        let launch = self.launch.clone();
+        let spawned = thread::Builder::new()
+            .name("frontier-load".into())
+            .spawn(move || {
+                let _ = sender.send(panes::load_component(component, launch));
+            });
+        match spawned {
+            Ok(_) => {
+                self.loading = Some(receiver);
+                self.request_redraw();
+            }
+            Err(err) => self.set_overlay_error("Runtime initialisation failed", &err.into()),
+        }
+    }
+
+    /// Pick up a finished load; returns when to check again while it is still running.
+    fn poll_loading(&mut self) -> Option<Instant> {
+        let receiver = self.loading.as_ref()?;
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return Some(Instant::now() + LOADING_POLL_INTERVAL),
+            Err(TryRecvError::Disconnected) => Err(anyhow!("component loader thread panicked")),
+        };
+        self.loading = None;
+        self.finish_loading(result);
+        None
+    }
+
+    /// Re-read the power state when it is due and tell the guest if it changed; returns
+    /// when to read it next.
+    fn poll_power(&mut self) -> Option<Instant> {
+        if !power::SUPPORTED {
+            return None;
+        }
+        let now = Instant::now();
+        if let Some(polled_at) = self.power_polled_at {
+            let due = polled_at + power::POLL_INTERVAL;
+            if now < due {
+                return Some(due);
+            }
+        }
+        self.power_polled_at = Some(now);
+        let state = PowerState::read();
+        if state != self.power {
+            tracing::info!(?state, "power state changed");
+            self.power = state.clone();
+            self.notify_power_state();
+        }
+        Some(now + power::POLL_INTERVAL)
+    }
+
+    fn notify_power_state(&mut self) {
+        let Some(runtime) = self.runtime.as_mut() else {
+            return;
+        };
+        runtime.set_power_state(self.power.clone());
+        let Some(state) = &self.power else {
+            return;
+        };
+        if self.overlay.is_some() {
+            return;
+        }
+        match runtime.call_power_state_changed(state) {
+            Ok(result) => self.handle_call_result(result),
+            Err(err) => self.set_overlay_error("Power state change failed", &err),
+        }
+    }
+
+    /// Check memory pressure when it is due; when it rose, empty the host's caches and ask
+    /// the guest to trim. Returns when to check next.
+    fn poll_memory(&mut self) -> Option<Instant> {
+        if !self.memory.enabled() {
+            return None;
+        }
+        let now = Instant::now();
+        if let Some(polled_at) = self.memory_polled_at {
+            let due = polled_at + memory::POLL_INTERVAL;
+            if now < due {
+                return Some(due);
+            }
+        }
+        self.memory_polled_at = Some(now);
+        let used = self
+            .runtime
+            .as_ref()
+            .map_or(0, ComponentRuntime::asset_bytes)
+            + self.graphics.as_ref().map_or(0, GraphicsState::cache_bytes);
+        if let Some(level) = self.memory.update(MemoryPressure::read(), used) {
+            tracing::info!(?level, used, "memory pressure rose");
+            if let Some(graphics) = self.graphics.as_mut() {
+                graphics.trim_caches(level);
+            }
+            self.notify_trim_memory(level);
+            if level == MemoryPressure::Critical {
+                self.evict_assets();
+            }
+        }
+        Some(now + memory::POLL_INTERVAL)
+    }
+
+    /// After the guest had its chance to trim, drop what it kept past the budget.
+    fn evict_assets(&mut self) {
+        if self.overlay.is_some() {
+            return;
+        }
+        let cache_bytes = self.graphics.as_ref().map_or(0, GraphicsState::cache_bytes);
+        let target = self.memory.eviction_target(cache_bytes);
+        let Some(runtime) = self.runtime.as_mut() else {
+            return;
+        };
+        match runtime.evict_assets(target) {
+            Ok(result) => self.handle_call_result(result),
+            Err(err) => self.set_overlay_error("Asset eviction failed", &err),
+        }
+    }
+
+    fn notify_trim_memory(&mut self, level: MemoryPressure) {
+        if self.overlay.is_some() {
+            return;
+        }
+        let Some(runtime) = self.runtime.as_mut() else {
+            return;
+        };
+        match runtime.call_trim_memory(level) {
+            Ok(result) => self.handle_call_result(result),
+            Err(err) => self.set_overlay_error("Memory trim failed", &err),
+        }
+    }
+
+    /// Tell the guest once the user has been away for the idle timeout; returns when to
+    /// check again.
+    fn poll_idle(&mut self) -> Option<Instant> {
+        let (became_idle, next) = self.idle.poll(Instant::now());
+        if became_idle {
+            tracing::debug!("user idle");
+            self.notify_idle();
+        }
+        next
+    }
+
+    /// Hand the guest what its background tasks and workers sent; returns when to check
+    /// again while any are running.
+    fn poll_background(&mut self) -> Option<Instant> {
+        if self.overlay.is_some() {
+            return None;
+        }
+        let mut pending = false;
+        if let Some(panes) = self.panes.as_mut() {
+            pending = panes.background_pending();
+            if pending && panes.poll_background() {
+                self.request_redraw();
+            }
+        }
+        let runtime = self.runtime.as_mut()?;
+        if runtime.tasks_in_flight() == 0 && runtime.workers_running() == 0 {
+            return pending.then(|| Instant::now() + tasks::POLL_INTERVAL);
+        }
+        let delivered = runtime.poll_tasks().and_then(|mut result| {
+            result.merge(runtime.poll_workers()?);
+            Ok(result)
+        });
+        match delivered {
+            Ok(result) => self.handle_call_result(result),
+            Err(err) => {
+                self.set_overlay_error("Background work notification failed", &err);
+                return None;
+            }
+        }
+        Some(Instant::now() + tasks::POLL_INTERVAL)
+    }
+
+    fn record_input(&mut self) {
+        let now = Instant::now();
+        if let Some(runtime) = self.runtime.as_mut() {
+            runtime.set_last_input(now);
+        }
+        if self.idle.record_input(now) {
+            tracing::debug!("user active again");
+            self.notify_idle();
+        }
+    }
+
+    fn notify_idle(&mut self) {
+        if self.overlay.is_some() {
+            return;
+        }
+        if let Some(runtime) = self.runtime.as_mut() {
+            match runtime.call_idle_changed(self.idle.is_idle()) {
+                Ok(result) => self.handle_call_result(result),
+                Err(err) => self.set_overlay_error("Idle notification failed", &err),
+            }
+        }
+    }
+
+    /// When the next guest frame may run under the `--power-save` cap, if that is later
+    /// than now.
+    fn frame_due(&self) -> Option<Instant> {
+        let interval = self.power_save.frame_interval(self.power.as_ref())?;
+        let due = self.last_frame_at? + interval;
+        (due > Instant::now()).then_some(due)
+    }
+
+    /// How often frames can be shown: the monitor's refresh period, or the `--power-save`
+    /// cap when that is longer.
+    fn frame_period(&self) -> Duration {
+        let refresh = self
+            .display
+            .as_ref()
+            .and_then(|display| display.refresh_rate_millihertz)
+            .filter(|&millihertz| millihertz > 0)
+            .map_or(pacing::DEFAULT_PERIOD, |millihertz| {
+                Duration::from_secs_f64(1000.0 / f64::from(millihertz))
+            });
+        self.power_save
+            .frame_interval(self.power.as_ref())
+            .map_or(refresh, |cap| cap.max(refresh))
+    }
+
+    fn finish_loading(&mut self, result: Result<Loaded>) {
+        let (mut runtime, mut panes) = match result {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                self.set_overlay_error("Runtime initialisation failed", &err);
+                return;
+            }
+        };
+        self.configure_runtime(&mut runtime, panes.is_none());
+        if let Some(panes) = panes.as_mut() {
+            for parked in panes.parked_runtimes() {
+                self.configure_runtime(parked, false);
+            }
+            panes.init(self.logical_size);
+        }
+        self.panes = panes;
+        // Normally done behind the loading screen; this catches loads that finished first.
+        self.warm_up_graphics();
+        self.last_good_state = None;
+        self.last_checkpoint_at = None;
+        self.checkpoints_enabled = true;
+        let init = runtime.call_init(self.guest_size());
+        self.runtime = Some(runtime);
+        match init {
+            Ok(result) => {
+                self.overlay = None;
+                self.start_transition();
+                self.handle_call_result(result);
+                if self.minimized {
+                    self.notify_minimized();
+                }
+                if self.idle.is_idle() {
+                    self.notify_idle();
+                }
+                if let Some(url) = self.deep_link.take() {
+                    self.open_deep_link(&url);
+                }
+            }
+            Err(err) => self.set_overlay_error("Component init failed", &err),
+        }
+        self.request_redraw();
+    }
+
+    /// Apply the window's settings to a freshly loaded runtime. Panes cannot navigate, as
+    /// that would replace one pane's component with another manifest's whole layout.
+    fn configure_runtime(&self, runtime: &mut ComponentRuntime, navigation: bool) {
+        runtime.set_limits(self.limits);
+        runtime.set_camera_access(self.camera_access);
+        runtime.set_navigation_allowed(self.allow_navigation && navigation);
+        runtime.set_display(self.display.clone());
+        runtime.set_gpu_info(self.graphics.as_ref().map(GraphicsState::gpu_info));
+        match DataDirs::platform() {
+            Ok(dirs) => runtime.set_data_dirs(Some(dirs)),
+            Err(err) => tracing::warn!(error = %err, "guest storage unavailable"),
+        }
+        runtime.set_power_state(self.power.clone());
+        runtime.set_last_input(self.idle.last_input());
+        runtime.set_window_position(self.window_position);
+        runtime.set_metrics(self.metrics.clone());
+    }
+
+    /// The size the component holding input draws at: the window's, or the focused pane's.
+    fn guest_size(&self) -> LogicalSize {
+        self.panes
+            .as_ref()
+            .map_or(self.logical_size, Panes::focused_size)
+    }
+
+    /// Make `name` the focused pane: it takes over the app's runtime, input and error
+    /// overlay, and the pane focused so far is parked.
+    fn focus_pane(&mut self, name: &str) {
+        // Moves waiting to be coalesced are relative to the pane losing focus.
+        self.flush_pointer_moves();
+        let (Some(panes), Some(current)) = (self.panes.as_mut(), self.runtime.take()) else {
+            return;
+        };
+        let (runtime, error) = panes.focus(name, current);
+        let resized = panes.take_focused_resize();
+        self.runtime = Some(runtime);
+        self.last_good_state = None;
+        self.last_checkpoint_at = None;
+        self.request_redraw();
+        if let Some(error) = error {
+            self.set_overlay_error("Component frame failed", &anyhow!(error));
+            return;
+        }
+        if let (Some(size), Some(runtime)) = (resized, self.runtime.as_mut()) {
+            match runtime.call_resize(size) {
+                Ok(result) => self.handle_call_result(result),
+                Err(err) => self.set_overlay_error("Component resize failed", &err),
+            }
+        }
+    }
+
+    fn ensure_graphics(&mut self, window: Arc<Window>) -> Result<()> {
+        if self.graphics.is_some() {
+            return Ok(());
+        }
+        let graphics = GraphicsState::new(
+            window.clone(),
+            self.effective_scale(),
+            self.logical_size,
+            self.render_options,
+        )?;
+        self.graphics = Some(graphics);
+        self.window = Some(window);
+        Ok(())
+    }
+
+    fn handle_call_result(&mut self, result: CallResult) {
+        self.apply_window_requests(&result.window_requests);
+        self.exit_requested |= result.quit_requested;
+        if result.requested_redraw {
+            self.request_redraw();
+        }
+        if let Some(frame) = result.frame {
+            if let Err(err) = self.present_frame(frame) {
+                self.set_overlay_error("Render failed", &err);
+            }
+        }
+        if let Some(path) = result.navigate_to {
+            self.navigate(path);
+        }
+    }
+
+    /// Tear down the running component and load `path` in its place, in the same window.
+    /// Launch arguments were meant for the first component and are not passed on.
+    fn navigate(&mut self, path: PathBuf) {
+        self.runtime = None;
+        self.panes = None;
+        self.component = ComponentSource::from_path(path);
+        self.launch.args.clear();
+        self.start_loading();
+    }
+
+    fn handle_frame_result(&mut self, frame: FrameResult) -> Result<()> {
+        self.apply_window_requests(&frame.window_requests);
+        self.exit_requested |= frame.quit_requested;
+        if frame.requested_redraw {
+            // Under the power-save cap the frame waits in `about_to_wait` until it is due.
+            if self.frame_due().is_some() {
+                self.needs_redraw = true;
+            } else {
+                self.request_redraw();
+            }
+        }
+        self.export_frame(&frame.frame, &frame.export_requests);
+        self.present_frame(frame.frame)?;
+        if let Some(path) = frame.navigate_to {
+            self.navigate(path);
+        }
+        Ok(())
+    }
+
+    /// Render what the guest drew, either in `frame` or in an event handler, and keep it
+    /// as the window's current contents.
+    fn present_frame(&mut self, frame: FrameOutput) -> Result<()> {
+        let frame = match self.panes.as_mut() {
+            Some(panes) => panes.compose(&frame),
+            None => frame,
+        };
+        let overlay_content = self.overlay_content();
+        let stats = self.stats_lines();
+        if let Some(graphics) = self.graphics.as_mut() {
+            graphics.render(Some(&frame), overlay_content.as_ref(), stats.as_deref())?;
+        }
+        self.dump_frame(&frame);
+        self.last_frame = Some(frame);
+        Ok(())
+    }
+
+    /// Write `frame` into the export directory once per requested format. Failures are
+    /// logged rather than stopping the guest.
+    fn export_frame(&self, frame: &FrameOutput, formats: &[ExportFormat]) {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        for format in formats {
+            let path = self
+                .export_dir
+                .join(format!("frame-{stamp}.{}", format.extension()));
+            match export::write_frame(frame, self.guest_size(), &path) {
+                Ok(()) => tracing::info!(path = %path.display(), "exported frame"),
+                Err(err) => tracing::warn!(error = %format!("{err:#}"), "frame export failed"),
+            }
+        }
+    }
+
+    fn dump_frame(&mut self, frame: &FrameOutput) {
+        let Some(dir) = &self.dump_dir else {
+            return;
+        };
+        let path = dir.join(format!(
+            "frame-{:06}.{}",
+            self.dumped_frames,
+            frame_file::EXTENSION
+        ));
+        self.dumped_frames += 1;
+        if let Err(err) = frame_file::write(&path, frame, self.logical_size) {
+            tracing::warn!(error = %format!("{err:#}"), "frame dump failed");
+        }
+    }
+
+    fn apply_window_requests(&mut self, requests: &[WindowRequest]) {
+        let Some(window) = self.window.clone() else {
+            return;
+        };
+        for request in requests {
+            match *request {
+                WindowRequest::SetLevel(level) => {
+                    self.window_options.level = level;
+                    host_window::set_window_level(&window, level);
+                }
+                WindowRequest::SetOpacity(opacity) => {
+                    self.window_options.opacity = opacity;
+                    window.set_transparent(opacity < 1.0);
+                    if let Some(graphics) = self.graphics.as_mut() {
+                        graphics.set_opacity(opacity);
+                    }
+                    self.request_redraw();
+                }
+                WindowRequest::SetClickThrough(enabled) => {
+                    self.window_options.click_through = enabled;
+                    host_window::set_click_through(&window, enabled);
+                }
+                WindowRequest::SetPosition(position) => window.set_outer_position(position),
+                WindowRequest::SetBackground(color) => {
+                    self.window_options.background = color;
+                    if let Some(graphics) = self.graphics.as_mut() {
+                        graphics.set_background(color);
+                    }
+                    self.request_redraw();
+                }
+            }
+        }
+    }
+
+    /// Ask the guest whether the window may close. A guest that is not running, or that
+    /// fails while answering, never blocks the close.
+    fn close_allowed(&mut self) -> bool {
+        if self.overlay.is_some() {
+            return true;
+        }
+        let Some(runtime) = self.runtime.as_mut() else {
+            return true;
+        };
+        match runtime.call_close_requested() {
+            Ok((response, result)) => {
+                self.handle_call_result(result);
+                response == CloseResponse::Allow
+            }
+            Err(err) => {
+                error!(error = %err, "close-requested failed; closing anyway");
+                true
+            }
+        }
+    }
+
+    fn stats_lines(&self) -> Option<Vec<String>> {
+        let status = self.clock.status();
+        if !self.show_stats && !self.show_console && status.is_none() {
+            return None;
+        }
+        let mut lines = Vec::new();
+        lines.extend(status);
+        if self.show_stats {
+            lines.extend(self.stats.lines());
+        }
+        if self.show_console {
+            let logs = self
+                .runtime
+                .as_ref()
+                .map(|runtime| runtime.recent_logs())
+                .unwrap_or_default();
+            lines.push("console:".to_string());
+            lines.extend(logs.iter().map(|log| format!("  {}", log.trim())));
+        }
+        Some(lines)
+    }
+
+    /// Semantics of the host-drawn UI, independent of the guest's own.
+    fn host_ui(&self) -> HostUi {
+        let mut ui = HostUi::default();
+        if let Some(overlay) = &self.overlay {
+            ui.nodes.push(accessibility::error_overlay(
+                &overlay.title,
+                &overlay.body,
+                &self.hotkeys.keybinds().restart.to_string(),
+                self.last_good_state.is_some(),
+            ));
+        }
+        if let Some(runtime) = &self.runtime {
+            ui.nodes.extend(
+                runtime
+                    .camera_prompt()
+                    .map(|name| accessibility::camera_prompt(&name)),
+            );
+            if self.show_console {
+                ui.nodes
+                    .push(accessibility::console(&runtime.recent_logs()));
+            }
+        }
+        if let Some(node) = ui.nodes.iter_mut().find(|node| node.is_modal()) {
+            if let Some(action) = self.focused_action(node) {
+                node.set_focus(action);
+            }
+        }
+        ui
+    }
+
+    /// The topmost modal host UI, with focus marked.
+    fn modal_ui(&self) -> Option<Node> {
+        self.host_ui().nodes.into_iter().find(Node::is_modal)
+    }
+
+    fn focused_action(&self, node: &Node) -> Option<Action> {
+        let actions = node.actions();
+        self.ui_focus
+            .filter(|action| actions.contains(action))
+            .or(actions.first().copied())
+    }
+
+    /// What to draw over the guest: the error overlay, or the camera prompt when that is
+    /// the only thing waiting on the user, with the modal UI's buttons.
+    fn overlay_content(&self) -> Option<OverlayContent> {
+        let buttons = self
+            .modal_ui()
+            .map(|node| {
+                node.children
+                    .iter()
+                    .filter(|child| child.action.is_some())
+                    .map(|child| OverlayButton {
+                        label: child.label.clone(),
+                        focused: child.focused,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(overlay) = &self.overlay {
+            return Some(OverlayContent {
+                buttons,
+                ..overlay.to_content()
+            });
+        }
+        let camera = self.runtime.as_ref()?.camera_prompt()?;
+        Some(OverlayContent {
+            title: "Camera access".to_string(),
+            body: vec![format!(
+                "Allow this component to use the camera \"{camera}\"?"
+            )],
+            footer: "Press Y to allow or N to deny, or pick a button with Tab and Enter or \
+                     the D-pad and A."
+                .to_string(),
+            scroll: 0,
+            buttons,
+        })
+    }
+
+    /// Move focus `step` buttons along the modal host UI, wrapping at either end.
+    fn move_ui_focus(&mut self, step: isize) {
+        let Some(node) = self.modal_ui() else {
+            return;
+        };
+        let actions = node.actions();
+        let Some(current) = self.focused_action(&node) else {
+            return;
+        };
+        let index = actions
+            .iter()
+            .position(|&action| action == current)
+            .unwrap_or(0);
+        let next = (index as isize + step).rem_euclid(actions.len() as isize) as usize;
+        self.ui_focus = Some(actions[next]);
+        self.request_redraw();
+    }
+
+    fn activate_ui_focus(&mut self) {
+        let action = self.modal_ui().and_then(|node| self.focused_action(&node));
+        if let Some(action) = action {
+            self.perform_ui_action(action);
+        }
+    }
+
+    fn perform_ui_action(&mut self, action: Action) {
+        match action {
+            Action::Restart => self.schedule_restart(),
+            Action::RestoreLastGoodState => self.restore_last_good_state(),
+            Action::CopyError => {
+                self.copy_overlay_error();
+                self.request_redraw();
+            }
+            Action::AllowCamera => self.answer_camera(true),
+            Action::DenyCamera => self.answer_camera(false),
+        }
+    }
+
+    /// Read gamepads while modal host UI is up and act on their navigation; returns when
+    /// to read them next.
+    fn poll_gamepads(&mut self) -> Option<Instant> {
+        if !gamepad::SUPPORTED {
+            return None;
+        }
+        if self.modal_ui().is_none() {
+            self.gamepads.release();
+            self.gamepads_polled_at = None;
+            return None;
+        }
+        let now = Instant::now();
+        if let Some(polled_at) = self.gamepads_polled_at {
+            let due = polled_at + gamepad::POLL_INTERVAL;
+            if now < due {
+                return Some(due);
+            }
+        }
+        self.gamepads_polled_at = Some(now);
+        for input in self.gamepads.poll(now) {
+            self.record_input();
+            self.handle_nav_input(input);
+        }
+        Some(now + gamepad::POLL_INTERVAL)
+    }
+
+    fn handle_nav_input(&mut self, input: NavInput) {
+        match input {
+            NavInput::Previous => self.move_ui_focus(-1),
+            NavInput::Next => self.move_ui_focus(1),
+            NavInput::Activate => self.activate_ui_focus(),
+            NavInput::Back if self.overlay.is_none() => self.answer_camera(false),
+            NavInput::Back => {}
+            NavInput::Up | NavInput::Down => {
+                if let Some(overlay) = self.overlay.as_mut() {
+                    overlay.scroll = if input == NavInput::Up {
+                        overlay.scroll.saturating_sub(1)
+                    } else {
+                        overlay.scroll + 1
+                    };
+                    let max = self
+                        .graphics
+                        .as_ref()
+                        .map_or(0, |graphics| graphics.overlay_scroll().max);
+                    overlay.scroll = overlay.scroll.min(max);
+                    self.request_redraw();
+                }
+            }
+        }
+    }
+
+    /// Send the host UI to the screen reader when it changed since the last update.
+    fn sync_accessibility(&mut self) {
+        if self.accessibility.is_none() {
+            return;
+        }
+        let ui = self.host_ui();
+        if self.published_ui.as_ref() == Some(&ui) {
+            return;
+        }
+        if let Some(adapter) = self.accessibility.as_mut() {
+            adapter.update_if_active(|| ui.tree_update(&self.window_options.title));
+        }
+        self.published_ui = Some(ui);
+    }
+
+    fn handle_accessibility_event(&mut self, event: accesskit_winit::WindowEvent) {
+        match event {
+            accesskit_winit::WindowEvent::InitialTreeRequested => {
+                self.published_ui = None;
+                self.sync_accessibility();
+            }
+            accesskit_winit::WindowEvent::ActionRequested(request) => {
+                let Some(action) = self.host_ui().action(request.target_node) else {
+                    return;
+                };
+                match request.action {
+                    accesskit::Action::Click => self.perform_ui_action(action),
+                    accesskit::Action::Focus => {
+                        self.ui_focus = Some(action);
+                        self.request_redraw();
+                    }
+                    _ => {}
+                }
+            }
+            accesskit_winit::WindowEvent::AccessibilityDeactivated => {}
+        }
+    }
+
+    fn render_overlay_only(&mut self) -> Result<()> {
+        let stats = self.stats_lines();
+        let overlay_content = self.overlay_content();
+        if let Some(graphics) = self.graphics.as_mut() {
+            graphics.render(None, overlay_content.as_ref(), stats.as_deref())?;
+        }
+        Ok(())
+    }
+
+    fn render_loading_screen(&mut self) -> Result<()> {
+        let content = OverlayContent {
+            title: "Loading component…".to_string(),
+            body: vec![self.component.label()],
+            footer: String::new(),
+            scroll: 0,
+            buttons: Vec::new(),
+        };
+        let stats = self.stats_lines();
+        if let Some(graphics) = self.graphics.as_mut() {
+            graphics.render(None, Some(&content), stats.as_deref())?;
+        }
+        self.warm_up_graphics();
+        Ok(())
+    }
+
+    /// `--warm-up`: get the renderer's first-use costs out of the way while the component
+    /// loads. A failure only costs the smoother first frame.
+    fn warm_up_graphics(&mut self) {
+        if !self.render_options.warm_up {
+            return;
+        }
+        if let Some(graphics) = self.graphics.as_mut() {
+            if let Err(err) = graphics.warm_up() {
+                tracing::warn!(error = %format!("{err:#}"), "graphics warm-up failed");
+            }
+        }
+    }
+
+    fn schedule_restart(&mut self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_restart();
+        }
+        if self.runtime.is_none() {
+            if self.loading.is_none() {
+                self.overlay = None;
+                self.start_loading();
+            }
+            return;
+        }
+
+        let size = self.guest_size();
+        if let Some(runtime) = self.runtime.as_mut() {
+            self.pacing.interrupt();
+            if let Err(err) = runtime.reload() {
+                self.set_overlay_error("Failed to restart component", &err);
+                return;
+            }
+            if let Err(err) = runtime.call_init(size) {
+                self.set_overlay_error("Component init failed", &err);
+            } else {
+                self.overlay = None;
+                self.start_transition();
+                self.request_redraw();
+            }
+        }
+    }
+
+    /// Snapshot the guest for the error overlay's "restore last good state", at most once
+    /// per [`CHECKPOINT_INTERVAL`].
+    fn checkpoint(&mut self) {
+        let now = Instant::now();
+        if !self.checkpoints_enabled
+            || self
+                .last_checkpoint_at
+                .is_some_and(|at| now.duration_since(at) < CHECKPOINT_INTERVAL)
+        {
+            return;
+        }
+        let Some(runtime) = self.runtime.as_mut() else {
+            return;
+        };
+        self.last_checkpoint_at = Some(now);
+        match runtime.snapshot() {
+            Ok(snapshot) => self.last_good_state = Some(snapshot),
+            Err(err) => {
+                tracing::debug!(error = %err, "guest state checkpoints disabled");
+                self.checkpoints_enabled = false;
+            }
+        }
+    }
+
+    /// Resume the guest from `snapshot` on a fresh instance.
+    fn restore_snapshot(&mut self, snapshot: &[u8]) -> Result<()> {
+        let runtime = self.runtime.as_mut().context("no component is running")?;
+        let result = runtime.restore(snapshot)?;
+        self.overlay = None;
+        self.handle_call_result(result);
+        self.request_redraw();
+        Ok(())
+    }
+
+    fn restore_last_good_state(&mut self) {
+        let Some(snapshot) = self.last_good_state.clone() else {
+            return;
+        };
+        if let Err(err) = self.restore_snapshot(&snapshot) {
+            self.set_overlay_error("Failed to restore the last good state", &err);
+        }
+    }
+
+    /// Blend the replaced component's last frame out over the new one's first frames.
+    fn start_transition(&mut self) {
+        let (Some(graphics), Some(frame)) = (self.graphics.as_mut(), self.last_frame.clone())
+        else {
+            return;
+        };
+        graphics.start_transition(self.transition, frame, self.transition_duration);
+    }
+
+    /// Re-read the window's monitor and tell the guest if anything about it changed.
+    fn refresh_display(&mut self, window: &Window) {
+        let display = DisplayInfo::current(window);
+        if display == self.display {
+            return;
+        }
+        self.display = display.clone();
+        let Some(runtime) = self.runtime.as_mut() else {
+            return;
+        };
+        runtime.set_display(display.clone());
+        let Some(info) = display else {
+            return;
+        };
+        if self.overlay.is_some() {
+            return;
+        }
+        match runtime.call_display_changed(&info) {
+            Ok(result) => self.handle_call_result(result),
+            Err(err) => self.set_overlay_error("Display change failed", &err),
+        }
+    }
+
+    fn set_overlay_error(&mut self, title: &str, err: &anyhow::Error) {
+        error!(error = %err, "guest runtime error");
+
+        let logs = self
+            .runtime
+            .as_ref()
+            .map(|runtime| runtime.recent_logs())
+            .unwrap_or_default();
+        let commands = self
+            .last_frame
+            .as_ref()
+            .map(|frame| frame.commands.as_slice())
+            .unwrap_or_default();
+        let body = Self::error_report(err, &logs, commands);
+
+        let restore = if self.last_good_state.is_some() {
+            "S to restore the last good state, "
+        } else {
+            ""
+        };
+        self.ui_focus = None;
+        self.overlay = Some(OverlayState {
+            title: title.to_string(),
+            body,
+            footer: format!(
+                "Press R or {} to restart, {restore}C to copy the error, Tab and Enter or \
+                 the D-pad and A to pick a button, Up/Down or PageUp/PageDown to scroll, or \
+                 close the window to exit.",
+                self.hotkeys.keybinds().restart
+            ),
+            scroll: 0,
+            notice: None,
+        });
+        self.request_redraw();
+    }
+
+    /// Overlay body for a guest failure: the root cause, the tail of the guest's logs, the
+    /// end of the last rendered frame, and the full error chain.
+    fn error_report(err: &anyhow::Error, logs: &[String], commands: &[DrawCommand]) -> String {
+        let root = err.root_cause();
+        let mut body = String::new();
+        let _ = writeln!(&mut body, "Reason: {}", root.to_string().trim());
+
+        if !logs.is_empty() {
+            body.push('\n');
+            body.push_str("Recent guest logs:\n");
+            for log in logs {
+                let _ = writeln!(&mut body, "  {}", log.trim());
+            }
+        }
+
+        if !commands.is_empty() {
+            let shown = commands.len().min(OVERLAY_DRAW_COMMANDS);
+            body.push('\n');
+            let _ = writeln!(
+                &mut body,
+                "Last draw commands ({shown} of {} in the last frame):",
+                commands.len()
+            );
+            for command in &commands[commands.len() - shown..] {
+                let mut line = command.to_string();
+                if let Some((cut, _)) = line.char_indices().nth(OVERLAY_COMMAND_CHARS) {
+                    line.truncate(cut);
+                    line.push_str("...");
+                }
+                let _ = writeln!(&mut body, "  {line}");
+            }
+        }
+
+        body.push('\n');
+        body.push_str("Error context:\n");
+        for line in format!("{err:#}").lines() {
+            let _ = writeln!(&mut body, "  {}", line);
+        }
+
+        body.trim_end().to_string()
+    }
+
+    /// Y or N answers a pending camera prompt, as do its buttons; returns whether the key
+    /// was used.
+    fn handle_camera_prompt_key(&mut self, event: &KeyEvent) -> bool {
+        if event.state != ElementState::Pressed {
+            return false;
+        }
+        if self
+            .runtime
+            .as_ref()
+            .and_then(|runtime| runtime.camera_prompt())
+            .is_none()
+        {
+            return false;
+        }
+        match &event.logical_key {
+            Key::Character(ch) if ch.eq_ignore_ascii_case("y") => self.answer_camera(true),
+            Key::Character(ch) if ch.eq_ignore_ascii_case("n") => self.answer_camera(false),
+            _ => return self.handle_focus_key(event),
+        }
+        true
+    }
+
+    fn answer_camera(&mut self, allow: bool) {
+        let Some(runtime) = self.runtime.as_mut() else {
+            return;
+        };
+        runtime.answer_camera_prompt(allow);
+        self.camera_access = if allow {
+            CameraAccess::Allow
+        } else {
+            CameraAccess::Deny
+        };
+        self.request_redraw();
+    }
+
+    /// Tab, Shift+Tab and Left/Right move focus between the modal host UI's buttons and
+    /// Enter or Space activates the focused one; returns whether the key was used.
+    fn handle_focus_key(&mut self, event: &KeyEvent) -> bool {
+        match &event.logical_key {
+            Key::Named(NamedKey::Tab) if self.modifiers.shift => self.move_ui_focus(-1),
+            Key::Named(NamedKey::Tab) | Key::Named(NamedKey::ArrowRight) => self.move_ui_focus(1),
+            Key::Named(NamedKey::ArrowLeft) => self.move_ui_focus(-1),
+            Key::Named(NamedKey::Enter) | Key::Named(NamedKey::Space) => self.activate_ui_focus(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Keys understood while the error overlay is up: R restarts, S restores the last good
+    /// state, C copies the error, the focus keys pick a button, and the Up/Down, page and
+    /// Home/End keys scroll the body.
+    fn handle_overlay_key(&mut self, event: &KeyEvent) {
+        if event.state != ElementState::Pressed || self.handle_focus_key(event) {
+            return;
+        }
+        let Some(overlay) = self.overlay.as_ref() else {
+            return;
+        };
+        let limits = self
+            .graphics
+            .as_ref()
+            .map(|graphics| graphics.overlay_scroll())
+            .unwrap_or_default();
+        let scroll = overlay.scroll.min(limits.max);
+        let scroll = match &event.logical_key {
+            Key::Character(ch) if ch.eq_ignore_ascii_case("r") => {
+                self.schedule_restart();
+                return;
+            }
+            Key::Character(ch) if ch.eq_ignore_ascii_case("s") => {
+                self.restore_last_good_state();
+                return;
+            }
+            Key::Character(ch) if ch.eq_ignore_ascii_case("c") => {
+                self.copy_overlay_error();
+                scroll
+            }
+            Key::Named(NamedKey::ArrowUp) => scroll.saturating_sub(1),
+            Key::Named(NamedKey::ArrowDown) => scroll + 1,
+            Key::Named(NamedKey::PageUp) => scroll.saturating_sub(limits.page),
+            Key::Named(NamedKey::PageDown) => scroll + limits.page,
+            Key::Named(NamedKey::Home) => 0,
+            Key::Named(NamedKey::End) => limits.max,
+            _ => return,
+        };
+        if let Some(overlay) = self.overlay.as_mut() {
+            overlay.scroll = scroll.min(limits.max);
+        }
+        self.request_redraw();
+    }
+
+    fn copy_overlay_error(&mut self) {
+        let Some(overlay) = self.overlay.as_ref() else {
+            return;
+        };
+        let text = format!("{}\n\n{}\n", overlay.title, overlay.body);
+        let result = match self.clipboard.as_mut() {
+            Some(clipboard) => clipboard.set_text(text),
+            None => arboard::Clipboard::new().and_then(|mut clipboard| {
+                clipboard.set_text(text)?;
+                self.clipboard = Some(clipboard);
+                Ok(())
+            }),
+        };
+        let notice = match result {
+            Ok(()) => "Copied the error to the clipboard.".to_string(),
+            Err(err) => {
+                tracing::warn!(%err, "failed to copy error to clipboard");
+                format!("Copy failed: {err}.")
+            }
+        };
+        if let Some(overlay) = self.overlay.as_mut() {
+            overlay.notice = Some(notice);
+        }
+    }
+
+    fn logical_from_physical(&self, size: PhysicalSize<u32>) -> Option<LogicalSize> {
+        Self::logical_size_for(size, self.effective_scale())
+    }
+
+    /// Physical pixels per guest logical pixel.
+    fn effective_scale(&self) -> f32 {
+        self.scale_factor * self.zoom
+    }
+
+    /// Step to the next zoom level up or down from `zoom`, clamped to the ends of
+    /// [`ZOOM_LEVELS`].
+    fn next_zoom(zoom: f32, zoom_in: bool) -> f32 {
+        let next = if zoom_in {
+            ZOOM_LEVELS
+                .iter()
+                .find(|level| **level > zoom + f32::EPSILON)
+        } else {
+            ZOOM_LEVELS
+                .iter()
+                .rev()
+                .find(|level| **level < zoom - f32::EPSILON)
+        };
+        next.copied().unwrap_or(zoom)
+    }
+
+    /// Rescale the canvas; the guest hears about it as a resize with a new scale factor.
+    fn set_zoom(&mut self, zoom: f32) {
+        if zoom == self.zoom {
+            return;
+        }
+        self.zoom = zoom;
+        tracing::info!(zoom, "canvas zoom");
+        if let Some(window) = self.window.clone() {
+            self.handle_window_resize(window.inner_size());
+        }
+    }
+
+    /// Guest-facing size of a window `size` physical pixels large, or `None` when it has no
+    /// area (minimized). Sizes are at least one logical pixel and a non-positive or
+    /// non-finite scale factor is treated as 1.
+    fn logical_size_for(size: PhysicalSize<u32>, scale_factor: f32) -> Option<LogicalSize> {
+        if size.width == 0 || size.height == 0 {
+            return None;
+        }
+        let scale = if scale_factor.is_finite() && scale_factor > 0.0 {
+            scale_factor
+        } else {
+            1.0
+        };
+        Some(LogicalSize {
+            width: (size.width as f32 / scale).max(1.0),
+            height: (size.height as f32 / scale).max(1.0),
+            scale_factor: scale,
+        })
+    }
+
+    /// Track a new physical window size. A zero-area window is reported as minimized
+    /// rather than resized, and the guest only hears about logical sizes that changed.
+    fn handle_window_resize(&mut self, size: PhysicalSize<u32>) {
+        if let Some(graphics) = self.graphics.as_mut() {
+            graphics.resize(size);
+        }
+        let Some(logical) = self.logical_from_physical(size) else {
+            self.set_minimized(true);
+            return;
+        };
+        self.set_minimized(false);
+        if logical == self.logical_size {
+            return;
+        }
+        self.logical_size = logical;
+        if let Some(graphics) = self.graphics.as_mut() {
+            graphics.set_scale_factor(logical.scale_factor);
+            graphics.set_logical_size(logical);
+        }
+        let resized = match self.panes.as_mut() {
+            Some(panes) => panes.resize(logical),
+            None => Some(logical),
+        };
+        if let (Some(size), Some(runtime)) = (resized, self.runtime.as_mut()) {
+            match runtime.call_resize(size) {
+                Ok(result) => self.handle_call_result(result),
+                Err(err) => self.set_overlay_error("Component resize failed", &err),
+            }
+        }
+        // Present a frame laid out for the new size before returning to the event loop;
+        // waiting for the next `RedrawRequested` shows the resized surface with stale
+        // contents for a frame, which flickers while dragging the window edge.
+        self.redraw();
+    }
+
+    fn set_minimized(&mut self, minimized: bool) {
+        if self.minimized == minimized {
+            return;
+        }
+        self.minimized = minimized;
+        tracing::debug!(minimized, "window minimized");
+        self.notify_minimized();
+        if !minimized {
+            self.request_redraw();
+        }
+    }
+
+    fn notify_minimized(&mut self) {
+        if self.overlay.is_some() {
+            return;
+        }
+        if let Some(runtime) = self.runtime.as_mut() {
+            match runtime.call_minimized_changed(self.minimized) {
+                Ok(result) => self.handle_call_result(result),
+                Err(err) => self.set_overlay_error("Minimize notification failed", &err),
+            }
+        }
+    }
+
+    fn pointer_event(&mut self, source: PointerSource) -> PointerEvent {
+        let pointer = *self.pointers.get(source);
+        PointerEvent {
+            kind: source.kind(),
+            position: self.to_guest(pointer.position),
+            buttons: pointer.buttons,
+            modifiers: self.modifiers,
+            pointer_id: pointer.id,
+            coalesced_count: 1,
+            history: Vec::new(),
+        }
+    }
+
+    fn dispatch_pointer_move(&mut self, source: PointerSource, position: PhysicalPosition<f64>) {
+        self.pointers.get(source).position = position;
+        if !matches!(source, PointerSource::Touch(..)) {
+            self.cursor_position = position;
+        }
+        if self.overlay.is_some() {
+            return;
+        }
+        if self.drag_divider(position) {
+            return;
+        }
+        if self.coalesce_pointer_moves {
+            let sample = PointerSample {
+                position: self.to_guest(position),
+                time_ms: self.input_time_ms(),
+            };
+            self.pending_moves.push(source, sample);
+            return;
+        }
+        self.deliver_pointer_move(source, 1, Vec::new());
+    }
+
+    /// Deliver the moves held back by coalescing, each at its pointer's latest position.
+    fn flush_pointer_moves(&mut self) {
+        for (source, count, history) in self.pending_moves.take() {
+            if self.overlay.is_some() {
+                return;
+            }
+            self.deliver_pointer_move(source, count, history);
+        }
+    }
+
+    fn deliver_pointer_move(
+        &mut self,
+        source: PointerSource,
+        coalesced_count: u32,
+        history: Vec<PointerSample>,
+    ) {
+        let event = PointerEvent {
+            coalesced_count,
+            history,
+            ..self.pointer_event(source)
+        };
+        if let Some(runtime) = self.runtime.as_mut() {
+            match runtime.call_pointer_move(&event) {
+                Ok(result) => self.handle_call_result(result),
+                Err(err) => self.set_overlay_error("Pointer move failed", &err),
+            }
+        }
+    }
+
+    fn dispatch_mouse_input(
+        &mut self,
+        source: PointerSource,
+        state: ElementState,
+        button: MouseButton,
+    ) {
+        let buttons = &mut self.pointers.get(source).buttons;
+        if button == MouseButton::Left {
+            buttons.primary = state == ElementState::Pressed;
+        } else if button == MouseButton::Right {
+            buttons.secondary = state == ElementState::Pressed;
+        }
+        self.dispatch_pointer_button(source, state);
+    }
+
+    /// Touch contacts map onto pointer down/move/up with the primary button held while the
+    /// finger is on the screen. Each contact gets its own pointer id, and also drags the
+    /// scroll region under it.
+    fn dispatch_touch(&mut self, touch: Touch) {
+        let source = PointerSource::Touch(touch.device_id, touch.id);
+        let position = self.to_guest(touch.location);
+        let time_ms = self.input_time_ms();
+        match touch.phase {
+            TouchPhase::Started => {
+                let pointer = self.pointers.get(source);
+                pointer.position = touch.location;
+                pointer.buttons.primary = true;
+                let pointer = pointer.id;
+                self.dispatch_pointer_button(source, ElementState::Pressed);
+                self.scroll(ScrollInput::DragStart {
+                    pointer,
+                    position,
+                    time_ms,
+                });
+            }
+            TouchPhase::Moved => {
+                self.dispatch_pointer_move(source, touch.location);
+                let pointer = self.pointers.get(source).id;
+                self.scroll(ScrollInput::DragMove {
+                    pointer,
+                    position,
+                    time_ms,
+                });
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                let pointer = self.pointers.get(source);
+                pointer.position = touch.location;
+                pointer.buttons.primary = false;
+                let pointer = pointer.id;
+                self.dispatch_pointer_button(source, ElementState::Released);
+                self.pointers.remove(&source);
+                self.scroll(ScrollInput::DragEnd { pointer, time_ms });
+            }
+        }
+    }
+
+    /// Wheel turns scroll the region under the cursor, `WHEEL_LINE` logical pixels per line.
+    fn dispatch_wheel(&mut self, delta: MouseScrollDelta) {
+        let delta = match delta {
+            MouseScrollDelta::LineDelta(x, y) => Vec2::new(x, y) * WHEEL_LINE,
+            MouseScrollDelta::PixelDelta(delta) => self.to_logical(delta),
+        };
+        let position = self.logical_cursor();
+        self.scroll(ScrollInput::Wheel { position, delta });
+    }
+
+    /// Route input to the guest's scroll regions, then draw any that moved or are flinging.
+    fn scroll(&mut self, input: ScrollInput) {
+        if self.overlay.is_some() {
+            return;
+        }
+        let Some(runtime) = self.runtime.as_mut() else {
+            return;
+        };
+        let changes = runtime.scroll_input(input);
+        let flinging = runtime.is_scrolling();
+        if !changes.is_empty() || flinging {
+            self.deliver_scroll_changes(changes);
+            self.request_redraw();
+        }
+    }
+
+    fn deliver_scroll_changes(&mut self, changes: Vec<ScrollEvent>) {
+        for change in changes {
+            let Some(runtime) = self.runtime.as_mut() else {
+                return;
+            };
+            let result = match change {
+                ScrollEvent::Region { id, offset } => runtime.call_scroll_changed(id, offset),
+                ScrollEvent::Kinetic { id, offset, delta } => {
+                    runtime.call_kinetic_scrolled(id, offset, delta)
+                }
+            };
+            match result {
+                Ok(result) => self.handle_call_result(result),
+                Err(err) => {
+                    self.set_overlay_error("Scroll notification failed", &err);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Milliseconds since the window opened, the clock input event times are on.
+    fn input_time_ms(&self) -> f64 {
+        self.input_epoch.elapsed().as_secs_f64() * 1000.0
+    }
+
+    fn dispatch_pointer_button(&mut self, source: PointerSource, state: ElementState) {
+        if self.overlay.is_some() {
+            return;
+        }
+        if self.route_pane_button(source, state) {
+            return;
+        }
+        let event = self.pointer_event(source);
+        if let Some(runtime) = self.runtime.as_mut() {
+            let result = match state {
+                ElementState::Pressed => runtime.call_pointer_down(&event),
+                ElementState::Released => runtime.call_pointer_up(&event),
+            };
+            match result {
+                Ok(res) => self.handle_call_result(res),
+                Err(err) => self.set_overlay_error("Pointer button failed", &err),
+            }
+        }
+    }
+
+    /// Let the pane layout act on a button: pressing another pane focuses it before the
+    /// press is delivered, and divider and tab presses, and the release ending a divider
+    /// drag, are the host's. True if the guest should not see the button.
+    fn route_pane_button(&mut self, source: PointerSource, state: ElementState) -> bool {
+        let position = self.pointers.get(source).position;
+        let point = self.to_logical(position);
+        let Some(panes) = self.panes.as_mut() else {
+            return false;
+        };
+        if state == ElementState::Released {
+            if !panes.release() {
+                return false;
+            }
+            self.request_redraw();
+            return true;
+        }
+        match panes.press(point) {
+            PanePress::Focused => false,
+            PanePress::Focus(name) => {
+                self.focus_pane(&name);
+                self.overlay.is_some()
+            }
+            PanePress::Host { focus } => {
+                match focus {
+                    Some(name) => self.focus_pane(&name),
+                    None => self.request_redraw(),
+                }
+                true
+            }
+        }
+    }
+
+    /// Follow the pointer with the divider being dragged, if one is; true if it moved it.
+    fn drag_divider(&mut self, position: PhysicalPosition<f64>) -> bool {
+        let point = self.to_logical(position);
+        let Some(resized) = self.panes.as_mut().and_then(|panes| panes.drag(point)) else {
+            return false;
+        };
+        if let (Some(size), Some(runtime)) = (resized, self.runtime.as_mut()) {
+            match runtime.call_resize(size) {
+                Ok(result) => self.handle_call_result(result),
+                Err(err) => self.set_overlay_error("Component resize failed", &err),
+            }
+        }
+        self.request_redraw();
+        true
+    }
+
+    /// Forwards typed text, minus control characters such as the `\r` winit reports for
+    /// Enter; those are already delivered as key events.
+    fn open_deep_link(&mut self, url: &str) {
+        if let Some(runtime) = self.runtime.as_mut() {
+            match runtime.call_deep_link_opened(url) {
+                Ok(res) => self.handle_call_result(res),
+                Err(err) => self.set_overlay_error("Deep link failed", &err),
+            }
+        }
+    }
+
+    fn dispatch_text_input(&mut self, text: &str) {
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+        if text.is_empty() {
+            return;
+        }
+        if let Some(runtime) = self.runtime.as_mut() {
+            match runtime.call_text_input(&text) {
+                Ok(res) => self.handle_call_result(res),
+                Err(err) => self.set_overlay_error("Text input failed", &err),
+            }
+        }
+    }
+
+    fn gesture_event(&self, phase: TouchPhase, delta: f32) -> GestureEvent {
+        GestureEvent {
+            phase: match phase {
+                TouchPhase::Started => GesturePhase::Started,
+                TouchPhase::Moved => GesturePhase::Changed,
+                TouchPhase::Ended => GesturePhase::Ended,
+                TouchPhase::Cancelled => GesturePhase::Cancelled,
+            },
+            position: self.logical_cursor(),
+            modifiers: self.modifiers,
+            delta,
+        }
+    }
+
+    fn logical_cursor(&self) -> Vec2 {
+        self.to_guest(self.cursor_position)
+    }
+
+    /// `position` relative to the component that gets input: the window, or the focused
+    /// pane.
+    fn to_guest(&self, position: PhysicalPosition<f64>) -> Vec2 {
+        let origin = self
+            .panes
+            .as_ref()
+            .map_or(Vec2::ZERO, Panes::focused_origin);
+        self.to_logical(position) - origin
+    }
+
+    fn to_logical(&self, position: PhysicalPosition<f64>) -> Vec2 {
+        let logical = position.to_logical::<f64>(self.effective_scale() as f64);
+        Vec2::new(logical.x as f32, logical.y as f32)
+    }
+
+    fn dispatch_gesture(&mut self, gesture: Gesture) {
+        if self.overlay.is_some() {
+            return;
+        }
+        let Some(runtime) = self.runtime.as_mut() else {
+            return;
+        };
+        let result = match gesture {
+            Gesture::Pinch(event) => runtime.call_pinch_gesture(&event),
+            Gesture::Rotation(event) => runtime.call_rotation_gesture(&event),
+            Gesture::DoubleTap(position) => {
+                runtime.call_double_tap_gesture(position, self.modifiers)
+            }
+        };
+        match result {
+            Ok(res) => self.handle_call_result(res),
+            Err(err) => self.set_overlay_error("Gesture failed", &err),
         }
     }
 
-    fn request_redraw(&mut self) {
-        if let Some(window) = &self.window {
-            window.request_redraw();
+    fn dispatch_key(&mut self, state: ElementState, key_event: &GuestKeyEvent) {
+        if let Some(runtime) = self.runtime.as_mut() {
+            let result = match state {
+                ElementState::Pressed => runtime.call_key_down(key_event),
+                ElementState::Released => runtime.call_key_up(key_event),
+            };
+            match result {
+                Ok(res) => self.handle_call_result(res),
+                Err(err) => self.set_overlay_error("Key event failed", &err),
+            }
         }
     }
 
-    fn ensure_runtime(&mut self) -> Result<()> {
-        if self.runtime.is_some() {
-            return Ok(());
-        }
-        let runtime = ComponentRuntime::new(self.component.clone())?;
-        self.runtime = Some(runtime);
-        Ok(())
+    fn key_event_from_winit(&self, event: &KeyEvent) -> GuestKeyEvent {
+        Self::translate_key_event(
+            &event.logical_key,
+            &event.physical_key,
+            event.location,
+            event.repeat,
+            self.modifiers,
+        )
     }
 
-    fn ensure_graphics(&mut self, window: Arc<Window>) -> Result<()> {
-        if self.graphics.is_some() {
-            return Ok(());
+    fn translate_key_event(
+        logical_key: &Key,
+        physical_key: &PhysicalKey,
+        location: KeyLocation,
+        repeat: bool,
+        modifiers: Modifiers,
+    ) -> GuestKeyEvent {
+        GuestKeyEvent {
+            key: keys::key_value(logical_key),
+            code: keys::code_value(physical_key).to_string(),
+            location: keys::key_location(location),
+            modifiers,
+            is_repeat: repeat,
         }
-        let graphics = GraphicsState::new(window.clone(), self.scale_factor, self.logical_size)?;
-        self.graphics = Some(graphics);
-        self.window = Some(window);
-        Ok(())
     }
 
-    fn handle_call_result(&mut self, result: CallResult) {
-        if result.requested_redraw {
-            self.request_redraw();
+    /// Render whatever the window should show now: the loading screen, the error overlay
+    /// or a fresh guest frame.
+    fn redraw(&mut self) {
+        self.sync_accessibility();
+        let suspended = self
+            .graphics
+            .as_ref()
+            .is_some_and(|graphics| graphics.is_suspended());
+        if self.minimized || suspended {
+            self.pacing.interrupt();
+            return;
         }
-    }
-
-    fn handle_frame_result(&mut self, frame: FrameResult) -> Result<()> {
-        if frame.requested_redraw {
-            self.request_redraw();
+        if self.loading.is_some() {
+            if let Err(err) = self.render_loading_screen() {
+                self.set_overlay_error("Overlay render failed", &err);
+            }
+            return;
         }
-        let overlay_content = self.overlay.as_ref().map(|state| state.to_content());
-        if let Some(graphics) = self.graphics.as_mut() {
-            graphics.render(Some(&frame.frame), overlay_content.as_ref())?;
+        if self.overlay.is_some() {
+            self.pacing.interrupt();
+            if let Err(err) = self.render_overlay_only() {
+                self.set_overlay_error("Overlay render failed", &err);
+            }
+            return;
         }
-        Ok(())
-    }
 
-    fn render_overlay_only(&mut self) -> Result<()> {
-        if let Some(graphics) = self.graphics.as_mut() {
-            let overlay_content = self.overlay.as_ref().map(|state| state.to_content());
-            graphics.render(None, overlay_content.as_ref())?;
+        self.run_frame();
+        if self
+            .graphics
+            .as_ref()
+            .is_some_and(GraphicsState::transition_running)
+        {
+            self.request_redraw();
         }
-        Ok(())
     }
 
-    fn schedule_restart(&mut self) {
-        if self.runtime.is_none() {
-            match ComponentRuntime::new(self.component.clone()) {
-                Ok(runtime) => self.runtime = Some(runtime),
-                Err(err) => {
-                    self.set_overlay_error("Failed to restart component", &err);
-                    return;
+    fn run_frame(&mut self) {
+        self.needs_redraw = false;
+        let started = Instant::now();
+        self.last_frame_at = Some(started);
+        let Some(dt_ms) = self.clock.tick(started) else {
+            self.pacing.interrupt();
+            if let Err(err) = self.render_last_frame() {
+                self.set_overlay_error("Render failed", &err);
+            }
+            return;
+        };
+        let missed = self.pacing.frame_started(started, self.frame_period());
+        if missed > 0 {
+            tracing::debug!(target: FRAME_TARGET, missed, "missed refreshes");
+            if let Some(runtime) = self.runtime.as_mut() {
+                match runtime.call_frame_missed(missed) {
+                    Ok(result) => self.handle_call_result(result),
+                    Err(err) => self.set_overlay_error("Frame-missed notification failed", &err),
                 }
             }
-        }
-
-        if let Some(runtime) = self.runtime.as_mut() {
-            if let Err(err) = runtime.reload() {
-                self.set_overlay_error("Failed to restart component", &err);
+            if self.overlay.is_some() {
                 return;
             }
-            if let Err(err) = runtime.call_init(self.logical_size) {
-                self.set_overlay_error("Component init failed", &err);
-            } else {
-                self.overlay = None;
+        }
+
+        let Some(runtime) = self.runtime.as_mut() else {
+            return;
+        };
+        // Flings and bounces move before the guest draws, and keep frames coming until they stop.
+        let scrolled = runtime.advance_scrolling(dt_ms);
+        if runtime.is_scrolling() {
+            self.request_redraw();
+        }
+        self.deliver_scroll_changes(scrolled);
+        if self.overlay.is_some() {
+            return;
+        }
+        if let Some(panes) = self.panes.as_mut() {
+            if panes.run_frames(dt_ms) {
                 self.request_redraw();
             }
         }
+        let Some(runtime) = self.runtime.as_mut() else {
+            return;
+        };
+        runtime.set_frame_elapsed(self.clock.elapsed_ms());
+        let guest_start = Instant::now();
+        match runtime.call_frame(dt_ms) {
+            Ok(frame) => {
+                let guest_ms = guest_start.elapsed().as_secs_f32() * 1000.0;
+                if frame.requested_redraw {
+                    self.pacing.frame_chained(started);
+                }
+                let commands = frame.frame.command_count();
+                let measures = runtime.take_perf_measures();
+                let (host_calls, text_bytes) = runtime.take_host_usage();
+                let render_start = Instant::now();
+                if let Err(err) = self.handle_frame_result(frame) {
+                    self.set_overlay_error("Render failed", &err);
+                }
+                let render_ms = render_start.elapsed().as_secs_f32() * 1000.0;
+                let gpu = self.graphics.as_ref().and_then(|g| g.gpu_timings());
+                let culled = self.graphics.as_ref().map_or(0, |g| g.culled_commands());
+                tracing::debug!(
+                    target: FRAME_TARGET,
+                    dt_ms,
+                    guest_ms,
+                    render_ms,
+                    gpu_raster_ms = gpu.map(|gpu| gpu.render_ms),
+                    gpu_blit_ms = gpu.map(|gpu| gpu.blit_ms),
+                    commands,
+                    culled,
+                    "frame"
+                );
+                self.stats = FrameStats {
+                    dt_ms,
+                    guest_ms,
+                    render_ms,
+                    commands,
+                    culled,
+                    gpu,
+                    measures,
+                    host_calls,
+                    text_bytes,
+                    missed,
+                    missed_total: self.pacing.missed_total(),
+                    one_percent_low_ms: self.pacing.one_percent_low_ms(),
+                };
+                if let Some(metrics) = &self.metrics {
+                    let gpu_memory = self.graphics.as_ref().and_then(|g| g.gpu_memory_bytes());
+                    metrics.record_frame(&self.stats, gpu_memory);
+                }
+                self.checkpoint();
+            }
+            Err(err) => self.set_overlay_error("Component frame failed", &err),
+        }
     }
 
-    fn set_overlay_error(&mut self, title: &str, err: &anyhow::Error) {
-        error!(error = %err, "guest runtime error");
-
-        let root = err.root_cause();
-        let mut body = String::new();
-        let _ = writeln!(&mut body, "Reason: {}", root.to_string().trim());
-
-        let logs = self
-            .runtime
-            .as_ref()
-            .map(|runtime| runtime.recent_logs())
-            .unwrap_or_default();
-        if !logs.is_empty() {
-            body.push('\n');
-            body.push_str("Recent guest logs:\n");
-            for log in logs {
-                let _ = writeln!(&mut body, "  {}", log.trim());
+    /// Present the last rendered frame unchanged; false when there is none to show.
+    fn present_last_frame(&mut self) -> bool {
+        let Some(graphics) = self.graphics.as_mut() else {
+            return false;
+        };
+        match graphics.present_last() {
+            Ok(presented) => presented,
+            Err(err) => {
+                tracing::warn!(error = %format!("{err:#}"), "re-presenting the last frame failed");
+                false
             }
         }
+    }
 
-        body.push('\n');
-        body.push_str("Error context:\n");
-        for line in format!("{err:#}").lines() {
-            let _ = writeln!(&mut body, "  {}", line);
+    fn render_last_frame(&mut self) -> Result<()> {
+        let stats = self.stats_lines();
+        let overlay_content = self.overlay_content();
+        if let Some(graphics) = self.graphics.as_mut() {
+            graphics.render(
+                self.last_frame.as_ref(),
+                overlay_content.as_ref(),
+                stats.as_deref(),
+            )?;
         }
+        Ok(())
+    }
 
-        let body = body.trim_end().to_string();
+    /// Pausing stops `frame` calls; redraws while paused re-present the last frame.
+    fn set_time_paused(&mut self, paused: bool) {
+        self.clock.set_paused(paused);
+        tracing::info!(paused, "guest time");
+    }
 
-        self.overlay = Some(OverlayState {
-            title: title.to_string(),
-            body,
-            footer: "Press R to restart the component or close the window to exit.".to_string(),
-        });
-        self.request_redraw();
+    fn cycle_time_scale(&mut self) {
+        let scale = self.clock.cycle_speed();
+        tracing::info!(scale, "guest time scale");
     }
 
-    fn logical_from_physical(&self, size: PhysicalSize<u32>) -> LogicalSize {
-        let scale = self.scale_factor.max(0.0001);
-        LogicalSize {
-            width: size.width as f32 / scale,
-            height: size.height as f32 / scale,
-            scale_factor: self.scale_factor,
+    fn run_host_action(&mut self, action: HostAction) {
+        match action {
+            HostAction::Restart => self.schedule_restart(),
+            HostAction::ToggleStats => self.show_stats = !self.show_stats,
+            HostAction::ToggleConsole => self.show_console = !self.show_console,
+            HostAction::TogglePause => self.set_time_paused(!self.clock.is_paused()),
+            HostAction::Step => self.clock.step(),
+            HostAction::CycleTimeScale => self.cycle_time_scale(),
+            HostAction::ZoomIn => self.set_zoom(Self::next_zoom(self.zoom, true)),
+            HostAction::ZoomOut => self.set_zoom(Self::next_zoom(self.zoom, false)),
+            HostAction::ResetZoom => self.set_zoom(1.0),
         }
+        self.request_redraw();
     }
 
-    fn pointer_event(&self, position: PhysicalPosition<f64>) -> PointerEvent {
-        let logical = position.to_logical::<f64>(self.scale_factor as f64);
-        PointerEvent {
-            kind: PointerKind::Mouse,
-            position: [logical.x as f32, logical.y as f32],
-            buttons: self.pointer_buttons,
-            modifiers: self.modifiers,
-            pointer_id: 0,
+    /// Give host hotkeys first refusal on a key event. Returns `true` when the host
+    /// consumed it; reserved chords are never forwarded to the guest.
+    fn route_host_hotkey(&mut self, state: ElementState, event: &GuestKeyEvent) -> bool {
+        let route = match state {
+            ElementState::Pressed => {
+                self.hotkeys
+                    .key_down(&event.key, &event.code, &event.modifiers, event.is_repeat)
+            }
+            ElementState::Released => self.hotkeys.key_up(&event.code),
+        };
+        match route {
+            KeyRoute::Guest => false,
+            KeyRoute::Host(action) => {
+                if let Some(action) = action {
+                    self.run_host_action(action);
+                }
+                true
+            }
         }
     }
 
-    fn key_event_from_winit(&self, event: &KeyEvent) -> GuestKeyEvent {
-        Self::translate_key_event(
-            &event.logical_key,
-            &event.physical_key,
-            event.repeat,
-            self.modifiers,
-        )
+    /// Run injected input through the same path as window events, reporting guest failures
+    /// back to the control client.
+    fn inject_input(&mut self, dispatch: impl FnOnce(&mut Self)) -> Result<()> {
+        if self.overlay.is_some() || self.runtime.is_none() {
+            bail!("component is not running");
+        }
+        dispatch(self);
+        if let Some(overlay) = &self.overlay {
+            bail!("{}", overlay.title);
+        }
+        Ok(())
     }
 
-    fn translate_key_event(
-        logical_key: &Key,
-        physical_key: &PhysicalKey,
-        repeat: bool,
-        modifiers: Modifiers,
-    ) -> GuestKeyEvent {
-        let key = match logical_key {
-            Key::Character(ch) => ch.to_string(),
-            Key::Named(named) => format!("{named:?}"),
-            Key::Dead(dead) => format!("Dead({dead:?})"),
-            other => format!("{other:?}"),
-        };
-        let code = match physical_key {
-            PhysicalKey::Code(code) => format!("{code:?}"),
-            PhysicalKey::Unidentified(id) => format!("Unidentified({id:?})"),
-        };
+    fn injected_key_event(&self, key: String, code: Option<String>) -> GuestKeyEvent {
         GuestKeyEvent {
+            code: code.unwrap_or_else(|| key.clone()),
             key,
-            code,
-            modifiers,
-            is_repeat: repeat,
+            modifiers: self.modifiers,
+            is_repeat: false,
+            location: Default::default(),
         }
     }
 
-    fn tick_frame_time(&mut self) -> f32 {
-        let now = Instant::now();
-        if let Some(last) = self.last_frame_instant.replace(now) {
-            (now - last).as_secs_f32() * 1000.0
-        } else {
-            0.0
+    fn physical_from_logical(&self, x: f32, y: f32) -> PhysicalPosition<f64> {
+        winit::dpi::LogicalPosition::new(x as f64, y as f64)
+            .to_physical(self.effective_scale() as f64)
+    }
+
+    fn handle_control(&mut self, command: ControlCommand) -> Result<Value> {
+        match command {
+            ControlCommand::PointerMove { x, y } => self.inject_input(|app| {
+                app.dispatch_pointer_move(
+                    PointerSource::Synthetic,
+                    app.physical_from_logical(x, y),
+                );
+            })?,
+            ControlCommand::PointerDown { x, y, button } => self.inject_input(|app| {
+                let position = app.physical_from_logical(x, y);
+                app.dispatch_pointer_move(PointerSource::Synthetic, position);
+                app.dispatch_mouse_input(
+                    PointerSource::Synthetic,
+                    ElementState::Pressed,
+                    button.into(),
+                );
+            })?,
+            ControlCommand::PointerUp { x, y, button } => self.inject_input(|app| {
+                let position = app.physical_from_logical(x, y);
+                app.dispatch_pointer_move(PointerSource::Synthetic, position);
+                app.dispatch_mouse_input(
+                    PointerSource::Synthetic,
+                    ElementState::Released,
+                    button.into(),
+                );
+            })?,
+            ControlCommand::KeyDown { key, code } => self.inject_input(|app| {
+                let event = app.injected_key_event(key, code);
+                app.dispatch_key(ElementState::Pressed, &event);
+            })?,
+            ControlCommand::KeyUp { key, code } => self.inject_input(|app| {
+                let event = app.injected_key_event(key, code);
+                app.dispatch_key(ElementState::Released, &event);
+            })?,
+            ControlCommand::TextInput { text } => {
+                self.inject_input(|app| app.dispatch_text_input(&text))?
+            }
+            ControlCommand::Activate { args, url } => {
+                let link = url.as_deref().map(DeepLink::parse).transpose()?;
+                if let Some(window) = &self.window {
+                    window.set_minimized(false);
+                    window.focus_window();
+                }
+                if let Some(runtime) = self.runtime.as_mut() {
+                    match runtime.call_instance_activated(&args) {
+                        Ok(res) => self.handle_call_result(res),
+                        Err(err) => self.set_overlay_error("Instance activation failed", &err),
+                    }
+                }
+                if let Some(link) = link {
+                    self.open_deep_link(&link.url);
+                }
+            }
+            ControlCommand::DeepLink { url } => {
+                let link = DeepLink::parse(&url)?;
+                if self.runtime.is_none() {
+                    bail!("no component is running");
+                }
+                self.open_deep_link(&link.url);
+            }
+            ControlCommand::Screenshot { path } => {
+                let graphics = self.graphics.as_ref().context("graphics not initialised")?;
+                let (width, height) = graphics.capture_png(&path)?;
+                return Ok(json!({ "path": path, "width": width, "height": height }));
+            }
+            ControlCommand::Snapshot { path } => {
+                if self.overlay.is_some() {
+                    bail!("the component has failed; restore or reload it first");
+                }
+                let runtime = self.runtime.as_mut().context("no component is running")?;
+                let snapshot = runtime.snapshot()?;
+                std::fs::write(&path, &snapshot)
+                    .with_context(|| format!("failed to write {}", path.display()))?;
+                return Ok(json!({ "path": path, "bytes": snapshot.len() }));
+            }
+            ControlCommand::Restore { path } => {
+                let snapshot = std::fs::read(&path)
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                self.restore_snapshot(&snapshot)?;
+            }
+            ControlCommand::Reload => {
+                self.schedule_restart();
+                if let Some(overlay) = &self.overlay {
+                    bail!("{}: {}", overlay.title, overlay.body);
+                }
+            }
+            ControlCommand::DumpFrame => {
+                let frame = self.last_frame.as_ref().context("no frame rendered yet")?;
+                let commands: Vec<String> = frame.commands.iter().map(|c| c.to_string()).collect();
+                return Ok(json!({
+                    "clear_color": frame.clear_color.map(|c| [c.r, c.g, c.b, c.a]),
+                    "commands": commands,
+                }));
+            }
+            ControlCommand::Stats => {
+                return Ok(json!({
+                    "frame": self.stats,
+                    "time": { "paused": self.clock.is_paused(), "scale": self.clock.scale() },
+                    "logical_size": [self.logical_size.width, self.logical_size.height],
+                    "scale_factor": self.effective_scale(),
+                    "zoom": self.zoom,
+                }));
+            }
+            ControlCommand::Pause => self.set_time_paused(true),
+            ControlCommand::Resume => self.set_time_paused(false),
+            ControlCommand::Step => self.clock.step(),
+            ControlCommand::TimeScale { scale } => {
+                if !(scale.is_finite() && scale > 0.0) {
+                    bail!("time scale must be a positive number, got {scale}");
+                }
+                self.clock.set_scale(scale);
+                tracing::info!(scale, "guest time scale");
+            }
         }
+        self.request_redraw();
+        Ok(Value::Null)
     }
 }
 
-impl ApplicationHandler for App {
+impl ApplicationHandler<AppEvent> for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        if self.window.is_some() {
+        if let Some(window) = self.window.clone() {
+            self.resume_graphics(window);
             return;
         }
 
+        // The accessibility adapter has to exist before the window is first shown.
         let window = event_loop
-            .create_window(
-                WindowAttributes::default()
-                    .with_title("Frontier Canvas Prototype")
-                    .with_inner_size(PhysicalSize::new(900, 600)),
-            )
+            .create_window(self.window_options.attributes().with_visible(false))
             .expect("failed to create window");
+        if let Some(proxy) = self.event_proxy.clone() {
+            self.accessibility = Some(accesskit_winit::Adapter::with_event_loop_proxy(
+                event_loop, &window, proxy,
+            ));
+        }
+        window.set_visible(true);
+        window.set_ime_allowed(true);
         let window = Arc::new(window);
+        self.display = DisplayInfo::current(&window);
+        self.window_position = window.outer_position().ok();
         self.scale_factor = window.scale_factor() as f32;
         let physical = window.inner_size();
-        self.logical_size = self.logical_from_physical(physical);
+        self.minimized = physical.width == 0 || physical.height == 0;
+        self.logical_size = self.logical_from_physical(physical).unwrap_or(LogicalSize {
+            width: MIN_WINDOW_SIZE.0 as f32,
+            height: MIN_WINDOW_SIZE.1 as f32,
+            scale_factor: self.effective_scale(),
+        });
 
         if let Err(err) = self.ensure_graphics(window.clone()) {
             self.set_overlay_error("Graphics initialisation failed", &err);
             return;
         }
 
-        if let Err(err) = self.ensure_runtime() {
-            self.set_overlay_error("Runtime initialisation failed", &err);
-            return;
+        self.window_options.apply_to(&window);
+        if let Some(graphics) = self.graphics.as_mut() {
+            graphics.set_logical_size(self.logical_size);
+            graphics.set_scale_factor(self.logical_size.scale_factor);
+            graphics.set_opacity(self.window_options.opacity);
+            graphics.set_background(self.window_options.background);
         }
 
+        self.start_loading();
+        self.poll_loading();
+    }
+
+    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
         if let Some(graphics) = self.graphics.as_mut() {
-            graphics.set_logical_size(self.logical_size);
-            graphics.set_scale_factor(self.scale_factor);
+            graphics.suspend();
+            tracing::info!("suspended; window surface released");
         }
+    }
 
-        if let Some(runtime) = self.runtime.as_mut() {
-            match runtime.call_init(self.logical_size) {
-                Ok(result) => {
-                    self.handle_call_result(result);
-                }
-                Err(err) => {
-                    self.set_overlay_error("Component init failed", &err);
+    fn user_event(&mut self, _event_loop: &ActiveEventLoop, event: AppEvent) {
+        let request = match event {
+            AppEvent::Control(request) => request,
+            AppEvent::Accessibility(event) => {
+                if self
+                    .window
+                    .as_ref()
+                    .is_some_and(|window| window.id() == event.window_id)
+                {
+                    self.handle_accessibility_event(event.window_event);
                 }
+                return;
             }
+        };
+        self.flush_pointer_moves();
+        let result = self.handle_control(request.command.clone());
+        if let Err(err) = &result {
+            tracing::debug!(error = %format!("{err:#}"), "control command failed");
         }
-
-        self.request_redraw();
-        event_loop.set_control_flow(ControlFlow::Wait);
+        request.respond(result);
     }
 
-    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if self.exit_requested {
+            event_loop.exit();
+            return;
+        }
+        let loading = self.poll_loading();
+        let power = self.poll_power();
+        let memory = self.poll_memory();
+        let idle = self.poll_idle();
+        let gamepads = self.poll_gamepads();
+        let background = self.poll_background();
+        let mut deferred_frame = None;
         if self.needs_redraw {
-            self.request_redraw();
-            self.needs_redraw = false;
+            deferred_frame = self.frame_due();
+            if deferred_frame.is_none() {
+                self.request_redraw();
+                self.needs_redraw = false;
+            }
+        }
+        let wake_at = [
+            loading,
+            power,
+            memory,
+            idle,
+            gamepads,
+            background,
+            deferred_frame,
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        event_loop.set_control_flow(wake_at.map_or(ControlFlow::Wait, ControlFlow::WaitUntil));
+        // With no frame coming, held-back moves would otherwise wait for the next event.
+        if !self.redraw_pending {
+            self.flush_pointer_moves();
         }
     }
 
@@ -302,139 +2398,127 @@ impl ApplicationHandler for App {
         window_id: winit::window::WindowId,
         event: WindowEvent,
     ) {
-        let Some(window) = &self.window else {
+        let Some(window) = self.window.clone() else {
             return;
         };
         if window.id() != window_id {
             return;
         }
+        // Anything other than another move must see the guest caught up on pointer position.
+        let is_move = matches!(
+            event,
+            WindowEvent::CursorMoved { .. }
+                | WindowEvent::Touch(Touch {
+                    phase: TouchPhase::Moved,
+                    ..
+                })
+        );
+        if !is_move {
+            self.flush_pointer_moves();
+        }
+        if idle::is_user_input(&event) {
+            self.record_input();
+        }
+        if let Some(adapter) = self.accessibility.as_mut() {
+            adapter.process_event(&window, &event);
+        }
 
         match event {
-            WindowEvent::CloseRequested => {
+            WindowEvent::CloseRequested if self.close_allowed() => {
                 event_loop.exit();
             }
             WindowEvent::Resized(size) => {
-                if let Some(graphics) = self.graphics.as_mut() {
-                    graphics.resize(size);
-                }
-                let logical = self.logical_from_physical(size);
-                self.logical_size = logical;
-                if let Some(graphics) = self.graphics.as_mut() {
-                    graphics.set_logical_size(logical);
-                }
+                self.handle_window_resize(size);
+                self.refresh_display(&window);
+            }
+            WindowEvent::Moved(position) => {
+                self.window_position = Some(position);
                 if let Some(runtime) = self.runtime.as_mut() {
-                    match runtime.call_resize(logical) {
-                        Ok(result) => self.handle_call_result(result),
-                        Err(err) => self.set_overlay_error("Component resize failed", &err),
+                    runtime.set_window_position(self.window_position);
+                    if self.overlay.is_none() {
+                        match runtime.call_window_moved(position) {
+                            Ok(result) => self.handle_call_result(result),
+                            Err(err) => self.set_overlay_error("Window move failed", &err),
+                        }
                     }
                 }
+                self.refresh_display(&window);
             }
             WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
                 self.scale_factor = scale_factor as f32;
-                let size = window.inner_size();
-                let logical = self.logical_from_physical(size);
-                self.logical_size = logical;
-                if let Some(graphics) = self.graphics.as_mut() {
-                    graphics.set_scale_factor(self.scale_factor);
-                    graphics.set_logical_size(logical);
-                    graphics.resize(size);
-                }
-                if let Some(runtime) = self.runtime.as_mut() {
-                    match runtime.call_resize(logical) {
-                        Ok(result) => self.handle_call_result(result),
-                        Err(err) => self.set_overlay_error("Component resize failed", &err),
-                    }
-                }
+                self.handle_window_resize(window.inner_size());
             }
             WindowEvent::RedrawRequested => {
-                if self.overlay.is_some() {
-                    if let Err(err) = self.render_overlay_only() {
-                        self.set_overlay_error("Overlay render failed", &err);
-                    }
+                // A redraw nobody asked for is the OS repainting the window, e.g. after it
+                // was uncovered; nothing changed, so the last frame is shown again rather
+                // than calling the guest and advancing its clock.
+                let requested = std::mem::take(&mut self.redraw_pending);
+                if !requested && !self.minimized && self.present_last_frame() {
                     return;
                 }
-
-                let dt_ms = self.tick_frame_time();
-                if let Some(runtime) = self.runtime.as_mut() {
-                    match runtime.call_frame(dt_ms) {
-                        Ok(frame) => {
-                            if let Err(err) = self.handle_frame_result(frame) {
-                                self.set_overlay_error("Render failed", &err);
-                            }
-                        }
-                        Err(err) => self.set_overlay_error("Component frame failed", &err),
-                    }
-                }
+                self.redraw();
             }
-            WindowEvent::CursorMoved { position, .. } => {
-                self.cursor_position = position;
-                if self.overlay.is_some() {
-                    return;
-                }
-                let event = self.pointer_event(position);
-                if let Some(runtime) = self.runtime.as_mut() {
-                    match runtime.call_pointer_move(&event) {
-                        Ok(result) => self.handle_call_result(result),
-                        Err(err) => self.set_overlay_error("Pointer move failed", &err),
-                    }
-                }
+            WindowEvent::CursorMoved {
+                device_id,
+                position,
+            } => {
+                self.dispatch_pointer_move(PointerSource::Mouse(device_id), position);
             }
-            WindowEvent::MouseInput { state, button, .. } => {
-                if self.overlay.is_some() {
-                    return;
-                }
-                if button == MouseButton::Left {
-                    self.pointer_buttons.primary = state == ElementState::Pressed;
-                } else if button == MouseButton::Right {
-                    self.pointer_buttons.secondary = state == ElementState::Pressed;
-                }
-                let event = self.pointer_event(self.cursor_position);
-                if let Some(runtime) = self.runtime.as_mut() {
-                    let result = match state {
-                        ElementState::Pressed => runtime.call_pointer_down(&event),
-                        ElementState::Released => runtime.call_pointer_up(&event),
-                    };
-                    match result {
-                        Ok(res) => self.handle_call_result(res),
-                        Err(err) => self.set_overlay_error("Pointer button failed", &err),
-                    }
-                }
+            WindowEvent::MouseInput {
+                device_id,
+                state,
+                button,
+            } => {
+                self.dispatch_mouse_input(PointerSource::Mouse(device_id), state, button);
+            }
+            WindowEvent::Touch(touch) => self.dispatch_touch(touch),
+            WindowEvent::MouseWheel { delta, .. } => self.dispatch_wheel(delta),
+            WindowEvent::PinchGesture { delta, phase, .. } => {
+                let event = self.gesture_event(phase, delta as f32);
+                self.dispatch_gesture(Gesture::Pinch(event));
+            }
+            WindowEvent::RotationGesture { delta, phase, .. } => {
+                let event = self.gesture_event(phase, delta);
+                self.dispatch_gesture(Gesture::Rotation(event));
+            }
+            WindowEvent::DoubleTapGesture { .. } => {
+                let position = self.logical_cursor();
+                self.dispatch_gesture(Gesture::DoubleTap(position));
             }
             WindowEvent::ModifiersChanged(state) => {
-                let state = state.state();
-                self.modifiers = Modifiers {
-                    shift: state.shift_key(),
-                    ctrl: state.control_key(),
-                    alt: state.alt_key(),
-                    meta: state.super_key(),
-                };
+                keys::apply_modifiers(&mut self.modifiers, &state);
             }
             WindowEvent::KeyboardInput { event, .. } => {
-                if let ElementState::Pressed = event.state {
-                    if let Key::Character(ch) = &event.logical_key {
-                        if self.overlay.is_some() && ch.eq_ignore_ascii_case("r") {
-                            self.schedule_restart();
-                            return;
-                        }
-                    }
+                keys::track_key(
+                    &mut self.modifiers,
+                    &event.physical_key,
+                    &event.logical_key,
+                    event.state,
+                    event.repeat,
+                );
+                let key_event = self.key_event_from_winit(&event);
+                if self.route_host_hotkey(event.state, &key_event) {
+                    return;
+                }
+                if self.overlay.is_none() && self.handle_camera_prompt_key(&event) {
+                    return;
                 }
 
                 if self.overlay.is_some() {
+                    self.handle_overlay_key(&event);
                     return;
                 }
 
-                let key_event = self.key_event_from_winit(&event);
-                if let Some(runtime) = self.runtime.as_mut() {
-                    let result = match event.state {
-                        ElementState::Pressed => runtime.call_key_down(&key_event),
-                        ElementState::Released => runtime.call_key_up(&key_event),
-                    };
-                    match result {
-                        Ok(res) => self.handle_call_result(res),
-                        Err(err) => self.set_overlay_error("Key event failed", &err),
+                self.dispatch_key(event.state, &key_event);
+                if event.state == ElementState::Pressed {
+                    if let Some(text) = &event.text {
+                        self.dispatch_text_input(text);
                     }
                 }
             }
+            WindowEvent::Ime(Ime::Commit(text)) if self.overlay.is_none() => {
+                self.dispatch_text_input(&text);
+            }
             _ => {}
         }
     }
@@ -443,8 +2527,10 @@ impl ApplicationHandler for App {
 #[cfg(test)]
 mod tests {
     use super::App;
+    use crate::geometry::Vec2;
+    use crate::host::{Color, DrawCommand, FontFamily, TextFlow};
     use crate::model::Modifiers;
-    use winit::keyboard::{Key, KeyCode, NamedKey, NativeKeyCode, PhysicalKey};
+    use winit::keyboard::{Key, KeyCode, KeyLocation, NamedKey, NativeKeyCode, PhysicalKey};
 
     #[test]
     fn translates_character_keys_with_modifiers() {
@@ -453,10 +2539,12 @@ mod tests {
             ctrl: false,
             alt: false,
             meta: true,
+            ..Modifiers::default()
         };
         let event = App::translate_key_event(
             &Key::Character("X".into()),
             &PhysicalKey::Code(KeyCode::KeyX),
+            KeyLocation::Standard,
             false,
             modifiers,
         );
@@ -474,11 +2562,72 @@ mod tests {
         let event = App::translate_key_event(
             &Key::Named(NamedKey::ArrowUp),
             &PhysicalKey::Unidentified(NativeKeyCode::MacOS(0x7E)),
+            KeyLocation::Standard,
             true,
             modifiers,
         );
         assert_eq!(event.key, "ArrowUp");
-        assert_eq!(event.code, "Unidentified(MacOS(0x007E))");
+        assert_eq!(event.code, "Unidentified");
         assert!(event.is_repeat);
     }
+
+    #[test]
+    fn error_report_lists_logs_and_the_end_of_the_last_frame() {
+        let err = anyhow::anyhow!("unreachable executed").context("frame failed");
+        let logs = vec!["[info] starting".to_string()];
+        let commands: Vec<DrawCommand> = (0..10)
+            .map(|i| DrawCommand::DrawText {
+                text: format!("label {i} {}", "x".repeat(if i == 9 { 500 } else { 0 })),
+                origin: Vec2 { x: 0.0, y: 0.0 },
+                size: 12.0,
+                color: Color::default(),
+                font: FontFamily::Sans,
+                flow: TextFlow::default(),
+            })
+            .collect();
+        let report = App::error_report(&err, &logs, &commands);
+        assert!(report.starts_with("Reason: unreachable executed"));
+        assert!(report.contains("  [info] starting"));
+        assert!(report.contains("Last draw commands (8 of 10 in the last frame):"));
+        assert!(!report.contains("label 1 "));
+        assert!(report.contains("label 2 "));
+        assert!(report.lines().all(|line| line.chars().count() <= 125));
+        assert!(report.contains("frame failed: unreachable executed"));
+    }
+
+    #[test]
+    fn zero_area_windows_have_no_logical_size() {
+        use winit::dpi::PhysicalSize;
+
+        assert!(App::logical_size_for(PhysicalSize::new(0, 0), 2.0).is_none());
+        assert!(App::logical_size_for(PhysicalSize::new(800, 0), 2.0).is_none());
+
+        let size = App::logical_size_for(PhysicalSize::new(800, 601), 2.0).unwrap();
+        assert_eq!(
+            (size.width, size.height, size.scale_factor),
+            (400.0, 300.5, 2.0)
+        );
+
+        let tiny = App::logical_size_for(PhysicalSize::new(1, 1), 3.0).unwrap();
+        assert_eq!((tiny.width, tiny.height), (1.0, 1.0));
+
+        for bad_scale in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            let size = App::logical_size_for(PhysicalSize::new(300, 200), bad_scale).unwrap();
+            assert_eq!(
+                (size.width, size.height, size.scale_factor),
+                (300.0, 200.0, 1.0)
+            );
+        }
+    }
+
+    #[test]
+    fn zoom_steps_through_levels_and_stops_at_the_ends() {
+        assert_eq!(App::next_zoom(1.0, true), 1.1);
+        assert_eq!(App::next_zoom(1.0, false), 0.9);
+        assert_eq!(App::next_zoom(3.0, true), 3.0);
+        assert_eq!(App::next_zoom(0.5, false), 0.5);
+        // Off-level zooms snap to the neighbouring level.
+        assert_eq!(App::next_zoom(1.3, true), 1.5);
+        assert_eq!(App::next_zoom(1.3, false), 1.25);
+    }
 }
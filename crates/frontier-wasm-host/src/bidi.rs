@@ -0,0 +1,305 @@
+//! Bidirectional text: the visual order of a line that mixes left-to-right and
+//! right-to-left scripts, so Hebrew and Arabic read correctly instead of in memory order.
+//!
+//! This follows the Unicode Bidirectional Algorithm (UAX #9) for what guests draw: strong
+//! letters, European and Arabic-Indic numbers with their separators and terminators,
+//! neutrals between them, trailing whitespace and mirrored brackets. Explicit embedding
+//! and isolate controls are not interpreted; LRM and RLM work as strong marks. Character
+//! classes come from script ranges rather than the full Unicode database, which is enough
+//! for the bundled fonts. Arabic letters are not joined into their contextual forms.
+
+/// Reading direction of a paragraph.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TextDirection {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+impl TextDirection {
+    /// The direction of the first strong letter in `text`, left-to-right when there is none
+    /// (rules P2 and P3).
+    pub fn detect(text: &str) -> Self {
+        text.chars()
+            .find_map(|ch| match class(ch) {
+                Class::L => Some(Self::Ltr),
+                Class::R => Some(Self::Rtl),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    fn level(self) -> u8 {
+        match self {
+            Self::Ltr => 0,
+            Self::Rtl => 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Class {
+    /// Strong left-to-right.
+    L,
+    /// Strong right-to-left.
+    R,
+    /// European number.
+    En,
+    /// Arabic number.
+    An,
+    /// Separator within a number: `+ - , . : /`.
+    Sep,
+    /// Terminator next to a number: currency, `%`, `#` and the like.
+    Term,
+    /// Combining mark, which takes the class of what it combines with.
+    Nsm,
+    Whitespace,
+    /// Other neutrals: punctuation and symbols.
+    On,
+}
+
+fn class(ch: char) -> Class {
+    match ch {
+        '0'..='9' | '\u{06F0}'..='\u{06F9}' => Class::En,
+        '\u{0660}'..='\u{0669}' | '\u{066B}'..='\u{066C}' => Class::An,
+        '+' | '-' | ',' | '.' | ':' | '/' => Class::Sep,
+        '#' | '$' | '%' | '°' | '¢' | '£' | '¥' | '€' | '‰' | '\u{066A}' => Class::Term,
+        '\u{200E}' => Class::L,
+        '\u{200F}' => Class::R,
+        '\u{0300}'..='\u{036F}'
+        | '\u{0483}'..='\u{0489}'
+        | '\u{0591}'..='\u{05BD}'
+        | '\u{05BF}'
+        | '\u{05C1}'..='\u{05C2}'
+        | '\u{05C4}'..='\u{05C5}'
+        | '\u{05C7}'
+        | '\u{0610}'..='\u{061A}'
+        | '\u{064B}'..='\u{065F}'
+        | '\u{0670}'
+        | '\u{06D6}'..='\u{06DC}'
+        | '\u{06DF}'..='\u{06E4}'
+        | '\u{06E7}'..='\u{06E8}'
+        | '\u{06EA}'..='\u{06ED}' => Class::Nsm,
+        '\u{0590}'..='\u{08FF}'
+        | '\u{FB1D}'..='\u{FDFF}'
+        | '\u{FE70}'..='\u{FEFF}'
+        | '\u{10800}'..='\u{10FFF}'
+        | '\u{1E800}'..='\u{1EFFF}' => Class::R,
+        _ if ch.is_whitespace() => Class::Whitespace,
+        _ if ch.is_alphanumeric() => Class::L,
+        _ => Class::On,
+    }
+}
+
+/// Whether `ch` can move when a left-to-right line is put in visual order: right-to-left
+/// letters, and Arabic-Indic digits, which stay left-to-right among themselves but move as
+/// a group.
+pub fn is_reordered(ch: char) -> bool {
+    matches!(class(ch), Class::R | Class::An)
+}
+
+/// Bracket pairs drawn swapped in right-to-left text (rule L4).
+fn mirror(ch: char) -> char {
+    match ch {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        '‹' => '›',
+        '›' => '‹',
+        _ => ch,
+    }
+}
+
+/// A character of a line in visual position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VisualChar {
+    /// Position of the character within the line, in logical order.
+    pub index: usize,
+    /// The character as drawn: brackets are mirrored in right-to-left runs.
+    pub ch: char,
+    /// Whether it reads right-to-left, so its leading edge is on the right.
+    pub rtl: bool,
+}
+
+/// The characters of one line, which must not contain a line break, left to right as they
+/// are drawn in a paragraph of `direction`.
+pub fn visual_order(line: &[char], direction: TextDirection) -> Vec<VisualChar> {
+    let levels = levels(line, direction.level());
+    let mut order: Vec<usize> = (0..line.len()).collect();
+    // Rule L2: from the highest level down to the lowest odd one, reverse every run at
+    // that level or above.
+    let highest = levels.iter().copied().max().unwrap_or(0);
+    let lowest_odd = levels.iter().copied().min().unwrap_or(0) | 1;
+    for level in (lowest_odd..=highest).rev() {
+        let mut start = 0;
+        while start < order.len() {
+            if levels[order[start]] < level {
+                start += 1;
+                continue;
+            }
+            let mut end = start;
+            while end < order.len() && levels[order[end]] >= level {
+                end += 1;
+            }
+            order[start..end].reverse();
+            start = end;
+        }
+    }
+    order
+        .into_iter()
+        .map(|index| {
+            let rtl = levels[index] % 2 == 1;
+            VisualChar {
+                index,
+                ch: if rtl {
+                    mirror(line[index])
+                } else {
+                    line[index]
+                },
+                rtl,
+            }
+        })
+        .collect()
+}
+
+/// Embedding level of each character of `line` in a paragraph at level `base`.
+fn levels(line: &[char], base: u8) -> Vec<u8> {
+    let start_class = if base == 0 { Class::L } else { Class::R };
+    let mut classes: Vec<Class> = line.iter().map(|&ch| class(ch)).collect();
+
+    // W1: combining marks take the class of the character before them.
+    let mut previous = start_class;
+    for class in &mut classes {
+        if *class == Class::Nsm {
+            *class = match previous {
+                Class::Whitespace => Class::On,
+                previous => previous,
+            };
+        }
+        previous = *class;
+    }
+    // W4: a single separator between two numbers of the same kind joins them.
+    for i in 1..classes.len().saturating_sub(1) {
+        let (before, after) = (classes[i - 1], classes[i + 1]);
+        if classes[i] == Class::Sep
+            && before == after
+            && (before == Class::En || (before == Class::An && matches!(line[i], ',' | ':')))
+        {
+            classes[i] = before;
+        }
+    }
+    // W5: terminators next to a European number belong to it.
+    for i in 0..classes.len() {
+        if classes[i] != Class::En {
+            continue;
+        }
+        for j in (0..i).rev() {
+            if classes[j] != Class::Term {
+                break;
+            }
+            classes[j] = Class::En;
+        }
+        for class in &mut classes[i + 1..] {
+            if *class != Class::Term {
+                break;
+            }
+            *class = Class::En;
+        }
+    }
+    // W6 and W7: leftover separators and terminators are neutral, and European numbers
+    // in left-to-right text are left-to-right.
+    let mut strong = start_class;
+    for class in &mut classes {
+        match *class {
+            Class::Sep | Class::Term => *class = Class::On,
+            Class::En if strong == Class::L => *class = Class::L,
+            Class::L | Class::R => strong = *class,
+            _ => {}
+        }
+    }
+    // N1 and N2: neutrals between text of one direction take it, numbers counting as
+    // right-to-left; other neutrals take the paragraph's direction.
+    let direction = |class: Class| match class {
+        Class::L => Some(Class::L),
+        Class::R | Class::En | Class::An => Some(Class::R),
+        _ => None,
+    };
+    let mut i = 0;
+    while i < classes.len() {
+        if direction(classes[i]).is_some() {
+            i += 1;
+            continue;
+        }
+        let run_start = i;
+        while i < classes.len() && direction(classes[i]).is_none() {
+            i += 1;
+        }
+        let before = match run_start {
+            0 => start_class,
+            _ => direction(classes[run_start - 1]).unwrap_or(start_class),
+        };
+        let after = classes
+            .get(i)
+            .and_then(|&class| direction(class))
+            .unwrap_or(start_class);
+        let resolved = if before == after { before } else { start_class };
+        for class in &mut classes[run_start..i] {
+            *class = resolved;
+        }
+    }
+
+    // I1 and I2, then L1: trailing whitespace goes back to the paragraph level.
+    let mut levels: Vec<u8> = classes
+        .iter()
+        .map(|class| match (base % 2, class) {
+            (0, Class::R) => base + 1,
+            (0, Class::En | Class::An) => base + 2,
+            (1, Class::L | Class::En | Class::An) => base + 1,
+            _ => base,
+        })
+        .collect();
+    for (level, ch) in levels.iter_mut().zip(line).rev() {
+        if !ch.is_whitespace() {
+            break;
+        }
+        *level = base;
+    }
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{visual_order, TextDirection};
+
+    fn visual(text: &str, direction: TextDirection) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        visual_order(&chars, direction)
+            .into_iter()
+            .map(|visual| visual.ch)
+            .collect()
+    }
+
+    #[test]
+    fn reorders_right_to_left_runs_and_keeps_numbers_readable() {
+        use TextDirection::{Ltr, Rtl};
+
+        assert_eq!(visual("abc", Ltr), "abc");
+        assert_eq!(visual("שלום", Ltr), "םולש");
+        assert_eq!(visual("go to שלום world", Ltr), "go to םולש world");
+        // Numbers and their punctuation read left to right inside right-to-left text.
+        assert_eq!(visual("מחיר 12.50$", Rtl), "12.50$ ריחמ");
+        assert_eq!(visual("abc (שלום)", Rtl), "(םולש) abc");
+        assert_eq!(visual("(שלום)", Ltr), "(םולש)");
+
+        assert_eq!(TextDirection::detect("123 שלום abc"), Rtl);
+        assert_eq!(TextDirection::detect("123 ..."), Ltr);
+    }
+}
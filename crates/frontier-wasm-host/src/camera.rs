@@ -0,0 +1,286 @@
+//! Webcam capture behind `open-camera`. A device is only opened once the user allows it,
+//! either up front with `--camera allow` or by answering the prompt the host shows in the
+//! window; the answer holds for the rest of the session. Capture runs on a background
+//! thread that keeps the newest frame as an `Image` for `draw-camera`.
+//!
+//! Devices are captured through Video4Linux2 when the host is built with
+//! `--features camera` on Linux; other builds list no cameras.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::images::Image;
+
+#[cfg(all(feature = "camera", target_os = "linux"))]
+mod v4l2;
+#[cfg(all(feature = "camera", target_os = "linux"))]
+use v4l2 as backend;
+
+/// Whether this build can capture from cameras at all.
+pub const SUPPORTED: bool = cfg!(all(feature = "camera", target_os = "linux"));
+
+/// How `open-camera` requests are answered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CameraAccess {
+    /// Show a prompt in the window and wait for the user.
+    #[default]
+    Ask,
+    Allow,
+    Deny,
+}
+
+/// A capture device, as listed by `list-cameras`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CameraDevice {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Where an opened camera is in its life.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CameraStatus {
+    /// Waiting for the user to answer the permission prompt.
+    Pending,
+    Streaming,
+    Denied,
+    /// The device could not be started or stopped delivering frames.
+    Failed,
+}
+
+/// The newest frame from a camera. `sequence` grows by at least one per new frame.
+#[derive(Clone, Debug)]
+pub struct CameraFrame {
+    pub image: Image,
+    pub sequence: u64,
+}
+
+enum CameraState {
+    Pending,
+    Denied,
+    Failed,
+    Streaming(backend::Capture),
+}
+
+struct OpenCamera {
+    device: CameraDevice,
+    state: CameraState,
+}
+
+/// The cameras a guest has opened, and the session's permission answer.
+#[derive(Default)]
+pub struct Cameras {
+    access: CameraAccess,
+    /// Devices found by the last `list`; `open` indexes into it.
+    devices: Vec<CameraDevice>,
+    open: HashMap<u32, OpenCamera>,
+    next_id: u32,
+}
+
+impl fmt::Debug for Cameras {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cameras")
+            .field("access", &self.access)
+            .field("devices", &self.devices)
+            .field("open", &self.open.len())
+            .finish()
+    }
+}
+
+impl Cameras {
+    pub fn set_access(&mut self, access: CameraAccess) {
+        self.access = access;
+    }
+
+    /// Whether `open-camera` can ever succeed this session.
+    pub fn available(&self) -> bool {
+        SUPPORTED && self.access != CameraAccess::Deny
+    }
+
+    /// Rescan for devices and return their names in `open` order.
+    pub fn list(&mut self) -> Vec<String> {
+        self.devices = backend::list();
+        self.devices
+            .iter()
+            .map(|device| device.name.clone())
+            .collect()
+    }
+
+    /// Open the `index`th device from the last `list`. The camera starts out pending,
+    /// streaming or denied depending on the session's access setting.
+    pub fn open(&mut self, index: u32) -> Result<u32, String> {
+        if !SUPPORTED {
+            return Err("this host was built without camera support".to_string());
+        }
+        if self.devices.is_empty() {
+            self.devices = backend::list();
+        }
+        let Some(device) = self.devices.get(index as usize).cloned() else {
+            return Err(format!("no camera at index {index}"));
+        };
+        let id = self.next_id;
+        self.next_id = self
+            .next_id
+            .checked_add(1)
+            .ok_or_else(|| "camera ids are exhausted".to_string())?;
+        let state = match self.access {
+            CameraAccess::Ask => CameraState::Pending,
+            CameraAccess::Allow => start(&device),
+            CameraAccess::Deny => CameraState::Denied,
+        };
+        self.open.insert(id, OpenCamera { device, state });
+        Ok(id)
+    }
+
+    pub fn status(&self, id: u32) -> Option<CameraStatus> {
+        Some(match &self.open.get(&id)?.state {
+            CameraState::Pending => CameraStatus::Pending,
+            CameraState::Denied => CameraStatus::Denied,
+            CameraState::Failed => CameraStatus::Failed,
+            CameraState::Streaming(capture) if capture.has_failed() => CameraStatus::Failed,
+            CameraState::Streaming(_) => CameraStatus::Streaming,
+        })
+    }
+
+    /// The newest frame, once the camera has delivered one.
+    pub fn frame(&self, id: u32) -> Option<CameraFrame> {
+        match &self.open.get(&id)?.state {
+            CameraState::Streaming(capture) => capture.latest(),
+            _ => None,
+        }
+    }
+
+    /// Stop capturing and forget `id`.
+    pub fn close(&mut self, id: u32) -> bool {
+        self.open.remove(&id).is_some()
+    }
+
+    /// Stop capturing from every open camera.
+    pub fn close_all(&mut self) {
+        self.open.clear();
+    }
+
+    /// Name of a device waiting on the permission prompt, if any.
+    pub fn prompt(&self) -> Option<&str> {
+        self.open
+            .values()
+            .find(|camera| matches!(camera.state, CameraState::Pending))
+            .map(|camera| camera.device.name.as_str())
+    }
+
+    /// Answer the permission prompt for every pending camera and for the rest of the
+    /// session.
+    pub fn answer(&mut self, allow: bool) {
+        self.access = if allow {
+            CameraAccess::Allow
+        } else {
+            CameraAccess::Deny
+        };
+        for camera in self.open.values_mut() {
+            if matches!(camera.state, CameraState::Pending) {
+                camera.state = if allow {
+                    start(&camera.device)
+                } else {
+                    CameraState::Denied
+                };
+            }
+        }
+    }
+}
+
+fn start(device: &CameraDevice) -> CameraState {
+    match backend::Capture::start(&device.path) {
+        Ok(capture) => {
+            tracing::info!(camera = %device.name, "camera capture started");
+            CameraState::Streaming(capture)
+        }
+        Err(err) => {
+            tracing::warn!(camera = %device.name, error = %format!("{err:#}"), "camera failed to start");
+            CameraState::Failed
+        }
+    }
+}
+
+/// Convert packed YUYV 4:2:2 (BT.601, limited range) to RGBA8.
+pub fn yuyv_to_rgba(yuyv: &[u8], width: u32, height: u32) -> Option<Vec<u8>> {
+    let pixels = width as usize * height as usize;
+    if !width.is_multiple_of(2) || yuyv.len() < pixels * 2 {
+        return None;
+    }
+    let mut rgba = Vec::with_capacity(pixels * 4);
+    for chunk in yuyv[..pixels * 2].chunks_exact(4) {
+        let (u, v) = (chunk[1] as f32 - 128.0, chunk[3] as f32 - 128.0);
+        for y in [chunk[0], chunk[2]] {
+            let y = (y as f32 - 16.0) * 1.164;
+            let channel = |value: f32| value.clamp(0.0, 255.0).round() as u8;
+            rgba.extend_from_slice(&[
+                channel(y + 1.596 * v),
+                channel(y - 0.392 * u - 0.813 * v),
+                channel(y + 2.017 * u),
+                255,
+            ]);
+        }
+    }
+    Some(rgba)
+}
+
+/// Stand-in for builds without a capture backend: no devices, nothing to start.
+#[cfg(not(all(feature = "camera", target_os = "linux")))]
+mod backend {
+    use std::path::Path;
+
+    use anyhow::{bail, Result};
+
+    use super::{CameraDevice, CameraFrame};
+
+    pub fn list() -> Vec<CameraDevice> {
+        Vec::new()
+    }
+
+    pub struct Capture;
+
+    impl Capture {
+        pub fn start(_path: &Path) -> Result<Self> {
+            bail!("this host was built without camera support")
+        }
+
+        pub fn latest(&self) -> Option<CameraFrame> {
+            None
+        }
+
+        pub fn has_failed(&self) -> bool {
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{yuyv_to_rgba, CameraAccess, Cameras, SUPPORTED};
+
+    #[test]
+    fn converts_yuyv_pairs() {
+        // Black, white, then a saturated red pair.
+        let rgba = yuyv_to_rgba(&[16, 128, 235, 128, 81, 90, 81, 240], 4, 1).unwrap();
+        assert_eq!(rgba[..8], [0, 0, 0, 255, 255, 255, 255, 255]);
+        assert_eq!(rgba[8..12], [254, 0, 0, 255]);
+        assert!(yuyv_to_rgba(&[0; 6], 3, 1).is_none(), "odd width");
+        assert!(yuyv_to_rgba(&[0; 4], 2, 2).is_none(), "short buffer");
+    }
+
+    #[test]
+    fn denied_sessions_never_start_capture() {
+        let mut cameras = Cameras::default();
+        cameras.set_access(CameraAccess::Deny);
+        if !SUPPORTED {
+            assert!(cameras.list().is_empty());
+            assert!(cameras
+                .open(0)
+                .unwrap_err()
+                .contains("without camera support"));
+        }
+        assert!(cameras.prompt().is_none());
+        assert!(cameras.status(0).is_none());
+        assert!(!cameras.close(0));
+    }
+}
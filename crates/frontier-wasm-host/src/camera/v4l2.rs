@@ -0,0 +1,410 @@
+//! Video4Linux2 capture: memory-mapped streaming of YUYV frames, which every UVC webcam
+//! offers. Struct layouts follow `linux/videodev2.h` on 64-bit targets.
+
+use std::ffi::CString;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::{io, mem, ptr, slice, thread};
+
+use anyhow::{bail, Context, Result};
+
+use super::{yuyv_to_rgba, CameraDevice, CameraFrame};
+use crate::images::Image;
+
+const CAP_VIDEO_CAPTURE: u32 = 0x0000_0001;
+const CAP_STREAMING: u32 = 0x0400_0000;
+const CAP_DEVICE_CAPS: u32 = 0x8000_0000;
+const BUF_TYPE_VIDEO_CAPTURE: u32 = 1;
+const MEMORY_MMAP: u32 = 1;
+const FIELD_NONE: u32 = 1;
+const PIX_FMT_YUYV: u32 = u32::from_le_bytes(*b"YUYV");
+const REQUESTED_SIZE: (u32, u32) = (640, 480);
+const BUFFER_COUNT: u32 = 4;
+/// How long the capture thread waits for a frame before checking whether to stop.
+const POLL_TIMEOUT_MS: i32 = 100;
+
+#[repr(C)]
+struct Capability {
+    driver: [u8; 16],
+    card: [u8; 32],
+    bus_info: [u8; 32],
+    version: u32,
+    capabilities: u32,
+    device_caps: u32,
+    reserved: [u32; 3],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct PixFormat {
+    width: u32,
+    height: u32,
+    pixelformat: u32,
+    field: u32,
+    bytesperline: u32,
+    sizeimage: u32,
+    colorspace: u32,
+    private: u32,
+    flags: u32,
+    ycbcr_enc: u32,
+    quantization: u32,
+    xfer_func: u32,
+}
+
+/// `struct v4l2_format`; the union is 200 bytes and 8-byte aligned.
+#[repr(C)]
+struct Format {
+    kind: u32,
+    fmt: FormatUnion,
+}
+
+#[repr(C)]
+union FormatUnion {
+    pix: PixFormat,
+    raw: [u64; 25],
+}
+
+#[repr(C)]
+struct RequestBuffers {
+    count: u32,
+    kind: u32,
+    memory: u32,
+    capabilities: u32,
+    flags: u32,
+}
+
+#[repr(C)]
+struct Buffer {
+    index: u32,
+    kind: u32,
+    bytesused: u32,
+    flags: u32,
+    field: u32,
+    timestamp: libc::timeval,
+    timecode: [u32; 4],
+    sequence: u32,
+    memory: u32,
+    offset: BufferLocation,
+    length: u32,
+    reserved2: u32,
+    request_fd: u32,
+}
+
+#[repr(C)]
+union BufferLocation {
+    offset: u32,
+    userptr: libc::c_ulong,
+}
+
+const fn ioc(dir: u64, nr: u64, size: usize) -> u64 {
+    (dir << 30) | ((size as u64) << 16) | ((b'V' as u64) << 8) | nr
+}
+
+const READ: u64 = 2;
+const WRITE: u64 = 1;
+const VIDIOC_QUERYCAP: u64 = ioc(READ, 0, mem::size_of::<Capability>());
+const VIDIOC_S_FMT: u64 = ioc(READ | WRITE, 5, mem::size_of::<Format>());
+const VIDIOC_REQBUFS: u64 = ioc(READ | WRITE, 8, mem::size_of::<RequestBuffers>());
+const VIDIOC_QUERYBUF: u64 = ioc(READ | WRITE, 9, mem::size_of::<Buffer>());
+const VIDIOC_QBUF: u64 = ioc(READ | WRITE, 15, mem::size_of::<Buffer>());
+const VIDIOC_DQBUF: u64 = ioc(READ | WRITE, 17, mem::size_of::<Buffer>());
+const VIDIOC_STREAMON: u64 = ioc(WRITE, 18, mem::size_of::<libc::c_int>());
+const VIDIOC_STREAMOFF: u64 = ioc(WRITE, 19, mem::size_of::<libc::c_int>());
+
+const _: () = assert!(mem::size_of::<Format>() == 208 && mem::size_of::<Buffer>() == 88);
+
+/// An open device node, closed on drop.
+struct Device {
+    fd: libc::c_int,
+}
+
+impl Device {
+    fn open(path: &Path) -> Result<Self> {
+        let path = CString::new(path.as_os_str().as_bytes())?;
+        // SAFETY: `path` is a valid NUL-terminated string.
+        let fd = unsafe { libc::open(path.as_ptr(), libc::O_RDWR | libc::O_NONBLOCK) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(Self { fd })
+    }
+
+    /// Issue `request` with `arg`, retrying on `EINTR`.
+    fn ioctl<T>(&self, request: u64, arg: &mut T) -> io::Result<()> {
+        loop {
+            // SAFETY: every request constant encodes the size of the `T` it is used with.
+            let result = unsafe { libc::ioctl(self.fd, request as _, arg as *mut T) };
+            if result >= 0 {
+                return Ok(());
+            }
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+    }
+
+    fn capability(&self) -> io::Result<Capability> {
+        // SAFETY: `Capability` is plain old data; all-zero is a valid value.
+        let mut capability: Capability = unsafe { mem::zeroed() };
+        self.ioctl(VIDIOC_QUERYCAP, &mut capability)?;
+        Ok(capability)
+    }
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        // SAFETY: `fd` was opened by `Device::open` and is closed only here.
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+/// `/dev/video*` nodes that capture video through streaming I/O.
+pub fn list() -> Vec<CameraDevice> {
+    let Ok(entries) = fs::read_dir("/dev") else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("video"))
+        })
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let capability = Device::open(&path).ok()?.capability().ok()?;
+            let caps = if capability.capabilities & CAP_DEVICE_CAPS != 0 {
+                capability.device_caps
+            } else {
+                capability.capabilities
+            };
+            let wanted = CAP_VIDEO_CAPTURE | CAP_STREAMING;
+            if caps & wanted != wanted {
+                return None;
+            }
+            let end = capability.card.iter().position(|&b| b == 0).unwrap_or(32);
+            Some(CameraDevice {
+                name: String::from_utf8_lossy(&capability.card[..end]).into_owned(),
+                path,
+            })
+        })
+        .collect()
+}
+
+/// A driver buffer mapped into our address space, unmapped on drop.
+struct Mapping {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+// SAFETY: the mapping is only read by the capture thread that owns it.
+unsafe impl Send for Mapping {}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` and `len` came from a successful `mmap`.
+        unsafe { libc::munmap(self.ptr, self.len) };
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    latest: Mutex<Option<CameraFrame>>,
+    stop: AtomicBool,
+    failed: AtomicBool,
+}
+
+/// A streaming camera. Dropping it stops the capture thread and releases the device.
+pub struct Capture {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Capture {
+    pub fn start(path: &Path) -> Result<Self> {
+        let device =
+            Device::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        let (width, height) = set_format(&device)?;
+        let buffers = map_buffers(&device)?;
+        let mut kind = BUF_TYPE_VIDEO_CAPTURE as libc::c_int;
+        device
+            .ioctl(VIDIOC_STREAMON, &mut kind)
+            .context("VIDIOC_STREAMON failed")?;
+        let shared = Arc::new(Shared::default());
+        let thread_shared = shared.clone();
+        let thread = thread::Builder::new()
+            .name("camera-capture".to_string())
+            .spawn(move || {
+                if let Err(err) = capture_loop(&device, &buffers, (width, height), &thread_shared) {
+                    tracing::warn!(error = %format!("{err:#}"), "camera capture stopped");
+                    thread_shared.failed.store(true, Ordering::Relaxed);
+                }
+                let mut kind = BUF_TYPE_VIDEO_CAPTURE as libc::c_int;
+                let _ = device.ioctl(VIDIOC_STREAMOFF, &mut kind);
+                drop(buffers);
+            })?;
+        Ok(Self {
+            shared,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn latest(&self) -> Option<CameraFrame> {
+        self.shared.latest.lock().ok()?.clone()
+    }
+
+    pub fn has_failed(&self) -> bool {
+        self.shared.failed.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Ask for YUYV at `REQUESTED_SIZE`; returns the size the driver settled on.
+fn set_format(device: &Device) -> Result<(u32, u32)> {
+    // SAFETY: `Format` is plain old data; all-zero is a valid value.
+    let mut format: Format = unsafe { mem::zeroed() };
+    format.kind = BUF_TYPE_VIDEO_CAPTURE;
+    format.fmt.pix = PixFormat {
+        width: REQUESTED_SIZE.0,
+        height: REQUESTED_SIZE.1,
+        pixelformat: PIX_FMT_YUYV,
+        field: FIELD_NONE,
+        // SAFETY: as above.
+        ..unsafe { mem::zeroed() }
+    };
+    device
+        .ioctl(VIDIOC_S_FMT, &mut format)
+        .context("VIDIOC_S_FMT failed")?;
+    // SAFETY: the driver fills in the `pix` member for capture buffers.
+    let pix = unsafe { format.fmt.pix };
+    if pix.pixelformat != PIX_FMT_YUYV {
+        bail!("camera does not offer YUYV frames");
+    }
+    if pix.bytesperline != 0 && pix.bytesperline != pix.width * 2 {
+        bail!("camera pads its rows, which is not supported");
+    }
+    Ok((pix.width, pix.height))
+}
+
+fn map_buffers(device: &Device) -> Result<Vec<Mapping>> {
+    let mut request = RequestBuffers {
+        count: BUFFER_COUNT,
+        kind: BUF_TYPE_VIDEO_CAPTURE,
+        memory: MEMORY_MMAP,
+        capabilities: 0,
+        flags: 0,
+    };
+    device
+        .ioctl(VIDIOC_REQBUFS, &mut request)
+        .context("VIDIOC_REQBUFS failed")?;
+    if request.count == 0 {
+        bail!("camera offered no capture buffers");
+    }
+    (0..request.count)
+        .map(|index| {
+            let mut buffer = empty_buffer(index);
+            device
+                .ioctl(VIDIOC_QUERYBUF, &mut buffer)
+                .context("VIDIOC_QUERYBUF failed")?;
+            let len = buffer.length as usize;
+            // SAFETY: the driver reported `offset` and `length` for this mmap buffer.
+            let ptr = unsafe {
+                libc::mmap(
+                    ptr::null_mut(),
+                    len,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_SHARED,
+                    device.fd,
+                    buffer.offset.offset as libc::off_t,
+                )
+            };
+            if ptr == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error()).context("mmap failed");
+            }
+            let mapping = Mapping { ptr, len };
+            device
+                .ioctl(VIDIOC_QBUF, &mut buffer)
+                .context("VIDIOC_QBUF failed")?;
+            Ok(mapping)
+        })
+        .collect()
+}
+
+fn empty_buffer(index: u32) -> Buffer {
+    // SAFETY: `Buffer` is plain old data; all-zero is a valid value.
+    let mut buffer: Buffer = unsafe { mem::zeroed() };
+    buffer.index = index;
+    buffer.kind = BUF_TYPE_VIDEO_CAPTURE;
+    buffer.memory = MEMORY_MMAP;
+    buffer
+}
+
+fn capture_loop(
+    device: &Device,
+    buffers: &[Mapping],
+    (width, height): (u32, u32),
+    shared: &Shared,
+) -> Result<()> {
+    let mut sequence = 0u64;
+    while !shared.stop.load(Ordering::Relaxed) {
+        let mut poll = libc::pollfd {
+            fd: device.fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `poll` points at one valid `pollfd`.
+        let ready = unsafe { libc::poll(&mut poll, 1, POLL_TIMEOUT_MS) };
+        if ready < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(err).context("poll failed");
+        }
+        if ready == 0 {
+            continue;
+        }
+        let mut buffer = empty_buffer(0);
+        match device.ioctl(VIDIOC_DQBUF, &mut buffer) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => continue,
+            Err(err) => return Err(err).context("VIDIOC_DQBUF failed"),
+        }
+        let mapping = buffers
+            .get(buffer.index as usize)
+            .context("driver returned an unknown buffer")?;
+        let used = (buffer.bytesused as usize).min(mapping.len);
+        // SAFETY: the driver filled `used` bytes of this mapping and will not touch it
+        // until it is queued again below.
+        let yuyv = unsafe { slice::from_raw_parts(mapping.ptr as *const u8, used) };
+        let image =
+            yuyv_to_rgba(yuyv, width, height).and_then(|rgba| Image::new(width, height, rgba));
+        device
+            .ioctl(VIDIOC_QBUF, &mut buffer)
+            .context("VIDIOC_QBUF failed")?;
+        if let Some(image) = image {
+            sequence += 1;
+            if let Ok(mut latest) = shared.latest.lock() {
+                *latest = Some(CameraFrame { image, sequence });
+            }
+        }
+    }
+    Ok(())
+}
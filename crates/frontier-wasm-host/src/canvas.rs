@@ -0,0 +1,124 @@
+//! The drawing primitives a renderer has to provide to show a guest's frame. Vello
+//! ([`SceneEncoder`](crate::graphics::SceneEncoder)) draws the windowed host on the GPU;
+//! [`SoftwareCanvas`](crate::software::SoftwareCanvas) rasterises on the CPU for tests and
+//! machines without a usable wgpu adapter.
+
+use crate::geometry::Vec2;
+use crate::host::{Color, DrawCommand, FontFamily, PatternRepeat, TextFlow};
+use crate::images::{Image, ImageStyle};
+use crate::lottie::Animation;
+use crate::particles::{ParticleBatch, ParticleSprite};
+use crate::svg::Picture;
+
+/// Positions and sizes are in logical pixels; backends apply their own scale factor.
+pub trait CanvasBackend {
+    fn fill_rect(&mut self, origin: Vec2, size: Vec2, color: Color);
+
+    /// `text` in visual order from [`bidi::visual_order`](crate::bidi::visual_order), each
+    /// line starting at `origin` when `flow.direction` is left-to-right and ending there when
+    /// it is right-to-left, or in columns for vertical text; then turned about `origin`.
+    fn draw_text(
+        &mut self,
+        text: &str,
+        origin: Vec2,
+        size: f32,
+        color: Color,
+        font: FontFamily,
+        flow: TextFlow,
+    );
+
+    /// Scale `image` to cover the rectangle.
+    fn draw_image(&mut self, image: &Image, origin: Vec2, size: Vec2, style: &ImageStyle);
+
+    /// Tile `image` over the rectangle, each copy `tile_size` logical pixels.
+    fn draw_pattern(
+        &mut self,
+        origin: Vec2,
+        size: Vec2,
+        image: &Image,
+        tile_size: Vec2,
+        repeat: PatternRepeat,
+        style: &ImageStyle,
+    );
+
+    /// Scale `picture` from its intrinsic size to fill the rectangle.
+    fn draw_picture(&mut self, picture: &Picture, origin: Vec2, size: Vec2);
+
+    /// Render the frame of `animation` at `time_ms`, scaled like a picture.
+    fn draw_animation(&mut self, animation: &Animation, time_ms: f64, origin: Vec2, size: Vec2);
+
+    /// Each particle as a filled circle centred on its position.
+    fn draw_particles(&mut self, particles: &[ParticleSprite]);
+
+    /// An emitter's live particles. Backends without a compute pass work them out here.
+    fn draw_emitter(&mut self, particles: &ParticleBatch) {
+        let sprites: Vec<ParticleSprite> = particles.sprites().collect();
+        self.draw_particles(&sprites);
+    }
+
+    /// Clip what is drawn until the matching [`pop_clip`](Self::pop_clip) to the
+    /// rectangle, within any clip already open.
+    fn push_clip(&mut self, origin: Vec2, size: Vec2);
+
+    /// End the innermost clip; ignored when none is open.
+    fn pop_clip(&mut self);
+
+    /// End every clip still open, so one run of commands cannot clip the next.
+    fn close_clips(&mut self);
+
+    fn encode_command(&mut self, command: &DrawCommand) {
+        match command {
+            DrawCommand::FillRect {
+                origin,
+                size,
+                color,
+            } => self.fill_rect(*origin, *size, *color),
+            DrawCommand::DrawText {
+                text,
+                origin,
+                size,
+                color,
+                font,
+                flow,
+            } => self.draw_text(text.as_str(), *origin, *size, *color, *font, *flow),
+            DrawCommand::DrawPicture {
+                picture,
+                origin,
+                size,
+            } => self.draw_picture(picture, *origin, *size),
+            DrawCommand::DrawAnimation {
+                animation,
+                time_ms,
+                origin,
+                size,
+            } => self.draw_animation(animation, *time_ms, *origin, *size),
+            DrawCommand::DrawImage {
+                image,
+                origin,
+                size,
+                style,
+            } => self.draw_image(image, *origin, *size, style),
+            DrawCommand::FillPattern {
+                origin,
+                size,
+                image,
+                tile_size,
+                repeat,
+                style,
+            } => self.draw_pattern(*origin, *size, image, *tile_size, *repeat, style),
+            // Layers are split out by `effects::layer_runs` before encoding.
+            DrawCommand::PushShaderLayer { .. } | DrawCommand::PopShaderLayer => {}
+            DrawCommand::DrawParticles { particles } => self.draw_particles(particles),
+            DrawCommand::PushClip { origin, size } => self.push_clip(*origin, *size),
+            DrawCommand::PopClip => self.pop_clip(),
+            DrawCommand::DrawEmitter { particles } => self.draw_emitter(particles),
+        }
+    }
+
+    fn encode_commands(&mut self, commands: &[DrawCommand]) {
+        for command in commands {
+            self.encode_command(command);
+        }
+        self.close_clips();
+    }
+}
@@ -0,0 +1,108 @@
+//! `drop-capability`: a guest giving up host features it no longer needs, e.g. storage
+//! once its settings are loaded, so a bug or a compromised dependency later on cannot use
+//! them. A dropped capability stays dropped until the instance is replaced by a reload or
+//! `navigate`, and the tasks and workers the instance starts afterwards lack it too.
+
+use std::collections::BTreeSet;
+
+use anyhow::{bail, Result};
+
+/// Names from `host-capabilities` that only affect what the guest can draw; dropping them
+/// would protect nothing.
+const DRAWING_FEATURES: &[&str] = &[
+    "images",
+    "svg",
+    "lottie",
+    "particles",
+    "shaders",
+    "pixel-buffers",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Capability {
+    Camera,
+    ExportFrame,
+    Haptics,
+    Navigation,
+    /// Never granted: WASI sockets here refuse every address. Accepted so a guest written
+    /// for hosts that do allow it can drop it unconditionally.
+    Network,
+    PowerState,
+    /// `storage-*` and the `database` interface.
+    Storage,
+}
+
+impl Capability {
+    pub fn parse(name: &str) -> Result<Self> {
+        Ok(match name {
+            "camera" => Self::Camera,
+            "export-frame" => Self::ExportFrame,
+            "haptics" => Self::Haptics,
+            "navigation" => Self::Navigation,
+            "net" => Self::Network,
+            "power-state" => Self::PowerState,
+            "storage" => Self::Storage,
+            _ if DRAWING_FEATURES.contains(&name) => {
+                bail!("'{name}' is a drawing feature and cannot be dropped")
+            }
+            _ => bail!("unknown capability '{name}'"),
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Camera => "camera",
+            Self::ExportFrame => "export-frame",
+            Self::Haptics => "haptics",
+            Self::Navigation => "navigation",
+            Self::Network => "net",
+            Self::PowerState => "power-state",
+            Self::Storage => "storage",
+        }
+    }
+}
+
+/// Capabilities an instance has given up.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DroppedCapabilities(BTreeSet<Capability>);
+
+impl DroppedCapabilities {
+    /// Record `capability` as dropped, returning false if it already was.
+    pub fn insert(&mut self, capability: Capability) -> bool {
+        self.0.insert(capability)
+    }
+
+    pub fn contains(&self, capability: Capability) -> bool {
+        self.0.contains(&capability)
+    }
+
+    /// Whether the `host-capabilities` entry `name` has been dropped.
+    pub fn contains_name(&self, name: &str) -> bool {
+        self.0.iter().any(|capability| capability.name() == name)
+    }
+
+    pub fn extend(&mut self, other: &Self) {
+        self.0.extend(other.0.iter().copied());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Capability, DroppedCapabilities};
+
+    #[test]
+    fn only_access_capabilities_can_be_dropped() {
+        let mut dropped = DroppedCapabilities::default();
+        assert!(dropped.insert(Capability::parse("storage").unwrap()));
+        assert!(!dropped.insert(Capability::parse("storage").unwrap()));
+        assert!(dropped.contains(Capability::Storage));
+        assert!(dropped.contains_name("storage"));
+        assert_eq!(Capability::parse("net").unwrap().name(), "net");
+
+        assert!(Capability::parse("images")
+            .unwrap_err()
+            .to_string()
+            .contains("drawing feature"));
+        assert!(Capability::parse("storge").is_err());
+    }
+}
@@ -0,0 +1,328 @@
+//! Bulk chart primitives. `draw-bars`, `draw-points` and `draw-heatmap` turn a whole series
+//! into a few draw commands on the host, so a dashboard plotting 100k values makes three
+//! calls rather than 100k. Geometry outside the viewport is culled before it is recorded,
+//! and bars thinner than a pixel are merged, so the command count follows the width of the
+//! chart on screen rather than the length of the series.
+
+use crate::component::vello::canvas::host::BarStyle as WitBarStyle;
+use crate::geometry::{Rect, Vec2};
+use crate::host::Color;
+use crate::images::{Image, MAX_IMAGE_DIMENSION};
+use crate::particles::ParticleSprite;
+
+/// How `draw-bars` maps values to bars; see `bar-style` in the WIT.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BarStyle {
+    pub color: Color,
+    /// Values at the bottom and top of the rectangle.
+    pub min: f32,
+    pub max: f32,
+    /// Value the bars grow from, up for larger values and down for smaller ones.
+    pub baseline: f32,
+    /// Fraction of each bar's slot left empty, split between its two sides.
+    pub gap: f32,
+}
+
+impl BarStyle {
+    pub fn from_wit(style: WitBarStyle) -> Self {
+        Self {
+            color: Color::from_wit(style.color),
+            min: style.min,
+            max: style.max,
+            baseline: style.baseline,
+            gap: if style.gap.is_finite() {
+                style.gap.clamp(0.0, 1.0)
+            } else {
+                0.0
+            },
+        }
+    }
+
+    /// A range that maps values to distinct heights.
+    pub fn is_valid(&self) -> bool {
+        self.min.is_finite()
+            && self.max.is_finite()
+            && self.baseline.is_finite()
+            && self.min != self.max
+    }
+}
+
+/// Indices `start..end` of the equal slots dividing `start_at..start_at + length` that
+/// overlap `visible_start..visible_end`.
+fn visible_slots(
+    count: usize,
+    start_at: f32,
+    length: f32,
+    visible_start: f32,
+    visible_end: f32,
+) -> (usize, usize) {
+    let slot = length / count as f32;
+    let index = |at: f32| ((at - start_at) / slot).clamp(0.0, count as f32);
+    (
+        index(visible_start).floor() as usize,
+        index(visible_end).ceil() as usize,
+    )
+}
+
+/// One rectangle per value, laid side by side across `rect`, left out where they fall
+/// outside `viewport`. Where bars are narrower than a logical pixel, each run of bars
+/// sharing a pixel becomes one rectangle spanning their extremes, so peaks survive.
+/// Non-finite values leave their slot empty.
+pub fn bars(values: &[f32], rect: Rect, style: &BarStyle, viewport: Option<Rect>) -> Vec<Rect> {
+    if values.is_empty() || rect.width <= 0.0 || rect.height <= 0.0 {
+        return Vec::new();
+    }
+    let slot = rect.width / values.len() as f32;
+    let (first, last) = match viewport {
+        Some(view) => visible_slots(
+            values.len(),
+            rect.x,
+            rect.width,
+            view.x,
+            view.x + view.width,
+        ),
+        None => (0, values.len()),
+    };
+    let merge = if slot < 1.0 {
+        (values.len() as f32 / rect.width).ceil() as usize
+    } else {
+        1
+    };
+    let bottom = rect.y + rect.height;
+    let height_of = |value: f32| {
+        let t = (value - style.min) / (style.max - style.min);
+        (bottom - t * rect.height).clamp(rect.y, bottom)
+    };
+    let base = height_of(style.baseline);
+    // Groups start at multiples of `merge`, so they stay put while the chart scrolls.
+    let mut start = first - first % merge;
+    let mut result = Vec::new();
+    while start < last {
+        let end = start.saturating_add(merge).min(values.len());
+        let (top, low) = values[start..end]
+            .iter()
+            .filter(|value| value.is_finite())
+            .map(|&value| height_of(value))
+            .fold((base, base), |(top, low), y| (top.min(y), low.max(y)));
+        let (x, width) = if merge == 1 {
+            (
+                rect.x + (start as f32 + style.gap * 0.5) * slot,
+                slot * (1.0 - style.gap),
+            )
+        } else {
+            (rect.x + start as f32 * slot, (end - start) as f32 * slot)
+        };
+        let bar = Rect::new(x, top, width, low - top);
+        if bar.height > 0.0 && viewport.is_none_or(|view| view.intersects(&bar)) {
+            result.push(bar);
+        }
+        start = end;
+    }
+    result
+}
+
+/// A circle of `radius` at each point, leaving out non-finite points and those entirely
+/// outside `viewport`.
+pub fn points(
+    points: &[Vec2],
+    radius: f32,
+    color: Color,
+    viewport: Option<Rect>,
+) -> Vec<ParticleSprite> {
+    let visible = |point: &&Vec2| {
+        point.is_finite()
+            && viewport.is_none_or(|view| {
+                point.x + radius > view.x
+                    && point.x - radius < view.x + view.width
+                    && point.y + radius > view.y
+                    && point.y - radius < view.y + view.height
+            })
+    };
+    points
+        .iter()
+        .filter(visible)
+        .map(|&position| ParticleSprite {
+            position,
+            size: radius * 2.0,
+            color,
+        })
+        .collect()
+}
+
+/// The colour `colormap` gives `t` from 0 to 1, its stops spread evenly and blended in
+/// between.
+fn sample(colormap: &[Color], t: f32) -> Color {
+    let position = t.clamp(0.0, 1.0) * (colormap.len() - 1) as f32;
+    let index = (position.floor() as usize).min(colormap.len() - 1);
+    let (from, to) = (
+        colormap[index],
+        colormap[(index + 1).min(colormap.len() - 1)],
+    );
+    let f = position - index as f32;
+    let lerp = |a: f32, b: f32| a + (b - a) * f;
+    Color {
+        r: lerp(from.r, to.r),
+        g: lerp(from.g, to.g),
+        b: lerp(from.b, to.b),
+        a: lerp(from.a, to.a),
+    }
+}
+
+/// `values` in rows of `columns` cells stretched over `rect`, coloured by `colormap` from
+/// `min` to `max`, as an image of the visible cells and the rectangle it covers. Cells
+/// past the end of `values` or holding a non-finite value are transparent. When more
+/// cells are visible along a side than an image may hold, every nth cell is kept.
+pub fn heatmap(
+    values: &[f32],
+    columns: usize,
+    (min, max): (f32, f32),
+    rect: Rect,
+    colormap: &[Color],
+    viewport: Option<Rect>,
+) -> Option<(Image, Rect)> {
+    if values.is_empty() || columns == 0 || colormap.is_empty() {
+        return None;
+    }
+    if rect.width <= 0.0 || rect.height <= 0.0 {
+        return None;
+    }
+    let rows = values.len().div_ceil(columns);
+    let view = viewport.unwrap_or(rect);
+    let (c0, c1) = visible_slots(columns, rect.x, rect.width, view.x, view.x + view.width);
+    let (r0, r1) = visible_slots(rows, rect.y, rect.height, view.y, view.y + view.height);
+    if c0 >= c1 || r0 >= r1 {
+        return None;
+    }
+    let max_side = MAX_IMAGE_DIMENSION as usize;
+    let (column_step, row_step) = ((c1 - c0).div_ceil(max_side), (r1 - r0).div_ceil(max_side));
+    let mut rgba = Vec::new();
+    for row in (r0..r1).step_by(row_step) {
+        for column in (c0..c1).step_by(column_step) {
+            let pixel = match values.get(row * columns + column) {
+                Some(&value) if value.is_finite() => {
+                    let color = sample(colormap, (value - min) / (max - min));
+                    let channel = |value: f32| (value * 255.0).round() as u8;
+                    [
+                        channel(color.r),
+                        channel(color.g),
+                        channel(color.b),
+                        channel(color.a),
+                    ]
+                }
+                _ => [0; 4],
+            };
+            rgba.extend(pixel);
+        }
+    }
+    let image = Image::new(
+        (c1 - c0).div_ceil(column_step) as u32,
+        (r1 - r0).div_ceil(row_step) as u32,
+        rgba,
+    )?;
+    let cell = Vec2::new(rect.width / columns as f32, rect.height / rows as f32);
+    let covered = Rect::new(
+        rect.x + c0 as f32 * cell.x,
+        rect.y + r0 as f32 * cell.y,
+        (c1 - c0) as f32 * cell.x,
+        (r1 - r0) as f32 * cell.y,
+    );
+    Some((image, covered))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bars, heatmap, points, BarStyle};
+    use crate::geometry::{Rect, Vec2};
+    use crate::host::Color;
+
+    const STYLE: BarStyle = BarStyle {
+        color: Color {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+        },
+        min: -10.0,
+        max: 10.0,
+        baseline: 0.0,
+        gap: 0.5,
+    };
+
+    #[test]
+    fn bars_grow_from_the_baseline_and_merge_below_a_pixel() {
+        let rect = Rect::new(0.0, 0.0, 40.0, 100.0);
+        assert_eq!(
+            bars(&[10.0, -5.0, f32::NAN, 0.0], rect, &STYLE, None),
+            [
+                Rect::new(2.5, 0.0, 5.0, 50.0),
+                Rect::new(12.5, 50.0, 5.0, 25.0),
+            ]
+        );
+
+        // 100k values in 100 pixels: one rectangle per pixel, reaching the tallest bar.
+        let mut values = vec![1.0; 100_000];
+        values[1500] = 10.0;
+        let merged = bars(&values, Rect::new(0.0, 0.0, 100.0, 100.0), &STYLE, None);
+        assert_eq!(merged.len(), 100);
+        assert_eq!(merged[1], Rect::new(1.0, 0.0, 1.0, 50.0));
+        assert_eq!(merged[2].y, 45.0);
+
+        // Only the bars overlapping the viewport are kept.
+        let viewport = Some(Rect::new(1.2, 0.0, 0.6, 10.0));
+        let culled = bars(&values, Rect::new(0.0, 0.0, 100.0, 100.0), &STYLE, viewport);
+        assert_eq!(culled, [Rect::new(1.0, 0.0, 1.0, 50.0)]);
+    }
+
+    #[test]
+    fn points_outside_the_viewport_are_culled() {
+        let viewport = Some(Rect::new(0.0, 0.0, 100.0, 100.0));
+        let sprites = points(
+            &[
+                Vec2::new(50.0, 50.0),
+                Vec2::new(-2.0, 50.0),
+                Vec2::new(-5.0, 50.0),
+                Vec2::new(f32::NAN, 0.0),
+            ],
+            3.0,
+            STYLE.color,
+            viewport,
+        );
+        let positions: Vec<_> = sprites.iter().map(|sprite| sprite.position).collect();
+        assert_eq!(positions, [Vec2::new(50.0, 50.0), Vec2::new(-2.0, 50.0)]);
+        assert_eq!(sprites[0].size, 6.0);
+    }
+
+    #[test]
+    fn heatmaps_draw_the_visible_cells_as_one_image() {
+        let black = STYLE.color;
+        let white = Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        };
+        // 4 columns by 3 rows, the last row short.
+        let values = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, f32::NAN, 9.0];
+        let rect = Rect::new(0.0, 0.0, 40.0, 30.0);
+        let (image, covered) =
+            heatmap(&values, 4, (0.0, 10.0), rect, &[black, white], None).unwrap();
+        assert_eq!((image.width(), image.height()), (4, 3));
+        assert_eq!(covered, rect);
+        let pixel = |x: usize, y: usize| {
+            let start = (y * 4 + x) * 4;
+            image.rgba()[start..start + 4].to_vec()
+        };
+        assert_eq!(pixel(0, 0), [0, 0, 0, 255]);
+        assert_eq!(pixel(1, 1), [128, 128, 128, 255]);
+        assert_eq!(pixel(0, 2), [0; 4], "NaN");
+        assert_eq!(pixel(2, 2), [0; 4], "past the end");
+
+        let viewport = Some(Rect::new(15.0, 12.0, 100.0, 5.0));
+        let (image, covered) =
+            heatmap(&values, 4, (0.0, 10.0), rect, &[black, white], viewport).unwrap();
+        assert_eq!((image.width(), image.height()), (3, 1));
+        assert_eq!(covered, Rect::new(10.0, 10.0, 30.0, 10.0));
+        let offscreen = Some(Rect::new(100.0, 0.0, 10.0, 10.0));
+        assert!(heatmap(&values, 4, (0.0, 10.0), rect, &[white], offscreen).is_none());
+    }
+}
@@ -0,0 +1,288 @@
+//! Chrome trace-event JSON output, viewable in `chrome://tracing` or Perfetto.
+//!
+//! Span activity and guest `perf-measure` regions become complete (`"ph":"X"`) events with
+//! a `dur`, so the viewer draws them as slices; every other event is an instant (`"i"`).
+
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use anyhow::{Context as _, Result};
+use serde_json::{json, Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::span;
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::logging::PERF_TARGET;
+
+/// Field carrying a measured duration on `perf` events.
+const DURATION_FIELD: &str = "duration_ms";
+
+/// Writes trace events to a file as they happen; [`FlushGuard`] closes the JSON array.
+pub struct ChromeLayer {
+    out: Arc<Mutex<TraceWriter>>,
+    start: Instant,
+}
+
+/// Finishes the trace file when dropped at exit.
+pub struct FlushGuard {
+    out: Arc<Mutex<TraceWriter>>,
+}
+
+struct TraceWriter {
+    out: Box<dyn Write + Send>,
+    first: bool,
+}
+
+/// Arguments recorded when a span is created.
+struct SpanArgs(Map<String, Value>);
+
+/// When the span was last entered, in microseconds since the trace started.
+struct EnteredAt(f64);
+
+impl ChromeLayer {
+    pub fn create(path: &Path) -> Result<(Self, FlushGuard)> {
+        let file = File::create(path)
+            .with_context(|| format!("failed to create trace file {}", path.display()))?;
+        Ok(Self::with_writer(BufWriter::new(file)))
+    }
+
+    fn with_writer(writer: impl Write + Send + 'static) -> (Self, FlushGuard) {
+        let out = Arc::new(Mutex::new(TraceWriter {
+            out: Box::new(writer),
+            first: true,
+        }));
+        let layer = Self {
+            out: out.clone(),
+            start: Instant::now(),
+        };
+        (layer, FlushGuard { out })
+    }
+
+    fn now_us(&self) -> f64 {
+        self.start.elapsed().as_secs_f64() * 1_000_000.0
+    }
+
+    fn write(&self, entry: Value) {
+        let mut writer = self.out.lock().unwrap_or_else(|poison| poison.into_inner());
+        // A failed write must not take the host down; the trace is best-effort.
+        let _ = writer.append(&entry);
+    }
+}
+
+impl TraceWriter {
+    fn append(&mut self, entry: &Value) -> io::Result<()> {
+        self.out
+            .write_all(if self.first { b"[\n" } else { b",\n" })?;
+        self.first = false;
+        serde_json::to_writer(&mut self.out, entry)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.out
+            .write_all(if self.first { b"[]\n" } else { b"\n]\n" })?;
+        self.first = false;
+        self.out.flush()
+    }
+}
+
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        let mut writer = self.out.lock().unwrap_or_else(|poison| poison.into_inner());
+        let _ = writer.finish();
+    }
+}
+
+impl<S> Layer<S> for ChromeLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let mut args = Args::default();
+        attrs.record(&mut args);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanArgs(args.fields));
+        }
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().replace(EnteredAt(self.now_us()));
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let end = self.now_us();
+        let extensions = span.extensions();
+        let Some(EnteredAt(start)) = extensions.get::<EnteredAt>() else {
+            return;
+        };
+        let args = extensions.get::<SpanArgs>().map(|args| args.0.clone());
+        self.write(json!({
+            "ph": "X",
+            "name": span.name(),
+            "cat": span.metadata().target(),
+            "ts": start,
+            "dur": end - start,
+            "pid": 1,
+            "tid": thread_id(),
+            "args": args.unwrap_or_default(),
+        }));
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let now = self.now_us();
+        let mut args = Args::default();
+        event.record(&mut args);
+        let target = event.metadata().target();
+        let duration_us = (target == PERF_TARGET)
+            .then(|| args.fields.get(DURATION_FIELD).and_then(Value::as_f64))
+            .flatten()
+            .filter(|ms| ms.is_finite() && *ms >= 0.0)
+            .map(|ms| ms * 1000.0);
+
+        let entry = match duration_us {
+            // The measure ends now; its slice starts `dur` earlier.
+            Some(dur) => json!({
+                "ph": "X",
+                "name": args.fields.get("name").and_then(Value::as_str).unwrap_or(&args.message),
+                "cat": target,
+                "ts": (now - dur).max(0.0),
+                "dur": dur,
+                "pid": 1,
+                "tid": thread_id(),
+                "args": args.fields,
+            }),
+            None => json!({
+                "ph": "i",
+                "s": "t",
+                "name": args.message,
+                "cat": target,
+                "ts": now,
+                "pid": 1,
+                "tid": thread_id(),
+                "args": args.fields,
+            }),
+        };
+        self.write(entry);
+    }
+}
+
+#[derive(Default)]
+struct Args {
+    message: String,
+    fields: Map<String, Value>,
+}
+
+impl Args {
+    fn insert(&mut self, field: &Field, value: Value) {
+        self.fields.insert(field.name().to_string(), value);
+    }
+}
+
+impl Visit for Args {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.insert(field, json!(value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record_str(field, &format!("{value:?}"));
+    }
+}
+
+/// Small, stable per-thread number for the `tid` column.
+fn thread_id() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    thread_local! {
+        static ID: Cell<u64> = const { Cell::new(0) };
+    }
+    ID.with(|id| {
+        if id.get() == 0 {
+            id.set(NEXT.fetch_add(1, Ordering::Relaxed));
+        }
+        id.get()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+
+    use serde_json::Value;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::ChromeLayer;
+    use crate::logging::PERF_TARGET;
+
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn writes_measures_and_spans_as_complete_events() {
+        let buffer = Shared::default();
+        let (layer, guard) = ChromeLayer::with_writer(buffer.clone());
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("component", label = "counter").in_scope(|| {
+                tracing::debug!(target: PERF_TARGET, name = "layout", duration_ms = 2.5, "perf-measure");
+                tracing::info!("hello");
+            });
+        });
+        drop(guard);
+
+        let events: Vec<Value> = serde_json::from_slice(&buffer.0.lock().unwrap()).unwrap();
+        let measure = &events[0];
+        assert_eq!(measure["ph"], "X");
+        assert_eq!(measure["name"], "layout");
+        assert_eq!(measure["dur"].as_f64(), Some(2500.0));
+        assert_eq!(events[1]["ph"], "i");
+        assert_eq!(events[1]["name"], "hello");
+        let span = &events[2];
+        assert_eq!(span["ph"], "X");
+        assert_eq!(span["name"], "component");
+        assert_eq!(span["args"]["label"], "counter");
+        assert!(span["dur"].as_f64().unwrap() >= 0.0);
+    }
+}
@@ -0,0 +1,206 @@
+use std::time::Instant;
+
+/// dt handed to the guest for a single-stepped frame, before time scaling.
+pub const STEP_DT_MS: f32 = 1000.0 / 60.0;
+
+/// Default cap on the real time one `frame` covers, so a stall (an overlay, a laptop
+/// waking from sleep) does not reach the guest as one multi-second step.
+pub const DEFAULT_MAX_DT_MS: f32 = 100.0;
+
+/// Time scales cycled by the speed hotkey.
+const SPEED_PRESETS: [f32; 4] = [1.0, 0.1, 0.5, 2.0];
+
+/// Virtual clock feeding `frame(dt-ms)`: supports pausing, time scaling, and single-stepping.
+#[derive(Debug)]
+pub struct VirtualClock {
+    paused: bool,
+    scale: f32,
+    pending_steps: u32,
+    last_tick: Option<Instant>,
+    /// The next running frame gets a dt of zero; see [`VirtualClock::resync`].
+    resynced: bool,
+    max_dt_ms: f32,
+    /// Real time between the last two ticks, before clamping and scaling.
+    elapsed_ms: f64,
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new(1.0, false)
+    }
+}
+
+impl VirtualClock {
+    pub fn new(scale: f32, paused: bool) -> Self {
+        Self {
+            paused,
+            scale: sanitize_scale(scale),
+            pending_steps: 0,
+            last_tick: None,
+            resynced: false,
+            max_dt_ms: DEFAULT_MAX_DT_MS,
+            elapsed_ms: 0.0,
+        }
+    }
+
+    /// Cap the real time a single frame's dt covers; non-positive values remove the cap.
+    pub fn with_max_dt(mut self, max_dt_ms: f32) -> Self {
+        self.max_dt_ms = if max_dt_ms > 0.0 {
+            max_dt_ms
+        } else {
+            f32::INFINITY
+        };
+        self
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        if !paused {
+            self.resync();
+            self.pending_steps = 0;
+        }
+    }
+
+    /// Give the next frame a dt of zero so time spent without frames (e.g. while the app
+    /// was suspended) is not reported as one enormous frame. The gap still shows up in
+    /// [`VirtualClock::elapsed_ms`].
+    pub fn resync(&mut self) {
+        self.resynced = true;
+    }
+
+    /// Real milliseconds between the last two frames, unclamped and unscaled, for guests
+    /// that keep wall-clock time.
+    pub fn elapsed_ms(&self) -> f64 {
+        self.elapsed_ms
+    }
+
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = sanitize_scale(scale);
+    }
+
+    /// Advance to the next speed preset, returning the new scale.
+    pub fn cycle_speed(&mut self) -> f32 {
+        let next = SPEED_PRESETS
+            .iter()
+            .position(|preset| (*preset - self.scale).abs() < f32::EPSILON)
+            .map(|index| SPEED_PRESETS[(index + 1) % SPEED_PRESETS.len()])
+            .unwrap_or(1.0);
+        self.scale = next;
+        next
+    }
+
+    /// Queue one frame while paused (pausing first if the clock is running).
+    pub fn step(&mut self) {
+        self.paused = true;
+        self.pending_steps += 1;
+    }
+
+    /// Returns the scaled dt for the next guest frame, or `None` if frames are paused.
+    /// Real time beyond the dt cap is dropped before scaling.
+    pub fn tick(&mut self, now: Instant) -> Option<f32> {
+        if self.paused && self.pending_steps == 0 {
+            return None;
+        }
+        let elapsed = self.last_tick.replace(now).map(|last| now - last);
+        self.elapsed_ms = elapsed.map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0);
+        let resynced = std::mem::take(&mut self.resynced);
+        if self.paused {
+            self.pending_steps -= 1;
+            return Some(STEP_DT_MS * self.scale);
+        }
+        let dt_ms = match elapsed {
+            Some(elapsed) if !resynced => (elapsed.as_secs_f32() * 1000.0).min(self.max_dt_ms),
+            _ => 0.0,
+        };
+        Some(dt_ms * self.scale)
+    }
+
+    /// Short status for the stats overlay; `None` when running in real time.
+    pub fn status(&self) -> Option<String> {
+        if self.paused {
+            Some(format!("time paused ({}x)", self.scale))
+        } else if self.scale != 1.0 {
+            Some(format!("time {}x", self.scale))
+        } else {
+            None
+        }
+    }
+}
+
+fn sanitize_scale(scale: f32) -> f32 {
+    if scale.is_finite() && scale > 0.0 {
+        scale
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{VirtualClock, DEFAULT_MAX_DT_MS, STEP_DT_MS};
+
+    #[test]
+    fn scales_elapsed_time() {
+        let start = Instant::now();
+        let mut clock = VirtualClock::new(0.5, false);
+        assert_eq!(clock.tick(start), Some(0.0));
+        let dt = clock.tick(start + Duration::from_millis(20)).unwrap();
+        assert!((dt - 10.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn paused_clock_only_advances_on_steps() {
+        let start = Instant::now();
+        let mut clock = VirtualClock::new(2.0, true);
+        assert_eq!(clock.tick(start), None);
+        clock.step();
+        assert_eq!(clock.tick(start), Some(STEP_DT_MS * 2.0));
+        assert_eq!(clock.tick(start), None);
+
+        clock.set_paused(false);
+        assert_eq!(clock.tick(start + Duration::from_secs(5)), Some(0.0));
+    }
+
+    #[test]
+    fn resync_skips_the_gap_without_frames() {
+        let start = Instant::now();
+        let mut clock = VirtualClock::default();
+        clock.tick(start);
+        clock.resync();
+        assert_eq!(clock.tick(start + Duration::from_secs(60)), Some(0.0));
+        assert_eq!(clock.elapsed_ms(), 60_000.0);
+    }
+
+    #[test]
+    fn clamps_long_frames_but_reports_real_time() {
+        let start = Instant::now();
+        let mut clock = VirtualClock::new(2.0, false);
+        clock.tick(start);
+        let later = start + Duration::from_secs(3);
+        assert_eq!(clock.tick(later), Some(DEFAULT_MAX_DT_MS * 2.0));
+        assert_eq!(clock.elapsed_ms(), 3000.0);
+
+        let mut uncapped = VirtualClock::default().with_max_dt(0.0);
+        uncapped.tick(start);
+        assert_eq!(uncapped.tick(later), Some(3000.0));
+    }
+
+    #[test]
+    fn cycles_speed_presets() {
+        let mut clock = VirtualClock::default();
+        assert_eq!(clock.cycle_speed(), 0.1);
+        assert_eq!(clock.cycle_speed(), 0.5);
+        assert_eq!(clock.cycle_speed(), 2.0);
+        assert_eq!(clock.cycle_speed(), 1.0);
+    }
+}
@@ -3,4 +3,21 @@
 wasmtime::component::bindgen!({
     path: "../../wit/vello",
     world: "canvas-app",
+    // Host functions return `wasmtime::Result` so quota violations can trap the guest.
+    trappable_imports: true,
 });
+
+/// Bindings for `canvas-worker` components. The interfaces they share with `canvas-app`
+/// reuse its types, so `HostCtx` implements them once.
+pub mod worker {
+    wasmtime::component::bindgen!({
+        path: "../../wit/vello",
+        world: "canvas-worker",
+        trappable_imports: true,
+        with: {
+            "vello:canvas/math": super::vello::canvas::math,
+            "vello:canvas/host": super::vello::canvas::host,
+            "vello:canvas/database": super::vello::canvas::database,
+        },
+    });
+}
@@ -0,0 +1,144 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::engine::EngineOptions;
+use crate::graphics::{Antialiasing, PresentMode};
+use crate::host::Color;
+use crate::hotkeys::Keybinds;
+use crate::window::Theme;
+
+pub const CONFIG_FILE_NAME: &str = "frontier-host.toml";
+
+/// Per-user host defaults read from `frontier-host.toml`. Every field is optional; command
+/// line flags take precedence over anything set here.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct HostConfig {
+    pub present_mode: Option<PresentMode>,
+    pub antialiasing: Option<Antialiasing>,
+    /// Tracing filter, as for `--log-filter`.
+    pub log_filter: Option<String>,
+    pub theme: Option<Theme>,
+    /// CSS colour, as for `--background-color`.
+    pub background_color: Option<String>,
+    pub keybinds: Keybinds,
+    pub engine: EngineOptions,
+}
+
+/// `frontier-host.toml` under the platform config directory (`$XDG_CONFIG_HOME` on Linux).
+pub fn default_config_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(CONFIG_FILE_NAME))
+}
+
+impl HostConfig {
+    pub fn parse(text: &str) -> Result<Self> {
+        let config: Self = toml::from_str(text)?;
+        config.background_color()?;
+        Ok(config)
+    }
+
+    pub fn background_color(&self) -> Result<Option<Color>> {
+        self.background_color
+            .as_deref()
+            .map(Color::parse)
+            .transpose()
+            .context("invalid background-color")
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read config {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid config {}", path.display()))
+    }
+
+    /// Load the default config file; a missing file yields the built-in defaults.
+    pub fn load_default() -> Result<Self> {
+        let Some(path) = default_config_file() else {
+            return Ok(Self::default());
+        };
+        match Self::load(&path) {
+            Err(err)
+                if err
+                    .downcast_ref::<io::Error>()
+                    .is_some_and(|err| err.kind() == io::ErrorKind::NotFound) =>
+            {
+                Ok(Self::default())
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::HostConfig;
+    use crate::engine::EngineOptions;
+    use crate::graphics::{Antialiasing, PresentMode};
+    use crate::hotkeys::Keybinds;
+    use crate::window::Theme;
+
+    #[test]
+    fn parses_full_config() {
+        let config = HostConfig::parse(
+            r##"
+            present-mode = "mailbox"
+            antialiasing = "msaa16"
+            log-filter = "info,guest=debug"
+            theme = "dark"
+            background-color = "#102030"
+
+            [keybinds]
+            restart = "Ctrl+Shift+R"
+            console = "`"
+
+            [engine]
+            simd = false
+            pooling-allocator = true
+            worker-threads = 2
+            "##,
+        )
+        .unwrap();
+        assert_eq!(config.present_mode, Some(PresentMode::Mailbox));
+        assert_eq!(config.antialiasing, Some(Antialiasing::Msaa16));
+        assert_eq!(config.log_filter.as_deref(), Some("info,guest=debug"));
+        assert_eq!(config.theme, Some(Theme::Dark));
+        let background = config.background_color().unwrap().unwrap();
+        assert_eq!(
+            background.to_peniko().to_rgba8().to_u8_array(),
+            [16, 32, 48, 255]
+        );
+        assert_eq!(
+            config.engine,
+            EngineOptions {
+                simd: false,
+                pooling_allocator: true,
+                worker_threads: NonZeroUsize::new(2),
+                ..EngineOptions::default()
+            }
+        );
+        assert_eq!(
+            config.keybinds,
+            Keybinds {
+                restart: "Ctrl+Shift+R".parse().unwrap(),
+                console: "`".parse().unwrap(),
+                ..Keybinds::default()
+            }
+        );
+    }
+
+    #[test]
+    fn empty_config_uses_defaults_and_typos_are_rejected() {
+        assert_eq!(HostConfig::parse("").unwrap(), HostConfig::default());
+        assert!(HostConfig::parse("present_mode = \"fifo\"").is_err());
+        assert!(HostConfig::parse("antialiasing = \"msaa4\"").is_err());
+        assert!(HostConfig::parse("[keybinds]\nstats = \"Hyper+S\"").is_err());
+        assert!(HostConfig::parse("[engine]\nsimd128 = true").is_err());
+        assert!(HostConfig::parse("background-color = \"#zz\"").is_err());
+    }
+}
@@ -0,0 +1,341 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use winit::event_loop::EventLoopProxy;
+
+use crate::app::AppEvent;
+
+/// Where the control endpoint listens: `unix:/path/to.sock` or `tcp:127.0.0.1:7878`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ControlAddress {
+    #[cfg(unix)]
+    Unix(PathBuf),
+    Tcp(String),
+}
+
+impl FromStr for ControlAddress {
+    type Err = anyhow::Error;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        match spec.split_once(':') {
+            #[cfg(unix)]
+            Some(("unix", path)) if !path.is_empty() => Ok(ControlAddress::Unix(path.into())),
+            #[cfg(not(unix))]
+            Some(("unix", _)) => bail!("unix control sockets are not supported on this platform"),
+            Some(("tcp", addr)) if !addr.is_empty() => Ok(ControlAddress::Tcp(addr.to_string())),
+            _ => bail!("expected `unix:PATH` or `tcp:HOST:PORT`, got '{spec}'"),
+        }
+    }
+}
+
+/// A JSON command read from the control socket, one object per line, e.g.
+/// `{"cmd":"pointer-down","x":10,"y":20}`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "cmd", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum ControlCommand {
+    /// Move the pointer to a position in logical pixels.
+    PointerMove {
+        x: f32,
+        y: f32,
+    },
+    PointerDown {
+        x: f32,
+        y: f32,
+        #[serde(default)]
+        button: ControlButton,
+    },
+    PointerUp {
+        x: f32,
+        y: f32,
+        #[serde(default)]
+        button: ControlButton,
+    },
+    /// `key` uses the same names the guest receives (`"a"`, `"ArrowUp"`); `code` defaults to `key`.
+    KeyDown {
+        key: String,
+        #[serde(default)]
+        code: Option<String>,
+    },
+    KeyUp {
+        key: String,
+        #[serde(default)]
+        code: Option<String>,
+    },
+    /// Deliver typed text to the guest's `text-input` export.
+    TextInput {
+        text: String,
+    },
+    /// Deliver a `frontier://` link to the guest's `deep-link-opened` export.
+    DeepLink {
+        url: String,
+    },
+    /// Raise the window and pass a later launch's arguments and `--open` link to the
+    /// guest; sent by `--single-instance`.
+    Activate {
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        url: Option<String>,
+    },
+    /// Write the most recently rendered frame to a PNG file.
+    Screenshot {
+        path: PathBuf,
+    },
+    /// Save the guest's state to a file, as a checkpoint to `restore` later.
+    Snapshot {
+        path: PathBuf,
+    },
+    /// Reinstantiate the component from a `snapshot` file taken from the same build.
+    Restore {
+        path: PathBuf,
+    },
+    /// Reload the component from its source and re-run `init`.
+    Reload,
+    /// Describe the draw commands of the last guest frame.
+    DumpFrame,
+    Stats,
+    Pause,
+    Resume,
+    Step,
+    TimeScale {
+        scale: f32,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ControlButton {
+    #[default]
+    Left,
+    Right,
+}
+
+impl From<ControlButton> for winit::event::MouseButton {
+    fn from(button: ControlButton) -> Self {
+        match button {
+            ControlButton::Left => winit::event::MouseButton::Left,
+            ControlButton::Right => winit::event::MouseButton::Right,
+        }
+    }
+}
+
+/// A command forwarded to the event loop, plus the channel its reply goes back on.
+#[derive(Debug)]
+pub struct ControlRequest {
+    pub command: ControlCommand,
+    reply: mpsc::Sender<Value>,
+}
+
+impl ControlRequest {
+    pub fn respond(self, result: Result<Value>) {
+        let _ = self.reply.send(response(result));
+    }
+}
+
+pub(crate) fn response(result: Result<Value>) -> Value {
+    match result {
+        Ok(Value::Null) => json!({ "ok": true }),
+        Ok(result) => json!({ "ok": true, "result": result }),
+        Err(err) => json!({ "ok": false, "error": format!("{err:#}") }),
+    }
+}
+
+/// Keeps the control endpoint alive; removes the unix socket file when dropped.
+pub struct ControlServer {
+    #[cfg(unix)]
+    socket_path: Option<PathBuf>,
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(path) = &self.socket_path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Start listening on `address`, forwarding each command to the event loop through `proxy`.
+pub fn spawn(address: &ControlAddress, proxy: EventLoopProxy<AppEvent>) -> Result<ControlServer> {
+    match address {
+        #[cfg(unix)]
+        ControlAddress::Unix(path) => {
+            use std::os::unix::fs::FileTypeExt;
+            use std::os::unix::net::UnixListener;
+
+            if let Ok(metadata) = std::fs::symlink_metadata(path) {
+                if !metadata.file_type().is_socket() {
+                    bail!("{} exists and is not a socket", path.display());
+                }
+                std::fs::remove_file(path)
+                    .with_context(|| format!("failed to remove stale socket {}", path.display()))?;
+            }
+            let listener = UnixListener::bind(path)
+                .with_context(|| format!("failed to bind control socket {}", path.display()))?;
+            tracing::info!(path = %path.display(), "control endpoint listening");
+            accept_loop(move || listener.accept().map(|(stream, _)| stream), proxy);
+            Ok(ControlServer {
+                socket_path: Some(path.clone()),
+            })
+        }
+        ControlAddress::Tcp(addr) => {
+            let listener = TcpListener::bind(addr)
+                .with_context(|| format!("failed to bind control address {addr}"))?;
+            let local = listener.local_addr()?;
+            if !local.ip().is_loopback() {
+                tracing::warn!(%local, "control endpoint is reachable from other machines");
+            }
+            tracing::info!(%local, "control endpoint listening");
+            accept_loop(
+                move || {
+                    let (stream, _) = listener.accept()?;
+                    stream.set_nodelay(true)?;
+                    Ok(stream)
+                },
+                proxy,
+            );
+            Ok(ControlServer {
+                #[cfg(unix)]
+                socket_path: None,
+            })
+        }
+    }
+}
+
+trait Connection: std::io::Read + Write + Send + 'static {
+    fn try_clone_boxed(&self) -> std::io::Result<Box<dyn Write + Send>>;
+}
+
+impl Connection for TcpStream {
+    fn try_clone_boxed(&self) -> std::io::Result<Box<dyn Write + Send>> {
+        Ok(Box::new(self.try_clone()?))
+    }
+}
+
+#[cfg(unix)]
+impl Connection for std::os::unix::net::UnixStream {
+    fn try_clone_boxed(&self) -> std::io::Result<Box<dyn Write + Send>> {
+        Ok(Box::new(self.try_clone()?))
+    }
+}
+
+fn accept_loop<A, S>(mut accept: A, proxy: EventLoopProxy<AppEvent>)
+where
+    A: FnMut() -> std::io::Result<S> + Send + 'static,
+    S: Connection,
+{
+    let spawn_result = thread::Builder::new()
+        .name("frontier-control".into())
+        .spawn(move || loop {
+            match accept() {
+                Ok(stream) => {
+                    let proxy = proxy.clone();
+                    thread::spawn(move || {
+                        if let Err(err) = serve(stream, proxy) {
+                            tracing::debug!(error = %err, "control connection closed");
+                        }
+                    });
+                }
+                Err(err) => tracing::warn!(%err, "failed to accept control connection"),
+            }
+        });
+    if let Err(err) = spawn_result {
+        tracing::error!(%err, "failed to start control endpoint thread");
+    }
+}
+
+fn serve<S: Connection>(stream: S, proxy: EventLoopProxy<AppEvent>) -> Result<()> {
+    let mut writer = stream.try_clone_boxed()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str::<ControlCommand>(&line) {
+            Ok(command) => dispatch(command, &proxy),
+            Err(err) => response(Err(anyhow!("invalid command: {err}"))),
+        };
+        serde_json::to_writer(&mut writer, &reply)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+pub(crate) fn dispatch(command: ControlCommand, proxy: &EventLoopProxy<AppEvent>) -> Value {
+    tracing::debug!(?command, "control command");
+    let (reply, receiver) = mpsc::channel();
+    if proxy
+        .send_event(ControlRequest { command, reply }.into())
+        .is_err()
+    {
+        return response(Err(anyhow!("host is shutting down")));
+    }
+    receiver
+        .recv()
+        .unwrap_or_else(|_| response(Err(anyhow!("host dropped the command"))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{response, ControlAddress, ControlButton, ControlCommand};
+    use serde_json::json;
+
+    #[test]
+    fn parses_control_addresses() {
+        assert_eq!(
+            "tcp:127.0.0.1:7878".parse::<ControlAddress>().unwrap(),
+            ControlAddress::Tcp("127.0.0.1:7878".into())
+        );
+        #[cfg(unix)]
+        assert_eq!(
+            "unix:/tmp/frontier.sock".parse::<ControlAddress>().unwrap(),
+            ControlAddress::Unix("/tmp/frontier.sock".into())
+        );
+        assert!("tcp:".parse::<ControlAddress>().is_err());
+        assert!("/tmp/frontier.sock".parse::<ControlAddress>().is_err());
+    }
+
+    #[test]
+    fn parses_json_commands() {
+        let command: ControlCommand =
+            serde_json::from_str(r#"{"cmd":"pointer-down","x":4,"y":8.5}"#).unwrap();
+        assert_eq!(
+            command,
+            ControlCommand::PointerDown {
+                x: 4.0,
+                y: 8.5,
+                button: ControlButton::Left
+            }
+        );
+        let command: ControlCommand =
+            serde_json::from_str(r#"{"cmd":"time-scale","scale":0.5}"#).unwrap();
+        assert_eq!(command, ControlCommand::TimeScale { scale: 0.5 });
+        let command: ControlCommand =
+            serde_json::from_str(r#"{"cmd":"text-input","text":"héllo"}"#).unwrap();
+        assert_eq!(
+            command,
+            ControlCommand::TextInput {
+                text: "héllo".into()
+            }
+        );
+        assert!(serde_json::from_str::<ControlCommand>(r#"{"cmd":"explode"}"#).is_err());
+    }
+
+    #[test]
+    fn wraps_results_in_responses() {
+        assert_eq!(response(Ok(json!(null))), json!({ "ok": true }));
+        assert_eq!(response(Ok(json!(3))), json!({ "ok": true, "result": 3 }));
+        assert_eq!(
+            response(Err(anyhow::anyhow!("nope"))),
+            json!({ "ok": false, "error": "nope" })
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! Per-component SQLite databases behind the `database` interface, for guests whose data
+//! is more structured than `storage-set` values.
+//!
+//! Each component gets one database file beside its key-value storage, opened on first
+//! use. The file is capped at `--max-database-bytes` through SQLite's page limit, so
+//! writes past the quota fail with "database or disk is full". Guests cannot attach other
+//! files or set the pragmas that would lift the cap.
+//!
+//! SQLite runs natively, out of reach of the epoch interrupts that stop guest code, so a
+//! progress handler interrupts any statement still running after the call timeout, and
+//! value and result sizes are capped before they become host allocations.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+use rusqlite::limits::Limit;
+use rusqlite::types::{ToSqlOutput, ValueRef};
+use rusqlite::{params_from_iter, Connection, ErrorCode, ToSql};
+
+/// The database's name in the component's storage directory.
+pub const DATABASE_FILE: &str = "database.sqlite";
+/// Prepared statements a component may hold at once.
+pub const MAX_STATEMENTS: usize = 256;
+/// Rows a single `query` may return.
+pub const MAX_QUERY_ROWS: usize = 100_000;
+/// Bytes a single `query` may return, counting 8 for each number or null.
+pub const MAX_QUERY_BYTES: usize = 64 * 1024 * 1024;
+/// Largest string or blob SQLite will build, e.g. through `zeroblob` or `printf`.
+pub const MAX_VALUE_BYTES: i32 = 16 * 1024 * 1024;
+/// Virtual machine steps between checks of the statement deadline.
+const PROGRESS_STEPS: i32 = 10_000;
+/// Pragmas guests may set; the rest are read-only so the size cap stays in place.
+const WRITABLE_PRAGMAS: &[&str] = &["foreign_keys", "recursive_triggers", "user_version"];
+
+/// A statement parameter or result column.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl ToSql for SqlValue {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(match self {
+            SqlValue::Null => ValueRef::Null,
+            SqlValue::Integer(value) => ValueRef::Integer(*value),
+            SqlValue::Real(value) => ValueRef::Real(*value),
+            SqlValue::Text(value) => ValueRef::Text(value.as_bytes()),
+            SqlValue::Blob(value) => ValueRef::Blob(value),
+        }))
+    }
+}
+
+impl From<ValueRef<'_>> for SqlValue {
+    fn from(value: ValueRef<'_>) -> Self {
+        match value {
+            ValueRef::Null => SqlValue::Null,
+            ValueRef::Integer(value) => SqlValue::Integer(value),
+            ValueRef::Real(value) => SqlValue::Real(value),
+            ValueRef::Text(value) => SqlValue::Text(String::from_utf8_lossy(value).into_owned()),
+            ValueRef::Blob(value) => SqlValue::Blob(value.to_vec()),
+        }
+    }
+}
+
+/// A component's database and the statements it has prepared.
+#[derive(Default)]
+pub struct Database {
+    /// `None` when the host keeps no data for the component.
+    path: Option<PathBuf>,
+    max_bytes: u64,
+    /// How long one statement may run; the guest's call timeout.
+    timeout: Option<Duration>,
+    connection: Option<Connection>,
+    /// SQL by statement id; compiled statements live in the connection's cache.
+    statements: HashMap<u32, String>,
+    next_id: u32,
+}
+
+impl std::fmt::Debug for Database {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Database")
+            .field("path", &self.path)
+            .field("open", &self.connection.is_some())
+            .field("statements", &self.statements.len())
+            .finish()
+    }
+}
+
+impl Database {
+    pub fn new(path: PathBuf, max_bytes: u64) -> Self {
+        Self {
+            path: Some(path),
+            max_bytes,
+            ..Self::default()
+        }
+    }
+
+    /// Interrupt statements that run longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn available(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Compile `sql` and keep it under a new id.
+    pub fn prepare(&mut self, sql: &str) -> Result<u32> {
+        if self.statements.len() >= MAX_STATEMENTS {
+            bail!("too many prepared statements; finalize some first (limit {MAX_STATEMENTS})");
+        }
+        self.connection()?.prepare_cached(sql)?;
+        let id = self.next_id;
+        self.next_id = self
+            .next_id
+            .checked_add(1)
+            .context("statement ids exhausted")?;
+        self.statements.insert(id, sql.to_string());
+        Ok(id)
+    }
+
+    /// Run statement `id` for its side effects; returns the rows it changed.
+    pub fn execute(&mut self, id: u32, params: &[SqlValue]) -> Result<u64> {
+        let sql = self.sql(id)?;
+        let timeout = self.timeout;
+        let mut statement = self.connection()?.prepare_cached(&sql)?;
+        let changed = statement
+            .execute(params_from_iter(params))
+            .map_err(|err| interrupted(err, timeout))?;
+        Ok(changed as u64)
+    }
+
+    /// Run statement `id` and collect its rows.
+    pub fn query(&mut self, id: u32, params: &[SqlValue]) -> Result<Vec<Vec<SqlValue>>> {
+        let sql = self.sql(id)?;
+        let timeout = self.timeout;
+        let mut statement = self.connection()?.prepare_cached(&sql)?;
+        let columns = statement.column_count();
+        let mut rows = statement
+            .query(params_from_iter(params))
+            .map_err(|err| interrupted(err, timeout))?;
+        let mut result = Vec::new();
+        let mut bytes = 0;
+        while let Some(row) = rows.next().map_err(|err| interrupted(err, timeout))? {
+            if result.len() == MAX_QUERY_ROWS {
+                bail!("query returned more than {MAX_QUERY_ROWS} rows");
+            }
+            let values = (0..columns)
+                .map(|index| row.get_ref(index))
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            bytes += values.iter().map(value_size).sum::<usize>();
+            if bytes > MAX_QUERY_BYTES {
+                bail!("query returned more than {} MiB", MAX_QUERY_BYTES >> 20);
+            }
+            result.push(values.into_iter().map(SqlValue::from).collect());
+        }
+        Ok(result)
+    }
+
+    pub fn finalize(&mut self, id: u32) -> bool {
+        self.statements.remove(&id).is_some()
+    }
+
+    fn sql(&self, id: u32) -> Result<String> {
+        match self.statements.get(&id) {
+            Some(sql) => Ok(sql.clone()),
+            None => bail!("unknown statement {id}"),
+        }
+    }
+
+    /// The open connection, with its statement deadline restarted from now.
+    fn connection(&mut self) -> Result<&Connection> {
+        if self.connection.is_none() {
+            let path = self
+                .path
+                .as_ref()
+                .context("the database is not available in this host")?;
+            let connection = open(path, self.max_bytes)
+                .with_context(|| format!("failed to open {}", path.display()))?;
+            self.connection = Some(connection);
+        }
+        let connection = self.connection.as_ref().expect("opened above");
+        if let Some(timeout) = self.timeout {
+            let deadline = Instant::now() + timeout;
+            connection.progress_handler(PROGRESS_STEPS, Some(move || Instant::now() >= deadline));
+        }
+        Ok(connection)
+    }
+}
+
+/// Explain an interrupt from the progress handler; other errors pass through.
+fn interrupted(err: rusqlite::Error, timeout: Option<Duration>) -> anyhow::Error {
+    match (err.sqlite_error_code(), timeout) {
+        (Some(ErrorCode::OperationInterrupted), Some(timeout)) => {
+            anyhow!(
+                "statement ran longer than {} ms and was interrupted",
+                timeout.as_millis()
+            )
+        }
+        _ => err.into(),
+    }
+}
+
+fn value_size(value: &ValueRef<'_>) -> usize {
+    match value {
+        ValueRef::Text(bytes) | ValueRef::Blob(bytes) => bytes.len(),
+        ValueRef::Null | ValueRef::Integer(_) | ValueRef::Real(_) => 8,
+    }
+}
+
+fn open(path: &PathBuf, max_bytes: u64) -> Result<Connection> {
+    let connection = Connection::open(path)?;
+    let page_size: u64 = connection.pragma_query_value(None, "page_size", |row| row.get(0))?;
+    connection.pragma_update(None, "max_page_count", (max_bytes / page_size).max(1))?;
+    connection.set_prepared_statement_cache_capacity(MAX_STATEMENTS);
+    // No ATTACH, which would also let VACUUM INTO write anywhere.
+    connection.set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0)?;
+    connection.set_limit(Limit::SQLITE_LIMIT_LENGTH, MAX_VALUE_BYTES)?;
+    connection.authorizer(Some(authorize));
+    Ok(connection)
+}
+
+fn authorize(context: AuthContext<'_>) -> Authorization {
+    match context.action {
+        AuthAction::Attach { .. } | AuthAction::Detach { .. } => Authorization::Deny,
+        AuthAction::Pragma {
+            pragma_name,
+            pragma_value: Some(_),
+        } if !WRITABLE_PRAGMAS.contains(&pragma_name.to_ascii_lowercase().as_str()) => {
+            Authorization::Deny
+        }
+        _ => Authorization::Allow,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{Duration, Instant};
+
+    use super::{Database, SqlValue};
+
+    #[test]
+    fn runs_statements_and_enforces_the_quota() {
+        let dir = std::env::temp_dir().join(format!("frontier-database-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let mut db = Database::new(dir.join("test.sqlite"), 64 * 1024);
+
+        let create = db
+            .prepare("CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT, data BLOB)")
+            .unwrap();
+        assert_eq!(db.execute(create, &[]).unwrap(), 0);
+        let insert = db
+            .prepare("INSERT INTO notes (body, data) VALUES (?1, ?2)")
+            .unwrap();
+        let row = [SqlValue::Text("hello".into()), SqlValue::Blob(vec![1, 2])];
+        assert_eq!(db.execute(insert, &row).unwrap(), 1);
+        let select = db.prepare("SELECT id, body, data FROM notes").unwrap();
+        assert_eq!(
+            db.query(select, &[]).unwrap(),
+            [[
+                SqlValue::Integer(1),
+                SqlValue::Text("hello".into()),
+                SqlValue::Blob(vec![1, 2])
+            ]]
+        );
+
+        let big = [SqlValue::Null, SqlValue::Blob(vec![0; 128 * 1024])];
+        let err = db.execute(insert, &big).unwrap_err();
+        assert!(format!("{err:#}").contains("full"), "{err:#}");
+
+        for sql in [
+            "PRAGMA max_page_count = 1000000",
+            "ATTACH DATABASE '/tmp/other.sqlite' AS other",
+            "SELECT 1; SELECT 2",
+        ] {
+            assert!(db.prepare(sql).is_err(), "{sql}");
+        }
+        assert!(db.prepare("PRAGMA user_version = 3").is_ok());
+
+        assert!(db.finalize(select));
+        assert!(db.query(select, &[]).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn interrupts_runaway_statements_and_caps_sizes() {
+        let dir =
+            std::env::temp_dir().join(format!("frontier-database-limits-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let mut db = Database::new(dir.join("test.sqlite"), 1024 * 1024)
+            .with_timeout(Some(Duration::from_millis(50)));
+
+        let forever = db
+            .prepare(
+                "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n) \
+                 SELECT count(*) FROM n",
+            )
+            .unwrap();
+        let started = Instant::now();
+        let err = db.query(forever, &[]).unwrap_err();
+        assert!(format!("{err:#}").contains("interrupted"), "{err:#}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let huge = db.prepare("SELECT zeroblob(1000000000)").unwrap();
+        let err = db.query(huge, &[]).unwrap_err();
+        assert!(format!("{err:#}").contains("too big"), "{err:#}");
+
+        let wide = db
+            .prepare(
+                "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 8) \
+                 SELECT zeroblob(10000000) FROM n",
+            )
+            .unwrap();
+        let err = db.query(wide, &[]).unwrap_err();
+        assert!(format!("{err:#}").contains("MiB"), "{err:#}");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fails_without_a_data_directory() {
+        let mut db = Database::default();
+        assert!(!db.available());
+        assert!(db.prepare("SELECT 1").is_err());
+    }
+}
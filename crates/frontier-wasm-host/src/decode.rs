@@ -0,0 +1,615 @@
+//! Decoding of PNG, APNG and GIF files for `decode-image`. Every frame is composited
+//! onto the full canvas once, at load time, and kept as an `Image`, so playback only picks
+//! which cached frame to draw.
+
+use std::fmt;
+use std::io::Cursor;
+
+use anyhow::{bail, Context, Result};
+use weezl::{decode::Decoder as LzwDecoder, BitOrder, LzwStatus};
+
+use crate::images::{Image, MAX_IMAGE_DIMENSION};
+
+/// Frames kept from one file; later frames are dropped with a warning.
+pub const MAX_FRAMES: usize = 1024;
+/// Browsers play frames this short or shorter at `DEFAULT_FRAME_MS`.
+const MIN_FRAME_MS: u32 = 10;
+const DEFAULT_FRAME_MS: u32 = 100;
+
+/// A decoded still or animated image: at least one frame, all the same size.
+#[derive(Clone)]
+pub struct AnimatedImage {
+    frames: Vec<Frame>,
+    /// How many times the animation runs; 0 means forever.
+    plays: u32,
+}
+
+#[derive(Clone)]
+struct Frame {
+    image: Image,
+    duration_ms: u32,
+}
+
+impl AnimatedImage {
+    /// Decode `bytes`, failing once the composited frames would need more than `max_bytes`.
+    pub fn decode(bytes: &[u8], max_bytes: usize) -> Result<Self> {
+        let mut canvas = if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            decode_png(bytes, max_bytes)?
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            decode_gif(bytes, max_bytes)?
+        } else {
+            bail!("not a PNG, APNG or GIF file");
+        };
+        if canvas.frames.is_empty() {
+            bail!("image has no frames");
+        }
+        if canvas.truncated {
+            tracing::warn!(
+                frames = canvas.frames.len(),
+                "animated image has more than {MAX_FRAMES} frames; the rest were dropped"
+            );
+        }
+        let frames = canvas
+            .frames
+            .drain(..)
+            .map(|(rgba, duration_ms)| Frame {
+                image: Image::new(canvas.width, canvas.height, rgba)
+                    .expect("canvas size was validated"),
+                duration_ms: if duration_ms <= MIN_FRAME_MS {
+                    DEFAULT_FRAME_MS
+                } else {
+                    duration_ms
+                },
+            })
+            .collect();
+        Ok(Self {
+            frames,
+            plays: canvas.plays,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.frames[0].image.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.frames[0].image.height()
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// `frame` modulo the frame count.
+    pub fn frame(&self, frame: u32) -> &Image {
+        &self.frames[frame as usize % self.frames.len()].image
+    }
+
+    pub fn frame_durations_ms(&self) -> Vec<u32> {
+        self.frames.iter().map(|frame| frame.duration_ms).collect()
+    }
+
+    pub fn plays(&self) -> u32 {
+        self.plays
+    }
+
+    /// Pixel bytes of every cached frame.
+    pub fn byte_len(&self) -> usize {
+        self.frames.iter().map(|frame| frame.image.byte_len()).sum()
+    }
+}
+
+impl fmt::Debug for AnimatedImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "AnimatedImage({}x{}, {} frames)",
+            self.width(),
+            self.height(),
+            self.frame_count()
+        )
+    }
+}
+
+/// What a frame leaves behind once its display time is over.
+#[derive(Clone, Copy, PartialEq)]
+enum Disposal {
+    Keep,
+    Clear,
+    Restore,
+}
+
+/// The full-size RGBA buffer frames are composited onto, and the frames produced so far.
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    frames: Vec<(Vec<u8>, u32)>,
+    plays: u32,
+    max_bytes: usize,
+    truncated: bool,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, max_bytes: usize) -> Result<Self> {
+        if !(1..=MAX_IMAGE_DIMENSION).contains(&width)
+            || !(1..=MAX_IMAGE_DIMENSION).contains(&height)
+        {
+            bail!("image is {width}x{height}; each side must be 1 to {MAX_IMAGE_DIMENSION}");
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+            frames: Vec::new(),
+            plays: 1,
+            max_bytes,
+            truncated: false,
+        })
+    }
+
+    /// Whether another frame still fits; sets `truncated` when the frame limit is hit.
+    fn has_room(&mut self) -> Result<bool> {
+        if self.frames.len() == MAX_FRAMES {
+            self.truncated = true;
+            return Ok(false);
+        }
+        let bytes = (self.frames.len() + 1) * self.pixels.len();
+        if bytes > self.max_bytes {
+            bail!(
+                "decoded frames need more than the {} bytes the image budget and upload rate \
+                 leave",
+                self.max_bytes
+            );
+        }
+        Ok(true)
+    }
+
+    /// Draw a `width` x `height` RGBA frame at (`x`, `y`), snapshot the canvas as the next
+    /// frame, then apply `disposal`. `blend` composites over what is there instead of
+    /// replacing it.
+    fn composite(
+        &mut self,
+        rgba: &[u8],
+        (x, y, width, height): (u32, u32, u32, u32),
+        blend: bool,
+        disposal: Disposal,
+        duration_ms: u32,
+    ) {
+        let previous = (disposal == Disposal::Restore).then(|| self.pixels.clone());
+        let columns = width.min(self.width.saturating_sub(x)) as usize;
+        let rows = height.min(self.height.saturating_sub(y)) as usize;
+        let canvas_row = |row: usize| ((y as usize + row) * self.width as usize + x as usize) * 4;
+        for row in 0..rows {
+            let source = &rgba[row * width as usize * 4..][..columns * 4];
+            let start = canvas_row(row);
+            let target = &mut self.pixels[start..start + columns * 4];
+            if blend {
+                for (dst, src) in target.chunks_exact_mut(4).zip(source.chunks_exact(4)) {
+                    blend_over(dst, src);
+                }
+            } else {
+                target.copy_from_slice(source);
+            }
+        }
+        self.frames.push((self.pixels.clone(), duration_ms));
+        match (disposal, previous) {
+            (Disposal::Restore, Some(previous)) => self.pixels = previous,
+            (Disposal::Clear, _) => {
+                for row in 0..rows {
+                    let start = canvas_row(row);
+                    self.pixels[start..start + columns * 4].fill(0);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Straight-alpha source-over for one pixel.
+fn blend_over(dst: &mut [u8], src: &[u8]) {
+    let src_a = src[3] as f32 / 255.0;
+    let dst_a = dst[3] as f32 / 255.0;
+    let out_a = src_a + dst_a * (1.0 - src_a);
+    if out_a <= 0.0 {
+        dst.fill(0);
+        return;
+    }
+    for i in 0..3 {
+        let value = (src[i] as f32 * src_a + dst[i] as f32 * dst_a * (1.0 - src_a)) / out_a;
+        dst[i] = value.round() as u8;
+    }
+    dst[3] = (out_a * 255.0).round() as u8;
+}
+
+fn decode_png(bytes: &[u8], max_bytes: usize) -> Result<Canvas> {
+    let mut decoder = png::Decoder::new_with_limits(
+        Cursor::new(bytes),
+        png::Limits {
+            bytes: max_bytes.max(1),
+        },
+    );
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().context("malformed PNG")?;
+    let info = reader.info();
+    let mut canvas = Canvas::new(info.width, info.height, max_bytes)?;
+    // Without an `fcTL` before it, the default image is only a fallback for decoders that
+    // do not understand APNG.
+    let (frames, skip_default) = match info.animation_control {
+        Some(control) => {
+            canvas.plays = control.num_plays;
+            let skip = info.frame_control.is_none();
+            (control.num_frames as usize + skip as usize, skip)
+        }
+        None => (1, false),
+    };
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    if skip_default {
+        reader.next_frame(&mut buffer).context("malformed PNG")?;
+    }
+    for index in skip_default as usize..frames {
+        if !canvas.has_room()? {
+            break;
+        }
+        let output = reader.next_frame(&mut buffer).context("malformed PNG")?;
+        let rgba = to_rgba(&buffer, &output)?;
+        // A plain PNG has no frame control; the defaults cover the whole canvas.
+        let control = reader.info().frame_control.unwrap_or_default();
+        let disposal = match control.dispose_op {
+            // The first frame restores to a transparent canvas.
+            png::DisposeOp::Previous if index == skip_default as usize => Disposal::Clear,
+            png::DisposeOp::Previous => Disposal::Restore,
+            png::DisposeOp::Background => Disposal::Clear,
+            png::DisposeOp::None => Disposal::Keep,
+        };
+        let denominator = if control.delay_den == 0 {
+            100
+        } else {
+            control.delay_den
+        };
+        canvas.composite(
+            &rgba,
+            (
+                control.x_offset,
+                control.y_offset,
+                output.width,
+                output.height,
+            ),
+            control.blend_op == png::BlendOp::Over,
+            disposal,
+            (control.delay_num as u32 * 1000) / denominator as u32,
+        );
+    }
+    Ok(canvas)
+}
+
+/// Expand one decoded PNG (sub)frame to packed RGBA8.
+fn to_rgba(buffer: &[u8], output: &png::OutputInfo) -> Result<Vec<u8>> {
+    let samples = output.color_type.samples();
+    if output.bit_depth != png::BitDepth::Eight {
+        bail!("unsupported PNG bit depth {:?}", output.bit_depth);
+    }
+    let mut rgba = Vec::with_capacity(output.width as usize * output.height as usize * 4);
+    for row in buffer
+        .chunks_exact(output.line_size)
+        .take(output.height as usize)
+    {
+        for pixel in row[..output.width as usize * samples].chunks_exact(samples) {
+            rgba.extend_from_slice(&match *pixel {
+                [gray] => [gray, gray, gray, 255],
+                [gray, alpha] => [gray, gray, gray, alpha],
+                [r, g, b] => [r, g, b, 255],
+                [r, g, b, a] => [r, g, b, a],
+                _ => bail!("unsupported PNG color type {:?}", output.color_type),
+            });
+        }
+    }
+    Ok(rgba)
+}
+
+/// Byte reader over a GIF stream; running out of data reads as an error.
+struct GifReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> GifReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let slice = self
+            .bytes
+            .get(self.offset..self.offset + len)
+            .context("GIF file is truncated")?;
+        self.offset += len;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// A color table of `2^(size + 1)` RGB entries.
+    fn color_table(&mut self, size: u8) -> Result<&'a [u8]> {
+        self.take(3 << (size + 1))
+    }
+
+    /// Concatenated data sub-blocks up to the zero-length terminator.
+    fn sub_blocks(&mut self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        loop {
+            let len = self.byte()? as usize;
+            if len == 0 {
+                return Ok(data);
+            }
+            data.extend_from_slice(self.take(len)?);
+        }
+    }
+}
+
+/// One top-level block of a GIF stream, parsed but not yet decoded.
+enum GifBlock<'a> {
+    Extension { label: u8, data: Vec<u8> },
+    Frame(GifFrame<'a>),
+    Trailer,
+}
+
+struct GifFrame<'a> {
+    rect: (u16, u16, u16, u16),
+    interlaced: bool,
+    colors: &'a [u8],
+    min_code_size: u8,
+    data: Vec<u8>,
+}
+
+impl<'a> GifReader<'a> {
+    fn block(&mut self, global_colors: &'a [u8]) -> Result<GifBlock<'a>> {
+        match self.byte()? {
+            0x21 => Ok(GifBlock::Extension {
+                label: self.byte()?,
+                data: self.sub_blocks()?,
+            }),
+            0x2C => {
+                let rect = (self.u16()?, self.u16()?, self.u16()?, self.u16()?);
+                let flags = self.byte()?;
+                let colors = if flags & 0x80 != 0 {
+                    self.color_table(flags & 0x07)?
+                } else {
+                    global_colors
+                };
+                Ok(GifBlock::Frame(GifFrame {
+                    rect,
+                    interlaced: flags & 0x40 != 0,
+                    colors,
+                    min_code_size: self.byte()?,
+                    data: self.sub_blocks()?,
+                }))
+            }
+            0x3B => Ok(GifBlock::Trailer),
+            other => bail!("GIF file has an unknown block type {other:#04x}"),
+        }
+    }
+}
+
+fn decode_gif(bytes: &[u8], max_bytes: usize) -> Result<Canvas> {
+    let mut reader = GifReader { bytes, offset: 6 };
+    let (width, height) = (reader.u16()?, reader.u16()?);
+    let flags = reader.byte()?;
+    reader.take(2)?; // background color index and pixel aspect ratio
+    let global_colors = if flags & 0x80 != 0 {
+        reader.color_table(flags & 0x07)?
+    } else {
+        &[]
+    };
+    let mut canvas = Canvas::new(width.into(), height.into(), max_bytes)?;
+    let mut control = (Disposal::Keep, 0u32, None::<u8>);
+    loop {
+        let frame = match reader.block(global_colors) {
+            Ok(GifBlock::Frame(frame)) => frame,
+            Ok(GifBlock::Extension { label, data }) => {
+                match (label, data.as_slice()) {
+                    (0xF9, [flags, delay_lo, delay_hi, transparent, ..]) => {
+                        let disposal = match (flags >> 2) & 0x07 {
+                            2 => Disposal::Clear,
+                            3 => Disposal::Restore,
+                            _ => Disposal::Keep,
+                        };
+                        let delay = u16::from_le_bytes([*delay_lo, *delay_hi]) as u32 * 10;
+                        control = (disposal, delay, (flags & 1 == 1).then_some(*transparent));
+                    }
+                    // NETSCAPE2.0 loop extension: loops after the first play, 0 = forever.
+                    (0xFF, [rest @ .., 1, lo, hi]) if rest == b"NETSCAPE2.0" => {
+                        let loops = u16::from_le_bytes([*lo, *hi]) as u32;
+                        canvas.plays = if loops == 0 { 0 } else { loops + 1 };
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            Ok(GifBlock::Trailer) => break,
+            // A file cut short after some frames still plays the frames it has.
+            Err(_) if !canvas.frames.is_empty() => break,
+            Err(err) => return Err(err),
+        };
+        if !canvas.has_room()? {
+            break;
+        }
+        let (x, y, w, h) = frame.rect;
+        let indices = frame.indices()?;
+        let (disposal, delay, transparent) =
+            std::mem::replace(&mut control, (Disposal::Keep, 0, None));
+        let rgba: Vec<u8> = indices
+            .iter()
+            .flat_map(|&index| {
+                let color = index
+                    .filter(|&index| Some(index) != transparent)
+                    .and_then(|index| frame.colors.get(index as usize * 3..index as usize * 3 + 3));
+                match color {
+                    Some(rgb) => [rgb[0], rgb[1], rgb[2], 255],
+                    None => [0; 4],
+                }
+            })
+            .collect();
+        // Transparent GIF pixels leave the canvas untouched, which blending a fully
+        // transparent source over it does.
+        canvas.composite(
+            &rgba,
+            (x.into(), y.into(), w.into(), h.into()),
+            true,
+            disposal,
+            delay,
+        );
+    }
+    Ok(canvas)
+}
+
+impl GifFrame<'_> {
+    /// Palette index of each pixel in row order, or `None` where the data ran out.
+    fn indices(&self) -> Result<Vec<Option<u8>>> {
+        if !(1..=11).contains(&self.min_code_size) {
+            bail!(
+                "GIF frame has an invalid LZW code size {}",
+                self.min_code_size
+            );
+        }
+        let (_, _, width, height) = self.rect;
+        let mut indices = vec![0; width as usize * height as usize];
+        let mut lzw = LzwDecoder::new(BitOrder::Lsb, self.min_code_size);
+        let (mut read, mut written) = (0, 0);
+        while written < indices.len() {
+            let result = lzw.decode_bytes(&self.data[read..], &mut indices[written..]);
+            read += result.consumed_in;
+            written += result.consumed_out;
+            if !matches!(result.status, Ok(LzwStatus::Ok)) {
+                break;
+            }
+        }
+        let mut indices: Vec<Option<u8>> = indices
+            .into_iter()
+            .enumerate()
+            .map(|(i, index)| (i < written).then_some(index))
+            .collect();
+        if self.interlaced {
+            indices = deinterlace(&indices, width as usize, height as usize);
+        }
+        Ok(indices)
+    }
+}
+
+/// Reorder the rows of an interlaced GIF frame (passes of every 8th row from 0, every 8th
+/// from 4, every 4th from 2, then every 2nd from 1).
+fn deinterlace<T: Copy + Default>(indices: &[T], width: usize, height: usize) -> Vec<T> {
+    let mut out = vec![T::default(); indices.len()];
+    let rows = [(0, 8), (4, 8), (2, 4), (1, 2)]
+        .into_iter()
+        .flat_map(|(start, step)| (start..height).step_by(step));
+    for (source, target) in rows.enumerate() {
+        out[target * width..][..width].copy_from_slice(&indices[source * width..][..width]);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AnimatedImage;
+
+    /// A 2x2 GIF: an opaque red frame, then a frame with one blue pixel whose transparent
+    /// pixels let the red show through. Loops forever.
+    fn two_frame_gif() -> Vec<u8> {
+        let mut gif = b"GIF89a\x02\x00\x02\x00\x80\x00\x00".to_vec();
+        gif.extend_from_slice(&[255, 0, 0, 0, 0, 255]); // palette: red, blue
+        gif.extend_from_slice(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00");
+        let frame = |gif: &mut Vec<u8>, control: [u8; 4], indices: &[u8]| {
+            gif.extend_from_slice(&[0x21, 0xF9, 4]);
+            gif.extend_from_slice(&control);
+            gif.extend_from_slice(&[0, 0x2C, 0, 0, 0, 0, 2, 0, 2, 0, 0, 2]);
+            let data = weezl::encode::Encoder::new(weezl::BitOrder::Lsb, 2)
+                .encode(indices)
+                .unwrap();
+            for block in data.chunks(255) {
+                gif.push(block.len() as u8);
+                gif.extend_from_slice(block);
+            }
+            gif.push(0);
+        };
+        frame(&mut gif, [0, 5, 0, 0], &[0, 0, 0, 0]);
+        // Transparent index 0, 2 cs delay.
+        frame(&mut gif, [1, 2, 0, 0], &[1, 0, 0, 0]);
+        gif.push(0x3B);
+        gif
+    }
+
+    fn pixels(image: &AnimatedImage, frame: u32) -> Vec<u8> {
+        image.frame(frame).to_peniko().data.data().to_vec()
+    }
+
+    #[test]
+    fn composites_gif_frames() {
+        let gif = AnimatedImage::decode(&two_frame_gif(), usize::MAX).unwrap();
+        assert_eq!((gif.width(), gif.height(), gif.frame_count()), (2, 2, 2));
+        assert_eq!(gif.frame_durations_ms(), [50, 20]);
+        assert_eq!(gif.plays(), 0);
+        assert_eq!(pixels(&gif, 0), [255, 0, 0, 255].repeat(4));
+        assert_eq!(pixels(&gif, 1)[..8], [0, 0, 255, 255, 255, 0, 0, 255]);
+        assert_eq!(pixels(&gif, 3), pixels(&gif, 1), "frames wrap around");
+        assert_eq!(gif.byte_len(), 32);
+
+        assert!(
+            AnimatedImage::decode(&two_frame_gif(), 16).is_err(),
+            "over budget"
+        );
+        let mut truncated = two_frame_gif();
+        truncated.truncate(truncated.len() - 12);
+        assert_eq!(
+            AnimatedImage::decode(&truncated, usize::MAX)
+                .unwrap()
+                .frame_count(),
+            1
+        );
+        assert!(AnimatedImage::decode(b"GIF89a", usize::MAX).is_err());
+        assert!(AnimatedImage::decode(b"\xFF\xD8\xFF", usize::MAX).is_err());
+    }
+
+    #[test]
+    fn decodes_apng_frames_and_still_pngs() {
+        let mut apng = Vec::new();
+        let mut encoder = png::Encoder::new(&mut apng, 2, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_animated(2, 3).unwrap();
+        encoder.set_frame_delay(1, 4).unwrap();
+        let mut writer = encoder.write_header().unwrap();
+        writer
+            .write_image_data(&[0, 255, 0, 255, 0, 255, 0, 255])
+            .unwrap();
+        writer.set_frame_dimension(1, 1).unwrap();
+        writer.set_frame_position(1, 0).unwrap();
+        writer.set_blend_op(png::BlendOp::Over).unwrap();
+        writer.write_image_data(&[255, 255, 255, 0]).unwrap();
+        writer.finish().unwrap();
+
+        let image = AnimatedImage::decode(&apng, usize::MAX).unwrap();
+        assert_eq!(image.frame_count(), 2);
+        assert_eq!(image.frame_durations_ms(), [250, 250]);
+        assert_eq!(image.plays(), 3);
+        assert_eq!(
+            pixels(&image, 1),
+            pixels(&image, 0),
+            "transparent frame blends over"
+        );
+
+        let mut still = Vec::new();
+        let mut encoder = png::Encoder::new(&mut still, 1, 1);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(&[128])
+            .unwrap();
+        let image = AnimatedImage::decode(&still, usize::MAX).unwrap();
+        assert_eq!((image.frame_count(), image.plays()), (1, 1));
+        assert_eq!(pixels(&image, 0), [128, 128, 128, 255]);
+    }
+}
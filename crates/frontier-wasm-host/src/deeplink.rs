@@ -0,0 +1,170 @@
+//! `frontier://` deep links. `frontier://run?src=PATH` launches the host with the
+//! component at `PATH`; every link the host is opened with is also handed to the guest's
+//! `deep-link-opened` export so it can route on the rest of the URL.
+//!
+//! `register-scheme` makes the host the system handler for the scheme: a desktop entry
+//! plus `xdg-mime` on Linux, the per-user `HKCU\Software\Classes` key on Windows. macOS
+//! reads URL schemes from an app bundle's `Info.plist`, which a bare binary does not have.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+use crate::navigation;
+
+pub const SCHEME: &str = "frontier";
+
+/// A parsed `frontier://` link.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeepLink {
+    /// The link as given, passed on to the guest.
+    pub url: String,
+    /// `src` of a `run` link: the component to launch.
+    pub src: Option<String>,
+}
+
+impl DeepLink {
+    pub fn parse(url: &str) -> Result<Self> {
+        let Some(rest) = url
+            .split_once(':')
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(SCHEME))
+            .map(|(_, rest)| rest.trim_start_matches('/'))
+        else {
+            bail!("'{url}' is not a {SCHEME}:// link");
+        };
+        let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let action = action.trim_end_matches('/');
+        let src = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == "src")
+            .map(|(_, value)| navigation::percent_decode(&value.replace('+', " ")))
+            .transpose()?;
+        match action {
+            "run" if src.is_none() => bail!("'{url}' is missing the src parameter"),
+            "run" => {}
+            // Other actions are only for the guest.
+            _ if src.is_some() => bail!("src is only understood by {SCHEME}://run links"),
+            _ => {}
+        }
+        Ok(Self {
+            url: url.to_string(),
+            src,
+        })
+    }
+
+    /// The local component file a `run` link points at.
+    pub fn component(&self) -> Result<Option<PathBuf>> {
+        self.src
+            .as_deref()
+            .map(|src| navigation::resolve(src, None))
+            .transpose()
+    }
+}
+
+/// Register `exe` as the handler for `frontier://` links for the current user; returns
+/// what was changed, for the command's output.
+pub fn register(exe: &Path) -> Result<String> {
+    if cfg!(target_os = "linux") {
+        register_xdg(exe)
+    } else if cfg!(windows) {
+        register_windows(exe)
+    } else {
+        bail!(
+            "registering the {SCHEME}:// scheme is not supported on this platform; \
+             declare it under CFBundleURLTypes in the app bundle's Info.plist instead"
+        )
+    }
+}
+
+fn register_xdg(exe: &Path) -> Result<String> {
+    const DESKTOP_FILE: &str = "frontier-wasm-host.desktop";
+    let dir = dirs::data_dir()
+        .context("no user data directory to install a desktop entry in")?
+        .join("applications");
+    std::fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let path = dir.join(DESKTOP_FILE);
+    std::fs::write(&path, desktop_entry(exe))
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    run(Command::new("xdg-mime").args([
+        "default",
+        DESKTOP_FILE,
+        &format!("x-scheme-handler/{SCHEME}"),
+    ]))?;
+    Ok(format!(
+        "installed {} and set it as the {SCHEME}:// handler",
+        path.display()
+    ))
+}
+
+fn desktop_entry(exe: &Path) -> String {
+    format!(
+        "[Desktop Entry]\nType=Application\nName=Frontier\nExec=\"{}\" --open %u\n\
+         NoDisplay=true\nMimeType=x-scheme-handler/{SCHEME};\n",
+        exe.display()
+    )
+}
+
+fn register_windows(exe: &Path) -> Result<String> {
+    let key = format!(r"HKCU\Software\Classes\{SCHEME}");
+    let command = format!("\"{}\" --open \"%1\"", exe.display());
+    run(Command::new("reg").args(["add", &key, "/ve", "/d", "URL:Frontier", "/f"]))?;
+    run(Command::new("reg").args(["add", &key, "/v", "URL Protocol", "/d", "", "/f"]))?;
+    run(Command::new("reg").args([
+        "add",
+        &format!(r"{key}\shell\open\command"),
+        "/ve",
+        "/d",
+        &command,
+        "/f",
+    ]))?;
+    Ok(format!("registered {key}"))
+}
+
+fn run(command: &mut Command) -> Result<()> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let status = command
+        .status()
+        .with_context(|| format!("failed to run {program}"))?;
+    if !status.success() {
+        bail!("{program} exited with {status}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{desktop_entry, DeepLink};
+
+    #[test]
+    fn parses_run_and_guest_links() {
+        let link = DeepLink::parse("frontier://run?src=%2Ftmp%2Fmy+app.wasm&theme=dark").unwrap();
+        assert_eq!(link.src.as_deref(), Some("/tmp/my app.wasm"));
+        assert_eq!(
+            link.url,
+            "frontier://run?src=%2Ftmp%2Fmy+app.wasm&theme=dark"
+        );
+
+        let link = DeepLink::parse("FRONTIER://notes/42?highlight=yes").unwrap();
+        assert_eq!(link.src, None);
+
+        for url in [
+            "https://example.com",
+            "frontier://run",
+            "frontier://notes?src=app.wasm",
+            "frontier://run?src=%zz",
+        ] {
+            assert!(DeepLink::parse(url).is_err(), "accepted {url}");
+        }
+    }
+
+    #[test]
+    fn desktop_entry_passes_the_link_to_open() {
+        let entry = desktop_entry(Path::new("/opt/frontier/frontier-wasm-host"));
+        assert!(entry.contains("Exec=\"/opt/frontier/frontier-wasm-host\" --open %u\n"));
+        assert!(entry.contains("MimeType=x-scheme-handler/frontier;\n"));
+    }
+}
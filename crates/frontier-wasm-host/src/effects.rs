@@ -0,0 +1,559 @@
+//! Guest-supplied WGSL post-process effects. Draw commands between `push-shader-layer` and
+//! `pop-shader-layer` are rendered into an offscreen layer, which the effect then samples
+//! while compositing it over everything drawn before.
+//!
+//! Guests only write a `shade` function; the host wraps it with fixed bindings and entry
+//! points, and rejects sources that declare their own. wgpu bounds every loop and memory
+//! access in the generated code, so an effect can be slow but cannot read outside its
+//! layer.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use wgpu::naga;
+
+use crate::host::DrawCommand;
+
+/// Largest accepted `register-shader` source.
+pub const MAX_SHADER_BYTES: usize = 64 * 1024;
+/// Floats passed to an effect as `effect.params`.
+pub const PARAM_COUNT: usize = 8;
+
+/// Declarations every effect can use. `resolution` is the layer size in physical pixels.
+const PRELUDE: &str = "\
+struct EffectInputs {
+    resolution: vec2<f32>,
+    params: array<vec4<f32>, 2>,
+}
+
+@group(0) @binding(0) var layer_texture: texture_2d<f32>;
+@group(0) @binding(1) var layer_sampler: sampler;
+@group(0) @binding(2) var<uniform> effect: EffectInputs;
+
+fn sample_layer(uv: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(layer_texture, layer_sampler, uv, 0.0);
+}
+";
+
+/// A full-screen triangle whose fragments call the guest's `shade`.
+const EPILOGUE: &str = "
+struct HostVertex {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn host_vertex(@builtin(vertex_index) index: u32) -> HostVertex {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return HostVertex(vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0), uv);
+}
+
+@fragment
+fn host_fragment(vertex: HostVertex) -> @location(0) vec4<f32> {
+    return shade(vertex.uv);
+}
+";
+
+/// Composites plain layers unchanged.
+const IDENTITY_SHADE: &str = "fn shade(uv: vec2<f32>) -> vec4<f32> { return sample_layer(uv); }";
+
+/// Bindings declared by `PRELUDE`; guests may not add any.
+const HOST_BINDINGS: usize = 3;
+
+static NEXT_SHADER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A validated effect, ready to compile on the GPU. Cloning shares the source.
+#[derive(Clone)]
+pub struct Shader {
+    inner: Arc<ShaderData>,
+}
+
+struct ShaderData {
+    /// Prelude, guest source and epilogue.
+    wgsl: String,
+    /// The guest's part, as registered.
+    source: String,
+    id: u64,
+}
+
+impl Shader {
+    /// Check that `source` defines `fn shade(uv: vec2<f32>) -> vec4<f32>` and stays inside
+    /// the sandbox. Error line numbers refer to `source`.
+    pub fn parse(source: &str) -> Result<Self> {
+        if source.len() > MAX_SHADER_BYTES {
+            bail!(
+                "shader is {} bytes; the limit is {MAX_SHADER_BYTES}",
+                source.len()
+            );
+        }
+        let wgsl = format!("{PRELUDE}{source}\n{EPILOGUE}");
+        let module =
+            naga::front::wgsl::parse_str(&wgsl).map_err(|err| match err.location(&wgsl) {
+                Some(location) => {
+                    let prelude_lines = PRELUDE.lines().count() as u32;
+                    let line = location.line_number.saturating_sub(prelude_lines);
+                    anyhow!("line {line}: {}", err.message())
+                }
+                None => anyhow!("{}", err.message()),
+            })?;
+        let bindings = module
+            .global_variables
+            .iter()
+            .filter(|(_, global)| global.binding.is_some())
+            .count();
+        if bindings != HOST_BINDINGS {
+            bail!("effect shaders may not declare resource bindings");
+        }
+        if module.entry_points.len() != 2 {
+            bail!("effect shaders may not declare entry points");
+        }
+        if !module.overrides.is_empty() {
+            bail!("effect shaders may not declare override constants");
+        }
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .map_err(|err| anyhow!(err.into_inner()))?;
+        Ok(Self {
+            inner: Arc::new(ShaderData {
+                wgsl,
+                source: source.to_string(),
+                id: NEXT_SHADER_ID.fetch_add(1, Ordering::Relaxed),
+            }),
+        })
+    }
+
+    /// Identifies the compiled pipeline: clones share it.
+    pub fn id(&self) -> u64 {
+        self.inner.id
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.inner.source.len()
+    }
+
+    pub fn source(&self) -> &str {
+        &self.inner.source
+    }
+
+    fn wgsl(&self) -> &str {
+        &self.inner.wgsl
+    }
+}
+
+impl fmt::Debug for Shader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Shader({} bytes)", self.byte_len())
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Shader {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let source: &str = u.arbitrary()?;
+        Ok(Self::parse(source)
+            .unwrap_or_else(|_| Self::parse(IDENTITY_SHADE).expect("identity shader parses")))
+    }
+}
+
+/// Consecutive draw commands rendered into one layer; `effect` is `None` for plain layers.
+#[derive(Debug)]
+pub struct LayerRun<'a> {
+    pub commands: &'a [DrawCommand],
+    pub effect: Option<(&'a Shader, &'a [f32; PARAM_COUNT])>,
+}
+
+/// Split `commands` at the outermost shader layers. The first run is always plain and
+/// draws over the clear colour; layers pushed inside another draw without their effect,
+/// and unbalanced pops are ignored.
+pub fn layer_runs(commands: &[DrawCommand]) -> Vec<LayerRun<'_>> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut effect = None;
+    let mut depth = 0usize;
+    for (index, command) in commands.iter().enumerate() {
+        match command {
+            DrawCommand::PushShaderLayer { shader, params } => {
+                depth += 1;
+                if depth == 1 {
+                    runs.push(LayerRun {
+                        commands: &commands[start..index],
+                        effect: None,
+                    });
+                    start = index + 1;
+                    effect = Some((shader, params));
+                }
+            }
+            DrawCommand::PopShaderLayer if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    runs.push(LayerRun {
+                        commands: &commands[start..index],
+                        effect: effect.take(),
+                    });
+                    start = index + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    runs.push(LayerRun {
+        commands: &commands[start..],
+        effect,
+    });
+    if runs.last().is_some_and(|run| run.effect.is_some()) {
+        runs.push(LayerRun {
+            commands: &[],
+            effect: None,
+        });
+    }
+    runs
+}
+
+/// GPU side: an offscreen layer texture and one render pipeline per shader, compositing
+/// the layer over the render target with straight-alpha blending (Vello's output).
+pub struct EffectCompositor {
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    sampler: wgpu::Sampler,
+    uniforms: wgpu::Buffer,
+    identity: wgpu::RenderPipeline,
+    /// `None` when the driver rejected the shader; its layers then draw unchanged.
+    pipelines: HashMap<u64, CachedPipeline>,
+    /// Shared with Vello so guest shaders land in the on-disk pipeline cache too.
+    cache: Option<wgpu::PipelineCache>,
+    layer: Option<(wgpu::Texture, wgpu::TextureView)>,
+}
+
+struct CachedPipeline {
+    pipeline: Option<wgpu::RenderPipeline>,
+    used: bool,
+}
+
+impl EffectCompositor {
+    pub fn new(device: &wgpu::Device, cache: Option<wgpu::PipelineCache>) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("frontier.effects"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("frontier.effects"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("frontier.effects"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frontier.effects.uniforms"),
+            size: UNIFORM_FLOATS as u64 * 4,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let identity_source = format!("{PRELUDE}{IDENTITY_SHADE}\n{EPILOGUE}");
+        let identity = create_pipeline(device, &pipeline_layout, cache.as_ref(), &identity_source);
+        Self {
+            bind_group_layout,
+            pipeline_layout,
+            sampler,
+            uniforms,
+            identity,
+            pipelines: HashMap::new(),
+            cache,
+            layer: None,
+        }
+    }
+
+    /// The texture to render the next layer into, resized to `width` x `height`.
+    pub fn layer_view(
+        &mut self,
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+    ) -> &wgpu::TextureView {
+        let stale = self
+            .layer
+            .as_ref()
+            .is_none_or(|(texture, _)| texture.width() != width || texture.height() != height);
+        if stale {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("frontier.effects.layer"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.layer = Some((texture, view));
+        }
+        &self.layer.as_ref().expect("layer was just created").1
+    }
+
+    /// Draw the layer over `target` through `effect`, or unchanged without one.
+    pub fn composite(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target: &wgpu::TextureView,
+        effect: Option<(&Shader, &[f32; PARAM_COUNT])>,
+    ) {
+        let Some((texture, view)) = &self.layer else {
+            return;
+        };
+        let mut uniforms = [0.0f32; UNIFORM_FLOATS];
+        uniforms[0] = texture.width() as f32;
+        uniforms[1] = texture.height() as f32;
+        let pipeline = match effect {
+            Some((shader, params)) => {
+                uniforms[4..].copy_from_slice(params);
+                let cached = self
+                    .pipelines
+                    .entry(shader.id())
+                    .or_insert_with(|| CachedPipeline {
+                        pipeline: compile(
+                            device,
+                            &self.pipeline_layout,
+                            self.cache.as_ref(),
+                            shader,
+                        ),
+                        used: false,
+                    });
+                cached.used = true;
+                cached.pipeline.as_ref().unwrap_or(&self.identity)
+            }
+            None => &self.identity,
+        };
+        let bytes: Vec<u8> = uniforms
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        queue.write_buffer(&self.uniforms, 0, &bytes);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("frontier.effects"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.uniforms.as_entire_binding(),
+                },
+            ],
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("frontier.effects"),
+        });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("frontier.effects"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Drop pipelines for shaders nobody drew with since the last call.
+    pub fn trim(&mut self) {
+        self.pipelines
+            .retain(|_, cached| std::mem::take(&mut cached.used));
+    }
+
+    /// Free everything that is rebuilt on demand: shader pipelines and the layer texture.
+    pub fn release(&mut self) {
+        self.pipelines.clear();
+        self.layer = None;
+    }
+}
+
+/// `EffectInputs`: the resolution padded to 16 bytes, then the parameters.
+const UNIFORM_FLOATS: usize = 4 + PARAM_COUNT;
+
+/// Build `shader`'s pipeline, or `None` (with a warning) if the driver rejects it.
+fn compile(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    cache: Option<&wgpu::PipelineCache>,
+    shader: &Shader,
+) -> Option<wgpu::RenderPipeline> {
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let pipeline = create_pipeline(device, layout, cache, shader.wgsl());
+    match pollster::block_on(device.pop_error_scope()) {
+        None => Some(pipeline),
+        Some(err) => {
+            tracing::warn!(%err, "effect shader failed to compile; drawing its layers without it");
+            None
+        }
+    }
+}
+
+fn create_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    cache: Option<&wgpu::PipelineCache>,
+    wgsl: &str,
+) -> wgpu::RenderPipeline {
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("frontier.effects"),
+        source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+    });
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("frontier.effects"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: &module,
+            entry_point: Some("host_vertex"),
+            compilation_options: Default::default(),
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &module,
+            entry_point: Some("host_fragment"),
+            compilation_options: Default::default(),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{layer_runs, Shader, IDENTITY_SHADE};
+    use crate::geometry::Vec2;
+    use crate::host::{Color, DrawCommand};
+
+    #[test]
+    fn accepts_shade_functions_and_rejects_escapes() {
+        let crt = "fn shade(uv: vec2<f32>) -> vec4<f32> {\n    let line = 0.8 + 0.2 * sin(uv.y * effect.resolution.y);\n    return sample_layer(uv) * vec4<f32>(vec3<f32>(line * effect.params[0].x), 1.0);\n}";
+        assert!(Shader::parse(crt).is_ok());
+        assert_ne!(
+            Shader::parse(IDENTITY_SHADE).unwrap().id(),
+            Shader::parse(IDENTITY_SHADE).unwrap().id()
+        );
+
+        let err = |source: &str| format!("{:#}", Shader::parse(source).unwrap_err());
+        assert!(
+            err("fn other() {}").contains("shade"),
+            "{}",
+            err("fn other() {}")
+        );
+        assert!(
+            err("\n\nfn shade(uv: vec2<f32>) -> vec4<f32> { return nope; }").starts_with("line 3:")
+        );
+        let storage = format!(
+            "@group(0) @binding(3) var<storage, read_write> leak: array<u32>;\n{IDENTITY_SHADE}"
+        );
+        assert!(err(&storage).contains("resource bindings"));
+        let entry = format!("@fragment fn main() -> @location(0) vec4<f32> {{ return vec4<f32>(); }}\n{IDENTITY_SHADE}");
+        assert!(err(&entry).contains("entry points"));
+        assert!(err(&" ".repeat(super::MAX_SHADER_BYTES + 1)).contains("limit"));
+    }
+
+    #[test]
+    fn splits_commands_at_outermost_layers() {
+        let shader = Shader::parse(IDENTITY_SHADE).unwrap();
+        let push = DrawCommand::PushShaderLayer {
+            shader: shader.clone(),
+            params: [0.5; 8],
+        };
+        let rect = DrawCommand::FillRect {
+            origin: Vec2 { x: 0.0, y: 0.0 },
+            size: Vec2 { x: 1.0, y: 1.0 },
+            color: Color::default(),
+        };
+        let commands = [
+            rect.clone(),
+            DrawCommand::PopShaderLayer,
+            push.clone(),
+            rect.clone(),
+            push,
+            rect.clone(),
+            DrawCommand::PopShaderLayer,
+            DrawCommand::PopShaderLayer,
+            rect.clone(),
+        ];
+        let runs = layer_runs(&commands);
+        let shape: Vec<_> = runs
+            .iter()
+            .map(|run| (run.commands.len(), run.effect.is_some()))
+            .collect();
+        assert_eq!(shape, [(2, false), (4, true), (1, false)]);
+        assert_eq!(runs[1].effect.unwrap().0.id(), shader.id());
+
+        let unclosed = [DrawCommand::PopShaderLayer, commands[2].clone(), rect];
+        let shape: Vec<_> = layer_runs(&unclosed)
+            .iter()
+            .map(|run| (run.commands.len(), run.effect.is_some()))
+            .collect();
+        assert_eq!(shape, [(1, false), (1, true), (0, false)]);
+    }
+}
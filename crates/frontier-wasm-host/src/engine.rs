@@ -0,0 +1,118 @@
+//! Wasmtime engine tuning: which proposals guests may use, how instances are allocated
+//! and how many host threads run them off the main thread. Set with `--simd`, `--threads`,
+//! `--memory64`, `--pooling-allocator` and `--worker-threads`, or the `[engine]` table of
+//! `frontier-host.toml`.
+//!
+//! `compile` must be given the same options as the host that will load its output;
+//! Wasmtime refuses `.cwasm` files built for a different configuration.
+
+use std::num::NonZeroUsize;
+
+use serde::Deserialize;
+use wasmtime::{Config, InstanceAllocationStrategy, PoolingAllocationConfig};
+
+/// Instances the pool holds at once. A reload instantiates the new component before the
+/// old one is dropped, so two of everything must fit.
+const POOLED_COMPONENTS: u32 = 4;
+/// Core instances, memories and tables across the pooled components. A component built
+/// with `cargo component` is several core modules: the program, the WASI adapter and shims.
+const POOLED_CORE_INSTANCES: u32 = 64;
+const POOLED_MEMORIES: u32 = 16;
+const POOLED_TABLES: u32 = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct EngineOptions {
+    /// Fixed-width SIMD (`v128`) and relaxed SIMD.
+    pub simd: bool,
+    /// Atomics and shared memories. The guest still cannot spawn threads.
+    pub threads: bool,
+    /// Memories indexed with 64-bit addresses.
+    pub memory64: bool,
+    /// Preallocate instance slots so instantiating, and so reloading, is faster at the
+    /// cost of reserving address space up front.
+    pub pooling_allocator: bool,
+    /// Threads in the [`WorkerPool`](crate::pool::WorkerPool) that runs background tasks
+    /// and `compile` jobs; one per core, at most 8, when unset.
+    pub worker_threads: Option<NonZeroUsize>,
+}
+
+impl Default for EngineOptions {
+    /// Wasmtime's own defaults.
+    fn default() -> Self {
+        Self {
+            simd: true,
+            threads: true,
+            memory64: false,
+            pooling_allocator: false,
+            worker_threads: None,
+        }
+    }
+}
+
+impl EngineOptions {
+    pub fn worker_threads(&self) -> NonZeroUsize {
+        self.worker_threads
+            .unwrap_or_else(crate::pool::WorkerPool::default_size)
+    }
+
+    pub fn config(&self) -> Config {
+        let mut config = Config::new();
+        config.wasm_component_model(true);
+        config.wasm_backtrace_details(wasmtime::WasmBacktraceDetails::Enable);
+        config.wasm_simd(self.simd);
+        config.wasm_relaxed_simd(self.simd);
+        config.wasm_threads(self.threads);
+        config.wasm_memory64(self.memory64);
+        if self.pooling_allocator {
+            let mut pooling = PoolingAllocationConfig::default();
+            pooling
+                .total_component_instances(POOLED_COMPONENTS)
+                .total_core_instances(POOLED_CORE_INSTANCES)
+                .total_memories(POOLED_MEMORIES)
+                .total_tables(POOLED_TABLES);
+            config.allocation_strategy(InstanceAllocationStrategy::Pooling(pooling));
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::EngineOptions;
+    use crate::model::LogicalSize;
+    use crate::runtime::{ComponentRuntime, ComponentSource};
+    use crate::LaunchConfig;
+
+    const COUNTER: &[u8] = include_bytes!("../../../assets/counter-component.wasm");
+
+    #[test]
+    fn counter_runs_under_every_option() {
+        for engine in [
+            EngineOptions::default(),
+            EngineOptions {
+                simd: false,
+                threads: false,
+                ..EngineOptions::default()
+            },
+            EngineOptions {
+                memory64: true,
+                pooling_allocator: true,
+                worker_threads: Some(NonZeroUsize::MIN),
+                ..EngineOptions::default()
+            },
+        ] {
+            let launch = LaunchConfig {
+                engine,
+                ..LaunchConfig::default()
+            };
+            let source = ComponentSource::embedded("counter", COUNTER);
+            let mut runtime = ComponentRuntime::with_launch(source, launch).unwrap();
+            runtime.call_init(LogicalSize::default()).unwrap();
+            runtime.reload().unwrap();
+            runtime.call_init(LogicalSize::default()).unwrap();
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Linux input device nodes (`/dev/input/event*`), shared by gamepad navigation and
+//! rumble. Struct layouts follow `linux/input.h` on 64-bit targets.
+
+use std::ffi::CString;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::{io, mem};
+
+use anyhow::Result;
+
+pub const EV_KEY: u16 = 0x01;
+pub const EV_ABS: u16 = 0x03;
+pub const EV_FF: u16 = 0x15;
+/// Bytes needed for the largest event code bitmask (`KEY_MAX` is 0x2ff).
+const BITS_LEN: usize = 96;
+
+#[repr(C)]
+pub struct InputEvent {
+    pub time: libc::timeval,
+    pub kind: u16,
+    pub code: u16,
+    pub value: i32,
+}
+
+pub const fn ioc(dir: u64, nr: u64, size: usize) -> u64 {
+    (dir << 30) | ((size as u64) << 16) | ((b'E' as u64) << 8) | nr
+}
+
+pub const READ: u64 = 2;
+pub const WRITE: u64 = 1;
+
+const _: () = assert!(mem::size_of::<InputEvent>() == 24);
+
+/// An open event node, closed on drop.
+pub struct Device {
+    fd: libc::c_int,
+}
+
+impl Device {
+    /// Open `path` for non-blocking reads and writes.
+    pub fn open(path: &Path) -> Result<Self> {
+        let path = CString::new(path.as_os_str().as_bytes())?;
+        // SAFETY: `path` is a valid NUL-terminated string.
+        let fd = unsafe { libc::open(path.as_ptr(), libc::O_RDWR | libc::O_NONBLOCK) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(Self { fd })
+    }
+
+    /// Issue `request` with `arg`, retrying on `EINTR`.
+    pub fn ioctl<T>(&self, request: u64, arg: &mut T) -> io::Result<()> {
+        loop {
+            // SAFETY: every request constant encodes the size of the `T` it is used with.
+            let result = unsafe { libc::ioctl(self.fd, request as _, arg as *mut T) };
+            if result >= 0 {
+                return Ok(());
+            }
+            let err = io::Error::last_os_error();
+            if err.kind() != io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+    }
+
+    /// Whether the device reports `code` for events of type `kind` (`EVIOCGBIT`).
+    pub fn supports(&self, kind: u16, code: u16) -> bool {
+        let mut bits = [0u8; BITS_LEN];
+        let request = ioc(READ, 0x20 + kind as u64, BITS_LEN);
+        self.ioctl(request, &mut bits).is_ok()
+            && bits
+                .get(code as usize / 8)
+                .is_some_and(|byte| byte & (1 << (code % 8)) != 0)
+    }
+
+    pub fn write_event(&self, kind: u16, code: u16, value: i32) -> io::Result<()> {
+        let event = InputEvent {
+            time: libc::timeval {
+                tv_sec: 0,
+                tv_usec: 0,
+            },
+            kind,
+            code,
+            value,
+        };
+        // SAFETY: `event` is a live `input_event` and the length is its size.
+        let written = unsafe {
+            libc::write(
+                self.fd,
+                (&event as *const InputEvent).cast(),
+                mem::size_of::<InputEvent>(),
+            )
+        };
+        if written < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Every event queued since the last call. Fails once the device is unplugged.
+    pub fn read_events(&self) -> io::Result<Vec<InputEvent>> {
+        let mut events = Vec::new();
+        loop {
+            // SAFETY: `InputEvent` is plain old data; all-zero is a valid value.
+            let mut event: InputEvent = unsafe { mem::zeroed() };
+            // SAFETY: `event` is writable for its whole size.
+            let read = unsafe {
+                libc::read(
+                    self.fd,
+                    (&mut event as *mut InputEvent).cast(),
+                    mem::size_of::<InputEvent>(),
+                )
+            };
+            if read == mem::size_of::<InputEvent>() as isize {
+                events.push(event);
+                continue;
+            }
+            if read >= 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let err = io::Error::last_os_error();
+            match err.kind() {
+                io::ErrorKind::WouldBlock => return Ok(events),
+                io::ErrorKind::Interrupted => continue,
+                _ => return Err(err),
+            }
+        }
+    }
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        // SAFETY: `fd` was opened by `Device::open` and is closed only here.
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+/// Every `/dev/input/event*` node we are allowed to open, in name order.
+pub fn open_all() -> Vec<Device> {
+    let Ok(entries) = fs::read_dir("/dev/input") else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("event"))
+        })
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| Device::open(&path).ok())
+        .collect()
+}
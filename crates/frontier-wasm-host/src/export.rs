@@ -0,0 +1,1070 @@
+//! Vector export of a frame's draw commands, which are already resolution independent.
+//! SVG suits the web and design tools; PDF suits printing. Text is written as text, so it
+//! stays selectable: SVG names the host's fonts and PDF uses the standard Helvetica and
+//! Courier faces, so line widths can differ slightly from the window. Shader layers have no
+//! vector equivalent and their contents are exported without the effect.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use vello::kurbo::{Affine, BezPath, Cap, Circle, Join, PathEl, Point, Rect, Shape, Stroke};
+use vello::peniko::{Color as PenikoColor, Fill};
+
+use crate::bidi::TextDirection;
+use crate::component::vello::canvas::host::ExportFormat as WitExportFormat;
+use crate::geometry::Vec2;
+use crate::graphics::{self, DEFAULT_CLEAR_COLOR};
+use crate::host::{DrawCommand, FontFamily, FrameOutput, PatternRepeat, TextFlow, WritingMode};
+use crate::images::{FilterCache, Image, ImageStyle};
+use crate::model::LogicalSize;
+use crate::particles::ParticleSprite;
+use crate::vector::VectorSink;
+
+/// Pattern fills needing more tiles than this are left out of exports.
+const MAX_PATTERN_TILES: usize = 10_000;
+/// PDF points per logical pixel (72 points per inch at 96 logical pixels per inch).
+const PDF_POINTS_PER_PIXEL: f64 = 0.75;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    Svg,
+    Pdf,
+}
+
+impl ExportFormat {
+    pub fn from_wit(format: WitExportFormat) -> Self {
+        match format {
+            WitExportFormat::Svg => ExportFormat::Svg,
+            WitExportFormat::Pdf => ExportFormat::Pdf,
+        }
+    }
+
+    /// The format named by `path`'s extension.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("svg") => Ok(ExportFormat::Svg),
+            Some("pdf") => Ok(ExportFormat::Pdf),
+            _ => bail!("{} does not end in .svg or .pdf", path.display()),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Svg => "svg",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// `frame` as a `size.width` x `size.height` document in `format`.
+pub fn export_frame(frame: &FrameOutput, size: LogicalSize, format: ExportFormat) -> Vec<u8> {
+    let (width, height) = (size.width as f64, size.height as f64);
+    match format {
+        ExportFormat::Svg => {
+            let mut page = SvgPage::default();
+            draw_frame(frame, width, height, &mut page);
+            page.finish(width, height).into_bytes()
+        }
+        ExportFormat::Pdf => {
+            let mut page = PdfPage::default();
+            draw_frame(frame, width, height, &mut page);
+            page.finish(width, height)
+        }
+    }
+}
+
+/// Export `frame` to `path`, in the format its extension names.
+pub fn write_frame(frame: &FrameOutput, size: LogicalSize, path: &Path) -> Result<()> {
+    let format = ExportFormat::from_path(path)?;
+    std::fs::write(path, export_frame(frame, size, format))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// A document page: vector paths plus the text and images draw commands carry.
+trait Page: VectorSink {
+    /// One line of text with its baseline starting at `origin`, or a column of it in
+    /// vertical text, turned about `origin` by `flow.rotation`.
+    fn text(
+        &mut self,
+        text: &str,
+        origin: Vec2,
+        size: f32,
+        color: PenikoColor,
+        font: FontFamily,
+        flow: TextFlow,
+    );
+
+    /// `image` with its pixel grid mapped through `transform`.
+    fn image(&mut self, image: &Image, transform: Affine, opacity: f32);
+}
+
+fn draw_frame(frame: &FrameOutput, width: f64, height: f64, page: &mut impl Page) {
+    let background = frame.clear_color.unwrap_or(DEFAULT_CLEAR_COLOR);
+    page.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        background.to_peniko(),
+        &Rect::new(0.0, 0.0, width, height).to_path(0.1),
+    );
+    let mut filters = FilterCache::default();
+    let (mut shader_layers, mut skipped_patterns, mut clips) = (0, 0, 0);
+    for command in &frame.commands {
+        match command {
+            DrawCommand::FillRect {
+                origin,
+                size,
+                color,
+            } => page.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                color.to_peniko(),
+                &rect(*origin, *size).to_path(0.1),
+            ),
+            DrawCommand::DrawText {
+                text,
+                origin,
+                size,
+                color,
+                font,
+                flow,
+            } => page.text(text, *origin, *size, color.to_peniko(), *font, *flow),
+            DrawCommand::DrawPicture {
+                picture,
+                origin,
+                size,
+            } => {
+                if let Some(transform) = fit(*origin, *size, picture.width(), picture.height()) {
+                    picture.replay(page, transform);
+                }
+            }
+            DrawCommand::DrawAnimation {
+                animation,
+                time_ms,
+                origin,
+                size,
+            } => {
+                if let Some(transform) = fit(*origin, *size, animation.width(), animation.height())
+                {
+                    animation.render(page, *time_ms, transform);
+                }
+            }
+            DrawCommand::DrawImage {
+                image,
+                origin,
+                size,
+                style,
+            } => {
+                if let Some(transform) =
+                    fit(*origin, *size, image.width() as f32, image.height() as f32)
+                {
+                    page.image(&filters.get(image, style), transform, style.opacity);
+                }
+            }
+            DrawCommand::FillPattern {
+                origin,
+                size,
+                image,
+                tile_size,
+                repeat,
+                style,
+            } => {
+                let image = filters.get(image, style);
+                if !draw_pattern(page, *origin, *size, &image, *tile_size, *repeat, style) {
+                    skipped_patterns += 1;
+                }
+            }
+            DrawCommand::PushShaderLayer { .. } => shader_layers += 1,
+            DrawCommand::PopShaderLayer => {}
+            DrawCommand::PushClip { origin, size } => {
+                page.push_clip(Affine::IDENTITY, rect(*origin, *size).abs());
+                clips += 1;
+            }
+            DrawCommand::PopClip if clips > 0 => {
+                page.pop_clip();
+                clips -= 1;
+            }
+            DrawCommand::PopClip => {}
+            DrawCommand::DrawParticles { particles } => {
+                draw_particles(page, particles.iter().copied());
+            }
+            DrawCommand::DrawEmitter { particles } => draw_particles(page, particles.sprites()),
+        }
+    }
+    for _ in 0..clips {
+        page.pop_clip();
+    }
+    if shader_layers > 0 {
+        tracing::warn!(
+            shader_layers,
+            "exported shader layer contents without their effects"
+        );
+    }
+    if skipped_patterns > 0 {
+        tracing::warn!(
+            skipped = skipped_patterns,
+            limit = MAX_PATTERN_TILES,
+            "left pattern fills with too many tiles out of the export"
+        );
+    }
+}
+
+fn draw_particles(page: &mut impl Page, particles: impl Iterator<Item = ParticleSprite>) {
+    for particle in particles {
+        let radius = particle.size as f64 * 0.5;
+        if !particle.position.is_finite() || !radius.is_finite() || radius <= 0.0 {
+            continue;
+        }
+        let center = (particle.position.x as f64, particle.position.y as f64);
+        page.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            particle.color.to_peniko(),
+            &Circle::new(center, radius).to_path(0.1),
+        );
+    }
+}
+
+fn rect(origin: Vec2, size: Vec2) -> Rect {
+    Rect::new(
+        origin.x as f64,
+        origin.y as f64,
+        (origin.x + size.x) as f64,
+        (origin.y + size.y) as f64,
+    )
+}
+
+/// Maps a `width` x `height` box onto the rectangle at `origin`, like the renderer does.
+fn fit(origin: Vec2, size: Vec2, width: f32, height: f32) -> Option<Affine> {
+    let scale_x = (size.x / width) as f64;
+    let scale_y = (size.y / height) as f64;
+    (scale_x.is_finite() && scale_y.is_finite() && origin.is_finite()).then(|| {
+        Affine::translate((origin.x as f64, origin.y as f64))
+            .pre_scale_non_uniform(scale_x, scale_y)
+    })
+}
+
+/// Draw a pattern fill tile by tile, clipped to its area. `false` if it needs more than
+/// `MAX_PATTERN_TILES` tiles.
+fn draw_pattern(
+    page: &mut impl Page,
+    origin: Vec2,
+    size: Vec2,
+    image: &Image,
+    tile_size: Vec2,
+    repeat: PatternRepeat,
+    style: &ImageStyle,
+) -> bool {
+    let (tile_width, tile_height) = (tile_size.x as f64, tile_size.y as f64);
+    if !(tile_width > 0.0 && tile_height > 0.0 && tile_width.is_finite() && tile_height.is_finite())
+    {
+        return true;
+    }
+    let height = match repeat {
+        PatternRepeat::RepeatX => size.y.min(tile_size.y),
+        PatternRepeat::Repeat | PatternRepeat::Mirror => size.y,
+    };
+    let area = rect(
+        origin,
+        Vec2 {
+            x: size.x,
+            y: height,
+        },
+    );
+    let columns = (area.width() / tile_width).ceil().max(0.0) as usize;
+    let rows = (area.height() / tile_height).ceil().max(0.0) as usize;
+    if columns.saturating_mul(rows) > MAX_PATTERN_TILES {
+        return false;
+    }
+    let mirror = repeat == PatternRepeat::Mirror;
+    let scale = Affine::scale_non_uniform(
+        tile_width / image.width() as f64,
+        tile_height / image.height() as f64,
+    );
+    page.push_clip(Affine::IDENTITY, area);
+    for row in 0..rows {
+        for column in 0..columns {
+            let mut tile = Affine::translate((
+                area.x0 + column as f64 * tile_width,
+                area.y0 + row as f64 * tile_height,
+            ));
+            if mirror && column % 2 == 1 {
+                tile = tile * Affine::translate((tile_width, 0.0)) * Affine::FLIP_X;
+            }
+            if mirror && row % 2 == 1 {
+                tile = tile * Affine::translate((0.0, tile_height)) * Affine::FLIP_Y;
+            }
+            page.image(image, tile * scale, style.opacity);
+        }
+    }
+    page.pop_clip();
+    true
+}
+
+/// Shortest decimal form, to three places.
+fn number(value: f64) -> String {
+    let text = format!("{value:.3}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" | "" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
+
+fn matrix(transform: Affine) -> String {
+    transform.as_coeffs().map(number).join(" ")
+}
+
+fn png(image: &Image) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, image.width(), image.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(image.to_peniko().data.data()))
+        .expect("encoding an in-memory PNG cannot fail");
+    bytes
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let triple = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &byte)| acc | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(triple >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[derive(Default)]
+struct SvgPage {
+    defs: String,
+    body: String,
+    /// `defs` id of each image already embedded, by image id.
+    images: HashMap<u64, usize>,
+    clips: usize,
+}
+
+impl SvgPage {
+    fn finish(self, width: f64, height: f64) -> String {
+        let (width, height) = (number(width), number(height));
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <svg xmlns=\"http://www.w3.org/2000/svg\" \
+             xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+             width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n\
+             <defs>\n{}</defs>\n{}</svg>\n",
+            self.defs, self.body
+        )
+    }
+
+    /// ` transform="..."` unless `transform` is the identity.
+    fn transform(transform: Affine) -> String {
+        if transform == Affine::IDENTITY {
+            String::new()
+        } else {
+            format!(" transform=\"matrix({})\"", matrix(transform))
+        }
+    }
+
+    /// `name="rgb(...)"`, plus `name-opacity` when translucent.
+    fn paint(name: &str, color: PenikoColor) -> String {
+        let rgba = color.to_rgba8();
+        let mut paint = format!(" {name}=\"rgb({},{},{})\"", rgba.r, rgba.g, rgba.b);
+        if rgba.a < 255 {
+            let _ = write!(
+                paint,
+                " {name}-opacity=\"{}\"",
+                number(color.components[3] as f64)
+            );
+        }
+        paint
+    }
+}
+
+/// Path data with coordinates rounded like every other number in the document.
+fn svg_path(path: &BezPath) -> String {
+    let point = |p: Point| format!("{} {}", number(p.x), number(p.y));
+    let mut data = String::new();
+    for element in path.elements() {
+        let _ = match *element {
+            PathEl::MoveTo(p) => write!(data, "M{}", point(p)),
+            PathEl::LineTo(p) => write!(data, "L{}", point(p)),
+            PathEl::QuadTo(c, p) => write!(data, "Q{} {}", point(c), point(p)),
+            PathEl::CurveTo(c1, c2, p) => {
+                write!(data, "C{} {} {}", point(c1), point(c2), point(p))
+            }
+            PathEl::ClosePath => write!(data, "Z"),
+        };
+    }
+    data
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            // Control characters are not allowed in XML 1.0.
+            ch if ch.is_control() => escaped.push(' '),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+impl VectorSink for SvgPage {
+    fn fill(&mut self, rule: Fill, transform: Affine, color: PenikoColor, path: &BezPath) {
+        let rule = match rule {
+            Fill::NonZero => "",
+            Fill::EvenOdd => " fill-rule=\"evenodd\"",
+        };
+        let _ = writeln!(
+            self.body,
+            "<path d=\"{}\"{}{rule}{}/>",
+            svg_path(path),
+            Self::paint("fill", color),
+            Self::transform(transform),
+        );
+    }
+
+    fn stroke(&mut self, stroke: &Stroke, transform: Affine, color: PenikoColor, path: &BezPath) {
+        let cap = match stroke.start_cap {
+            Cap::Butt => "butt",
+            Cap::Round => "round",
+            Cap::Square => "square",
+        };
+        let join = match stroke.join {
+            Join::Bevel => "bevel",
+            Join::Miter => "miter",
+            Join::Round => "round",
+        };
+        let _ = writeln!(
+            self.body,
+            "<path d=\"{}\" fill=\"none\"{} stroke-width=\"{}\" stroke-linecap=\"{cap}\" \
+             stroke-linejoin=\"{join}\" stroke-miterlimit=\"{}\"{}/>",
+            svg_path(path),
+            Self::paint("stroke", color),
+            number(stroke.width),
+            number(stroke.miter_limit),
+            Self::transform(transform),
+        );
+    }
+
+    fn push_clip(&mut self, transform: Affine, rect: Rect) {
+        let id = self.clips;
+        self.clips += 1;
+        let _ = writeln!(
+            self.body,
+            "<clipPath id=\"clip{id}\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}/>\
+             </clipPath>\n<g clip-path=\"url(#clip{id})\">",
+            number(rect.x0),
+            number(rect.y0),
+            number(rect.width()),
+            number(rect.height()),
+            Self::transform(transform),
+        );
+    }
+
+    fn pop_clip(&mut self) {
+        self.body.push_str("</g>\n");
+    }
+}
+
+impl Page for SvgPage {
+    fn text(
+        &mut self,
+        text: &str,
+        origin: Vec2,
+        size: f32,
+        color: PenikoColor,
+        font: FontFamily,
+        flow: TextFlow,
+    ) {
+        let font = match font {
+            FontFamily::Sans => "font-family=\"Cantarell, sans-serif\"",
+            FontFamily::SansBold => "font-family=\"DejaVu Sans, sans-serif\" font-weight=\"bold\"",
+            FontFamily::Monospace => "font-family=\"DejaVu Sans Mono, monospace\"",
+        };
+        // SVG viewers apply the bidi algorithm themselves; right-to-left text ends at x.
+        let direction = match flow.direction {
+            TextDirection::Ltr => "",
+            TextDirection::Rtl => " direction=\"rtl\"",
+        };
+        let mode = match flow.mode {
+            WritingMode::HorizontalTb => "",
+            WritingMode::VerticalRl => " writing-mode=\"vertical-rl\"",
+        };
+        let rotation = if flow.rotation == 0.0 {
+            String::new()
+        } else {
+            format!(
+                " transform=\"rotate({} {} {})\"",
+                number(flow.rotation.to_degrees() as f64),
+                number(origin.x as f64),
+                number(origin.y as f64)
+            )
+        };
+        let _ = writeln!(
+            self.body,
+            "<text x=\"{}\" y=\"{}\" font-size=\"{}\" {font}{}{direction}{mode}{rotation} \
+             xml:space=\"preserve\">{}</text>",
+            number(origin.x as f64),
+            number(origin.y as f64),
+            number(size as f64),
+            Self::paint("fill", color),
+            escape_xml(text),
+        );
+    }
+
+    fn image(&mut self, image: &Image, transform: Affine, opacity: f32) {
+        let next = self.images.len();
+        let id = *self.images.entry(image.id()).or_insert_with(|| {
+            let _ = writeln!(
+                self.defs,
+                "<image id=\"image{next}\" width=\"{}\" height=\"{}\" \
+                 preserveAspectRatio=\"none\" xlink:href=\"data:image/png;base64,{}\"/>",
+                image.width(),
+                image.height(),
+                base64(&png(image)),
+            );
+            next
+        });
+        let opacity = if opacity < 1.0 {
+            format!(" opacity=\"{}\"", number(opacity.max(0.0) as f64))
+        } else {
+            String::new()
+        };
+        let _ = writeln!(
+            self.body,
+            "<use xlink:href=\"#image{id}\"{}{opacity}/>",
+            Self::transform(transform),
+        );
+    }
+}
+
+/// A single-page PDF. Drawing is in logical pixels with y pointing down; `finish` maps
+/// that onto the page.
+#[derive(Default)]
+struct PdfPage {
+    content: String,
+    /// `/Image<n>` XObjects, in first-use order, and their index by image id.
+    images: Vec<Image>,
+    image_names: HashMap<u64, usize>,
+    /// `/Alpha<n>` graphics states by 8-bit alpha.
+    alphas: Vec<u8>,
+}
+
+/// The standard Type 1 fonts standing in for each bundled family.
+const PDF_FONTS: [(FontFamily, &str); 3] = [
+    (FontFamily::Sans, "Helvetica"),
+    (FontFamily::SansBold, "Helvetica-Bold"),
+    (FontFamily::Monospace, "Courier"),
+];
+
+impl PdfPage {
+    /// Select `color` as the fill (`rg`) or stroke (`RG`) colour, with its alpha.
+    fn color(&mut self, color: PenikoColor, operator: &str) {
+        let [r, g, b, _] = color.components;
+        let _ = write!(
+            self.content,
+            "{} {} {} {operator} ",
+            number(r as f64),
+            number(g as f64),
+            number(b as f64)
+        );
+        self.alpha(color.components[3]);
+    }
+
+    fn alpha(&mut self, alpha: f32) {
+        let alpha = (alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+        if alpha == 255 {
+            return;
+        }
+        let index = match self.alphas.iter().position(|&known| known == alpha) {
+            Some(index) => index,
+            None => {
+                self.alphas.push(alpha);
+                self.alphas.len() - 1
+            }
+        };
+        let _ = write!(self.content, "/Alpha{index} gs ");
+    }
+
+    fn path(&mut self, path: &BezPath) {
+        let mut last = Point::ZERO;
+        let mut start = Point::ZERO;
+        let point = |p: Point| format!("{} {}", number(p.x), number(p.y));
+        for element in path.elements() {
+            let _ = match *element {
+                PathEl::MoveTo(p) => {
+                    (last, start) = (p, p);
+                    write!(self.content, "{} m ", point(p))
+                }
+                PathEl::LineTo(p) => {
+                    last = p;
+                    write!(self.content, "{} l ", point(p))
+                }
+                PathEl::QuadTo(control, p) => {
+                    // PDF has only cubic curves; raise the degree.
+                    let c1 = last + (control - last) * (2.0 / 3.0);
+                    let c2 = p + (control - p) * (2.0 / 3.0);
+                    last = p;
+                    write!(self.content, "{} {} {} c ", point(c1), point(c2), point(p))
+                }
+                PathEl::CurveTo(c1, c2, p) => {
+                    last = p;
+                    write!(self.content, "{} {} {} c ", point(c1), point(c2), point(p))
+                }
+                PathEl::ClosePath => {
+                    last = start;
+                    write!(self.content, "h ")
+                }
+            };
+        }
+    }
+
+    fn finish(self, width: f64, height: f64) -> Vec<u8> {
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+        // 1: catalog, 2: page tree, 3: page, 4: content, then fonts, then images.
+        let first_image = 5 + PDF_FONTS.len();
+
+        let mut fonts = String::new();
+        for i in 0..PDF_FONTS.len() {
+            let _ = write!(fonts, "/Font{i} {} 0 R ", 5 + i);
+        }
+        let mut states = String::new();
+        for (i, alpha) in self.alphas.iter().enumerate() {
+            let alpha = number(*alpha as f64 / 255.0);
+            let _ = write!(states, "/Alpha{i} << /ca {alpha} /CA {alpha} >> ");
+        }
+        let mut image_objects = Vec::new();
+        let mut xobjects = String::new();
+        for (i, image) in self.images.iter().enumerate() {
+            let id = first_image + image_objects.len();
+            let _ = write!(xobjects, "/Image{i} {id} 0 R ");
+            image_objects.extend(pdf_image(image, id));
+        }
+
+        let (page_width, page_height) =
+            (width * PDF_POINTS_PER_PIXEL, height * PDF_POINTS_PER_PIXEL);
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        objects.push(b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec());
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents 4 0 R \
+                 /Resources << /Font << {fonts}>> /ExtGState << {states}>> \
+                 /XObject << {xobjects}>> >> >>",
+                number(page_width),
+                number(page_height),
+            )
+            .into_bytes(),
+        );
+        let content = format!(
+            "{} 0 0 {} 0 {} cm\n{}",
+            number(PDF_POINTS_PER_PIXEL),
+            number(-PDF_POINTS_PER_PIXEL),
+            number(page_height),
+            self.content
+        );
+        objects.push(stream("", content.as_bytes()));
+        for (_, name) in PDF_FONTS {
+            objects.push(
+                format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{name} \
+                     /Encoding /WinAnsiEncoding >>"
+                )
+                .into_bytes(),
+            );
+        }
+        objects.extend(image_objects);
+
+        let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend(format!("{} 0 obj\n", i + 1).bytes());
+            pdf.extend(object);
+            pdf.extend(b"\nendobj\n");
+        }
+        let xref = pdf.len();
+        pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).bytes());
+        for offset in offsets {
+            pdf.extend(format!("{offset:010} 00000 n \n").bytes());
+        }
+        pdf.extend(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+                objects.len() + 1
+            )
+            .bytes(),
+        );
+        pdf
+    }
+}
+
+fn stream(dictionary: &str, data: &[u8]) -> Vec<u8> {
+    let mut object = format!("<< {dictionary}/Length {} >>\nstream\n", data.len()).into_bytes();
+    object.extend(data);
+    object.extend(b"\nendstream");
+    object
+}
+
+/// The image XObject numbered `id`, followed by its soft mask when it is not opaque.
+fn pdf_image(image: &Image, id: usize) -> Vec<Vec<u8>> {
+    let pixels = image.to_peniko().data;
+    let rgba = pixels.data();
+    let rgb: Vec<u8> = rgba
+        .chunks_exact(4)
+        .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+        .collect();
+    let alpha: Vec<u8> = rgba.chunks_exact(4).map(|pixel| pixel[3]).collect();
+    let opaque = alpha.iter().all(|&a| a == 255);
+    let dictionary = |color_space: &str, extra: &str| {
+        format!(
+            "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /{color_space} \
+             /BitsPerComponent 8 /Filter /LZWDecode {extra}",
+            image.width(),
+            image.height()
+        )
+    };
+    let mask = if opaque {
+        String::new()
+    } else {
+        format!("/SMask {} 0 R ", id + 1)
+    };
+    let mut objects = vec![stream(&dictionary("DeviceRGB", &mask), &lzw(&rgb))];
+    if !opaque {
+        objects.push(stream(&dictionary("DeviceGray", ""), &lzw(&alpha)));
+    }
+    objects
+}
+
+/// LZW as `/LZWDecode` reads it by default: MSB-first codes that widen one code early.
+fn lzw(data: &[u8]) -> Vec<u8> {
+    weezl::encode::Encoder::with_tiff_size_switch(weezl::BitOrder::Msb, 8)
+        .encode(data)
+        .expect("LZW encoding into memory cannot fail")
+}
+
+/// `text` as a PDF string in WinAnsi encoding; characters outside Latin-1 become `?`.
+fn pdf_string(text: &str) -> String {
+    let mut string = String::from("(");
+    for ch in text.chars() {
+        let byte = match u32::from(ch) {
+            code @ (0x20..=0x7e | 0xa0..=0xff) => code as u8,
+            _ => b'?',
+        };
+        match byte {
+            b'(' | b')' | b'\\' => {
+                string.push('\\');
+                string.push(byte as char);
+            }
+            0x20..=0x7e => string.push(byte as char),
+            _ => {
+                let _ = write!(string, "\\{byte:03o}");
+            }
+        }
+    }
+    string.push(')');
+    string
+}
+
+impl VectorSink for PdfPage {
+    fn fill(&mut self, rule: Fill, transform: Affine, color: PenikoColor, path: &BezPath) {
+        self.content.push_str("q ");
+        self.color(color, "rg");
+        let _ = write!(self.content, "{} cm ", matrix(transform));
+        self.path(path);
+        self.content.push_str(match rule {
+            Fill::NonZero => "f Q\n",
+            Fill::EvenOdd => "f* Q\n",
+        });
+    }
+
+    fn stroke(&mut self, stroke: &Stroke, transform: Affine, color: PenikoColor, path: &BezPath) {
+        self.content.push_str("q ");
+        self.color(color, "RG");
+        let cap = match stroke.start_cap {
+            Cap::Butt => 0,
+            Cap::Round => 1,
+            Cap::Square => 2,
+        };
+        let join = match stroke.join {
+            Join::Miter => 0,
+            Join::Round => 1,
+            Join::Bevel => 2,
+        };
+        let _ = write!(
+            self.content,
+            "{} cm {} w {cap} J {join} j {} M ",
+            matrix(transform),
+            number(stroke.width),
+            number(stroke.miter_limit)
+        );
+        self.path(path);
+        self.content.push_str("S Q\n");
+    }
+
+    fn push_clip(&mut self, transform: Affine, rect: Rect) {
+        // The clip outlives this call, so map the corners here instead of changing the
+        // transform for everything drawn inside it.
+        self.content.push_str("q ");
+        self.path(&(transform * rect.to_path(0.1)));
+        self.content.push_str("W n\n");
+    }
+
+    fn pop_clip(&mut self) {
+        self.content.push_str("Q\n");
+    }
+}
+
+impl Page for PdfPage {
+    /// Right-to-left text starts at `origin` too: the standard fonts' widths are not known
+    /// here, and their encoding has no right-to-left scripts to reorder. Vertical text is
+    /// stacked one character at a time, centred by the placeholder advance for that reason.
+    fn text(
+        &mut self,
+        text: &str,
+        origin: Vec2,
+        size: f32,
+        color: PenikoColor,
+        font: FontFamily,
+        flow: TextFlow,
+    ) {
+        let font = PDF_FONTS
+            .iter()
+            .position(|(family, _)| *family == font)
+            .unwrap_or(0);
+        let pieces = match flow.mode {
+            WritingMode::HorizontalTb => vec![(text.to_string(), Vec2::ZERO)],
+            WritingMode::VerticalRl => {
+                graphics::place_glyphs(text, size, flow, graphics::advance(None, size))
+                    .into_iter()
+                    .map(|(ch, baseline)| (ch.to_string(), baseline))
+                    .collect()
+            }
+        };
+        let placement = Affine::translate((origin.x as f64, origin.y as f64))
+            * Affine::rotate(flow.rotation as f64);
+        self.content.push_str("q ");
+        self.color(color, "rg");
+        for (piece, baseline) in pieces {
+            // Flip text back upright inside the y-down page.
+            let text_matrix = matrix(
+                placement
+                    * Affine::translate((baseline.x as f64, baseline.y as f64))
+                    * Affine::FLIP_Y,
+            );
+            let _ = write!(
+                self.content,
+                "BT /Font{font} {} Tf {text_matrix} Tm {} Tj ET ",
+                number(size as f64),
+                pdf_string(&piece)
+            );
+        }
+        self.content.push_str("Q\n");
+    }
+
+    fn image(&mut self, image: &Image, transform: Affine, opacity: f32) {
+        let next = self.images.len();
+        let index = *self.image_names.entry(image.id()).or_insert(next);
+        if index == next {
+            self.images.push(image.clone());
+        }
+        // Images occupy the unit square with their first row at the top (y = 1).
+        let (width, height) = (image.width() as f64, image.height() as f64);
+        let placement = transform * Affine::new([width, 0.0, 0.0, -height, 0.0, height]);
+        self.content.push_str("q ");
+        self.alpha(opacity);
+        let _ = writeln!(self.content, "{} cm /Image{index} Do Q", matrix(placement));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{base64, export_frame, lzw, pdf_string, ExportFormat};
+    use crate::geometry::Vec2;
+    use crate::host::{Color, DrawCommand, FontFamily, FrameOutput, PatternRepeat, TextFlow};
+    use crate::images::{Image, ImageStyle};
+    use crate::model::LogicalSize;
+    use crate::particles::ParticleSprite;
+    use crate::svg::Picture;
+
+    const RED: Color = Color {
+        r: 1.0,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    };
+
+    fn frame() -> FrameOutput {
+        let image = Image::new(2, 1, vec![255, 0, 0, 255, 0, 0, 255, 128]).unwrap();
+        let picture = Picture::parse(
+            br#"<svg width="10" height="10"><circle cx="5" cy="5" r="4" fill="lime"/></svg>"#,
+        )
+        .unwrap();
+        FrameOutput {
+            clear_color: Some(Color::default()),
+            commands: vec![
+                DrawCommand::FillRect {
+                    origin: Vec2 { x: 10.0, y: 20.0 },
+                    size: Vec2 { x: 30.0, y: 40.0 },
+                    color: RED,
+                },
+                DrawCommand::DrawText {
+                    text: "Total: <5> & (café)".to_string(),
+                    origin: Vec2 { x: 5.0, y: 90.0 },
+                    size: 16.0,
+                    color: RED,
+                    font: FontFamily::SansBold,
+                    flow: TextFlow {
+                        rotation: std::f32::consts::FRAC_PI_2,
+                        ..TextFlow::default()
+                    },
+                },
+                DrawCommand::DrawPicture {
+                    picture,
+                    origin: Vec2 { x: 50.0, y: 0.0 },
+                    size: Vec2 { x: 20.0, y: 20.0 },
+                },
+                DrawCommand::DrawImage {
+                    image: image.clone(),
+                    origin: Vec2 { x: 0.0, y: 0.0 },
+                    size: Vec2 { x: 8.0, y: 4.0 },
+                    style: ImageStyle::default(),
+                },
+                DrawCommand::FillPattern {
+                    origin: Vec2 { x: 0.0, y: 50.0 },
+                    size: Vec2 { x: 6.0, y: 2.0 },
+                    image,
+                    tile_size: Vec2 { x: 2.0, y: 1.0 },
+                    repeat: PatternRepeat::Mirror,
+                    style: ImageStyle::default(),
+                },
+                DrawCommand::DrawParticles {
+                    particles: Arc::from([ParticleSprite {
+                        position: Vec2 { x: 3.0, y: 3.0 },
+                        size: 2.0,
+                        color: RED,
+                    }]),
+                },
+            ],
+        }
+    }
+
+    fn size() -> LogicalSize {
+        LogicalSize {
+            width: 100.0,
+            height: 80.0,
+            scale_factor: 2.0,
+        }
+    }
+
+    #[test]
+    fn svg_export_keeps_shapes_text_and_shared_images() {
+        let svg = String::from_utf8(export_frame(&frame(), size(), ExportFormat::Svg)).unwrap();
+        assert!(svg.contains(r#"width="100" height="80" viewBox="0 0 100 80""#));
+        assert!(svg.contains(r#"<path d="M10 20L40 20L40 60L10 60Z" fill="rgb(255,0,0)"/>"#));
+        assert!(svg.contains("font-weight=\"bold\""));
+        assert!(svg.contains(">Total: &lt;5&gt; &amp; (café)</text>"));
+        assert!(svg.contains("transform=\"rotate(90 5 90)\""));
+        assert!(
+            svg.contains("fill=\"rgb(0,255,0)\""),
+            "picture shapes are paths"
+        );
+        assert_eq!(svg.matches("<image ").count(), 1, "one copy of the image");
+        assert_eq!(
+            svg.matches("<use ").count(),
+            1 + 6,
+            "the draw and six tiles"
+        );
+        assert!(
+            svg.contains("transform=\"matrix(-1 0 0 1 4 50)\""),
+            "mirrored tile"
+        );
+        assert_eq!(svg.matches("<g ").count(), svg.matches("</g>").count());
+    }
+
+    #[test]
+    fn pdf_export_is_a_well_formed_single_page() {
+        let pdf = export_frame(&frame(), size(), ExportFormat::Pdf);
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("/MediaBox [0 0 75 60]"));
+        assert!(text.contains("/BaseFont /Helvetica-Bold"));
+        assert!(text.contains("0 1 1 0 5 90 Tm (Total: <5> & \\(caf\\351\\)) Tj"));
+        assert!(
+            text.contains("/SMask"),
+            "the translucent image gets a soft mask"
+        );
+        assert_eq!(text.matches("/Subtype /Image").count(), 2);
+
+        // The content stream comes first and saves and restores state in pairs.
+        let content = text.split("stream\n").nth(1).unwrap();
+        let content = content.split("\nendstream").next().unwrap();
+        let tokens: Vec<&str> = content.split_whitespace().collect();
+        let count = |op| tokens.iter().filter(|token| **token == op).count();
+        assert_eq!(count("q"), count("Q"));
+        assert_eq!(count("Do"), 7, "the image and six pattern tiles");
+
+        // The cross-reference table points at each object.
+        let find = |needle: &[u8]| {
+            pdf.windows(needle.len())
+                .rposition(|window| window == needle)
+                .unwrap()
+        };
+        let tail = String::from_utf8(pdf[find(b"startxref\n")..].to_vec()).unwrap();
+        let start: usize = tail.lines().nth(1).unwrap().parse().unwrap();
+        assert_eq!(start, find(b"xref\n0 "));
+        let table = String::from_utf8(pdf[start..].to_vec()).unwrap();
+        let offsets: Vec<usize> = table
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .map(|line| line[..10].parse().unwrap())
+            .collect();
+        assert_eq!(
+            offsets.len(),
+            9,
+            "catalog, pages, page, content, 3 fonts, image, mask"
+        );
+        for (i, offset) in offsets.iter().enumerate() {
+            assert!(pdf[*offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn encodes_strings_base64_and_lzw() {
+        assert_eq!(pdf_string("a\\b→"), "(a\\\\b?)");
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+        let data = b"TOBEORNOTTOBEORTOBEORNOT".repeat(20);
+        let decoded = weezl::decode::Decoder::with_tiff_size_switch(weezl::BitOrder::Msb, 8)
+            .decode(&lzw(&data))
+            .unwrap();
+        assert_eq!(decoded, data);
+        assert!(ExportFormat::from_path("shot.PDF".as_ref()).is_ok());
+        assert!(ExportFormat::from_path("shot.png".as_ref()).is_err());
+    }
+}
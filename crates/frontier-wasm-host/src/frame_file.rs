@@ -0,0 +1,587 @@
+//! `.fframe` files: one frame's draw commands together with every image, picture, animation
+//! and shader they use, in a compact binary encoding. `--dump-frames` writes one per frame
+//! and `render-file` rasterizes them later, so a rendering bug can be reproduced without
+//! the component that produced it.
+//!
+//! A file is [`MAGIC`], a little-endian `u16` [`FORMAT_VERSION`], then a postcard-encoded
+//! body. Assets are stored once per file, as the pixels or source the guest supplied, and
+//! commands refer to them by index.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::bidi::TextDirection;
+use crate::effects::{Shader, PARAM_COUNT};
+use crate::geometry::Vec2;
+use crate::host::{
+    Color, DrawCommand, FontFamily, FrameOutput, PatternRepeat, TextFlow, WritingMode,
+};
+use crate::images::{Image, ImageStyle};
+use crate::lottie::Animation;
+use crate::model::LogicalSize;
+use crate::particles::{ParticleBatch, ParticleSprite};
+use crate::svg::Picture;
+
+/// File name extension, without the dot.
+pub const EXTENSION: &str = "fframe";
+/// Leading bytes of every file.
+const MAGIC: &[u8; 8] = b"FFRAME\r\n";
+/// Bumped whenever the body encoding changes; files from other versions are rejected.
+pub const FORMAT_VERSION: u16 = 5;
+
+/// A frame read back from a file, with the window size it was drawn for.
+#[derive(Debug)]
+pub struct SavedFrame {
+    pub size: LogicalSize,
+    pub frame: FrameOutput,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Body {
+    size: LogicalSize,
+    clear_color: Option<Color>,
+    images: Vec<ImagePixels>,
+    pictures: Vec<Vec<u8>>,
+    animations: Vec<Vec<u8>>,
+    shaders: Vec<String>,
+    commands: Vec<Command>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ImagePixels {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+/// [`DrawCommand`] with assets replaced by their index in [`Body`].
+#[derive(Serialize, Deserialize)]
+enum Command {
+    FillRect {
+        origin: Vec2,
+        size: Vec2,
+        color: Color,
+    },
+    DrawText {
+        text: String,
+        origin: Vec2,
+        size: f32,
+        color: Color,
+        font: FontFamily,
+        direction: TextDirection,
+        mode: WritingMode,
+        rotation: f32,
+    },
+    DrawPicture {
+        picture: u32,
+        origin: Vec2,
+        size: Vec2,
+    },
+    DrawAnimation {
+        animation: u32,
+        time_ms: f64,
+        origin: Vec2,
+        size: Vec2,
+    },
+    DrawImage {
+        image: u32,
+        origin: Vec2,
+        size: Vec2,
+        style: ImageStyle,
+    },
+    FillPattern {
+        origin: Vec2,
+        size: Vec2,
+        image: u32,
+        tile_size: Vec2,
+        repeat: PatternRepeat,
+        style: ImageStyle,
+    },
+    PushShaderLayer {
+        shader: u32,
+        params: [f32; PARAM_COUNT],
+    },
+    PopShaderLayer,
+    DrawParticles {
+        particles: Vec<ParticleSprite>,
+    },
+    PushClip {
+        origin: Vec2,
+        size: Vec2,
+    },
+    PopClip,
+    DrawEmitter {
+        particles: ParticleBatch,
+    },
+}
+
+/// Assets already stored in the body being written, in index order.
+#[derive(Default)]
+struct Assets<'a> {
+    images: Vec<&'a Image>,
+    pictures: Vec<&'a Picture>,
+    animations: Vec<&'a Animation>,
+    shaders: Vec<&'a Shader>,
+}
+
+/// Index of the first entry `same` matches, appending `item` when there is none.
+fn intern<'a, T>(list: &mut Vec<&'a T>, item: &'a T, same: impl Fn(&T, &T) -> bool) -> u32 {
+    let index = match list.iter().position(|known| same(known, item)) {
+        Some(index) => index,
+        None => {
+            list.push(item);
+            list.len() - 1
+        }
+    };
+    index as u32
+}
+
+/// `frame` in the `.fframe` format.
+pub fn encode(frame: &FrameOutput, size: LogicalSize) -> Vec<u8> {
+    let mut assets = Assets::default();
+    let commands = frame
+        .commands
+        .iter()
+        .map(|command| match command {
+            DrawCommand::FillRect {
+                origin,
+                size,
+                color,
+            } => Command::FillRect {
+                origin: *origin,
+                size: *size,
+                color: *color,
+            },
+            DrawCommand::DrawText {
+                text,
+                origin,
+                size,
+                color,
+                font,
+                flow,
+            } => Command::DrawText {
+                text: text.clone(),
+                origin: *origin,
+                size: *size,
+                color: *color,
+                font: *font,
+                direction: flow.direction,
+                mode: flow.mode,
+                rotation: flow.rotation,
+            },
+            DrawCommand::DrawPicture {
+                picture,
+                origin,
+                size,
+            } => Command::DrawPicture {
+                picture: intern(&mut assets.pictures, picture, Picture::ptr_eq),
+                origin: *origin,
+                size: *size,
+            },
+            DrawCommand::DrawAnimation {
+                animation,
+                time_ms,
+                origin,
+                size,
+            } => Command::DrawAnimation {
+                animation: intern(&mut assets.animations, animation, Animation::ptr_eq),
+                time_ms: *time_ms,
+                origin: *origin,
+                size: *size,
+            },
+            DrawCommand::DrawImage {
+                image,
+                origin,
+                size,
+                style,
+            } => Command::DrawImage {
+                image: intern(&mut assets.images, image, |a, b| a.id() == b.id()),
+                origin: *origin,
+                size: *size,
+                style: *style,
+            },
+            DrawCommand::FillPattern {
+                origin,
+                size,
+                image,
+                tile_size,
+                repeat,
+                style,
+            } => Command::FillPattern {
+                origin: *origin,
+                size: *size,
+                image: intern(&mut assets.images, image, |a, b| a.id() == b.id()),
+                tile_size: *tile_size,
+                repeat: *repeat,
+                style: *style,
+            },
+            DrawCommand::PushShaderLayer { shader, params } => Command::PushShaderLayer {
+                shader: intern(&mut assets.shaders, shader, |a, b| a.id() == b.id()),
+                params: *params,
+            },
+            DrawCommand::PopShaderLayer => Command::PopShaderLayer,
+            DrawCommand::DrawParticles { particles } => Command::DrawParticles {
+                particles: particles.to_vec(),
+            },
+            DrawCommand::PushClip { origin, size } => Command::PushClip {
+                origin: *origin,
+                size: *size,
+            },
+            DrawCommand::PopClip => Command::PopClip,
+            DrawCommand::DrawEmitter { particles } => Command::DrawEmitter {
+                particles: ParticleBatch::clone(particles),
+            },
+        })
+        .collect();
+    let body = Body {
+        size,
+        clear_color: frame.clear_color,
+        images: assets
+            .images
+            .iter()
+            .map(|image| ImagePixels {
+                width: image.width(),
+                height: image.height(),
+                rgba: image.to_peniko().data.data().to_vec(),
+            })
+            .collect(),
+        pictures: assets
+            .pictures
+            .iter()
+            .map(|picture| picture.source().to_vec())
+            .collect(),
+        animations: assets
+            .animations
+            .iter()
+            .map(|animation| animation.source().to_vec())
+            .collect(),
+        shaders: assets
+            .shaders
+            .iter()
+            .map(|shader| shader.source().to_string())
+            .collect(),
+        commands,
+    };
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(FORMAT_VERSION.to_le_bytes());
+    postcard::to_extend(&body, bytes).expect("frames always serialize")
+}
+
+/// Read a frame written by [`encode`], parsing its assets again.
+pub fn decode(bytes: &[u8]) -> Result<SavedFrame> {
+    let Some(rest) = bytes.strip_prefix(MAGIC) else {
+        bail!("not a .{EXTENSION} file");
+    };
+    let (version, body) = match rest {
+        [low, high, body @ ..] => (u16::from_le_bytes([*low, *high]), body),
+        _ => bail!("truncated header"),
+    };
+    if version != FORMAT_VERSION {
+        bail!(
+            "format version {version} is not supported; this host reads version {FORMAT_VERSION}"
+        );
+    }
+    let body: Body = postcard::from_bytes(body).context("corrupt frame data")?;
+
+    let images = body
+        .images
+        .into_iter()
+        .enumerate()
+        .map(|(index, pixels)| {
+            Image::new(pixels.width, pixels.height, pixels.rgba)
+                .ok_or_else(|| anyhow!("image {index} has invalid dimensions"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let pictures = body
+        .pictures
+        .iter()
+        .enumerate()
+        .map(|(index, source)| Picture::parse(source).with_context(|| format!("picture {index}")))
+        .collect::<Result<Vec<_>>>()?;
+    let animations = body
+        .animations
+        .iter()
+        .enumerate()
+        .map(|(index, source)| {
+            Animation::parse(source).with_context(|| format!("animation {index}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let shaders = body
+        .shaders
+        .iter()
+        .enumerate()
+        .map(|(index, source)| Shader::parse(source).with_context(|| format!("shader {index}")))
+        .collect::<Result<Vec<_>>>()?;
+
+    fn get<T: Clone>(list: &[T], index: u32, kind: &str) -> Result<T> {
+        list.get(index as usize)
+            .cloned()
+            .ok_or_else(|| anyhow!("command refers to missing {kind} {index}"))
+    }
+    let commands = body
+        .commands
+        .into_iter()
+        .map(|command| {
+            Ok(match command {
+                Command::FillRect {
+                    origin,
+                    size,
+                    color,
+                } => DrawCommand::FillRect {
+                    origin,
+                    size,
+                    color,
+                },
+                Command::DrawText {
+                    text,
+                    origin,
+                    size,
+                    color,
+                    font,
+                    direction,
+                    mode,
+                    rotation,
+                } => DrawCommand::DrawText {
+                    text,
+                    origin,
+                    size,
+                    color,
+                    font,
+                    flow: TextFlow {
+                        direction,
+                        mode,
+                        rotation,
+                    },
+                },
+                Command::DrawPicture {
+                    picture,
+                    origin,
+                    size,
+                } => DrawCommand::DrawPicture {
+                    picture: get(&pictures, picture, "picture")?,
+                    origin,
+                    size,
+                },
+                Command::DrawAnimation {
+                    animation,
+                    time_ms,
+                    origin,
+                    size,
+                } => DrawCommand::DrawAnimation {
+                    animation: get(&animations, animation, "animation")?,
+                    time_ms,
+                    origin,
+                    size,
+                },
+                Command::DrawImage {
+                    image,
+                    origin,
+                    size,
+                    style,
+                } => DrawCommand::DrawImage {
+                    image: get(&images, image, "image")?,
+                    origin,
+                    size,
+                    style,
+                },
+                Command::FillPattern {
+                    origin,
+                    size,
+                    image,
+                    tile_size,
+                    repeat,
+                    style,
+                } => DrawCommand::FillPattern {
+                    origin,
+                    size,
+                    image: get(&images, image, "image")?,
+                    tile_size,
+                    repeat,
+                    style,
+                },
+                Command::PushShaderLayer { shader, params } => DrawCommand::PushShaderLayer {
+                    shader: get(&shaders, shader, "shader")?,
+                    params,
+                },
+                Command::PopShaderLayer => DrawCommand::PopShaderLayer,
+                Command::DrawParticles { particles } => DrawCommand::DrawParticles {
+                    particles: Arc::from(particles),
+                },
+                Command::PushClip { origin, size } => DrawCommand::PushClip { origin, size },
+                Command::PopClip => DrawCommand::PopClip,
+                Command::DrawEmitter { particles } => DrawCommand::DrawEmitter {
+                    particles: Arc::new(particles),
+                },
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(SavedFrame {
+        size: body.size,
+        frame: FrameOutput {
+            clear_color: body.clear_color,
+            commands,
+        },
+    })
+}
+
+pub fn write(path: &Path, frame: &FrameOutput, size: LogicalSize) -> Result<()> {
+    std::fs::write(path, encode(frame, size))
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+pub fn read(path: &Path) -> Result<SavedFrame> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    decode(&bytes).with_context(|| format!("failed to load {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{decode, encode, FORMAT_VERSION, MAGIC};
+    use crate::bidi::TextDirection;
+    use crate::effects::Shader;
+    use crate::geometry::Vec2;
+    use crate::host::{
+        Color, DrawCommand, FontFamily, FrameOutput, PatternRepeat, TextFlow, WritingMode,
+    };
+    use crate::images::{Image, ImageStyle};
+    use crate::model::LogicalSize;
+    use crate::particles::{ParticleBatch, ParticleSprite, Spawn};
+    use crate::svg::Picture;
+
+    #[test]
+    fn round_trips_commands_and_stores_shared_assets_once() {
+        let image = Image::new(1, 1, vec![1, 2, 3, 4]).unwrap();
+        let picture = Picture::parse(br#"<svg width="4" height="2"/>"#).unwrap();
+        let at = Vec2 { x: 1.0, y: 2.0 };
+        let red = Color {
+            r: 1.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.5,
+        };
+        let frame = FrameOutput {
+            clear_color: Some(red),
+            commands: vec![
+                DrawCommand::DrawText {
+                    text: "héllo".to_string(),
+                    origin: at,
+                    size: 12.0,
+                    color: red,
+                    font: FontFamily::Monospace,
+                    flow: TextFlow {
+                        direction: TextDirection::Rtl,
+                        mode: WritingMode::VerticalRl,
+                        rotation: 0.5,
+                    },
+                },
+                DrawCommand::DrawImage {
+                    image: image.clone(),
+                    origin: at,
+                    size: at,
+                    style: ImageStyle::default(),
+                },
+                DrawCommand::FillPattern {
+                    origin: at,
+                    size: at,
+                    image,
+                    tile_size: at,
+                    repeat: PatternRepeat::RepeatX,
+                    style: ImageStyle::default(),
+                },
+                DrawCommand::DrawPicture {
+                    picture: picture.clone(),
+                    origin: at,
+                    size: at,
+                },
+                DrawCommand::DrawPicture {
+                    picture,
+                    origin: at,
+                    size: at,
+                },
+                DrawCommand::PushShaderLayer {
+                    shader: Shader::parse(
+                        "fn shade(uv: vec2<f32>) -> vec4<f32> { return sample_layer(uv); }",
+                    )
+                    .unwrap(),
+                    params: [3.0; 8],
+                },
+                DrawCommand::DrawParticles {
+                    particles: Arc::from([ParticleSprite {
+                        position: at,
+                        size: 2.0,
+                        color: red,
+                    }]),
+                },
+                DrawCommand::PopShaderLayer,
+                DrawCommand::PushClip {
+                    origin: at,
+                    size: at,
+                },
+                DrawCommand::PopClip,
+                DrawCommand::DrawEmitter {
+                    particles: Arc::new(ParticleBatch {
+                        seed: 9,
+                        configs: Vec::new(),
+                        spawns: vec![Spawn {
+                            first: 0,
+                            count: 3,
+                            age_ms: 1.0,
+                            config: 0,
+                        }],
+                    }),
+                },
+            ],
+        };
+        let size = LogicalSize {
+            width: 320.0,
+            height: 240.0,
+            scale_factor: 2.0,
+        };
+        let bytes = encode(&frame, size);
+        let saved = decode(&bytes).unwrap();
+        assert_eq!(saved.size, size);
+        assert_eq!(saved.frame.clear_color, frame.clear_color);
+        let listing = |frame: &FrameOutput| -> Vec<String> {
+            frame.commands.iter().map(ToString::to_string).collect()
+        };
+        assert_eq!(listing(&saved.frame), listing(&frame));
+        let (
+            Some(DrawCommand::DrawEmitter { particles: read }),
+            Some(DrawCommand::DrawEmitter { particles }),
+        ) = (saved.frame.commands.last(), frame.commands.last())
+        else {
+            panic!("unexpected commands");
+        };
+        assert_eq!(read, particles);
+
+        let [DrawCommand::DrawImage { image: a, .. }, DrawCommand::FillPattern { image: b, .. }] =
+            &saved.frame.commands[1..3]
+        else {
+            panic!("unexpected commands");
+        };
+        assert_eq!(a.id(), b.id(), "one image, shared by both commands");
+        assert_eq!(a.to_peniko().data.data(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn rejects_foreign_truncated_and_future_files() {
+        let frame = FrameOutput::default();
+        let bytes = encode(&frame, LogicalSize::default());
+        assert!(decode(&bytes).is_ok());
+        assert!(decode(b"PNG").is_err());
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut future = MAGIC.to_vec();
+        future.extend((FORMAT_VERSION + 1).to_le_bytes());
+        future.extend(&bytes[MAGIC.len() + 2..]);
+        let err = decode(&future).unwrap_err();
+        assert!(err.to_string().contains("not supported"), "{err}");
+    }
+}
@@ -0,0 +1,169 @@
+//! Gamepad navigation of the UI the host draws itself, so the error overlay and the camera
+//! prompt stay usable when a controller is the only input. The D-pad moves focus, A
+//! activates the focused button and B backs out.
+//!
+//! Gamepads are read through evdev when the host is built with `--features gamepad` on
+//! Linux; other builds never see any. They are only opened while host UI is on screen,
+//! so presses meant for the guest are never replayed into it later.
+
+use std::time::{Duration, Instant};
+
+#[cfg(all(feature = "gamepad", target_os = "linux"))]
+mod evdev;
+#[cfg(all(feature = "gamepad", target_os = "linux"))]
+use evdev as backend;
+
+/// Whether this build can read gamepads at all.
+pub const SUPPORTED: bool = cfg!(all(feature = "gamepad", target_os = "linux"));
+
+/// How often open gamepads are read while host UI is up.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long to wait before looking for gamepads again after finding none.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+// Codes from `linux/input-event-codes.h`.
+const BTN_SOUTH: u16 = 0x130;
+const BTN_EAST: u16 = 0x131;
+const BTN_DPAD_UP: u16 = 0x220;
+const BTN_DPAD_DOWN: u16 = 0x221;
+const BTN_DPAD_LEFT: u16 = 0x222;
+const BTN_DPAD_RIGHT: u16 = 0x223;
+const ABS_HAT0X: u16 = 0x10;
+const ABS_HAT0Y: u16 = 0x11;
+
+/// A navigation step, whichever gamepad it came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NavInput {
+    Up,
+    Down,
+    Previous,
+    Next,
+    /// A: trigger the focused button.
+    Activate,
+    /// B: dismiss, where the UI has a way out.
+    Back,
+}
+
+/// The navigation a button press means; releases and autorepeat mean nothing.
+pub fn button_input(code: u16, value: i32) -> Option<NavInput> {
+    if value != 1 {
+        return None;
+    }
+    match code {
+        BTN_SOUTH => Some(NavInput::Activate),
+        BTN_EAST => Some(NavInput::Back),
+        BTN_DPAD_UP => Some(NavInput::Up),
+        BTN_DPAD_DOWN => Some(NavInput::Down),
+        BTN_DPAD_LEFT => Some(NavInput::Previous),
+        BTN_DPAD_RIGHT => Some(NavInput::Next),
+        _ => None,
+    }
+}
+
+/// The navigation a D-pad reported as a hat axis means; returning to centre means nothing.
+pub fn axis_input(code: u16, value: i32) -> Option<NavInput> {
+    match (code, value.signum()) {
+        (ABS_HAT0X, -1) => Some(NavInput::Previous),
+        (ABS_HAT0X, 1) => Some(NavInput::Next),
+        (ABS_HAT0Y, -1) => Some(NavInput::Up),
+        (ABS_HAT0Y, 1) => Some(NavInput::Down),
+        _ => None,
+    }
+}
+
+/// The gamepads open while host UI is up.
+#[derive(Default)]
+pub struct Gamepads {
+    devices: Vec<backend::Pad>,
+    scanned_at: Option<Instant>,
+}
+
+impl std::fmt::Debug for Gamepads {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Gamepads")
+            .field("devices", &self.devices.len())
+            .finish()
+    }
+}
+
+impl Gamepads {
+    /// Navigation pressed on any gamepad since the last poll, opening gamepads first when
+    /// none are open.
+    pub fn poll(&mut self, now: Instant) -> Vec<NavInput> {
+        if !SUPPORTED {
+            return Vec::new();
+        }
+        let rescan_due = self
+            .scanned_at
+            .is_none_or(|at| now.duration_since(at) >= RESCAN_INTERVAL);
+        if self.devices.is_empty() && rescan_due {
+            self.devices = backend::scan();
+            self.scanned_at = Some(now);
+            if !self.devices.is_empty() {
+                tracing::info!(count = self.devices.len(), "found gamepads");
+            }
+        }
+        let mut inputs = Vec::new();
+        // A device that fails was most likely unplugged; forget it.
+        self.devices.retain(|device| match device.read() {
+            Ok(read) => {
+                inputs.extend(read);
+                true
+            }
+            Err(err) => {
+                tracing::debug!(error = %err, "dropping gamepad");
+                false
+            }
+        });
+        inputs
+    }
+
+    /// Close every gamepad once host UI is gone; the next [`Gamepads::poll`] reopens them
+    /// with nothing queued.
+    pub fn release(&mut self) {
+        self.devices.clear();
+        self.scanned_at = None;
+    }
+}
+
+/// Stand-in for builds without a gamepad backend: no devices to find.
+#[cfg(not(all(feature = "gamepad", target_os = "linux")))]
+mod backend {
+    use std::io;
+
+    use super::NavInput;
+
+    pub fn scan() -> Vec<Pad> {
+        Vec::new()
+    }
+
+    pub struct Pad;
+
+    impl Pad {
+        pub fn read(&self) -> io::Result<Vec<NavInput>> {
+            Ok(Vec::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{axis_input, button_input, NavInput, ABS_HAT0X, ABS_HAT0Y, BTN_SOUTH};
+
+    #[test]
+    fn only_presses_navigate() {
+        assert_eq!(button_input(BTN_SOUTH, 1), Some(NavInput::Activate));
+        assert_eq!(button_input(BTN_SOUTH, 0), None);
+        assert_eq!(button_input(BTN_SOUTH, 2), None);
+        assert_eq!(button_input(0x13b, 1), None);
+    }
+
+    #[test]
+    fn hat_axes_map_to_directions() {
+        assert_eq!(axis_input(ABS_HAT0X, -1), Some(NavInput::Previous));
+        assert_eq!(axis_input(ABS_HAT0X, 1), Some(NavInput::Next));
+        assert_eq!(axis_input(ABS_HAT0Y, -1), Some(NavInput::Up));
+        assert_eq!(axis_input(ABS_HAT0Y, 0), None);
+    }
+}
@@ -0,0 +1,34 @@
+//! Gamepads read through evdev: any input device with a south face button.
+
+use std::io;
+
+use super::{axis_input, button_input, NavInput, BTN_SOUTH};
+use crate::evdev::{self, Device, EV_ABS, EV_KEY};
+
+pub struct Pad {
+    device: Device,
+}
+
+impl Pad {
+    /// Navigation in the events queued since the last read.
+    pub fn read(&self) -> io::Result<Vec<NavInput>> {
+        let events = self.device.read_events()?;
+        Ok(events
+            .iter()
+            .filter_map(|event| match event.kind {
+                EV_KEY => button_input(event.code, event.value),
+                EV_ABS => axis_input(event.code, event.value),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+/// Input devices we may open that look like gamepads.
+pub fn scan() -> Vec<Pad> {
+    evdev::open_all()
+        .into_iter()
+        .filter(|device| device.supports(EV_KEY, BTN_SOUTH))
+        .map(|device| Pad { device })
+        .collect()
+}
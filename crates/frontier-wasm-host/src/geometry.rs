@@ -0,0 +1,299 @@
+//! Geometry in logical pixels shared by the host, the WIT layer and embedders: points,
+//! sizes, rectangles and 2D affine transforms, with conversions to and from the WIT
+//! `vec2` so call sites stop copying fields by hand.
+
+use std::ops::{Add, Mul, Sub};
+
+use crate::component::vello::canvas::math::Vec2 as WitVec2;
+use crate::model::LogicalSize;
+
+/// A point or offset in logical pixels.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2 { x: 0.0, y: 0.0 };
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn is_finite(self) -> bool {
+        self.x.is_finite() && self.y.is_finite()
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f32> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, scale: f32) -> Vec2 {
+        Vec2::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl From<[f32; 2]> for Vec2 {
+    fn from([x, y]: [f32; 2]) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<Vec2> for [f32; 2] {
+    fn from(vec: Vec2) -> Self {
+        [vec.x, vec.y]
+    }
+}
+
+impl From<WitVec2> for Vec2 {
+    fn from(vec: WitVec2) -> Self {
+        Self { x: vec.x, y: vec.y }
+    }
+}
+
+impl From<Vec2> for WitVec2 {
+    fn from(vec: Vec2) -> Self {
+        Self { x: vec.x, y: vec.y }
+    }
+}
+
+/// A width and height in logical pixels.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Size {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Size {
+    pub const fn new(width: f32, height: f32) -> Self {
+        Self { width, height }
+    }
+
+    /// True when either side is zero or negative.
+    pub fn is_empty(self) -> bool {
+        !(self.width > 0.0 && self.height > 0.0)
+    }
+}
+
+/// The WIT passes sizes as `vec2`s.
+impl From<Vec2> for Size {
+    fn from(vec: Vec2) -> Self {
+        Self::new(vec.x, vec.y)
+    }
+}
+
+impl From<Size> for Vec2 {
+    fn from(size: Size) -> Self {
+        Vec2::new(size.width, size.height)
+    }
+}
+
+impl From<WitVec2> for Size {
+    fn from(vec: WitVec2) -> Self {
+        Self::new(vec.x, vec.y)
+    }
+}
+
+impl From<Size> for WitVec2 {
+    fn from(size: Size) -> Self {
+        Self {
+            x: size.width,
+            y: size.height,
+        }
+    }
+}
+
+impl From<LogicalSize> for Size {
+    fn from(size: LogicalSize) -> Self {
+        Self::new(size.width, size.height)
+    }
+}
+
+/// Axis-aligned rectangle in logical pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn from_origin_size(origin: Vec2, size: Size) -> Self {
+        Self::new(origin.x, origin.y, size.width, size.height)
+    }
+
+    pub fn origin(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+
+    /// Half-open containment: the right and bottom edges are outside.
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.x
+            && point.x < self.x + self.width
+            && point.y >= self.y
+            && point.y < self.y + self.height
+    }
+
+    /// Whether the two rectangles overlap with a non-zero area.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+
+    /// The area both rectangles cover, with zero width or height when they do not overlap.
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let (x, y) = (self.x.max(other.x), self.y.max(other.y));
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        Rect::new(x, y, (right - x).max(0.0), (bottom - y).max(0.0))
+    }
+
+    /// The device pixels this rectangle covers at `scale`, with negative sizes flipped.
+    /// Each edge is rounded to the nearest pixel on its own, so rectangles sharing a
+    /// logical edge share a physical one and leave no antialiased seam at fractional
+    /// scale factors. A non-empty rectangle keeps at least one pixel in each direction.
+    pub fn snap_to_pixels(&self, scale: f32) -> Rect {
+        let snap = |start: f32, length: f32| {
+            let (a, b) = (start * scale, (start + length) * scale);
+            let (low, high) = (a.min(b).round(), a.max(b).round());
+            if length != 0.0 && high <= low {
+                (low, low + 1.0)
+            } else {
+                (low, high)
+            }
+        };
+        let (x0, x1) = snap(self.x, self.width);
+        let (y0, y1) = snap(self.y, self.height);
+        Rect::new(x0, y0, x1 - x0, y1 - y0)
+    }
+}
+
+/// A 2D affine transform, `[a, b, c, d, e, f]` mapping `(x, y)` to
+/// `(a * x + c * y + e, b * x + d * y + f)`, the same layout as `kurbo::Affine`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform(pub [f32; 6]);
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    pub fn translate(offset: Vec2) -> Self {
+        Self([1.0, 0.0, 0.0, 1.0, offset.x, offset.y])
+    }
+
+    pub fn scale(scale: f32) -> Self {
+        Self([scale, 0.0, 0.0, scale, 0.0, 0.0])
+    }
+
+    /// Apply `self`, then `next`.
+    pub fn then(self, next: Transform) -> Self {
+        let [a, b, c, d, e, f] = self.0;
+        let [na, nb, nc, nd, ne, nf] = next.0;
+        Self([
+            na * a + nc * b,
+            nb * a + nd * b,
+            na * c + nc * d,
+            nb * c + nd * d,
+            na * e + nc * f + ne,
+            nb * e + nd * f + nf,
+        ])
+    }
+
+    pub fn apply(&self, point: Vec2) -> Vec2 {
+        let [a, b, c, d, e, f] = self.0;
+        Vec2::new(a * point.x + c * point.y + e, b * point.x + d * point.y + f)
+    }
+}
+
+impl From<Transform> for vello::kurbo::Affine {
+    fn from(transform: Transform) -> Self {
+        vello::kurbo::Affine::new(transform.0.map(f64::from))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rect, Size, Transform, Vec2, WitVec2};
+
+    #[test]
+    fn converts_to_and_from_wit() {
+        let wit: WitVec2 = Vec2::new(1.5, -2.0).into();
+        assert_eq!((wit.x, wit.y), (1.5, -2.0));
+        assert_eq!(Vec2::from(wit), Vec2::from([1.5, -2.0]));
+        assert_eq!(Size::from(wit), Size::new(1.5, -2.0));
+        assert!(Size::from(wit).is_empty());
+    }
+
+    #[test]
+    fn rect_containment_is_half_open() {
+        let rect = Rect::from_origin_size(Vec2::new(10.0, 10.0), Size::new(5.0, 5.0));
+        assert!(rect.contains(rect.origin()));
+        assert!(!rect.contains(Vec2::new(15.0, 12.0)));
+        assert!(rect.intersects(&Rect::new(14.0, 14.0, 1.0, 1.0)));
+        assert!(!rect.intersects(&Rect::new(15.0, 10.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn snapped_neighbours_share_an_edge() {
+        for scale in [1.0, 1.25, 1.5, 1.75, 2.25] {
+            let left = Rect::new(0.3, 0.0, 10.0, 10.0).snap_to_pixels(scale);
+            let right = Rect::new(10.3, 0.0, 10.0, 10.0).snap_to_pixels(scale);
+            assert_eq!(left.x + left.width, right.x, "scale {scale}");
+            assert_eq!(left.x.fract(), 0.0);
+        }
+        let flipped = Rect::new(10.0, 10.0, -4.0, -4.0).snap_to_pixels(1.5);
+        assert_eq!(flipped, Rect::new(9.0, 9.0, 6.0, 6.0));
+        let hairline = Rect::new(2.0, 0.0, 0.2, 4.0).snap_to_pixels(1.25);
+        assert_eq!((hairline.x, hairline.width), (3.0, 1.0));
+    }
+
+    #[test]
+    fn transforms_compose_in_order() {
+        let transform = Transform::scale(2.0).then(Transform::translate(Vec2::new(1.0, 0.0)));
+        assert_eq!(transform.apply(Vec2::new(3.0, 4.0)), Vec2::new(7.0, 8.0));
+        assert_eq!(
+            Transform::IDENTITY.apply(Vec2::new(3.0, 4.0)),
+            Vec2::new(3.0, 4.0)
+        );
+    }
+}
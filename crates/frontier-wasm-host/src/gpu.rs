@@ -0,0 +1,452 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use wgpu::util::TextureBlitter;
+use winit::window::Window;
+
+/// Device features we use when the adapter offers them.
+const OPTIONAL_FEATURES: wgpu::Features = wgpu::Features::CLEAR_TEXTURE
+    .union(wgpu::Features::PIPELINE_CACHE)
+    .union(wgpu::Features::TIMESTAMP_QUERY)
+    .union(wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS);
+
+/// wgpu instance, adapter, and device shared by the renderer.
+///
+/// This mirrors `vello::util::RenderContext`, but owns the device so the host can opt into
+/// extra features such as timestamp queries.
+pub struct GpuContext {
+    pub instance: wgpu::Instance,
+    pub adapter: wgpu::Adapter,
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+}
+
+/// The adapter in use, as reported by `host-info`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GpuInfo {
+    pub name: String,
+    /// wgpu's lowercase backend name, such as `vulkan` or `metal`.
+    pub backend: String,
+}
+
+/// Window surface plus the intermediate texture Vello renders into before the blit.
+pub struct RenderSurface {
+    /// The window surface; `None` while the app is suspended.
+    pub surface: Option<wgpu::Surface<'static>>,
+    pub config: wgpu::SurfaceConfiguration,
+    pub target_texture: wgpu::Texture,
+    pub target_view: wgpu::TextureView,
+    pub blitter: TextureBlitter,
+}
+
+impl GpuContext {
+    pub async fn with_surface(
+        window: Arc<Window>,
+        width: u32,
+        height: u32,
+        present_mode: wgpu::PresentMode,
+    ) -> Result<(Self, RenderSurface)> {
+        let instance = new_instance();
+        let surface = instance
+            .create_surface(window)
+            .context("failed to create window surface")?;
+        let gpu = Self::with_instance(instance, Some(&surface)).await?;
+
+        let format = surface
+            .get_capabilities(&gpu.adapter)
+            .formats
+            .into_iter()
+            .find(|format| {
+                matches!(
+                    format,
+                    wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Bgra8Unorm
+                )
+            })
+            .context("surface does not support an 8-bit RGBA/BGRA format")?;
+        let supported = surface.get_capabilities(&gpu.adapter).present_modes;
+        let present_mode = if supported.contains(&present_mode) {
+            present_mode
+        } else {
+            tracing::warn!(
+                ?present_mode,
+                "present mode unsupported by surface; using fifo"
+            );
+            wgpu::PresentMode::Fifo
+        };
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width,
+            height,
+            present_mode,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+        };
+        let (target_texture, target_view) = create_targets(&gpu.device, width, height);
+        let render_surface = RenderSurface {
+            surface: Some(surface),
+            config,
+            target_texture,
+            target_view,
+            blitter: TextureBlitter::new(&gpu.device, format),
+        };
+        gpu.configure_surface(&render_surface);
+        Ok((gpu, render_surface))
+    }
+
+    /// A device with no window, rendering only into the intermediate target, which
+    /// [`GpuContext::read_texture_rgba`] reads back. Used to rasterize saved frames.
+    pub async fn headless(width: u32, height: u32) -> Result<(Self, RenderSurface)> {
+        let gpu = Self::with_instance(new_instance(), None).await?;
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+        let (target_texture, target_view) = create_targets(&gpu.device, width, height);
+        let render_surface = RenderSurface {
+            surface: None,
+            config: wgpu::SurfaceConfiguration {
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                format,
+                width,
+                height,
+                present_mode: wgpu::PresentMode::Fifo,
+                desired_maximum_frame_latency: 2,
+                alpha_mode: wgpu::CompositeAlphaMode::Auto,
+                view_formats: vec![],
+            },
+            target_texture,
+            target_view,
+            blitter: TextureBlitter::new(&gpu.device, format),
+        };
+        Ok((gpu, render_surface))
+    }
+
+    async fn with_instance(
+        instance: wgpu::Instance,
+        surface: Option<&wgpu::Surface<'_>>,
+    ) -> Result<Self> {
+        let adapter = wgpu::util::initialize_adapter_from_env_or_default(&instance, surface)
+            .await
+            .context("no compatible GPU adapter")?;
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: Some("frontier.device"),
+                required_features: adapter.features() & OPTIONAL_FEATURES,
+                required_limits: wgpu::Limits::default(),
+                ..Default::default()
+            })
+            .await
+            .context("failed to create GPU device")?;
+        Ok(Self {
+            instance,
+            adapter,
+            device,
+            queue,
+        })
+    }
+
+    pub fn info(&self) -> GpuInfo {
+        let info = self.adapter.get_info();
+        GpuInfo {
+            name: info.name,
+            backend: info.backend.to_str().to_string(),
+        }
+    }
+
+    pub fn resize_surface(&self, surface: &mut RenderSurface, width: u32, height: u32) {
+        let (texture, view) = create_targets(&self.device, width, height);
+        surface.target_texture = texture;
+        surface.target_view = view;
+        surface.config.width = width;
+        surface.config.height = height;
+        self.configure_surface(surface);
+    }
+
+    pub fn configure_surface(&self, surface: &RenderSurface) {
+        if let Some(window_surface) = &surface.surface {
+            window_surface.configure(&self.device, &surface.config);
+        }
+    }
+
+    /// Drop the window surface, keeping the device and render targets. Platforms such as
+    /// Android destroy the native window on suspend, so the surface must not outlive it.
+    pub fn release_surface(&self, surface: &mut RenderSurface) {
+        surface.surface = None;
+    }
+
+    /// Create a fresh surface for `window` after a suspend, with the previous configuration.
+    pub fn recreate_surface(&self, surface: &mut RenderSurface, window: Arc<Window>) -> Result<()> {
+        let window_surface = self
+            .instance
+            .create_surface(window)
+            .context("failed to recreate window surface")?;
+        surface.surface = Some(window_surface);
+        self.configure_surface(surface);
+        Ok(())
+    }
+
+    /// Ask an attached graphics debugger (RenderDoc, or Xcode on Metal) to start recording
+    /// API calls. Does nothing when none is attached.
+    pub fn start_debugger_capture(&self) {
+        // SAFETY: wgpu requires no other capture to be in progress; `GraphicsState` only
+        // starts one around a single frame and always stops it.
+        unsafe { self.device.start_graphics_debugger_capture() };
+    }
+
+    /// Finish the capture started by [`GpuContext::start_debugger_capture`] once the GPU has
+    /// executed everything submitted during it.
+    pub fn stop_debugger_capture(&self) {
+        if let Err(err) = self.device.poll(wgpu::PollType::Wait) {
+            tracing::warn!(%err, "failed to wait for the GPU before ending the capture");
+        }
+        // SAFETY: paired with the `start_debugger_capture` call for the same frame.
+        unsafe { self.device.stop_graphics_debugger_capture() };
+    }
+
+    /// Copy an RGBA8 texture back to the CPU as tightly packed rows. Blocks until the GPU
+    /// finishes, so this is only meant for occasional captures.
+    pub fn read_texture_rgba(&self, texture: &wgpu::Texture) -> Result<Vec<u8>> {
+        let width = texture.width();
+        let height = texture.height();
+        let row_bytes = width * 4;
+        let padded_row_bytes = row_bytes.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frontier.readback"),
+            size: padded_row_bytes as u64 * height as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("frontier.readback"),
+            });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        buffer.map_async(wgpu::MapMode::Read, .., move |result| {
+            let _ = sender.send(result);
+        });
+        self.device
+            .poll(wgpu::PollType::Wait)
+            .context("failed to wait for GPU readback")?;
+        receiver
+            .recv()
+            .context("GPU readback was cancelled")?
+            .context("failed to map readback buffer")?;
+
+        let mut rgba = Vec::with_capacity((row_bytes * height) as usize);
+        {
+            let data = buffer.slice(..).get_mapped_range();
+            for row in data.chunks_exact(padded_row_bytes as usize) {
+                rgba.extend_from_slice(&row[..row_bytes as usize]);
+            }
+        }
+        buffer.unmap();
+        Ok(rgba)
+    }
+}
+
+fn new_instance() -> wgpu::Instance {
+    wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::from_env().unwrap_or_default(),
+        flags: wgpu::InstanceFlags::from_build_config().with_env(),
+        memory_budget_thresholds: wgpu::MemoryBudgetThresholds::default(),
+        backend_options: wgpu::BackendOptions::from_env_or_default(),
+    })
+}
+
+/// Vello renders with compute shaders, so it draws into a storage texture that is then blitted
+/// onto the surface. Shader layers are composited onto it with render passes.
+pub(crate) fn create_targets(
+    device: &wgpu::Device,
+    width: u32,
+    height: u32,
+) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("frontier.target"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        usage: wgpu::TextureUsages::STORAGE_BINDING
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::COPY_SRC,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}
+
+/// GPU time spent on the most recently measured frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize)]
+pub struct GpuTimings {
+    /// Vello's compute passes rasterising the scene.
+    pub render_ms: f32,
+    /// Copying the rendered target onto the window surface.
+    pub blit_ms: f32,
+}
+
+/// Timestamp queries: before the Vello render, between render and blit, and after the blit.
+const TIMESTAMP_COUNT: u32 = 3;
+const TIMESTAMP_BYTES: u64 = TIMESTAMP_COUNT as u64 * wgpu::QUERY_SIZE as u64;
+const READBACK_SLOTS: usize = 3;
+
+const SLOT_IDLE: u8 = 0;
+const SLOT_PENDING: u8 = 1;
+const SLOT_MAPPED: u8 = 2;
+
+struct ReadbackSlot {
+    buffer: wgpu::Buffer,
+    state: Arc<AtomicU8>,
+}
+
+/// Measures GPU render and blit time with timestamp queries, reading results back
+/// asynchronously so the CPU never waits on the GPU.
+pub struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    slots: Vec<ReadbackSlot>,
+    active_slot: Option<usize>,
+    period_ns: f32,
+    latest: Option<GpuTimings>,
+}
+
+impl GpuTimer {
+    /// Returns `None` when the device cannot write timestamps from command encoders.
+    pub fn new(gpu: &GpuContext) -> Option<Self> {
+        let required =
+            wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS;
+        if !gpu.device.features().contains(required) {
+            return None;
+        }
+        let query_set = gpu.device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("frontier.gpu-timer"),
+            ty: wgpu::QueryType::Timestamp,
+            count: TIMESTAMP_COUNT,
+        });
+        let resolve_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frontier.gpu-timer.resolve"),
+            size: TIMESTAMP_BYTES,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let slots = (0..READBACK_SLOTS)
+            .map(|_| ReadbackSlot {
+                buffer: gpu.device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("frontier.gpu-timer.readback"),
+                    size: TIMESTAMP_BYTES,
+                    usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }),
+                state: Arc::new(AtomicU8::new(SLOT_IDLE)),
+            })
+            .collect();
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            slots,
+            active_slot: None,
+            period_ns: gpu.queue.get_timestamp_period(),
+            latest: None,
+        })
+    }
+
+    pub fn latest(&self) -> Option<GpuTimings> {
+        self.latest
+    }
+
+    /// Collect finished readbacks and write the "frame start" timestamp.
+    pub fn begin_frame(&mut self, gpu: &GpuContext) {
+        let _ = gpu.device.poll(wgpu::PollType::Poll);
+        for slot in &self.slots {
+            if slot.state.load(Ordering::Acquire) != SLOT_MAPPED {
+                continue;
+            }
+            {
+                let data = slot.buffer.slice(..).get_mapped_range();
+                let ticks: Vec<u64> = data
+                    .chunks_exact(8)
+                    .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+                    .collect();
+                let to_ms = |start: u64, end: u64| {
+                    end.saturating_sub(start) as f32 * self.period_ns / 1_000_000.0
+                };
+                self.latest = Some(GpuTimings {
+                    render_ms: to_ms(ticks[0], ticks[1]),
+                    blit_ms: to_ms(ticks[1], ticks[2]),
+                });
+            }
+            slot.buffer.unmap();
+            slot.state.store(SLOT_IDLE, Ordering::Release);
+        }
+
+        self.active_slot = self
+            .slots
+            .iter()
+            .position(|slot| slot.state.load(Ordering::Acquire) == SLOT_IDLE);
+        if self.active_slot.is_some() {
+            let mut encoder = gpu
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("frontier.gpu-timer.begin"),
+                });
+            encoder.write_timestamp(&self.query_set, 0);
+            gpu.queue.submit(std::iter::once(encoder.finish()));
+        }
+    }
+
+    /// Mark the end of the Vello render (start of the blit).
+    pub fn mark_render_done(&self, encoder: &mut wgpu::CommandEncoder) {
+        if self.active_slot.is_some() {
+            encoder.write_timestamp(&self.query_set, 1);
+        }
+    }
+
+    /// Mark the end of the blit and queue the results for readback.
+    pub fn end_frame(&self, encoder: &mut wgpu::CommandEncoder) {
+        let Some(slot) = self.active_slot.map(|index| &self.slots[index]) else {
+            return;
+        };
+        encoder.write_timestamp(&self.query_set, 2);
+        encoder.resolve_query_set(&self.query_set, 0..TIMESTAMP_COUNT, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &slot.buffer, 0, TIMESTAMP_BYTES);
+    }
+
+    /// Start mapping the readback buffer once the frame's commands were submitted.
+    pub fn after_submit(&mut self) {
+        let Some(slot) = self.active_slot.take().map(|index| &self.slots[index]) else {
+            return;
+        };
+        slot.state.store(SLOT_PENDING, Ordering::Release);
+        let state = slot.state.clone();
+        slot.buffer
+            .map_async(wgpu::MapMode::Read, .., move |result| {
+                let next = if result.is_ok() {
+                    SLOT_MAPPED
+                } else {
+                    SLOT_IDLE
+                };
+                state.store(next, Ordering::Release);
+            });
+    }
+}
@@ -1,38 +1,193 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context, Result};
-use vello::kurbo::{Affine, Rect};
-use vello::peniko::{Brush, Fill};
-use vello::util::{RenderContext, RenderSurface};
-use vello::{AaConfig, Glyph, Renderer, RendererOptions, Scene};
+use vello::kurbo::{Affine, Circle, Rect, Stroke};
+use vello::peniko::{Brush, Extend, Fill, ImageBrush, ImageQuality, Mix};
+use vello::{AaConfig, AaSupport, Glyph, Renderer, RendererOptions, Scene};
 use wgpu::SurfaceError;
 use winit::dpi::PhysicalSize;
 use winit::window::Window;
 
-use crate::host::{Color, DrawCommand, FrameOutput};
+use crate::bidi::{self, TextDirection};
+use crate::canvas::CanvasBackend;
+use crate::effects::{self, EffectCompositor, LayerRun};
+use crate::geometry::{self, Vec2};
+use crate::gpu::{self, GpuContext, GpuInfo, GpuTimer, GpuTimings, RenderSurface};
+use crate::host::{
+    Color, DrawCommand, FontFamily, FrameOutput, PatternRepeat, TextFlow, WritingMode,
+};
+use crate::images::{FilterCache, Image, ImageStyle};
+use crate::lottie::Animation;
+use crate::memory::MemoryPressure;
+use crate::particles::{ParticleRenderer, ParticleSprite, ParticleTarget, Particles};
+use crate::pipeline_cache::PipelineCacheFile;
+use crate::preferences::ForcedColors;
+use crate::svg::Picture;
+use crate::transition::{Transition, TransitionKind};
 
-const FONT_BYTES: &[u8] = include_bytes!("../../../assets/Cantarell-Regular.ttf");
+const SANS_FONT_BYTES: &[u8] = include_bytes!("../../../assets/Cantarell-Regular.ttf");
+const SANS_BOLD_FONT_BYTES: &[u8] = include_bytes!("../../../assets/DejaVuSans-Bold.ttf");
+const MONOSPACE_FONT_BYTES: &[u8] = include_bytes!("../../../assets/DejaVuSansMono.ttf");
+
+/// Width and height of the off-screen target [`GraphicsState::warm_up`] renders into.
+const WARM_UP_SIZE: u32 = 64;
+
+/// Background used when the guest does not call `clear`.
+pub const DEFAULT_CLEAR_COLOR: Color = Color {
+    r: 0.06,
+    g: 0.07,
+    b: 0.09,
+    a: 1.0,
+};
+
+/// Swapchain presentation mode. `fifo` (vsync) is supported everywhere; other modes fall
+/// back to it when the surface does not offer them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PresentMode {
+    #[default]
+    Fifo,
+    FifoRelaxed,
+    Mailbox,
+    Immediate,
+}
+
+impl PresentMode {
+    fn to_wgpu(self) -> wgpu::PresentMode {
+        match self {
+            PresentMode::Fifo => wgpu::PresentMode::Fifo,
+            PresentMode::FifoRelaxed => wgpu::PresentMode::FifoRelaxed,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+        }
+    }
+}
+
+/// Vello antialiasing method used for every frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Antialiasing {
+    #[default]
+    Area,
+    Msaa8,
+    Msaa16,
+}
+
+impl Antialiasing {
+    fn to_vello(self) -> AaConfig {
+        match self {
+            Antialiasing::Area => AaConfig::Area,
+            Antialiasing::Msaa8 => AaConfig::Msaa8,
+            Antialiasing::Msaa16 => AaConfig::Msaa16,
+        }
+    }
+
+    fn support(self) -> AaSupport {
+        match self {
+            Antialiasing::Area => AaSupport::area_only(),
+            Antialiasing::Msaa8 => AaSupport {
+                area: false,
+                msaa8: true,
+                msaa16: false,
+            },
+            Antialiasing::Msaa16 => AaSupport {
+                area: false,
+                msaa8: false,
+                msaa16: true,
+            },
+        }
+    }
+}
+
+/// Renderer settings fixed when the window's graphics are created.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RenderOptions {
+    pub present_mode: PresentMode,
+    pub antialiasing: Antialiasing,
+    /// Wrap the GPU work of this rendered frame, counting from 1, in a graphics debugger
+    /// capture.
+    pub gpu_capture: Option<u64>,
+    /// Map guest colours onto this palette (`--force-colors`).
+    pub forced_colors: Option<ForcedColors>,
+    /// Keep compiled pipelines on disk between runs; off with `--no-pipeline-cache`.
+    pub pipeline_cache: bool,
+    /// Render a throwaway scene behind the loading screen (`--warm-up`).
+    pub warm_up: bool,
+}
 
 pub struct OverlayContent {
     pub title: String,
     pub body: Vec<String>,
     pub footer: String,
+    /// First body line shown, counted after wrapping; clamped when drawn.
+    pub scroll: usize,
+    /// Actions drawn as a row of buttons below the body.
+    pub buttons: Vec<OverlayButton>,
+}
+
+/// One overlay action; the focused one is outlined so keyboard and gamepad users can see
+/// what Enter or A will do.
+pub struct OverlayButton {
+    pub label: String,
+    pub focused: bool,
+}
+
+/// How far the last drawn overlay body can scroll.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OverlayScroll {
+    /// Largest useful `OverlayContent::scroll`.
+    pub max: usize,
+    /// Body lines that fit on screen at once.
+    pub page: usize,
 }
 
 pub struct GraphicsState {
-    render_cx: RenderContext,
-    surface: RenderSurface<'static>,
+    gpu: GpuContext,
+    surface: RenderSurface,
     renderer: Renderer,
-    scene: Scene,
-    font: FontAssets,
-    scale_factor: f32,
+    effects: EffectCompositor,
+    particles: ParticleRenderer,
+    timer: Option<GpuTimer>,
+    encoder: SceneEncoder,
+    /// Created by [`GraphicsState::headless`]: there is no surface, but it still renders.
+    headless: bool,
     logical_size: crate::model::LogicalSize,
     default_clear: Color,
+    opacity: f32,
+    antialiasing: Antialiasing,
+    overlay_scroll: OverlayScroll,
+    /// Old component's frame being blended out after a restart.
+    transition: Option<Transition>,
+    /// The offscreen target holds a finished frame at the surface's current size, so it
+    /// can be presented again without rendering.
+    target_ready: bool,
+    /// Frames rendered so far, to find the one `--gpu-capture` asked for.
+    frames_rendered: u64,
+    /// Guest commands the last frame skipped for lying outside the window.
+    culled: usize,
+    gpu_capture: Option<u64>,
+    /// Saved after warming up and again when the window's graphics are dropped.
+    pipeline_cache: Option<PipelineCacheFile>,
+    warmed_up: bool,
 }
 
-struct FontAssets {
+/// The bundled typefaces. A face that fails to load is left out with a warning; text in
+/// it falls back to another face, or to placeholder boxes when none loaded, so a broken
+/// font never takes down the host.
+pub(crate) struct FontAssets {
+    sans: Option<FontFace>,
+    sans_bold: Option<FontFace>,
+    monospace: Option<FontFace>,
+}
+
+/// One embedded font, loaded once for Vello (drawing) and once for ab_glyph (layout).
+pub(crate) struct FontFace {
     font_data: vello::peniko::FontData,
-    font_arc: ab_glyph::FontArc,
+    pub(crate) font_arc: ab_glyph::FontArc,
 }
 
 impl GraphicsState {
@@ -40,58 +195,218 @@ impl GraphicsState {
         window: Arc<Window>,
         scale_factor: f32,
         logical_size: crate::model::LogicalSize,
+        options: RenderOptions,
     ) -> Result<Self> {
-        let mut render_cx = RenderContext::new();
         let physical = window.inner_size();
-        let surface = pollster::block_on(render_cx.create_surface(
+        let (gpu, surface) = pollster::block_on(GpuContext::with_surface(
             window.clone(),
             physical.width.max(1),
             physical.height.max(1),
-            wgpu::PresentMode::Fifo,
+            options.present_mode.to_wgpu(),
         ))
         .context("failed to create wgpu surface")?;
+        Self::with_gpu(gpu, surface, scale_factor, logical_size, options, false)
+    }
+
+    /// Render without a window, for rasterizing saved frames. Frames stay in the target
+    /// texture for [`GraphicsState::capture_png`]; GPU timings are not collected.
+    pub fn headless(
+        width: u32,
+        height: u32,
+        scale_factor: f32,
+        logical_size: crate::model::LogicalSize,
+        options: RenderOptions,
+    ) -> Result<Self> {
+        let (gpu, surface) = pollster::block_on(GpuContext::headless(width.max(1), height.max(1)))
+            .context("failed to create headless GPU context")?;
+        Self::with_gpu(gpu, surface, scale_factor, logical_size, options, true)
+    }
 
-        let device = &render_cx.devices[surface.dev_id].device;
+    fn with_gpu(
+        gpu: GpuContext,
+        surface: RenderSurface,
+        scale_factor: f32,
+        logical_size: crate::model::LogicalSize,
+        options: RenderOptions,
+        headless: bool,
+    ) -> Result<Self> {
+        let pipeline_cache = options
+            .pipeline_cache
+            .then(|| PipelineCacheFile::open(&gpu))
+            .flatten();
+        let cache = pipeline_cache.as_ref().map(|file| file.cache.clone());
         let renderer = Renderer::new(
-            device,
+            &gpu.device,
             RendererOptions {
                 use_cpu: false,
-                antialiasing_support: vello::AaSupport::area_only(),
+                antialiasing_support: options.antialiasing.support(),
                 num_init_threads: if cfg!(target_os = "macos") {
                     Some(std::num::NonZeroUsize::new(1).unwrap())
                 } else {
                     None
                 },
-                pipeline_cache: None,
+                pipeline_cache: cache.clone(),
             },
         )
         .context("failed to initialise vello renderer")?;
 
-        let font = FontAssets::new().context("failed to prepare font assets")?;
+        let mut encoder = SceneEncoder::new(scale_factor);
+        encoder.forced_colors = options.forced_colors;
+        let particles = ParticleRenderer::new(&gpu.device, cache.as_ref());
+        let effects = EffectCompositor::new(&gpu.device, cache);
+
+        let timer = if headless { None } else { GpuTimer::new(&gpu) };
+        if timer.is_none() && !headless {
+            tracing::debug!("GPU timestamp queries unavailable; GPU timings disabled");
+        }
 
         Ok(Self {
-            render_cx,
+            gpu,
             surface,
             renderer,
-            scene: Scene::new(),
-            font,
-            scale_factor,
+            effects,
+            particles,
+            timer,
+            encoder,
+            headless,
             logical_size,
-            default_clear: Color {
-                r: 0.06,
-                g: 0.07,
-                b: 0.09,
-                a: 1.0,
-            },
+            default_clear: DEFAULT_CLEAR_COLOR,
+            opacity: 1.0,
+            antialiasing: options.antialiasing,
+            overlay_scroll: OverlayScroll::default(),
+            transition: None,
+            target_ready: false,
+            frames_rendered: 0,
+            culled: 0,
+            gpu_capture: options.gpu_capture,
+            pipeline_cache,
+            warmed_up: false,
         })
     }
 
+    /// Render a throwaway scene off screen that uses every kind of drawing the host
+    /// encodes, so the driver finishes compiling and allocating before the first real
+    /// frame instead of during it. Only the first call does anything.
+    pub fn warm_up(&mut self) -> Result<()> {
+        if self.warmed_up || self.is_suspended() {
+            return Ok(());
+        }
+        self.warmed_up = true;
+        let started = Instant::now();
+        let (_texture, view) = gpu::create_targets(&self.gpu.device, WARM_UP_SIZE, WARM_UP_SIZE);
+        let extent = Vec2::new(WARM_UP_SIZE as f32, WARM_UP_SIZE as f32);
+        let color = Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 0.5,
+        };
+        let image = Image::new(2, 2, vec![255; 16]).context("warm-up image")?;
+        let mut commands = vec![
+            DrawCommand::FillRect {
+                origin: Vec2::ZERO,
+                size: extent,
+                color,
+            },
+            DrawCommand::DrawImage {
+                image,
+                origin: Vec2::ZERO,
+                size: extent,
+                style: ImageStyle::default(),
+            },
+        ];
+        for font in [
+            FontFamily::Sans,
+            FontFamily::SansBold,
+            FontFamily::Monospace,
+        ] {
+            commands.push(DrawCommand::DrawText {
+                text: "Ag".to_string(),
+                origin: Vec2::new(0.0, 12.0),
+                size: 12.0,
+                color,
+                font,
+                flow: TextFlow::default(),
+            });
+        }
+        // Transitions and window opacity draw through a layer.
+        let bounds = Rect::new(0.0, 0.0, extent.x.into(), extent.y.into());
+        self.encoder.scene.reset();
+        self.encoder
+            .scene
+            .push_layer(Mix::Normal, 0.5, Affine::IDENTITY, &bounds);
+        self.encoder.encode_commands(&commands);
+        self.encoder.scene.pop_layer();
+        let render_params = vello::RenderParams {
+            base_color: vello::peniko::Color::TRANSPARENT,
+            width: WARM_UP_SIZE,
+            height: WARM_UP_SIZE,
+            antialiasing_method: self.antialiasing.to_vello(),
+        };
+        self.renderer
+            .render_to_texture(
+                &self.gpu.device,
+                &self.gpu.queue,
+                &self.encoder.scene,
+                &view,
+                &render_params,
+            )
+            .context("vello warm-up render failed")?;
+        self.encoder.scene.reset();
+        self.gpu
+            .device
+            .poll(wgpu::PollType::Wait)
+            .context("waiting for the warm-up render failed")?;
+        tracing::debug!(
+            elapsed_ms = started.elapsed().as_secs_f32() * 1000.0,
+            "graphics warmed up"
+        );
+        self.save_pipeline_cache();
+        Ok(())
+    }
+
+    /// Bytes held by the renderer's own caches, for `--memory-budget`.
+    pub fn cache_bytes(&self) -> usize {
+        self.encoder.filtered_images.bytes() + self.particles.bytes()
+    }
+
+    /// Shrink the renderer's caches under memory pressure. Moderate pressure halves the
+    /// recoloured image copies, least recently drawn first; critical pressure drops them
+    /// all along with effect pipelines, the layer texture and the particle buffers.
+    pub fn trim_caches(&mut self, level: MemoryPressure) {
+        let before = self.cache_bytes();
+        match level {
+            MemoryPressure::Normal => return,
+            MemoryPressure::Moderate => self.encoder.filtered_images.evict_to(before / 2),
+            MemoryPressure::Critical => {
+                self.encoder.filtered_images.evict_to(0);
+                self.effects.release();
+                self.particles.release();
+            }
+        }
+        tracing::debug!(
+            ?level,
+            before,
+            after = self.cache_bytes(),
+            "trimmed render caches"
+        );
+    }
+
+    fn save_pipeline_cache(&self) {
+        if let Some(file) = &self.pipeline_cache {
+            if let Err(err) = file.save() {
+                tracing::warn!(error = %format!("{err:#}"), "failed to save the pipeline cache");
+            }
+        }
+    }
+
     pub fn resize(&mut self, new_size: PhysicalSize<u32>) {
         if new_size.width == 0 || new_size.height == 0 {
             return;
         }
-        self.render_cx
+        self.gpu
             .resize_surface(&mut self.surface, new_size.width, new_size.height);
+        self.target_ready = false;
     }
 
     pub fn set_logical_size(&mut self, logical_size: crate::model::LogicalSize) {
@@ -99,53 +414,215 @@ impl GraphicsState {
     }
 
     pub fn set_scale_factor(&mut self, scale: f32) {
-        self.scale_factor = scale;
+        self.encoder.scale_factor = scale;
+    }
+
+    /// Release the window surface while the app is suspended; rendering is skipped until
+    /// [`GraphicsState::resume`].
+    pub fn suspend(&mut self) {
+        self.gpu.release_surface(&mut self.surface);
+    }
+
+    pub fn gpu_info(&self) -> GpuInfo {
+        self.gpu.info()
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        !self.headless && self.surface.surface.is_none()
+    }
+
+    /// Recreate the surface for `window`, which may have been resized while suspended.
+    pub fn resume(&mut self, window: Arc<Window>) -> Result<()> {
+        let size = window.inner_size();
+        self.gpu.recreate_surface(&mut self.surface, window)?;
+        self.resize(size);
+        self.set_opacity(self.opacity);
+        Ok(())
+    }
+
+    /// What the window is cleared to when the guest asked for `requested`.
+    fn clear_color(&self, requested: Option<Color>) -> Color {
+        match &self.encoder.forced_colors {
+            Some(palette) => palette.clear(),
+            None => requested.unwrap_or(self.default_clear),
+        }
+    }
+
+    pub fn set_background(&mut self, color: Color) {
+        self.default_clear = color;
+    }
+
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+        let Some(window_surface) = &self.surface.surface else {
+            return;
+        };
+        let alpha_mode = if opacity < 1.0 {
+            let caps = window_surface.get_capabilities(&self.gpu.adapter);
+            [
+                wgpu::CompositeAlphaMode::PreMultiplied,
+                wgpu::CompositeAlphaMode::PostMultiplied,
+            ]
+            .into_iter()
+            .find(|mode| caps.alpha_modes.contains(mode))
+            .unwrap_or_else(|| {
+                tracing::warn!("surface does not support transparency; opacity has no effect");
+                wgpu::CompositeAlphaMode::Auto
+            })
+        } else {
+            wgpu::CompositeAlphaMode::Auto
+        };
+        if self.surface.config.alpha_mode != alpha_mode {
+            self.surface.config.alpha_mode = alpha_mode;
+            self.gpu.configure_surface(&self.surface);
+        }
+    }
+
+    /// Blend `outgoing` out over the frames rendered during the next `duration`.
+    pub fn start_transition(
+        &mut self,
+        kind: TransitionKind,
+        outgoing: FrameOutput,
+        duration: Duration,
+    ) {
+        self.transition = Transition::start(kind, outgoing, duration, Instant::now());
+    }
+
+    /// A transition still needs frames; the window should keep redrawing until it ends.
+    pub fn transition_running(&self) -> bool {
+        self.transition.is_some()
     }
 
     pub fn render(
         &mut self,
         frame: Option<&FrameOutput>,
         overlay: Option<&OverlayContent>,
+        stats: Option<&[String]>,
     ) -> Result<()> {
-        self.scene.reset();
+        if self.is_suspended() {
+            return Ok(());
+        }
+        let translucent = self.opacity < 1.0;
+        let mut base_color = self.clear_color(frame.and_then(|frame| frame.clear_color));
+        if translucent {
+            base_color.a *= self.opacity;
+        }
+        let runs = match frame {
+            Some(frame) => effects::layer_runs(&frame.commands),
+            None => vec![LayerRun {
+                commands: &[],
+                effect: None,
+            }],
+        };
 
-        let mut base_color = self.default_clear;
+        self.frames_rendered += 1;
+        let capture = self.gpu_capture == Some(self.frames_rendered);
+        if capture {
+            tracing::info!(frame = self.frames_rendered, "starting GPU capture");
+            self.gpu.start_debugger_capture();
+        }
+        if let Some(timer) = self.timer.as_mut() {
+            timer.begin_frame(&self.gpu);
+        }
+        // Stop the capture even when rendering fails, so the debugger is not left recording.
+        let presented = self
+            .render_runs(&runs, base_color, overlay, stats)
+            .and_then(|()| self.present_target(true));
+        if capture {
+            self.gpu.stop_debugger_capture();
+            tracing::info!(frame = self.frames_rendered, "finished GPU capture");
+        }
+        presented
+    }
 
-        if let Some(frame) = frame {
-            if let Some(clear) = frame.clear_color {
-                base_color = clear;
+    fn render_runs(
+        &mut self,
+        runs: &[LayerRun],
+        base_color: Color,
+        overlay: Option<&OverlayContent>,
+        stats: Option<&[String]>,
+    ) -> Result<()> {
+        let viewport =
+            geometry::Rect::new(0.0, 0.0, self.logical_size.width, self.logical_size.height);
+        self.encoder
+            .set_viewport((viewport.width > 0.0 && viewport.height > 0.0).then_some(viewport));
+        self.encoder.take_culled();
+        let transparent = Color {
+            a: 0.0,
+            ..base_color
+        };
+        // The first pass draws over the clear colour straight into the target; each later
+        // pass renders into the offscreen layer and is composited over what came before.
+        // Plain runs are cut at particle draws, which go straight onto the target between
+        // passes. Inside a shader layer they stay in the scene so the effect sees them.
+        let mut first = true;
+        for (index, run) in runs.iter().enumerate() {
+            let last = index + 1 == runs.len();
+            if index > 0 && run.effect.is_none() && run.commands.is_empty() && !last {
+                continue;
             }
-            for command in &frame.commands {
-                self.apply_command(command);
+            let extras = last.then_some((overlay, stats));
+            if run.effect.is_some() {
+                self.encode_run(run.commands, &[], extras);
+                self.render_scene(transparent, Some(run))?;
+                first = false;
+                continue;
+            }
+            let mut clips = Vec::new();
+            let mut rest = run.commands;
+            loop {
+                let split = rest.iter().position(|command| {
+                    matches!(
+                        command,
+                        DrawCommand::DrawParticles { .. } | DrawCommand::DrawEmitter { .. }
+                    )
+                });
+                let segment = &rest[..split.unwrap_or(rest.len())];
+                let extras = extras.filter(|_| split.is_none());
+                if first || !segment.is_empty() || extras.is_some() {
+                    clips = self.encode_run(segment, &clips, extras);
+                    if first {
+                        self.render_scene(base_color, None)?;
+                    } else {
+                        let pass = LayerRun {
+                            commands: segment,
+                            effect: None,
+                        };
+                        self.render_scene(transparent, Some(&pass))?;
+                    }
+                    first = false;
+                }
+                let Some(split) = split else {
+                    break;
+                };
+                self.draw_particles(&rest[split], clips.last());
+                rest = &rest[split + 1..];
             }
         }
+        self.culled = self.encoder.take_culled();
+        self.effects.trim();
+        self.target_ready = true;
+        Ok(())
+    }
 
-        if let Some(overlay) = overlay {
-            self.draw_overlay(overlay);
+    /// Show the last rendered frame again, e.g. when the window is uncovered, without
+    /// rendering anything. False when there is no frame at the current size to show.
+    pub fn present_last(&mut self) -> Result<bool> {
+        if self.is_suspended() || !self.target_ready {
+            return Ok(false);
         }
+        self.present_target(false)?;
+        Ok(true)
+    }
 
-        let device_handle = &self.render_cx.devices[self.surface.dev_id];
-        let device = &device_handle.device;
-        let queue = &device_handle.queue;
-
-        let render_params = vello::RenderParams {
-            base_color: base_color.to_peniko(),
-            width: self.surface.config.width,
-            height: self.surface.config.height,
-            antialiasing_method: AaConfig::Area,
+    /// Copy the offscreen target to the window; `timed` frames close the GPU timer's query.
+    fn present_target(&mut self, timed: bool) -> Result<()> {
+        let device = &self.gpu.device;
+        let queue = &self.gpu.queue;
+        let Some(window_surface) = &self.surface.surface else {
+            return Ok(());
         };
-
-        self.renderer
-            .render_to_texture(
-                device,
-                queue,
-                &self.scene,
-                &self.surface.target_view,
-                &render_params,
-            )
-            .context("vello render failed")?;
-
-        let frame = match self.surface.surface.get_current_texture() {
+        let frame = match window_surface.get_current_texture() {
             Ok(frame) => frame,
             Err(SurfaceError::Lost) => {
                 tracing::warn!("surface lost, reconfiguring");
@@ -172,80 +649,273 @@ impl GraphicsState {
             label: Some("frontier.present"),
         });
 
+        let timer = self.timer.as_mut().filter(|_| timed);
+        if let Some(timer) = &timer {
+            timer.mark_render_done(&mut encoder);
+        }
         self.surface
             .blitter
             .copy(device, &mut encoder, &self.surface.target_view, &view);
+        if let Some(timer) = &timer {
+            timer.end_frame(&mut encoder);
+        }
 
         queue.submit(std::iter::once(encoder.finish()));
+        if let Some(timer) = timer {
+            timer.after_submit();
+        }
         frame.present();
         Ok(())
     }
 
-    fn apply_command(&mut self, command: &DrawCommand) {
-        match command {
-            DrawCommand::FillRect {
-                origin,
-                size,
-                color,
-            } => {
-                self.draw_rect([origin.x, origin.y], [size.x, size.y], *color);
+    /// Fill the scene with `commands` inside `clips`, plus the host's stats and overlay when
+    /// given. Returns the clips still open at the end of `commands`, for the next pass.
+    fn encode_run(
+        &mut self,
+        commands: &[DrawCommand],
+        clips: &[geometry::Rect],
+        extras: Option<(Option<&OverlayContent>, Option<&[String]>)>,
+    ) -> Vec<geometry::Rect> {
+        self.encoder.scene.reset();
+        let translucent = self.opacity < 1.0;
+        if translucent {
+            let bounds = Rect::new(
+                0.0,
+                0.0,
+                self.surface.config.width as f64,
+                self.surface.config.height as f64,
+            );
+            self.encoder
+                .scene
+                .push_layer(Mix::Normal, self.opacity, Affine::IDENTITY, &bounds);
+        }
+        self.encoder.reopen_clips(clips);
+        self.encoder.encode_unclosed(commands);
+        let open = self.encoder.clips.clone();
+        self.encoder.close_clips();
+        if let Some((overlay, stats)) = extras {
+            self.draw_transition();
+            if let Some(stats) = stats {
+                self.draw_stats(stats);
             }
-            DrawCommand::DrawText {
-                text,
-                origin,
-                size,
-                color,
-            } => {
-                self.draw_text(text.as_str(), [origin.x, origin.y], *size, *color);
+            if let Some(overlay) = overlay {
+                self.draw_overlay(overlay);
             }
         }
+        if translucent {
+            self.encoder.scene.pop_layer();
+        }
+        open
     }
 
-    fn draw_rect(&mut self, origin: [f32; 2], size: [f32; 2], color: Color) {
-        let x0 = (origin[0] * self.scale_factor) as f64;
-        let y0 = (origin[1] * self.scale_factor) as f64;
-        let rect = Rect::new(
-            x0,
-            y0,
-            x0 + (size[0] * self.scale_factor) as f64,
-            y0 + (size[1] * self.scale_factor) as f64,
+    /// Draw a `DrawParticles` or `DrawEmitter` command onto the target with the particle
+    /// renderer, within `clip` and the window.
+    fn draw_particles(&mut self, command: &DrawCommand, clip: Option<&geometry::Rect>) {
+        let (width, height) = (self.surface.config.width, self.surface.config.height);
+        let window =
+            geometry::Rect::new(0.0, 0.0, self.logical_size.width, self.logical_size.height);
+        let visible = clip.map_or(window, |clip| clip.intersection(&window));
+        if self
+            .encoder
+            .extent(command)
+            .is_some_and(|extent| !visible.intersects(&extent))
+        {
+            self.encoder.culled += 1;
+            return;
+        }
+        let particles = match command {
+            DrawCommand::DrawParticles { particles } => Particles::Sprites(particles),
+            DrawCommand::DrawEmitter { particles } => Particles::Batch(particles),
+            _ => return,
+        };
+        let scale = self.encoder.scale_factor;
+        let area = match clip {
+            Some(clip) => clip.snap_to_pixels(scale),
+            None => geometry::Rect::new(0.0, 0.0, width as f32, height as f32),
+        };
+        let (x0, y0) = (area.x.max(0.0), area.y.max(0.0));
+        let x1 = (area.x + area.width).min(width as f32);
+        let y1 = (area.y + area.height).min(height as f32);
+        if x1 <= x0 || y1 <= y0 {
+            return;
+        }
+        let target = ParticleTarget {
+            view: &self.surface.target_view,
+            width,
+            height,
+            scale_factor: scale,
+            opacity: self.opacity,
+            scissor: [x0 as u32, y0 as u32, (x1 - x0) as u32, (y1 - y0) as u32],
+        };
+        let forced_colors = &self.encoder.forced_colors;
+        let recolor = |color| match forced_colors {
+            Some(palette) => palette.text(color),
+            None => color,
+        };
+        self.particles.draw(
+            &self.gpu.device,
+            &self.gpu.queue,
+            &target,
+            particles,
+            &recolor,
         );
-        self.scene.fill(
+    }
+
+    /// Draw the outgoing frame of a running transition over the guest's, ending the
+    /// transition once it is done.
+    fn draw_transition(&mut self) {
+        let Some(transition) = self.transition.take() else {
+            return;
+        };
+        let Some(progress) = transition.progress(Instant::now()) else {
+            return;
+        };
+        let (width, height) = (
+            self.surface.config.width as f64,
+            self.surface.config.height as f64,
+        );
+        let bounds = Rect::new(0.0, 0.0, width, height);
+        let clear = self.clear_color(transition.outgoing.clear_color);
+
+        // Encode the old frame on its own so it can be moved and faded as a whole. Its
+        // culling is left out of the new frame's count.
+        let culled = self.encoder.take_culled();
+        let incoming = std::mem::take(&mut self.encoder.scene);
+        self.encoder.scene.fill(
             Fill::NonZero,
             Affine::IDENTITY,
-            Brush::Solid(color.to_peniko()),
+            clear.to_peniko(),
             None,
-            &rect,
+            &bounds,
         );
+        self.encoder.encode_commands(&transition.outgoing.commands);
+        let outgoing = std::mem::replace(&mut self.encoder.scene, incoming);
+        self.encoder.culled = culled;
+
+        let scene = &mut self.encoder.scene;
+        match transition.kind {
+            TransitionKind::None => {}
+            TransitionKind::Crossfade => {
+                scene.push_layer(Mix::Normal, 1.0 - progress, Affine::IDENTITY, &bounds);
+                scene.append(&outgoing, None);
+                scene.pop_layer();
+            }
+            TransitionKind::Slide => {
+                let offset = Affine::translate((-width * progress as f64, 0.0));
+                scene.append(&outgoing, Some(offset));
+            }
+        }
+        self.transition = Some(transition);
     }
 
-    fn draw_text(&mut self, text: &str, origin: [f32; 2], size: f32, color: Color) {
-        if text.is_empty() {
-            return;
+    /// Rasterise the scene into the target, or into the effect layer and composite that
+    /// over the target when `layer` is given.
+    fn render_scene(&mut self, base_color: Color, layer: Option<&LayerRun<'_>>) -> Result<()> {
+        let device = &self.gpu.device;
+        let queue = &self.gpu.queue;
+        let (width, height) = (self.surface.config.width, self.surface.config.height);
+        let render_params = vello::RenderParams {
+            base_color: base_color.to_peniko(),
+            width,
+            height,
+            antialiasing_method: self.antialiasing.to_vello(),
+        };
+        let view = match layer {
+            Some(_) => self.effects.layer_view(device, width, height),
+            None => &self.surface.target_view,
+        };
+        self.renderer
+            .render_to_texture(device, queue, &self.encoder.scene, view, &render_params)
+            .context("vello render failed")?;
+        if let Some(layer) = layer {
+            self.effects
+                .composite(device, queue, &self.surface.target_view, layer.effect);
         }
-        let physical_origin = [origin[0] * self.scale_factor, origin[1] * self.scale_factor];
-        let font_size = size * self.scale_factor;
-        let glyphs = layout_text(&self.font.font_arc, text, font_size);
-        if glyphs.is_empty() {
-            return;
+        Ok(())
+    }
+
+    /// Save the most recently rendered frame (including host overlays) as a PNG.
+    pub fn capture_png(&self, path: &Path) -> Result<(u32, u32)> {
+        let texture = &self.surface.target_texture;
+        let rgba = self.gpu.read_texture_rgba(texture)?;
+        let (width, height) = (texture.width(), texture.height());
+        let file = File::create(path)
+            .with_context(|| format!("failed to create screenshot {}", path.display()))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&rgba))
+            .with_context(|| format!("failed to encode screenshot {}", path.display()))?;
+        Ok((width, height))
+    }
+
+    /// Guest commands the last rendered frame skipped for lying entirely outside the window.
+    pub fn culled_commands(&self) -> usize {
+        self.culled
+    }
+
+    /// GPU render/blit time of a frame a few back, if timestamp queries are supported.
+    pub fn gpu_timings(&self) -> Option<GpuTimings> {
+        self.timer.as_ref().and_then(GpuTimer::latest)
+    }
+
+    /// Bytes the device has allocated, on backends that report it (Vulkan, DX12, Metal).
+    pub fn gpu_memory_bytes(&self) -> Option<u64> {
+        self.gpu
+            .device
+            .generate_allocator_report()
+            .map(|report| report.total_allocated_bytes)
+    }
+
+    pub fn overlay_scroll(&self) -> OverlayScroll {
+        self.overlay_scroll
+    }
+
+    fn draw_stats(&mut self, lines: &[String]) {
+        const LINE_HEIGHT: f32 = 16.0;
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as f32
+            * 7.5;
+        self.encoder.fill_rect(
+            Vec2::new(4.0, 4.0),
+            Vec2::new(width + 12.0, lines.len() as f32 * LINE_HEIGHT + 8.0),
+            Color {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.6,
+            },
+        );
+        let text_color = Color {
+            r: 0.6,
+            g: 1.0,
+            b: 0.6,
+            a: 1.0,
+        };
+        for (index, line) in lines.iter().enumerate() {
+            let baseline = 4.0 + LINE_HEIGHT * (index as f32 + 1.0);
+            self.encoder.draw_text(
+                line,
+                Vec2::new(10.0, baseline),
+                13.0,
+                text_color,
+                FontFamily::Sans,
+                TextFlow::default(),
+            );
         }
-        self.scene
-            .draw_glyphs(&self.font.font_data)
-            .font_size(font_size)
-            .brush(Brush::Solid(color.to_peniko()))
-            .transform(Affine::translate((
-                physical_origin[0] as f64,
-                physical_origin[1] as f64,
-            )))
-            .draw(Fill::NonZero, glyphs.into_iter());
     }
 
     fn draw_overlay(&mut self, overlay: &OverlayContent) {
         let width = self.logical_size.width;
         let height = self.logical_size.height;
-        self.draw_rect(
-            [0.0, 0.0],
-            [width, height],
+        self.encoder.fill_rect(
+            Vec2::new(0.0, 0.0),
+            Vec2::new(width, height),
             Color {
                 r: 0.0,
                 g: 0.0,
@@ -254,6 +924,8 @@ impl GraphicsState {
             },
         );
 
+        let left = width * 0.1;
+        let max_width = width * 0.8;
         let mut cursor_y = height * 0.2;
         let title_color = Color {
             r: 1.0,
@@ -261,36 +933,652 @@ impl GraphicsState {
             b: 0.2,
             a: 1.0,
         };
-        self.draw_text(&overlay.title, [width * 0.1, cursor_y], 28.0, title_color);
+        self.encoder.draw_text(
+            &overlay.title,
+            Vec2::new(left, cursor_y),
+            28.0,
+            title_color,
+            FontFamily::SansBold,
+            TextFlow::default(),
+        );
         cursor_y += 36.0;
 
+        let footer = self.encoder.wrap_text(&overlay.footer, 18.0, max_width);
+        let body: Vec<String> = overlay
+            .body
+            .iter()
+            .flat_map(|line| self.encoder.wrap_text(line, 20.0, max_width))
+            .collect();
+        // Leave room below the body for the buttons, the scroll position line and the
+        // footer.
+        let buttons_height = if overlay.buttons.is_empty() {
+            0.0
+        } else {
+            52.0
+        };
+        let reserved = 16.0 + buttons_height + 22.0 * (footer.len() + 1) as f32;
+        let page = (((height - reserved - cursor_y) / 26.0).floor() as usize).max(1);
+        let max = body.len().saturating_sub(page);
+        let scroll = overlay.scroll.min(max);
+        self.overlay_scroll = OverlayScroll { max, page };
+
         let body_color = Color {
             r: 0.9,
             g: 0.9,
             b: 0.9,
             a: 1.0,
         };
-        for line in &overlay.body {
-            self.draw_text(line, [width * 0.1, cursor_y], 20.0, body_color);
+        for line in body.iter().skip(scroll).take(page) {
+            self.encoder.draw_text(
+                line,
+                Vec2::new(left, cursor_y),
+                20.0,
+                body_color,
+                FontFamily::Sans,
+                TextFlow::default(),
+            );
             cursor_y += 26.0;
         }
 
         cursor_y += 16.0;
+        if !overlay.buttons.is_empty() {
+            self.draw_overlay_buttons(&overlay.buttons, Vec2::new(left, cursor_y));
+            cursor_y += buttons_height;
+        }
         let footer_color = Color {
             r: 0.7,
             g: 0.7,
             b: 0.7,
             a: 1.0,
         };
-        self.draw_text(&overlay.footer, [width * 0.1, cursor_y], 18.0, footer_color);
+        if max > 0 {
+            let position = format!(
+                "Lines {}-{} of {}",
+                scroll + 1,
+                (scroll + page).min(body.len()),
+                body.len()
+            );
+            self.encoder.draw_text(
+                &position,
+                Vec2::new(left, cursor_y),
+                18.0,
+                footer_color,
+                FontFamily::Sans,
+                TextFlow::default(),
+            );
+            cursor_y += 22.0;
+        }
+        for line in &footer {
+            self.encoder.draw_text(
+                line,
+                Vec2::new(left, cursor_y),
+                18.0,
+                footer_color,
+                FontFamily::Sans,
+                TextFlow::default(),
+            );
+            cursor_y += 22.0;
+        }
+    }
+
+    /// A row of buttons from `origin`; the focused one gets a bright outline.
+    fn draw_overlay_buttons(&mut self, buttons: &[OverlayButton], origin: Vec2) {
+        const HEIGHT: f32 = 36.0;
+        const PADDING: f32 = 16.0;
+        const BORDER: f32 = 3.0;
+        let focus_color = Color {
+            r: 1.0,
+            g: 0.78,
+            b: 0.2,
+            a: 1.0,
+        };
+        let fill_color = Color {
+            r: 0.25,
+            g: 0.25,
+            b: 0.25,
+            a: 1.0,
+        };
+        let label_color = Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        };
+        let mut x = origin.x;
+        for button in buttons {
+            let width = self.encoder.text_width(&button.label, 18.0) + PADDING * 2.0;
+            if button.focused {
+                self.encoder.fill_rect(
+                    Vec2::new(x - BORDER, origin.y - BORDER),
+                    Vec2::new(width + BORDER * 2.0, HEIGHT + BORDER * 2.0),
+                    focus_color,
+                );
+            }
+            self.encoder
+                .fill_rect(Vec2::new(x, origin.y), Vec2::new(width, HEIGHT), fill_color);
+            self.encoder.draw_text(
+                &button.label,
+                Vec2::new(x + PADDING, origin.y + 24.0),
+                18.0,
+                label_color,
+                FontFamily::Sans,
+                TextFlow::default(),
+            );
+            x += width + PADDING;
+        }
+    }
+}
+
+impl Drop for GraphicsState {
+    /// Save pipelines compiled since warming up, such as guest shaders.
+    fn drop(&mut self) {
+        self.save_pipeline_cache();
+    }
+}
+
+/// The Vello [`CanvasBackend`]: turns draw commands into a Vello scene. This is the CPU
+/// half of rendering: it needs no GPU, so it can be exercised headlessly (see the
+/// `frame_encode` fuzz target).
+pub struct SceneEncoder {
+    scene: Scene,
+    fonts: FontAssets,
+    scale_factor: f32,
+    filtered_images: FilterCache,
+    forced_colors: Option<ForcedColors>,
+    /// Logical area the scene is shown in; commands entirely outside it are skipped.
+    /// `None` encodes everything.
+    viewport: Option<geometry::Rect>,
+    /// Commands skipped for lying outside the viewport since the last
+    /// [`SceneEncoder::take_culled`].
+    culled: usize,
+    /// Logical area each open clip leaves visible, within the clips around it; innermost
+    /// last.
+    clips: Vec<geometry::Rect>,
+}
+
+impl SceneEncoder {
+    pub fn new(scale_factor: f32) -> Self {
+        Self::with_fonts(scale_factor, FontAssets::new())
+    }
+
+    pub(crate) fn with_fonts(scale_factor: f32, fonts: FontAssets) -> Self {
+        Self {
+            scene: Scene::new(),
+            fonts,
+            scale_factor,
+            filtered_images: FilterCache::default(),
+            forced_colors: None,
+            viewport: None,
+            culled: 0,
+            clips: Vec::new(),
+        }
+    }
+
+    pub fn set_viewport(&mut self, viewport: Option<geometry::Rect>) {
+        self.viewport = viewport;
+    }
+
+    /// How many commands were culled since the last call.
+    pub fn take_culled(&mut self) -> usize {
+        std::mem::take(&mut self.culled)
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    pub fn scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    pub fn reset(&mut self) {
+        self.scene.reset();
+        self.clips.clear();
+        self.filtered_images.trim();
+    }
+
+    /// Device pixels covered by a logical rectangle; see [`geometry::Rect::snap_to_pixels`].
+    fn physical_rect(&self, origin: Vec2, size: Vec2) -> Rect {
+        let snapped = geometry::Rect::new(origin.x, origin.y, size.x, size.y)
+            .snap_to_pixels(self.scale_factor);
+        Rect::new(
+            snapped.x as f64,
+            snapped.y as f64,
+            (snapped.x + snapped.width) as f64,
+            (snapped.y + snapped.height) as f64,
+        )
+    }
+
+    /// Maps a `width` x `height` box onto the logical rectangle at `origin`, or `None` when
+    /// the result would not be finite.
+    fn fit(&self, origin: Vec2, size: Vec2, width: f32, height: f32) -> Option<Affine> {
+        let scale_x = (size.x * self.scale_factor / width) as f64;
+        let scale_y = (size.y * self.scale_factor / height) as f64;
+        let x0 = (origin.x * self.scale_factor) as f64;
+        let y0 = (origin.y * self.scale_factor) as f64;
+        (scale_x.is_finite() && scale_y.is_finite() && x0.is_finite() && y0.is_finite())
+            .then(|| Affine::translate((x0, y0)).pre_scale_non_uniform(scale_x, scale_y))
+    }
+
+    /// Fill the rectangle with `image` scaled so one copy covers `image_size` logical
+    /// pixels from `origin`. Images drawn larger than their pixel size use
+    /// nearest-neighbour sampling so pixel art stays sharp.
+    fn fill_with_image(
+        &mut self,
+        origin: Vec2,
+        size: Vec2,
+        image: &Image,
+        image_size: Vec2,
+        (x_extend, y_extend): (Extend, Extend),
+        style: &ImageStyle,
+    ) {
+        let scale_x = (image_size.x * self.scale_factor) as f64 / image.width() as f64;
+        let scale_y = (image_size.y * self.scale_factor) as f64 / image.height() as f64;
+        if !(scale_x.is_finite() && scale_y.is_finite() && scale_x > 0.0 && scale_y > 0.0) {
+            return;
+        }
+        let quality = if scale_x > 1.0 || scale_y > 1.0 {
+            ImageQuality::Low
+        } else {
+            ImageQuality::Medium
+        };
+        let image = self.filtered_images.get(image, style);
+        let brush = ImageBrush::new(image.to_peniko())
+            .with_x_extend(x_extend)
+            .with_y_extend(y_extend)
+            .with_quality(quality)
+            .with_alpha(style.opacity);
+        let x0 = (origin.x * self.scale_factor) as f64;
+        let y0 = (origin.y * self.scale_factor) as f64;
+        let rect = self.physical_rect(origin, size);
+        self.scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            &brush,
+            Some(Affine::translate((x0, y0)).pre_scale_non_uniform(scale_x, scale_y)),
+            &rect,
+        );
+    }
+
+    /// Break `text` into lines no wider than `max_width` logical pixels, at spaces where
+    /// possible and mid-word for words that do not fit on a line of their own.
+    pub fn wrap_text(&self, text: &str, size: f32, max_width: f32) -> Vec<String> {
+        let advance = self.advance(size);
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        let mut line_width = 0.0;
+        for word in text.split(' ') {
+            let word_width: f32 = word.chars().map(&advance).sum();
+            let space = if line.is_empty() { 0.0 } else { advance(' ') };
+            if !line.is_empty() && line_width + space + word_width > max_width {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0.0;
+            } else if !line.is_empty() {
+                line.push(' ');
+                line_width += space;
+            }
+            for ch in word.chars() {
+                let width = advance(ch);
+                if !line.is_empty() && line_width + width > max_width {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0.0;
+                }
+                line.push(ch);
+                line_width += width;
+            }
+        }
+        lines.push(line);
+        lines
+    }
+
+    /// Width of `text` on one line in the sans font, in logical pixels.
+    pub fn text_width(&self, text: &str, size: f32) -> f32 {
+        text.chars().map(self.advance(size)).sum()
+    }
+
+    /// Horizontal advance of each character in the sans font at `size`.
+    fn advance(&self, size: f32) -> impl Fn(char) -> f32 + '_ {
+        advance(self.fonts.face(FontFamily::Sans), size)
+    }
+
+    /// Logical area `command` may draw in, for culling, or `None` when it must always be
+    /// encoded. Text is laid out to find its extent, padded by a font size on every side
+    /// for ascenders, descenders and overhangs, then rotated with it.
+    fn extent(&self, command: &DrawCommand) -> Option<geometry::Rect> {
+        match command {
+            DrawCommand::DrawText {
+                text,
+                origin,
+                size,
+                font,
+                flow,
+                ..
+            } => {
+                let advance = advance(self.fonts.face(*font), *size);
+                let (low, high) = bounding(
+                    place_glyphs(text, *size, *flow, &advance)
+                        .into_iter()
+                        .flat_map(|(ch, at)| [at, at + Vec2::new(advance(ch), 0.0)]),
+                )?;
+                let pad = Vec2::new(*size, *size);
+                let (low, high) = (low - pad, high + pad);
+                let (sin, cos) = flow.rotation.sin_cos();
+                let corners = [
+                    low,
+                    Vec2::new(high.x, low.y),
+                    Vec2::new(low.x, high.y),
+                    high,
+                ]
+                .map(|at| *origin + Vec2::new(at.x * cos - at.y * sin, at.x * sin + at.y * cos));
+                bounding(corners.into_iter()).map(bounds_of)
+            }
+            DrawCommand::DrawParticles { particles } => {
+                let reach = |sprite: &ParticleSprite| Vec2::new(sprite.size, sprite.size) * 0.5;
+                let low = bounding(
+                    particles
+                        .iter()
+                        .map(|sprite| sprite.position - reach(sprite)),
+                );
+                let high = bounding(
+                    particles
+                        .iter()
+                        .map(|sprite| sprite.position + reach(sprite)),
+                );
+                Some(bounds_of((low?.0, high?.1)))
+            }
+            DrawCommand::PushShaderLayer { .. }
+            | DrawCommand::PopShaderLayer
+            | DrawCommand::PushClip { .. }
+            | DrawCommand::PopClip => None,
+            _ => {
+                let bounds = command.bounds()?;
+                let origin = bounds.origin();
+                let corner = origin + Vec2::new(bounds.width, bounds.height);
+                bounding([origin, corner].into_iter()).map(bounds_of)
+            }
+        }
+    }
+
+    /// Encode `commands`, skipping those outside the viewport and open clip, and leave
+    /// their clips open.
+    fn encode_unclosed(&mut self, commands: &[DrawCommand]) {
+        for command in commands {
+            let visible = match (self.viewport, self.clips.last()) {
+                (Some(viewport), Some(clip)) => Some(viewport.intersection(clip)),
+                (viewport, clip) => viewport.or(clip.copied()),
+            };
+            let outside = visible
+                .zip(self.extent(command))
+                .is_some_and(|(visible, extent)| !visible.intersects(&extent));
+            if outside {
+                self.culled += 1;
+            } else {
+                self.encode_command(command);
+            }
+        }
+    }
+
+    /// Open clips left open by an earlier scene's [`encode_unclosed`](Self::encode_unclosed),
+    /// outermost first.
+    fn reopen_clips(&mut self, clips: &[geometry::Rect]) {
+        for area in clips {
+            self.open_clip(*area);
+        }
+    }
+
+    /// Clip to `area`, which already lies within the clips around it.
+    fn open_clip(&mut self, area: geometry::Rect) {
+        let rect = self.physical_rect(area.origin(), Vec2::new(area.width, area.height));
+        self.scene.push_clip_layer(Affine::IDENTITY, &rect);
+        self.clips.push(area);
+    }
+}
+
+/// The smallest and largest coordinates among `points`, or `None` when there are none or
+/// one is not finite.
+fn bounding(mut points: impl Iterator<Item = Vec2>) -> Option<(Vec2, Vec2)> {
+    points.try_fold(None, |bounds: Option<(Vec2, Vec2)>, at| {
+        if !at.is_finite() {
+            return None;
+        }
+        Some(Some(match bounds {
+            Some((low, high)) => (
+                Vec2::new(low.x.min(at.x), low.y.min(at.y)),
+                Vec2::new(high.x.max(at.x), high.y.max(at.y)),
+            ),
+            None => (at, at),
+        }))
+    })?
+}
+
+fn bounds_of((low, high): (Vec2, Vec2)) -> geometry::Rect {
+    geometry::Rect::new(low.x, low.y, high.x - low.x, high.y - low.y)
+}
+
+impl CanvasBackend for SceneEncoder {
+    fn fill_rect(&mut self, origin: Vec2, size: Vec2, color: Color) {
+        let color = match &self.forced_colors {
+            Some(palette) => palette.fill(color, size),
+            None => color,
+        };
+        let rect = self.physical_rect(origin, size);
+        self.scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            Brush::Solid(color.to_peniko()),
+            None,
+            &rect,
+        );
+    }
+
+    fn draw_text(
+        &mut self,
+        text: &str,
+        origin: Vec2,
+        size: f32,
+        color: Color,
+        font: FontFamily,
+        flow: TextFlow,
+    ) {
+        if text.is_empty() {
+            return;
+        }
+        let physical_origin = origin * self.scale_factor;
+        let font_size = size * self.scale_factor;
+        let color = match &self.forced_colors {
+            Some(palette) => palette.text(color),
+            None => color,
+        };
+        let brush = Brush::Solid(color.to_peniko());
+        let transform = Affine::translate((physical_origin.x as f64, physical_origin.y as f64))
+            * Affine::rotate(flow.rotation as f64);
+        let Some(face) = self.fonts.face(font) else {
+            let stroke = Stroke::new((font_size * PLACEHOLDER_STROKE).max(1.0) as f64);
+            for (offset, size) in placeholder_boxes(text, font_size, flow) {
+                let rect = Rect::from_origin_size(
+                    (offset.x as f64, offset.y as f64),
+                    (size.x as f64, size.y as f64),
+                );
+                self.scene.stroke(&stroke, transform, &brush, None, &rect);
+            }
+            return;
+        };
+        let glyphs = layout_text(&face.font_arc, text, font_size, flow);
+        if glyphs.is_empty() {
+            return;
+        }
+        self.scene
+            .draw_glyphs(&face.font_data)
+            .font_size(font_size)
+            .brush(&brush)
+            .transform(transform)
+            .draw(Fill::NonZero, glyphs.into_iter());
+    }
+
+    fn draw_image(&mut self, image: &Image, origin: Vec2, size: Vec2, style: &ImageStyle) {
+        self.fill_with_image(origin, size, image, size, (Extend::Pad, Extend::Pad), style);
+    }
+
+    fn draw_pattern(
+        &mut self,
+        origin: Vec2,
+        size: Vec2,
+        image: &Image,
+        tile_size: Vec2,
+        repeat: PatternRepeat,
+        style: &ImageStyle,
+    ) {
+        let (extend, height) = match repeat {
+            PatternRepeat::Repeat => ((Extend::Repeat, Extend::Repeat), size.y),
+            PatternRepeat::RepeatX => ((Extend::Repeat, Extend::Pad), size.y.min(tile_size.y)),
+            PatternRepeat::Mirror => ((Extend::Reflect, Extend::Reflect), size.y),
+        };
+        let size = Vec2 {
+            x: size.x,
+            y: height,
+        };
+        self.fill_with_image(origin, size, image, tile_size, extend, style);
+    }
+
+    fn draw_picture(&mut self, picture: &Picture, origin: Vec2, size: Vec2) {
+        if let Some(transform) = self.fit(origin, size, picture.width(), picture.height()) {
+            self.scene.append(picture.scene(), Some(transform));
+        }
+    }
+
+    fn draw_animation(&mut self, animation: &Animation, time_ms: f64, origin: Vec2, size: Vec2) {
+        if let Some(transform) = self.fit(origin, size, animation.width(), animation.height()) {
+            animation.render(&mut self.scene, time_ms, transform);
+        }
+    }
+
+    fn encode_commands(&mut self, commands: &[DrawCommand]) {
+        self.encode_unclosed(commands);
+        self.close_clips();
+    }
+
+    fn push_clip(&mut self, origin: Vec2, size: Vec2) {
+        let area = bounding([origin, origin + size].into_iter())
+            .map(bounds_of)
+            .unwrap_or_default();
+        let area = match self.clips.last() {
+            Some(outer) => outer.intersection(&area),
+            None => area,
+        };
+        self.open_clip(area);
+    }
+
+    fn pop_clip(&mut self) {
+        if self.clips.pop().is_some() {
+            self.scene.pop_layer();
+        }
+    }
+
+    fn close_clips(&mut self) {
+        while !self.clips.is_empty() {
+            self.pop_clip();
+        }
+    }
+
+    fn draw_particles(&mut self, particles: &[ParticleSprite]) {
+        let scale = self.scale_factor as f64;
+        for particle in particles {
+            let radius = particle.size as f64 * 0.5 * scale;
+            if !particle.position.is_finite() || !radius.is_finite() || radius <= 0.0 {
+                continue;
+            }
+            let center = (
+                particle.position.x as f64 * scale,
+                particle.position.y as f64 * scale,
+            );
+            let color = match &self.forced_colors {
+                Some(palette) => palette.text(particle.color),
+                None => particle.color,
+            };
+            self.scene.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                Brush::Solid(color.to_peniko()),
+                None,
+                &Circle::new(center, radius),
+            );
+        }
+    }
+}
+
+impl Color {
+    pub fn to_peniko(self) -> vello::peniko::Color {
+        vello::peniko::Color::new([self.r, self.g, self.b, self.a])
+    }
+
+    /// Parse a CSS colour such as `#1e1e2e`, `rgb(30 30 46)` or `navy`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let color = vello::peniko::color::parse_color(spec)
+            .map_err(|err| anyhow::anyhow!("invalid colour '{spec}': {err}"))?;
+        let [r, g, b, a] = color
+            .to_alpha_color::<vello::peniko::color::Srgb>()
+            .components;
+        Ok(Self { r, g, b, a })
     }
 }
 
 impl FontAssets {
-    fn new() -> Result<Self> {
-        let font_arc = ab_glyph::FontArc::try_from_slice(FONT_BYTES)
+    pub(crate) fn new() -> Self {
+        Self::from_bytes(SANS_FONT_BYTES, SANS_BOLD_FONT_BYTES, MONOSPACE_FONT_BYTES)
+    }
+
+    pub(crate) fn from_bytes(
+        sans: &'static [u8],
+        sans_bold: &'static [u8],
+        monospace: &'static [u8],
+    ) -> Self {
+        let load = |family: FontFamily, bytes| match FontFace::new(bytes) {
+            Ok(face) => Some(face),
+            Err(err) => {
+                tracing::warn!(
+                    ?family,
+                    "failed to load bundled font ({err:#}); its text falls back to another \
+                     font or placeholder boxes"
+                );
+                None
+            }
+        };
+        Self {
+            sans: load(FontFamily::Sans, sans),
+            sans_bold: load(FontFamily::SansBold, sans_bold),
+            monospace: load(FontFamily::Monospace, monospace),
+        }
+    }
+
+    /// `family`, or the first face that did load in its place.
+    pub(crate) fn face(&self, family: FontFamily) -> Option<&FontFace> {
+        let requested = match family {
+            FontFamily::Sans => &self.sans,
+            FontFamily::SansBold => &self.sans_bold,
+            FontFamily::Monospace => &self.monospace,
+        };
+        requested
+            .as_ref()
+            .or(self.sans.as_ref())
+            .or(self.sans_bold.as_ref())
+            .or(self.monospace.as_ref())
+    }
+}
+
+impl FontFace {
+    /// Height of the font's tallest glyphs above the baseline at `font_size`.
+    pub(crate) fn ascent(&self, font_size: f32) -> f32 {
+        use ab_glyph::{Font, ScaleFont};
+
+        self.font_arc.as_scaled(font_size).ascent()
+    }
+
+    fn new(bytes: &'static [u8]) -> Result<Self> {
+        let font_arc = ab_glyph::FontArc::try_from_slice(bytes)
             .context("embedded font corrupted or unsupported")?;
-        let blob: vello::peniko::Blob<u8> = FONT_BYTES.to_vec().into();
+        // Vello reads the font with skrifa; a font only ab_glyph accepts would draw nothing.
+        skrifa::FontRef::new(bytes).context("embedded font unreadable by the renderer")?;
+        let blob: vello::peniko::Blob<u8> = bytes.to_vec().into();
         let font_data = vello::peniko::FontData::new(blob, 0);
         Ok(Self {
             font_data,
@@ -299,27 +1587,380 @@ impl FontAssets {
     }
 }
 
-fn layout_text(font: &ab_glyph::FontArc, text: &str, font_size: f32) -> Vec<Glyph> {
+/// Distance between baselines, as a fraction of the font size.
+pub(crate) const LINE_HEIGHT: f32 = 1.2;
+/// Baseline of an upright character in vertical text, as a fraction of the font size below
+/// the top of its cell.
+const VERTICAL_BASELINE: f32 = 0.88;
+/// Advance of a placeholder box, as a fraction of the font size.
+const PLACEHOLDER_ADVANCE: f32 = 0.6;
+/// Outline width of a placeholder box, as a fraction of the font size.
+pub(crate) const PLACEHOLDER_STROKE: f32 = 0.06;
+
+/// Boxes drawn for the characters of `text` when no font loaded, as offsets from the
+/// baseline origin and sizes, before `flow.rotation`. Whitespace advances without a box.
+pub(crate) fn placeholder_boxes(text: &str, font_size: f32, flow: TextFlow) -> Vec<(Vec2, Vec2)> {
+    place_glyphs(text, font_size, flow, advance(None, font_size))
+        .into_iter()
+        .filter(|(ch, _)| !ch.is_whitespace())
+        .map(|(_, baseline)| {
+            (
+                baseline + Vec2::new(font_size * 0.05, -font_size * 0.7),
+                Vec2::new(font_size * 0.5, font_size * 0.7),
+            )
+        })
+        .collect()
+}
+
+/// Horizontal advance of each character drawn in `face` at `font_size`, or of a
+/// placeholder box when no face loaded; what `layout_text` and `placeholder_boxes` step by.
+pub(crate) fn advance(face: Option<&FontFace>, font_size: f32) -> impl Fn(char) -> f32 + '_ {
+    use ab_glyph::{Font, ScaleFont};
+
+    let scaled = face.map(|face| face.font_arc.as_scaled(font_size));
+    move |ch| match &scaled {
+        Some(scaled) => scaled.h_advance(scaled.glyph_id(ch)),
+        None => font_size * PLACEHOLDER_ADVANCE,
+    }
+}
+
+pub(crate) fn layout_text(
+    font: &ab_glyph::FontArc,
+    text: &str,
+    font_size: f32,
+    flow: TextFlow,
+) -> Vec<Glyph> {
     use ab_glyph::{Font, ScaleFont};
 
-    let mut glyphs = Vec::with_capacity(text.len());
     let scaled = font.as_scaled(font_size);
-    let mut caret_x = 0.0f32;
-    let mut caret_y = 0.0f32;
-    let line_height = font_size * 1.2;
-    for ch in text.chars() {
-        if ch == '\n' {
-            caret_x = 0.0;
-            caret_y += line_height;
-            continue;
-        }
-        let glyph_id = scaled.glyph_id(ch);
-        glyphs.push(Glyph {
-            id: glyph_id.0 as u32,
-            x: caret_x,
-            y: caret_y,
+    place_glyphs(text, font_size, flow, |ch| {
+        scaled.h_advance(scaled.glyph_id(ch))
+    })
+    .into_iter()
+    .map(|(ch, baseline)| Glyph {
+        id: scaled.glyph_id(ch).0 as u32,
+        x: baseline.x,
+        y: baseline.y,
+    })
+    .collect()
+}
+
+/// Each character of `text` as drawn, with its baseline start relative to the origin
+/// before `flow.rotation`: lines from [`place_text`], or columns of upright characters in
+/// vertical text. Vertical columns are centred on their line, one `font_size` cell per
+/// character, and keep their logical order.
+pub(crate) fn place_glyphs(
+    text: &str,
+    font_size: f32,
+    flow: TextFlow,
+    advance: impl Fn(char) -> f32,
+) -> Vec<(char, Vec2)> {
+    match flow.mode {
+        WritingMode::HorizontalTb => {
+            let line_height = font_size * LINE_HEIGHT;
+            place_text(text, flow.direction, advance)
+                .chars
+                .into_iter()
+                .map(|placed| {
+                    (
+                        placed.ch,
+                        Vec2::new(placed.x, placed.line as f32 * line_height),
+                    )
+                })
+                .collect()
+        }
+        WritingMode::VerticalRl => text
+            .split('\n')
+            .enumerate()
+            .flat_map(|(column, line)| {
+                let centre = -(column as f32) * font_size * LINE_HEIGHT;
+                let advance = &advance;
+                line.chars().enumerate().map(move |(row, ch)| {
+                    let baseline = (row as f32 + VERTICAL_BASELINE) * font_size;
+                    (ch, Vec2::new(centre - advance(ch) / 2.0, baseline))
+                })
+            })
+            .collect(),
+    }
+}
+
+/// A character of `text` where `place_text` put it.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PlacedChar {
+    /// Position in `text`, in characters.
+    pub index: usize,
+    /// The character as drawn, with brackets mirrored in right-to-left runs.
+    pub ch: char,
+    pub line: u32,
+    /// Left edge, relative to the origin.
+    pub x: f32,
+    pub advance: f32,
+    pub rtl: bool,
+}
+
+pub(crate) struct TextPlacement {
+    pub chars: Vec<PlacedChar>,
+    /// Where each line ends in reading order: its right edge in left-to-right text, its
+    /// left edge in right-to-left text.
+    pub line_ends: Vec<f32>,
+}
+
+/// Place each character of `text`, breaking lines at `\n` and putting each line in
+/// visual order with [`bidi::visual_order`]. Left-to-right lines start at the origin;
+/// right-to-left lines end there, extending to the left.
+pub(crate) fn place_text(
+    text: &str,
+    direction: TextDirection,
+    advance: impl Fn(char) -> f32,
+) -> TextPlacement {
+    let mut placement = TextPlacement {
+        chars: Vec::with_capacity(text.len()),
+        line_ends: Vec::new(),
+    };
+    let mut first = 0;
+    for (line, logical) in text.split('\n').enumerate() {
+        let logical: Vec<char> = logical.chars().collect();
+        let visual = bidi::visual_order(&logical, direction);
+        let width: f32 = visual.iter().map(|visual| advance(visual.ch)).sum();
+        let mut x = match direction {
+            TextDirection::Ltr => 0.0,
+            TextDirection::Rtl => -width,
+        };
+        for visual in visual {
+            let advance = advance(visual.ch);
+            placement.chars.push(PlacedChar {
+                index: first + visual.index,
+                ch: visual.ch,
+                line: line as u32,
+                x,
+                advance,
+                rtl: visual.rtl,
+            });
+            x += advance;
+        }
+        placement.line_ends.push(match direction {
+            TextDirection::Ltr => width,
+            TextDirection::Rtl => -width,
         });
-        caret_x += scaled.h_advance(glyph_id);
+        first += logical.len() + 1;
+    }
+    placement
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FontAssets, SceneEncoder};
+    use crate::bidi::TextDirection;
+    use crate::canvas::CanvasBackend;
+    use crate::geometry::{Rect, Vec2};
+    use crate::host::{Color, DrawCommand, FontFamily, PatternRepeat, TextFlow, WritingMode};
+    use crate::images::{Image, ImageFilter, ImageStyle};
+    use crate::lottie::Animation;
+    use crate::particles::ParticleSprite;
+    use crate::svg::Picture;
+
+    #[test]
+    fn encodes_hostile_commands_without_panicking() {
+        let mut encoder = SceneEncoder::new(f32::NAN);
+        let nan = Vec2 {
+            x: f32::NAN,
+            y: f32::INFINITY,
+        };
+        encoder.encode_commands(&[
+            DrawCommand::FillRect {
+                origin: nan,
+                size: nan,
+                color: Color {
+                    r: f32::NAN,
+                    g: -1.0,
+                    b: 9.0,
+                    a: f32::INFINITY,
+                },
+            },
+            DrawCommand::DrawText {
+                text: "\u{0}\u{FFFF}\n🦀".repeat(1024),
+                origin: nan,
+                size: f32::MAX,
+                color: Color::default(),
+                font: FontFamily::Monospace,
+                flow: TextFlow {
+                    direction: TextDirection::Rtl,
+                    mode: WritingMode::VerticalRl,
+                    rotation: f32::INFINITY,
+                },
+            },
+            DrawCommand::FillPattern {
+                origin: nan,
+                size: Vec2 { x: 1e30, y: 1e30 },
+                image: Image::new(1, 1, vec![255; 4]).unwrap(),
+                tile_size: Vec2 { x: 1e-30, y: 0.0 },
+                repeat: PatternRepeat::Mirror,
+                style: ImageStyle {
+                    filter: ImageFilter::Sepia,
+                    opacity: f32::NAN,
+                    ..ImageStyle::default()
+                },
+            },
+            DrawCommand::DrawPicture {
+                picture: Picture::parse(br#"<svg><circle r="5"/></svg>"#).unwrap(),
+                origin: nan,
+                size: Vec2 {
+                    x: 0.0,
+                    y: f32::MAX,
+                },
+            },
+            DrawCommand::DrawAnimation {
+                animation: Animation::parse(
+                    br##"{"w":8,"h":8,"fr":30,"ip":0,"op":30,"layers":[{"ty":1,"sc":"#f00","sw":8,"sh":8}]}"##,
+                )
+                .unwrap(),
+                time_ms: -1e300,
+                origin: nan,
+                size: Vec2 { x: 1.0, y: 1.0 },
+            },
+            DrawCommand::DrawImage {
+                image: Image::new(1, 1, vec![255; 4]).unwrap(),
+                origin: nan,
+                size: Vec2 { x: -4.0, y: 4.0 },
+                style: ImageStyle::default(),
+            },
+            DrawCommand::DrawParticles {
+                particles: [nan, Vec2 { x: 1.0, y: 1.0 }]
+                    .map(|position| ParticleSprite {
+                        position,
+                        size: f32::INFINITY,
+                        color: Color::default(),
+                    })
+                    .into(),
+            },
+        ]);
+        encoder.reset();
+    }
+
+    #[test]
+    fn wraps_text_at_spaces_and_inside_long_words() {
+        let encoder = SceneEncoder::new(1.0);
+        let lines = encoder.wrap_text("alpha beta gamma", 20.0, 60.0);
+        assert!(lines.len() > 1);
+        assert_eq!(lines.join(" "), "alpha beta gamma");
+        assert_eq!(
+            encoder.wrap_text("alpha beta gamma", 20.0, f32::MAX),
+            ["alpha beta gamma"]
+        );
+
+        let long = "x".repeat(200);
+        let lines = encoder.wrap_text(&long, 20.0, 100.0);
+        assert!(lines.len() > 1);
+        assert_eq!(lines.concat(), long);
+
+        assert_eq!(encoder.wrap_text("", 20.0, 100.0), [""]);
+    }
+
+    #[test]
+    fn broken_fonts_fall_back_to_other_faces_then_boxes() {
+        let sans = super::SANS_FONT_BYTES;
+        let fonts = FontAssets::from_bytes(b"not a font", sans, b"");
+        assert!(fonts.face(FontFamily::Sans).is_some());
+        assert!(fonts.face(FontFamily::Monospace).is_some());
+
+        let mut encoder = SceneEncoder::with_fonts(1.0, FontAssets::from_bytes(b"", b"", b""));
+        assert_eq!(encoder.wrap_text("ab cd", 10.0, 14.0), ["ab", "cd"]);
+        encoder.draw_text(
+            "a b\nc",
+            Vec2::ZERO,
+            10.0,
+            Color::default(),
+            FontFamily::Sans,
+            TextFlow::default(),
+        );
+        assert!(!encoder.scene().encoding().is_empty());
+        assert_eq!(
+            super::placeholder_boxes("a b\nc", 10.0, TextFlow::default()).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn vertical_text_stacks_characters_in_columns_leftwards() {
+        let flow = TextFlow {
+            mode: WritingMode::VerticalRl,
+            ..TextFlow::default()
+        };
+        let placed = super::place_glyphs("日本\n語", 10.0, flow, |_| 10.0);
+        let expected = [
+            ('日', Vec2::new(-5.0, 8.8)),
+            ('本', Vec2::new(-5.0, 18.8)),
+            ('語', Vec2::new(-17.0, 8.8)),
+        ];
+        assert_eq!(placed.len(), expected.len());
+        for ((ch, baseline), (expected_ch, expected_baseline)) in placed.into_iter().zip(expected) {
+            assert_eq!(ch, expected_ch);
+            let error = baseline - expected_baseline;
+            assert!(error.x.abs() < 1e-4 && error.y.abs() < 1e-4, "{baseline:?}");
+        }
+    }
+
+    #[test]
+    fn monospace_glyphs_share_one_advance() {
+        use ab_glyph::{Font, ScaleFont};
+
+        let encoder = SceneEncoder::new(1.0);
+        let advance = |family: FontFamily, ch: char| {
+            let font = encoder.fonts.face(family).unwrap().font_arc.as_scaled(20.0);
+            font.h_advance(font.glyph_id(ch))
+        };
+        assert_eq!(
+            advance(FontFamily::Monospace, 'i'),
+            advance(FontFamily::Monospace, 'W')
+        );
+        assert!(advance(FontFamily::Sans, 'i') < advance(FontFamily::Sans, 'W'));
+        assert!(advance(FontFamily::Sans, 'W') < advance(FontFamily::SansBold, 'W'));
+    }
+
+    #[test]
+    fn culls_commands_entirely_outside_the_viewport() {
+        let mut encoder = SceneEncoder::new(2.0);
+        encoder.set_viewport(Some(Rect::new(0.0, 0.0, 100.0, 100.0)));
+        let rect = |x, y, width, height| DrawCommand::FillRect {
+            origin: Vec2::new(x, y),
+            size: Vec2::new(width, height),
+            color: Color::default(),
+        };
+        let text = |rotation| DrawCommand::DrawText {
+            text: "Hello world".into(),
+            origin: Vec2::new(150.0, 50.0),
+            size: 16.0,
+            color: Color::default(),
+            font: FontFamily::Sans,
+            flow: TextFlow {
+                rotation,
+                ..TextFlow::default()
+            },
+        };
+        let particles = |x| DrawCommand::DrawParticles {
+            particles: vec![ParticleSprite {
+                position: Vec2::new(x, 50.0),
+                size: 10.0,
+                color: Color::default(),
+            }]
+            .into(),
+        };
+        encoder.encode_commands(&[
+            rect(10.0, 10.0, 5.0, 5.0),
+            rect(200.0, 200.0, 5.0, 5.0),
+            // A negative width reaches back into the window.
+            rect(110.0, 50.0, -20.0, 5.0),
+            text(0.0),
+            // Turned half a circle about its origin, the text runs back into the window.
+            text(std::f32::consts::PI),
+            particles(104.0),
+            particles(106.0),
+        ]);
+        assert_eq!(encoder.take_culled(), 3);
+        assert_eq!(encoder.take_culled(), 0);
+
+        encoder.set_viewport(None);
+        encoder.encode_commands(&[rect(200.0, 200.0, 5.0, 5.0)]);
+        assert_eq!(encoder.take_culled(), 0);
     }
-    glyphs
 }
@@ -0,0 +1,152 @@
+//! Tactile feedback behind `haptic-feedback`, played as a short rumble on every connected
+//! gamepad that has rumble motors.
+//!
+//! Gamepads are driven through evdev force feedback when the host is built with
+//! `--features haptics` on Linux; other builds have nothing to play on.
+
+use std::time::{Duration, Instant};
+
+use crate::component::vello::canvas::host::HapticKind as WitHapticKind;
+
+#[cfg(all(feature = "haptics", target_os = "linux"))]
+mod evdev;
+#[cfg(all(feature = "haptics", target_os = "linux"))]
+use evdev as backend;
+
+/// Whether this build can play haptic feedback at all.
+pub const SUPPORTED: bool = cfg!(all(feature = "haptics", target_os = "linux"));
+
+/// How long to wait before looking for gamepads again after finding none.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What the guest is confirming, from a light tick to an error buzz.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HapticKind {
+    Light,
+    Medium,
+    Heavy,
+    Success,
+    Error,
+}
+
+/// One rumble: motor strengths out of `u16::MAX` and how long they run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pulse {
+    /// The low-frequency (heavy) motor.
+    pub strong: u16,
+    /// The high-frequency (light) motor.
+    pub weak: u16,
+    pub duration: Duration,
+}
+
+impl HapticKind {
+    pub fn from_wit(kind: WitHapticKind) -> Self {
+        match kind {
+            WitHapticKind::Light => HapticKind::Light,
+            WitHapticKind::Medium => HapticKind::Medium,
+            WitHapticKind::Heavy => HapticKind::Heavy,
+            WitHapticKind::Success => HapticKind::Success,
+            WitHapticKind::Error => HapticKind::Error,
+        }
+    }
+
+    pub fn pulse(self) -> Pulse {
+        let (strong, weak, ms) = match self {
+            HapticKind::Light => (0, 0x6000, 25),
+            HapticKind::Medium => (0x4000, 0xa000, 40),
+            HapticKind::Heavy => (0xffff, 0xffff, 70),
+            HapticKind::Success => (0x2000, 0xc000, 60),
+            HapticKind::Error => (0xc000, 0x4000, 180),
+        };
+        Pulse {
+            strong,
+            weak,
+            duration: Duration::from_millis(ms),
+        }
+    }
+}
+
+/// The rumble devices found so far.
+#[derive(Default)]
+pub struct Haptics {
+    devices: Vec<backend::Rumbler>,
+    scanned_at: Option<Instant>,
+}
+
+impl std::fmt::Debug for Haptics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Haptics")
+            .field("devices", &self.devices.len())
+            .finish()
+    }
+}
+
+impl Haptics {
+    /// Play `kind` on every rumble device; returns whether any of them played it.
+    pub fn play(&mut self, kind: HapticKind) -> bool {
+        if !SUPPORTED {
+            return false;
+        }
+        let now = Instant::now();
+        let rescan_due = self
+            .scanned_at
+            .is_none_or(|at| now.duration_since(at) >= RESCAN_INTERVAL);
+        if self.devices.is_empty() && rescan_due {
+            self.devices = backend::scan();
+            self.scanned_at = Some(now);
+            if !self.devices.is_empty() {
+                tracing::info!(count = self.devices.len(), "found rumble devices");
+            }
+        }
+        let pulse = kind.pulse();
+        // A device that fails was most likely unplugged; forget it.
+        self.devices.retain_mut(|device| match device.play(pulse) {
+            Ok(()) => true,
+            Err(err) => {
+                tracing::debug!(error = %format!("{err:#}"), "dropping rumble device");
+                false
+            }
+        });
+        !self.devices.is_empty()
+    }
+}
+
+/// Stand-in for builds without a haptics backend: no devices to find.
+#[cfg(not(all(feature = "haptics", target_os = "linux")))]
+mod backend {
+    use anyhow::{bail, Result};
+
+    use super::Pulse;
+
+    pub fn scan() -> Vec<Rumbler> {
+        Vec::new()
+    }
+
+    pub struct Rumbler;
+
+    impl Rumbler {
+        pub fn play(&mut self, _pulse: Pulse) -> Result<()> {
+            bail!("this host was built without haptics support")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HapticKind, Haptics, SUPPORTED};
+
+    #[test]
+    fn stronger_kinds_rumble_harder() {
+        let light = HapticKind::Light.pulse();
+        let heavy = HapticKind::Heavy.pulse();
+        assert!(light.strong < heavy.strong && light.weak < heavy.weak);
+        assert!(HapticKind::Error.pulse().duration > HapticKind::Success.pulse().duration);
+    }
+
+    #[test]
+    fn reports_nothing_played_without_support() {
+        if !SUPPORTED {
+            assert!(!Haptics::default().play(HapticKind::Medium));
+        }
+    }
+}
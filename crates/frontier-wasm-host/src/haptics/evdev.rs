@@ -0,0 +1,89 @@
+//! evdev force feedback: one `FF_RUMBLE` effect uploaded per gamepad and replayed for each
+//! pulse. Struct layouts follow `linux/input.h` on 64-bit targets.
+
+use std::mem;
+
+use anyhow::{Context, Result};
+
+use super::Pulse;
+use crate::evdev::{self, Device, EV_FF};
+
+const FF_RUMBLE: u16 = 0x50;
+
+#[repr(C)]
+struct Trigger {
+    button: u16,
+    interval: u16,
+}
+
+#[repr(C)]
+struct Replay {
+    length: u16,
+    delay: u16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Rumble {
+    strong_magnitude: u16,
+    weak_magnitude: u16,
+}
+
+/// `struct ff_effect`; the union is 32 bytes and pointer aligned.
+#[repr(C)]
+struct Effect {
+    kind: u16,
+    id: i16,
+    direction: u16,
+    trigger: Trigger,
+    replay: Replay,
+    u: EffectUnion,
+}
+
+#[repr(C)]
+union EffectUnion {
+    rumble: Rumble,
+    raw: [u64; 4],
+}
+
+const EVIOCSFF: u64 = evdev::ioc(evdev::WRITE, 0x80, mem::size_of::<Effect>());
+
+const _: () = assert!(mem::size_of::<Effect>() == 48);
+
+/// A gamepad with rumble motors and the effect slot uploaded to it.
+pub struct Rumbler {
+    device: Device,
+    /// Slot returned by the first `EVIOCSFF`; -1 until then. Closing the device frees it.
+    effect: i16,
+}
+
+impl Rumbler {
+    /// Upload `pulse` into this device's effect slot and start it.
+    pub fn play(&mut self, pulse: Pulse) -> Result<()> {
+        // SAFETY: `Effect` is plain old data; all-zero is a valid value.
+        let mut effect: Effect = unsafe { mem::zeroed() };
+        effect.kind = FF_RUMBLE;
+        effect.id = self.effect;
+        effect.replay.length = pulse.duration.as_millis().min(u16::MAX as u128) as u16;
+        effect.u.rumble = Rumble {
+            strong_magnitude: pulse.strong,
+            weak_magnitude: pulse.weak,
+        };
+        self.device
+            .ioctl(EVIOCSFF, &mut effect)
+            .context("EVIOCSFF failed")?;
+        self.effect = effect.id;
+        self.device
+            .write_event(EV_FF, self.effect as u16, 1)
+            .context("failed to start rumble")
+    }
+}
+
+/// Input devices we may open that support `FF_RUMBLE`.
+pub fn scan() -> Vec<Rumbler> {
+    evdev::open_all()
+        .into_iter()
+        .filter(|device| device.supports(EV_FF, FF_RUMBLE))
+        .map(|device| Rumbler { device, effect: -1 })
+        .collect()
+}
@@ -1,10 +1,74 @@
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::component::vello::canvas::host::{Host as GuestHost, LogLevel};
+use chrono::{DateTime, Local};
+use winit::dpi::PhysicalPosition;
+
+use crate::bidi::TextDirection;
+use crate::camera::{CameraAccess, CameraStatus, Cameras};
+use crate::capabilities::{Capability, DroppedCapabilities};
+use crate::charts::{self, BarStyle};
+use crate::component::vello::canvas::database::{
+    Host as DatabaseHost, SqlValue as WitSqlValue, StatementId,
+};
+use crate::component::vello::canvas::host::{
+    AnimatedImageId, AnimatedImageMetadata as WitAnimatedImageMetadata, AnimationId,
+    AnimationMetadata as WitAnimationMetadata, BarStyle as WitBarStyle,
+    CameraFrameMetadata as WitCameraFrameMetadata, CameraId, CameraState as WitCameraState,
+    DatetimeStyle as WitDatetimeStyle, DisplayInfo as WitDisplayInfo, Easing as WitEasing,
+    EmitterConfig as WitEmitterConfig, EmitterId, ExportFormat as WitExportFormat,
+    FontFamily as WitFontFamily, GridCell as WitGridCell, HapticKind as WitHapticKind,
+    Heatmap as WitHeatmap, Host as GuestHost, HostEnvironment as WitHostEnvironment, ImageId,
+    ImageStyle as WitImageStyle, LogLevel, PatternRepeat as WitPatternRepeat, PictureId,
+    PixelBufferId, PowerStatus as WitPowerStatus, Preferences as WitPreferences, Rect as WitRect,
+    ShaderId, StorageScope as WitStorageScope, StyledSpan as WitStyledSpan, TaskId,
+    TextDirection as WitTextDirection, WindowLevel as WitWindowLevel,
+    WindowPosition as WitWindowPosition, WorkerId, WorkerPermissions as WitWorkerPermissions,
+    WorkerSource as WitWorkerSource, WritingMode as WitWritingMode,
+};
 use crate::component::vello::canvas::math::{Color as WitColor, Vec2 as WitVec2};
+use crate::component::worker::vello::canvas::worker::Host as WorkerHost;
+use crate::database::{Database, SqlValue};
+use crate::decode::AnimatedImage;
+use crate::effects::{Shader, PARAM_COUNT};
+use crate::export::ExportFormat;
+use crate::geometry::{Rect, Size, Vec2};
+use crate::gpu::GpuInfo;
+use crate::haptics::{self, HapticKind, Haptics};
+use crate::images::{AssetStore, Image, ImageStyle};
+use crate::inertia::KineticScrollers;
+use crate::limits::{ResourceLimits, UploadMeter};
+use crate::locale::{DateTimeStyle, Locale};
+use crate::logging::{GUEST_TARGET, PERF_TARGET};
+use crate::lottie::Animation;
+use crate::markdown;
+use crate::memory::EvictedAssets;
+use crate::model::LogicalSize;
+use crate::navigation;
+use crate::particles::{Emitter, EmitterConfig, ParticleBatch, ParticleSprite};
+use crate::pixels::PixelBuffer;
+use crate::power::{self, PowerState};
+use crate::preferences::Preferences;
+use crate::qr::QrCode;
+use crate::runtime::ComponentSource;
+use crate::sandbox::HostInterfaces;
+use crate::scroll::{ScrollEvent, ScrollInput, ScrollRegions};
+use crate::stats::{HostCallStats, PerfMeasure};
+use crate::stdio::GuestStream;
+use crate::storage::{Scope, Storage};
+use crate::svg::Picture;
+use crate::tasks::{TaskQueue, TaskReporter, TaskRequest};
+use crate::text::{layout_grid, Span, TextMetrics};
+use crate::timezone;
+use crate::tween::{Easing, Tweens};
+use crate::window::{clamp_opacity, DisplayInfo, WindowLevel, WindowRequest};
+use crate::workers::{WorkerOutbox, WorkerRequest, Workers};
 
-#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -13,53 +77,292 @@ pub struct Color {
 }
 
 impl Color {
+    /// Channels are clamped to 0.0-1.0; NaN and infinities become 0.0.
     pub fn from_wit(color: WitColor) -> Self {
+        let channel = |value: f32| {
+            if value.is_finite() {
+                value.clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
         Self {
-            r: color.r,
-            g: color.g,
-            b: color.b,
-            a: color.a,
+            r: channel(color.r),
+            g: channel(color.g),
+            b: channel(color.b),
+            a: channel(color.a),
+        }
+    }
+}
+
+/// One of the typefaces bundled with the host.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum FontFamily {
+    #[default]
+    Sans,
+    SansBold,
+    Monospace,
+}
+
+impl FontFamily {
+    pub fn from_wit(font: WitFontFamily) -> Self {
+        match font {
+            WitFontFamily::Sans => FontFamily::Sans,
+            WitFontFamily::SansBold => FontFamily::SansBold,
+            WitFontFamily::Monospace => FontFamily::Monospace,
+        }
+    }
+}
+
+/// Whether text runs in lines across or in columns down.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum WritingMode {
+    #[default]
+    HorizontalTb,
+    /// Upright characters stacked top to bottom, each line a column to the left of the last.
+    VerticalRl,
+}
+
+impl WritingMode {
+    pub fn from_wit(mode: WitWritingMode) -> Self {
+        match mode {
+            WitWritingMode::HorizontalTb => WritingMode::HorizontalTb,
+            WitWritingMode::VerticalRl => WritingMode::VerticalRl,
         }
     }
+}
+
+/// Which way a text command reads and how it is turned.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextFlow {
+    /// Paragraph direction of horizontal lines; vertical columns keep their logical order.
+    pub direction: TextDirection,
+    pub mode: WritingMode,
+    /// Clockwise turn about the origin, in radians.
+    pub rotation: f32,
+}
 
-    pub fn to_peniko(self) -> vello::peniko::Color {
-        vello::peniko::Color::new([self.r, self.g, self.b, self.a])
+impl TextFlow {
+    pub fn horizontal(direction: TextDirection) -> Self {
+        Self {
+            direction,
+            ..Self::default()
+        }
     }
 }
 
-#[derive(Clone, Copy, Debug, Default)]
-pub struct Vec2 {
-    pub x: f32,
-    pub y: f32,
+/// How a pattern fill repeats its image beyond the first tile.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PatternRepeat {
+    Repeat,
+    /// One row of tiles along the top edge; the rest of the area stays untouched.
+    RepeatX,
+    /// Every other tile is flipped.
+    Mirror,
 }
 
-impl Vec2 {
-    pub fn from_wit(vec: WitVec2) -> Self {
-        Self { x: vec.x, y: vec.y }
+impl PatternRepeat {
+    pub fn from_wit(repeat: WitPatternRepeat) -> Self {
+        match repeat {
+            WitPatternRepeat::Repeat => PatternRepeat::Repeat,
+            WitPatternRepeat::RepeatX => PatternRepeat::RepeatX,
+            WitPatternRepeat::Mirror => PatternRepeat::Mirror,
+        }
     }
 }
 
+/// A drawing call recorded from the guest during `frame`.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone)]
 pub enum DrawCommand {
+    /// `fill-rect`: a solid rectangle with its top-left corner at `origin`.
     FillRect {
         origin: Vec2,
         size: Vec2,
         color: Color,
     },
+    /// `draw-text`: text in logical order with its first baseline at `origin`, starting
+    /// there in left-to-right paragraphs and ending there in right-to-left ones.
     DrawText {
         text: String,
         origin: Vec2,
+        /// Font size in logical pixels.
         size: f32,
         color: Color,
+        font: FontFamily,
+        flow: TextFlow,
+    },
+    /// `draw-picture`: an SVG picture scaled to the rectangle at `origin`.
+    DrawPicture {
+        picture: Picture,
+        origin: Vec2,
+        size: Vec2,
+    },
+    /// `draw-animation`: the Lottie frame at `time_ms` scaled to the rectangle at `origin`.
+    DrawAnimation {
+        animation: Animation,
+        time_ms: f64,
+        origin: Vec2,
+        size: Vec2,
+    },
+    /// `draw-image`: `image` stretched over the rectangle at `origin`.
+    DrawImage {
+        image: Image,
+        origin: Vec2,
+        size: Vec2,
+        style: ImageStyle,
     },
+    /// `fill-pattern`: `image` tiled across a rectangle, one tile per `tile_size` logical
+    /// pixels, starting at `origin`.
+    FillPattern {
+        origin: Vec2,
+        size: Vec2,
+        image: Image,
+        tile_size: Vec2,
+        repeat: PatternRepeat,
+        style: ImageStyle,
+    },
+    /// `push-shader-layer`: the commands up to the matching `PopShaderLayer` draw into an
+    /// offscreen layer that `shader` composites.
+    PushShaderLayer {
+        shader: Shader,
+        params: [f32; PARAM_COUNT],
+    },
+    /// `pop-shader-layer`: ends the innermost shader layer.
+    PopShaderLayer,
+    /// `draw-points`: circles worked out on the CPU and drawn by the particle renderer.
+    DrawParticles { particles: Arc<[ParticleSprite]> },
+    /// `push-scroll-region`: the commands up to the matching `PopClip` are clipped to the
+    /// rectangle at `origin`.
+    PushClip { origin: Vec2, size: Vec2 },
+    /// `pop-scroll-region`: ends the innermost clip.
+    PopClip,
+    /// `draw-emitter`: an emitter's live particles, simulated on the GPU when drawn.
+    DrawEmitter { particles: Arc<ParticleBatch> },
+}
+
+impl DrawCommand {
+    /// Area covered by a fill or image. Text has no known extent without shaping, so `None`.
+    pub fn bounds(&self) -> Option<Rect> {
+        match self {
+            DrawCommand::FillRect { origin, size, .. }
+            | DrawCommand::DrawImage { origin, size, .. }
+            | DrawCommand::DrawPicture { origin, size, .. }
+            | DrawCommand::DrawAnimation { origin, size, .. } => {
+                Some(Rect::from_origin_size(*origin, (*size).into()))
+            }
+            DrawCommand::FillPattern {
+                origin,
+                size,
+                tile_size,
+                repeat,
+                ..
+            } => {
+                let height = match repeat {
+                    PatternRepeat::RepeatX => size.y.min(tile_size.y),
+                    PatternRepeat::Repeat | PatternRepeat::Mirror => size.y,
+                };
+                Some(Rect::new(origin.x, origin.y, size.x, height))
+            }
+            DrawCommand::DrawText { .. }
+            | DrawCommand::PushShaderLayer { .. }
+            | DrawCommand::PopShaderLayer
+            | DrawCommand::DrawParticles { .. }
+            | DrawCommand::PushClip { .. }
+            | DrawCommand::PopClip
+            | DrawCommand::DrawEmitter { .. } => None,
+        }
+    }
+
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            DrawCommand::DrawText { text, .. } => Some(text),
+            DrawCommand::FillRect { .. }
+            | DrawCommand::DrawImage { .. }
+            | DrawCommand::DrawPicture { .. }
+            | DrawCommand::DrawAnimation { .. }
+            | DrawCommand::FillPattern { .. }
+            | DrawCommand::PushShaderLayer { .. }
+            | DrawCommand::PopShaderLayer
+            | DrawCommand::DrawParticles { .. }
+            | DrawCommand::PushClip { .. }
+            | DrawCommand::PopClip
+            | DrawCommand::DrawEmitter { .. } => None,
+        }
+    }
+
+    /// Move the command by `offset`, as content inside a scroll region is.
+    pub fn translate(&mut self, offset: Vec2) {
+        match self {
+            DrawCommand::FillRect { origin, .. }
+            | DrawCommand::DrawText { origin, .. }
+            | DrawCommand::DrawPicture { origin, .. }
+            | DrawCommand::DrawAnimation { origin, .. }
+            | DrawCommand::DrawImage { origin, .. }
+            | DrawCommand::FillPattern { origin, .. }
+            | DrawCommand::PushClip { origin, .. } => *origin = *origin + offset,
+            DrawCommand::DrawParticles { particles } => {
+                *particles = particles
+                    .iter()
+                    .map(|particle| ParticleSprite {
+                        position: particle.position + offset,
+                        ..*particle
+                    })
+                    .collect();
+            }
+            DrawCommand::DrawEmitter { particles } => {
+                Arc::make_mut(particles).translate(offset);
+            }
+            DrawCommand::PushShaderLayer { .. }
+            | DrawCommand::PopShaderLayer
+            | DrawCommand::PopClip => {}
+        }
+    }
 }
 
+/// Everything a guest drew during one `frame` call, in drawing order.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone)]
 pub struct FrameOutput {
+    /// Colour passed to `clear`, if the guest called it.
     pub clear_color: Option<Color>,
     pub commands: Vec<DrawCommand>,
 }
 
+impl FrameOutput {
+    pub fn command_count(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// The guest neither cleared nor drew anything.
+    pub fn is_empty(&self) -> bool {
+        self.clear_color.is_none() && self.commands.is_empty()
+    }
+
+    /// Text of every `draw-text` command, in drawing order.
+    pub fn texts(&self) -> impl Iterator<Item = &str> {
+        self.commands.iter().filter_map(DrawCommand::text)
+    }
+
+    /// Whether some `draw-text` command draws exactly `text`.
+    pub fn contains_text(&self, text: &str) -> bool {
+        self.texts().any(|drawn| drawn == text)
+    }
+
+    /// `fill-rect` commands that overlap `rect`.
+    pub fn rects_intersecting(&self, rect: Rect) -> impl Iterator<Item = &DrawCommand> {
+        self.commands.iter().filter(move |command| {
+            command
+                .bounds()
+                .is_some_and(|bounds| bounds.intersects(&rect))
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Phase {
     #[default]
@@ -68,11 +371,29 @@ pub enum Phase {
     Resize,
     Event,
     Frame,
+    /// Inside `run-task` on a task instance, which has no call timeout or host call quota.
+    Task,
+    /// Inside `on-message` on a worker instance.
+    Worker,
 }
 
 impl Phase {
+    /// Lower-case label used in logs and metrics.
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Idle => "idle",
+            Phase::Init => "init",
+            Phase::Resize => "resize",
+            Phase::Event => "event",
+            Phase::Frame => "frame",
+            Phase::Task => "task",
+            Phase::Worker => "worker",
+        }
+    }
+
+    /// Event handlers may draw too; what they draw is presented straight away as a frame.
     fn allows_draw(self) -> bool {
-        matches!(self, Phase::Frame)
+        matches!(self, Phase::Frame | Phase::Event)
     }
 
     fn allows_request_frame(self) -> bool {
@@ -81,6 +402,38 @@ impl Phase {
 }
 
 const RECENT_LOG_LIMIT: usize = 16;
+const PERF_MARK_LIMIT: usize = 256;
+const PERF_MEASURE_LIMIT: usize = 256;
+/// How often repeated out-of-phase draw calls are summarized in the log.
+const OUT_OF_PHASE_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+/// Larger text is clamped; beyond this a single glyph already covers any realistic window.
+const MAX_FONT_SIZE: f32 = 2048.0;
+/// Distinct `draw-qr` codes kept encoded; past this the cache starts over.
+const MAX_QR_CODES: usize = 16;
+
+/// The paragraph direction a guest asked for, with `auto` resolved from `text`.
+fn text_direction(direction: WitTextDirection, text: &str) -> TextDirection {
+    match direction {
+        WitTextDirection::Auto => TextDirection::detect(text),
+        WitTextDirection::Ltr => TextDirection::Ltr,
+        WitTextDirection::Rtl => TextDirection::Rtl,
+    }
+}
+
+/// `rect` in host types, or `None` when a coordinate is not finite.
+fn finite_rect(rect: WitRect) -> Option<Rect> {
+    let (origin, size) = (Vec2::from(rect.origin), Vec2::from(rect.size));
+    (origin.is_finite() && size.is_finite()).then(|| Rect::new(origin.x, origin.y, size.x, size.y))
+}
+
+/// A guest font size as the renderer would draw it, with non-finite sizes drawing nothing.
+fn text_size(size: f32) -> f32 {
+    if size.is_finite() {
+        size.clamp(0.0, MAX_FONT_SIZE)
+    } else {
+        0.0
+    }
+}
 
 #[derive(Default, Debug)]
 pub struct HostCtx {
@@ -88,6 +441,137 @@ pub struct HostCtx {
     frame: FrameOutput,
     redraw_requested: bool,
     recent_logs: VecDeque<String>,
+    window_requests: Vec<WindowRequest>,
+    quit_requested: bool,
+    /// `--allow-navigation` was given.
+    navigation_allowed: bool,
+    /// Directory relative `navigate` targets and `spawn-worker` URLs are resolved against.
+    navigation_base: Option<PathBuf>,
+    /// Component the guest asked to `navigate` to.
+    navigate_to: Option<PathBuf>,
+    /// Formats the guest asked to export the current frame in.
+    export_requests: Vec<ExportFormat>,
+    launch_args: Vec<String>,
+    locale: Locale,
+    preferences: Preferences,
+    display: Option<DisplayInfo>,
+    /// The window in logical pixels, outside which chart geometry is culled; `None` until
+    /// the runtime reports a size.
+    viewport: Option<Rect>,
+    gpu: Option<GpuInfo>,
+    power: Option<PowerState>,
+    /// When the user last gave the window input, for `seconds-since-input`.
+    last_input: Option<Instant>,
+    window_position: Option<PhysicalPosition<i32>>,
+    /// Real time since the previous frame, reported by `frame-elapsed-ms`.
+    frame_elapsed_ms: f64,
+    perf_marks: HashMap<String, Instant>,
+    perf_measures: Vec<PerfMeasure>,
+    limits: ResourceLimits,
+    /// Host calls made during the current guest callback.
+    host_calls: u32,
+    /// `draw-text` bytes accepted this frame.
+    text_bytes: usize,
+    uploads: UploadMeter,
+    usage: HostUsage,
+    dropped: DroppedCommands,
+    out_of_phase: OutOfPhase,
+    /// Draw phases entered so far, so assets know which frame last drew them.
+    draw_phases: u64,
+    images: AssetStore<Image>,
+    pictures: AssetStore<Picture>,
+    animations: AssetStore<Animation>,
+    animated_images: AssetStore<AnimatedImage>,
+    pixel_buffers: AssetStore<PixelBuffer>,
+    shaders: AssetStore<Shader>,
+    emitters: AssetStore<Emitter>,
+    /// Open `push-shader-layer` calls this frame; false for pushes that were dropped, so
+    /// their pops are dropped too.
+    shader_layers: Vec<bool>,
+    scroll_regions: ScrollRegions,
+    /// For each open `push-scroll-region` this frame, the translation and clip around it;
+    /// `None` for pushes of unknown regions, so their pops are dropped too.
+    scroll_stack: Vec<Option<(Vec2, Option<Rect>)>>,
+    /// Offset added to every command recorded inside the open scroll regions.
+    scroll_translation: Vec2,
+    /// Window area the open scroll regions leave visible.
+    scroll_clip: Option<Rect>,
+    kinetic: KineticScrollers,
+    tweens: Tweens,
+    /// Start of the clock `kinetic-press` and `kinetic-drag` are timed on, set by the first.
+    kinetic_epoch: Option<Instant>,
+    cameras: Cameras,
+    haptics: Haptics,
+    /// `None` until the host decides where component data lives; storage calls fail until
+    /// then.
+    storage: Option<Storage>,
+    database: Database,
+    tasks: TaskQueue,
+    /// Set on task instances, where `report-task-progress` reports to it.
+    task_reporter: Option<TaskReporter>,
+    workers: Workers,
+    /// Set on worker instances, where `post-message` sends to it.
+    worker_outbox: Option<WorkerOutbox>,
+    /// Given up with `drop-capability`; checked on every call that needs one, so setters
+    /// run later by the runtime cannot hand it back.
+    dropped_capabilities: DroppedCapabilities,
+    text_metrics: TextMetrics,
+    /// `draw-qr` images by their data, so a code redrawn every frame is encoded once.
+    qr_codes: HashMap<String, Image>,
+}
+
+/// Host imports the guest called since the last frame was taken.
+#[derive(Debug, Default)]
+struct HostUsage {
+    calls: BTreeMap<&'static str, (u32, Duration)>,
+    /// Bytes passed to `draw-text`, including text dropped by the quota.
+    text_bytes: usize,
+    /// The call in progress: when it started and, once it has charged itself, which
+    /// import it is. Calls that never charge are WASI.
+    current: Option<(Instant, Option<&'static str>)>,
+}
+
+/// Draw calls made outside `frame`, summarized periodically rather than warned one by one
+/// since a buggy guest can make thousands a second.
+#[derive(Debug, Default)]
+struct OutOfPhase {
+    /// Violations per phase and action since `since`.
+    counts: BTreeMap<(&'static str, &'static str), u32>,
+    since: Option<Instant>,
+    /// The console hint has been shown; it is shown once per component.
+    hinted: bool,
+}
+
+impl OutOfPhase {
+    /// Count a violation, returning true for the first one this component has made.
+    fn record(&mut self, phase: Phase, action: &'static str, now: Instant) -> bool {
+        *self.counts.entry((phase.name(), action)).or_default() += 1;
+        self.since.get_or_insert(now);
+        !std::mem::replace(&mut self.hinted, true)
+    }
+
+    /// The counts to report once a full interval has passed since the first of them.
+    fn take_due(&mut self, now: Instant) -> Vec<((&'static str, &'static str), u32)> {
+        match self.since {
+            Some(since) if now.duration_since(since) >= OUT_OF_PHASE_REPORT_INTERVAL => {
+                self.since = None;
+                std::mem::take(&mut self.counts).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Draw commands rejected during the current frame, reported once when the frame is taken.
+#[derive(Debug, Default)]
+struct DroppedCommands {
+    /// Non-finite geometry.
+    invalid: usize,
+    command_quota: usize,
+    text_quota: usize,
+    /// Image, picture, animation, shader layer and particle draws naming an id that does
+    /// not exist.
+    unknown_image: usize,
 }
 
 impl HostCtx {
@@ -95,12 +579,248 @@ impl HostCtx {
         Self::default()
     }
 
+    pub fn with_launch_args(launch_args: Vec<String>) -> Self {
+        Self {
+            launch_args,
+            ..Self::default()
+        }
+    }
+
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = locale;
+    }
+
+    pub fn set_preferences(&mut self, preferences: Preferences) {
+        self.preferences = preferences;
+    }
+
+    pub fn set_limits(&mut self, limits: ResourceLimits) {
+        self.limits = limits;
+    }
+
+    pub fn set_camera_access(&mut self, access: CameraAccess) {
+        self.cameras.set_access(access);
+    }
+
+    /// Name of a camera waiting on the user's permission, for the window's prompt.
+    pub fn camera_prompt(&self) -> Option<String> {
+        self.cameras.prompt().map(str::to_string)
+    }
+
+    /// The user's answer to the camera prompt; holds for the rest of the session.
+    pub fn answer_camera_prompt(&mut self, allow: bool) {
+        self.cameras.answer(allow);
+    }
+
+    /// Route wheel and touch input to the guest's scroll regions, returning each region it
+    /// scrolled.
+    pub fn scroll_input(&mut self, input: ScrollInput) -> Vec<ScrollEvent> {
+        self.scroll_regions
+            .input(input)
+            .into_iter()
+            .map(|(id, offset)| ScrollEvent::Region { id, offset })
+            .collect()
+    }
+
+    /// Carry scroll region and kinetic scroller motion forward by `dt_ms`, returning what
+    /// it moved.
+    pub fn advance_scrolling(&mut self, dt_ms: f32) -> Vec<ScrollEvent> {
+        let regions = self
+            .scroll_regions
+            .advance(dt_ms)
+            .into_iter()
+            .map(|(id, offset)| ScrollEvent::Region { id, offset });
+        let kinetic = self
+            .kinetic
+            .advance(dt_ms)
+            .into_iter()
+            .map(|(id, offset, delta)| ScrollEvent::Kinetic { id, offset, delta });
+        regions.chain(kinetic).collect()
+    }
+
+    /// Some scroll region or kinetic scroller is still flinging or bouncing, so frames
+    /// should keep coming.
+    pub fn is_scrolling(&self) -> bool {
+        self.scroll_regions.is_flinging() || self.kinetic.is_moving()
+    }
+
+    /// Milliseconds on the clock kinetic scroller drags are timed on.
+    fn kinetic_time_ms(&mut self) -> f64 {
+        let epoch = *self.kinetic_epoch.get_or_insert_with(Instant::now);
+        epoch.elapsed().as_secs_f64() * 1000.0
+    }
+
+    /// Step every tween by the `dt` about to be passed to `frame`, asking for the frame
+    /// after it while any is still running.
+    pub fn advance_tweens(&mut self, dt_ms: f32) {
+        self.tweens.advance(dt_ms);
+        self.redraw_requested |= self.tweens.is_running();
+    }
+
+    /// Hand over the guest's tweens, to carry them into a reloaded instance.
+    pub fn take_tweens(&mut self) -> Tweens {
+        std::mem::take(&mut self.tweens)
+    }
+
+    pub fn set_tweens(&mut self, tweens: Tweens) {
+        self.tweens = tweens;
+    }
+
+    /// Step every particle emitter by the `dt` about to be passed to `frame`.
+    pub fn advance_particles(&mut self, dt_ms: f32) {
+        for emitter in self.emitters.values_mut() {
+            emitter.advance(dt_ms);
+        }
+    }
+
+    pub fn set_display(&mut self, display: Option<DisplayInfo>) {
+        self.display = display;
+    }
+
+    pub fn set_viewport(&mut self, size: LogicalSize) {
+        self.viewport = (size.width > 0.0 && size.height > 0.0)
+            .then(|| Rect::new(0.0, 0.0, size.width, size.height));
+    }
+
+    pub fn set_gpu_info(&mut self, gpu: Option<GpuInfo>) {
+        self.gpu = gpu;
+    }
+
+    pub fn set_power_state(&mut self, power: Option<PowerState>) {
+        self.power = power;
+    }
+
+    pub fn set_storage(&mut self, storage: Option<Storage>) {
+        self.storage = storage;
+    }
+
+    pub fn set_database(&mut self, database: Database) {
+        self.database = database;
+    }
+
+    pub fn storage(&self) -> Option<&Storage> {
+        self.storage.as_ref()
+    }
+
+    pub fn set_task_reporter(&mut self, reporter: Option<TaskReporter>) {
+        self.task_reporter = reporter;
+    }
+
+    /// Tasks spawned since the last call, for the runtime to start.
+    pub fn take_task_requests(&mut self) -> Vec<TaskRequest> {
+        self.tasks.take_pending()
+    }
+
+    /// A task's result is being delivered, freeing its slot.
+    pub fn task_finished(&mut self) {
+        self.tasks.finished();
+    }
+
+    pub fn tasks_in_flight(&self) -> usize {
+        self.tasks.in_flight()
+    }
+
+    pub fn set_worker_outbox(&mut self, outbox: Option<WorkerOutbox>) {
+        self.worker_outbox = outbox;
+    }
+
+    pub fn workers_mut(&mut self) -> &mut Workers {
+        &mut self.workers
+    }
+
+    /// Workers spawned since the last call, for the runtime to start.
+    pub fn take_worker_requests(&mut self) -> Vec<WorkerRequest> {
+        self.workers.take_pending()
+    }
+
+    pub fn workers_running(&self) -> usize {
+        self.workers.running()
+    }
+
+    pub fn dropped_capabilities(&self) -> &DroppedCapabilities {
+        &self.dropped_capabilities
+    }
+
+    /// Start without capabilities another instance dropped, for the tasks and workers it
+    /// spawns.
+    pub fn inherit_dropped_capabilities(&mut self, dropped: &DroppedCapabilities) {
+        self.dropped_capabilities.extend(dropped);
+    }
+
+    /// Fail a call that needs `capability` once the guest has dropped it.
+    fn require(&self, capability: Capability) -> Result<(), String> {
+        if self.dropped_capabilities.contains(capability) {
+            return Err(format!("the {} capability was dropped", capability.name()));
+        }
+        Ok(())
+    }
+
+    /// Task and worker instances run on behalf of another instance and cannot start
+    /// their own, which nothing would deliver.
+    fn is_secondary(&self) -> bool {
+        self.task_reporter.is_some() || self.worker_outbox.is_some()
+    }
+
+    pub fn set_last_input(&mut self, at: Instant) {
+        self.last_input = Some(at);
+    }
+
+    pub fn set_frame_elapsed(&mut self, elapsed_ms: f64) {
+        self.frame_elapsed_ms = elapsed_ms;
+    }
+
+    /// Where the app last saw the window; reported by `get-window-position`.
+    pub fn record_window_position(&mut self, position: Option<PhysicalPosition<i32>>) {
+        self.window_position = position;
+    }
+
     pub fn enter_phase(&mut self, phase: Phase) {
-        if matches!(phase, Phase::Frame) {
+        if phase.allows_draw() {
+            self.draw_phases += 1;
             self.frame.clear_color = None;
             self.frame.commands.clear();
+            self.text_bytes = 0;
+            self.dropped = DroppedCommands::default();
         }
+        self.host_calls = 0;
         self.phase = phase;
+        self.report_out_of_phase(Instant::now());
+    }
+
+    /// The guest is entering a host function; paired with [`HostCtx::end_host_call`]
+    /// through the store's call hook.
+    pub fn begin_host_call(&mut self) {
+        self.usage.current = Some((Instant::now(), None));
+    }
+
+    pub fn end_host_call(&mut self) {
+        let Some((started, import)) = self.usage.current.take() else {
+            return;
+        };
+        let entry = self
+            .usage
+            .calls
+            .entry(import.unwrap_or("wasi"))
+            .or_default();
+        entry.0 += 1;
+        entry.1 += started.elapsed();
+    }
+
+    /// Host calls since the last time this was called, most time-consuming first, and the
+    /// `draw-text` bytes among them.
+    pub fn take_host_usage(&mut self) -> (Vec<HostCallStats>, usize) {
+        let usage = std::mem::take(&mut self.usage);
+        let mut calls: Vec<HostCallStats> = usage
+            .calls
+            .into_iter()
+            .map(|(name, (calls, time))| HostCallStats {
+                name,
+                calls,
+                total_ms: time.as_secs_f32() * 1000.0,
+            })
+            .collect();
+        calls.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+        (calls, usage.text_bytes)
     }
 
     pub fn exit_phase(&mut self) {
@@ -108,6 +828,37 @@ impl HostCtx {
     }
 
     pub fn take_frame_output(&mut self) -> FrameOutput {
+        let dropped = std::mem::take(&mut self.dropped);
+        if dropped.invalid > 0 {
+            tracing::warn!(
+                dropped = dropped.invalid,
+                "dropped draw commands with non-finite geometry"
+            );
+        }
+        if dropped.command_quota > 0 {
+            tracing::warn!(
+                dropped = dropped.command_quota,
+                limit = self.limits.max_draw_commands,
+                "frame exceeded the draw command quota; dropped the excess"
+            );
+        }
+        if dropped.text_quota > 0 {
+            tracing::warn!(
+                dropped = dropped.text_quota,
+                limit_bytes = self.limits.max_text_bytes,
+                "frame exceeded the text quota; dropped the excess"
+            );
+        }
+        if dropped.unknown_image > 0 {
+            tracing::warn!(
+                dropped = dropped.unknown_image,
+                "dropped image draws referencing unknown images"
+            );
+        }
+        self.shader_layers.clear();
+        self.scroll_stack.clear();
+        self.scroll_translation = Vec2::ZERO;
+        self.scroll_clip = None;
         let commands = self.frame.commands.drain(..).collect();
         FrameOutput {
             clear_color: self.frame.clear_color.take(),
@@ -121,14 +872,89 @@ impl HostCtx {
         requested
     }
 
+    pub fn take_window_requests(&mut self) -> Vec<WindowRequest> {
+        std::mem::take(&mut self.window_requests)
+    }
+
+    pub fn take_quit_request(&mut self) -> bool {
+        std::mem::take(&mut self.quit_requested)
+    }
+
+    /// Whether `navigate` is allowed, and where relative targets are looked up.
+    pub fn set_navigation(&mut self, allowed: bool, base: Option<PathBuf>) {
+        self.navigation_allowed = allowed;
+        self.navigation_base = base;
+    }
+
+    /// Names reported by `host-capabilities`.
+    pub fn capabilities(&self) -> Vec<String> {
+        let mut capabilities = vec![
+            "images",
+            "svg",
+            "lottie",
+            "particles",
+            "shaders",
+            "pixel-buffers",
+            "export-frame",
+        ];
+        if self.cameras.available() {
+            capabilities.push("camera");
+        }
+        if self.navigation_allowed {
+            capabilities.push("navigation");
+        }
+        if power::SUPPORTED {
+            capabilities.push("power-state");
+        }
+        if haptics::SUPPORTED {
+            capabilities.push("haptics");
+        }
+        if self.storage.is_some() {
+            capabilities.push("storage");
+        }
+        capabilities
+            .into_iter()
+            .filter(|name| !self.dropped_capabilities.contains_name(name))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Run a storage call, reporting failures to the guest as messages.
+    fn with_storage<T>(
+        &self,
+        call: impl FnOnce(&Storage) -> anyhow::Result<T>,
+    ) -> Result<T, String> {
+        self.require(Capability::Storage)?;
+        let storage = self
+            .storage
+            .as_ref()
+            .ok_or("storage is not available in this host")?;
+        call(storage).map_err(|err| format!("{err:#}"))
+    }
+
+    pub fn take_navigation(&mut self) -> Option<PathBuf> {
+        self.navigate_to.take()
+    }
+
+    pub fn take_export_requests(&mut self) -> Vec<ExportFormat> {
+        std::mem::take(&mut self.export_requests)
+    }
+
+    /// Drain the guest `perf-measure` regions recorded since the last call.
+    pub fn take_perf_measures(&mut self) -> Vec<PerfMeasure> {
+        std::mem::take(&mut self.perf_measures)
+    }
+
     pub fn recent_logs_snapshot(&self) -> Vec<String> {
         self.recent_logs.iter().cloned().collect()
     }
 
+    /// Record a line the guest printed to WASI stdout/stderr.
+    pub fn record_guest_output(&mut self, stream: GuestStream, line: &str) {
+        self.push_recent_log(&stream.to_string(), line);
+    }
+
     fn record_guest_log(&mut self, level: LogLevel, message: &str) {
-        if self.recent_logs.len() == RECENT_LOG_LIMIT {
-            self.recent_logs.pop_front();
-        }
         let level_label = match level {
             LogLevel::Trace => "TRACE",
             LogLevel::Debug => "DEBUG",
@@ -136,75 +962,1871 @@ impl HostCtx {
             LogLevel::Warn => "WARN",
             LogLevel::Error => "ERROR",
         };
-        self.recent_logs
-            .push_back(format!("[{level_label}] {message}"));
+        self.push_recent_log(level_label, message);
+    }
+
+    fn push_recent_log(&mut self, label: &str, message: &str) {
+        if self.recent_logs.len() == RECENT_LOG_LIMIT {
+            self.recent_logs.pop_front();
+        }
+        self.recent_logs.push_back(format!("[{label}] {message}"));
     }
 
-    fn push_command(&mut self, cmd: DrawCommand) {
+    fn push_command(&mut self, mut cmd: DrawCommand) {
+        if self.frame.commands.len() >= self.limits.max_draw_commands {
+            self.dropped.command_quota += 1;
+            return;
+        }
+        if self.scroll_translation != Vec2::ZERO {
+            cmd.translate(self.scroll_translation);
+        }
         self.frame.commands.push(cmd);
     }
 
-    fn warn_out_of_phase(&self, action: &str) {
-        tracing::warn!(phase = ?self.phase, "guest attempted to {action} outside of a frame phase");
+    /// The part of the window that can show what is drawn now, in the coordinates the
+    /// guest draws in; chart geometry outside it is culled.
+    fn visible_area(&self) -> Option<Rect> {
+        let area = match (self.viewport, self.scroll_clip) {
+            (Some(viewport), Some(clip)) => viewport.intersection(&clip),
+            (viewport, clip) => viewport.or(clip)?,
+        };
+        let origin = area.origin() - self.scroll_translation;
+        Some(Rect::new(origin.x, origin.y, area.width, area.height))
     }
-}
 
-impl GuestHost for HostCtx {
-    fn clear(&mut self, color: WitColor) {
-        if self.phase.allows_draw() {
-            self.frame.clear_color = Some(Color::from_wit(color));
-        } else {
-            self.warn_out_of_phase("clear the scene");
+    /// Bytes held by every kind of guest asset together.
+    pub fn asset_bytes(&self) -> usize {
+        self.images.bytes()
+            + self.pictures.bytes()
+            + self.animations.bytes()
+            + self.animated_images.bytes()
+            + self.pixel_buffers.bytes()
+            + self.shaders.bytes()
+            + self.emitters.bytes()
+    }
+
+    /// Drop images, pictures and shaders, least recently drawn first, until the guest's
+    /// assets hold at most `max_bytes`. Assets drawn in the last two frames are kept, so
+    /// what is on screen stays there.
+    pub fn evict_assets(&mut self, max_bytes: usize) -> EvictedAssets {
+        #[derive(Clone, Copy)]
+        enum Kind {
+            Image,
+            Picture,
+            Shader,
+        }
+        let mut bytes = self.asset_bytes();
+        let mut evicted = EvictedAssets::default();
+        if bytes <= max_bytes {
+            return evicted;
+        }
+        let recent = self.draw_phases.saturating_sub(1);
+        let tag = |kind| move |(id, len, drawn)| (drawn, kind, id, len);
+        let mut candidates: Vec<(u64, Kind, u32, usize)> = self
+            .images
+            .draw_history()
+            .map(tag(Kind::Image))
+            .chain(self.pictures.draw_history().map(tag(Kind::Picture)))
+            .chain(self.shaders.draw_history().map(tag(Kind::Shader)))
+            .filter(|&(drawn, ..)| drawn == 0 || drawn < recent)
+            .collect();
+        candidates.sort_unstable_by_key(|&(drawn, _, id, _)| (drawn, id));
+        for (_, kind, id, len) in candidates {
+            if bytes <= max_bytes {
+                break;
+            }
+            match kind {
+                Kind::Image => {
+                    self.images.remove(id);
+                    evicted.images.push(id);
+                }
+                Kind::Picture => {
+                    self.pictures.remove(id);
+                    evicted.pictures.push(id);
+                }
+                Kind::Shader => {
+                    self.shaders.remove(id);
+                    evicted.shaders.push(id);
+                }
+            }
+            bytes -= len;
         }
+        evicted
     }
 
-    fn fill_rect(&mut self, origin: WitVec2, size: WitVec2, color: WitColor) {
-        if self.phase.allows_draw() {
-            self.push_command(DrawCommand::FillRect {
-                origin: Vec2::from_wit(origin),
-                size: Vec2::from_wit(size),
-                color: Color::from_wit(color),
-            });
-        } else {
-            self.warn_out_of_phase("issue fill-rect");
+    /// Counts `bytes` of pixels `call` uploads against the per-second upload quota,
+    /// returning whether they fit.
+    fn charge_upload(&mut self, call: &str, bytes: usize) -> bool {
+        let limit = self.limits.max_image_upload_bytes_per_second;
+        let fits = self.uploads.charge(Instant::now(), bytes, limit);
+        if !fits {
+            tracing::warn!(
+                target: GUEST_TARGET,
+                bytes,
+                limit_bytes_per_second = limit,
+                "{call} exceeded the image upload rate"
+            );
         }
+        fits
     }
 
-    fn draw_text(&mut self, text: String, origin: WitVec2, size: f32, color: WitColor) {
+    /// How large a store already holding `own` bytes may grow within the shared budget.
+    fn image_budget(&self, own: usize) -> usize {
+        self.limits
+            .max_image_bytes
+            .saturating_sub(self.asset_bytes() - own)
+    }
+
+    /// Shared by the calls that draw pixels as an image; `lookup` finds the pixels.
+    fn record_image(
+        &mut self,
+        origin: WitVec2,
+        size: WitVec2,
+        style: ImageStyle,
+        lookup: impl FnOnce(&mut Self) -> Option<Image>,
+    ) {
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("draw an image");
+            return;
+        }
+        let origin = Vec2::from(origin);
+        let size = Vec2::from(size);
+        if !origin.is_finite() || !size.is_finite() {
+            self.dropped.invalid += 1;
+            return;
+        }
+        let Some(image) = lookup(self) else {
+            self.dropped.unknown_image += 1;
+            return;
+        };
+        self.push_command(DrawCommand::DrawImage {
+            image,
+            origin,
+            size,
+            style,
+        });
+    }
+
+    /// Shared by the `draw-text` imports, once they have charged for the call.
+    fn record_text(
+        &mut self,
+        text: String,
+        origin: WitVec2,
+        size: f32,
+        color: WitColor,
+        font: FontFamily,
+        flow: TextFlow,
+    ) -> wasmtime::Result<()> {
+        self.usage.text_bytes += text.len();
         if self.phase.allows_draw() {
+            let origin = Vec2::from(origin);
+            if !origin.is_finite() || !size.is_finite() || !flow.rotation.is_finite() {
+                self.dropped.invalid += 1;
+                return Ok(());
+            }
+            if self.text_bytes + text.len() > self.limits.max_text_bytes {
+                self.dropped.text_quota += 1;
+                return Ok(());
+            }
+            self.text_bytes += text.len();
             self.push_command(DrawCommand::DrawText {
                 text,
-                origin: Vec2::from_wit(origin),
-                size,
+                origin,
+                size: size.clamp(0.0, MAX_FONT_SIZE),
                 color: Color::from_wit(color),
+                font,
+                flow,
             });
         } else {
             self.warn_out_of_phase("draw text");
         }
+        Ok(())
     }
 
-    fn request_frame(&mut self) {
-        if self.phase.allows_request_frame() {
-            self.redraw_requested = true;
-        } else {
-            tracing::debug!(phase = ?self.phase, "guest requested frame while idle; ignoring");
+    /// Count a call to the `import` host function against the per-callback quota, trapping
+    /// the guest once it is spent.
+    fn charge_host_call(&mut self, import: &'static str) -> wasmtime::Result<()> {
+        if let Some(call) = self.usage.current.as_mut() {
+            call.1 = Some(import);
         }
+        self.host_calls = self.host_calls.saturating_add(1);
+        if self.host_calls > self.limits.max_host_calls && self.phase != Phase::Task {
+            anyhow::bail!(
+                "component exceeded the host call quota ({} calls in one {:?} callback)",
+                self.limits.max_host_calls,
+                self.phase
+            );
+        }
+        Ok(())
     }
 
-    fn log(&mut self, level: LogLevel, message: String) {
-        self.record_guest_log(level, &message);
-        match level {
-            LogLevel::Trace => tracing::trace!(target: "guest", "{message}"),
-            LogLevel::Debug => tracing::debug!(target: "guest", "{message}"),
-            LogLevel::Info => tracing::info!(target: "guest", "{message}"),
-            LogLevel::Warn => tracing::warn!(target: "guest", "{message}"),
-            LogLevel::Error => tracing::error!(target: "guest", "{message}"),
+    fn warn_out_of_phase(&mut self, action: &'static str) {
+        let now = Instant::now();
+        if self.out_of_phase.record(self.phase, action, now) {
+            tracing::warn!(phase = ?self.phase, "guest attempted to {action} outside of a frame phase");
+            self.push_recent_log(
+                "HOST",
+                "drawing only works inside `frame`; call `request-frame` and draw there",
+            );
+        }
+        self.report_out_of_phase(now);
+    }
+
+    fn report_out_of_phase(&mut self, now: Instant) {
+        for ((phase, action), count) in self.out_of_phase.take_due(now) {
+            tracing::warn!(
+                phase,
+                count,
+                "guest attempted to {action} outside of a frame phase {count} times"
+            );
         }
     }
 }
 
-impl fmt::Display for DrawCommand {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl GuestHost for HostCtx {
+    fn clear(&mut self, color: WitColor) -> wasmtime::Result<()> {
+        self.charge_host_call("clear")?;
+        if self.phase.allows_draw() {
+            self.frame.clear_color = Some(Color::from_wit(color));
+        } else {
+            self.warn_out_of_phase("clear the scene");
+        }
+        Ok(())
+    }
+
+    fn fill_rect(
+        &mut self,
+        origin: WitVec2,
+        size: WitVec2,
+        color: WitColor,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("fill-rect")?;
+        if self.phase.allows_draw() {
+            let (origin, size) = (Vec2::from(origin), Vec2::from(size));
+            if !(origin.is_finite() && size.is_finite()) {
+                self.dropped.invalid += 1;
+                return Ok(());
+            }
+            self.push_command(DrawCommand::FillRect {
+                origin,
+                size,
+                color: Color::from_wit(color),
+            });
+        } else {
+            self.warn_out_of_phase("issue fill-rect");
+        }
+        Ok(())
+    }
+
+    fn draw_text(
+        &mut self,
+        text: String,
+        origin: WitVec2,
+        size: f32,
+        color: WitColor,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-text")?;
+        self.record_text(
+            text,
+            origin,
+            size,
+            color,
+            FontFamily::Sans,
+            TextFlow::default(),
+        )
+    }
+
+    fn draw_text_with_font(
+        &mut self,
+        text: String,
+        origin: WitVec2,
+        size: f32,
+        color: WitColor,
+        font: WitFontFamily,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-text-with-font")?;
+        self.record_text(
+            text,
+            origin,
+            size,
+            color,
+            FontFamily::from_wit(font),
+            TextFlow::default(),
+        )
+    }
+
+    fn draw_text_with_direction(
+        &mut self,
+        text: String,
+        origin: WitVec2,
+        size: f32,
+        color: WitColor,
+        font: WitFontFamily,
+        direction: WitTextDirection,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-text-with-direction")?;
+        let direction = text_direction(direction, &text);
+        self.record_text(
+            text,
+            origin,
+            size,
+            color,
+            FontFamily::from_wit(font),
+            TextFlow::horizontal(direction),
+        )
+    }
+
+    fn draw_text_oriented(
+        &mut self,
+        text: String,
+        origin: WitVec2,
+        size: f32,
+        color: WitColor,
+        font: WitFontFamily,
+        direction: WitTextDirection,
+        mode: WitWritingMode,
+        rotation: f32,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-text-oriented")?;
+        let flow = TextFlow {
+            direction: text_direction(direction, &text),
+            mode: WritingMode::from_wit(mode),
+            rotation,
+        };
+        self.record_text(text, origin, size, color, FontFamily::from_wit(font), flow)
+    }
+
+    fn draw_rich_text(
+        &mut self,
+        spans: Vec<WitStyledSpan>,
+        origin: WitVec2,
+        max_width: f32,
+        direction: WitTextDirection,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-rich-text")?;
+        let bytes: usize = spans.iter().map(|span| span.text.len()).sum();
+        self.usage.text_bytes += bytes;
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("draw text");
+            return Ok(());
+        }
+        let origin = Vec2::from(origin);
+        if !origin.is_finite() || max_width.is_nan() {
+            self.dropped.invalid += 1;
+            return Ok(());
+        }
+        if self.text_bytes + bytes > self.limits.max_text_bytes {
+            self.dropped.text_quota += 1;
+            return Ok(());
+        }
+        self.text_bytes += bytes;
+        let layout: Vec<Span> = spans
+            .iter()
+            .map(|span| Span {
+                text: &span.text,
+                size: text_size(span.size),
+                font: FontFamily::from_wit(span.font),
+            })
+            .collect();
+        let paragraph: String = spans.iter().map(|span| span.text.as_str()).collect();
+        let direction = text_direction(direction, &paragraph);
+        let runs = self
+            .text_metrics
+            .layout_paragraph(&layout, max_width, direction);
+        for run in runs {
+            let span = &layout[run.span];
+            self.push_command(DrawCommand::DrawText {
+                text: run.text,
+                origin: origin + run.offset,
+                size: span.size,
+                color: Color::from_wit(spans[run.span].color),
+                font: span.font,
+                flow: TextFlow::horizontal(run.direction),
+            });
+        }
+        Ok(())
+    }
+
+    fn draw_text_grid(
+        &mut self,
+        cells: Vec<WitGridCell>,
+        origin: WitVec2,
+        size: f32,
+        tab_width: u32,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-text-grid")?;
+        let bytes: usize = cells.iter().map(|cell| cell.text.len()).sum();
+        self.usage.text_bytes += bytes;
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("draw text");
+            return Ok(());
+        }
+        let origin = Vec2::from(origin);
+        if !origin.is_finite() || !size.is_finite() {
+            self.dropped.invalid += 1;
+            return Ok(());
+        }
+        if self.text_bytes + bytes > self.limits.max_text_bytes {
+            self.dropped.text_quota += 1;
+            return Ok(());
+        }
+        self.text_bytes += bytes;
+        let size = text_size(size);
+        let (cell, ascent) = self.text_metrics.grid_cell(size);
+        let texts: Vec<&str> = cells.iter().map(|cell| cell.text.as_str()).collect();
+        for run in layout_grid(&texts, tab_width) {
+            let style = &cells[run.cell];
+            let corner = origin + Vec2::new(run.column as f32 * cell.x, run.row as f32 * cell.y);
+            if let Some(bg) = style.bg {
+                let columns = run.text.chars().count() as f32;
+                self.push_command(DrawCommand::FillRect {
+                    origin: corner,
+                    size: Vec2::new(columns * cell.x, cell.y),
+                    color: Color::from_wit(bg),
+                });
+            }
+            if !run.text.trim().is_empty() {
+                self.push_command(DrawCommand::DrawText {
+                    text: run.text,
+                    origin: corner + Vec2::new(0.0, ascent),
+                    size,
+                    color: Color::from_wit(style.fg),
+                    font: FontFamily::Monospace,
+                    flow: TextFlow::default(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn text_grid_cell(&mut self, size: f32) -> wasmtime::Result<WitVec2> {
+        self.charge_host_call("text-grid-cell")?;
+        Ok(self.text_metrics.grid_cell(text_size(size)).0.into())
+    }
+
+    fn draw_markdown(
+        &mut self,
+        source: String,
+        rect: WitRect,
+        base_size: f32,
+        color: WitColor,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-markdown")?;
+        self.usage.text_bytes += source.len();
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("draw text");
+            return Ok(());
+        }
+        let (origin, size) = (Vec2::from(rect.origin), Vec2::from(rect.size));
+        if !origin.is_finite() || !size.is_finite() || !base_size.is_finite() {
+            self.dropped.invalid += 1;
+            return Ok(());
+        }
+        if self.text_bytes + source.len() > self.limits.max_text_bytes {
+            self.dropped.text_quota += 1;
+            return Ok(());
+        }
+        self.text_bytes += source.len();
+        let commands = markdown::layout(
+            &source,
+            Rect::new(origin.x, origin.y, size.x, size.y),
+            text_size(base_size),
+            Color::from_wit(color),
+            &mut self.text_metrics,
+        );
+        for command in commands {
+            self.push_command(command);
+        }
+        Ok(())
+    }
+
+    fn draw_qr(&mut self, data: String, rect: WitRect) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-qr")?;
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("draw a QR code");
+            return Ok(());
+        }
+        let (origin, size) = (Vec2::from(rect.origin), Vec2::from(rect.size));
+        if !origin.is_finite() || !size.is_finite() {
+            self.dropped.invalid += 1;
+            return Ok(());
+        }
+        let image = match self.qr_codes.get(&data) {
+            Some(image) => image.clone(),
+            None => {
+                let Some(image) = QrCode::encode(data.as_bytes()).and_then(|code| code.to_image())
+                else {
+                    self.dropped.invalid += 1;
+                    return Ok(());
+                };
+                if self.qr_codes.len() >= MAX_QR_CODES {
+                    self.qr_codes.clear();
+                }
+                self.qr_codes.insert(data, image.clone());
+                image
+            }
+        };
+        let side = size.x.min(size.y).max(0.0);
+        self.push_command(DrawCommand::DrawImage {
+            image,
+            origin: origin + (size - Vec2::new(side, side)) * 0.5,
+            size: Vec2::new(side, side),
+            style: ImageStyle::default(),
+        });
+        Ok(())
+    }
+
+    fn draw_bars(
+        &mut self,
+        values: Vec<f32>,
+        rect: WitRect,
+        style: WitBarStyle,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-bars")?;
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("draw bars");
+            return Ok(());
+        }
+        let style = BarStyle::from_wit(style);
+        let Some(rect) = finite_rect(rect).filter(|_| style.is_valid()) else {
+            self.dropped.invalid += 1;
+            return Ok(());
+        };
+        for bar in charts::bars(&values, rect, &style, self.visible_area()) {
+            self.push_command(DrawCommand::FillRect {
+                origin: bar.origin(),
+                size: Vec2::new(bar.width, bar.height),
+                color: style.color,
+            });
+        }
+        Ok(())
+    }
+
+    fn draw_points(
+        &mut self,
+        points: Vec<WitVec2>,
+        radius: f32,
+        color: WitColor,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-points")?;
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("draw points");
+            return Ok(());
+        }
+        if !radius.is_finite() || radius <= 0.0 {
+            self.dropped.invalid += 1;
+            return Ok(());
+        }
+        let points: Vec<Vec2> = points.into_iter().map(Vec2::from).collect();
+        let sprites = charts::points(&points, radius, Color::from_wit(color), self.visible_area());
+        if !sprites.is_empty() {
+            self.push_command(DrawCommand::DrawParticles {
+                particles: sprites.into(),
+            });
+        }
+        Ok(())
+    }
+
+    fn draw_heatmap(
+        &mut self,
+        grid: WitHeatmap,
+        rect: WitRect,
+        colormap: Vec<WitColor>,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-heatmap")?;
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("draw a heatmap");
+            return Ok(());
+        }
+        let range_is_valid = grid.min.is_finite() && grid.max.is_finite() && grid.min != grid.max;
+        let Some(rect) = finite_rect(rect).filter(|_| range_is_valid && !colormap.is_empty())
+        else {
+            self.dropped.invalid += 1;
+            return Ok(());
+        };
+        let colormap: Vec<Color> = colormap.into_iter().map(Color::from_wit).collect();
+        if let Some((image, covered)) = charts::heatmap(
+            &grid.values,
+            grid.columns as usize,
+            (grid.min, grid.max),
+            rect,
+            &colormap,
+            self.visible_area(),
+        ) {
+            self.push_command(DrawCommand::DrawImage {
+                image,
+                origin: covered.origin(),
+                size: Vec2::new(covered.width, covered.height),
+                style: ImageStyle::default(),
+            });
+        }
+        Ok(())
+    }
+
+    fn define_scroll_region(
+        &mut self,
+        id: u32,
+        content_size: WitVec2,
+        viewport: WitRect,
+    ) -> wasmtime::Result<bool> {
+        self.charge_host_call("define-scroll-region")?;
+        let defined = finite_rect(viewport).is_some_and(|viewport| {
+            self.scroll_regions
+                .define(id, Size::from(content_size), viewport)
+        });
+        if !defined {
+            tracing::warn!(
+                target: GUEST_TARGET,
+                id,
+                "define-scroll-region called with invalid sizes or too many regions"
+            );
+        }
+        Ok(defined)
+    }
+
+    fn remove_scroll_region(&mut self, id: u32) -> wasmtime::Result<()> {
+        self.charge_host_call("remove-scroll-region")?;
+        self.scroll_regions.remove(id);
+        Ok(())
+    }
+
+    fn scroll_offset(&mut self, id: u32) -> wasmtime::Result<Option<WitVec2>> {
+        self.charge_host_call("scroll-offset")?;
+        Ok(self.scroll_regions.offset(id).map(WitVec2::from))
+    }
+
+    fn set_scroll_offset(&mut self, id: u32, offset: WitVec2) -> wasmtime::Result<()> {
+        self.charge_host_call("set-scroll-offset")?;
+        let offset = Vec2::from(offset);
+        if offset.is_finite() {
+            self.scroll_regions.set_offset(id, offset);
+        }
+        Ok(())
+    }
+
+    fn push_scroll_region(&mut self, id: u32) -> wasmtime::Result<()> {
+        self.charge_host_call("push-scroll-region")?;
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("push a scroll region");
+            return Ok(());
+        }
+        let (Some(viewport), Some(offset)) = (
+            self.scroll_regions.viewport(id),
+            self.scroll_regions.offset(id),
+        ) else {
+            self.dropped.invalid += 1;
+            self.scroll_stack.push(None);
+            return Ok(());
+        };
+        let origin = viewport.origin() + self.scroll_translation;
+        let screen = Rect::new(origin.x, origin.y, viewport.width, viewport.height);
+        self.scroll_regions.drawn_at(id, screen);
+        self.push_command(DrawCommand::PushClip {
+            origin: viewport.origin(),
+            size: Vec2::new(viewport.width, viewport.height),
+        });
+        self.scroll_stack
+            .push(Some((self.scroll_translation, self.scroll_clip)));
+        self.scroll_translation = origin - offset;
+        self.scroll_clip = Some(match self.scroll_clip {
+            Some(outer) => outer.intersection(&screen),
+            None => screen,
+        });
+        Ok(())
+    }
+
+    fn pop_scroll_region(&mut self) -> wasmtime::Result<()> {
+        self.charge_host_call("pop-scroll-region")?;
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("pop a scroll region");
+            return Ok(());
+        }
+        match self.scroll_stack.pop() {
+            Some(Some((translation, clip))) => {
+                self.scroll_translation = translation;
+                self.scroll_clip = clip;
+                self.push_command(DrawCommand::PopClip);
+            }
+            Some(None) => {}
+            None => self.dropped.invalid += 1,
+        }
+        Ok(())
+    }
+
+    fn kinetic_press(
+        &mut self,
+        id: u32,
+        position: WitVec2,
+        offset: WitVec2,
+        min: WitVec2,
+        max: WitVec2,
+    ) -> wasmtime::Result<bool> {
+        self.charge_host_call("kinetic-press")?;
+        let time_ms = self.kinetic_time_ms();
+        let pressed = self.kinetic.press(
+            id,
+            position.into(),
+            offset.into(),
+            min.into(),
+            max.into(),
+            time_ms,
+        );
+        if !pressed {
+            tracing::warn!(
+                target: GUEST_TARGET,
+                id,
+                "kinetic-press called with non-finite values or too many scrollers"
+            );
+        }
+        Ok(pressed)
+    }
+
+    fn kinetic_drag(&mut self, id: u32, position: WitVec2) -> wasmtime::Result<Option<WitVec2>> {
+        self.charge_host_call("kinetic-drag")?;
+        let time_ms = self.kinetic_time_ms();
+        Ok(self
+            .kinetic
+            .drag(id, position.into(), time_ms)
+            .map(WitVec2::from))
+    }
+
+    fn kinetic_release(&mut self, id: u32) -> wasmtime::Result<()> {
+        self.charge_host_call("kinetic-release")?;
+        let time_ms = self.kinetic_time_ms();
+        self.kinetic.release(id, time_ms);
+        // The fling is carried forward frame by frame, so frames must keep coming.
+        self.redraw_requested |= self.kinetic.is_moving();
+        Ok(())
+    }
+
+    fn kinetic_stop(&mut self, id: u32) -> wasmtime::Result<Option<WitVec2>> {
+        self.charge_host_call("kinetic-stop")?;
+        Ok(self.kinetic.stop(id).map(WitVec2::from))
+    }
+
+    fn hit_test_text(
+        &mut self,
+        text: String,
+        size: f32,
+        point: WitVec2,
+        direction: WitTextDirection,
+    ) -> wasmtime::Result<u32> {
+        self.charge_host_call("hit-test-text")?;
+        let direction = text_direction(direction, &text);
+        Ok(self
+            .text_metrics
+            .hit_test(&text, text_size(size), Vec2::from(point), direction))
+    }
+
+    fn caret_rect(
+        &mut self,
+        text: String,
+        size: f32,
+        index: u32,
+        direction: WitTextDirection,
+    ) -> wasmtime::Result<WitRect> {
+        self.charge_host_call("caret-rect")?;
+        let direction = text_direction(direction, &text);
+        let rect = self
+            .text_metrics
+            .caret_rect(&text, text_size(size), index, direction);
+        Ok(WitRect {
+            origin: WitVec2 {
+                x: rect.x,
+                y: rect.y,
+            },
+            size: WitVec2 {
+                x: rect.width,
+                y: rect.height,
+            },
+        })
+    }
+
+    fn create_image(
+        &mut self,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> wasmtime::Result<Option<ImageId>> {
+        self.charge_host_call("create-image")?;
+        let Some(image) = Image::new(width, height, rgba) else {
+            tracing::warn!(
+                target: GUEST_TARGET,
+                width,
+                height,
+                "create-image called with mismatched pixel data or an unsupported size"
+            );
+            return Ok(None);
+        };
+        if !self.charge_upload("create-image", image.byte_len()) {
+            return Ok(None);
+        }
+        let id = self
+            .images
+            .insert(image, self.image_budget(self.images.bytes()));
+        if id.is_none() {
+            tracing::warn!(
+                target: GUEST_TARGET,
+                held_bytes = self.asset_bytes(),
+                limit_bytes = self.limits.max_image_bytes,
+                "create-image exceeded the image memory budget"
+            );
+        }
+        Ok(id)
+    }
+
+    fn drop_image(&mut self, image: ImageId) -> wasmtime::Result<()> {
+        self.charge_host_call("drop-image")?;
+        self.images.remove(image);
+        Ok(())
+    }
+
+    fn load_svg(&mut self, bytes: Vec<u8>) -> wasmtime::Result<Result<PictureId, String>> {
+        self.charge_host_call("load-svg")?;
+        let picture = match Picture::parse(&bytes) {
+            Ok(picture) => picture,
+            Err(err) => return Ok(Err(format!("{err:#}"))),
+        };
+        Ok(self
+            .pictures
+            .insert(picture, self.image_budget(self.pictures.bytes()))
+            .ok_or_else(|| "the image memory budget is exhausted".to_string()))
+    }
+
+    fn picture_size(&mut self, picture: PictureId) -> wasmtime::Result<Option<WitVec2>> {
+        self.charge_host_call("picture-size")?;
+        Ok(self.pictures.get(picture).map(|picture| WitVec2 {
+            x: picture.width(),
+            y: picture.height(),
+        }))
+    }
+
+    fn drop_picture(&mut self, picture: PictureId) -> wasmtime::Result<()> {
+        self.charge_host_call("drop-picture")?;
+        self.pictures.remove(picture);
+        Ok(())
+    }
+
+    fn draw_picture(
+        &mut self,
+        picture: PictureId,
+        origin: WitVec2,
+        size: WitVec2,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-picture")?;
+        if self.phase.allows_draw() {
+            let origin = Vec2::from(origin);
+            let size = Vec2::from(size);
+            if !origin.is_finite() || !size.is_finite() {
+                self.dropped.invalid += 1;
+                return Ok(());
+            }
+            let Some(picture) = self.pictures.draw(picture, self.draw_phases).cloned() else {
+                self.dropped.unknown_image += 1;
+                return Ok(());
+            };
+            self.push_command(DrawCommand::DrawPicture {
+                picture,
+                origin,
+                size,
+            });
+        } else {
+            self.warn_out_of_phase("draw a picture");
+        }
+        Ok(())
+    }
+
+    fn load_lottie(&mut self, bytes: Vec<u8>) -> wasmtime::Result<Result<AnimationId, String>> {
+        self.charge_host_call("load-lottie")?;
+        let animation = match Animation::parse(&bytes) {
+            Ok(animation) => animation,
+            Err(err) => return Ok(Err(format!("{err:#}"))),
+        };
+        Ok(self
+            .animations
+            .insert(animation, self.image_budget(self.animations.bytes()))
+            .ok_or_else(|| "the image memory budget is exhausted".to_string()))
+    }
+
+    fn animation_info(
+        &mut self,
+        animation: AnimationId,
+    ) -> wasmtime::Result<Option<WitAnimationMetadata>> {
+        self.charge_host_call("animation-info")?;
+        Ok(self
+            .animations
+            .get(animation)
+            .map(|animation| WitAnimationMetadata {
+                size: WitVec2 {
+                    x: animation.width(),
+                    y: animation.height(),
+                },
+                duration_ms: animation.duration_ms(),
+                frame_rate: animation.frame_rate(),
+            }))
+    }
+
+    fn drop_animation(&mut self, animation: AnimationId) -> wasmtime::Result<()> {
+        self.charge_host_call("drop-animation")?;
+        self.animations.remove(animation);
+        Ok(())
+    }
+
+    fn draw_animation(
+        &mut self,
+        animation: AnimationId,
+        time_ms: f64,
+        origin: WitVec2,
+        size: WitVec2,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-animation")?;
+        if self.phase.allows_draw() {
+            let origin = Vec2::from(origin);
+            let size = Vec2::from(size);
+            if !origin.is_finite() || !size.is_finite() || !time_ms.is_finite() {
+                self.dropped.invalid += 1;
+                return Ok(());
+            }
+            let Some(animation) = self.animations.get(animation).cloned() else {
+                self.dropped.unknown_image += 1;
+                return Ok(());
+            };
+            self.push_command(DrawCommand::DrawAnimation {
+                animation,
+                time_ms,
+                origin,
+                size,
+            });
+        } else {
+            self.warn_out_of_phase("draw an animation");
+        }
+        Ok(())
+    }
+
+    fn draw_image(
+        &mut self,
+        image: ImageId,
+        origin: WitVec2,
+        size: WitVec2,
+        style: WitImageStyle,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-image")?;
+        self.record_image(origin, size, ImageStyle::from_wit(style), |host| {
+            host.images.draw(image, host.draw_phases).cloned()
+        });
+        Ok(())
+    }
+
+    fn decode_image(
+        &mut self,
+        bytes: Vec<u8>,
+    ) -> wasmtime::Result<Result<AnimatedImageId, String>> {
+        self.charge_host_call("decode-image")?;
+        let budget = self.image_budget(self.animated_images.bytes());
+        let remaining = budget.saturating_sub(self.animated_images.bytes());
+        // Decoded frames are what reach the GPU, so they count against the upload rate, and
+        // the decoder stops as soon as they would exceed what is left of it.
+        let limit = self.limits.max_image_upload_bytes_per_second;
+        let allowance = self.uploads.remaining(Instant::now(), limit);
+        let image = match AnimatedImage::decode(&bytes, remaining.min(allowance)) {
+            Ok(image) => image,
+            Err(err) => return Ok(Err(format!("{err:#}"))),
+        };
+        if !self.charge_upload("decode-image", image.byte_len()) {
+            return Ok(Err("the image upload rate is exhausted".to_string()));
+        }
+        Ok(self
+            .animated_images
+            .insert(image, budget)
+            .ok_or_else(|| "the image memory budget is exhausted".to_string()))
+    }
+
+    fn animated_image_info(
+        &mut self,
+        image: AnimatedImageId,
+    ) -> wasmtime::Result<Option<WitAnimatedImageMetadata>> {
+        self.charge_host_call("animated-image-info")?;
+        Ok(self
+            .animated_images
+            .get(image)
+            .map(|image| WitAnimatedImageMetadata {
+                width: image.width(),
+                height: image.height(),
+                frame_durations_ms: image.frame_durations_ms(),
+                plays: image.plays(),
+            }))
+    }
+
+    fn drop_animated_image(&mut self, image: AnimatedImageId) -> wasmtime::Result<()> {
+        self.charge_host_call("drop-animated-image")?;
+        self.animated_images.remove(image);
+        Ok(())
+    }
+
+    fn draw_animated_image(
+        &mut self,
+        image: AnimatedImageId,
+        frame: u32,
+        origin: WitVec2,
+        size: WitVec2,
+        style: WitImageStyle,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-animated-image")?;
+        self.record_image(origin, size, ImageStyle::from_wit(style), |host| {
+            Some(host.animated_images.get(image)?.frame(frame).clone())
+        });
+        Ok(())
+    }
+
+    fn list_cameras(&mut self) -> wasmtime::Result<Vec<String>> {
+        self.charge_host_call("list-cameras")?;
+        if self.dropped_capabilities.contains(Capability::Camera) {
+            return Ok(Vec::new());
+        }
+        Ok(self.cameras.list())
+    }
+
+    fn open_camera(&mut self, device: u32) -> wasmtime::Result<Result<CameraId, String>> {
+        self.charge_host_call("open-camera")?;
+        if let Err(err) = self.require(Capability::Camera) {
+            return Ok(Err(err));
+        }
+        Ok(self.cameras.open(device))
+    }
+
+    fn camera_status(&mut self, camera: CameraId) -> wasmtime::Result<Option<WitCameraState>> {
+        self.charge_host_call("camera-status")?;
+        Ok(self.cameras.status(camera).map(|status| match status {
+            CameraStatus::Pending => WitCameraState::Pending,
+            CameraStatus::Streaming => WitCameraState::Streaming,
+            CameraStatus::Denied => WitCameraState::Denied,
+            CameraStatus::Failed => WitCameraState::Failed,
+        }))
+    }
+
+    fn camera_frame_info(
+        &mut self,
+        camera: CameraId,
+    ) -> wasmtime::Result<Option<WitCameraFrameMetadata>> {
+        self.charge_host_call("camera-frame-info")?;
+        Ok(self
+            .cameras
+            .frame(camera)
+            .map(|frame| WitCameraFrameMetadata {
+                width: frame.image.width(),
+                height: frame.image.height(),
+                sequence: frame.sequence,
+            }))
+    }
+
+    fn draw_camera(
+        &mut self,
+        camera: CameraId,
+        origin: WitVec2,
+        size: WitVec2,
+        style: WitImageStyle,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-camera")?;
+        if self.cameras.status(camera).is_some() && self.cameras.frame(camera).is_none() {
+            // An open camera without a frame yet draws nothing rather than counting as an
+            // unknown image.
+            return Ok(());
+        }
+        self.record_image(origin, size, ImageStyle::from_wit(style), |host| {
+            host.cameras.frame(camera).map(|frame| frame.image)
+        });
+        Ok(())
+    }
+
+    fn close_camera(&mut self, camera: CameraId) -> wasmtime::Result<()> {
+        self.charge_host_call("close-camera")?;
+        self.cameras.close(camera);
+        Ok(())
+    }
+
+    fn create_pixel_buffer(
+        &mut self,
+        width: u32,
+        height: u32,
+    ) -> wasmtime::Result<Option<PixelBufferId>> {
+        self.charge_host_call("create-pixel-buffer")?;
+        let Some(buffer) = PixelBuffer::new(width, height) else {
+            tracing::warn!(
+                target: GUEST_TARGET,
+                width,
+                height,
+                "create-pixel-buffer called with an unsupported size"
+            );
+            return Ok(None);
+        };
+        let id = self
+            .pixel_buffers
+            .insert(buffer, self.image_budget(self.pixel_buffers.bytes()));
+        if id.is_none() {
+            tracing::warn!(
+                target: GUEST_TARGET,
+                held_bytes = self.asset_bytes(),
+                limit_bytes = self.limits.max_image_bytes,
+                "create-pixel-buffer exceeded the image memory budget"
+            );
+        }
+        Ok(id)
+    }
+
+    fn write_pixel_buffer(
+        &mut self,
+        buffer: PixelBufferId,
+        x: u32,
+        y: u32,
+        width: u32,
+        rgba: Vec<u8>,
+    ) -> wasmtime::Result<bool> {
+        self.charge_host_call("write-pixel-buffer")?;
+        if !self.charge_upload("write-pixel-buffer", rgba.len()) {
+            return Ok(false);
+        }
+        Ok(self
+            .pixel_buffers
+            .get_mut(buffer)
+            .is_some_and(|buffer| buffer.write(x, y, width, &rgba)))
+    }
+
+    fn present_pixel_buffer(
+        &mut self,
+        buffer: PixelBufferId,
+        origin: WitVec2,
+        size: WitVec2,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("present-pixel-buffer")?;
+        self.record_image(origin, size, ImageStyle::default(), |host| {
+            host.pixel_buffers.get_mut(buffer).map(PixelBuffer::image)
+        });
+        Ok(())
+    }
+
+    fn drop_pixel_buffer(&mut self, buffer: PixelBufferId) -> wasmtime::Result<()> {
+        self.charge_host_call("drop-pixel-buffer")?;
+        self.pixel_buffers.remove(buffer);
+        Ok(())
+    }
+
+    fn create_emitter(&mut self, config: WitEmitterConfig) -> wasmtime::Result<Option<EmitterId>> {
+        self.charge_host_call("create-emitter")?;
+        let Some(emitter) = Emitter::new(EmitterConfig::from_wit(config)) else {
+            tracing::warn!(
+                target: GUEST_TARGET,
+                "create-emitter called with invalid settings"
+            );
+            return Ok(None);
+        };
+        let id = self
+            .emitters
+            .insert(emitter, self.image_budget(self.emitters.bytes()));
+        if id.is_none() {
+            tracing::warn!(
+                target: GUEST_TARGET,
+                held_bytes = self.asset_bytes(),
+                limit_bytes = self.limits.max_image_bytes,
+                "create-emitter exceeded the image memory budget"
+            );
+        }
+        Ok(id)
+    }
+
+    fn configure_emitter(
+        &mut self,
+        emitter: EmitterId,
+        config: WitEmitterConfig,
+    ) -> wasmtime::Result<bool> {
+        self.charge_host_call("configure-emitter")?;
+        Ok(self
+            .emitters
+            .get_mut(emitter)
+            .is_some_and(|emitter| emitter.configure(EmitterConfig::from_wit(config))))
+    }
+
+    fn burst_emitter(&mut self, emitter: EmitterId, count: u32) -> wasmtime::Result<()> {
+        self.charge_host_call("burst-emitter")?;
+        if let Some(emitter) = self.emitters.get_mut(emitter) {
+            emitter.burst(count);
+        }
+        Ok(())
+    }
+
+    fn emitter_particle_count(&mut self, emitter: EmitterId) -> wasmtime::Result<Option<u32>> {
+        self.charge_host_call("emitter-particle-count")?;
+        Ok(self
+            .emitters
+            .get(emitter)
+            .map(|emitter| emitter.len() as u32))
+    }
+
+    fn draw_emitter(&mut self, emitter: EmitterId) -> wasmtime::Result<()> {
+        self.charge_host_call("draw-emitter")?;
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("draw particles");
+            return Ok(());
+        }
+        let Some(emitter) = self.emitters.get_mut(emitter) else {
+            self.dropped.unknown_image += 1;
+            return Ok(());
+        };
+        if !emitter.is_empty() {
+            let particles = emitter.batch();
+            self.push_command(DrawCommand::DrawEmitter { particles });
+        }
+        Ok(())
+    }
+
+    fn drop_emitter(&mut self, emitter: EmitterId) -> wasmtime::Result<()> {
+        self.charge_host_call("drop-emitter")?;
+        self.emitters.remove(emitter);
+        Ok(())
+    }
+
+    fn register_shader(&mut self, source: String) -> wasmtime::Result<Result<ShaderId, String>> {
+        self.charge_host_call("register-shader")?;
+        let shader = match Shader::parse(&source) {
+            Ok(shader) => shader,
+            Err(err) => return Ok(Err(format!("{err:#}"))),
+        };
+        Ok(self
+            .shaders
+            .insert(shader, self.image_budget(self.shaders.bytes()))
+            .ok_or_else(|| "the image memory budget is exhausted".to_string()))
+    }
+
+    fn drop_shader(&mut self, shader: ShaderId) -> wasmtime::Result<()> {
+        self.charge_host_call("drop-shader")?;
+        self.shaders.remove(shader);
+        Ok(())
+    }
+
+    fn push_shader_layer(&mut self, shader: ShaderId, params: Vec<f32>) -> wasmtime::Result<()> {
+        self.charge_host_call("push-shader-layer")?;
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("push a shader layer");
+            return Ok(());
+        }
+        if !params.iter().all(|param| param.is_finite()) {
+            self.dropped.invalid += 1;
+            self.shader_layers.push(false);
+            return Ok(());
+        }
+        let Some(shader) = self.shaders.draw(shader, self.draw_phases).cloned() else {
+            self.dropped.unknown_image += 1;
+            self.shader_layers.push(false);
+            return Ok(());
+        };
+        let mut padded = [0.0; PARAM_COUNT];
+        for (slot, param) in padded.iter_mut().zip(params) {
+            *slot = param;
+        }
+        self.shader_layers.push(true);
+        self.push_command(DrawCommand::PushShaderLayer {
+            shader,
+            params: padded,
+        });
+        Ok(())
+    }
+
+    fn pop_shader_layer(&mut self) -> wasmtime::Result<()> {
+        self.charge_host_call("pop-shader-layer")?;
+        if !self.phase.allows_draw() {
+            self.warn_out_of_phase("pop a shader layer");
+            return Ok(());
+        }
+        match self.shader_layers.pop() {
+            Some(true) => self.push_command(DrawCommand::PopShaderLayer),
+            Some(false) => {}
+            None => self.dropped.invalid += 1,
+        }
+        Ok(())
+    }
+
+    fn fill_pattern(
+        &mut self,
+        origin: WitVec2,
+        size: WitVec2,
+        image: ImageId,
+        tile_size: WitVec2,
+        repeat: WitPatternRepeat,
+        style: WitImageStyle,
+    ) -> wasmtime::Result<()> {
+        self.charge_host_call("fill-pattern")?;
+        if self.phase.allows_draw() {
+            let origin = Vec2::from(origin);
+            let size = Vec2::from(size);
+            let tile_size = Vec2::from(tile_size);
+            if !origin.is_finite()
+                || !size.is_finite()
+                || !tile_size.is_finite()
+                || tile_size.x <= 0.0
+                || tile_size.y <= 0.0
+            {
+                self.dropped.invalid += 1;
+                return Ok(());
+            }
+            let Some(image) = self.images.draw(image, self.draw_phases).cloned() else {
+                self.dropped.unknown_image += 1;
+                return Ok(());
+            };
+            self.push_command(DrawCommand::FillPattern {
+                origin,
+                size,
+                image,
+                tile_size,
+                repeat: PatternRepeat::from_wit(repeat),
+                style: ImageStyle::from_wit(style),
+            });
+        } else {
+            self.warn_out_of_phase("issue fill-pattern");
+        }
+        Ok(())
+    }
+
+    fn export_frame(&mut self, format: WitExportFormat) -> wasmtime::Result<()> {
+        self.charge_host_call("export-frame")?;
+        if self.dropped_capabilities.contains(Capability::ExportFrame) {
+            tracing::warn!("export-frame ignored; the capability was dropped");
+            return Ok(());
+        }
+        if self.phase != Phase::Frame {
+            self.warn_out_of_phase("export a frame");
+            return Ok(());
+        }
+        let format = ExportFormat::from_wit(format);
+        if !self.export_requests.contains(&format) {
+            self.export_requests.push(format);
+        }
+        Ok(())
+    }
+
+    fn request_frame(&mut self) -> wasmtime::Result<()> {
+        self.charge_host_call("request-frame")?;
+        if self.phase.allows_request_frame() {
+            self.redraw_requested = true;
+        } else {
+            tracing::debug!(phase = ?self.phase, "guest requested frame while idle; ignoring");
+        }
+        Ok(())
+    }
+
+    fn frame_elapsed_ms(&mut self) -> wasmtime::Result<f64> {
+        self.charge_host_call("frame-elapsed-ms")?;
+        Ok(self.frame_elapsed_ms)
+    }
+
+    fn animate(
+        &mut self,
+        id: u32,
+        from: f32,
+        to: f32,
+        duration_ms: f32,
+        easing: WitEasing,
+    ) -> wasmtime::Result<bool> {
+        self.charge_host_call("animate")?;
+        let started = self
+            .tweens
+            .start(id, from, to, duration_ms, Easing::from_wit(easing));
+        if started {
+            self.redraw_requested = true;
+        } else {
+            tracing::warn!(
+                target: GUEST_TARGET,
+                id,
+                "animate called with invalid values or too many tweens"
+            );
+        }
+        Ok(started)
+    }
+
+    fn spring(
+        &mut self,
+        id: u32,
+        target: f32,
+        stiffness: f32,
+        damping: f32,
+    ) -> wasmtime::Result<bool> {
+        self.charge_host_call("spring")?;
+        let started = self.tweens.spring(id, target, stiffness, damping);
+        if started {
+            self.redraw_requested = true;
+        } else {
+            tracing::warn!(
+                target: GUEST_TARGET,
+                id,
+                "spring called with invalid values or too many tweens"
+            );
+        }
+        Ok(started)
+    }
+
+    fn animated_value(&mut self, id: u32) -> wasmtime::Result<Option<f32>> {
+        self.charge_host_call("animated-value")?;
+        Ok(self.tweens.value(id))
+    }
+
+    fn stop_animating(&mut self, id: u32) -> wasmtime::Result<Option<f32>> {
+        self.charge_host_call("stop-animating")?;
+        Ok(self.tweens.stop(id))
+    }
+
+    fn log(&mut self, level: LogLevel, message: String) -> wasmtime::Result<()> {
+        self.charge_host_call("log")?;
+        self.record_guest_log(level, &message);
+        match level {
+            LogLevel::Trace => tracing::trace!(target: GUEST_TARGET, "{message}"),
+            LogLevel::Debug => tracing::debug!(target: GUEST_TARGET, "{message}"),
+            LogLevel::Info => tracing::info!(target: GUEST_TARGET, "{message}"),
+            LogLevel::Warn => tracing::warn!(target: GUEST_TARGET, "{message}"),
+            LogLevel::Error => tracing::error!(target: GUEST_TARGET, "{message}"),
+        }
+        Ok(())
+    }
+
+    fn set_window_level(&mut self, level: WitWindowLevel) -> wasmtime::Result<()> {
+        self.charge_host_call("set-window-level")?;
+        let level = match level {
+            WitWindowLevel::Normal => WindowLevel::Normal,
+            WitWindowLevel::AlwaysOnTop => WindowLevel::AlwaysOnTop,
+            WitWindowLevel::AlwaysOnBottom => WindowLevel::AlwaysOnBottom,
+        };
+        self.window_requests.push(WindowRequest::SetLevel(level));
+        Ok(())
+    }
+
+    fn set_window_opacity(&mut self, opacity: f32) -> wasmtime::Result<()> {
+        self.charge_host_call("set-window-opacity")?;
+        self.window_requests
+            .push(WindowRequest::SetOpacity(clamp_opacity(opacity)));
+        Ok(())
+    }
+
+    fn set_background_color(&mut self, color: WitColor) -> wasmtime::Result<()> {
+        self.charge_host_call("set-background-color")?;
+        self.window_requests
+            .push(WindowRequest::SetBackground(Color::from_wit(color)));
+        Ok(())
+    }
+
+    fn set_click_through(&mut self, enabled: bool) -> wasmtime::Result<()> {
+        self.charge_host_call("set-click-through")?;
+        self.window_requests
+            .push(WindowRequest::SetClickThrough(enabled));
+        Ok(())
+    }
+
+    fn set_window_position(&mut self, position: WitWindowPosition) -> wasmtime::Result<()> {
+        self.charge_host_call("set-window-position")?;
+        self.window_requests
+            .push(WindowRequest::SetPosition(PhysicalPosition::new(
+                position.x, position.y,
+            )));
+        Ok(())
+    }
+
+    fn get_window_position(&mut self) -> wasmtime::Result<Option<WitWindowPosition>> {
+        self.charge_host_call("get-window-position")?;
+        Ok(self.window_position.map(to_wit_window_position))
+    }
+
+    fn request_quit(&mut self) -> wasmtime::Result<()> {
+        self.charge_host_call("request-quit")?;
+        self.quit_requested = true;
+        Ok(())
+    }
+
+    fn navigate(&mut self, target: String) -> wasmtime::Result<Result<(), String>> {
+        self.charge_host_call("navigate")?;
+        if let Err(err) = self.require(Capability::Navigation) {
+            return Ok(Err(err));
+        }
+        if !self.navigation_allowed {
+            return Ok(Err(
+                "navigation is disabled; start the host with --allow-navigation".into(),
+            ));
+        }
+        match navigation::resolve(&target, self.navigation_base.as_deref()) {
+            Ok(path) => {
+                tracing::info!(path = %path.display(), "guest is navigating");
+                self.navigate_to = Some(path);
+                Ok(Ok(()))
+            }
+            Err(err) => Ok(Err(format!("{err:#}"))),
+        }
+    }
+
+    fn get_launch_args(&mut self) -> wasmtime::Result<Vec<String>> {
+        self.charge_host_call("get-launch-args")?;
+        Ok(self.launch_args.clone())
+    }
+
+    fn host_capabilities(&mut self) -> wasmtime::Result<Vec<String>> {
+        self.charge_host_call("host-capabilities")?;
+        Ok(self.capabilities())
+    }
+
+    fn drop_capability(&mut self, capability: String) -> wasmtime::Result<Result<(), String>> {
+        self.charge_host_call("drop-capability")?;
+        let capability = match Capability::parse(&capability) {
+            Ok(capability) => capability,
+            Err(err) => return Ok(Err(format!("{err:#}"))),
+        };
+        if !self.dropped_capabilities.insert(capability) {
+            return Ok(Ok(()));
+        }
+        tracing::info!(capability = capability.name(), "guest dropped a capability");
+        // Release what the capability was holding; the checks above keep it released.
+        match capability {
+            Capability::Camera => self.cameras.close_all(),
+            Capability::Storage => {
+                self.storage = None;
+                self.database = Database::default();
+            }
+            _ => {}
+        }
+        Ok(Ok(()))
+    }
+
+    fn host_info(&mut self) -> wasmtime::Result<WitHostEnvironment> {
+        self.charge_host_call("host-info")?;
+        Ok(WitHostEnvironment {
+            host_version: env!("CARGO_PKG_VERSION").to_string(),
+            vello_version: env!("VELLO_VERSION").to_string(),
+            gpu_name: self.gpu.as_ref().map(|gpu| gpu.name.clone()),
+            backend: self.gpu.as_ref().map(|gpu| gpu.backend.clone()),
+            os: std::env::consts::OS.to_string(),
+        })
+    }
+
+    fn get_locale(&mut self) -> wasmtime::Result<String> {
+        self.charge_host_call("get-locale")?;
+        Ok(self.locale.to_string())
+    }
+
+    fn format_number(&mut self, value: f64, fraction_digits: u8) -> wasmtime::Result<String> {
+        self.charge_host_call("format-number")?;
+        Ok(self.locale.format_number(value, fraction_digits))
+    }
+
+    fn format_currency(
+        &mut self,
+        value: f64,
+        currency: String,
+    ) -> wasmtime::Result<Result<String, String>> {
+        self.charge_host_call("format-currency")?;
+        Ok(self
+            .locale
+            .format_currency(value, &currency)
+            .map_err(|err| format!("{err:#}")))
+    }
+
+    fn format_datetime(
+        &mut self,
+        unix_ms: i64,
+        style: WitDatetimeStyle,
+    ) -> wasmtime::Result<Option<String>> {
+        self.charge_host_call("format-datetime")?;
+        let Some(time) = DateTime::from_timestamp_millis(unix_ms) else {
+            return Ok(None);
+        };
+        let local = time.with_timezone(&Local).naive_local();
+        Ok(Some(
+            self.locale
+                .format_datetime(local, DateTimeStyle::from_wit(style)),
+        ))
+    }
+
+    fn local_timezone(&mut self) -> wasmtime::Result<Option<String>> {
+        self.charge_host_call("local-timezone")?;
+        Ok(timezone::local_timezone())
+    }
+
+    fn get_preferences(&mut self) -> wasmtime::Result<WitPreferences> {
+        self.charge_host_call("get-preferences")?;
+        Ok(self.preferences.to_wit())
+    }
+
+    fn utc_offset_at(&mut self, unix_ms: i64) -> wasmtime::Result<Option<i32>> {
+        self.charge_host_call("utc-offset-at")?;
+        Ok(timezone::utc_offset_at(unix_ms))
+    }
+
+    fn power_state(&mut self) -> wasmtime::Result<Option<WitPowerStatus>> {
+        self.charge_host_call("power-state")?;
+        if self.dropped_capabilities.contains(Capability::PowerState) {
+            return Ok(None);
+        }
+        Ok(self.power.as_ref().map(to_wit_power_status))
+    }
+
+    fn haptic_feedback(&mut self, kind: WitHapticKind) -> wasmtime::Result<bool> {
+        self.charge_host_call("haptic-feedback")?;
+        if self.dropped_capabilities.contains(Capability::Haptics) {
+            return Ok(false);
+        }
+        Ok(self.haptics.play(HapticKind::from_wit(kind)))
+    }
+
+    fn storage_get(
+        &mut self,
+        scope: WitStorageScope,
+        key: String,
+    ) -> wasmtime::Result<Result<Option<Vec<u8>>, String>> {
+        self.charge_host_call("storage-get")?;
+        Ok(self.with_storage(|storage| storage.get(&from_wit_storage_scope(scope), &key)))
+    }
+
+    fn storage_set(
+        &mut self,
+        scope: WitStorageScope,
+        key: String,
+        value: Vec<u8>,
+    ) -> wasmtime::Result<Result<(), String>> {
+        self.charge_host_call("storage-set")?;
+        Ok(self.with_storage(|storage| storage.set(&from_wit_storage_scope(scope), &key, value)))
+    }
+
+    fn storage_delete(
+        &mut self,
+        scope: WitStorageScope,
+        key: String,
+    ) -> wasmtime::Result<Result<(), String>> {
+        self.charge_host_call("storage-delete")?;
+        Ok(self.with_storage(|storage| storage.delete(&from_wit_storage_scope(scope), &key)))
+    }
+
+    fn spawn_task(
+        &mut self,
+        name: String,
+        input: Vec<u8>,
+    ) -> wasmtime::Result<Result<TaskId, String>> {
+        self.charge_host_call("spawn-task")?;
+        if self.is_secondary() {
+            return Ok(Err("only the main instance can spawn tasks".into()));
+        }
+        Ok(self
+            .tasks
+            .spawn(name, input)
+            .map_err(|err| format!("{err:#}")))
+    }
+
+    fn report_task_progress(&mut self, progress: f32) -> wasmtime::Result<()> {
+        self.charge_host_call("report-task-progress")?;
+        match &self.task_reporter {
+            Some(reporter) => reporter.progress(progress),
+            None => Ok(()),
+        }
+    }
+
+    fn spawn_worker(
+        &mut self,
+        source: WitWorkerSource,
+        permissions: WitWorkerPermissions,
+    ) -> wasmtime::Result<Result<WorkerId, String>> {
+        self.charge_host_call("spawn-worker")?;
+        if self.is_secondary() {
+            return Ok(Err("only the main instance can spawn workers".into()));
+        }
+        let source = match source {
+            WitWorkerSource::Bytes(bytes) => ComponentSource::Embedded {
+                bytes: bytes.into(),
+                label: "worker",
+            },
+            WitWorkerSource::Url(url) => {
+                match navigation::resolve_within(&url, self.navigation_base.as_deref()) {
+                    Ok(path) => ComponentSource::Path(path),
+                    Err(err) => return Ok(Err(format!("{err:#}"))),
+                }
+            }
+        };
+        Ok(self
+            .workers
+            .spawn(source, from_wit_worker_permissions(permissions))
+            .map_err(|err| format!("{err:#}")))
+    }
+
+    fn post_to_worker(
+        &mut self,
+        worker: WorkerId,
+        message: Vec<u8>,
+    ) -> wasmtime::Result<Result<(), String>> {
+        self.charge_host_call("post-to-worker")?;
+        Ok(self
+            .workers
+            .post(worker, message)
+            .map_err(|err| format!("{err:#}")))
+    }
+
+    fn terminate_worker(&mut self, worker: WorkerId) -> wasmtime::Result<bool> {
+        self.charge_host_call("terminate-worker")?;
+        Ok(self.workers.terminate(worker))
+    }
+
+    fn seconds_since_input(&mut self) -> wasmtime::Result<f64> {
+        self.charge_host_call("seconds-since-input")?;
+        Ok(self.last_input.map_or(0.0, |at| at.elapsed().as_secs_f64()))
+    }
+
+    fn get_display_info(&mut self) -> wasmtime::Result<Option<WitDisplayInfo>> {
+        self.charge_host_call("get-display-info")?;
+        Ok(self.display.as_ref().map(to_wit_display_info))
+    }
+
+    fn perf_mark(&mut self, name: String) -> wasmtime::Result<()> {
+        self.charge_host_call("perf-mark")?;
+        if self.perf_marks.len() >= PERF_MARK_LIMIT && !self.perf_marks.contains_key(&name) {
+            tracing::debug!(%name, "perf mark limit reached; ignoring mark");
+            return Ok(());
+        }
+        tracing::trace!(target: PERF_TARGET, %name, "perf-mark");
+        self.perf_marks.insert(name, Instant::now());
+        Ok(())
+    }
+
+    fn perf_measure(&mut self, name: String, start_mark: String) -> wasmtime::Result<()> {
+        self.charge_host_call("perf-measure")?;
+        let Some(start) = self.perf_marks.get(&start_mark) else {
+            tracing::warn!(%name, %start_mark, "perf-measure references an unknown mark");
+            return Ok(());
+        };
+        let duration_ms = start.elapsed().as_secs_f32() * 1000.0;
+        tracing::debug!(target: PERF_TARGET, %name, %start_mark, duration_ms, "perf-measure");
+        if self.perf_measures.len() < PERF_MEASURE_LIMIT {
+            self.perf_measures.push(PerfMeasure { name, duration_ms });
+        }
+        Ok(())
+    }
+}
+
+impl WorkerHost for HostCtx {
+    fn post_message(&mut self, message: Vec<u8>) -> wasmtime::Result<Result<(), String>> {
+        self.charge_host_call("post-message")?;
+        let Some(outbox) = &self.worker_outbox else {
+            return Ok(Err("only workers can post messages".into()));
+        };
+        Ok(outbox.post(message).map_err(|err| format!("{err:#}")))
+    }
+}
+
+impl DatabaseHost for HostCtx {
+    fn prepare(&mut self, sql: String) -> wasmtime::Result<Result<StatementId, String>> {
+        self.charge_host_call("prepare")?;
+        if let Err(err) = self.require(Capability::Storage) {
+            return Ok(Err(err));
+        }
+        Ok(self
+            .database
+            .prepare(&sql)
+            .map_err(|err| format!("{err:#}")))
+    }
+
+    fn execute(
+        &mut self,
+        statement: StatementId,
+        params: Vec<WitSqlValue>,
+    ) -> wasmtime::Result<Result<u64, String>> {
+        self.charge_host_call("execute")?;
+        if let Err(err) = self.require(Capability::Storage) {
+            return Ok(Err(err));
+        }
+        let params: Vec<SqlValue> = params.into_iter().map(from_wit_sql_value).collect();
+        Ok(self
+            .database
+            .execute(statement, &params)
+            .map_err(|err| format!("{err:#}")))
+    }
+
+    fn query(
+        &mut self,
+        statement: StatementId,
+        params: Vec<WitSqlValue>,
+    ) -> wasmtime::Result<Result<Vec<Vec<WitSqlValue>>, String>> {
+        self.charge_host_call("query")?;
+        if let Err(err) = self.require(Capability::Storage) {
+            return Ok(Err(err));
+        }
+        let params: Vec<SqlValue> = params.into_iter().map(from_wit_sql_value).collect();
+        Ok(self
+            .database
+            .query(statement, &params)
+            .map(|rows| {
+                rows.into_iter()
+                    .map(|row| row.into_iter().map(to_wit_sql_value).collect())
+                    .collect()
+            })
+            .map_err(|err| format!("{err:#}")))
+    }
+
+    fn finalize(&mut self, statement: StatementId) -> wasmtime::Result<bool> {
+        self.charge_host_call("finalize")?;
+        Ok(self.database.finalize(statement))
+    }
+}
+
+fn from_wit_sql_value(value: WitSqlValue) -> SqlValue {
+    match value {
+        WitSqlValue::Null => SqlValue::Null,
+        WitSqlValue::Integer(value) => SqlValue::Integer(value),
+        WitSqlValue::Real(value) => SqlValue::Real(value),
+        WitSqlValue::Text(value) => SqlValue::Text(value),
+        WitSqlValue::Blob(value) => SqlValue::Blob(value),
+    }
+}
+
+fn to_wit_sql_value(value: SqlValue) -> WitSqlValue {
+    match value {
+        SqlValue::Null => WitSqlValue::Null,
+        SqlValue::Integer(value) => WitSqlValue::Integer(value),
+        SqlValue::Real(value) => WitSqlValue::Real(value),
+        SqlValue::Text(value) => WitSqlValue::Text(value),
+        SqlValue::Blob(value) => WitSqlValue::Blob(value),
+    }
+}
+
+fn from_wit_worker_permissions(permissions: WitWorkerPermissions) -> HostInterfaces {
+    HostInterfaces {
+        wasi: permissions.contains(WitWorkerPermissions::WASI),
+        canvas: permissions.contains(WitWorkerPermissions::CANVAS),
+        database: permissions.contains(WitWorkerPermissions::DATABASE),
+    }
+}
+
+fn from_wit_storage_scope(scope: WitStorageScope) -> Scope {
+    match scope {
+        WitStorageScope::Private => Scope::Private,
+        WitStorageScope::Shared(owner) => Scope::Shared(owner),
+    }
+}
+
+pub(crate) fn to_wit_display_info(info: &DisplayInfo) -> WitDisplayInfo {
+    WitDisplayInfo {
+        name: info.name.clone(),
+        width: info.width,
+        height: info.height,
+        scale_factor: info.scale_factor,
+        refresh_rate_millihertz: info.refresh_rate_millihertz,
+    }
+}
+
+pub(crate) fn to_wit_power_status(state: &PowerState) -> WitPowerStatus {
+    WitPowerStatus {
+        on_battery: state.on_battery,
+        battery_percent: state.battery_percent,
+        power_saver: state.power_saver,
+    }
+}
+
+pub(crate) fn to_wit_window_position(position: PhysicalPosition<i32>) -> WitWindowPosition {
+    WitWindowPosition {
+        x: position.x,
+        y: position.y,
+    }
+}
+
+impl fmt::Display for DrawCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             DrawCommand::FillRect { origin, size, .. } => {
                 write!(
@@ -214,14 +2836,1109 @@ impl fmt::Display for DrawCommand {
                 )
             }
             DrawCommand::DrawText {
-                text, origin, size, ..
+                text,
+                origin,
+                size,
+                font,
+                flow,
+                ..
             } => {
                 write!(
                     f,
-                    "DrawText(text='{text}', origin=({:.1}, {:.1}), size={:.1})",
+                    "DrawText(text='{text}', origin=({:.1}, {:.1}), size={:.1}",
                     origin.x, origin.y, size
+                )?;
+                if *font != FontFamily::Sans {
+                    write!(f, ", font={font:?}")?;
+                }
+                if flow.direction != TextDirection::Ltr {
+                    write!(f, ", direction={:?}", flow.direction)?;
+                }
+                if flow.mode != WritingMode::HorizontalTb {
+                    write!(f, ", mode={:?}", flow.mode)?;
+                }
+                if flow.rotation != 0.0 {
+                    write!(f, ", rotation={:.2}", flow.rotation)?;
+                }
+                f.write_str(")")
+            }
+            DrawCommand::DrawPicture {
+                picture,
+                origin,
+                size,
+            } => {
+                write!(
+                    f,
+                    "DrawPicture(picture={}x{}, origin=({:.1}, {:.1}), size=({:.1}, {:.1}))",
+                    picture.width(),
+                    picture.height(),
+                    origin.x,
+                    origin.y,
+                    size.x,
+                    size.y
+                )
+            }
+            DrawCommand::DrawAnimation {
+                animation,
+                time_ms,
+                origin,
+                size,
+            } => {
+                write!(
+                    f,
+                    "DrawAnimation(animation={}x{}, time={:.0}ms, origin=({:.1}, {:.1}), size=({:.1}, {:.1}))",
+                    animation.width(),
+                    animation.height(),
+                    time_ms,
+                    origin.x,
+                    origin.y,
+                    size.x,
+                    size.y
+                )
+            }
+            DrawCommand::DrawImage {
+                image,
+                origin,
+                size,
+                ..
+            } => {
+                write!(
+                    f,
+                    "DrawImage(image={}x{}, origin=({:.1}, {:.1}), size=({:.1}, {:.1}))",
+                    image.width(),
+                    image.height(),
+                    origin.x,
+                    origin.y,
+                    size.x,
+                    size.y
+                )
+            }
+            DrawCommand::FillPattern {
+                origin,
+                size,
+                image,
+                tile_size,
+                repeat,
+                ..
+            } => {
+                write!(
+                    f,
+                    "FillPattern(origin=({:.1}, {:.1}), size=({:.1}, {:.1}), image={}x{}, tile=({:.1}, {:.1}), repeat={repeat:?})",
+                    origin.x,
+                    origin.y,
+                    size.x,
+                    size.y,
+                    image.width(),
+                    image.height(),
+                    tile_size.x,
+                    tile_size.y
+                )
+            }
+            DrawCommand::PushShaderLayer { shader, params } => {
+                write!(
+                    f,
+                    "PushShaderLayer(shader={} bytes, params={params:?})",
+                    shader.byte_len()
                 )
             }
+            DrawCommand::PopShaderLayer => f.write_str("PopShaderLayer"),
+            DrawCommand::DrawParticles { particles } => {
+                write!(f, "DrawParticles(count={})", particles.len())
+            }
+            DrawCommand::DrawEmitter { particles } => {
+                write!(f, "DrawEmitter(count={})", particles.len())
+            }
+            DrawCommand::PushClip { origin, size } => {
+                write!(
+                    f,
+                    "PushClip(origin=({:.1}, {:.1}), size=({:.1}, {:.1}))",
+                    origin.x, origin.y, size.x, size.y
+                )
+            }
+            DrawCommand::PopClip => f.write_str("PopClip"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Color, DrawCommand, FontFamily, FrameOutput, GuestHost, HostCtx, PatternRepeat, Phase,
+        Rect, ScrollEvent, ScrollInput, TextFlow, Vec2, WindowRequest, WitEasing, WitEmitterConfig,
+        WitFontFamily, WitImageStyle, WitPatternRepeat, WitRect, WitWindowPosition,
+        OUT_OF_PHASE_REPORT_INTERVAL,
+    };
+    use crate::camera::CameraAccess;
+    use crate::capabilities::Capability;
+    use crate::component::vello::canvas::host::{
+        ExportFormat as WitExportFormat, ImageFilter as WitImageFilter,
+        StorageScope as WitStorageScope, WorkerPermissions as WitWorkerPermissions,
+        WorkerSource as WitWorkerSource,
+    };
+    use crate::component::vello::canvas::math::{Color as WitColor, Vec2 as WitVec2};
+    use crate::export::ExportFormat;
+    use crate::gpu::GpuInfo;
+    use crate::images::ImageFilter;
+    use crate::limits::ResourceLimits;
+    use crate::window::DisplayInfo;
+    use std::time::{Duration, Instant};
+    use winit::dpi::PhysicalPosition;
+
+    #[test]
+    fn queues_window_moves_and_reports_position() {
+        let mut host = HostCtx::new();
+        assert!(host.get_window_position().unwrap().is_none());
+        host.set_window_position(WitWindowPosition { x: -40, y: 120 })
+            .unwrap();
+        assert_eq!(
+            host.take_window_requests(),
+            vec![WindowRequest::SetPosition(PhysicalPosition::new(-40, 120))]
+        );
+        host.record_window_position(Some(PhysicalPosition::new(10, 20)));
+        let position = host.get_window_position().unwrap().expect("position");
+        assert_eq!((position.x, position.y), (10, 20));
+    }
+
+    #[test]
+    fn reports_capabilities_that_depend_on_the_session() {
+        let mut host = HostCtx::new();
+        let capabilities = host.host_capabilities().unwrap();
+        assert!(capabilities.iter().any(|name| name == "images"));
+        assert!(!capabilities.iter().any(|name| name == "navigation"));
+
+        host.set_navigation(true, None);
+        host.set_camera_access(CameraAccess::Deny);
+        let capabilities = host.host_capabilities().unwrap();
+        assert!(capabilities.iter().any(|name| name == "navigation"));
+        assert!(!capabilities.iter().any(|name| name == "camera"));
+    }
+
+    #[test]
+    fn reports_gpu_in_host_info_once_known() {
+        let mut host = HostCtx::new();
+        let info = host.host_info().unwrap();
+        assert_eq!(info.host_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.os, std::env::consts::OS);
+        assert!(info.gpu_name.is_none());
+
+        host.set_gpu_info(Some(GpuInfo {
+            name: "Test Adapter".into(),
+            backend: "vulkan".into(),
+        }));
+        let info = host.host_info().unwrap();
+        assert_eq!(info.gpu_name.as_deref(), Some("Test Adapter"));
+        assert_eq!(info.backend.as_deref(), Some("vulkan"));
+    }
+
+    #[test]
+    fn storage_fails_until_the_host_provides_it() {
+        let mut host = HostCtx::new();
+        assert!(!host.capabilities().iter().any(|name| name == "storage"));
+        let result = host
+            .storage_get(WitStorageScope::Private, "key".into())
+            .unwrap();
+        assert!(result.unwrap_err().contains("not available"));
+    }
+
+    #[test]
+    fn dropped_capabilities_stay_dropped() {
+        let mut host = HostCtx::new();
+        host.set_navigation(true, None);
+        assert!(host.drop_capability("navigation".into()).unwrap().is_ok());
+        assert!(host.drop_capability("navigation".into()).unwrap().is_ok());
+        assert!(host.drop_capability("images".into()).unwrap().is_err());
+
+        // The runtime re-applying its settings does not hand the capability back.
+        host.set_navigation(true, None);
+        assert!(!host.capabilities().iter().any(|name| name == "navigation"));
+        let result = host.navigate("next.wasm".into()).unwrap();
+        assert!(result.unwrap_err().contains("dropped"));
+
+        let mut task = HostCtx::new();
+        task.inherit_dropped_capabilities(host.dropped_capabilities());
+        assert!(task.dropped_capabilities().contains(Capability::Navigation));
+    }
+
+    #[test]
+    fn spawned_workers_get_only_the_permissions_asked_for() {
+        let mut host = HostCtx::new();
+        let missing = host
+            .spawn_worker(
+                WitWorkerSource::Url("https://example.com/plugin.wasm".into()),
+                WitWorkerPermissions::all(),
+            )
+            .unwrap();
+        assert!(missing.unwrap_err().contains("not supported"));
+
+        let worker = host
+            .spawn_worker(
+                WitWorkerSource::Bytes(b"plugin".to_vec()),
+                WitWorkerPermissions::DATABASE,
+            )
+            .unwrap()
+            .unwrap();
+        let requests = host.take_worker_requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].interfaces.database && !requests[0].interfaces.wasi);
+        assert!(host.post_to_worker(worker, vec![1]).unwrap().is_ok());
+        assert!(host.terminate_worker(worker).unwrap());
+        assert!(host.post_to_worker(worker, vec![1]).unwrap().is_err());
+    }
+
+    #[test]
+    fn counts_seconds_since_the_last_input() {
+        let mut host = HostCtx::new();
+        assert_eq!(host.seconds_since_input().unwrap(), 0.0);
+        host.set_last_input(Instant::now() - Duration::from_secs(90));
+        let seconds = host.seconds_since_input().unwrap();
+        assert!((90.0..91.0).contains(&seconds), "{seconds}");
+    }
+
+    #[test]
+    fn queues_clamped_background_colors() {
+        let mut host = HostCtx::new();
+        host.set_background_color(WitColor {
+            r: 2.0,
+            g: f32::NAN,
+            b: 0.5,
+            a: 1.0,
+        })
+        .unwrap();
+        assert_eq!(
+            host.take_window_requests(),
+            vec![WindowRequest::SetBackground(Color {
+                r: 1.0,
+                g: 0.0,
+                b: 0.5,
+                a: 1.0,
+            })]
+        );
+    }
+
+    #[test]
+    fn reports_display_info_once_known() {
+        let mut host = HostCtx::new();
+        assert!(host.get_display_info().unwrap().is_none());
+        host.set_display(Some(DisplayInfo {
+            name: Some("Built-in".into()),
+            width: 2560,
+            height: 1600,
+            scale_factor: 2.0,
+            refresh_rate_millihertz: Some(120_000),
+        }));
+        let info = host.get_display_info().unwrap().expect("display info");
+        assert_eq!(info.name.as_deref(), Some("Built-in"));
+        assert_eq!((info.width, info.height), (2560, 1600));
+        assert_eq!(info.refresh_rate_millihertz, Some(120_000));
+    }
+
+    #[test]
+    fn enforces_frame_and_host_call_quotas() {
+        let mut host = HostCtx::new();
+        host.set_limits(ResourceLimits {
+            max_draw_commands: 3,
+            max_text_bytes: 5,
+            max_host_calls: 6,
+            ..ResourceLimits::default()
+        });
+        let at = WitVec2 { x: 0.0, y: 0.0 };
+        let color = WitColor {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        };
+        host.enter_phase(Phase::Frame);
+        host.draw_text("abc".into(), at, 12.0, color).unwrap();
+        host.draw_text("def".into(), at, 12.0, color).unwrap();
+        host.draw_text("gh".into(), at, 12.0, color).unwrap();
+        host.fill_rect(at, at, color).unwrap();
+        host.fill_rect(at, at, color).unwrap();
+        host.request_frame().unwrap();
+        let trap = host.request_frame().expect_err("seventh call should trap");
+        assert!(trap.to_string().contains("host call quota"));
+        host.exit_phase();
+
+        let frame = host.take_frame_output();
+        assert_eq!(frame.texts().collect::<Vec<_>>(), ["abc", "gh"]);
+        assert_eq!(frame.command_count(), 3);
+
+        host.enter_phase(Phase::Event);
+        host.request_frame()
+            .expect("quota resets for each callback");
+    }
+
+    #[test]
+    fn event_handlers_draw_a_fresh_frame_but_cannot_export() {
+        let mut host = HostCtx::new();
+        let at = WitVec2 { x: 0.0, y: 0.0 };
+        let color = WitColor {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        };
+        host.enter_phase(Phase::Frame);
+        host.fill_rect(at, at, color).unwrap();
+        host.exit_phase();
+        assert_eq!(host.take_frame_output().command_count(), 1);
+
+        host.enter_phase(Phase::Event);
+        assert!(host.take_frame_output().is_empty());
+        host.clear(color).unwrap();
+        host.draw_text("hi".into(), at, 12.0, color).unwrap();
+        host.export_frame(WitExportFormat::Svg).unwrap();
+        host.exit_phase();
+        let frame = host.take_frame_output();
+        assert!(frame.clear_color.is_some());
+        assert_eq!(frame.texts().collect::<Vec<_>>(), ["hi"]);
+        assert!(host.take_export_requests().is_empty());
+    }
+
+    #[test]
+    fn summarizes_out_of_phase_draws_and_hints_once() {
+        let mut host = HostCtx::new();
+        host.enter_phase(Phase::Resize);
+        let at = WitVec2 { x: 0.0, y: 0.0 };
+        let color = WitColor {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        };
+        for _ in 0..5 {
+            host.fill_rect(at, at, color).unwrap();
+            host.clear(color).unwrap();
+        }
+        host.exit_phase();
+        let hints = host.recent_logs_snapshot();
+        assert_eq!(hints.len(), 1, "{hints:?}");
+        assert!(hints[0].starts_with("[HOST]"));
+
+        let start = host.out_of_phase.since.expect("violations are pending");
+        assert!(host.out_of_phase.take_due(start).is_empty());
+        let due = host
+            .out_of_phase
+            .take_due(start + OUT_OF_PHASE_REPORT_INTERVAL);
+        assert_eq!(
+            due,
+            [
+                (("resize", "clear the scene"), 5),
+                (("resize", "issue fill-rect"), 5),
+            ]
+        );
+        assert!(host.out_of_phase.since.is_none());
+    }
+
+    #[test]
+    fn drops_non_finite_geometry_and_sanitizes_colors() {
+        let mut host = HostCtx::new();
+        host.enter_phase(Phase::Frame);
+        let color = WitColor {
+            r: f32::NAN,
+            g: 2.0,
+            b: -1.0,
+            a: f32::INFINITY,
+        };
+        let nan = WitVec2 {
+            x: f32::NAN,
+            y: 0.0,
+        };
+        let one = WitVec2 { x: 1.0, y: 1.0 };
+        host.fill_rect(nan, one, color).unwrap();
+        host.draw_text("x".into(), one, f32::INFINITY, color)
+            .unwrap();
+        host.fill_rect(one, one, color).unwrap();
+        host.draw_text("y".into(), one, 1e9, color).unwrap();
+        host.exit_phase();
+
+        let frame = host.take_frame_output();
+        assert_eq!(frame.command_count(), 2);
+        let DrawCommand::FillRect { color, .. } = &frame.commands[0] else {
+            panic!("expected the finite fill-rect to survive");
+        };
+        assert_eq!((color.r, color.g, color.b, color.a), (0.0, 1.0, 0.0, 0.0));
+        let DrawCommand::DrawText { size, .. } = &frame.commands[1] else {
+            panic!("expected the finite draw-text to survive");
+        };
+        assert_eq!(*size, super::MAX_FONT_SIZE);
+    }
+
+    #[test]
+    fn records_the_requested_font() {
+        let mut host = HostCtx::new();
+        let at = WitVec2 { x: 1.0, y: 2.0 };
+        let color = WitColor {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        };
+        host.enter_phase(Phase::Frame);
+        host.draw_text("plain".into(), at, 12.0, color).unwrap();
+        host.draw_text_with_font("code".into(), at, 12.0, color, WitFontFamily::Monospace)
+            .unwrap();
+        host.exit_phase();
+
+        let frame = host.take_frame_output();
+        let fonts: Vec<_> = frame
+            .commands
+            .iter()
+            .map(|command| match command {
+                DrawCommand::DrawText { font, .. } => *font,
+                _ => panic!("expected text"),
+            })
+            .collect();
+        assert_eq!(fonts, [FontFamily::Sans, FontFamily::Monospace]);
+        assert_eq!(
+            frame.commands[0].to_string(),
+            "DrawText(text='plain', origin=(1.0, 2.0), size=12.0)"
+        );
+        assert_eq!(
+            frame.commands[1].to_string(),
+            "DrawText(text='code', origin=(1.0, 2.0), size=12.0, font=Monospace)"
+        );
+    }
+
+    #[test]
+    fn image_draws_reference_live_images() {
+        let mut host = HostCtx::new();
+        host.set_limits(ResourceLimits {
+            max_image_bytes: 16,
+            ..ResourceLimits::default()
+        });
+        let checker = [[0, 0, 0, 255], [255; 4], [255; 4], [0, 0, 0, 255]].concat();
+        assert_eq!(host.create_image(2, 2, vec![0; 3]).unwrap(), None);
+        let image = host.create_image(2, 2, checker.clone()).unwrap().unwrap();
+        assert_eq!(
+            host.create_image(2, 2, checker).unwrap(),
+            None,
+            "over budget"
+        );
+
+        let origin = WitVec2 { x: 0.0, y: 0.0 };
+        let size = WitVec2 { x: 64.0, y: 64.0 };
+        let tile = WitVec2 { x: 8.0, y: 8.0 };
+        let style = WitImageStyle {
+            tint: WitColor {
+                r: 1.0,
+                g: 0.5,
+                b: 0.5,
+                a: 1.0,
+            },
+            filter: WitImageFilter::Grayscale,
+            opacity: f32::NAN,
+        };
+        host.enter_phase(Phase::Frame);
+        host.draw_image(image, origin, size, style).unwrap();
+        host.fill_pattern(origin, size, image, tile, WitPatternRepeat::RepeatX, style)
+            .unwrap();
+        host.fill_pattern(origin, size, image, origin, WitPatternRepeat::Repeat, style)
+            .unwrap();
+        host.drop_image(image).unwrap();
+        host.draw_image(image, origin, size, style).unwrap();
+        host.exit_phase();
+
+        let frame = host.take_frame_output();
+        assert_eq!(frame.command_count(), 2);
+        let DrawCommand::DrawImage { style, .. } = &frame.commands[0] else {
+            panic!("expected an image draw");
+        };
+        assert_eq!(style.filter, ImageFilter::Grayscale);
+        assert_eq!(style.opacity, 1.0);
+        let DrawCommand::FillPattern { image, repeat, .. } = &frame.commands[1] else {
+            panic!("expected a pattern fill");
+        };
+        assert_eq!((image.width(), image.height()), (2, 2));
+        assert_eq!(*repeat, PatternRepeat::RepeatX);
+        assert_eq!(
+            frame.commands[1].bounds(),
+            Some(Rect::new(0.0, 0.0, 64.0, 8.0))
+        );
+    }
+
+    #[test]
+    fn evicts_least_recently_drawn_assets_but_keeps_recent_ones() {
+        let mut host = HostCtx::new();
+        let pixels = || vec![0; 16];
+        let stale = host.create_image(2, 2, pixels()).unwrap().unwrap();
+        let older = host.create_image(2, 2, pixels()).unwrap().unwrap();
+        let shown = host.create_image(2, 2, pixels()).unwrap().unwrap();
+        let never = host.create_image(2, 2, pixels()).unwrap().unwrap();
+        let at = WitVec2 { x: 0.0, y: 0.0 };
+        let style = WitImageStyle {
+            tint: WitColor {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+                a: 1.0,
+            },
+            filter: WitImageFilter::None,
+            opacity: 1.0,
+        };
+        for drawn in [
+            [older, stale],
+            [stale, shown],
+            [shown, shown],
+            [shown, shown],
+        ] {
+            host.enter_phase(Phase::Frame);
+            for image in drawn {
+                host.draw_image(image, at, at, style).unwrap();
+            }
+            host.exit_phase();
+            host.take_frame_output();
+        }
+
+        assert!(
+            host.evict_assets(64).is_empty(),
+            "already within the target"
+        );
+        let evicted = host.evict_assets(20);
+        assert_eq!(evicted.images, [never, older, stale]);
+        assert_eq!(host.asset_bytes(), 16);
+        assert!(
+            host.evict_assets(0).is_empty(),
+            "drawn in the last two frames"
+        );
+    }
+
+    #[test]
+    fn svg_pictures_share_the_image_budget() {
+        let mut host = HostCtx::new();
+        let svg = br#"<svg width="32" height="16"><rect width="8" height="8"/></svg>"#;
+        host.set_limits(ResourceLimits {
+            max_image_bytes: svg.len() + 16,
+            ..ResourceLimits::default()
+        });
+        let error = host.load_svg(b"<svg".to_vec()).unwrap().unwrap_err();
+        assert!(error.contains("malformed SVG"), "{error}");
+        let picture = host.load_svg(svg.to_vec()).unwrap().unwrap();
+        assert!(host.create_image(2, 2, vec![0; 16]).unwrap().is_some());
+        assert!(host.load_svg(svg.to_vec()).unwrap().is_err(), "over budget");
+        let size = host.picture_size(picture).unwrap().unwrap();
+        assert_eq!((size.x, size.y), (32.0, 16.0));
+
+        let at = WitVec2 { x: 4.0, y: 4.0 };
+        host.enter_phase(Phase::Frame);
+        host.draw_picture(picture, at, at).unwrap();
+        host.drop_picture(picture).unwrap();
+        host.draw_picture(picture, at, at).unwrap();
+        host.exit_phase();
+
+        let frame = host.take_frame_output();
+        assert_eq!(frame.command_count(), 1);
+        assert_eq!(
+            frame.commands[0].to_string(),
+            "DrawPicture(picture=32x16, origin=(4.0, 4.0), size=(4.0, 4.0))"
+        );
+        assert!(host.picture_size(picture).unwrap().is_none());
+    }
+
+    #[test]
+    fn lottie_animations_loop_and_share_the_image_budget() {
+        let mut host = HostCtx::new();
+        let lottie = br#"{"w":40,"h":20,"fr":25,"ip":0,"op":50,"layers":[]}"#;
+        host.set_limits(ResourceLimits {
+            max_image_bytes: lottie.len() + 16,
+            ..ResourceLimits::default()
+        });
+        let error = host.load_lottie(b"[]".to_vec()).unwrap().unwrap_err();
+        assert!(error.contains("not a Lottie animation"), "{error}");
+        let animation = host.load_lottie(lottie.to_vec()).unwrap().unwrap();
+        assert!(host.create_image(2, 2, vec![0; 16]).unwrap().is_some());
+        assert!(
+            host.load_lottie(lottie.to_vec()).unwrap().is_err(),
+            "over budget"
+        );
+        let info = host.animation_info(animation).unwrap().unwrap();
+        assert_eq!((info.size.x, info.size.y), (40.0, 20.0));
+        assert_eq!((info.duration_ms, info.frame_rate), (2000.0, 25.0));
+
+        let at = WitVec2 { x: 4.0, y: 4.0 };
+        host.enter_phase(Phase::Frame);
+        host.draw_animation(animation, f64::NAN, at, at).unwrap();
+        host.draw_animation(animation, 2500.0, at, at).unwrap();
+        host.drop_animation(animation).unwrap();
+        host.draw_animation(animation, 0.0, at, at).unwrap();
+        host.exit_phase();
+
+        let frame = host.take_frame_output();
+        assert_eq!(frame.command_count(), 1);
+        assert_eq!(
+            frame.commands[0].to_string(),
+            "DrawAnimation(animation=40x20, time=2500ms, origin=(4.0, 4.0), size=(4.0, 4.0))"
+        );
+        assert!(host.animation_info(animation).unwrap().is_none());
+    }
+
+    #[test]
+    fn decoded_frames_draw_as_images() {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, 3, 2);
+        encoder.set_color(png::ColorType::Rgb);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[200; 18]).unwrap();
+        writer.finish().unwrap();
+
+        let mut host = HostCtx::new();
+        host.set_limits(ResourceLimits {
+            max_image_bytes: 40,
+            ..ResourceLimits::default()
+        });
+        let error = host.decode_image(b"BM".to_vec()).unwrap().unwrap_err();
+        assert!(error.contains("not a PNG"), "{error}");
+        let image = host.decode_image(png.clone()).unwrap().unwrap();
+        let info = host.animated_image_info(image).unwrap().unwrap();
+        assert_eq!((info.width, info.height, info.plays), (3, 2, 1));
+        assert_eq!(info.frame_durations_ms.len(), 1);
+        assert!(host.decode_image(png).unwrap().is_err(), "over budget");
+
+        let at = WitVec2 { x: 1.0, y: 1.0 };
+        let style = WitImageStyle {
+            tint: WitColor {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+                a: 1.0,
+            },
+            filter: WitImageFilter::None,
+            opacity: 1.0,
+        };
+        host.enter_phase(Phase::Frame);
+        host.draw_animated_image(image, 7, at, at, style).unwrap();
+        host.drop_animated_image(image).unwrap();
+        host.draw_animated_image(image, 0, at, at, style).unwrap();
+        host.exit_phase();
+
+        let frame = host.take_frame_output();
+        assert_eq!(frame.command_count(), 1);
+        assert_eq!(
+            frame.commands[0].to_string(),
+            "DrawImage(image=3x2, origin=(1.0, 1.0), size=(1.0, 1.0))"
+        );
+    }
+
+    #[test]
+    fn emitters_advance_with_frames_and_draw_in_one_command() {
+        let mut host = HostCtx::new();
+        let white = WitColor {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        };
+        let config = WitEmitterConfig {
+            position: WitVec2 { x: 5.0, y: 5.0 },
+            rate: 1000.0,
+            max_particles: 64,
+            lifetime_ms: 500.0,
+            angle: 0.0,
+            spread: 3.0,
+            speed: 50.0,
+            speed_jitter: 10.0,
+            gravity: WitVec2 { x: 0.0, y: 98.0 },
+            start_size: 2.0,
+            end_size: 0.0,
+            start_color: white,
+            end_color: white,
+        };
+        assert_eq!(
+            host.create_emitter(WitEmitterConfig {
+                lifetime_ms: f32::NAN,
+                ..config
+            })
+            .unwrap(),
+            None
+        );
+        let emitter = host.create_emitter(config).unwrap().unwrap();
+        assert_eq!(host.emitter_particle_count(emitter).unwrap(), Some(0));
+
+        host.advance_particles(16.0);
+        host.burst_emitter(emitter, 10).unwrap();
+        assert_eq!(host.emitter_particle_count(emitter).unwrap(), Some(26));
+        assert!(!host
+            .configure_emitter(
+                emitter,
+                WitEmitterConfig {
+                    rate: -1.0,
+                    ..config
+                }
+            )
+            .unwrap());
+
+        host.enter_phase(Phase::Frame);
+        host.draw_emitter(emitter).unwrap();
+        host.draw_emitter(emitter + 1).unwrap();
+        host.exit_phase();
+        let frame = host.take_frame_output();
+        assert_eq!(frame.command_count(), 1);
+        assert_eq!(frame.commands[0].to_string(), "DrawEmitter(count=26)");
+
+        host.drop_emitter(emitter).unwrap();
+        assert_eq!(host.emitter_particle_count(emitter).unwrap(), None);
+    }
+
+    #[test]
+    fn shader_layers_wrap_commands_and_stay_balanced() {
+        let mut host = HostCtx::new();
+        let error = host
+            .register_shader("\nfn shade(uv: vec2<f32>) -> vec4<f32> { return missing; }".into())
+            .unwrap()
+            .unwrap_err();
+        assert!(error.starts_with("line 2:"), "{error}");
+        let shader = host
+            .register_shader(
+                "fn shade(uv: vec2<f32>) -> vec4<f32> { return sample_layer(uv.yx); }".into(),
+            )
+            .unwrap()
+            .unwrap();
+
+        let at = WitVec2 { x: 0.0, y: 0.0 };
+        let white = WitColor {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        };
+        host.enter_phase(Phase::Frame);
+        host.push_shader_layer(shader + 1, Vec::new()).unwrap();
+        host.pop_shader_layer().unwrap();
+        host.push_shader_layer(shader, vec![1.0, 2.0]).unwrap();
+        host.fill_rect(at, at, white).unwrap();
+        host.pop_shader_layer().unwrap();
+        host.pop_shader_layer().unwrap();
+        host.push_shader_layer(shader, vec![f32::NAN]).unwrap();
+        host.pop_shader_layer().unwrap();
+        host.exit_phase();
+
+        let frame = host.take_frame_output();
+        let commands: Vec<_> = frame.commands.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            commands,
+            [
+                "PushShaderLayer(shader=68 bytes, params=[1.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0])",
+                "FillRect(origin=(0.0, 0.0), size=(0.0, 0.0))",
+                "PopShaderLayer",
+            ]
+        );
+        host.drop_shader(shader).unwrap();
+    }
+
+    #[test]
+    fn scroll_regions_move_and_clip_their_content() {
+        let mut host = HostCtx::new();
+        let vec2 = |x, y| WitVec2 { x, y };
+        let rect = |x, y, width, height| WitRect {
+            origin: vec2(x, y),
+            size: vec2(width, height),
+        };
+        let white = WitColor {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        };
+        assert!(host
+            .define_scroll_region(1, vec2(100.0, 1000.0), rect(10.0, 20.0, 100.0, 200.0))
+            .unwrap());
+        assert!(host
+            .define_scroll_region(2, vec2(200.0, 50.0), rect(0.0, 50.0, 50.0, 50.0))
+            .unwrap());
+        assert!(!host
+            .define_scroll_region(3, vec2(-1.0, 0.0), rect(0.0, 0.0, 1.0, 1.0))
+            .unwrap());
+        host.set_scroll_offset(1, vec2(0.0, 30.0)).unwrap();
+
+        host.enter_phase(Phase::Frame);
+        host.push_scroll_region(1).unwrap();
+        host.fill_rect(vec2(0.0, 0.0), vec2(5.0, 5.0), white)
+            .unwrap();
+        host.push_scroll_region(2).unwrap();
+        host.fill_rect(vec2(0.0, 0.0), vec2(1.0, 1.0), white)
+            .unwrap();
+        host.pop_scroll_region().unwrap();
+        host.pop_scroll_region().unwrap();
+        host.pop_scroll_region().unwrap();
+        host.push_scroll_region(99).unwrap();
+        host.fill_rect(vec2(0.0, 0.0), vec2(1.0, 1.0), white)
+            .unwrap();
+        host.pop_scroll_region().unwrap();
+        host.exit_phase();
+
+        let frame = host.take_frame_output();
+        let commands: Vec<_> = frame.commands.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            commands,
+            [
+                "PushClip(origin=(10.0, 20.0), size=(100.0, 200.0))",
+                "FillRect(origin=(10.0, -10.0), size=(5.0, 5.0))",
+                "PushClip(origin=(10.0, 40.0), size=(50.0, 50.0))",
+                "FillRect(origin=(10.0, 40.0), size=(1.0, 1.0))",
+                "PopClip",
+                "PopClip",
+                "FillRect(origin=(0.0, 0.0), size=(1.0, 1.0))",
+            ]
+        );
+
+        // Region 2 was drawn on top, but cannot scroll vertically, so the list takes it.
+        let wheel = ScrollInput::Wheel {
+            position: Vec2::new(15.0, 45.0),
+            delta: Vec2::new(0.0, -10.0),
+        };
+        assert_eq!(
+            host.scroll_input(wheel),
+            [ScrollEvent::Region {
+                id: 1,
+                offset: Vec2::new(0.0, 40.0)
+            }]
+        );
+        assert_eq!(
+            host.scroll_offset(1).unwrap().map(Vec2::from),
+            Some(Vec2::new(0.0, 40.0))
+        );
+    }
+
+    #[test]
+    fn kinetic_scrollers_spring_back_frame_by_frame() {
+        let mut host = HostCtx::new();
+        let vec2 = |x, y| WitVec2 { x, y };
+        assert!(host
+            .kinetic_press(
+                1,
+                vec2(0.0, 0.0),
+                vec2(0.0, 0.0),
+                vec2(0.0, 0.0),
+                vec2(0.0, 500.0)
+            )
+            .unwrap());
+        assert!(!host
+            .kinetic_press(
+                2,
+                vec2(f32::NAN, 0.0),
+                vec2(0.0, 0.0),
+                vec2(0.0, 0.0),
+                vec2(0.0, 0.0)
+            )
+            .unwrap());
+        assert!(host.kinetic_drag(2, vec2(0.0, 0.0)).unwrap().is_none());
+
+        // Pulling down at the top stretches past it, less far than the finger went.
+        let pulled = Vec2::from(host.kinetic_drag(1, vec2(0.0, 300.0)).unwrap().unwrap());
+        assert!(pulled.y < 0.0 && pulled.y > -300.0, "pulled to {pulled:?}");
+        host.kinetic_release(1).unwrap();
+        assert!(host.is_scrolling());
+
+        let mut offset = pulled;
+        for _ in 0..500 {
+            for event in host.advance_scrolling(16.0) {
+                let ScrollEvent::Kinetic {
+                    id,
+                    offset: next,
+                    delta,
+                } = event
+                else {
+                    panic!("unexpected {event:?}");
+                };
+                assert_eq!(id, 1);
+                assert_eq!(next, offset + delta);
+                offset = next;
+            }
+        }
+        assert_eq!(offset, Vec2::ZERO);
+        assert!(!host.is_scrolling());
+        assert!(
+            host.kinetic_stop(1).unwrap().is_none(),
+            "forgotten once at rest"
+        );
+    }
+
+    #[test]
+    fn tweens_step_before_each_frame_and_keep_frames_coming() {
+        let mut host = HostCtx::new();
+        assert!(host
+            .animate(1, 0.0, 100.0, 100.0, WitEasing::Linear)
+            .unwrap());
+        assert!(host.take_redraw_request(), "a new tween asks for a frame");
+        assert!(!host
+            .animate(2, 0.0, f32::NAN, 100.0, WitEasing::Ease)
+            .unwrap());
+        assert_eq!(host.animated_value(1).unwrap(), Some(0.0));
+
+        host.advance_tweens(50.0);
+        assert_eq!(host.animated_value(1).unwrap(), Some(50.0));
+        assert!(host.take_redraw_request());
+        host.advance_tweens(60.0);
+        assert_eq!(host.animated_value(1).unwrap(), Some(100.0));
+        assert!(!host.take_redraw_request(), "finished");
+
+        // A reloaded instance picks up where the old one left off.
+        let mut reloaded = HostCtx::new();
+        reloaded.set_tweens(host.take_tweens());
+        assert_eq!(reloaded.stop_animating(1).unwrap(), Some(100.0));
+        assert_eq!(reloaded.animated_value(1).unwrap(), None);
+    }
+
+    #[test]
+    fn pixel_buffers_present_their_latest_writes() {
+        let mut host = HostCtx::new();
+        host.set_limits(ResourceLimits {
+            max_image_bytes: 16,
+            ..ResourceLimits::default()
+        });
+        let buffer = host.create_pixel_buffer(2, 2).unwrap().unwrap();
+        assert_eq!(host.create_pixel_buffer(1, 1).unwrap(), None, "over budget");
+        assert!(host
+            .write_pixel_buffer(buffer, 1, 1, 1, vec![9; 4])
+            .unwrap());
+        assert!(!host
+            .write_pixel_buffer(buffer, 2, 0, 1, vec![9; 4])
+            .unwrap());
+        assert!(!host.write_pixel_buffer(7, 0, 0, 1, vec![9; 4]).unwrap());
+
+        let at = WitVec2 { x: 0.0, y: 0.0 };
+        let size = WitVec2 { x: 8.0, y: 8.0 };
+        let present = |host: &mut HostCtx| {
+            host.enter_phase(Phase::Frame);
+            host.present_pixel_buffer(buffer, at, size).unwrap();
+            host.exit_phase();
+            match &host.take_frame_output().commands[..] {
+                [DrawCommand::DrawImage { image, .. }] => image.clone(),
+                other => panic!("expected one image, got {other:?}"),
+            }
+        };
+        let first = present(&mut host);
+        assert_eq!(first.to_peniko().data.data()[12..], [9; 4]);
+        assert_eq!(
+            present(&mut host).id(),
+            first.id(),
+            "no write, no new upload"
+        );
+        host.write_pixel_buffer(buffer, 0, 0, 2, vec![1; 8])
+            .unwrap();
+        assert_ne!(present(&mut host).id(), first.id());
+
+        host.drop_pixel_buffer(buffer).unwrap();
+        assert!(host.create_pixel_buffer(1, 1).unwrap().is_some());
+    }
+
+    #[test]
+    fn image_uploads_share_a_per_second_rate() {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, 2, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[200; 8]).unwrap();
+        writer.finish().unwrap();
+
+        let mut host = HostCtx::new();
+        host.set_limits(ResourceLimits {
+            max_image_upload_bytes_per_second: 28,
+            ..ResourceLimits::default()
+        });
+        let buffer = host.create_pixel_buffer(2, 2).unwrap().unwrap();
+        assert!(host.create_image(2, 2, vec![0; 16]).unwrap().is_some());
+        assert!(host.decode_image(png.clone()).unwrap().is_ok());
+        assert!(
+            host.decode_image(png).unwrap().is_err(),
+            "decoded frames count"
+        );
+        assert!(host
+            .write_pixel_buffer(buffer, 0, 0, 1, vec![9; 4])
+            .unwrap());
+        assert!(
+            !host
+                .write_pixel_buffer(buffer, 0, 1, 1, vec![9; 4])
+                .unwrap(),
+            "the second's uploads are spent"
+        );
+        assert_eq!(host.create_image(1, 1, vec![0; 4]).unwrap(), None);
+    }
+
+    #[test]
+    fn export_requests_are_per_frame_and_deduplicated() {
+        let mut host = HostCtx::new();
+        host.export_frame(WitExportFormat::Svg).unwrap();
+        assert!(
+            host.take_export_requests().is_empty(),
+            "idle requests are ignored"
+        );
+
+        host.enter_phase(Phase::Frame);
+        host.export_frame(WitExportFormat::Pdf).unwrap();
+        host.export_frame(WitExportFormat::Svg).unwrap();
+        host.export_frame(WitExportFormat::Pdf).unwrap();
+        host.exit_phase();
+        assert_eq!(
+            host.take_export_requests(),
+            [ExportFormat::Pdf, ExportFormat::Svg]
+        );
+        assert!(host.take_export_requests().is_empty());
+    }
+
+    #[test]
+    fn unknown_cameras_draw_nothing_and_never_prompt() {
+        let mut host = HostCtx::new();
+        let at = WitVec2 { x: 0.0, y: 0.0 };
+        let style = WitImageStyle {
+            tint: WitColor {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+                a: 1.0,
+            },
+            filter: WitImageFilter::None,
+            opacity: 1.0,
+        };
+        if !crate::camera::SUPPORTED {
+            assert!(host.list_cameras().unwrap().is_empty());
+            assert!(host.open_camera(0).unwrap().is_err());
         }
+        assert_eq!(host.camera_status(7).unwrap(), None);
+        assert!(host.camera_frame_info(7).unwrap().is_none());
+        host.enter_phase(Phase::Frame);
+        host.draw_camera(7, at, at, style).unwrap();
+        host.close_camera(7).unwrap();
+        host.exit_phase();
+
+        assert_eq!(host.take_frame_output().command_count(), 0);
+        assert_eq!(host.camera_prompt(), None);
+    }
+
+    #[test]
+    fn queries_frame_content() {
+        let frame = FrameOutput {
+            clear_color: None,
+            commands: vec![
+                DrawCommand::FillRect {
+                    origin: Vec2 { x: 10.0, y: 10.0 },
+                    size: Vec2 { x: 20.0, y: 20.0 },
+                    color: Color::default(),
+                },
+                DrawCommand::DrawText {
+                    text: "1".into(),
+                    origin: Vec2 { x: 0.0, y: 0.0 },
+                    size: 12.0,
+                    color: Color::default(),
+                    font: FontFamily::default(),
+                    flow: TextFlow::default(),
+                },
+            ],
+        };
+        assert_eq!(frame.command_count(), 2);
+        assert!(frame.contains_text("1"));
+        assert!(!frame.contains_text("10"));
+        assert_eq!(
+            frame
+                .rects_intersecting(Rect::new(25.0, 25.0, 10.0, 10.0))
+                .count(),
+            1
+        );
+        assert_eq!(
+            frame
+                .rects_intersecting(Rect::new(30.0, 0.0, 10.0, 10.0))
+                .count(),
+            0
+        );
     }
 }
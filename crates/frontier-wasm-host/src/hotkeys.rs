@@ -0,0 +1,287 @@
+//! Host hotkeys. Each action is bound to a chord (a key plus the exact set of modifiers
+//! held with it) so bindings like `Ctrl+Shift+R` can stay out of the way of guest
+//! shortcuts. Chords are written as `+`-separated modifiers followed by a key named by
+//! its W3C `KeyboardEvent.key` value (`"F5"`, `"r"`, `` "`" ``) or `code` (`"KeyR"`).
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+use crate::model::Modifiers;
+
+/// What a host hotkey does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostAction {
+    Restart,
+    ToggleStats,
+    ToggleConsole,
+    TogglePause,
+    Step,
+    CycleTimeScale,
+    ZoomIn,
+    ZoomOut,
+    ResetZoom,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Chord {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub meta: bool,
+    pub key: String,
+}
+
+impl Chord {
+    /// Letters match either case, since Shift changes the key value.
+    pub fn matches(&self, key: &str, code: &str, modifiers: &Modifiers) -> bool {
+        (self.key.eq_ignore_ascii_case(key) || self.key == code)
+            && self.ctrl == modifiers.ctrl
+            && self.shift == modifiers.shift
+            && self.alt == modifiers.alt
+            && self.meta == modifiers.meta
+    }
+}
+
+impl FromStr for Chord {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        // A trailing `+` is the key itself (`Ctrl++`).
+        let (mods, key) = if text == "+" || text.ends_with("++") {
+            (&text[..text.len() - 1], "+")
+        } else {
+            text.rsplit_once('+').unwrap_or(("", text))
+        };
+        if key.is_empty() {
+            bail!("hotkey `{text}` has no key");
+        }
+        let mut chord = Chord {
+            ctrl: false,
+            shift: false,
+            alt: false,
+            meta: false,
+            key: key.to_string(),
+        };
+        for modifier in mods.split('+').filter(|m| !m.is_empty()) {
+            let flag = match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => &mut chord.ctrl,
+                "shift" => &mut chord.shift,
+                "alt" | "option" => &mut chord.alt,
+                "meta" | "super" | "cmd" => &mut chord.meta,
+                _ => bail!("unknown modifier `{modifier}` in hotkey `{text}`"),
+            };
+            *flag = true;
+        }
+        Ok(chord)
+    }
+}
+
+impl TryFrom<String> for Chord {
+    type Error = anyhow::Error;
+
+    fn try_from(text: String) -> Result<Self> {
+        text.parse()
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (held, name) in [
+            (self.ctrl, "Ctrl+"),
+            (self.shift, "Shift+"),
+            (self.alt, "Alt+"),
+            (self.meta, "Meta+"),
+        ] {
+            if held {
+                f.write_str(name)?;
+            }
+        }
+        f.write_str(&self.key)
+    }
+}
+
+/// Chords for each host action. Keys bound here are consumed by the host and never reach
+/// the guest.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Keybinds {
+    /// Reload the component and re-run `init`.
+    pub restart: Chord,
+    /// Toggle the frame statistics panel.
+    pub stats: Chord,
+    /// Toggle the console listing recent guest logs.
+    pub console: Chord,
+    /// Pause or resume guest time.
+    pub pause: Chord,
+    /// Run a single `frame` call while paused.
+    pub step: Chord,
+    /// Cycle the guest time scale.
+    pub time_scale: Chord,
+    /// Scale the whole canvas up one zoom level.
+    pub zoom_in: Chord,
+    /// Scale the whole canvas down one zoom level.
+    pub zoom_out: Chord,
+    /// Return the canvas to 100% zoom.
+    pub reset_zoom: Chord,
+}
+
+impl Default for Keybinds {
+    fn default() -> Self {
+        let chord = |key: &str| key.parse().expect("valid default hotkey");
+        Self {
+            restart: chord("F5"),
+            stats: chord("F3"),
+            console: chord("F12"),
+            pause: chord("F6"),
+            step: chord("F7"),
+            time_scale: chord("F8"),
+            zoom_in: chord("Ctrl+="),
+            zoom_out: chord("Ctrl+-"),
+            reset_zoom: chord("Ctrl+0"),
+        }
+    }
+}
+
+impl Keybinds {
+    pub fn action(&self, key: &str, code: &str, modifiers: &Modifiers) -> Option<HostAction> {
+        [
+            (&self.restart, HostAction::Restart),
+            (&self.stats, HostAction::ToggleStats),
+            (&self.console, HostAction::ToggleConsole),
+            (&self.pause, HostAction::TogglePause),
+            (&self.step, HostAction::Step),
+            (&self.time_scale, HostAction::CycleTimeScale),
+            (&self.zoom_in, HostAction::ZoomIn),
+            (&self.zoom_out, HostAction::ZoomOut),
+            (&self.reset_zoom, HostAction::ResetZoom),
+        ]
+        .into_iter()
+        .find(|(chord, _)| chord.matches(key, code, modifiers))
+        .map(|(_, action)| action)
+    }
+}
+
+/// Routes key events either to a host action or to the guest. A key whose press triggered
+/// a host action stays reserved until it is released, so neither its repeats nor its
+/// key-up leak to the guest even if the modifiers are let go first.
+#[derive(Debug, Default)]
+pub struct HotkeyDispatcher {
+    keybinds: Keybinds,
+    /// `code` values of keys pressed as part of a host chord and not yet released.
+    held: HashSet<String>,
+}
+
+/// What to do with a key event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyRoute {
+    Guest,
+    /// Swallowed by the host; run the action if there is one.
+    Host(Option<HostAction>),
+}
+
+impl HotkeyDispatcher {
+    pub fn new(keybinds: Keybinds) -> Self {
+        Self {
+            keybinds,
+            held: HashSet::new(),
+        }
+    }
+
+    pub fn keybinds(&self) -> &Keybinds {
+        &self.keybinds
+    }
+
+    pub fn key_down(
+        &mut self,
+        key: &str,
+        code: &str,
+        modifiers: &Modifiers,
+        repeat: bool,
+    ) -> KeyRoute {
+        if self.held.contains(code) {
+            return KeyRoute::Host(None);
+        }
+        match self.keybinds.action(key, code, modifiers) {
+            Some(action) => {
+                self.held.insert(code.to_string());
+                KeyRoute::Host((!repeat).then_some(action))
+            }
+            None => KeyRoute::Guest,
+        }
+    }
+
+    pub fn key_up(&mut self, code: &str) -> KeyRoute {
+        if self.held.remove(code) {
+            KeyRoute::Host(None)
+        } else {
+            KeyRoute::Guest
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chord, HostAction, HotkeyDispatcher, KeyRoute, Keybinds};
+    use crate::model::Modifiers;
+
+    fn ctrl_shift() -> Modifiers {
+        Modifiers {
+            ctrl: true,
+            shift: true,
+            ..Modifiers::default()
+        }
+    }
+
+    #[test]
+    fn parses_chords() {
+        let chord: Chord = "Ctrl+Shift+R".parse().unwrap();
+        assert!(chord.ctrl && chord.shift && !chord.alt && !chord.meta);
+        assert_eq!(chord.key, "R");
+        assert_eq!(chord.to_string(), "Ctrl+Shift+R");
+        assert_eq!("Ctrl++".parse::<Chord>().unwrap().key, "+");
+        assert_eq!("+".parse::<Chord>().unwrap().key, "+");
+        assert!("Hyper+R".parse::<Chord>().is_err());
+        assert!("Ctrl+".parse::<Chord>().is_err());
+    }
+
+    #[test]
+    fn chords_need_exact_modifiers() {
+        let chord: Chord = "Ctrl+Shift+r".parse().unwrap();
+        assert!(chord.matches("R", "KeyR", &ctrl_shift()));
+        assert!(!chord.matches("r", "KeyR", &Modifiers::default()));
+        let plain: Chord = "F5".parse().unwrap();
+        assert!(plain.matches("F5", "F5", &Modifiers::default()));
+        assert!(!plain.matches("F5", "F5", &ctrl_shift()));
+    }
+
+    #[test]
+    fn reserved_keys_never_reach_the_guest() {
+        let keybinds = Keybinds {
+            restart: "Ctrl+Shift+R".parse().unwrap(),
+            ..Keybinds::default()
+        };
+        let mut dispatcher = HotkeyDispatcher::new(keybinds);
+        assert_eq!(
+            dispatcher.key_down("r", "KeyR", &Modifiers::default(), false),
+            KeyRoute::Guest
+        );
+        assert_eq!(dispatcher.key_up("KeyR"), KeyRoute::Guest);
+
+        assert_eq!(
+            dispatcher.key_down("R", "KeyR", &ctrl_shift(), false),
+            KeyRoute::Host(Some(HostAction::Restart))
+        );
+        // Modifiers released before the key: the repeat and key-up still belong to the host.
+        assert_eq!(
+            dispatcher.key_down("r", "KeyR", &Modifiers::default(), true),
+            KeyRoute::Host(None)
+        );
+        assert_eq!(dispatcher.key_up("KeyR"), KeyRoute::Host(None));
+        assert_eq!(dispatcher.key_up("KeyR"), KeyRoute::Guest);
+    }
+}
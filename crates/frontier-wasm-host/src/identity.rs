@@ -0,0 +1,357 @@
+//! Which component a piece of host-side data belongs to. Storage, settings, permission
+//! answers and caches are all filed under a [`ComponentIdentity`] so two components never
+//! see each other's data, and so `frontier-wasm-host data` can list and clear it.
+//!
+//! The identity's `id` follows where a component comes from (its manifest id or origin),
+//! not its bytes, so rebuilding a component keeps its data. Caches are additionally keyed
+//! by the content hash, since compiled or derived artifacts go stale on every rebuild.
+//! An id belongs to the origin that used it first: another component declaring the same
+//! manifest id is refused rather than handed that data.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::manifest::ComponentManifest;
+use crate::runtime::ComponentSource;
+
+const IDENTITY_FILE: &str = "identity.json";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentIdentity {
+    /// Directory-safe key for the component's data.
+    pub id: String,
+    /// Hex SHA-256 of the component's bytes.
+    pub content_hash: String,
+    /// Id the component declares for itself, when it has a manifest.
+    pub manifest_id: Option<String>,
+    /// `file://` URL of the component, or `embedded:LABEL` for built-in demos.
+    pub origin: String,
+}
+
+/// The kinds of per-component data the host keeps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataKind {
+    Storage,
+    Settings,
+    Permissions,
+    /// Derived artifacts; kept per content hash and safe to delete.
+    Cache,
+}
+
+/// Where per-component data lives: `<data>/frontier-wasm/components/ID/` and
+/// `<cache>/frontier-wasm/components/ID/` under the platform directories by default.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataDirs {
+    pub data: PathBuf,
+    pub cache: PathBuf,
+}
+
+impl DataDirs {
+    pub fn platform() -> Result<Self> {
+        let data = dirs::data_local_dir().context("no platform data directory")?;
+        let cache = dirs::cache_dir().unwrap_or_else(|| data.clone());
+        Ok(Self {
+            data: data.join("frontier-wasm").join("components"),
+            cache: cache.join("frontier-wasm").join("components"),
+        })
+    }
+
+    /// Where the component with `id` keeps its storage, whether or not it exists yet.
+    pub fn storage_dir(&self, id: &str) -> PathBuf {
+        self.data.join(id).join("storage")
+    }
+}
+
+impl ComponentIdentity {
+    pub fn of(source: &ComponentSource) -> Result<Self> {
+        match source {
+            ComponentSource::Path(path) => {
+                let bytes = fs::read(path)
+                    .with_context(|| format!("failed to read component {}", path.display()))?;
+                let manifest = ComponentManifest::of(source)?;
+                let path = path.canonicalize().unwrap_or_else(|_| path.clone());
+                Ok(Self::new(
+                    format!("file://{}", path.display()),
+                    &source.label(),
+                    &bytes,
+                    manifest.id,
+                ))
+            }
+            ComponentSource::Embedded { bytes, label } => {
+                Ok(Self::new(format!("embedded:{label}"), label, bytes, None))
+            }
+        }
+    }
+
+    /// A worker `parent` loaded from `path`. Its data is kept apart from the parent's and
+    /// from the file's own when run as an app, and any manifest id it declares is ignored.
+    pub fn worker(parent: &Self, path: &Path, bytes: &[u8]) -> Self {
+        let name = path
+            .file_stem()
+            .map_or("worker".into(), |stem| stem.to_string_lossy());
+        let origin = format!("worker:{}#{}", parent.origin, path.display());
+        Self::new(origin, &name, bytes, None)
+    }
+
+    /// A worker `parent` spawned from `bytes` in memory. Every copy of the same build
+    /// `parent` spawns shares one id, but other parents spawning it get their own.
+    pub fn worker_from_bytes(parent: &Self, name: &str, bytes: &[u8]) -> Self {
+        let origin = format!(
+            "worker:{}#bytes:{}",
+            parent.origin,
+            hex(&Sha256::digest(bytes))
+        );
+        Self::new(origin, name, bytes, None)
+    }
+
+    /// `name` (the file stem or label) keeps ids readable; a hash of `origin` keeps
+    /// same-named components apart. A manifest id replaces both.
+    pub fn new(origin: String, name: &str, bytes: &[u8], manifest_id: Option<String>) -> Self {
+        let id = match &manifest_id {
+            Some(manifest_id) => sanitize(manifest_id),
+            None => {
+                let origin_hash = hex(&Sha256::digest(origin.as_bytes()));
+                format!("{}-{}", sanitize(name), &origin_hash[..12])
+            }
+        };
+        Self {
+            id,
+            content_hash: hex(&Sha256::digest(bytes)),
+            manifest_id,
+            origin,
+        }
+    }
+
+    /// The directory for `kind`, created on first use along with a record of this
+    /// identity for `data list`. Fails when the id is already bound to another origin.
+    pub fn dir(&self, dirs: &DataDirs, kind: DataKind) -> Result<PathBuf> {
+        let root = dirs.data.join(&self.id);
+        self.check_owner(&root)?;
+        let dir = match kind {
+            DataKind::Storage => dirs.storage_dir(&self.id),
+            DataKind::Settings => root.join("settings"),
+            DataKind::Permissions => root.join("permissions"),
+            DataKind::Cache => dirs.cache.join(&self.id).join(&self.content_hash[..16]),
+        };
+        fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        fs::create_dir_all(&root)?;
+        fs::write(root.join(IDENTITY_FILE), serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("failed to record identity in {}", root.display()))?;
+        Ok(dir)
+    }
+
+    /// Refuse to use `root` when its recorded identity came from a different origin.
+    fn check_owner(&self, root: &Path) -> Result<()> {
+        let record = root.join(IDENTITY_FILE);
+        let bytes = match fs::read(&record) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", record.display()))
+            }
+        };
+        let owner: Self = serde_json::from_slice(&bytes)
+            .with_context(|| format!("corrupt identity record {}", record.display()))?;
+        if owner.origin != self.origin {
+            bail!(
+                "component id '{}' belongs to {}, not {}; give this component its own \
+                 manifest id, or run `data clear {}` to release it",
+                self.id,
+                owner.origin,
+                self.origin,
+                self.id
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A component with data on disk, for `data list`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredComponent {
+    pub id: String,
+    /// Missing when the identity record is unreadable.
+    pub identity: Option<ComponentIdentity>,
+    /// Bytes used across data and cache.
+    pub bytes: u64,
+}
+
+pub fn list(dirs: &DataDirs) -> Result<Vec<StoredComponent>> {
+    let mut ids = Vec::new();
+    for root in [&dirs.data, &dirs.cache] {
+        let entries = match fs::read_dir(root) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", root.display()))
+            }
+        };
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                ids.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+    }
+    ids.sort();
+    ids.dedup();
+    Ok(ids
+        .into_iter()
+        .map(|id| {
+            let identity = fs::read(dirs.data.join(&id).join(IDENTITY_FILE))
+                .ok()
+                .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+            let bytes = dir_size(&dirs.data.join(&id)) + dir_size(&dirs.cache.join(&id));
+            StoredComponent {
+                id,
+                identity,
+                bytes,
+            }
+        })
+        .collect())
+}
+
+/// Delete everything stored for `id`; returns false when there was nothing.
+pub fn clear(dirs: &DataDirs, id: &str) -> Result<bool> {
+    if id.is_empty() || sanitize(id) != id {
+        bail!("'{id}' is not a component id; see `data list`");
+    }
+    let mut removed = false;
+    for dir in [dirs.data.join(id), dirs.cache.join(id)] {
+        match fs::remove_dir_all(&dir) {
+            Ok(()) => removed = true,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to remove {}", dir.display()))
+            }
+        }
+    }
+    Ok(removed)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Lower-case ASCII letters, digits, `-`, `_` and `.`; anything else becomes `-`.
+pub(crate) fn sanitize(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' | '-' | '_' | '.' => c,
+            'A'..='Z' => c.to_ascii_lowercase(),
+            _ => '-',
+        })
+        .collect();
+    match name.trim_matches('.') {
+        "" => "component".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{clear, list, ComponentIdentity, DataDirs, DataKind};
+
+    #[test]
+    fn ids_follow_the_origin_not_the_bytes() {
+        let a = ComponentIdentity::new("file:///apps/Notes.wasm".into(), "Notes", b"v1", None);
+        let rebuilt =
+            ComponentIdentity::new("file:///apps/Notes.wasm".into(), "Notes", b"v2", None);
+        let elsewhere =
+            ComponentIdentity::new("file:///other/Notes.wasm".into(), "Notes", b"v1", None);
+        assert!(a.id.starts_with("notes-"), "{}", a.id);
+        assert_eq!(a.id, rebuilt.id);
+        assert_ne!(a.content_hash, rebuilt.content_hash);
+        assert_ne!(a.id, elsewhere.id);
+
+        let plugin = std::path::Path::new("/apps/plugins/Notes.wasm");
+        let worker = ComponentIdentity::worker(&a, plugin, b"v1");
+        let other_parent = ComponentIdentity::worker(&elsewhere, plugin, b"v1");
+        assert!(worker.id.starts_with("notes-"), "{}", worker.id);
+        assert_ne!(worker.id, a.id);
+        assert_ne!(worker.id, other_parent.id);
+
+        let spawned = ComponentIdentity::worker_from_bytes(&a, "echo", b"worker");
+        let again = ComponentIdentity::worker_from_bytes(&rebuilt, "echo", b"worker");
+        let unrelated = ComponentIdentity::worker_from_bytes(&elsewhere, "echo", b"worker");
+        assert!(spawned.id.starts_with("echo-"), "{}", spawned.id);
+        assert_eq!(spawned.id, again.id, "same parent, same bytes");
+        assert_ne!(
+            spawned.id, unrelated.id,
+            "two parents never share a worker's data"
+        );
+        assert_ne!(
+            spawned.id,
+            ComponentIdentity::worker_from_bytes(&a, "echo", b"other").id
+        );
+
+        let declared = ComponentIdentity::new(
+            "file:///apps/Notes.wasm".into(),
+            "Notes",
+            b"v1",
+            Some("com.example/Notes".into()),
+        );
+        assert_eq!(declared.id, "com.example-notes");
+    }
+
+    #[test]
+    fn lists_and_clears_component_data() {
+        let root = std::env::temp_dir().join(format!("frontier-identity-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let dirs = DataDirs {
+            data: root.join("data"),
+            cache: root.join("cache"),
+        };
+        assert!(list(&dirs).unwrap().is_empty());
+
+        let identity = ComponentIdentity::new("embedded:demo".into(), "demo", b"wasm", None);
+        let storage = identity.dir(&dirs, DataKind::Storage).unwrap();
+        fs::write(storage.join("kv"), b"12345").unwrap();
+        let cache = identity.dir(&dirs, DataKind::Cache).unwrap();
+        assert!(cache.starts_with(dirs.cache.join(&identity.id)));
+
+        let stored = list(&dirs).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].identity.as_ref(), Some(&identity));
+        assert!(stored[0].bytes > 5);
+
+        let impostor = ComponentIdentity::new(
+            "file:///elsewhere/demo.wasm".into(),
+            "demo",
+            b"wasm",
+            Some(identity.id.clone()),
+        );
+        assert_eq!(impostor.id, identity.id);
+        assert!(impostor.dir(&dirs, DataKind::Storage).is_err());
+        let rebuilt = ComponentIdentity::new("embedded:demo".into(), "demo", b"wasm2", None);
+        assert!(rebuilt.dir(&dirs, DataKind::Storage).is_ok());
+
+        assert!(clear(&dirs, "../data").is_err());
+        assert!(clear(&dirs, &identity.id).unwrap());
+        assert!(!clear(&dirs, &identity.id).unwrap());
+        assert!(list(&dirs).unwrap().is_empty());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+//! User inactivity for `seconds-since-input` and the `idle-changed` export.
+
+use std::time::{Duration, Instant};
+
+use winit::event::WindowEvent;
+
+/// How long without input before the guest is told the user is idle, unless
+/// `--idle-timeout` says otherwise.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// When the user last touched the window, and whether the guest has been told they left.
+#[derive(Clone, Debug)]
+pub struct IdleTracker {
+    /// `None` turns `idle-changed` off; `seconds-since-input` still works.
+    timeout: Option<Duration>,
+    last_input: Instant,
+    idle: bool,
+}
+
+impl IdleTracker {
+    pub fn new(timeout: Option<Duration>, now: Instant) -> Self {
+        Self {
+            timeout,
+            last_input: now,
+            idle: false,
+        }
+    }
+
+    pub fn last_input(&self) -> Instant {
+        self.last_input
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+
+    /// Note user input; returns true when it ends an idle period.
+    pub fn record_input(&mut self, now: Instant) -> bool {
+        self.last_input = now;
+        std::mem::replace(&mut self.idle, false)
+    }
+
+    /// Returns whether the user has just become idle, and when to check again.
+    pub fn poll(&mut self, now: Instant) -> (bool, Option<Instant>) {
+        let Some(timeout) = self.timeout else {
+            return (false, None);
+        };
+        if self.idle {
+            return (false, None);
+        }
+        let due = self.last_input + timeout;
+        if now < due {
+            return (false, Some(due));
+        }
+        self.idle = true;
+        (true, None)
+    }
+}
+
+/// Events that show someone is at the machine. Window moves, resizes and focus changes
+/// are left out since the compositor produces them on its own.
+pub fn is_user_input(event: &WindowEvent) -> bool {
+    matches!(
+        event,
+        WindowEvent::CursorMoved { .. }
+            | WindowEvent::MouseInput { .. }
+            | WindowEvent::MouseWheel { .. }
+            | WindowEvent::Touch(_)
+            | WindowEvent::PinchGesture { .. }
+            | WindowEvent::RotationGesture { .. }
+            | WindowEvent::DoubleTapGesture { .. }
+            | WindowEvent::KeyboardInput { .. }
+            | WindowEvent::Ime(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::IdleTracker;
+
+    #[test]
+    fn goes_idle_once_after_the_timeout_and_wakes_on_input() {
+        let start = Instant::now();
+        let timeout = Duration::from_secs(60);
+        let mut idle = IdleTracker::new(Some(timeout), start);
+
+        assert_eq!(idle.poll(start), (false, Some(start + timeout)));
+        let later = start + timeout;
+        assert_eq!(idle.poll(later), (true, None));
+        assert_eq!(idle.poll(later), (false, None));
+        assert!(idle.is_idle());
+
+        assert!(idle.record_input(later));
+        assert!(!idle.record_input(later));
+        assert_eq!(idle.poll(later), (false, Some(later + timeout)));
+    }
+
+    #[test]
+    fn never_goes_idle_without_a_timeout() {
+        let start = Instant::now();
+        let mut idle = IdleTracker::new(None, start);
+        assert_eq!(idle.poll(start + Duration::from_secs(3600)), (false, None));
+        assert!(!idle.is_idle());
+    }
+}
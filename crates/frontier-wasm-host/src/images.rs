@@ -0,0 +1,473 @@
+//! Pixel images uploaded by the guest. Each image is stored once as a Vello blob, so draw
+//! commands that reference it share the pixels (and Vello's atlas entry) across frames.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use vello::peniko::{Blob, ImageAlphaType, ImageData, ImageFormat};
+
+use crate::component::vello::canvas::host::{
+    ImageFilter as WitImageFilter, ImageStyle as WitImageStyle,
+};
+use crate::host::Color;
+use crate::window::clamp_opacity;
+
+/// Largest width or height accepted by `create-image`; wgpu guarantees 8192 only on
+/// desktop-class adapters, so stay well inside the downlevel texture limits.
+pub const MAX_IMAGE_DIMENSION: u32 = 4096;
+
+/// Straight-alpha RGBA8 pixels, row-major. Cloning is cheap and keeps the same blob.
+#[derive(Clone, PartialEq)]
+pub struct Image {
+    data: ImageData,
+}
+
+impl Image {
+    /// `None` unless `rgba` holds exactly `width * height` pixels and both sides are in
+    /// `1..=MAX_IMAGE_DIMENSION`.
+    pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Option<Self> {
+        if !(1..=MAX_IMAGE_DIMENSION).contains(&width)
+            || !(1..=MAX_IMAGE_DIMENSION).contains(&height)
+            || ImageFormat::Rgba8.size_in_bytes(width, height) != Some(rgba.len())
+        {
+            return None;
+        }
+        Some(Self {
+            data: ImageData {
+                data: Blob::new(Arc::new(rgba)),
+                format: ImageFormat::Rgba8,
+                alpha_type: ImageAlphaType::Alpha,
+                width,
+                height,
+            },
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.data.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.data.height
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.data.data.len()
+    }
+
+    /// Identifies the pixels: clones share it, separate uploads never do.
+    pub fn id(&self) -> u64 {
+        self.data.data.id()
+    }
+
+    /// The straight-alpha RGBA8 pixels.
+    pub fn rgba(&self) -> &[u8] {
+        self.data.data.data()
+    }
+
+    pub fn to_peniko(&self) -> ImageData {
+        self.data.clone()
+    }
+
+    /// A copy with `filter` and then `tint` applied to every pixel.
+    pub fn filtered(&self, filter: ImageFilter, tint: Color) -> Image {
+        let mut rgba = self.data.data.data().to_vec();
+        for pixel in rgba.chunks_exact_mut(4) {
+            let [r, g, b, a] = [0, 1, 2, 3].map(|i| pixel[i] as f32 / 255.0);
+            let [r, g, b] = match filter {
+                ImageFilter::None => [r, g, b],
+                ImageFilter::Grayscale => [0.2126 * r + 0.7152 * g + 0.0722 * b; 3],
+                ImageFilter::Sepia => [
+                    0.393 * r + 0.769 * g + 0.189 * b,
+                    0.349 * r + 0.686 * g + 0.168 * b,
+                    0.272 * r + 0.534 * g + 0.131 * b,
+                ],
+            };
+            let out = [r * tint.r, g * tint.g, b * tint.b, a * tint.a];
+            for (channel, value) in pixel.iter_mut().zip(out) {
+                *channel = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        }
+        Image::new(self.width(), self.height(), rgba).expect("same dimensions as the source")
+    }
+}
+
+impl fmt::Debug for Image {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Image({}x{})", self.width(), self.height())
+    }
+}
+
+/// Colour processing applied to an image as it is drawn.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum ImageFilter {
+    #[default]
+    None,
+    Grayscale,
+    Sepia,
+}
+
+/// Per-draw image attributes: `filter` runs first, then every channel is multiplied by
+/// `tint`, and `opacity` scales the result.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ImageStyle {
+    pub tint: Color,
+    pub filter: ImageFilter,
+    pub opacity: f32,
+}
+
+impl Default for ImageStyle {
+    fn default() -> Self {
+        Self {
+            tint: Color {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+                a: 1.0,
+            },
+            filter: ImageFilter::None,
+            opacity: 1.0,
+        }
+    }
+}
+
+impl ImageStyle {
+    pub fn from_wit(style: WitImageStyle) -> Self {
+        Self {
+            tint: Color::from_wit(style.tint),
+            filter: match style.filter {
+                WitImageFilter::None => ImageFilter::None,
+                WitImageFilter::Grayscale => ImageFilter::Grayscale,
+                WitImageFilter::Sepia => ImageFilter::Sepia,
+            },
+            opacity: clamp_opacity(style.opacity),
+        }
+    }
+
+    /// Whether drawing needs a recoloured copy of the pixels; opacity alone does not.
+    pub fn changes_pixels(&self) -> bool {
+        self.filter != ImageFilter::None || self.tint != ImageStyle::default().tint
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Image {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let width = u.int_in_range(1..=16)?;
+        let height = u.int_in_range(1..=16)?;
+        let mut rgba = vec![0; (width * height * 4) as usize];
+        u.fill_buffer(&mut rgba)?;
+        Ok(Self::new(width, height, rgba).expect("dimensions are in range"))
+    }
+}
+
+/// Something a guest holds by id and that counts against its image memory budget.
+pub trait Asset {
+    fn byte_len(&self) -> usize;
+}
+
+impl Asset for Image {
+    fn byte_len(&self) -> usize {
+        self.byte_len()
+    }
+}
+
+impl Asset for crate::svg::Picture {
+    fn byte_len(&self) -> usize {
+        self.byte_len()
+    }
+}
+
+impl Asset for crate::lottie::Animation {
+    fn byte_len(&self) -> usize {
+        self.byte_len()
+    }
+}
+
+impl Asset for crate::decode::AnimatedImage {
+    fn byte_len(&self) -> usize {
+        self.byte_len()
+    }
+}
+
+impl Asset for crate::pixels::PixelBuffer {
+    fn byte_len(&self) -> usize {
+        self.byte_len()
+    }
+}
+
+impl Asset for crate::particles::Emitter {
+    fn byte_len(&self) -> usize {
+        self.byte_len()
+    }
+}
+
+impl Asset for crate::effects::Shader {
+    fn byte_len(&self) -> usize {
+        self.byte_len()
+    }
+}
+
+/// Images (or pictures, or animations) the guest currently holds, keyed by the id handed out when they
+/// were created.
+#[derive(Debug)]
+pub struct AssetStore<T> {
+    images: HashMap<u32, T>,
+    next_id: u32,
+    bytes: usize,
+    /// The frame each asset was last drawn in, for evicting the least recently drawn.
+    drawn: HashMap<u32, u64>,
+}
+
+impl<T> Default for AssetStore<T> {
+    fn default() -> Self {
+        Self {
+            images: HashMap::new(),
+            next_id: 0,
+            bytes: 0,
+            drawn: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Asset> AssetStore<T> {
+    /// Store `image` unless that would take the store past `max_bytes`.
+    pub fn insert(&mut self, image: T, max_bytes: usize) -> Option<u32> {
+        let bytes = self.bytes.checked_add(image.byte_len())?;
+        if bytes > max_bytes {
+            return None;
+        }
+        let id = self.next_id;
+        self.next_id = self.next_id.checked_add(1)?;
+        self.bytes = bytes;
+        self.images.insert(id, image);
+        Some(id)
+    }
+
+    pub fn remove(&mut self, id: u32) -> bool {
+        self.drawn.remove(&id);
+        match self.images.remove(&id) {
+            Some(image) => {
+                self.bytes -= image.byte_len();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get(&self, id: u32) -> Option<&T> {
+        self.images.get(&id)
+    }
+
+    /// Look `id` up for drawing in `frame`, remembering when it was last drawn.
+    pub fn draw(&mut self, id: u32, frame: u64) -> Option<&T> {
+        let image = self.images.get(&id)?;
+        self.drawn.insert(id, frame);
+        Some(image)
+    }
+
+    /// Every asset's id, size and the frame it was last drawn in (0 if never).
+    pub fn draw_history(&self) -> impl Iterator<Item = (u32, usize, u64)> + '_ {
+        self.images.iter().map(|(&id, image)| {
+            let drawn = self.drawn.get(&id).copied().unwrap_or(0);
+            (id, image.byte_len(), drawn)
+        })
+    }
+
+    /// Mutable access for assets whose size never changes, such as pixel buffers.
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut T> {
+        self.images.get_mut(&id)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.images.values_mut()
+    }
+
+    /// Pixel bytes held by live images.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+/// Recoloured copies of images for styles that tint or filter them, so an icon drawn
+/// every frame is processed once. Copies not used since the previous `trim` are dropped
+/// there.
+#[derive(Debug, Default)]
+pub struct FilterCache {
+    entries: HashMap<(u64, ImageFilter, [u32; 4]), FilteredImage>,
+    /// Counts lookups, so entries can be evicted least recently used first.
+    lookups: u64,
+}
+
+#[derive(Debug)]
+struct FilteredImage {
+    image: Image,
+    used: bool,
+    last_used: u64,
+}
+
+impl FilterCache {
+    /// `image` as `style` colours it; opacity is left to the caller.
+    pub fn get(&mut self, image: &Image, style: &ImageStyle) -> Image {
+        if !style.changes_pixels() {
+            return image.clone();
+        }
+        let tint = [style.tint.r, style.tint.g, style.tint.b, style.tint.a].map(f32::to_bits);
+        self.lookups += 1;
+        let entry = self
+            .entries
+            .entry((image.id(), style.filter, tint))
+            .or_insert_with(|| FilteredImage {
+                image: image.filtered(style.filter, style.tint),
+                used: false,
+                last_used: 0,
+            });
+        entry.used = true;
+        entry.last_used = self.lookups;
+        entry.image.clone()
+    }
+
+    /// Drop copies nobody drew since the last call.
+    pub fn trim(&mut self) {
+        self.entries
+            .retain(|_, entry| std::mem::take(&mut entry.used));
+    }
+
+    /// Drop copies, least recently drawn first, until the rest hold at most `max_bytes`.
+    pub fn evict_to(&mut self, max_bytes: usize) {
+        let mut bytes = self.bytes();
+        if bytes <= max_bytes {
+            return;
+        }
+        let mut by_age: Vec<_> = self
+            .entries
+            .iter()
+            .map(|(key, entry)| (entry.last_used, *key))
+            .collect();
+        by_age.sort_unstable_by_key(|(last_used, _)| *last_used);
+        for (_, key) in by_age {
+            if bytes <= max_bytes {
+                break;
+            }
+            if let Some(entry) = self.entries.remove(&key) {
+                bytes -= entry.image.byte_len();
+            }
+        }
+    }
+
+    /// Pixel bytes held by the copies.
+    pub fn bytes(&self) -> usize {
+        self.entries
+            .values()
+            .map(|entry| entry.image.byte_len())
+            .sum()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AssetStore, FilterCache, Image, ImageFilter, ImageStyle, MAX_IMAGE_DIMENSION};
+    use crate::host::Color;
+
+    #[test]
+    fn rejects_mismatched_pixels_and_sizes() {
+        assert!(Image::new(2, 2, vec![0; 16]).is_some());
+        assert!(Image::new(2, 2, vec![0; 15]).is_none());
+        assert!(Image::new(0, 0, Vec::new()).is_none());
+        let wide = MAX_IMAGE_DIMENSION + 1;
+        assert!(Image::new(wide, 1, vec![0; wide as usize * 4]).is_none());
+    }
+
+    #[test]
+    fn filters_then_tints_pixels() {
+        let image = Image::new(1, 1, vec![255, 0, 0, 255]).unwrap();
+        let half_blue = Color {
+            r: 0.5,
+            g: 0.5,
+            b: 1.0,
+            a: 0.5,
+        };
+        let pixels = |image: Image| image.to_peniko().data.data().to_vec();
+        assert_eq!(
+            pixels(image.filtered(ImageFilter::Grayscale, half_blue)),
+            [27, 27, 54, 128]
+        );
+        assert_eq!(
+            pixels(image.filtered(
+                ImageFilter::Sepia,
+                Color {
+                    a: 1.0,
+                    ..half_blue
+                }
+            )),
+            [50, 44, 69, 255]
+        );
+        assert_ne!(
+            image.filtered(ImageFilter::None, half_blue).id(),
+            image.id()
+        );
+    }
+
+    #[test]
+    fn filter_cache_reuses_copies_until_unused() {
+        let image = Image::new(1, 1, vec![255; 4]).unwrap();
+        let mut cache = FilterCache::default();
+        let plain = ImageStyle {
+            opacity: 0.5,
+            ..ImageStyle::default()
+        };
+        assert_eq!(cache.get(&image, &plain).id(), image.id());
+        assert!(cache.is_empty());
+
+        let gray = ImageStyle {
+            filter: ImageFilter::Grayscale,
+            ..ImageStyle::default()
+        };
+        let first = cache.get(&image, &gray);
+        assert_eq!(cache.get(&image, &gray).id(), first.id());
+        cache.trim();
+        assert_eq!(cache.len(), 1);
+        cache.trim();
+        assert!(cache.is_empty());
+
+        let sepia = ImageStyle {
+            filter: ImageFilter::Sepia,
+            ..ImageStyle::default()
+        };
+        cache.get(&image, &gray);
+        let recent = cache.get(&image, &sepia);
+        assert_eq!(cache.bytes(), 8);
+        cache.evict_to(4);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(&image, &sepia).id(), recent.id());
+    }
+
+    #[test]
+    fn store_enforces_the_byte_budget() {
+        let image = || Image::new(2, 2, vec![255; 16]).unwrap();
+        let mut store = AssetStore::default();
+        let first = store.insert(image(), 32).unwrap();
+        let second = store.insert(image(), 32).unwrap();
+        assert_ne!(first, second);
+        assert!(store.insert(image(), 32).is_none());
+
+        assert!(store.remove(first));
+        assert!(!store.remove(first));
+        assert!(store.get(first).is_none());
+        assert_eq!(store.bytes(), 16);
+        assert!(store.insert(image(), 32).is_some());
+    }
+}
@@ -0,0 +1,455 @@
+//! Scroll physics shared by host scroll regions and by guests that scroll on their own
+//! through `kinetic-press`, `kinetic-drag` and `kinetic-release`, so every component flings
+//! and bounces the same way. A drag moves the offset one-to-one within its bounds and with
+//! growing resistance past them. On release the offset keeps the drag's velocity and slows
+//! exponentially, and an offset past a bound springs back, critically damped so it settles
+//! without wobbling.
+
+use crate::geometry::Vec2;
+
+/// Guest scrollers that may be held or moving at once.
+pub const MAX_KINETIC_SCROLLERS: usize = 64;
+/// Time constant of a fling's exponential slowdown.
+const FLING_TIME_CONSTANT_MS: f32 = 325.0;
+/// Motion slower than this, in logical pixels per millisecond, stops.
+const MIN_SPEED: f32 = 0.02;
+/// Drags faster than this fling at this speed, so a burst of closely timed moves cannot
+/// send the content flying.
+const MAX_SPEED: f32 = 8.0;
+/// A drag held still this long before it is released does not fling.
+const REST_MS: f64 = 50.0;
+/// Weight of the newest move in a drag's velocity estimate.
+const VELOCITY_SMOOTHING: f32 = 0.8;
+/// How far past a bound a drag can pull the offset, approached but never reached.
+const MAX_OVERSCROLL: f32 = 120.0;
+/// How far past a bound a drag is followed; `resist` is flat long before this, and beyond
+/// it the sums would overflow.
+const MAX_PULL: f32 = 1.0e6;
+/// Natural frequency of the spring pulling an offset back inside its bounds, per
+/// millisecond.
+const SPRING_RATE: f32 = 0.012;
+/// A spring this close to its bound and slower than `MIN_SPEED` snaps home.
+const SETTLE_DISTANCE: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug)]
+struct Drag {
+    position: Vec2,
+    time_ms: f64,
+    /// Where the offset would be with no resistance past the bounds.
+    raw: Vec2,
+}
+
+/// A scroll offset within bounds, moved by drags and by the motion they leave behind.
+/// Offsets grow as content moves up and left, the opposite way to the finger.
+#[derive(Clone, Debug)]
+pub struct Inertia {
+    offset: Vec2,
+    min: Vec2,
+    max: Vec2,
+    /// Logical pixels per millisecond.
+    velocity: Vec2,
+    drag: Option<Drag>,
+}
+
+impl Inertia {
+    /// At rest at `offset`, which drags and flings may carry past `min` and `max` for a
+    /// moment but always return within.
+    pub fn new(offset: Vec2, min: Vec2, max: Vec2) -> Self {
+        let mut inertia = Self {
+            offset,
+            min,
+            max,
+            velocity: Vec2::ZERO,
+            drag: None,
+        };
+        inertia.set_bounds(min, max);
+        inertia
+    }
+
+    pub fn offset(&self) -> Vec2 {
+        self.offset
+    }
+
+    /// Move the bounds. A moving offset left outside them springs back; one at rest is
+    /// moved inside straight away.
+    pub fn set_bounds(&mut self, min: Vec2, max: Vec2) {
+        self.min = min;
+        self.max = Vec2::new(max.x.max(min.x), max.y.max(min.y));
+        if !self.is_dragging() && self.velocity == Vec2::ZERO {
+            self.offset = self.clamp(self.offset);
+        }
+    }
+
+    /// The bounds leave room to move along some axis.
+    pub fn can_scroll(&self) -> bool {
+        self.min != self.max
+    }
+
+    /// Jump to `offset` within the bounds, stopping any motion.
+    pub fn jump_to(&mut self, offset: Vec2) {
+        if !offset.is_finite() {
+            return;
+        }
+        self.offset = self.clamp(offset);
+        self.velocity = Vec2::ZERO;
+        if let Some(drag) = self.drag.as_mut() {
+            drag.raw = self.offset;
+        }
+    }
+
+    /// Move by `delta` within the bounds, as a wheel does, stopping any motion; false
+    /// when already at the bound in that direction.
+    pub fn scroll_by(&mut self, delta: Vec2) -> bool {
+        let before = self.offset;
+        self.jump_to(self.clamp(before) + delta);
+        self.offset != before
+    }
+
+    /// A finger went down at `position`, catching any motion.
+    pub fn press(&mut self, position: Vec2, time_ms: f64) {
+        self.velocity = Vec2::ZERO;
+        self.drag = Some(Drag {
+            position,
+            time_ms,
+            raw: self.offset,
+        });
+    }
+
+    /// The finger moved to `position`; false when the offset did not move.
+    pub fn drag_to(&mut self, position: Vec2, time_ms: f64) -> bool {
+        let Some(drag) = self.drag.as_mut() else {
+            return false;
+        };
+        let delta = drag.position - position;
+        let elapsed = (time_ms - drag.time_ms) as f32;
+        drag.position = position;
+        drag.time_ms = time_ms;
+        if !delta.is_finite() {
+            return false;
+        }
+        let velocity =
+            delta * (VELOCITY_SMOOTHING / elapsed) + self.velocity * (1.0 - VELOCITY_SMOOTHING);
+        if elapsed > 0.0 && velocity.is_finite() {
+            self.velocity = Vec2::new(
+                velocity.x.clamp(-MAX_SPEED, MAX_SPEED),
+                velocity.y.clamp(-MAX_SPEED, MAX_SPEED),
+            );
+        }
+        let raw = drag.raw + delta;
+        let raw = Vec2::new(
+            raw.x.clamp(self.min.x - MAX_PULL, self.max.x + MAX_PULL),
+            raw.y.clamp(self.min.y - MAX_PULL, self.max.y + MAX_PULL),
+        );
+        drag.raw = raw;
+        let before = self.offset;
+        self.offset = Vec2::new(
+            resist(raw.x, self.min.x, self.max.x),
+            resist(raw.y, self.min.y, self.max.y),
+        );
+        self.offset != before
+    }
+
+    /// The finger lifted: fling on unless it rested first, and spring back if pulled past
+    /// a bound.
+    pub fn release(&mut self, time_ms: f64) {
+        let Some(drag) = self.drag.take() else {
+            return;
+        };
+        let rested = time_ms - drag.time_ms > REST_MS;
+        if rested || self.velocity.x.hypot(self.velocity.y) < MIN_SPEED {
+            self.velocity = Vec2::ZERO;
+        }
+        let bound = self.clamp(self.offset);
+        self.velocity = Vec2::new(
+            bounce(self.offset.x - bound.x, self.velocity.x),
+            bounce(self.offset.y - bound.y, self.velocity.y),
+        );
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Released and still flinging or springing back.
+    pub fn is_moving(&self) -> bool {
+        !self.is_dragging()
+            && (self.velocity != Vec2::ZERO || self.offset != self.clamp(self.offset))
+    }
+
+    /// Carry the motion `dt_ms` forward; false when the offset did not move.
+    pub fn advance(&mut self, dt_ms: f32) -> bool {
+        if !(dt_ms.is_finite() && dt_ms > 0.0 && self.is_moving()) {
+            return false;
+        }
+        let before = self.offset;
+        let (x, vx) = step(
+            self.offset.x,
+            self.velocity.x,
+            self.min.x,
+            self.max.x,
+            dt_ms,
+        );
+        let (y, vy) = step(
+            self.offset.y,
+            self.velocity.y,
+            self.min.y,
+            self.max.y,
+            dt_ms,
+        );
+        self.offset = Vec2::new(x, y);
+        self.velocity = Vec2::new(vx, vy);
+        self.offset != before
+    }
+
+    fn clamp(&self, offset: Vec2) -> Vec2 {
+        Vec2::new(
+            offset.x.clamp(self.min.x, self.max.x),
+            offset.y.clamp(self.min.y, self.max.y),
+        )
+    }
+}
+
+/// Scrollers a guest drives through `kinetic-press`, `kinetic-drag` and `kinetic-release`,
+/// kept only while held or moving. The guest owns the offset at rest; the host reports
+/// each frame's motion after a release through `kinetic-scrolled`.
+#[derive(Debug, Default)]
+pub struct KineticScrollers {
+    scrollers: Vec<(u32, Inertia)>,
+}
+
+impl KineticScrollers {
+    /// Press scroller `id` at `position`. One still moving is caught where it is; otherwise
+    /// it starts at `offset` between `min` and `max`. False when a value is not finite or
+    /// `MAX_KINETIC_SCROLLERS` are active.
+    pub fn press(
+        &mut self,
+        id: u32,
+        position: Vec2,
+        offset: Vec2,
+        min: Vec2,
+        max: Vec2,
+        time_ms: f64,
+    ) -> bool {
+        if ![position, offset, min, max]
+            .iter()
+            .all(|value| value.is_finite())
+        {
+            return false;
+        }
+        if let Some(inertia) = self.get_mut(id) {
+            if !inertia.is_moving() {
+                *inertia = Inertia::new(offset, min, max);
+            }
+            inertia.set_bounds(min, max);
+            inertia.press(position, time_ms);
+            return true;
+        }
+        if self.scrollers.len() >= MAX_KINETIC_SCROLLERS {
+            return false;
+        }
+        let mut inertia = Inertia::new(offset, min, max);
+        inertia.press(position, time_ms);
+        self.scrollers.push((id, inertia));
+        true
+    }
+
+    /// Drag held scroller `id` to `position`, returning its new offset.
+    pub fn drag(&mut self, id: u32, position: Vec2, time_ms: f64) -> Option<Vec2> {
+        let inertia = self.get_mut(id).filter(|inertia| inertia.is_dragging())?;
+        inertia.drag_to(position, time_ms);
+        Some(inertia.offset())
+    }
+
+    /// Let go of scroller `id`, which flings or springs back from here, or is forgotten
+    /// when it has nowhere to go.
+    pub fn release(&mut self, id: u32, time_ms: f64) {
+        if let Some(inertia) = self.get_mut(id) {
+            inertia.release(time_ms);
+        }
+        self.scrollers
+            .retain(|(_, inertia)| inertia.is_dragging() || inertia.is_moving());
+    }
+
+    /// Forget scroller `id`, returning where it stopped.
+    pub fn stop(&mut self, id: u32) -> Option<Vec2> {
+        let index = self.scrollers.iter().position(|(other, _)| *other == id)?;
+        Some(self.scrollers.remove(index).1.offset())
+    }
+
+    /// Some released scroller is still flinging or springing back.
+    pub fn is_moving(&self) -> bool {
+        self.scrollers
+            .iter()
+            .any(|(_, inertia)| inertia.is_moving())
+    }
+
+    /// Carry every released scroller `dt_ms` forward, returning each one that moved with
+    /// its new offset and how far it went. Scrollers that come to rest are forgotten.
+    pub fn advance(&mut self, dt_ms: f32) -> Vec<(u32, Vec2, Vec2)> {
+        let mut moved = Vec::new();
+        self.scrollers.retain_mut(|(id, inertia)| {
+            let before = inertia.offset();
+            if inertia.advance(dt_ms) {
+                moved.push((*id, inertia.offset(), inertia.offset() - before));
+            }
+            inertia.is_dragging() || inertia.is_moving()
+        });
+        moved
+    }
+
+    fn get_mut(&mut self, id: u32) -> Option<&mut Inertia> {
+        self.scrollers
+            .iter_mut()
+            .find(|(other, _)| *other == id)
+            .map(|(_, inertia)| inertia)
+    }
+}
+
+/// Where a drag that would reach `raw` shows, held back past the bounds.
+fn resist(raw: f32, min: f32, max: f32) -> f32 {
+    let pull = |distance: f32| distance * MAX_OVERSCROLL / (distance + MAX_OVERSCROLL);
+    if raw < min {
+        min - pull(min - raw)
+    } else if raw > max {
+        max + pull(raw - max)
+    } else {
+        raw
+    }
+}
+
+/// `velocity` slowed, when `overshoot` past a bound is not zero, so that the spring pulling
+/// it back peaks within `MAX_OVERSCROLL`.
+fn bounce(overshoot: f32, velocity: f32) -> f32 {
+    if overshoot == 0.0 {
+        return velocity;
+    }
+    // The spring peaks at most `overshoot + velocity / (SPRING_RATE * e)` out.
+    let room = (MAX_OVERSCROLL - overshoot.abs()).max(0.0);
+    let limit = room * SPRING_RATE * std::f32::consts::E;
+    velocity.clamp(-limit, limit)
+}
+
+/// One axis of a released offset `dt_ms` later, with its new velocity.
+fn step(offset: f32, velocity: f32, min: f32, max: f32, dt_ms: f32) -> (f32, f32) {
+    let bound = offset.clamp(min, max);
+    if offset != bound {
+        // x(t) = (x0 + (v0 + w x0) t) e^(-w t), the critically damped spring.
+        let x = offset - bound;
+        let rate = velocity + SPRING_RATE * x;
+        let decay = (-SPRING_RATE * dt_ms).exp();
+        let x = (x + rate * dt_ms) * decay;
+        let velocity = (velocity - rate * SPRING_RATE * dt_ms) * decay;
+        if x.abs() < SETTLE_DISTANCE && velocity.abs() < MIN_SPEED {
+            return (bound, 0.0);
+        }
+        return (bound + x, velocity);
+    }
+    // The distance an exponentially slowing fling covers in `dt_ms`, exact for any step so
+    // a stalled frame does not overshoot.
+    let decay = (-dt_ms / FLING_TIME_CONSTANT_MS).exp();
+    let offset = offset + velocity * FLING_TIME_CONSTANT_MS * (1.0 - decay);
+    let velocity = velocity * decay;
+    let bound = offset.clamp(min, max);
+    if offset != bound {
+        // Hitting an end: hold the overshoot back as a drag would, and the spring takes
+        // over next step.
+        let offset = resist(offset, min, max);
+        return (offset, bounce(offset - bound, velocity));
+    }
+    if velocity.abs() < MIN_SPEED {
+        (offset, 0.0)
+    } else {
+        (offset, velocity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Inertia, MAX_OVERSCROLL};
+    use crate::geometry::Vec2;
+
+    fn list() -> Inertia {
+        Inertia::new(Vec2::ZERO, Vec2::ZERO, Vec2::new(0.0, 1000.0))
+    }
+
+    /// Advance until the motion stops, returning every offset on the way.
+    fn settle(inertia: &mut Inertia) -> Vec<f32> {
+        let mut offsets = Vec::new();
+        for _ in 0..1000 {
+            if !inertia.advance(16.0) {
+                break;
+            }
+            offsets.push(inertia.offset().y);
+        }
+        assert!(!inertia.is_moving());
+        offsets
+    }
+
+    #[test]
+    fn quick_drags_fling_and_slow_down() {
+        let mut inertia = list();
+        inertia.press(Vec2::new(0.0, 300.0), 0.0);
+        assert!(inertia.drag_to(Vec2::new(0.0, 290.0), 10.0));
+        assert!(inertia.drag_to(Vec2::new(0.0, 270.0), 20.0));
+        assert_eq!(inertia.offset(), Vec2::new(0.0, 30.0));
+        inertia.release(25.0);
+        assert!(inertia.is_moving());
+        let offsets = settle(&mut inertia);
+        assert!(offsets.windows(2).all(|pair| pair[1] > pair[0]));
+        let last = offsets.last().copied().unwrap();
+        assert!(last > 100.0 && last < 1000.0, "flung to {last}");
+
+        // A drag that rests before lifting stops where it is.
+        inertia.press(Vec2::ZERO, 1000.0);
+        inertia.drag_to(Vec2::new(0.0, -20.0), 1010.0);
+        inertia.release(1200.0);
+        assert!(!inertia.is_moving());
+    }
+
+    #[test]
+    fn pulls_past_a_bound_resist_and_spring_back() {
+        let mut inertia = list();
+        inertia.press(Vec2::ZERO, 0.0);
+        inertia.drag_to(Vec2::new(0.0, 1000.0), 2000.0);
+        let pulled = inertia.offset().y;
+        assert!(
+            pulled < 0.0 && pulled > -MAX_OVERSCROLL,
+            "pulled to {pulled}"
+        );
+        inertia.release(2100.0);
+        let offsets = settle(&mut inertia);
+        assert!(
+            offsets.windows(2).all(|pair| pair[1] > pair[0]),
+            "no wobble"
+        );
+        assert_eq!(inertia.offset(), Vec2::ZERO);
+
+        // A fling into the end overshoots a little, then comes back to it.
+        inertia.jump_to(Vec2::new(0.0, 990.0));
+        inertia.press(Vec2::ZERO, 3000.0);
+        inertia.drag_to(Vec2::new(0.0, -5.0), 3001.0);
+        inertia.release(3001.0);
+        let offsets = settle(&mut inertia);
+        let furthest = offsets.iter().copied().fold(0.0, f32::max);
+        assert!(furthest > 1000.0 && furthest < 1000.0 + MAX_OVERSCROLL);
+        assert_eq!(inertia.offset(), Vec2::new(0.0, 1000.0));
+
+        // Wheels stop at the bounds.
+        assert!(!inertia.scroll_by(Vec2::new(0.0, 10.0)));
+        assert!(inertia.scroll_by(Vec2::new(0.0, -10.0)));
+    }
+
+    #[test]
+    fn huge_drags_stay_finite_and_settle() {
+        let mut inertia = list();
+        inertia.press(Vec2::new(0.0, f32::MAX), 0.0);
+        inertia.drag_to(Vec2::ZERO, 100.0);
+        inertia.drag_to(Vec2::new(0.0, -f32::MAX), 200.0);
+        assert!(inertia.offset().is_finite());
+        inertia.jump_to(Vec2::new(f32::NAN, 0.0));
+        assert!(inertia.offset().is_finite());
+        inertia.release(400.0);
+        settle(&mut inertia);
+        assert!(!inertia.is_moving());
+    }
+}
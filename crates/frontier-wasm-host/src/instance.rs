@@ -0,0 +1,181 @@
+//! `--single-instance`: the first host running a component listens on a per-user socket
+//! named after it; later launches of the same component hand their arguments over that
+//! socket and exit instead of opening a second window. The running host brings its
+//! window to the front and tells the guest through `instance-activated`.
+//!
+//! Needs unix domain sockets; elsewhere every launch runs on its own.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use winit::event_loop::EventLoopProxy;
+
+use crate::app::AppEvent;
+
+/// What a second launch passes on to the running host.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Activation {
+    /// Launch arguments after `--`.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// `--open` link, if any.
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// Socket the instance running `component` listens on; `None` is the embedded demo.
+pub fn socket_path(component: Option<&Path>) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    match component {
+        Some(path) => path
+            .canonicalize()
+            .unwrap_or_else(|_| path.to_path_buf())
+            .hash(&mut hasher),
+        None => "embedded".hash(&mut hasher),
+    }
+    let dir = dirs::runtime_dir().unwrap_or_else(std::env::temp_dir);
+    dir.join(format!("frontier-{:016x}.sock", hasher.finish()))
+}
+
+/// Keeps the instance socket open; removes it when dropped.
+pub struct InstanceGuard {
+    #[cfg(unix)]
+    path: PathBuf,
+}
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Hand `activation` to a host already running on `path`. Returns false when there is
+/// none, in which case this launch should [`listen`] and run normally.
+#[cfg(unix)]
+pub fn forward(path: &Path, activation: &Activation) -> Result<bool> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    use anyhow::{bail, Context};
+
+    let mut stream = match UnixStream::connect(path) {
+        Ok(stream) => stream,
+        // Nothing listening: no socket, or one left behind by a host that crashed.
+        Err(_) => return Ok(false),
+    };
+    serde_json::to_writer(&mut stream, activation)?;
+    stream.write_all(b"\n")?;
+    let mut reply = String::new();
+    BufReader::new(stream)
+        .read_line(&mut reply)
+        .context("running instance did not answer")?;
+    let reply: serde_json::Value =
+        serde_json::from_str(&reply).context("invalid answer from the running instance")?;
+    if reply["ok"] != true {
+        bail!(
+            "running instance refused the activation: {}",
+            reply["error"]
+        );
+    }
+    Ok(true)
+}
+
+#[cfg(not(unix))]
+pub fn forward(_path: &Path, _activation: &Activation) -> Result<bool> {
+    Ok(false)
+}
+
+/// Accept activations from later launches on `path` and pass them to the event loop.
+#[cfg(unix)]
+pub fn listen(path: &Path, proxy: EventLoopProxy<AppEvent>) -> Result<InstanceGuard> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+
+    use anyhow::{anyhow, Context};
+
+    use crate::control::{self, ControlCommand};
+
+    // `forward` found nobody listening, so a socket file here is stale.
+    let _ = std::fs::remove_file(path);
+    let listener = UnixListener::bind(path)
+        .with_context(|| format!("failed to bind instance socket {}", path.display()))?;
+    tracing::info!(path = %path.display(), "single-instance socket listening");
+    std::thread::Builder::new()
+        .name("frontier-instance".into())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                let result = stream.map_err(anyhow::Error::from).and_then(|stream| {
+                    let mut line = String::new();
+                    BufReader::new(&stream).read_line(&mut line)?;
+                    let reply = match serde_json::from_str::<Activation>(&line) {
+                        Ok(Activation { args, url }) => {
+                            control::dispatch(ControlCommand::Activate { args, url }, &proxy)
+                        }
+                        Err(err) => control::response(Err(anyhow!("invalid activation: {err}"))),
+                    };
+                    let mut stream = &stream;
+                    serde_json::to_writer(&mut stream, &reply)?;
+                    stream.write_all(b"\n")?;
+                    Ok(())
+                });
+                if let Err(err) = result {
+                    tracing::debug!(error = %err, "instance activation failed");
+                }
+            }
+        })
+        .context("failed to start instance socket thread")?;
+    Ok(InstanceGuard {
+        path: path.to_path_buf(),
+    })
+}
+
+#[cfg(not(unix))]
+pub fn listen(_path: &Path, _proxy: EventLoopProxy<AppEvent>) -> Result<InstanceGuard> {
+    tracing::warn!("--single-instance is not supported on this platform");
+    Ok(InstanceGuard {})
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+    use std::path::Path;
+
+    use super::{forward, socket_path, Activation};
+
+    #[test]
+    fn socket_names_depend_on_the_component() {
+        let a = socket_path(Some(Path::new("/nonexistent/a.wasm")));
+        assert_eq!(a, socket_path(Some(Path::new("/nonexistent/a.wasm"))));
+        assert_ne!(a, socket_path(Some(Path::new("/nonexistent/b.wasm"))));
+        assert_ne!(a, socket_path(None));
+    }
+
+    #[test]
+    fn forwards_activations_to_a_listening_instance() {
+        let path =
+            std::env::temp_dir().join(format!("frontier-instance-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let activation = Activation {
+            args: vec!["notes.txt".into()],
+            url: Some("frontier://notes/1".into()),
+        };
+        assert!(!forward(&path, &activation).unwrap());
+
+        let listener = UnixListener::bind(&path).unwrap();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut line = String::new();
+            BufReader::new(&stream).read_line(&mut line).unwrap();
+            (&stream).write_all(b"{\"ok\":true}\n").unwrap();
+            serde_json::from_str::<Activation>(&line).unwrap()
+        });
+        assert!(forward(&path, &activation).unwrap());
+        assert_eq!(server.join().unwrap(), activation);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,814 @@
+//! Maps winit keys onto the W3C UI Events `KeyboardEvent.key` and `KeyboardEvent.code`
+//! values, so guests compiled from web-oriented code can reuse their key handling.
+//!
+//! winit's names mostly follow the specification already; the exceptions are `Space`
+//! (whose key value is `" "`) and `Super`/`SuperLeft`/`SuperRight` (`Meta`, `MetaLeft`,
+//! `MetaRight`). Anything winit cannot identify becomes `"Unidentified"`.
+
+use winit::event::{ElementState, Modifiers as WinitModifiers};
+use winit::keyboard::{
+    Key, KeyCode, KeyLocation as WinitKeyLocation, ModifiersKeyState, NamedKey, PhysicalKey,
+};
+
+use crate::model::{KeyLocation, Modifiers};
+
+const UNIDENTIFIED: &str = "Unidentified";
+
+/// `KeyboardEvent.key`: the character produced, or the name of a non-printing key.
+pub fn key_value(key: &Key) -> String {
+    match key {
+        Key::Character(ch) => ch.to_string(),
+        Key::Named(named) => named_key_value(*named).to_string(),
+        Key::Dead(_) => "Dead".to_string(),
+        Key::Unidentified(_) => UNIDENTIFIED.to_string(),
+    }
+}
+
+/// `KeyboardEvent.code`: the physical key, independent of keyboard layout.
+pub fn code_value(key: &PhysicalKey) -> &'static str {
+    match key {
+        PhysicalKey::Code(code) => key_code_value(*code),
+        PhysicalKey::Unidentified(_) => UNIDENTIFIED,
+    }
+}
+
+/// `KeyboardEvent.location`.
+pub fn key_location(location: WinitKeyLocation) -> KeyLocation {
+    match location {
+        WinitKeyLocation::Standard => KeyLocation::Standard,
+        WinitKeyLocation::Left => KeyLocation::Left,
+        WinitKeyLocation::Right => KeyLocation::Right,
+        WinitKeyLocation::Numpad => KeyLocation::Numpad,
+    }
+}
+
+/// Update the held sides and lock toggles in `modifiers` from a raw key event. winit has
+/// no way to query lock state, so Caps and Scroll Lock flip on each fresh press and start
+/// off. NumLock stays unknown until a keypad digit reports a digit (on) or a navigation
+/// key (off); after that its key toggles it.
+pub fn track_key(
+    modifiers: &mut Modifiers,
+    key: &PhysicalKey,
+    logical: &Key,
+    state: ElementState,
+    repeat: bool,
+) {
+    let PhysicalKey::Code(code) = key else {
+        return;
+    };
+    let pressed = state == ElementState::Pressed;
+    // Shift inverts the keypad on some platforms, so only unshifted presses say anything.
+    if pressed && !modifiers.shift && is_keypad_digit(*code) {
+        match logical {
+            Key::Character(_) => modifiers.locks.num_lock = Some(true),
+            Key::Named(_) => modifiers.locks.num_lock = Some(false),
+            Key::Dead(_) | Key::Unidentified(_) => {}
+        }
+    }
+    let keys = &mut modifiers.keys;
+    let locks = &mut modifiers.locks;
+    match code {
+        KeyCode::ShiftLeft => keys.left_shift = pressed,
+        KeyCode::ShiftRight => keys.right_shift = pressed,
+        KeyCode::ControlLeft => keys.left_ctrl = pressed,
+        KeyCode::ControlRight => keys.right_ctrl = pressed,
+        KeyCode::AltLeft => keys.left_alt = pressed,
+        KeyCode::AltRight => keys.right_alt = pressed,
+        KeyCode::SuperLeft => keys.left_meta = pressed,
+        KeyCode::SuperRight => keys.right_meta = pressed,
+        KeyCode::CapsLock if pressed && !repeat => locks.caps_lock = !locks.caps_lock,
+        KeyCode::NumLock if pressed && !repeat => locks.num_lock = locks.num_lock.map(|on| !on),
+        KeyCode::ScrollLock if pressed && !repeat => locks.scroll_lock = !locks.scroll_lock,
+        _ => {}
+    }
+}
+
+fn is_keypad_digit(code: KeyCode) -> bool {
+    matches!(
+        code,
+        KeyCode::Numpad0
+            | KeyCode::Numpad1
+            | KeyCode::Numpad2
+            | KeyCode::Numpad3
+            | KeyCode::Numpad4
+            | KeyCode::Numpad5
+            | KeyCode::Numpad6
+            | KeyCode::Numpad7
+            | KeyCode::Numpad8
+            | KeyCode::Numpad9
+            | KeyCode::NumpadDecimal
+    )
+}
+
+/// Apply winit's modifier state. Sides winit reports as pressed are marked held, and a
+/// modifier that is no longer down clears both of its sides (its key-up may have gone to
+/// another window).
+pub fn apply_modifiers(modifiers: &mut Modifiers, state: &WinitModifiers) {
+    let flags = state.state();
+    modifiers.shift = flags.shift_key();
+    modifiers.ctrl = flags.control_key();
+    modifiers.alt = flags.alt_key();
+    modifiers.meta = flags.super_key();
+
+    let keys = &mut modifiers.keys;
+    sync_sides(
+        modifiers.shift,
+        [state.lshift_state(), state.rshift_state()],
+        [&mut keys.left_shift, &mut keys.right_shift],
+    );
+    sync_sides(
+        modifiers.ctrl,
+        [state.lcontrol_state(), state.rcontrol_state()],
+        [&mut keys.left_ctrl, &mut keys.right_ctrl],
+    );
+    sync_sides(
+        modifiers.alt,
+        [state.lalt_state(), state.ralt_state()],
+        [&mut keys.left_alt, &mut keys.right_alt],
+    );
+    sync_sides(
+        modifiers.meta,
+        [state.lsuper_state(), state.rsuper_state()],
+        [&mut keys.left_meta, &mut keys.right_meta],
+    );
+}
+
+fn sync_sides(held: bool, states: [ModifiersKeyState; 2], sides: [&mut bool; 2]) {
+    for (state, side) in states.into_iter().zip(sides) {
+        *side = held && (*side || state == ModifiersKeyState::Pressed);
+    }
+}
+
+fn named_key_value(key: NamedKey) -> &'static str {
+    match key {
+        NamedKey::Alt => "Alt",
+        NamedKey::AltGraph => "AltGraph",
+        NamedKey::CapsLock => "CapsLock",
+        NamedKey::Control => "Control",
+        NamedKey::Fn => "Fn",
+        NamedKey::FnLock => "FnLock",
+        NamedKey::NumLock => "NumLock",
+        NamedKey::ScrollLock => "ScrollLock",
+        NamedKey::Shift => "Shift",
+        NamedKey::Symbol => "Symbol",
+        NamedKey::SymbolLock => "SymbolLock",
+        NamedKey::Meta => "Meta",
+        NamedKey::Hyper => "Hyper",
+        NamedKey::Super => "Meta",
+        NamedKey::Enter => "Enter",
+        NamedKey::Tab => "Tab",
+        NamedKey::Space => " ",
+        NamedKey::ArrowDown => "ArrowDown",
+        NamedKey::ArrowLeft => "ArrowLeft",
+        NamedKey::ArrowRight => "ArrowRight",
+        NamedKey::ArrowUp => "ArrowUp",
+        NamedKey::End => "End",
+        NamedKey::Home => "Home",
+        NamedKey::PageDown => "PageDown",
+        NamedKey::PageUp => "PageUp",
+        NamedKey::Backspace => "Backspace",
+        NamedKey::Clear => "Clear",
+        NamedKey::Copy => "Copy",
+        NamedKey::CrSel => "CrSel",
+        NamedKey::Cut => "Cut",
+        NamedKey::Delete => "Delete",
+        NamedKey::EraseEof => "EraseEof",
+        NamedKey::ExSel => "ExSel",
+        NamedKey::Insert => "Insert",
+        NamedKey::Paste => "Paste",
+        NamedKey::Redo => "Redo",
+        NamedKey::Undo => "Undo",
+        NamedKey::Accept => "Accept",
+        NamedKey::Again => "Again",
+        NamedKey::Attn => "Attn",
+        NamedKey::Cancel => "Cancel",
+        NamedKey::ContextMenu => "ContextMenu",
+        NamedKey::Escape => "Escape",
+        NamedKey::Execute => "Execute",
+        NamedKey::Find => "Find",
+        NamedKey::Help => "Help",
+        NamedKey::Pause => "Pause",
+        NamedKey::Play => "Play",
+        NamedKey::Props => "Props",
+        NamedKey::Select => "Select",
+        NamedKey::ZoomIn => "ZoomIn",
+        NamedKey::ZoomOut => "ZoomOut",
+        NamedKey::BrightnessDown => "BrightnessDown",
+        NamedKey::BrightnessUp => "BrightnessUp",
+        NamedKey::Eject => "Eject",
+        NamedKey::LogOff => "LogOff",
+        NamedKey::Power => "Power",
+        NamedKey::PowerOff => "PowerOff",
+        NamedKey::PrintScreen => "PrintScreen",
+        NamedKey::Hibernate => "Hibernate",
+        NamedKey::Standby => "Standby",
+        NamedKey::WakeUp => "WakeUp",
+        NamedKey::AllCandidates => "AllCandidates",
+        NamedKey::Alphanumeric => "Alphanumeric",
+        NamedKey::CodeInput => "CodeInput",
+        NamedKey::Compose => "Compose",
+        NamedKey::Convert => "Convert",
+        NamedKey::FinalMode => "FinalMode",
+        NamedKey::GroupFirst => "GroupFirst",
+        NamedKey::GroupLast => "GroupLast",
+        NamedKey::GroupNext => "GroupNext",
+        NamedKey::GroupPrevious => "GroupPrevious",
+        NamedKey::ModeChange => "ModeChange",
+        NamedKey::NextCandidate => "NextCandidate",
+        NamedKey::NonConvert => "NonConvert",
+        NamedKey::PreviousCandidate => "PreviousCandidate",
+        NamedKey::Process => "Process",
+        NamedKey::SingleCandidate => "SingleCandidate",
+        NamedKey::HangulMode => "HangulMode",
+        NamedKey::HanjaMode => "HanjaMode",
+        NamedKey::JunjaMode => "JunjaMode",
+        NamedKey::Eisu => "Eisu",
+        NamedKey::Hankaku => "Hankaku",
+        NamedKey::Hiragana => "Hiragana",
+        NamedKey::HiraganaKatakana => "HiraganaKatakana",
+        NamedKey::KanaMode => "KanaMode",
+        NamedKey::KanjiMode => "KanjiMode",
+        NamedKey::Katakana => "Katakana",
+        NamedKey::Romaji => "Romaji",
+        NamedKey::Zenkaku => "Zenkaku",
+        NamedKey::ZenkakuHankaku => "ZenkakuHankaku",
+        NamedKey::Soft1 => "Soft1",
+        NamedKey::Soft2 => "Soft2",
+        NamedKey::Soft3 => "Soft3",
+        NamedKey::Soft4 => "Soft4",
+        NamedKey::ChannelDown => "ChannelDown",
+        NamedKey::ChannelUp => "ChannelUp",
+        NamedKey::Close => "Close",
+        NamedKey::MailForward => "MailForward",
+        NamedKey::MailReply => "MailReply",
+        NamedKey::MailSend => "MailSend",
+        NamedKey::MediaClose => "MediaClose",
+        NamedKey::MediaFastForward => "MediaFastForward",
+        NamedKey::MediaPause => "MediaPause",
+        NamedKey::MediaPlay => "MediaPlay",
+        NamedKey::MediaPlayPause => "MediaPlayPause",
+        NamedKey::MediaRecord => "MediaRecord",
+        NamedKey::MediaRewind => "MediaRewind",
+        NamedKey::MediaStop => "MediaStop",
+        NamedKey::MediaTrackNext => "MediaTrackNext",
+        NamedKey::MediaTrackPrevious => "MediaTrackPrevious",
+        NamedKey::New => "New",
+        NamedKey::Open => "Open",
+        NamedKey::Print => "Print",
+        NamedKey::Save => "Save",
+        NamedKey::SpellCheck => "SpellCheck",
+        NamedKey::Key11 => "Key11",
+        NamedKey::Key12 => "Key12",
+        NamedKey::AudioBalanceLeft => "AudioBalanceLeft",
+        NamedKey::AudioBalanceRight => "AudioBalanceRight",
+        NamedKey::AudioBassBoostDown => "AudioBassBoostDown",
+        NamedKey::AudioBassBoostToggle => "AudioBassBoostToggle",
+        NamedKey::AudioBassBoostUp => "AudioBassBoostUp",
+        NamedKey::AudioFaderFront => "AudioFaderFront",
+        NamedKey::AudioFaderRear => "AudioFaderRear",
+        NamedKey::AudioSurroundModeNext => "AudioSurroundModeNext",
+        NamedKey::AudioTrebleDown => "AudioTrebleDown",
+        NamedKey::AudioTrebleUp => "AudioTrebleUp",
+        NamedKey::AudioVolumeDown => "AudioVolumeDown",
+        NamedKey::AudioVolumeUp => "AudioVolumeUp",
+        NamedKey::AudioVolumeMute => "AudioVolumeMute",
+        NamedKey::MicrophoneToggle => "MicrophoneToggle",
+        NamedKey::MicrophoneVolumeDown => "MicrophoneVolumeDown",
+        NamedKey::MicrophoneVolumeUp => "MicrophoneVolumeUp",
+        NamedKey::MicrophoneVolumeMute => "MicrophoneVolumeMute",
+        NamedKey::SpeechCorrectionList => "SpeechCorrectionList",
+        NamedKey::SpeechInputToggle => "SpeechInputToggle",
+        NamedKey::LaunchApplication1 => "LaunchApplication1",
+        NamedKey::LaunchApplication2 => "LaunchApplication2",
+        NamedKey::LaunchCalendar => "LaunchCalendar",
+        NamedKey::LaunchContacts => "LaunchContacts",
+        NamedKey::LaunchMail => "LaunchMail",
+        NamedKey::LaunchMediaPlayer => "LaunchMediaPlayer",
+        NamedKey::LaunchMusicPlayer => "LaunchMusicPlayer",
+        NamedKey::LaunchPhone => "LaunchPhone",
+        NamedKey::LaunchScreenSaver => "LaunchScreenSaver",
+        NamedKey::LaunchSpreadsheet => "LaunchSpreadsheet",
+        NamedKey::LaunchWebBrowser => "LaunchWebBrowser",
+        NamedKey::LaunchWebCam => "LaunchWebCam",
+        NamedKey::LaunchWordProcessor => "LaunchWordProcessor",
+        NamedKey::BrowserBack => "BrowserBack",
+        NamedKey::BrowserFavorites => "BrowserFavorites",
+        NamedKey::BrowserForward => "BrowserForward",
+        NamedKey::BrowserHome => "BrowserHome",
+        NamedKey::BrowserRefresh => "BrowserRefresh",
+        NamedKey::BrowserSearch => "BrowserSearch",
+        NamedKey::BrowserStop => "BrowserStop",
+        NamedKey::AppSwitch => "AppSwitch",
+        NamedKey::Call => "Call",
+        NamedKey::Camera => "Camera",
+        NamedKey::CameraFocus => "CameraFocus",
+        NamedKey::EndCall => "EndCall",
+        NamedKey::GoBack => "GoBack",
+        NamedKey::GoHome => "GoHome",
+        NamedKey::HeadsetHook => "HeadsetHook",
+        NamedKey::LastNumberRedial => "LastNumberRedial",
+        NamedKey::Notification => "Notification",
+        NamedKey::MannerMode => "MannerMode",
+        NamedKey::VoiceDial => "VoiceDial",
+        NamedKey::TV => "TV",
+        NamedKey::TV3DMode => "TV3DMode",
+        NamedKey::TVAntennaCable => "TVAntennaCable",
+        NamedKey::TVAudioDescription => "TVAudioDescription",
+        NamedKey::TVAudioDescriptionMixDown => "TVAudioDescriptionMixDown",
+        NamedKey::TVAudioDescriptionMixUp => "TVAudioDescriptionMixUp",
+        NamedKey::TVContentsMenu => "TVContentsMenu",
+        NamedKey::TVDataService => "TVDataService",
+        NamedKey::TVInput => "TVInput",
+        NamedKey::TVInputComponent1 => "TVInputComponent1",
+        NamedKey::TVInputComponent2 => "TVInputComponent2",
+        NamedKey::TVInputComposite1 => "TVInputComposite1",
+        NamedKey::TVInputComposite2 => "TVInputComposite2",
+        NamedKey::TVInputHDMI1 => "TVInputHDMI1",
+        NamedKey::TVInputHDMI2 => "TVInputHDMI2",
+        NamedKey::TVInputHDMI3 => "TVInputHDMI3",
+        NamedKey::TVInputHDMI4 => "TVInputHDMI4",
+        NamedKey::TVInputVGA1 => "TVInputVGA1",
+        NamedKey::TVMediaContext => "TVMediaContext",
+        NamedKey::TVNetwork => "TVNetwork",
+        NamedKey::TVNumberEntry => "TVNumberEntry",
+        NamedKey::TVPower => "TVPower",
+        NamedKey::TVRadioService => "TVRadioService",
+        NamedKey::TVSatellite => "TVSatellite",
+        NamedKey::TVSatelliteBS => "TVSatelliteBS",
+        NamedKey::TVSatelliteCS => "TVSatelliteCS",
+        NamedKey::TVSatelliteToggle => "TVSatelliteToggle",
+        NamedKey::TVTerrestrialAnalog => "TVTerrestrialAnalog",
+        NamedKey::TVTerrestrialDigital => "TVTerrestrialDigital",
+        NamedKey::TVTimer => "TVTimer",
+        NamedKey::AVRInput => "AVRInput",
+        NamedKey::AVRPower => "AVRPower",
+        NamedKey::ColorF0Red => "ColorF0Red",
+        NamedKey::ColorF1Green => "ColorF1Green",
+        NamedKey::ColorF2Yellow => "ColorF2Yellow",
+        NamedKey::ColorF3Blue => "ColorF3Blue",
+        NamedKey::ColorF4Grey => "ColorF4Grey",
+        NamedKey::ColorF5Brown => "ColorF5Brown",
+        NamedKey::ClosedCaptionToggle => "ClosedCaptionToggle",
+        NamedKey::Dimmer => "Dimmer",
+        NamedKey::DisplaySwap => "DisplaySwap",
+        NamedKey::DVR => "DVR",
+        NamedKey::Exit => "Exit",
+        NamedKey::FavoriteClear0 => "FavoriteClear0",
+        NamedKey::FavoriteClear1 => "FavoriteClear1",
+        NamedKey::FavoriteClear2 => "FavoriteClear2",
+        NamedKey::FavoriteClear3 => "FavoriteClear3",
+        NamedKey::FavoriteRecall0 => "FavoriteRecall0",
+        NamedKey::FavoriteRecall1 => "FavoriteRecall1",
+        NamedKey::FavoriteRecall2 => "FavoriteRecall2",
+        NamedKey::FavoriteRecall3 => "FavoriteRecall3",
+        NamedKey::FavoriteStore0 => "FavoriteStore0",
+        NamedKey::FavoriteStore1 => "FavoriteStore1",
+        NamedKey::FavoriteStore2 => "FavoriteStore2",
+        NamedKey::FavoriteStore3 => "FavoriteStore3",
+        NamedKey::Guide => "Guide",
+        NamedKey::GuideNextDay => "GuideNextDay",
+        NamedKey::GuidePreviousDay => "GuidePreviousDay",
+        NamedKey::Info => "Info",
+        NamedKey::InstantReplay => "InstantReplay",
+        NamedKey::Link => "Link",
+        NamedKey::ListProgram => "ListProgram",
+        NamedKey::LiveContent => "LiveContent",
+        NamedKey::Lock => "Lock",
+        NamedKey::MediaApps => "MediaApps",
+        NamedKey::MediaAudioTrack => "MediaAudioTrack",
+        NamedKey::MediaLast => "MediaLast",
+        NamedKey::MediaSkipBackward => "MediaSkipBackward",
+        NamedKey::MediaSkipForward => "MediaSkipForward",
+        NamedKey::MediaStepBackward => "MediaStepBackward",
+        NamedKey::MediaStepForward => "MediaStepForward",
+        NamedKey::MediaTopMenu => "MediaTopMenu",
+        NamedKey::NavigateIn => "NavigateIn",
+        NamedKey::NavigateNext => "NavigateNext",
+        NamedKey::NavigateOut => "NavigateOut",
+        NamedKey::NavigatePrevious => "NavigatePrevious",
+        NamedKey::NextFavoriteChannel => "NextFavoriteChannel",
+        NamedKey::NextUserProfile => "NextUserProfile",
+        NamedKey::OnDemand => "OnDemand",
+        NamedKey::Pairing => "Pairing",
+        NamedKey::PinPDown => "PinPDown",
+        NamedKey::PinPMove => "PinPMove",
+        NamedKey::PinPToggle => "PinPToggle",
+        NamedKey::PinPUp => "PinPUp",
+        NamedKey::PlaySpeedDown => "PlaySpeedDown",
+        NamedKey::PlaySpeedReset => "PlaySpeedReset",
+        NamedKey::PlaySpeedUp => "PlaySpeedUp",
+        NamedKey::RandomToggle => "RandomToggle",
+        NamedKey::RcLowBattery => "RcLowBattery",
+        NamedKey::RecordSpeedNext => "RecordSpeedNext",
+        NamedKey::RfBypass => "RfBypass",
+        NamedKey::ScanChannelsToggle => "ScanChannelsToggle",
+        NamedKey::ScreenModeNext => "ScreenModeNext",
+        NamedKey::Settings => "Settings",
+        NamedKey::SplitScreenToggle => "SplitScreenToggle",
+        NamedKey::STBInput => "STBInput",
+        NamedKey::STBPower => "STBPower",
+        NamedKey::Subtitle => "Subtitle",
+        NamedKey::Teletext => "Teletext",
+        NamedKey::VideoModeNext => "VideoModeNext",
+        NamedKey::Wink => "Wink",
+        NamedKey::ZoomToggle => "ZoomToggle",
+        NamedKey::F1 => "F1",
+        NamedKey::F2 => "F2",
+        NamedKey::F3 => "F3",
+        NamedKey::F4 => "F4",
+        NamedKey::F5 => "F5",
+        NamedKey::F6 => "F6",
+        NamedKey::F7 => "F7",
+        NamedKey::F8 => "F8",
+        NamedKey::F9 => "F9",
+        NamedKey::F10 => "F10",
+        NamedKey::F11 => "F11",
+        NamedKey::F12 => "F12",
+        NamedKey::F13 => "F13",
+        NamedKey::F14 => "F14",
+        NamedKey::F15 => "F15",
+        NamedKey::F16 => "F16",
+        NamedKey::F17 => "F17",
+        NamedKey::F18 => "F18",
+        NamedKey::F19 => "F19",
+        NamedKey::F20 => "F20",
+        NamedKey::F21 => "F21",
+        NamedKey::F22 => "F22",
+        NamedKey::F23 => "F23",
+        NamedKey::F24 => "F24",
+        NamedKey::F25 => "F25",
+        NamedKey::F26 => "F26",
+        NamedKey::F27 => "F27",
+        NamedKey::F28 => "F28",
+        NamedKey::F29 => "F29",
+        NamedKey::F30 => "F30",
+        NamedKey::F31 => "F31",
+        NamedKey::F32 => "F32",
+        NamedKey::F33 => "F33",
+        NamedKey::F34 => "F34",
+        NamedKey::F35 => "F35",
+        _ => UNIDENTIFIED,
+    }
+}
+
+fn key_code_value(code: KeyCode) -> &'static str {
+    match code {
+        KeyCode::Backquote => "Backquote",
+        KeyCode::Backslash => "Backslash",
+        KeyCode::BracketLeft => "BracketLeft",
+        KeyCode::BracketRight => "BracketRight",
+        KeyCode::Comma => "Comma",
+        KeyCode::Digit0 => "Digit0",
+        KeyCode::Digit1 => "Digit1",
+        KeyCode::Digit2 => "Digit2",
+        KeyCode::Digit3 => "Digit3",
+        KeyCode::Digit4 => "Digit4",
+        KeyCode::Digit5 => "Digit5",
+        KeyCode::Digit6 => "Digit6",
+        KeyCode::Digit7 => "Digit7",
+        KeyCode::Digit8 => "Digit8",
+        KeyCode::Digit9 => "Digit9",
+        KeyCode::Equal => "Equal",
+        KeyCode::IntlBackslash => "IntlBackslash",
+        KeyCode::IntlRo => "IntlRo",
+        KeyCode::IntlYen => "IntlYen",
+        KeyCode::KeyA => "KeyA",
+        KeyCode::KeyB => "KeyB",
+        KeyCode::KeyC => "KeyC",
+        KeyCode::KeyD => "KeyD",
+        KeyCode::KeyE => "KeyE",
+        KeyCode::KeyF => "KeyF",
+        KeyCode::KeyG => "KeyG",
+        KeyCode::KeyH => "KeyH",
+        KeyCode::KeyI => "KeyI",
+        KeyCode::KeyJ => "KeyJ",
+        KeyCode::KeyK => "KeyK",
+        KeyCode::KeyL => "KeyL",
+        KeyCode::KeyM => "KeyM",
+        KeyCode::KeyN => "KeyN",
+        KeyCode::KeyO => "KeyO",
+        KeyCode::KeyP => "KeyP",
+        KeyCode::KeyQ => "KeyQ",
+        KeyCode::KeyR => "KeyR",
+        KeyCode::KeyS => "KeyS",
+        KeyCode::KeyT => "KeyT",
+        KeyCode::KeyU => "KeyU",
+        KeyCode::KeyV => "KeyV",
+        KeyCode::KeyW => "KeyW",
+        KeyCode::KeyX => "KeyX",
+        KeyCode::KeyY => "KeyY",
+        KeyCode::KeyZ => "KeyZ",
+        KeyCode::Minus => "Minus",
+        KeyCode::Period => "Period",
+        KeyCode::Quote => "Quote",
+        KeyCode::Semicolon => "Semicolon",
+        KeyCode::Slash => "Slash",
+        KeyCode::AltLeft => "AltLeft",
+        KeyCode::AltRight => "AltRight",
+        KeyCode::Backspace => "Backspace",
+        KeyCode::CapsLock => "CapsLock",
+        KeyCode::ContextMenu => "ContextMenu",
+        KeyCode::ControlLeft => "ControlLeft",
+        KeyCode::ControlRight => "ControlRight",
+        KeyCode::Enter => "Enter",
+        KeyCode::SuperLeft => "MetaLeft",
+        KeyCode::SuperRight => "MetaRight",
+        KeyCode::ShiftLeft => "ShiftLeft",
+        KeyCode::ShiftRight => "ShiftRight",
+        KeyCode::Space => "Space",
+        KeyCode::Tab => "Tab",
+        KeyCode::Convert => "Convert",
+        KeyCode::KanaMode => "KanaMode",
+        KeyCode::Lang1 => "Lang1",
+        KeyCode::Lang2 => "Lang2",
+        KeyCode::Lang3 => "Lang3",
+        KeyCode::Lang4 => "Lang4",
+        KeyCode::Lang5 => "Lang5",
+        KeyCode::NonConvert => "NonConvert",
+        KeyCode::Delete => "Delete",
+        KeyCode::End => "End",
+        KeyCode::Help => "Help",
+        KeyCode::Home => "Home",
+        KeyCode::Insert => "Insert",
+        KeyCode::PageDown => "PageDown",
+        KeyCode::PageUp => "PageUp",
+        KeyCode::ArrowDown => "ArrowDown",
+        KeyCode::ArrowLeft => "ArrowLeft",
+        KeyCode::ArrowRight => "ArrowRight",
+        KeyCode::ArrowUp => "ArrowUp",
+        KeyCode::NumLock => "NumLock",
+        KeyCode::Numpad0 => "Numpad0",
+        KeyCode::Numpad1 => "Numpad1",
+        KeyCode::Numpad2 => "Numpad2",
+        KeyCode::Numpad3 => "Numpad3",
+        KeyCode::Numpad4 => "Numpad4",
+        KeyCode::Numpad5 => "Numpad5",
+        KeyCode::Numpad6 => "Numpad6",
+        KeyCode::Numpad7 => "Numpad7",
+        KeyCode::Numpad8 => "Numpad8",
+        KeyCode::Numpad9 => "Numpad9",
+        KeyCode::NumpadAdd => "NumpadAdd",
+        KeyCode::NumpadBackspace => "NumpadBackspace",
+        KeyCode::NumpadClear => "NumpadClear",
+        KeyCode::NumpadClearEntry => "NumpadClearEntry",
+        KeyCode::NumpadComma => "NumpadComma",
+        KeyCode::NumpadDecimal => "NumpadDecimal",
+        KeyCode::NumpadDivide => "NumpadDivide",
+        KeyCode::NumpadEnter => "NumpadEnter",
+        KeyCode::NumpadEqual => "NumpadEqual",
+        KeyCode::NumpadHash => "NumpadHash",
+        KeyCode::NumpadMemoryAdd => "NumpadMemoryAdd",
+        KeyCode::NumpadMemoryClear => "NumpadMemoryClear",
+        KeyCode::NumpadMemoryRecall => "NumpadMemoryRecall",
+        KeyCode::NumpadMemoryStore => "NumpadMemoryStore",
+        KeyCode::NumpadMemorySubtract => "NumpadMemorySubtract",
+        KeyCode::NumpadMultiply => "NumpadMultiply",
+        KeyCode::NumpadParenLeft => "NumpadParenLeft",
+        KeyCode::NumpadParenRight => "NumpadParenRight",
+        KeyCode::NumpadStar => "NumpadStar",
+        KeyCode::NumpadSubtract => "NumpadSubtract",
+        KeyCode::Escape => "Escape",
+        KeyCode::Fn => "Fn",
+        KeyCode::FnLock => "FnLock",
+        KeyCode::PrintScreen => "PrintScreen",
+        KeyCode::ScrollLock => "ScrollLock",
+        KeyCode::Pause => "Pause",
+        KeyCode::BrowserBack => "BrowserBack",
+        KeyCode::BrowserFavorites => "BrowserFavorites",
+        KeyCode::BrowserForward => "BrowserForward",
+        KeyCode::BrowserHome => "BrowserHome",
+        KeyCode::BrowserRefresh => "BrowserRefresh",
+        KeyCode::BrowserSearch => "BrowserSearch",
+        KeyCode::BrowserStop => "BrowserStop",
+        KeyCode::Eject => "Eject",
+        KeyCode::LaunchApp1 => "LaunchApp1",
+        KeyCode::LaunchApp2 => "LaunchApp2",
+        KeyCode::LaunchMail => "LaunchMail",
+        KeyCode::MediaPlayPause => "MediaPlayPause",
+        KeyCode::MediaSelect => "MediaSelect",
+        KeyCode::MediaStop => "MediaStop",
+        KeyCode::MediaTrackNext => "MediaTrackNext",
+        KeyCode::MediaTrackPrevious => "MediaTrackPrevious",
+        KeyCode::Power => "Power",
+        KeyCode::Sleep => "Sleep",
+        KeyCode::AudioVolumeDown => "AudioVolumeDown",
+        KeyCode::AudioVolumeMute => "AudioVolumeMute",
+        KeyCode::AudioVolumeUp => "AudioVolumeUp",
+        KeyCode::WakeUp => "WakeUp",
+        KeyCode::Meta => "Meta",
+        KeyCode::Hyper => "Hyper",
+        KeyCode::Turbo => "Turbo",
+        KeyCode::Abort => "Abort",
+        KeyCode::Resume => "Resume",
+        KeyCode::Suspend => "Suspend",
+        KeyCode::Again => "Again",
+        KeyCode::Copy => "Copy",
+        KeyCode::Cut => "Cut",
+        KeyCode::Find => "Find",
+        KeyCode::Open => "Open",
+        KeyCode::Paste => "Paste",
+        KeyCode::Props => "Props",
+        KeyCode::Select => "Select",
+        KeyCode::Undo => "Undo",
+        KeyCode::Hiragana => "Hiragana",
+        KeyCode::Katakana => "Katakana",
+        KeyCode::F1 => "F1",
+        KeyCode::F2 => "F2",
+        KeyCode::F3 => "F3",
+        KeyCode::F4 => "F4",
+        KeyCode::F5 => "F5",
+        KeyCode::F6 => "F6",
+        KeyCode::F7 => "F7",
+        KeyCode::F8 => "F8",
+        KeyCode::F9 => "F9",
+        KeyCode::F10 => "F10",
+        KeyCode::F11 => "F11",
+        KeyCode::F12 => "F12",
+        KeyCode::F13 => "F13",
+        KeyCode::F14 => "F14",
+        KeyCode::F15 => "F15",
+        KeyCode::F16 => "F16",
+        KeyCode::F17 => "F17",
+        KeyCode::F18 => "F18",
+        KeyCode::F19 => "F19",
+        KeyCode::F20 => "F20",
+        KeyCode::F21 => "F21",
+        KeyCode::F22 => "F22",
+        KeyCode::F23 => "F23",
+        KeyCode::F24 => "F24",
+        KeyCode::F25 => "F25",
+        KeyCode::F26 => "F26",
+        KeyCode::F27 => "F27",
+        KeyCode::F28 => "F28",
+        KeyCode::F29 => "F29",
+        KeyCode::F30 => "F30",
+        KeyCode::F31 => "F31",
+        KeyCode::F32 => "F32",
+        KeyCode::F33 => "F33",
+        KeyCode::F34 => "F34",
+        KeyCode::F35 => "F35",
+        _ => UNIDENTIFIED,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{code_value, key_value, track_key};
+    use crate::model::Modifiers;
+    use winit::event::ElementState;
+    use winit::keyboard::{Key, KeyCode, NamedKey, NativeKey, NativeKeyCode, PhysicalKey};
+
+    #[test]
+    fn maps_keys_to_web_values() {
+        assert_eq!(key_value(&Key::Character("a".into())), "a");
+        assert_eq!(key_value(&Key::Named(NamedKey::ArrowLeft)), "ArrowLeft");
+        assert_eq!(key_value(&Key::Named(NamedKey::Space)), " ");
+        assert_eq!(key_value(&Key::Named(NamedKey::Super)), "Meta");
+        assert_eq!(key_value(&Key::Named(NamedKey::Enter)), "Enter");
+        assert_eq!(key_value(&Key::Dead(Some('`'))), "Dead");
+        assert_eq!(
+            key_value(&Key::Unidentified(NativeKey::Unidentified)),
+            "Unidentified"
+        );
+    }
+
+    #[test]
+    fn maps_physical_keys_to_web_codes() {
+        assert_eq!(code_value(&PhysicalKey::Code(KeyCode::KeyA)), "KeyA");
+        assert_eq!(code_value(&PhysicalKey::Code(KeyCode::Digit1)), "Digit1");
+        assert_eq!(code_value(&PhysicalKey::Code(KeyCode::Space)), "Space");
+        assert_eq!(
+            code_value(&PhysicalKey::Code(KeyCode::SuperLeft)),
+            "MetaLeft"
+        );
+        assert_eq!(
+            code_value(&PhysicalKey::Code(KeyCode::NumpadEnter)),
+            "NumpadEnter"
+        );
+        assert_eq!(
+            code_value(&PhysicalKey::Unidentified(NativeKeyCode::MacOS(0x7E))),
+            "Unidentified"
+        );
+    }
+
+    #[test]
+    fn tracks_modifier_sides_and_lock_toggles() {
+        let mut modifiers = Modifiers::default();
+        let shift_right = PhysicalKey::Code(KeyCode::ShiftRight);
+        let shift = Key::Named(NamedKey::Shift);
+        track_key(
+            &mut modifiers,
+            &shift_right,
+            &shift,
+            ElementState::Pressed,
+            false,
+        );
+        assert!(modifiers.keys.right_shift);
+        assert!(!modifiers.keys.left_shift);
+        track_key(
+            &mut modifiers,
+            &shift_right,
+            &shift,
+            ElementState::Released,
+            false,
+        );
+        assert!(!modifiers.keys.right_shift);
+
+        let caps = PhysicalKey::Code(KeyCode::CapsLock);
+        let caps_key = Key::Named(NamedKey::CapsLock);
+        track_key(
+            &mut modifiers,
+            &caps,
+            &caps_key,
+            ElementState::Pressed,
+            false,
+        );
+        track_key(
+            &mut modifiers,
+            &caps,
+            &caps_key,
+            ElementState::Pressed,
+            true,
+        );
+        track_key(
+            &mut modifiers,
+            &caps,
+            &caps_key,
+            ElementState::Released,
+            false,
+        );
+        assert!(
+            modifiers.locks.caps_lock,
+            "repeats and releases do not toggle"
+        );
+        track_key(
+            &mut modifiers,
+            &caps,
+            &caps_key,
+            ElementState::Pressed,
+            false,
+        );
+        assert!(!modifiers.locks.caps_lock);
+    }
+
+    #[test]
+    fn num_lock_is_unknown_until_a_keypad_key_shows_it() {
+        let mut modifiers = Modifiers::default();
+        let num_lock = PhysicalKey::Code(KeyCode::NumLock);
+        let num_lock_key = Key::Named(NamedKey::NumLock);
+        track_key(
+            &mut modifiers,
+            &num_lock,
+            &num_lock_key,
+            ElementState::Pressed,
+            false,
+        );
+        assert_eq!(
+            modifiers.locks.num_lock, None,
+            "a toggle from unknown is unknown"
+        );
+
+        let numpad_7 = PhysicalKey::Code(KeyCode::Numpad7);
+        let home = Key::Named(NamedKey::Home);
+        track_key(
+            &mut modifiers,
+            &numpad_7,
+            &home,
+            ElementState::Pressed,
+            false,
+        );
+        assert_eq!(modifiers.locks.num_lock, Some(false));
+        track_key(
+            &mut modifiers,
+            &num_lock,
+            &num_lock_key,
+            ElementState::Pressed,
+            false,
+        );
+        assert_eq!(modifiers.locks.num_lock, Some(true));
+
+        modifiers.shift = true;
+        track_key(
+            &mut modifiers,
+            &numpad_7,
+            &home,
+            ElementState::Pressed,
+            false,
+        );
+        assert_eq!(
+            modifiers.locks.num_lock,
+            Some(true),
+            "shifted presses prove nothing"
+        );
+        modifiers.shift = false;
+        let seven = Key::Character("7".into());
+        track_key(
+            &mut modifiers,
+            &numpad_7,
+            &seven,
+            ElementState::Pressed,
+            false,
+        );
+        assert_eq!(modifiers.locks.num_lock, Some(true));
+    }
+}
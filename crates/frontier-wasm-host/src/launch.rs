@@ -0,0 +1,75 @@
+use anyhow::{bail, Result};
+
+use crate::engine::EngineOptions;
+use crate::locale::Locale;
+use crate::preferences::Preferences;
+
+/// Arguments and environment handed to the guest at instantiation time.
+#[derive(Clone, Debug, Default)]
+pub struct LaunchConfig {
+    /// Trailing command-line arguments (everything after `--`), without a program name.
+    pub args: Vec<String>,
+    /// Environment variables exposed through WASI.
+    pub env: Vec<(String, String)>,
+    /// What `get-locale` reports and the `format-*` functions follow.
+    pub locale: Locale,
+    /// What `get-preferences` reports.
+    pub preferences: Preferences,
+    /// How the Wasmtime engine the component runs on is configured.
+    pub engine: EngineOptions,
+}
+
+impl LaunchConfig {
+    /// Add an environment variable from a `NAME=VALUE` spec, or copy `NAME` from the host
+    /// environment when no value is given. Unset host variables are skipped.
+    pub fn push_env_spec(&mut self, spec: &str) -> Result<()> {
+        let (name, value) = match spec.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (spec, std::env::var(spec).ok()),
+        };
+        if name.is_empty() {
+            bail!("invalid --env value '{spec}': missing variable name");
+        }
+        match value {
+            Some(value) => self.env.push((name.to_string(), value)),
+            None => tracing::debug!(name, "host environment variable not set; not forwarding"),
+        }
+        Ok(())
+    }
+
+    /// WASI argv, with the component label standing in for the program name.
+    pub fn wasi_args(&self, program: &str) -> Vec<String> {
+        std::iter::once(program.to_string())
+            .chain(self.args.iter().cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LaunchConfig;
+
+    #[test]
+    fn parses_env_specs() {
+        let mut launch = LaunchConfig::default();
+        launch.push_env_spec("MODE=dark=true").unwrap();
+        launch.push_env_spec("FRONTIER_SURELY_UNSET_VAR").unwrap();
+        assert!(launch.push_env_spec("=value").is_err());
+        assert_eq!(
+            launch.env,
+            vec![("MODE".to_string(), "dark=true".to_string())]
+        );
+    }
+
+    #[test]
+    fn prefixes_program_name_to_wasi_args() {
+        let launch = LaunchConfig {
+            args: vec!["--flag".into(), "value".into()],
+            env: Vec::new(),
+            locale: Default::default(),
+            preferences: Default::default(),
+            engine: Default::default(),
+        };
+        assert_eq!(launch.wasi_args("counter"), ["counter", "--flag", "value"]);
+    }
+}
@@ -0,0 +1,476 @@
+//! Pane layout for hosting several components in one window: horizontal and vertical
+//! splits with draggable dividers, and tab stacks whose tab bars the host draws.
+//!
+//! A [`Layout`] is a tree described in the `[layout]` table of a component manifest;
+//! [`Layout::arrange`] turns it into the rects each pane's scene is clipped and offset to,
+//! plus hit regions for dividers and tabs. [`Panes`](crate::panes::Panes) runs the
+//! components and composites them.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+use crate::geometry::{Rect, Vec2};
+
+/// Thickness of the gap between split children, in logical pixels.
+pub const DIVIDER_WIDTH: f32 = 4.0;
+
+/// Height of a tab stack's tab bar, in logical pixels.
+pub const TAB_BAR_HEIGHT: f32 = 28.0;
+
+/// Neither side of a split can be dragged smaller than this fraction.
+const MIN_RATIO: f32 = 0.05;
+
+/// Which way a split lays out its two children.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Axis {
+    /// Side by side, with a vertical divider.
+    Horizontal,
+    /// Stacked, with a horizontal divider.
+    Vertical,
+}
+
+/// A node of the layout tree, e.g. in TOML:
+///
+/// ```toml
+/// [layout.split]
+/// axis = "horizontal"
+/// ratio = 0.25
+/// first = { pane = "files" }
+/// second = { tabs = { children = [{ pane = "editor" }, { pane = "preview" }] } }
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub enum Layout {
+    /// A component, by name.
+    Pane(String),
+    Split(Split),
+    Tabs(Tabs),
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Split {
+    pub axis: Axis,
+    /// Share of the space given to `first`.
+    #[serde(default = "half")]
+    pub ratio: f32,
+    pub first: Box<Layout>,
+    pub second: Box<Layout>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tabs {
+    pub children: Vec<Layout>,
+    /// Index of the visible child.
+    #[serde(default)]
+    pub active: usize,
+}
+
+fn half() -> f32 {
+    0.5
+}
+
+/// Child indices from the root to a node: `0`/`1` for split children, the tab index for
+/// tab children.
+pub type NodePath = Vec<usize>;
+
+/// Where everything goes for one window size.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Arrangement {
+    /// Visible panes; panes behind an inactive tab are left out.
+    pub panes: Vec<PaneSlot>,
+    pub dividers: Vec<Divider>,
+    pub tab_bars: Vec<TabBar>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaneSlot {
+    pub name: String,
+    /// Clip rect and origin of the pane's scene.
+    pub rect: Rect,
+}
+
+impl PaneSlot {
+    /// `point` relative to the pane, for input routed to it.
+    pub fn to_local(&self, point: Vec2) -> Vec2 {
+        point - self.rect.origin()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divider {
+    /// The split this divider resizes.
+    pub path: NodePath,
+    pub axis: Axis,
+    pub rect: Rect,
+    /// Space shared by the split's children, used to turn a drag position into a ratio.
+    pub span: Rect,
+}
+
+impl Divider {
+    /// Split ratio that puts the divider's centre at `point`.
+    pub fn ratio_at(&self, point: Vec2) -> f32 {
+        let (offset, length) = match self.axis {
+            Axis::Horizontal => (point.x - self.span.x, self.span.width),
+            Axis::Vertical => (point.y - self.span.y, self.span.height),
+        };
+        let available = length - DIVIDER_WIDTH;
+        if available <= 0.0 {
+            return half();
+        }
+        ((offset - DIVIDER_WIDTH / 2.0) / available).clamp(MIN_RATIO, 1.0 - MIN_RATIO)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TabBar {
+    /// The tab stack this bar switches.
+    pub path: NodePath,
+    pub rect: Rect,
+    /// Name of the first pane under each tab.
+    pub titles: Vec<String>,
+    pub active: usize,
+}
+
+impl TabBar {
+    /// Tabs share the bar's width equally.
+    pub fn tab_rect(&self, index: usize) -> Rect {
+        let width = self.rect.width / self.titles.len().max(1) as f32;
+        Rect::new(
+            self.rect.x + width * index as f32,
+            self.rect.y,
+            width,
+            self.rect.height,
+        )
+    }
+
+    pub fn tab_at(&self, point: Vec2) -> Option<usize> {
+        (0..self.titles.len()).find(|&index| self.tab_rect(index).contains(point))
+    }
+}
+
+impl Arrangement {
+    pub fn pane_at(&self, point: Vec2) -> Option<&PaneSlot> {
+        self.panes.iter().find(|pane| pane.rect.contains(point))
+    }
+
+    pub fn divider_at(&self, point: Vec2) -> Option<&Divider> {
+        self.dividers
+            .iter()
+            .find(|divider| divider.rect.contains(point))
+    }
+
+    /// The tab bar and tab index under `point`.
+    pub fn tab_at(&self, point: Vec2) -> Option<(&TabBar, usize)> {
+        self.tab_bars
+            .iter()
+            .find_map(|bar| bar.tab_at(point).map(|index| (bar, index)))
+    }
+}
+
+impl Layout {
+    /// A layout on its own, as the value of a manifest's `[layout]` table.
+    pub fn parse(text: &str) -> Result<Self> {
+        let layout: Layout = toml::from_str(text)?;
+        layout.validate()?;
+        Ok(layout)
+    }
+
+    /// Reject layouts that cannot be arranged: empty tab stacks, out-of-range ratios or
+    /// active tabs, and a pane name used twice.
+    pub fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        self.validate_into(&mut names)
+    }
+
+    fn validate_into<'a>(&'a self, names: &mut HashSet<&'a str>) -> Result<()> {
+        match self {
+            Layout::Pane(name) => {
+                if !names.insert(name) {
+                    bail!("pane '{name}' appears more than once in the layout");
+                }
+            }
+            Layout::Split(split) => {
+                if !(MIN_RATIO..=1.0 - MIN_RATIO).contains(&split.ratio) {
+                    bail!(
+                        "split ratio {} is outside {MIN_RATIO}..={}",
+                        split.ratio,
+                        1.0 - MIN_RATIO
+                    );
+                }
+                split.first.validate_into(names)?;
+                split.second.validate_into(names)?;
+            }
+            Layout::Tabs(tabs) => {
+                if tabs.children.is_empty() {
+                    bail!("a tab stack needs at least one child");
+                }
+                if tabs.active >= tabs.children.len() {
+                    bail!(
+                        "active tab {} is out of range for {} tabs",
+                        tabs.active,
+                        tabs.children.len()
+                    );
+                }
+                for child in &tabs.children {
+                    child.validate_into(names)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Every pane name, in tree order, including panes behind inactive tabs.
+    pub fn pane_names(&self) -> Vec<&str> {
+        match self {
+            Layout::Pane(name) => vec![name.as_str()],
+            Layout::Split(split) => {
+                let mut names = split.first.pane_names();
+                names.extend(split.second.pane_names());
+                names
+            }
+            Layout::Tabs(tabs) => tabs.children.iter().flat_map(Layout::pane_names).collect(),
+        }
+    }
+
+    pub fn arrange(&self, bounds: Rect) -> Arrangement {
+        let mut arrangement = Arrangement::default();
+        self.arrange_into(bounds, &mut Vec::new(), &mut arrangement);
+        arrangement
+    }
+
+    fn arrange_into(&self, bounds: Rect, path: &mut NodePath, out: &mut Arrangement) {
+        match self {
+            Layout::Pane(name) => out.panes.push(PaneSlot {
+                name: name.clone(),
+                rect: bounds,
+            }),
+            Layout::Split(split) => {
+                let (first, divider, second) = split_rect(bounds, split.axis, split.ratio);
+                out.dividers.push(Divider {
+                    path: path.clone(),
+                    axis: split.axis,
+                    rect: divider,
+                    span: bounds,
+                });
+                for (index, (child, rect)) in [(&split.first, first), (&split.second, second)]
+                    .into_iter()
+                    .enumerate()
+                {
+                    path.push(index);
+                    child.arrange_into(rect, path, out);
+                    path.pop();
+                }
+            }
+            Layout::Tabs(tabs) => {
+                let bar_height = TAB_BAR_HEIGHT.min(bounds.height);
+                out.tab_bars.push(TabBar {
+                    path: path.clone(),
+                    rect: Rect::new(bounds.x, bounds.y, bounds.width, bar_height),
+                    titles: tabs
+                        .children
+                        .iter()
+                        .map(|child| child.pane_names().first().copied().unwrap_or("").into())
+                        .collect(),
+                    active: tabs.active,
+                });
+                if let Some(child) = tabs.children.get(tabs.active) {
+                    let content = Rect::new(
+                        bounds.x,
+                        bounds.y + bar_height,
+                        bounds.width,
+                        bounds.height - bar_height,
+                    );
+                    path.push(tabs.active);
+                    child.arrange_into(content, path, out);
+                    path.pop();
+                }
+            }
+        }
+    }
+
+    /// The node at `path`, e.g. a tab's child at a [`TabBar::path`] plus the tab index.
+    pub fn node(&self, path: &[usize]) -> Option<&Layout> {
+        let Some((&index, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        let child = match self {
+            Layout::Pane(_) => return None,
+            Layout::Split(split) => match index {
+                0 => &split.first,
+                1 => &split.second,
+                _ => return None,
+            },
+            Layout::Tabs(tabs) => tabs.children.get(index)?,
+        };
+        child.node(rest)
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut Layout> {
+        let Some((&index, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        let child = match self {
+            Layout::Pane(_) => return None,
+            Layout::Split(split) => match index {
+                0 => &mut split.first,
+                1 => &mut split.second,
+                _ => return None,
+            },
+            Layout::Tabs(tabs) => tabs.children.get_mut(index)?,
+        };
+        child.node_mut(rest)
+    }
+
+    /// Move a divider, e.g. to [`Divider::ratio_at`] while it is dragged.
+    pub fn set_ratio(&mut self, path: &[usize], ratio: f32) -> bool {
+        match self.node_mut(path) {
+            Some(Layout::Split(split)) => {
+                split.ratio = ratio.clamp(MIN_RATIO, 1.0 - MIN_RATIO);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn select_tab(&mut self, path: &[usize], index: usize) -> bool {
+        match self.node_mut(path) {
+            Some(Layout::Tabs(tabs)) if index < tabs.children.len() => {
+                tabs.active = index;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The first child's rect, the divider's, and the second child's.
+fn split_rect(bounds: Rect, axis: Axis, ratio: f32) -> (Rect, Rect, Rect) {
+    match axis {
+        Axis::Horizontal => {
+            let divider = DIVIDER_WIDTH.min(bounds.width);
+            let first = ((bounds.width - divider) * ratio).round();
+            let second = bounds.width - divider - first;
+            (
+                Rect::new(bounds.x, bounds.y, first, bounds.height),
+                Rect::new(bounds.x + first, bounds.y, divider, bounds.height),
+                Rect::new(bounds.x + first + divider, bounds.y, second, bounds.height),
+            )
+        }
+        Axis::Vertical => {
+            let divider = DIVIDER_WIDTH.min(bounds.height);
+            let first = ((bounds.height - divider) * ratio).round();
+            let second = bounds.height - divider - first;
+            (
+                Rect::new(bounds.x, bounds.y, bounds.width, first),
+                Rect::new(bounds.x, bounds.y + first, bounds.width, divider),
+                Rect::new(bounds.x, bounds.y + first + divider, bounds.width, second),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Layout, TAB_BAR_HEIGHT};
+    use crate::geometry::{Rect, Vec2};
+
+    const EDITOR: &str = r#"
+        [split]
+        axis = "horizontal"
+        ratio = 0.25
+        first = { pane = "files" }
+
+        [split.second.split]
+        axis = "vertical"
+        first = { tabs = { children = [{ pane = "editor" }, { pane = "preview" }] } }
+        second = { pane = "terminal" }
+    "#;
+
+    #[test]
+    fn arranges_splits_and_tabs() {
+        let layout = Layout::parse(EDITOR).unwrap();
+        assert_eq!(
+            layout.pane_names(),
+            ["files", "editor", "preview", "terminal"]
+        );
+
+        let arrangement = layout.arrange(Rect::new(0.0, 0.0, 804.0, 604.0));
+        let rects: Vec<_> = arrangement
+            .panes
+            .iter()
+            .map(|pane| (pane.name.as_str(), pane.rect))
+            .collect();
+        assert_eq!(
+            rects,
+            [
+                ("files", Rect::new(0.0, 0.0, 200.0, 604.0)),
+                (
+                    "editor",
+                    Rect::new(204.0, TAB_BAR_HEIGHT, 600.0, 300.0 - TAB_BAR_HEIGHT)
+                ),
+                ("terminal", Rect::new(204.0, 304.0, 600.0, 300.0)),
+            ]
+        );
+        assert_eq!(arrangement.dividers.len(), 2);
+        assert_eq!(arrangement.tab_bars[0].titles, ["editor", "preview"]);
+
+        let pane = arrangement.pane_at(Vec2::new(300.0, 400.0)).unwrap();
+        assert_eq!(pane.name, "terminal");
+        assert_eq!(
+            pane.to_local(Vec2::new(300.0, 400.0)),
+            Vec2::new(96.0, 96.0)
+        );
+        assert!(arrangement.pane_at(Vec2::new(202.0, 10.0)).is_none());
+    }
+
+    #[test]
+    fn dragging_dividers_and_switching_tabs() {
+        let mut layout = Layout::parse(EDITOR).unwrap();
+        let bounds = Rect::new(0.0, 0.0, 804.0, 604.0);
+        let arrangement = layout.arrange(bounds);
+
+        let divider = arrangement
+            .divider_at(Vec2::new(201.0, 50.0))
+            .unwrap()
+            .clone();
+        assert!(divider.path.is_empty());
+        let ratio = divider.ratio_at(Vec2::new(402.0, 50.0));
+        assert_eq!(ratio, 0.5);
+        assert!(layout.set_ratio(&divider.path, ratio));
+        assert_eq!(divider.ratio_at(Vec2::new(-50.0, 0.0)), 0.05);
+
+        let (bar, tab) = arrangement.tab_at(Vec2::new(750.0, 10.0)).unwrap();
+        assert_eq!(tab, 1);
+        assert!(layout.select_tab(&bar.path, tab));
+        assert!(!layout.select_tab(&bar.path, 2));
+        assert!(!layout.set_ratio(&bar.path, 0.5));
+        let tab_path = [bar.path.as_slice(), &[tab]].concat();
+        assert_eq!(
+            layout.node(&tab_path),
+            Some(&Layout::Pane("preview".into()))
+        );
+
+        let arrangement = layout.arrange(bounds);
+        assert_eq!(arrangement.panes[0].rect.width, 400.0);
+        assert_eq!(arrangement.panes[1].name, "preview");
+    }
+
+    #[test]
+    fn rejects_invalid_layouts() {
+        for text in [
+            r#"split = { axis = "vertical", first = { pane = "a" }, second = { pane = "a" } }"#,
+            r#"split = { axis = "vertical", ratio = 1.5, first = { pane = "a" }, second = { pane = "b" } }"#,
+            r#"tabs = { children = [] }"#,
+            r#"tabs = { children = [{ pane = "a" }], active = 1 }"#,
+            r#"pane = "a"
+               extra = 1"#,
+        ] {
+            assert!(Layout::parse(text).is_err(), "accepted {text}");
+        }
+    }
+}
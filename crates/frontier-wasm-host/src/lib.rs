@@ -1,9 +1,87 @@
+pub mod accessibility;
 pub mod app;
+pub mod bidi;
+pub mod camera;
+pub mod canvas;
+pub mod capabilities;
+pub mod charts;
+pub mod chrome_trace;
+pub mod clock;
 pub mod component;
+pub mod config;
+pub mod control;
+pub mod database;
+pub mod decode;
+pub mod deeplink;
+pub mod effects;
+pub mod engine;
+#[cfg(all(any(feature = "gamepad", feature = "haptics"), target_os = "linux"))]
+mod evdev;
+pub mod export;
+pub mod frame_file;
+pub mod gamepad;
+pub mod geometry;
+pub mod gpu;
 pub mod graphics;
+pub mod haptics;
 pub mod host;
+pub mod hotkeys;
+pub mod identity;
+pub mod idle;
+pub mod images;
+pub mod inertia;
+pub mod instance;
+pub mod keys;
+pub mod launch;
+pub mod layout;
+pub mod limits;
+pub mod locale;
+pub mod logging;
+pub mod lottie;
+pub mod manifest;
+pub mod markdown;
+pub mod memory;
+pub mod metrics;
 pub mod model;
+pub mod navigation;
+pub mod pacing;
+pub mod panes;
+pub mod particles;
+pub mod pipeline_cache;
+pub mod pixels;
+pub mod pointers;
+pub mod pool;
+pub mod power;
+pub mod precompile;
+pub mod preferences;
+pub mod qr;
 pub mod runtime;
+pub mod sandbox;
+pub mod script;
+pub mod scroll;
+pub mod snapshot;
+pub mod software;
+pub mod stats;
+pub mod stdio;
+pub mod storage;
+pub mod svg;
+pub mod tasks;
+pub mod text;
+pub mod timezone;
+pub mod transition;
+pub mod tween;
+pub mod vector;
+pub mod window;
+pub mod workers;
 
+pub use geometry::{Rect, Size, Transform, Vec2};
+pub use host::{Color, DrawCommand, FrameOutput};
+pub use launch::LaunchConfig;
+pub use limits::ResourceLimits;
 pub use model::LogicalSize;
-pub use runtime::{ComponentRuntime, ComponentSource};
+pub use runtime::{
+    CallResult, CloseResponse, ComponentRuntime, ComponentRuntimeBuilder, ComponentSource,
+    FrameResult,
+};
+pub use sandbox::{GuestStdio, HostInterfaces, Sandbox};
+pub use window::WindowOptions;
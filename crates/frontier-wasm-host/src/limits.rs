@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+/// Resource quotas that keep a misbehaving or hostile component from monopolising the host.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Draw commands kept per frame; later commands are dropped with a warning.
+    pub max_draw_commands: usize,
+    /// Total `draw-text` bytes kept per frame; text past the budget is dropped with a warning.
+    pub max_text_bytes: usize,
+    /// Host calls allowed during a single guest callback. Exceeding it traps the guest, which
+    /// surfaces as the error overlay.
+    pub max_host_calls: u32,
+    /// Bytes the guest may hold in host-side assets at once: images, pixel buffers, decoded
+    /// frames, SVG pictures, Lottie animations and emitters all share it. Creating an asset
+    /// past the budget fails until others are dropped.
+    pub max_image_bytes: usize,
+    /// Pixel bytes the guest may upload per second through `create-image`, `decode-image`
+    /// (counting the decoded frames) and `write-pixel-buffer`; uploads past it fail until the
+    /// next second.
+    pub max_image_upload_bytes_per_second: usize,
+    /// Size a component's SQLite database may grow to; writes past it fail.
+    pub max_database_bytes: u64,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            max_draw_commands: 20_000,
+            max_text_bytes: 1024 * 1024,
+            max_host_calls: 100_000,
+            max_image_bytes: 64 * 1024 * 1024,
+            max_image_upload_bytes_per_second: 512 * 1024 * 1024,
+            max_database_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// Image bytes uploaded in the current one-second window, checked against
+/// `max_image_upload_bytes_per_second`.
+#[derive(Debug, Default)]
+pub struct UploadMeter {
+    window: Option<Instant>,
+    bytes: usize,
+}
+
+impl UploadMeter {
+    /// Bytes that may still be uploaded in the window holding `now`.
+    pub fn remaining(&mut self, now: Instant, limit: usize) -> usize {
+        if self
+            .window
+            .is_none_or(|start| now.duration_since(start) >= Duration::from_secs(1))
+        {
+            self.window = Some(now);
+            self.bytes = 0;
+        }
+        limit.saturating_sub(self.bytes)
+    }
+
+    /// Counts `bytes` against the window holding `now` if they fit, returning whether they did.
+    pub fn charge(&mut self, now: Instant, bytes: usize, limit: usize) -> bool {
+        if bytes > self.remaining(now, limit) {
+            return false;
+        }
+        self.bytes += bytes;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::UploadMeter;
+
+    #[test]
+    fn uploads_refill_each_second() {
+        let mut meter = UploadMeter::default();
+        let start = Instant::now();
+        assert!(meter.charge(start, 60, 100));
+        assert!(!meter.charge(start + Duration::from_millis(500), 50, 100));
+        assert_eq!(meter.remaining(start + Duration::from_millis(999), 100), 40);
+        assert!(meter.charge(start + Duration::from_millis(999), 40, 100));
+        assert!(meter.charge(start + Duration::from_secs(1), 100, 100));
+        assert!(!meter.charge(start + Duration::from_secs(1), 1, 100));
+    }
+}
@@ -0,0 +1,519 @@
+//! The user's locale and locale-aware formatting for `format-number`, `format-currency` and
+//! `format-datetime`, so guests do not each bundle their own CLDR data.
+//!
+//! Tags are parsed and canonicalized with ICU4X's `icu_locale_core`. Formatting follows a
+//! built-in table of conventions for common locales: an exact language and region match
+//! wins, then the language alone, then US English.
+
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{Datelike, NaiveDateTime, Timelike};
+
+use crate::component::vello::canvas::host::DatetimeStyle as WitDatetimeStyle;
+
+/// Most digits `format-number` will show after the decimal separator.
+pub const MAX_FRACTION_DIGITS: u8 = 20;
+
+/// What `format-datetime` shows of a timestamp.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateTimeStyle {
+    Date,
+    Time,
+    DateTime,
+}
+
+impl DateTimeStyle {
+    pub fn from_wit(style: WitDatetimeStyle) -> Self {
+        match style {
+            WitDatetimeStyle::Date => DateTimeStyle::Date,
+            WitDatetimeStyle::Time => DateTimeStyle::Time,
+            WitDatetimeStyle::DateTime => DateTimeStyle::DateTime,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Grouping {
+    /// `1,234,567`
+    Thousands,
+    /// `12,34,567`: the lowest group has three digits, the rest two.
+    Indian,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum CurrencyPosition {
+    /// `$1.00`
+    Before,
+    /// `R$ 1,00`
+    BeforeSpace,
+    /// `1,00 €`
+    AfterSpace,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum DateOrder {
+    Mdy,
+    Dmy,
+    Ymd,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Clock {
+    /// `3:04 PM`
+    H12,
+    /// `15:04`, with the hour zero-padded when `pad_hour` is set.
+    H24 { pad_hour: bool },
+}
+
+/// How one locale writes numbers, amounts and dates.
+#[derive(Debug, PartialEq, Eq)]
+struct Conventions {
+    decimal: char,
+    group: char,
+    grouping: Grouping,
+    /// Integer digits needed before any grouping happens, beyond the first group; 2 keeps
+    /// `1234` ungrouped in Spanish and Polish.
+    min_grouping: usize,
+    currency: CurrencyPosition,
+    date_order: DateOrder,
+    date_separator: char,
+    /// Zero-pad day and month (`05/01` rather than `5/1`).
+    pad_date: bool,
+    clock: Clock,
+    /// Between the date and the time in [`DateTimeStyle::DateTime`].
+    date_time_separator: &'static str,
+}
+
+const NBSP: char = '\u{a0}';
+const NARROW_NBSP: char = '\u{202f}';
+
+const EN_US: Conventions = Conventions {
+    decimal: '.',
+    group: ',',
+    grouping: Grouping::Thousands,
+    min_grouping: 1,
+    currency: CurrencyPosition::Before,
+    date_order: DateOrder::Mdy,
+    date_separator: '/',
+    pad_date: false,
+    clock: Clock::H12,
+    date_time_separator: ", ",
+};
+
+const EN_GB: Conventions = Conventions {
+    date_order: DateOrder::Dmy,
+    pad_date: true,
+    clock: Clock::H24 { pad_hour: true },
+    ..EN_US
+};
+
+const EN_IN: Conventions = Conventions {
+    grouping: Grouping::Indian,
+    date_order: DateOrder::Dmy,
+    pad_date: true,
+    ..EN_US
+};
+
+const HI: Conventions = Conventions {
+    grouping: Grouping::Indian,
+    date_order: DateOrder::Dmy,
+    ..EN_US
+};
+
+const DE: Conventions = Conventions {
+    decimal: ',',
+    group: '.',
+    grouping: Grouping::Thousands,
+    min_grouping: 1,
+    currency: CurrencyPosition::AfterSpace,
+    date_order: DateOrder::Dmy,
+    date_separator: '.',
+    pad_date: true,
+    clock: Clock::H24 { pad_hour: true },
+    date_time_separator: ", ",
+};
+
+const DE_CH: Conventions = Conventions {
+    decimal: '.',
+    group: '’',
+    currency: CurrencyPosition::BeforeSpace,
+    ..DE
+};
+
+const IT: Conventions = Conventions {
+    date_separator: '/',
+    ..DE
+};
+
+const ES: Conventions = Conventions {
+    min_grouping: 2,
+    pad_date: false,
+    clock: Clock::H24 { pad_hour: false },
+    ..IT
+};
+
+const FR: Conventions = Conventions {
+    group: NARROW_NBSP,
+    date_time_separator: " ",
+    ..IT
+};
+
+const PT: Conventions = Conventions {
+    currency: CurrencyPosition::BeforeSpace,
+    date_time_separator: " ",
+    ..IT
+};
+
+const NL: Conventions = Conventions {
+    currency: CurrencyPosition::BeforeSpace,
+    date_separator: '-',
+    pad_date: false,
+    date_time_separator: " ",
+    ..DE
+};
+
+const PL: Conventions = Conventions {
+    group: NBSP,
+    min_grouping: 2,
+    ..DE
+};
+
+const RU: Conventions = Conventions { group: NBSP, ..DE };
+
+const SV: Conventions = Conventions {
+    group: NBSP,
+    date_order: DateOrder::Ymd,
+    date_separator: '-',
+    date_time_separator: " ",
+    ..DE
+};
+
+const JA: Conventions = Conventions {
+    date_order: DateOrder::Ymd,
+    pad_date: true,
+    clock: Clock::H24 { pad_hour: false },
+    date_time_separator: " ",
+    ..EN_US
+};
+
+const ZH: Conventions = Conventions {
+    date_order: DateOrder::Ymd,
+    clock: Clock::H24 { pad_hour: true },
+    date_time_separator: " ",
+    ..EN_US
+};
+
+/// Conventions by language and, optionally, region. Region entries come first so they win.
+const CONVENTIONS: &[(&str, Option<&str>, &Conventions)] = &[
+    ("en", Some("GB"), &EN_GB),
+    ("en", Some("IE"), &EN_GB),
+    ("en", Some("IN"), &EN_IN),
+    ("de", Some("CH"), &DE_CH),
+    ("en", None, &EN_US),
+    ("hi", None, &HI),
+    ("de", None, &DE),
+    ("es", None, &ES),
+    ("fr", None, &FR),
+    ("it", None, &IT),
+    ("ja", None, &JA),
+    ("nl", None, &NL),
+    ("pl", None, &PL),
+    ("pt", None, &PT),
+    ("ru", None, &RU),
+    ("sv", None, &SV),
+    ("zh", None, &ZH),
+];
+
+/// A locale and the conventions it formats with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Locale {
+    id: icu_locale_core::Locale,
+    conventions: &'static Conventions,
+}
+
+impl Default for Locale {
+    /// US English, so headless runs and tests format the same everywhere.
+    fn default() -> Self {
+        "en-US".parse().expect("en-US is a valid locale")
+    }
+}
+
+impl FromStr for Locale {
+    type Err = anyhow::Error;
+
+    /// Accepts BCP 47 tags (`pt-BR`) and POSIX locale names (`pt_BR.UTF-8`).
+    fn from_str(spec: &str) -> Result<Self> {
+        let tag = spec.split(['.', '@']).next().unwrap_or_default();
+        let tag = match tag {
+            "C" | "POSIX" => "en-US".to_string(),
+            tag => tag.replace('_', "-"),
+        };
+        let id = icu_locale_core::Locale::try_from_str(&tag)
+            .map_err(|err| anyhow!("invalid locale '{spec}': {err}"))?;
+        let language = id.id.language.as_str();
+        let region = id.id.region.as_ref().map(|region| region.as_str());
+        let conventions = CONVENTIONS
+            .iter()
+            .find(|(lang, reg, _)| *lang == language && (reg.is_none() || *reg == region))
+            .map_or(&EN_US, |(_, _, conventions)| *conventions);
+        Ok(Self { id, conventions })
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.id.fmt(f)
+    }
+}
+
+impl Locale {
+    /// The locale named by `LC_ALL` or `LANG`, or US English when neither names a valid one.
+    pub fn detect() -> Self {
+        ["LC_ALL", "LANG"]
+            .into_iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| match value.parse() {
+                Ok(locale) => Some(locale),
+                Err(err) => {
+                    tracing::debug!(error = %err, "ignoring host locale");
+                    None
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    /// `value` with exactly `fraction_digits` digits after the decimal separator (at most
+    /// [`MAX_FRACTION_DIGITS`]), rounded half to even.
+    pub fn format_number(&self, value: f64, fraction_digits: u8) -> String {
+        let mut out = String::new();
+        self.write_signed(&mut out, value, fraction_digits, |out, number| {
+            out.push_str(number)
+        });
+        out
+    }
+
+    /// `value` as an amount of `currency`, an ISO 4217 code such as `EUR`, with that
+    /// currency's usual number of minor digits.
+    pub fn format_currency(&self, value: f64, currency: &str) -> Result<String> {
+        if currency.len() != 3 || !currency.bytes().all(|byte| byte.is_ascii_alphabetic()) {
+            bail!("'{currency}' is not an ISO 4217 currency code");
+        }
+        let code = currency.to_ascii_uppercase();
+        let (symbol, digits) = match code.as_str() {
+            "USD" => ("$", 2),
+            "EUR" => ("€", 2),
+            "GBP" => ("£", 2),
+            "JPY" => ("¥", 0),
+            "CNY" => ("CN¥", 2),
+            "INR" => ("₹", 2),
+            "KRW" => ("₩", 0),
+            "BRL" => ("R$", 2),
+            "RUB" => ("₽", 2),
+            code => (code, 2),
+        };
+        let mut out = String::new();
+        self.write_signed(&mut out, value, digits, |out, number| {
+            match self.conventions.currency {
+                CurrencyPosition::Before => {
+                    out.push_str(symbol);
+                    out.push_str(number);
+                }
+                CurrencyPosition::BeforeSpace => {
+                    out.push_str(symbol);
+                    out.push(NBSP);
+                    out.push_str(number);
+                }
+                CurrencyPosition::AfterSpace => {
+                    out.push_str(number);
+                    out.push(NBSP);
+                    out.push_str(symbol);
+                }
+            }
+        });
+        Ok(out)
+    }
+
+    /// `time`, a wall-clock time, as a short numeric date, time, or both.
+    pub fn format_datetime(&self, time: NaiveDateTime, style: DateTimeStyle) -> String {
+        let mut out = String::new();
+        if style != DateTimeStyle::Time {
+            self.write_date(&mut out, time);
+        }
+        if style == DateTimeStyle::DateTime {
+            out.push_str(self.conventions.date_time_separator);
+        }
+        if style != DateTimeStyle::Date {
+            self.write_time(&mut out, time);
+        }
+        out
+    }
+
+    /// Write the sign, then let `body` place the localized digits of `|value|`.
+    fn write_signed(
+        &self,
+        out: &mut String,
+        value: f64,
+        fraction_digits: u8,
+        body: impl FnOnce(&mut String, &str),
+    ) {
+        if value.is_nan() {
+            out.push_str("NaN");
+            return;
+        }
+        let number = if value.is_infinite() {
+            "∞".to_string()
+        } else {
+            self.digits(value.abs(), fraction_digits.min(MAX_FRACTION_DIGITS))
+        };
+        // Values that round to zero drop their sign, as in `-0.001` → `0.00`.
+        let nonzero = value.is_infinite() || number.chars().any(|c| matches!(c, '1'..='9'));
+        if value.is_sign_negative() && nonzero {
+            out.push('-');
+        }
+        body(out, &number);
+    }
+
+    fn digits(&self, value: f64, fraction_digits: u8) -> String {
+        let plain = format!("{value:.*}", usize::from(fraction_digits));
+        let (integer, fraction) = plain.split_once('.').unwrap_or((&plain, ""));
+        let conventions = self.conventions;
+        let mut out = String::with_capacity(plain.len() + plain.len() / 3);
+        if integer.len() < 4 + conventions.min_grouping - 1 {
+            out.push_str(integer);
+        } else {
+            let (head, last) = integer.split_at(integer.len() - 3);
+            let size = match conventions.grouping {
+                Grouping::Thousands => 3,
+                Grouping::Indian => 2,
+            };
+            let first = head.len() % size;
+            for (index, digit) in head.chars().enumerate() {
+                if index > 0 && (index + size - first) % size == 0 {
+                    out.push(conventions.group);
+                }
+                out.push(digit);
+            }
+            out.push(conventions.group);
+            out.push_str(last);
+        }
+        if !fraction.is_empty() {
+            out.push(conventions.decimal);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    fn write_date(&self, out: &mut String, time: NaiveDateTime) {
+        let conventions = self.conventions;
+        let (year, month, day) = (time.year(), time.month(), time.day());
+        let width = if conventions.pad_date { 2 } else { 1 };
+        let sep = conventions.date_separator;
+        let _ = match conventions.date_order {
+            DateOrder::Mdy => write!(out, "{month:0width$}{sep}{day:0width$}{sep}{year}"),
+            DateOrder::Dmy => write!(out, "{day:0width$}{sep}{month:0width$}{sep}{year}"),
+            DateOrder::Ymd => write!(out, "{year}{sep}{month:0width$}{sep}{day:0width$}"),
+        };
+    }
+
+    fn write_time(&self, out: &mut String, time: NaiveDateTime) {
+        let (hour, minute) = (time.hour(), time.minute());
+        let _ = match self.conventions.clock {
+            Clock::H12 => {
+                let (is_pm, hour) = time.hour12();
+                let suffix = if is_pm { "PM" } else { "AM" };
+                write!(out, "{hour}:{minute:02} {suffix}")
+            }
+            Clock::H24 { pad_hour: true } => write!(out, "{hour:02}:{minute:02}"),
+            Clock::H24 { pad_hour: false } => write!(out, "{hour}:{minute:02}"),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{DateTimeStyle, Locale};
+
+    fn locale(tag: &str) -> Locale {
+        tag.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_bcp47_and_posix_names() {
+        assert_eq!(locale("de_DE.UTF-8").to_string(), "de-DE");
+        assert_eq!(locale("sr_RS@latin").to_string(), "sr-RS");
+        assert_eq!(locale("EN-gb").to_string(), "en-GB");
+        assert_eq!(locale("C"), Locale::default());
+        assert!("not a locale!".parse::<Locale>().is_err());
+        assert!("".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn formats_numbers_with_local_separators_and_grouping() {
+        let en = Locale::default();
+        assert_eq!(en.format_number(1234567.891, 2), "1,234,567.89");
+        assert_eq!(en.format_number(2.5, 0), "2");
+        assert_eq!(en.format_number(-0.001, 2), "0.00");
+        assert_eq!(en.format_number(999.0, 1), "999.0");
+        assert_eq!(en.format_number(f64::NAN, 2), "NaN");
+        assert_eq!(en.format_number(f64::NEG_INFINITY, 2), "-∞");
+        assert_eq!(locale("de").format_number(1234567.891, 2), "1.234.567,89");
+        assert_eq!(locale("fr-FR").format_number(1234.5, 1), "1\u{202f}234,5");
+        assert_eq!(locale("en-IN").format_number(12345678.0, 0), "1,23,45,678");
+        assert_eq!(locale("es").format_number(1234.0, 0), "1234");
+        assert_eq!(locale("es").format_number(12345.0, 0), "12.345");
+        // Unknown languages keep their tag but format like US English.
+        assert_eq!(locale("tlh").format_number(1234.0, 0), "1,234");
+    }
+
+    #[test]
+    fn formats_currency_in_local_position() {
+        let en = Locale::default();
+        assert_eq!(en.format_currency(-1234.5, "usd").unwrap(), "-$1,234.50");
+        assert_eq!(en.format_currency(1234.6, "JPY").unwrap(), "¥1,235");
+        assert_eq!(en.format_currency(3.0, "CHF").unwrap(), "CHF3.00");
+        assert_eq!(
+            locale("de-DE").format_currency(1234.5, "EUR").unwrap(),
+            "1.234,50\u{a0}€"
+        );
+        assert_eq!(
+            locale("pt-BR").format_currency(10.0, "BRL").unwrap(),
+            "R$\u{a0}10,00"
+        );
+        assert!(en.format_currency(1.0, "dollars").is_err());
+        assert!(en.format_currency(1.0, "U$D").is_err());
+    }
+
+    #[test]
+    fn formats_dates_and_times_in_local_order() {
+        let time = NaiveDate::from_ymd_opt(2026, 1, 5)
+            .unwrap()
+            .and_hms_opt(15, 4, 9)
+            .unwrap();
+        let format = |tag: &str, style| locale(tag).format_datetime(time, style);
+        assert_eq!(
+            format("en-US", DateTimeStyle::DateTime),
+            "1/5/2026, 3:04 PM"
+        );
+        assert_eq!(
+            format("en-GB", DateTimeStyle::DateTime),
+            "05/01/2026, 15:04"
+        );
+        assert_eq!(format("de", DateTimeStyle::Date), "05.01.2026");
+        assert_eq!(format("ja", DateTimeStyle::DateTime), "2026/01/05 15:04");
+        assert_eq!(format("sv", DateTimeStyle::Date), "2026-01-05");
+        let morning = time.date().and_hms_opt(0, 30, 0).unwrap();
+        assert_eq!(
+            Locale::default().format_datetime(morning, DateTimeStyle::Time),
+            "12:30 AM"
+        );
+        assert_eq!(
+            locale("es").format_datetime(morning, DateTimeStyle::Time),
+            "0:30"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+use crate::chrome_trace::{self, ChromeLayer};
+
+/// Tracing target for guest `log` calls and captured stdout/stderr.
+pub const GUEST_TARGET: &str = "guest";
+/// Tracing target for per-frame timing events.
+pub const FRAME_TARGET: &str = "frame";
+/// Tracing target for guest `perf-mark` / `perf-measure` timings.
+pub const PERF_TARGET: &str = "perf";
+
+/// Rotate the log file once it grows past this size.
+const LOG_FILE_MAX_BYTES: u64 = 5 * 1024 * 1024;
+/// Number of rotated files (`host.log.1` .. `host.log.N`) kept next to the active one.
+const LOG_FILE_KEEP: usize = 3;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable single-line output.
+    #[default]
+    Compact,
+    /// Newline-delimited JSON for log aggregation.
+    Json,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LogOptions {
+    /// `EnvFilter` directives; falls back to `RUST_LOG`, then `info`.
+    pub filter: Option<String>,
+    pub format: LogFormat,
+    /// Persistent log file receiving host and guest logs, rotated by size.
+    pub file: Option<PathBuf>,
+    /// Chrome trace-event JSON file (open in `chrome://tracing` or Perfetto).
+    pub chrome_trace: Option<PathBuf>,
+}
+
+/// Keeps buffered log outputs alive; flushes them when dropped at exit.
+pub struct LogGuard {
+    _chrome: Option<chrome_trace::FlushGuard>,
+}
+
+/// Default log file location under the platform data directory, if one exists.
+pub fn default_log_file() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("frontier-wasm").join("logs").join("host.log"))
+}
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Install the global tracing subscriber.
+///
+/// Guest output is emitted under the `guest` target inside a `component{label=..}` span,
+/// so filters such as `guest=debug` or `[component{label=counter}]=trace` select it.
+pub fn init(options: &LogOptions) -> Result<LogGuard> {
+    let filter = match &options.filter {
+        Some(directives) => EnvFilter::try_new(directives)
+            .with_context(|| format!("invalid --log-filter '{directives}'"))?,
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    };
+
+    let mut layers: Vec<BoxedLayer> = Vec::with_capacity(2);
+    layers.push(match options.format {
+        LogFormat::Compact => tracing_subscriber::fmt::layer()
+            .with_target(false)
+            .compact()
+            .boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+    });
+
+    let mut file_error = None;
+    if let Some(path) = &options.file {
+        match RotatingFile::open(path, LOG_FILE_MAX_BYTES, LOG_FILE_KEEP) {
+            Ok(writer) => layers.push(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(false)
+                    .with_writer(Mutex::new(writer))
+                    .boxed(),
+            ),
+            Err(err) => file_error = Some(err),
+        }
+    }
+
+    let chrome = match &options.chrome_trace {
+        Some(path) => {
+            let (layer, guard) = ChromeLayer::create(path)?;
+            layers.push(layer.boxed());
+            Some(guard)
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(layers)
+        .with(filter)
+        .init();
+
+    install_panic_hook();
+
+    if let Some(err) = file_error {
+        tracing::warn!(error = %format!("{err:#}"), "log file disabled");
+    } else if let Some(path) = &options.file {
+        tracing::debug!(path = %path.display(), "writing logs to file");
+    }
+    Ok(LogGuard { _chrome: chrome })
+}
+
+/// Route panics through tracing as well, so they reach the log file.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        tracing::error!(%info, "host panicked");
+        default_hook(info);
+    }));
+}
+
+/// Append-only log file that rolls over to numbered backups once it exceeds a size limit.
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    keep: usize,
+}
+
+impl RotatingFile {
+    pub fn open(path: &Path, max_bytes: u64, keep: usize) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create log directory {}", parent.display()))?;
+        }
+        let file = Self::open_append(path)
+            .with_context(|| format!("failed to open log file {}", path.display()))?;
+        let written = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            written,
+            max_bytes,
+            keep,
+        })
+    }
+
+    fn open_append(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+
+    fn backup_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{index}"));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.keep == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            for index in (1..self.keep).rev() {
+                let from = self.backup_path(index);
+                if from.exists() {
+                    fs::rename(&from, self.backup_path(index + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.backup_path(1))?;
+            self.file = Self::open_append(&self.path)?;
+        }
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+
+    use super::RotatingFile;
+
+    #[test]
+    fn rotates_by_size_and_keeps_limited_backups() {
+        let dir = std::env::temp_dir().join(format!("frontier-log-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("host.log");
+
+        let mut file = RotatingFile::open(&path, 8, 2).unwrap();
+        for line in ["aaaaaa\n", "bbbbbb\n", "cccccc\n", "dddddd\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "dddddd\n");
+        assert_eq!(
+            fs::read_to_string(dir.join("host.log.1")).unwrap(),
+            "cccccc\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("host.log.2")).unwrap(),
+            "bbbbbb\n"
+        );
+        assert!(!dir.join("host.log.3").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,896 @@
+//! A small Lottie player behind `load-lottie`. It covers the shape-layer subset most
+//! loaders and UI animations use: shape, solid and null layers with parenting, animated
+//! transforms, groups of rectangles, ellipses and bezier paths, and solid fills and
+//! strokes, with eased keyframes. Precompositions, images, text, masks, mattes, effects,
+//! gradients and path modifiers (trim, repeater, round corners) are skipped with a warning.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use vello::kurbo::{Affine, BezPath, Cap, Ellipse, Join, Point, Rect, RoundedRect, Shape, Stroke};
+use vello::peniko::color::{parse_color, Srgb};
+use vello::peniko::{Color, Fill};
+
+use crate::vector::VectorSink;
+
+/// Largest document `load-lottie` accepts.
+pub const MAX_LOTTIE_BYTES: usize = 8 * 1024 * 1024;
+/// Shapes allowed in one animation, to bound the work of drawing a frame.
+const MAX_LOTTIE_SHAPES: usize = 20_000;
+/// Deepest parent chain followed when resolving layer transforms.
+const MAX_PARENT_DEPTH: usize = 64;
+const SHAPE_TOLERANCE: f64 = 0.01;
+
+/// A parsed Lottie animation. Cloning shares it.
+#[derive(Clone)]
+pub struct Animation {
+    inner: Arc<AnimationData>,
+}
+
+struct AnimationData {
+    width: f64,
+    height: f64,
+    frame_rate: f64,
+    in_point: f64,
+    out_point: f64,
+    /// Bottom-most first, the order they are drawn in.
+    layers: Vec<Layer>,
+    /// The JSON as loaded, kept so frames can be saved and replayed.
+    source: Box<[u8]>,
+}
+
+impl Animation {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() > MAX_LOTTIE_BYTES {
+            bail!(
+                "Lottie file is {} bytes; the limit is {MAX_LOTTIE_BYTES}",
+                bytes.len()
+            );
+        }
+        let root: Value = serde_json::from_slice(bytes).context("Lottie file is not JSON")?;
+        let number = |key| root.get(key).and_then(Value::as_f64);
+        let (Some(width), Some(height), Some(frame_rate), Some(in_point), Some(out_point)) = (
+            number("w"),
+            number("h"),
+            number("fr"),
+            number("ip"),
+            number("op"),
+        ) else {
+            bail!("not a Lottie animation: `w`, `h`, `fr`, `ip` or `op` is missing");
+        };
+        if !(width > 0.0 && height > 0.0 && frame_rate > 0.0 && out_point > in_point) {
+            bail!("Lottie animation has an empty size or duration");
+        }
+        let mut parser = Parser::default();
+        let mut layers = root
+            .get("layers")
+            .and_then(Value::as_array)
+            .context("Lottie animation has no `layers`")?
+            .iter()
+            .filter_map(|layer| parser.layer(layer))
+            .collect::<Vec<_>>();
+        if parser.shapes > MAX_LOTTIE_SHAPES {
+            bail!("Lottie animation has more than {MAX_LOTTIE_SHAPES} shapes");
+        }
+        if !parser.skipped.is_empty() {
+            tracing::warn!(
+                skipped = %parser.skipped.join(", "),
+                "Lottie animation uses features the host does not render; they were left out"
+            );
+        }
+        layers.reverse();
+        Ok(Self {
+            inner: Arc::new(AnimationData {
+                width,
+                height,
+                frame_rate,
+                in_point,
+                out_point,
+                layers,
+                source: bytes.into(),
+            }),
+        })
+    }
+
+    /// Intrinsic size in logical pixels.
+    pub fn width(&self) -> f32 {
+        self.inner.width as f32
+    }
+
+    pub fn height(&self) -> f32 {
+        self.inner.height as f32
+    }
+
+    pub fn frame_rate(&self) -> f32 {
+        self.inner.frame_rate as f32
+    }
+
+    pub fn duration_ms(&self) -> f64 {
+        (self.inner.out_point - self.inner.in_point) / self.inner.frame_rate * 1000.0
+    }
+
+    /// Bytes of the JSON source, charged against the guest's image budget.
+    pub fn byte_len(&self) -> usize {
+        self.inner.source.len()
+    }
+
+    pub fn source(&self) -> &[u8] {
+        &self.inner.source
+    }
+
+    /// Whether both are clones of one `parse` result.
+    pub fn ptr_eq(&self, other: &Animation) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// Draw the frame at `time_ms` (looping past the end) into `sink`, mapping the
+    /// animation's `width` x `height` box through `transform`.
+    pub fn render(&self, sink: &mut impl VectorSink, time_ms: f64, transform: Affine) {
+        let data = &self.inner;
+        let length = data.out_point - data.in_point;
+        let frame = data.in_point + (time_ms / 1000.0 * data.frame_rate).rem_euclid(length);
+        if !frame.is_finite() {
+            return;
+        }
+        let by_index: HashMap<i64, &Layer> = data
+            .layers
+            .iter()
+            .filter_map(|layer| Some((layer.index?, layer)))
+            .collect();
+        sink.push_clip(transform, Rect::new(0.0, 0.0, data.width, data.height));
+        for layer in &data.layers {
+            if layer.hidden || frame < layer.in_point || frame >= layer.out_point {
+                continue;
+            }
+            let local = frame - layer.start_time;
+            let mut matrix = layer.transform.matrix(local);
+            // Unlike position, opacity is not inherited from parent layers.
+            let opacity = layer.transform.opacity(local).clamp(0.0, 1.0);
+            let mut parent = layer.parent;
+            for _ in 0..MAX_PARENT_DEPTH {
+                let Some(ancestor) = parent.and_then(|index| by_index.get(&index)) else {
+                    break;
+                };
+                matrix = ancestor.transform.matrix(frame - ancestor.start_time) * matrix;
+                parent = ancestor.parent;
+            }
+            let transform = transform * matrix;
+            match &layer.content {
+                LayerContent::Shapes(items) => {
+                    render_group(sink, items, local, transform, opacity);
+                }
+                LayerContent::Solid { color, size } => {
+                    sink.fill(
+                        Fill::NonZero,
+                        transform,
+                        color.multiply_alpha(opacity as f32),
+                        &Rect::from_origin_size(Point::ZERO, *size).to_path(0.1),
+                    );
+                }
+                LayerContent::Null => {}
+            }
+        }
+        sink.pop_clip();
+    }
+}
+
+impl fmt::Debug for Animation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Animation({}x{}, {:.0}ms)",
+            self.width(),
+            self.height(),
+            self.duration_ms()
+        )
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Animation {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let source: &str = u.arbitrary()?;
+        Ok(Self::parse(source.as_bytes()).unwrap_or_else(|_| {
+            Self::parse(br#"{"w":1,"h":1,"fr":1,"ip":0,"op":1,"layers":[]}"#)
+                .expect("empty animation parses")
+        }))
+    }
+}
+
+struct Layer {
+    index: Option<i64>,
+    parent: Option<i64>,
+    hidden: bool,
+    in_point: f64,
+    out_point: f64,
+    start_time: f64,
+    transform: Transform,
+    content: LayerContent,
+}
+
+enum LayerContent {
+    /// Items in document order (top-most first).
+    Shapes(Vec<Item>),
+    Solid {
+        color: Color,
+        size: (f64, f64),
+    },
+    Null,
+}
+
+enum Item {
+    Group {
+        items: Vec<Item>,
+        transform: Transform,
+    },
+    Geometry(Geometry),
+    Fill {
+        color: Animated,
+        opacity: Animated,
+        rule: Fill,
+    },
+    Stroke {
+        color: Animated,
+        opacity: Animated,
+        width: Animated,
+        cap: Cap,
+        join: Join,
+        miter_limit: f64,
+    },
+}
+
+enum Geometry {
+    Rect {
+        position: Animated,
+        size: Animated,
+        roundness: Animated,
+    },
+    Ellipse {
+        position: Animated,
+        size: Animated,
+    },
+    Path(AnimatedPath),
+}
+
+/// A layer or group transform. Position may be split into separately animated axes.
+struct Transform {
+    anchor: Animated,
+    position: Position,
+    scale: Animated,
+    rotation: Animated,
+    opacity: Animated,
+}
+
+enum Position {
+    Joined(Animated),
+    Split(Animated, Animated),
+}
+
+impl Transform {
+    fn matrix(&self, frame: f64) -> Affine {
+        let anchor = self.anchor.at(frame);
+        let (x, y) = match &self.position {
+            Position::Joined(position) => {
+                let position = position.at(frame);
+                (component(&position, 0), component(&position, 1))
+            }
+            Position::Split(x, y) => (component(&x.at(frame), 0), component(&y.at(frame), 0)),
+        };
+        let scale = self.scale.at(frame);
+        let scale_x = component(&scale, 0) / 100.0;
+        let scale_y = scale.get(1).copied().unwrap_or(scale_x * 100.0) / 100.0;
+        Affine::translate((x, y))
+            * Affine::rotate(component(&self.rotation.at(frame), 0).to_radians())
+            * Affine::scale_non_uniform(scale_x, scale_y)
+            * Affine::translate((-component(&anchor, 0), -component(&anchor, 1)))
+    }
+
+    fn opacity(&self, frame: f64) -> f64 {
+        component(&self.opacity.at(frame), 0) / 100.0
+    }
+}
+
+/// Draw a group's items. Each fill or stroke paints the geometry listed before it in the
+/// same group, and earlier items draw on top of later ones.
+fn render_group(
+    sink: &mut impl VectorSink,
+    items: &[Item],
+    frame: f64,
+    transform: Affine,
+    opacity: f64,
+) {
+    let paths: Vec<BezPath> = items
+        .iter()
+        .map(|item| match item {
+            Item::Geometry(geometry) => geometry.path(frame),
+            _ => BezPath::new(),
+        })
+        .collect();
+    for (index, item) in items.iter().enumerate().rev() {
+        let above = || {
+            let mut combined = BezPath::new();
+            for path in &paths[..index] {
+                combined.extend(path.iter());
+            }
+            combined
+        };
+        match item {
+            Item::Group {
+                items,
+                transform: group,
+            } => {
+                let opacity = opacity * group.opacity(frame).clamp(0.0, 1.0);
+                render_group(sink, items, frame, transform * group.matrix(frame), opacity);
+            }
+            Item::Geometry(_) => {}
+            Item::Fill {
+                color,
+                opacity: fill_opacity,
+                rule,
+            } => {
+                let alpha = opacity * component(&fill_opacity.at(frame), 0) / 100.0;
+                sink.fill(*rule, transform, color_at(color, frame, alpha), &above());
+            }
+            Item::Stroke {
+                color,
+                opacity: stroke_opacity,
+                width,
+                cap,
+                join,
+                miter_limit,
+            } => {
+                let width = component(&width.at(frame), 0);
+                if width <= 0.0 || !width.is_finite() {
+                    continue;
+                }
+                let alpha = opacity * component(&stroke_opacity.at(frame), 0) / 100.0;
+                let stroke = Stroke::new(width)
+                    .with_caps(*cap)
+                    .with_join(*join)
+                    .with_miter_limit(*miter_limit);
+                sink.stroke(&stroke, transform, color_at(color, frame, alpha), &above());
+            }
+        }
+    }
+}
+
+fn color_at(color: &Animated, frame: f64, alpha: f64) -> Color {
+    let rgba = color.at(frame);
+    let channel = |i: usize| rgba.get(i).copied().unwrap_or(1.0).clamp(0.0, 1.0) as f32;
+    Color::new([channel(0), channel(1), channel(2), channel(3)])
+        .multiply_alpha(alpha.clamp(0.0, 1.0) as f32)
+}
+
+fn component(values: &[f64], index: usize) -> f64 {
+    values.get(index).copied().unwrap_or(0.0)
+}
+
+impl Geometry {
+    fn path(&self, frame: f64) -> BezPath {
+        match self {
+            Geometry::Rect {
+                position,
+                size,
+                roundness,
+            } => {
+                let (center, size) = (position.at(frame), size.at(frame));
+                let (width, height) = (component(&size, 0).abs(), component(&size, 1).abs());
+                let rect = Rect::from_center_size(
+                    (component(&center, 0), component(&center, 1)),
+                    (width, height),
+                );
+                let radius = component(&roundness.at(frame), 0).clamp(0.0, width.min(height) / 2.0);
+                RoundedRect::from_rect(rect, radius).to_path(SHAPE_TOLERANCE)
+            }
+            Geometry::Ellipse { position, size } => {
+                let (center, size) = (position.at(frame), size.at(frame));
+                Ellipse::new(
+                    (component(&center, 0), component(&center, 1)),
+                    (
+                        component(&size, 0).abs() / 2.0,
+                        component(&size, 1).abs() / 2.0,
+                    ),
+                    0.0,
+                )
+                .to_path(SHAPE_TOLERANCE)
+            }
+            Geometry::Path(path) => path.at(frame).to_path(),
+        }
+    }
+}
+
+/// Cubic bezier easing between two keyframes, as `cubic-bezier(out.x, out.y, in.x, in.y)`.
+#[derive(Clone, Copy)]
+struct Easing {
+    out: (f64, f64),
+    into: (f64, f64),
+}
+
+impl Easing {
+    const LINEAR: Easing = Easing {
+        out: (0.0, 0.0),
+        into: (1.0, 1.0),
+    };
+
+    /// Progress along the value for `t` of the way through the time span.
+    fn apply(self, t: f64) -> f64 {
+        let bezier = |a: f64, b: f64, s: f64| {
+            3.0 * (1.0 - s) * (1.0 - s) * s * a + 3.0 * (1.0 - s) * s * s * b + s * s * s
+        };
+        // Solve bezier_x(s) = t by bisection; x is monotonic for x control points in 0..=1.
+        let (x1, x2) = (self.out.0.clamp(0.0, 1.0), self.into.0.clamp(0.0, 1.0));
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..48 {
+            let mid = (low + high) / 2.0;
+            if bezier(x1, x2, mid) < t {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        bezier(self.out.1, self.into.1, (low + high) / 2.0)
+    }
+}
+
+/// A property that is either constant or keyframed. Values are flat number lists
+/// (`[x, y]`, `[r, g, b, a]`, `[degrees]`).
+struct Keyframed<T> {
+    /// Sorted by time; a single entry means the value is static.
+    keys: Vec<Key<T>>,
+}
+
+struct Key<T> {
+    time: f64,
+    value: T,
+    /// Explicit end value (older files); otherwise the next key's value.
+    end: Option<T>,
+    easing: Easing,
+    hold: bool,
+}
+
+type Animated = Keyframed<Vec<f64>>;
+type AnimatedPath = Keyframed<PathShape>;
+
+trait Lerp: Clone {
+    fn lerp(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Lerp for Vec<f64> {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        self.iter()
+            .zip(other)
+            .map(|(a, b)| a + (b - a) * t)
+            .collect()
+    }
+}
+
+impl<T: Lerp> Keyframed<T> {
+    fn constant(value: T) -> Self {
+        Self {
+            keys: vec![Key {
+                time: 0.0,
+                value,
+                end: None,
+                easing: Easing::LINEAR,
+                hold: false,
+            }],
+        }
+    }
+
+    fn at(&self, frame: f64) -> T {
+        let index = self.keys.partition_point(|key| key.time <= frame);
+        let Some(key) = index.checked_sub(1).map(|i| &self.keys[i]) else {
+            return self.keys[0].value.clone();
+        };
+        let (Some(next), false) = (self.keys.get(index), key.hold) else {
+            return key.value.clone();
+        };
+        let end = key.end.as_ref().unwrap_or(&next.value);
+        let span = next.time - key.time;
+        let t = if span > 0.0 {
+            (frame - key.time) / span
+        } else {
+            1.0
+        };
+        key.value.lerp(end, key.easing.apply(t.clamp(0.0, 1.0)))
+    }
+}
+
+/// Bezier path vertices with in and out tangents relative to each vertex.
+#[derive(Clone)]
+struct PathShape {
+    vertices: Vec<(f64, f64)>,
+    in_tangents: Vec<(f64, f64)>,
+    out_tangents: Vec<(f64, f64)>,
+    closed: bool,
+}
+
+impl Lerp for PathShape {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        if self.vertices.len() != other.vertices.len() {
+            return self.clone();
+        }
+        let mix = |a: &[(f64, f64)], b: &[(f64, f64)]| {
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t))
+                .collect()
+        };
+        Self {
+            vertices: mix(&self.vertices, &other.vertices),
+            in_tangents: mix(&self.in_tangents, &other.in_tangents),
+            out_tangents: mix(&self.out_tangents, &other.out_tangents),
+            closed: self.closed,
+        }
+    }
+}
+
+impl PathShape {
+    fn to_path(&self) -> BezPath {
+        let mut path = BezPath::new();
+        let count = self.vertices.len();
+        let Some(&first) = self.vertices.first() else {
+            return path;
+        };
+        let tangent = |list: &[(f64, f64)], i: usize| list.get(i).copied().unwrap_or_default();
+        path.move_to(first);
+        let segments = if self.closed { count } else { count - 1 };
+        for i in 0..segments {
+            let j = (i + 1) % count;
+            let (from, to) = (self.vertices[i], self.vertices[j]);
+            let out = tangent(&self.out_tangents, i);
+            let into = tangent(&self.in_tangents, j);
+            path.curve_to(
+                (from.0 + out.0, from.1 + out.1),
+                (to.0 + into.0, to.1 + into.1),
+                to,
+            );
+        }
+        if self.closed {
+            path.close_path();
+        }
+        path
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    shapes: usize,
+    /// Unsupported features, reported once when parsing finishes.
+    skipped: Vec<String>,
+}
+
+impl Parser {
+    fn skip(&mut self, feature: &str) {
+        if !self.skipped.iter().any(|s| s == feature) {
+            self.skipped.push(feature.to_string());
+        }
+    }
+
+    fn layer(&mut self, layer: &Value) -> Option<Layer> {
+        let number = |key| layer.get(key).and_then(Value::as_f64);
+        let content = match layer.get("ty").and_then(Value::as_i64) {
+            Some(4) => LayerContent::Shapes(self.items(layer.get("shapes")?)),
+            Some(1) => LayerContent::Solid {
+                color: layer
+                    .get("sc")
+                    .and_then(Value::as_str)
+                    .and_then(|hex| parse_color(hex).ok())
+                    .map(|color| color.to_alpha_color::<Srgb>())
+                    .unwrap_or(Color::BLACK),
+                size: (number("sw").unwrap_or(0.0), number("sh").unwrap_or(0.0)),
+            },
+            Some(3) => LayerContent::Null,
+            Some(0) => {
+                self.skip("precomposition layers");
+                return None;
+            }
+            Some(2) => {
+                self.skip("image layers");
+                return None;
+            }
+            Some(5) => {
+                self.skip("text layers");
+                return None;
+            }
+            _ => {
+                self.skip("unknown layer types");
+                return None;
+            }
+        };
+        let non_empty = |key| {
+            layer
+                .get(key)
+                .and_then(Value::as_array)
+                .is_some_and(|list| !list.is_empty())
+        };
+        if non_empty("masksProperties") {
+            self.skip("masks");
+        }
+        if layer.get("tt").is_some() {
+            self.skip("track mattes");
+        }
+        if non_empty("ef") {
+            self.skip("effects");
+        }
+        Some(Layer {
+            index: layer.get("ind").and_then(Value::as_i64),
+            parent: layer.get("parent").and_then(Value::as_i64),
+            hidden: layer.get("hd").and_then(Value::as_bool).unwrap_or(false),
+            in_point: number("ip").unwrap_or(f64::MIN),
+            out_point: number("op").unwrap_or(f64::MAX),
+            start_time: number("st").unwrap_or(0.0),
+            transform: self.transform(layer.get("ks")),
+            content,
+        })
+    }
+
+    fn transform(&mut self, transform: Option<&Value>) -> Transform {
+        let property = |key, default: &[f64]| {
+            transform
+                .and_then(|t| t.get(key))
+                .and_then(animated)
+                .unwrap_or_else(|| Keyframed::constant(default.to_vec()))
+        };
+        let position = match transform.and_then(|t| t.get("p")) {
+            Some(split) if split.get("s").and_then(Value::as_bool) == Some(true) => {
+                let axis = |key| {
+                    split
+                        .get(key)
+                        .and_then(animated)
+                        .unwrap_or_else(|| Keyframed::constant(vec![0.0]))
+                };
+                Position::Split(axis("x"), axis("y"))
+            }
+            _ => Position::Joined(property("p", &[0.0, 0.0])),
+        };
+        Transform {
+            anchor: property("a", &[0.0, 0.0]),
+            position,
+            scale: property("s", &[100.0, 100.0]),
+            rotation: property("r", &[0.0]),
+            opacity: property("o", &[100.0]),
+        }
+    }
+
+    fn items(&mut self, items: &Value) -> Vec<Item> {
+        let Some(items) = items.as_array() else {
+            return Vec::new();
+        };
+        let mut parsed = Vec::new();
+        for item in items {
+            if item.get("hd").and_then(Value::as_bool) == Some(true) {
+                continue;
+            }
+            let property = |key, default: &[f64]| {
+                item.get(key)
+                    .and_then(animated)
+                    .unwrap_or_else(|| Keyframed::constant(default.to_vec()))
+            };
+            let parsed_item = match item.get("ty").and_then(Value::as_str) {
+                Some("gr") => Item::Group {
+                    items: self.items(item.get("it").unwrap_or(&Value::Null)),
+                    transform: self.group_transform(item),
+                },
+                // Read by the enclosing group through `group_transform`.
+                Some("tr") => continue,
+                Some("rc") => Item::Geometry(Geometry::Rect {
+                    position: property("p", &[0.0, 0.0]),
+                    size: property("s", &[0.0, 0.0]),
+                    roundness: property("r", &[0.0]),
+                }),
+                Some("el") => Item::Geometry(Geometry::Ellipse {
+                    position: property("p", &[0.0, 0.0]),
+                    size: property("s", &[0.0, 0.0]),
+                }),
+                Some("sh") => match item.get("ks").and_then(animated_path) {
+                    Some(path) => Item::Geometry(Geometry::Path(path)),
+                    None => continue,
+                },
+                Some("fl") => Item::Fill {
+                    color: property("c", &[0.0, 0.0, 0.0, 1.0]),
+                    opacity: property("o", &[100.0]),
+                    rule: if item.get("r").and_then(Value::as_i64) == Some(2) {
+                        Fill::EvenOdd
+                    } else {
+                        Fill::NonZero
+                    },
+                },
+                Some("st") => Item::Stroke {
+                    color: property("c", &[0.0, 0.0, 0.0, 1.0]),
+                    opacity: property("o", &[100.0]),
+                    width: property("w", &[1.0]),
+                    cap: match item.get("lc").and_then(Value::as_i64) {
+                        Some(2) => Cap::Round,
+                        Some(3) => Cap::Square,
+                        _ => Cap::Butt,
+                    },
+                    join: match item.get("lj").and_then(Value::as_i64) {
+                        Some(2) => Join::Round,
+                        Some(3) => Join::Bevel,
+                        _ => Join::Miter,
+                    },
+                    miter_limit: item.get("ml").and_then(Value::as_f64).unwrap_or(4.0),
+                },
+                Some("gf" | "gs") => {
+                    self.skip("gradients");
+                    continue;
+                }
+                Some(other) => {
+                    self.skip(&format!("`{other}` shapes"));
+                    continue;
+                }
+                None => continue,
+            };
+            if matches!(parsed_item, Item::Geometry(_)) {
+                self.shapes += 1;
+            }
+            parsed.push(parsed_item);
+        }
+        parsed
+    }
+
+    fn group_transform(&mut self, group: &Value) -> Transform {
+        let transform = group.get("it").and_then(Value::as_array).and_then(|items| {
+            items
+                .iter()
+                .find(|item| item.get("ty").and_then(Value::as_str) == Some("tr"))
+        });
+        self.transform(transform)
+    }
+}
+
+/// `{"a": 0, "k": value}` or `{"a": 1, "k": [keyframes]}` with numeric values.
+fn animated(property: &Value) -> Option<Animated> {
+    keyframed(property, numbers)
+}
+
+fn animated_path(property: &Value) -> Option<AnimatedPath> {
+    keyframed(property, |value| {
+        // Keyframe values wrap the shape in a one-element array.
+        let shape = value.as_array().and_then(|v| v.first()).unwrap_or(value);
+        path_shape(shape)
+    })
+}
+
+fn keyframed<T: Lerp>(
+    property: &Value,
+    parse: impl Fn(&Value) -> Option<T>,
+) -> Option<Keyframed<T>> {
+    let value = property.get("k")?;
+    let keys = value
+        .as_array()
+        .filter(|keys| keys.first().is_some_and(|key| key.get("t").is_some()));
+    let Some(keys) = keys else {
+        return parse(value).map(Keyframed::constant);
+    };
+    let mut parsed: Vec<Key<T>> = Vec::with_capacity(keys.len());
+    for key in keys {
+        let time = key.get("t").and_then(Value::as_f64)?;
+        let value = match key.get("s").and_then(&parse) {
+            Some(value) => value,
+            // Old files end with a key holding only a time: it takes the previous end.
+            None => parsed.last()?.end.clone()?,
+        };
+        let handle = |key: &Value, which: &str| -> Option<(f64, f64)> {
+            let handle = key.get(which)?;
+            let first = |axis: &str| {
+                handle
+                    .get(axis)
+                    .and_then(|v| v.as_f64().or_else(|| v.get(0)?.as_f64()))
+            };
+            Some((first("x")?, first("y")?))
+        };
+        parsed.push(Key {
+            time,
+            value,
+            end: key.get("e").and_then(&parse),
+            easing: match (handle(key, "o"), handle(key, "i")) {
+                (Some(out), Some(into)) => Easing { out, into },
+                _ => Easing::LINEAR,
+            },
+            hold: key.get("h").and_then(Value::as_i64) == Some(1),
+        });
+    }
+    parsed.sort_by(|a, b| a.time.total_cmp(&b.time));
+    (!parsed.is_empty()).then_some(Keyframed { keys: parsed })
+}
+
+/// A number or list of numbers.
+fn numbers(value: &Value) -> Option<Vec<f64>> {
+    match value {
+        Value::Number(number) => Some(vec![number.as_f64()?]),
+        Value::Array(values) => values.iter().map(Value::as_f64).collect(),
+        _ => None,
+    }
+}
+
+fn path_shape(shape: &Value) -> Option<PathShape> {
+    let points = |key| -> Option<Vec<(f64, f64)>> {
+        shape
+            .get(key)?
+            .as_array()?
+            .iter()
+            .map(|point| Some((point.get(0)?.as_f64()?, point.get(1)?.as_f64()?)))
+            .collect()
+    };
+    Some(PathShape {
+        vertices: points("v")?,
+        in_tangents: points("i").unwrap_or_default(),
+        out_tangents: points("o").unwrap_or_default(),
+        closed: shape.get("c").and_then(Value::as_bool).unwrap_or(false),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Animated, Animation, Easing, Key, Keyframed};
+
+    const SPINNER: &[u8] = br##"{
+        "v": "5.7.4", "w": 64, "h": 32, "fr": 30, "ip": 0, "op": 60,
+        "layers": [
+            {"ty": 4, "ind": 1, "ip": 0, "op": 60, "st": 0,
+             "ks": {"p": {"a": 0, "k": [32, 16, 0]},
+                    "r": {"a": 1, "k": [{"t": 0, "s": [0], "o": {"x": [0.4], "y": [0]}, "i": {"x": [0.6], "y": [1]}},
+                                        {"t": 60, "s": [360]}]}},
+             "shapes": [{"ty": "gr", "it": [
+                 {"ty": "el", "p": {"a": 0, "k": [0, 0]}, "s": {"a": 0, "k": [20, 20]}},
+                 {"ty": "sh", "ks": {"a": 0, "k": {"i": [[0, 0], [0, 0]], "o": [[0, 0], [0, 0]], "v": [[0, 0], [8, 0]], "c": false}}},
+                 {"ty": "st", "c": {"a": 0, "k": [1, 0, 0, 1]}, "o": {"a": 0, "k": 100}, "w": {"a": 0, "k": 3}, "lc": 2},
+                 {"ty": "gf"},
+                 {"ty": "tr", "p": {"a": 0, "k": [0, 0]}, "o": {"a": 0, "k": 50}}
+             ]}]},
+            {"ty": 0, "refId": "comp_0"},
+            {"ty": 1, "sc": "#202020", "sw": 64, "sh": 32}
+        ]
+    }"##;
+
+    #[test]
+    fn reads_size_timing_and_supported_layers() {
+        let animation = Animation::parse(SPINNER).unwrap();
+        assert_eq!((animation.width(), animation.height()), (64.0, 32.0));
+        assert_eq!(animation.frame_rate(), 30.0);
+        assert_eq!(animation.duration_ms(), 2000.0);
+        // The precomposition is skipped; the solid is drawn first, beneath the shapes.
+        assert_eq!(animation.inner.layers.len(), 2);
+        let mut scene = vello::Scene::new();
+        animation.render(&mut scene, 2500.0, Default::default());
+
+        assert!(Animation::parse(b"{").is_err());
+        assert!(Animation::parse(br#"{"w": 10, "h": 10, "layers": []}"#).is_err());
+        assert!(Animation::parse(br#"{"w":1,"h":1,"fr":30,"ip":5,"op":5,"layers":[]}"#).is_err());
+    }
+
+    #[test]
+    fn interpolates_eased_and_held_keyframes() {
+        let key = |time, value: f64, easing, hold| Key {
+            time,
+            value: vec![value],
+            end: None,
+            easing,
+            hold,
+        };
+        let linear: Animated = Keyframed {
+            keys: vec![
+                key(10.0, 0.0, Easing::LINEAR, false),
+                key(20.0, 100.0, Easing::LINEAR, true),
+                key(30.0, 0.0, Easing::LINEAR, false),
+            ],
+        };
+        assert_eq!(linear.at(0.0), [0.0]);
+        assert!((linear.at(15.0)[0] - 50.0).abs() < 1e-9);
+        assert_eq!(linear.at(25.0), [100.0], "held until the next key");
+        assert_eq!(linear.at(99.0), [0.0]);
+
+        let ease_in = Easing {
+            out: (0.42, 0.0),
+            into: (1.0, 1.0),
+        };
+        let eased = ease_in.apply(0.5);
+        assert!(eased > 0.3 && eased < 0.35, "{eased}");
+        assert!(ease_in.apply(0.0).abs() < 1e-6 && (ease_in.apply(1.0) - 1.0).abs() < 1e-6);
+    }
+}
@@ -1,11 +1,42 @@
-use std::path::PathBuf;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use anyhow::Result;
-use clap::{Parser, ValueHint};
-use tracing_subscriber::EnvFilter;
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand, ValueHint};
+use winit::dpi::PhysicalSize;
 use winit::event_loop::EventLoop;
 
-use frontier_wasm_host::{app::App, ComponentSource};
+use frontier_wasm_host::camera::CameraAccess;
+use frontier_wasm_host::clock::{VirtualClock, DEFAULT_MAX_DT_MS};
+use frontier_wasm_host::config::HostConfig;
+use frontier_wasm_host::control::{self, ControlAddress};
+use frontier_wasm_host::deeplink::{self, DeepLink};
+use frontier_wasm_host::engine::EngineOptions;
+use frontier_wasm_host::export;
+use frontier_wasm_host::frame_file;
+use frontier_wasm_host::graphics::{
+    Antialiasing, GraphicsState, PresentMode, RenderOptions, DEFAULT_CLEAR_COLOR,
+};
+use frontier_wasm_host::identity::{self, DataDirs};
+use frontier_wasm_host::idle;
+use frontier_wasm_host::instance::{self, Activation};
+use frontier_wasm_host::locale::Locale;
+use frontier_wasm_host::logging::{self, LogFormat, LogOptions};
+use frontier_wasm_host::manifest::ComponentManifest;
+use frontier_wasm_host::metrics::{self, Metrics};
+use frontier_wasm_host::power::PowerSave;
+use frontier_wasm_host::precompile;
+use frontier_wasm_host::preferences::{ForcedColors, Preferences};
+use frontier_wasm_host::script::{ScriptRunner, TestScript};
+use frontier_wasm_host::software::SoftwareCanvas;
+use frontier_wasm_host::transition::{self, TransitionKind};
+use frontier_wasm_host::window::{clamp_opacity, sanitize_app_id, Theme, WindowLevel};
+use frontier_wasm_host::{
+    app::{App, AppEvent},
+    Color, ComponentRuntime, ComponentSource, LaunchConfig, LogicalSize, ResourceLimits,
+    WindowOptions,
+};
 
 const EMBEDDED_COUNTER_LABEL: &str = "embedded counter demo";
 const EMBEDDED_COUNTER_COMPONENT: &[u8] = include_bytes!(concat!(
@@ -23,29 +54,878 @@ struct Args {
         help = "Path to the guest component (.wasm). Omit to use the embedded counter demo."
     )]
     component: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "frontier://URL",
+        help = "Open a frontier:// link: `frontier://run?src=PATH` runs that component, and the link is passed to the guest's `deep-link-opened`."
+    )]
+    open: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        help = "Host config file. Defaults to frontier-host.toml under the platform config directory."
+    )]
+    config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "PNG",
+        value_hint = ValueHint::FilePath,
+        help = "PNG image used as the window and taskbar icon."
+    )]
+    icon: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "ID",
+        help = "Application id / WM_CLASS hint. Defaults to one derived from the component name."
+    )]
+    app_id: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = WindowLevel::Normal,
+        help = "Stack the window above or below other windows."
+    )]
+    window_level: WindowLevel,
+
+    #[arg(
+        long,
+        value_name = "0.0-1.0",
+        default_value_t = 1.0,
+        help = "Overall window opacity."
+    )]
+    opacity: f32,
+
+    #[arg(
+        long,
+        help = "Let pointer input pass through the window (overlay mode)."
+    )]
+    click_through: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Window decoration theme. Defaults to the system theme."
+    )]
+    theme: Option<Theme>,
+
+    #[arg(
+        long,
+        value_name = "COLOR",
+        value_parser = parse_color,
+        help = "Colour behind frames that do not clear, as a CSS colour such as #1e1e2e."
+    )]
+    background_color: Option<Color>,
+
+    #[arg(
+        long,
+        value_name = "PALETTE",
+        value_parser = load_palette,
+        help = "Force guest colours onto the `background` and `text` colours of a TOML palette, for high contrast."
+    )]
+    force_colors: Option<ForcedColors>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Swapchain present mode. Defaults to fifo (vsync)."
+    )]
+    present_mode: Option<PresentMode>,
+
+    #[arg(long, value_enum, help = "Antialiasing method. Defaults to area.")]
+    antialiasing: Option<Antialiasing>,
+
+    #[arg(
+        long,
+        value_name = "FRAME",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Capture the GPU work of the FRAMEth rendered frame in an attached RenderDoc or Xcode."
+    )]
+    gpu_capture: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Do not keep compiled GPU pipelines between runs (only cached on Vulkan)."
+    )]
+    no_pipeline_cache: bool,
+
+    #[arg(
+        long,
+        help = "Render a throwaway scene while the component loads so the first frame does not stutter."
+    )]
+    warm_up: bool,
+
+    #[arg(
+        long = "env",
+        value_name = "NAME[=VALUE]",
+        help = "Expose an environment variable to the guest; NAME alone forwards the host value."
+    )]
+    env: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "TAG",
+        help = "Locale for guest number and date formatting, e.g. `de-CH`. Defaults to LC_ALL or LANG."
+    )]
+    locale: Option<Locale>,
+
+    #[arg(
+        long,
+        value_name = "DIRECTIVES",
+        help = "Tracing filter (RUST_LOG syntax), e.g. `info,guest=debug,frame=debug`. Overrides RUST_LOG."
+    )]
+    log_filter: Option<String>,
+
+    #[arg(long, value_enum, default_value_t = LogFormat::Compact, help = "Log output format.")]
+    log_format: LogFormat,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        help = "Persistent, size-rotated log file. Defaults to a file under the platform data directory."
+    )]
+    log_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with = "log_file",
+        help = "Do not write a persistent log file."
+    )]
+    no_log_file: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        help = "Write a Chrome trace-event file (chrome://tracing, Perfetto) of host spans and guest perf measures."
+    )]
+    chrome_trace: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "SCALE",
+        default_value_t = 1.0,
+        help = "Scale the dt passed to guest frames (e.g. 0.1 for slow motion). F8 cycles speeds at runtime."
+    )]
+    time_scale: f32,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = DEFAULT_MAX_DT_MS,
+        help = "Cap the real time one guest frame's dt covers, so stalls do not arrive as one huge step; 0 disables. Guests read the real time with frame-elapsed-ms."
+    )]
+    max_frame_dt: f32,
+
+    #[arg(
+        long,
+        help = "Start with guest time paused. F6 toggles pause and F7 single-steps one frame."
+    )]
+    paused: bool,
+
+    #[arg(
+        long,
+        help = "Deliver at most one pointer move per frame (latest position) instead of one per raw OS event."
+    )]
+    coalesce_pointer_moves: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = ResourceLimits::default().max_draw_commands,
+        help = "Draw commands kept per frame; extra commands are dropped."
+    )]
+    max_draw_commands: usize,
+
+    #[arg(
+        long,
+        value_name = "BYTES",
+        default_value_t = ResourceLimits::default().max_text_bytes,
+        help = "Total draw-text bytes kept per frame; extra text is dropped."
+    )]
+    max_text_bytes: usize,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = ResourceLimits::default().max_host_calls,
+        help = "Host calls allowed per guest callback before the component is stopped."
+    )]
+    max_host_calls: u32,
+
+    #[arg(
+        long,
+        value_name = "BYTES",
+        default_value_t = ResourceLimits::default().max_image_bytes,
+        help = "Bytes the guest may hold in images and other assets at once; uploads past it fail."
+    )]
+    max_image_bytes: usize,
+
+    #[arg(
+        long,
+        value_name = "BYTES",
+        default_value_t = ResourceLimits::default().max_image_upload_bytes_per_second,
+        help = "Pixel bytes the guest may upload per second; uploads past it fail."
+    )]
+    max_image_upload_bytes_per_second: usize,
+
+    #[arg(
+        long,
+        value_name = "BYTES",
+        default_value_t = ResourceLimits::default().max_database_bytes,
+        help = "Size a component's database may grow to; writes past it fail."
+    )]
+    max_database_bytes: u64,
+
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        help = "Allow SIMD and relaxed SIMD instructions. On by default."
+    )]
+    simd: Option<bool>,
+
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        help = "Allow atomics and shared memories (the wasm threads proposal). On by default."
+    )]
+    threads: Option<bool>,
+
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        help = "Allow 64-bit memories (the memory64 proposal)."
+    )]
+    memory64: Option<bool>,
+
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        default_missing_value = "true",
+        help = "Preallocate instance slots so instantiation and reloads are faster."
+    )]
+    pooling_allocator: Option<bool>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Host threads that run background tasks and `compile` jobs. Defaults to one per core, up to 8."
+    )]
+    worker_threads: Option<NonZeroUsize>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = CameraAccess::Ask,
+        help = "Answer camera requests up front instead of prompting in the window. Capture needs a host built with `--features camera`."
+    )]
+    camera: CameraAccess,
+
+    #[arg(
+        long,
+        value_name = "OUT.svg|OUT.pdf",
+        value_hint = ValueHint::FilePath,
+        help = "Render the guest's first frame without a window, write it as SVG or PDF and exit."
+    )]
+    export_frame: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "WIDTHxHEIGHT",
+        default_value = "800x600",
+        value_parser = parse_size,
+        help = "Logical size of the frame rendered for --export-frame."
+    )]
+    export_size: (f32, f32),
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        value_hint = ValueHint::DirPath,
+        default_value = ".",
+        help = "Directory for frames the guest saves with `export-frame`."
+    )]
+    export_dir: PathBuf,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = PowerSave::Off,
+        help = "Cap guest frames at 30 fps to save power: `auto` while on battery or in power-saver mode, `on` always."
+    )]
+    power_save: PowerSave,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = idle::DEFAULT_TIMEOUT.as_secs(),
+        help = "Send the guest `idle-changed` after this long without pointer, touch or keyboard input; 0 turns it off."
+    )]
+    idle_timeout: u64,
+
+    #[arg(
+        long,
+        value_name = "MB",
+        help = "Ask the guest to free assets once its assets and the host's caches near this many megabytes."
+    )]
+    memory_budget: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Let the guest replace itself with another local component through `navigate`."
+    )]
+    allow_navigation: bool,
+
+    #[arg(
+        long,
+        help = "If this component is already running, pass this launch's arguments and --open link to that window and exit."
+    )]
+    single_instance: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = TransitionKind::None,
+        help = "Transition from the old component's last frame to the new one when the component restarts."
+    )]
+    transition: TransitionKind,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = transition::DEFAULT_DURATION.as_millis() as u64,
+        help = "Length of the --transition animation in milliseconds."
+    )]
+    transition_ms: u64,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        value_hint = ValueHint::DirPath,
+        help = "Save every guest frame to DIR as a numbered .fframe file for `render-file`."
+    )]
+    dump_frames: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "unix:PATH|tcp:HOST:PORT",
+        help = "Listen for JSON remote-control commands (input injection, screenshots, reload, stats)."
+    )]
+    control: Option<ControlAddress>,
+
+    #[arg(
+        long,
+        value_name = "HOST:PORT",
+        help = "Serve Prometheus metrics (frame times, guest call durations, draw commands, GPU memory, restarts) on http://HOST:PORT/metrics."
+    )]
+    metrics: Option<String>,
+
+    #[arg(
+        last = true,
+        value_name = "GUEST_ARGS",
+        help = "Arguments passed to the guest after `--`."
+    )]
+    guest_args: Vec<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run a YAML test script against the component headlessly; exits non-zero on failure.
+    Test {
+        #[arg(value_name = "SCRIPT", value_hint = ValueHint::FilePath)]
+        script: PathBuf,
+    },
+    /// Rasterize .fframe files from --dump-frames to PNG without running the component.
+    RenderFile {
+        #[arg(value_name = "FILE", required = true, value_hint = ValueHint::FilePath)]
+        files: Vec<PathBuf>,
+        /// Directory for the PNGs; each is written next to its input by default.
+        #[arg(long, short, value_name = "DIR", value_hint = ValueHint::DirPath)]
+        output: Option<PathBuf>,
+    },
+    /// Precompile components to .cwasm so they load without JIT compilation.
+    Compile {
+        #[arg(value_name = "WASM", required = true, value_hint = ValueHint::FilePath)]
+        inputs: Vec<PathBuf>,
+        /// Output file; defaults to each input with a .cwasm extension.
+        #[arg(long, short, value_name = "OUT.cwasm", value_hint = ValueHint::FilePath)]
+        output: Option<PathBuf>,
+        /// Target triple to compile for, e.g. aarch64-unknown-linux-gnu; defaults to this
+        /// machine.
+        #[arg(long, value_name = "TRIPLE")]
+        target: Option<String>,
+    },
+    /// Make this executable the handler for frontier:// links for the current user.
+    RegisterScheme,
+    /// Manage the data the host keeps per component.
+    Data {
+        #[command(subcommand)]
+        action: DataCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DataCommand {
+    /// List components with stored data, their origin and disk usage.
+    List,
+    /// Delete everything stored for a component id from `data list`.
+    Clear {
+        #[arg(value_name = "ID")]
+        id: String,
+    },
+}
+
+impl Args {
+    fn limits(&self) -> ResourceLimits {
+        ResourceLimits {
+            max_draw_commands: self.max_draw_commands,
+            max_text_bytes: self.max_text_bytes,
+            max_host_calls: self.max_host_calls,
+            max_image_bytes: self.max_image_bytes,
+            max_image_upload_bytes_per_second: self.max_image_upload_bytes_per_second,
+            max_database_bytes: self.max_database_bytes,
+        }
+    }
+
+    fn engine_options(&self, config: &HostConfig) -> EngineOptions {
+        EngineOptions {
+            simd: self.simd.unwrap_or(config.engine.simd),
+            threads: self.threads.unwrap_or(config.engine.threads),
+            memory64: self.memory64.unwrap_or(config.engine.memory64),
+            pooling_allocator: self
+                .pooling_allocator
+                .unwrap_or(config.engine.pooling_allocator),
+            worker_threads: self.worker_threads.or(config.engine.worker_threads),
+        }
+    }
+
+    /// Read the config file and fill in every option not given on the command line.
+    fn merge_config(&mut self) -> Result<HostConfig> {
+        let config = match &self.config {
+            Some(path) => HostConfig::load(path)?,
+            None => HostConfig::load_default()?,
+        };
+        self.log_filter = self.log_filter.take().or_else(|| config.log_filter.clone());
+        self.theme = self.theme.or(config.theme);
+        if self.background_color.is_none() {
+            self.background_color = config.background_color()?;
+        }
+        self.present_mode = self.present_mode.or(config.present_mode);
+        self.antialiasing = self.antialiasing.or(config.antialiasing);
+        Ok(config)
+    }
+
+    fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            present_mode: self.present_mode.unwrap_or_default(),
+            antialiasing: self.antialiasing.unwrap_or_default(),
+            gpu_capture: self.gpu_capture,
+            forced_colors: self.force_colors,
+            pipeline_cache: !self.no_pipeline_cache,
+            warm_up: self.warm_up,
+        }
+    }
+
+    fn log_options(&self) -> LogOptions {
+        LogOptions {
+            filter: self.log_filter.clone(),
+            format: self.log_format,
+            file: if self.no_log_file {
+                None
+            } else {
+                self.log_file.clone().or_else(logging::default_log_file)
+            },
+            chrome_trace: self.chrome_trace.clone(),
+        }
+    }
+}
+
+fn load_palette(path: &str) -> Result<ForcedColors, String> {
+    ForcedColors::load(Path::new(path)).map_err(|err| format!("{err:#}"))
+}
+
+fn parse_color(value: &str) -> Result<Color, String> {
+    Color::parse(value).map_err(|err| err.to_string())
+}
+
+fn parse_size(value: &str) -> Result<(f32, f32), String> {
+    let parsed = value.split_once('x').and_then(|(width, height)| {
+        Some((
+            width.trim().parse::<f32>().ok()?,
+            height.trim().parse::<f32>().ok()?,
+        ))
+    });
+    match parsed {
+        Some((width, height)) if width >= 1.0 && height >= 1.0 && (width * height).is_finite() => {
+            Ok((width, height))
+        }
+        _ => Err(format!(
+            "expected WIDTHxHEIGHT, e.g. 800x600, not `{value}`"
+        )),
+    }
 }
 
 fn main() -> Result<()> {
-    let Args { component } = Args::parse();
+    let mut args = Args::parse();
+    let config = args.merge_config()?;
+    let _log_guard = logging::init(&args.log_options())?;
 
-    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
-    tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
-        .with_target(false)
-        .compact()
-        .init();
+    let result = run(args, config);
+    if let Err(err) = &result {
+        tracing::error!(error = %format!("{err:#}"), "host exited with an error");
+    }
+    result
+}
 
-    let event_loop = EventLoop::new()?;
-    event_loop.set_control_flow(winit::event_loop::ControlFlow::Wait);
+fn run_data_command(action: &DataCommand) -> Result<()> {
+    let dirs = DataDirs::platform()?;
+    match action {
+        DataCommand::List => {
+            for stored in identity::list(&dirs)? {
+                let origin = stored
+                    .identity
+                    .map(|identity| identity.origin)
+                    .unwrap_or_else(|| "(unknown origin)".into());
+                println!(
+                    "{}\t{} KiB\t{origin}",
+                    stored.id,
+                    stored.bytes.div_ceil(1024)
+                );
+            }
+        }
+        DataCommand::Clear { id } => {
+            if identity::clear(&dirs, id)? {
+                println!("cleared {id}");
+            } else {
+                bail!("no data stored for {id}");
+            }
+        }
+    }
+    Ok(())
+}
 
-    let component_source = if let Some(path) = component {
+fn component_source(path: Option<PathBuf>) -> ComponentSource {
+    if let Some(path) = path {
         ComponentSource::from_path(path)
     } else {
         tracing::info!("No --component provided; using embedded counter demo component.");
         ComponentSource::embedded(EMBEDDED_COUNTER_LABEL, EMBEDDED_COUNTER_COMPONENT)
+    }
+}
+
+fn launch_config(
+    guest_args: Vec<String>,
+    env: &[String],
+    locale: Option<Locale>,
+    forced_colors: bool,
+    engine: EngineOptions,
+) -> Result<LaunchConfig> {
+    let detected = Preferences::detect();
+    let mut launch = LaunchConfig {
+        args: guest_args,
+        locale: locale.unwrap_or_else(Locale::detect),
+        preferences: Preferences {
+            high_contrast: detected.high_contrast || forced_colors,
+            forced_colors,
+        },
+        engine,
+        ..LaunchConfig::default()
+    };
+    for spec in env {
+        launch.push_env_spec(spec)?;
+    }
+    Ok(launch)
+}
+
+/// `--component` wins over the script's own `component`, which wins over the embedded demo.
+fn run_test_script(path: &Path, args: &Args, engine: EngineOptions) -> Result<()> {
+    let script = TestScript::load(path)?;
+    let script_dir = path.parent().unwrap_or(Path::new("."));
+    let source = match &args.component {
+        Some(component) => ComponentSource::from_path(component.clone()),
+        None => script
+            .component_source(script_dir)
+            .unwrap_or_else(|| component_source(None)),
+    };
+    let launch = launch_config(
+        args.guest_args.clone(),
+        &args.env,
+        args.locale.clone(),
+        args.force_colors.is_some(),
+        engine,
+    )?;
+
+    let mut runner = ScriptRunner::new(source, launch, script.size)?;
+    runner.set_limits(args.limits());
+    runner
+        .run(&script.steps)
+        .with_context(|| format!("{} failed", path.display()))?;
+    println!("ok: {} ({} steps)", path.display(), script.steps.len());
+    Ok(())
+}
+
+/// Run `init` and one `frame` headlessly and write what the guest drew to `path`.
+fn export_first_frame(path: &Path, args: &Args, engine: EngineOptions) -> Result<()> {
+    // Check the extension before spending time on the component.
+    export::ExportFormat::from_path(path)?;
+    let launch = launch_config(
+        args.guest_args.clone(),
+        &args.env,
+        args.locale.clone(),
+        args.force_colors.is_some(),
+        engine,
+    )?;
+    let mut runtime =
+        ComponentRuntime::with_launch(component_source(args.component.clone()), launch)?;
+    runtime.set_limits(args.limits());
+    let (width, height) = args.export_size;
+    let size = LogicalSize {
+        width,
+        height,
+        scale_factor: 1.0,
+    };
+    runtime.call_init(size).context("component init failed")?;
+    let frame = runtime.call_frame(0.0)?.frame;
+    export::write_frame(&frame, size, path)?;
+    println!(
+        "exported {} ({} commands)",
+        path.display(),
+        frame.command_count()
+    );
+    Ok(())
+}
+
+/// Draw each saved frame at the size it was recorded at and save it as a PNG.
+fn render_files(files: &[PathBuf], output: Option<&Path>, options: RenderOptions) -> Result<()> {
+    if let Some(dir) = output {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let mut graphics: Option<GraphicsState> = None;
+    let mut gpu_unavailable = false;
+    for file in files {
+        let saved = frame_file::read(file)?;
+        let size = saved.size;
+        let physical = PhysicalSize::new(
+            (size.width * size.scale_factor).ceil().max(1.0) as u32,
+            (size.height * size.scale_factor).ceil().max(1.0) as u32,
+        );
+        match &mut graphics {
+            Some(graphics) => {
+                graphics.resize(physical);
+                graphics.set_logical_size(size);
+                graphics.set_scale_factor(size.scale_factor);
+            }
+            None if !gpu_unavailable => {
+                match GraphicsState::headless(
+                    physical.width,
+                    physical.height,
+                    size.scale_factor,
+                    size,
+                    options,
+                ) {
+                    Ok(state) => graphics = Some(state),
+                    Err(err) => {
+                        tracing::warn!(
+                            "no GPU available ({err:#}); rendering on the CPU without SVG, \
+                             Lottie or shader layers"
+                        );
+                        gpu_unavailable = true;
+                    }
+                }
+            }
+            None => {}
+        }
+        let png = match output {
+            Some(dir) => dir.join(file.with_extension("png").file_name().unwrap_or_default()),
+            None => file.with_extension("png"),
+        };
+        let (width, height) = match &mut graphics {
+            Some(graphics) => {
+                graphics.render(Some(&saved.frame), None, None)?;
+                graphics.capture_png(&png)?
+            }
+            None => {
+                let mut canvas =
+                    SoftwareCanvas::new(physical.width, physical.height, size.scale_factor)?;
+                canvas.render(&saved.frame);
+                canvas.save_png(&png)?
+            }
+        };
+        println!("rendered {} ({width}x{height})", png.display());
+    }
+    Ok(())
+}
+
+fn run(args: Args, config: HostConfig) -> Result<()> {
+    let engine = args.engine_options(&config);
+    match &args.command {
+        Some(Command::Test { script }) => return run_test_script(script, &args, engine),
+        Some(Command::RenderFile { files, output }) => {
+            return render_files(files, output.as_deref(), args.render_options());
+        }
+        Some(Command::Data { action }) => return run_data_command(action),
+        Some(Command::Compile {
+            inputs,
+            output,
+            target,
+        }) => {
+            for path in
+                precompile::compile_all(inputs, output.as_deref(), target.as_deref(), &engine)?
+            {
+                println!("{}", path.display());
+            }
+            return Ok(());
+        }
+        Some(Command::RegisterScheme) => {
+            let exe = std::env::current_exe().context("failed to locate the host executable")?;
+            println!("{}", deeplink::register(&exe)?);
+            return Ok(());
+        }
+        None => {}
+    }
+    if let Some(path) = &args.export_frame {
+        return export_first_frame(path, &args, engine);
+    }
+    let limits = args.limits();
+    let render_options = args.render_options();
+
+    let Args {
+        mut component,
+        open,
+        icon,
+        app_id,
+        window_level,
+        opacity,
+        click_through,
+        theme,
+        background_color,
+        force_colors,
+        env,
+        locale,
+        time_scale,
+        max_frame_dt,
+        paused,
+        coalesce_pointer_moves,
+        camera,
+        export_dir,
+        dump_frames,
+        power_save,
+        idle_timeout,
+        memory_budget,
+        allow_navigation,
+        single_instance,
+        transition,
+        transition_ms,
+        control,
+        metrics: metrics_addr,
+        guest_args,
+        ..
+    } = args;
+
+    let deep_link = open.as_deref().map(DeepLink::parse).transpose()?;
+    if let Some(path) = deep_link
+        .as_ref()
+        .map(DeepLink::component)
+        .transpose()?
+        .flatten()
+    {
+        if component.is_some() {
+            bail!("--component and a frontier://run link both name a component");
+        }
+        component = Some(path);
+    }
+    let instance_socket = single_instance.then(|| instance::socket_path(component.as_deref()));
+    if let Some(path) = &instance_socket {
+        let activation = Activation {
+            args: guest_args.clone(),
+            url: open.clone(),
+        };
+        if instance::forward(path, &activation)? {
+            tracing::info!("handed off to the running instance");
+            return Ok(());
+        }
+    }
+
+    let event_loop = EventLoop::<AppEvent>::with_user_event().build()?;
+    event_loop.set_control_flow(winit::event_loop::ControlFlow::Wait);
+
+    let _instance_guard = instance_socket
+        .map(|path| instance::listen(&path, event_loop.create_proxy()))
+        .transpose()?;
+    let _control_server = control
+        .map(|address| control::spawn(&address, event_loop.create_proxy()))
+        .transpose()?;
+    let metrics = metrics_addr
+        .map(|addr| {
+            let metrics = Metrics::default();
+            metrics::spawn(&addr, metrics.clone()).map(|()| metrics)
+        })
+        .transpose()?;
+
+    let component_source = component_source(component);
+    let launch = launch_config(guest_args, &env, locale, force_colors.is_some(), engine)?;
+
+    // An explicit `--icon` wins; the component's own icon is the default.
+    let manifest_icon = match ComponentManifest::of(&component_source) {
+        Ok(manifest) => manifest.icon,
+        Err(err) => {
+            tracing::warn!(error = %format!("{err:#}"), "ignoring the component manifest's icon");
+            None
+        }
+    };
+    let window_options = WindowOptions {
+        icon: icon.or(manifest_icon),
+        app_id: app_id.unwrap_or_else(|| sanitize_app_id(&component_source.label())),
+        level: window_level,
+        opacity: clamp_opacity(opacity),
+        click_through,
+        theme: theme.unwrap_or_default(),
+        background: background_color.unwrap_or(DEFAULT_CLEAR_COLOR),
+        ..WindowOptions::default()
     };
 
-    let mut app = App::new(component_source);
+    let mut app = App::new(component_source, launch, window_options)
+        .with_event_proxy(event_loop.create_proxy())
+        .with_clock(VirtualClock::new(time_scale, paused).with_max_dt(max_frame_dt))
+        .with_limits(limits)
+        .with_render_options(render_options)
+        .with_keybinds(config.keybinds)
+        .with_pointer_coalescing(coalesce_pointer_moves)
+        .with_camera_access(camera)
+        .with_export_dir(export_dir)
+        .with_power_save(power_save)
+        .with_idle_timeout((idle_timeout > 0).then(|| Duration::from_secs(idle_timeout)))
+        .with_memory_budget(memory_budget.map(|mb| mb.saturating_mul(1024 * 1024)))
+        .with_navigation(allow_navigation)
+        .with_transition(transition, Duration::from_millis(transition_ms));
+    if let Some(dir) = dump_frames {
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+        app = app.with_frame_dump(dir);
+    }
+    if let Some(metrics) = metrics {
+        app = app.with_metrics(metrics);
+    }
+    if let Some(link) = deep_link {
+        app = app.with_deep_link(link.url);
+    }
     event_loop.run_app(&mut app)?;
     Ok(())
 }
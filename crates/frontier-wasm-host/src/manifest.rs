@@ -0,0 +1,167 @@
+//! What a component declares about itself in a `NAME.manifest.toml` file next to
+//! `NAME.wasm`. Components without one get the defaults: an id derived from where they
+//! were loaded from, and nothing shared.
+//!
+//! A manifest can also lay out several components in the window, as a `[layout]` tree of
+//! splits and tabs whose panes `[panes]` maps to component files.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::layout::Layout;
+use crate::runtime::ComponentSource;
+
+pub const MANIFEST_EXTENSION: &str = "manifest.toml";
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ComponentManifest {
+    /// Stable id the component's data is filed under, e.g. `com.example.editor`, so it
+    /// keeps its data when the file moves.
+    pub id: Option<String>,
+    /// Ids of components allowed to read and write this component's shared storage.
+    pub shares_storage_with: Vec<String>,
+    /// PNG used as the window and taskbar icon, relative to the manifest's directory.
+    pub icon: Option<PathBuf>,
+    /// Splits and tabs of components to show instead of this component alone.
+    pub layout: Option<Layout>,
+    /// The component file behind each pane of `layout`, relative to the manifest's
+    /// directory. This component only runs if it is listed too.
+    pub panes: BTreeMap<String, PathBuf>,
+}
+
+impl ComponentManifest {
+    pub fn parse(text: &str) -> Result<Self> {
+        let manifest: Self = toml::from_str(text)?;
+        let Some(layout) = &manifest.layout else {
+            if !manifest.panes.is_empty() {
+                bail!("[panes] needs a [layout] to place them in");
+            }
+            return Ok(manifest);
+        };
+        layout.validate()?;
+        let names = layout.pane_names();
+        if let Some(name) = names
+            .iter()
+            .find(|name| !manifest.panes.contains_key(**name))
+        {
+            bail!("pane '{name}' has no component in [panes]");
+        }
+        if let Some(name) = manifest
+            .panes
+            .keys()
+            .find(|name| !names.contains(&name.as_str()))
+        {
+            bail!("[panes] lists '{name}', which is not in the layout");
+        }
+        Ok(manifest)
+    }
+
+    /// Each pane's name and component, in layout order; none without a layout.
+    pub fn pane_sources(&self) -> Vec<(String, ComponentSource)> {
+        let Some(layout) = &self.layout else {
+            return Vec::new();
+        };
+        layout
+            .pane_names()
+            .into_iter()
+            .filter_map(|name| {
+                let path = self.panes.get(name)?;
+                Some((name.to_string(), ComponentSource::from_path(path)))
+            })
+            .collect()
+    }
+
+    /// The manifest beside `component`: `notes.wasm` is described by `notes.manifest.toml`.
+    pub fn path_for(component: &Path) -> PathBuf {
+        component.with_extension(MANIFEST_EXTENSION)
+    }
+
+    /// The manifest of `source`; the defaults when it has none.
+    pub fn of(source: &ComponentSource) -> Result<Self> {
+        let ComponentSource::Path(component) = source else {
+            return Ok(Self::default());
+        };
+        let path = Self::path_for(component);
+        match fs::read_to_string(&path) {
+            Ok(text) => {
+                let mut manifest = Self::parse(&text)
+                    .with_context(|| format!("invalid manifest {}", path.display()))?;
+                if let Some(dir) = path.parent() {
+                    let paths = manifest.icon.iter_mut().chain(manifest.panes.values_mut());
+                    for relative in paths {
+                        *relative = dir.join(&*relative);
+                    }
+                }
+                Ok(manifest)
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => {
+                Err(err).with_context(|| format!("failed to read manifest {}", path.display()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::ComponentManifest;
+
+    #[test]
+    fn parses_storage_grants_beside_the_component() {
+        let manifest = ComponentManifest::parse(
+            "id = \"com.example.editor\"\nshares-storage-with = [\"com.example.previewer\"]\n\
+             icon = \"editor.png\"\n",
+        )
+        .unwrap();
+        assert_eq!(manifest.id.as_deref(), Some("com.example.editor"));
+        assert_eq!(manifest.shares_storage_with, ["com.example.previewer"]);
+        assert_eq!(manifest.icon.as_deref(), Some(Path::new("editor.png")));
+
+        assert_eq!(ComponentManifest::parse("").unwrap(), Default::default());
+        assert!(ComponentManifest::parse("shares-with = []").is_err());
+        assert_eq!(
+            ComponentManifest::path_for(Path::new("/apps/notes.wasm")),
+            Path::new("/apps/notes.manifest.toml")
+        );
+    }
+
+    #[test]
+    fn lays_out_panes_from_the_manifest() {
+        let manifest = ComponentManifest::parse(
+            r#"
+            [layout.split]
+            axis = "vertical"
+            first = { pane = "editor" }
+            second = { pane = "console" }
+
+            [panes]
+            editor = "editor.wasm"
+            console = "tools/console.wasm"
+            "#,
+        )
+        .unwrap();
+        let names: Vec<_> = manifest
+            .pane_sources()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["editor", "console"]);
+        assert_eq!(manifest.panes["console"], Path::new("tools/console.wasm"));
+
+        for text in [
+            "[panes]\neditor = \"editor.wasm\"",
+            "layout = { pane = \"editor\" }",
+            "layout = { pane = \"editor\" }\n[panes]\neditor = \"a.wasm\"\nextra = \"b.wasm\"",
+        ] {
+            assert!(ComponentManifest::parse(text).is_err(), "accepted {text}");
+        }
+    }
+}
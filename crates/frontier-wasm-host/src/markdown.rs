@@ -0,0 +1,332 @@
+//! `draw-markdown`: CommonMark parsed on the host with pulldown-cmark and laid out with the
+//! `draw-rich-text` paragraph layout, so documentation viewers need neither a parser nor a
+//! layout engine of their own.
+//!
+//! Headings are bold and larger, strong and emphasised text bold (no italic face is
+//! bundled), inline code and code blocks monospace, and list items and block quotes
+//! indented. Links draw as their text and images as their alt text; raw HTML is left out.
+
+use std::mem;
+
+use pulldown_cmark::{Event, Parser, Tag};
+
+use crate::bidi::TextDirection;
+use crate::geometry::{Rect, Vec2};
+use crate::graphics::LINE_HEIGHT;
+use crate::host::{Color, DrawCommand, FontFamily, TextFlow};
+use crate::text::{Span, TextMetrics};
+
+/// Text size of headings from level 1 to 6, as multiples of the base size.
+const HEADING_SCALE: [f32; 6] = [2.0, 1.5, 1.25, 1.1, 1.0, 0.9];
+/// Space between blocks, as a multiple of the base size.
+const BLOCK_GAP: f32 = 0.75;
+/// Indent of each level of list or block quote, as a multiple of the base size.
+const INDENT: f32 = 1.5;
+/// Space around the text of a code block, as a multiple of the base size.
+const CODE_PADDING: f32 = 0.5;
+/// Opacity of code block backgrounds and rules, relative to the text colour.
+const TINT: f32 = 0.1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BlockKind {
+    Text,
+    Code,
+    Rule,
+}
+
+/// A paragraph, heading, list item, code block or rule, in the order they are drawn.
+#[derive(Debug, PartialEq)]
+struct Block {
+    kind: BlockKind,
+    /// Distance from the left of the rectangle, as a multiple of the base size.
+    indent: f32,
+    /// Text in pieces of one font and size, the size a multiple of the base size.
+    spans: Vec<(String, FontFamily, f32)>,
+}
+
+/// Collects parser events into blocks.
+#[derive(Default)]
+struct Builder {
+    blocks: Vec<Block>,
+    spans: Vec<(String, FontFamily, f32)>,
+    /// Open strong and emphasis tags.
+    bold: u32,
+    heading: Option<usize>,
+    code_block: bool,
+    /// The number of the next item of each open list, `None` in bullet lists.
+    lists: Vec<Option<u64>>,
+    quotes: u32,
+}
+
+impl Builder {
+    fn push(&mut self, text: &str, code: bool) {
+        let scale = self
+            .heading
+            .map_or(1.0, |level| HEADING_SCALE[level.clamp(1, 6) - 1]);
+        let font = if code || self.code_block {
+            FontFamily::Monospace
+        } else if self.heading.is_some() || self.bold > 0 {
+            FontFamily::SansBold
+        } else {
+            FontFamily::Sans
+        };
+        match self.spans.last_mut() {
+            Some(last) if last.1 == font && last.2 == scale => last.0.push_str(text),
+            _ => self.spans.push((text.to_string(), font, scale)),
+        }
+    }
+
+    /// Finish the text collected so far as a block, if there is any.
+    fn end_block(&mut self, kind: BlockKind) {
+        if self.spans.is_empty() {
+            return;
+        }
+        self.blocks.push(Block {
+            kind,
+            indent: self.indent(),
+            spans: mem::take(&mut self.spans),
+        });
+    }
+
+    fn indent(&self) -> f32 {
+        (self.lists.len() + self.quotes as usize) as f32 * INDENT
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => match tag {
+                Tag::Heading(level, ..) => {
+                    self.end_block(BlockKind::Text);
+                    self.heading = Some(level as usize);
+                }
+                Tag::BlockQuote => {
+                    self.end_block(BlockKind::Text);
+                    self.quotes += 1;
+                }
+                Tag::CodeBlock(_) => {
+                    self.end_block(BlockKind::Text);
+                    self.code_block = true;
+                }
+                Tag::List(first) => {
+                    self.end_block(BlockKind::Text);
+                    self.lists.push(first);
+                }
+                Tag::Item => {
+                    self.end_block(BlockKind::Text);
+                    let marker = match self.lists.last_mut() {
+                        Some(Some(number)) => {
+                            *number += 1;
+                            format!("{}. ", *number - 1)
+                        }
+                        Some(None) => "• ".to_string(),
+                        None => String::new(),
+                    };
+                    self.push(&marker, false);
+                }
+                Tag::Emphasis | Tag::Strong => self.bold += 1,
+                _ => {}
+            },
+            Event::End(tag) => match tag {
+                Tag::Paragraph | Tag::Item => self.end_block(BlockKind::Text),
+                Tag::Heading(..) => {
+                    self.end_block(BlockKind::Text);
+                    self.heading = None;
+                }
+                Tag::BlockQuote => {
+                    self.end_block(BlockKind::Text);
+                    self.quotes = self.quotes.saturating_sub(1);
+                }
+                Tag::CodeBlock(_) => {
+                    if let Some(last) = self.spans.last_mut() {
+                        last.0.truncate(last.0.trim_end_matches('\n').len());
+                    }
+                    self.end_block(BlockKind::Code);
+                    self.code_block = false;
+                }
+                Tag::List(_) => {
+                    self.end_block(BlockKind::Text);
+                    self.lists.pop();
+                }
+                Tag::Emphasis | Tag::Strong => self.bold = self.bold.saturating_sub(1),
+                _ => {}
+            },
+            Event::Text(text) => self.push(&text, false),
+            Event::Code(code) => self.push(&code, true),
+            Event::SoftBreak => self.push(" ", false),
+            Event::HardBreak => self.push("\n", false),
+            Event::TaskListMarker(checked) => {
+                self.push(if checked { "[x] " } else { "[ ] " }, false)
+            }
+            Event::Rule => {
+                self.end_block(BlockKind::Text);
+                self.blocks.push(Block {
+                    kind: BlockKind::Rule,
+                    indent: self.indent(),
+                    spans: Vec::new(),
+                });
+            }
+            Event::Html(_) | Event::FootnoteReference(_) => {}
+        }
+    }
+}
+
+fn parse(source: &str) -> Vec<Block> {
+    let mut builder = Builder::default();
+    for event in Parser::new(source) {
+        builder.event(event);
+    }
+    builder.end_block(BlockKind::Text);
+    builder.blocks
+}
+
+/// Draw commands for `source` laid out top to bottom in `rect`, body text at `base_size`
+/// and everything in `color`. Paragraphs wrap to the width of the rectangle and take the
+/// direction of their first strong letter. Lines whose baseline falls below the rectangle
+/// are left out.
+pub fn layout(
+    source: &str,
+    rect: Rect,
+    base_size: f32,
+    color: Color,
+    metrics: &mut TextMetrics,
+) -> Vec<DrawCommand> {
+    let tint = Color {
+        a: color.a * TINT,
+        ..color
+    };
+    let bottom = rect.y + rect.height;
+    let mut commands = Vec::new();
+    let mut top = rect.y;
+    for (number, block) in parse(source).into_iter().enumerate() {
+        if number > 0 {
+            top += base_size * BLOCK_GAP;
+        }
+        if top >= bottom {
+            break;
+        }
+        let left = rect.x + block.indent * base_size;
+        let width = (rect.x + rect.width - left).max(0.0);
+        if block.kind == BlockKind::Rule {
+            commands.push(DrawCommand::FillRect {
+                origin: Vec2::new(left, top),
+                size: Vec2::new(width, 1.0),
+                color: tint,
+            });
+            top += 1.0;
+            continue;
+        }
+        let padding = match block.kind {
+            BlockKind::Code => base_size * CODE_PADDING,
+            _ => 0.0,
+        };
+        let spans: Vec<Span> = block
+            .spans
+            .iter()
+            .map(|(text, font, scale)| Span {
+                text,
+                size: base_size * scale,
+                font: *font,
+            })
+            .collect();
+        let size = spans.iter().map(|span| span.size).fold(0.0, f32::max);
+        let text: String = block.spans.iter().map(|span| span.0.as_str()).collect();
+        let runs = metrics.layout_paragraph(
+            &spans,
+            (width - 2.0 * padding).max(0.0),
+            TextDirection::detect(&text),
+        );
+        let last_baseline = runs.iter().map(|run| run.offset.y).fold(0.0, f32::max);
+        let height = last_baseline + size * LINE_HEIGHT + 2.0 * padding;
+        if block.kind == BlockKind::Code {
+            commands.push(DrawCommand::FillRect {
+                origin: Vec2::new(left, top),
+                size: Vec2::new(width, height.min(bottom - top)),
+                color: tint,
+            });
+        }
+        let origin = Vec2::new(left + padding, top + padding + size);
+        for run in runs {
+            let baseline = origin + run.offset;
+            if baseline.y > bottom {
+                break;
+            }
+            let span = &spans[run.span];
+            commands.push(DrawCommand::DrawText {
+                text: run.text,
+                origin: baseline,
+                size: span.size,
+                color,
+                font: span.font,
+                flow: TextFlow::horizontal(run.direction),
+            });
+        }
+        top += height;
+    }
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{layout, parse, Block, BlockKind};
+    use crate::geometry::Rect;
+    use crate::host::{Color, DrawCommand, FontFamily};
+    use crate::text::TextMetrics;
+
+    #[test]
+    fn parses_headings_emphasis_lists_and_code() {
+        let source = "# Title\n\nSome *very* `fast`\ncode.\n\n1. one\n   - two\n\n```\nfn main() {}\n```\n\n---\n";
+        let block = |kind, indent, spans: &[(&str, FontFamily, f32)]| Block {
+            kind,
+            indent,
+            spans: spans
+                .iter()
+                .map(|&(text, font, scale)| (text.to_string(), font, scale))
+                .collect(),
+        };
+        use FontFamily::{Monospace, Sans, SansBold};
+        assert_eq!(
+            parse(source),
+            [
+                block(BlockKind::Text, 0.0, &[("Title", SansBold, 2.0)]),
+                block(
+                    BlockKind::Text,
+                    0.0,
+                    &[
+                        ("Some ", Sans, 1.0),
+                        ("very", SansBold, 1.0),
+                        (" ", Sans, 1.0),
+                        ("fast", Monospace, 1.0),
+                        (" code.", Sans, 1.0),
+                    ]
+                ),
+                block(BlockKind::Text, 1.5, &[("1. one", Sans, 1.0)]),
+                block(BlockKind::Text, 3.0, &[("• two", Sans, 1.0)]),
+                block(BlockKind::Code, 0.0, &[("fn main() {}", Monospace, 1.0)]),
+                block(BlockKind::Rule, 0.0, &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_the_bottom_of_the_rectangle() {
+        let mut metrics = TextMetrics::default();
+        let source = "first\n\nsecond\n\nthird";
+        let mut texts = |height| {
+            layout(
+                source,
+                Rect::new(0.0, 0.0, 200.0, height),
+                10.0,
+                Color::default(),
+                &mut metrics,
+            )
+            .into_iter()
+            .filter_map(|command| match command {
+                DrawCommand::DrawText { text, .. } => Some(text),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(texts(1000.0), ["first", "second", "third"]);
+        assert_eq!(texts(35.0), ["first", "second"]);
+    }
+}
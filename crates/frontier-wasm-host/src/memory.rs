@@ -0,0 +1,167 @@
+//! Memory pressure, so long-running sessions do not balloon: the host watches what the
+//! guest's assets and its own caches hold against `--memory-budget`, and the system's free
+//! memory where the platform reports it (`/proc/meminfo` on Linux). When pressure rises
+//! the host empties its caches, least recently used first, and the guest hears
+//! `trim-memory` so it can free assets it can recreate. Under critical pressure the host
+//! then drops the guest's least recently drawn images, pictures and shaders itself and
+//! names them in `assets-evicted`.
+
+use std::time::Duration;
+
+/// Whether this build can read system memory pressure; budgets work everywhere.
+pub const SUPPORTED: bool = cfg!(target_os = "linux");
+
+/// How often the window checks memory.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Share of system memory left below which pressure is moderate, and critical.
+const MODERATE_AVAILABLE: f64 = 0.15;
+const CRITICAL_AVAILABLE: f64 = 0.05;
+/// Share of the budget in use above which pressure is moderate; over budget is critical.
+const MODERATE_BUDGET: f64 = 0.8;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemoryPressure {
+    #[default]
+    Normal,
+    /// Free what is cheap to recreate.
+    Moderate,
+    /// Free everything that is not needed for the next frame.
+    Critical,
+}
+
+impl MemoryPressure {
+    /// System memory pressure, or `None` when the platform does not report it.
+    pub fn read() -> Option<Self> {
+        if SUPPORTED {
+            Self::from_meminfo(&std::fs::read_to_string("/proc/meminfo").ok()?)
+        } else {
+            None
+        }
+    }
+
+    /// Judge `MemAvailable` against `MemTotal` from `/proc/meminfo` text.
+    fn from_meminfo(meminfo: &str) -> Option<Self> {
+        let field = |name: &str| {
+            meminfo.lines().find_map(|line| {
+                let value = line.strip_prefix(name)?.strip_prefix(':')?;
+                value
+                    .trim()
+                    .trim_end_matches("kB")
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+            })
+        };
+        let total = field("MemTotal").filter(|&total| total > 0)?;
+        let available = field("MemAvailable")? as f64 / total as f64;
+        Some(if available < CRITICAL_AVAILABLE {
+            Self::Critical
+        } else if available < MODERATE_AVAILABLE {
+            Self::Moderate
+        } else {
+            Self::Normal
+        })
+    }
+}
+
+/// The pressure last acted on, so the host trims and tells the guest once per rise rather
+/// than on every check.
+#[derive(Debug, Default)]
+pub struct MemoryMonitor {
+    /// `--memory-budget`, in bytes.
+    budget: Option<usize>,
+    level: MemoryPressure,
+}
+
+impl MemoryMonitor {
+    pub fn new(budget: Option<usize>) -> Self {
+        Self {
+            budget,
+            level: MemoryPressure::Normal,
+        }
+    }
+
+    /// How many bytes of guest assets may stay once critical pressure evicts them: enough
+    /// to fall back under moderate pressure within the budget, or none beyond what was just
+    /// drawn when only the system is short.
+    pub fn eviction_target(&self, cache_bytes: usize) -> usize {
+        self.budget.map_or(0, |budget| {
+            ((budget as f64 * MODERATE_BUDGET) as usize).saturating_sub(cache_bytes)
+        })
+    }
+
+    /// Whether there is anything to watch in this build and session.
+    pub fn enabled(&self) -> bool {
+        SUPPORTED || self.budget.is_some()
+    }
+
+    /// Combine the system's pressure with `used` bytes against the budget. Returns the new
+    /// level when it rose, which is when the host should trim.
+    pub fn update(
+        &mut self,
+        system: Option<MemoryPressure>,
+        used: usize,
+    ) -> Option<MemoryPressure> {
+        let budget = match self.budget {
+            Some(budget) if used > budget => MemoryPressure::Critical,
+            Some(budget) if used as f64 > budget as f64 * MODERATE_BUDGET => {
+                MemoryPressure::Moderate
+            }
+            _ => MemoryPressure::Normal,
+        };
+        let level = budget.max(system.unwrap_or_default());
+        let rose = level > self.level;
+        self.level = level;
+        rose.then_some(level)
+    }
+}
+
+/// Guest assets the host dropped under critical memory pressure, by kind.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct EvictedAssets {
+    pub images: Vec<u32>,
+    pub pictures: Vec<u32>,
+    pub shaders: Vec<u32>,
+}
+
+impl EvictedAssets {
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty() && self.pictures.is_empty() && self.shaders.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.images.len() + self.pictures.len() + self.shaders.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MemoryMonitor, MemoryPressure};
+
+    #[test]
+    fn reads_pressure_from_meminfo() {
+        let meminfo = |available| {
+            format!("MemTotal:       1000000 kB\nMemFree:         1000 kB\nMemAvailable:   {available} kB\n")
+        };
+        let read = |available| MemoryPressure::from_meminfo(&meminfo(available));
+        assert_eq!(read(500_000), Some(MemoryPressure::Normal));
+        assert_eq!(read(100_000), Some(MemoryPressure::Moderate));
+        assert_eq!(read(10_000), Some(MemoryPressure::Critical));
+        assert_eq!(MemoryPressure::from_meminfo("MemTotal: 10 kB\n"), None);
+    }
+
+    #[test]
+    fn reports_each_rise_once() {
+        let mut monitor = MemoryMonitor::new(Some(1000));
+        assert_eq!(monitor.update(None, 500), None);
+        assert_eq!(monitor.update(None, 900), Some(MemoryPressure::Moderate));
+        assert_eq!(monitor.update(None, 900), None);
+        assert_eq!(
+            monitor.update(Some(MemoryPressure::Critical), 900),
+            Some(MemoryPressure::Critical)
+        );
+        assert_eq!(monitor.update(None, 100), None);
+        assert_eq!(monitor.update(None, 1001), Some(MemoryPressure::Critical));
+    }
+}
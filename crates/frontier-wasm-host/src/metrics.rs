@@ -0,0 +1,411 @@
+//! Prometheus metrics for `--metrics ADDR`: frame timing, guest call durations, draw
+//! command counts, host import usage, GPU memory and component restarts, served as text
+//! on `GET /metrics` so long-running deployments can be scraped like any other service.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use crate::host::Phase;
+use crate::stats::FrameStats;
+
+/// Upper bounds of the duration histogram buckets, in seconds; frame-sized around 16 ms.
+const DURATION_BUCKETS: [f64; 12] = [
+    0.0005, 0.001, 0.002, 0.004, 0.008, 0.016, 0.033, 0.066, 0.133, 0.25, 0.5, 1.0,
+];
+
+/// Longest request head read from a scraper before the connection is dropped.
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+
+/// Shared handle the window, runtime and HTTP thread all record into or read from.
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    registry: Arc<Mutex<Registry>>,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    frame_interval: Histogram,
+    render: Histogram,
+    /// Guest export calls by phase (`init`, `event`, `frame`, ...).
+    guest_calls: BTreeMap<&'static str, Histogram>,
+    draw_commands_total: u64,
+    draw_commands_last_frame: u64,
+    draw_commands_culled_total: u64,
+    /// Calls and seconds spent per host import.
+    host_calls: BTreeMap<&'static str, (u64, f64)>,
+    text_bytes_total: u64,
+    missed_refreshes_total: u64,
+    /// Absent on backends without an allocator report.
+    gpu_memory_bytes: Option<u64>,
+    restarts: u64,
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Observations per bucket, not yet cumulative.
+    buckets: [u64; DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        if let Some(bucket) = DURATION_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[bucket] += 1;
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+
+    /// Append the `_bucket`, `_sum` and `_count` series; `labels` is empty or `key="value",`.
+    fn write(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative = 0;
+        for (bound, count) in DURATION_BUCKETS.iter().zip(self.buckets) {
+            cumulative += count;
+            let _ = writeln!(out, "{name}_bucket{{{labels}le=\"{bound}\"}} {cumulative}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{{labels}le=\"+Inf\"}} {}", self.count);
+        let labels = labels.trim_end_matches(',');
+        let braces = |labels: &str| {
+            if labels.is_empty() {
+                String::new()
+            } else {
+                format!("{{{labels}}}")
+            }
+        };
+        let _ = writeln!(out, "{name}_sum{} {}", braces(labels), self.sum);
+        let _ = writeln!(out, "{name}_count{} {}", braces(labels), self.count);
+    }
+}
+
+impl Metrics {
+    pub fn observe_guest_call(&self, phase: Phase, duration: Duration) {
+        let mut registry = self.registry.lock().unwrap();
+        registry
+            .guest_calls
+            .entry(phase.name())
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Record a rendered guest frame. The guest's `frame` call itself is timed by
+    /// [`Metrics::observe_guest_call`].
+    pub fn record_frame(&self, stats: &FrameStats, gpu_memory_bytes: Option<u64>) {
+        let mut registry = self.registry.lock().unwrap();
+        registry
+            .frame_interval
+            .observe(f64::from(stats.dt_ms) / 1000.0);
+        registry.render.observe(f64::from(stats.render_ms) / 1000.0);
+        registry.draw_commands_total += stats.commands as u64;
+        registry.draw_commands_last_frame = stats.commands as u64;
+        registry.draw_commands_culled_total += stats.culled as u64;
+        for call in &stats.host_calls {
+            let entry = registry.host_calls.entry(call.name).or_default();
+            entry.0 += u64::from(call.calls);
+            entry.1 += f64::from(call.total_ms) / 1000.0;
+        }
+        registry.text_bytes_total += stats.text_bytes as u64;
+        registry.missed_refreshes_total += u64::from(stats.missed);
+        if gpu_memory_bytes.is_some() {
+            registry.gpu_memory_bytes = gpu_memory_bytes;
+        }
+    }
+
+    pub fn record_restart(&self) {
+        self.registry.lock().unwrap().restarts += 1;
+    }
+
+    /// Everything recorded so far in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let registry = self.registry.lock().unwrap();
+        let mut out = String::new();
+        let header = |out: &mut String, name: &str, kind: &str, help: &str| {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+        };
+
+        header(
+            &mut out,
+            "frontier_frame_interval_seconds",
+            "histogram",
+            "Time between consecutive guest frames.",
+        );
+        registry
+            .frame_interval
+            .write(&mut out, "frontier_frame_interval_seconds", "");
+        header(
+            &mut out,
+            "frontier_render_duration_seconds",
+            "histogram",
+            "CPU time spent encoding and submitting a frame to the GPU.",
+        );
+        registry
+            .render
+            .write(&mut out, "frontier_render_duration_seconds", "");
+        header(
+            &mut out,
+            "frontier_guest_call_duration_seconds",
+            "histogram",
+            "Duration of calls into the guest component, by phase.",
+        );
+        for (phase, histogram) in &registry.guest_calls {
+            histogram.write(
+                &mut out,
+                "frontier_guest_call_duration_seconds",
+                &format!("phase=\"{phase}\","),
+            );
+        }
+        header(
+            &mut out,
+            "frontier_draw_commands_total",
+            "counter",
+            "Draw commands submitted by the guest.",
+        );
+        let _ = writeln!(
+            out,
+            "frontier_draw_commands_total {}",
+            registry.draw_commands_total
+        );
+        header(
+            &mut out,
+            "frontier_draw_commands",
+            "gauge",
+            "Draw commands in the most recent frame.",
+        );
+        let _ = writeln!(
+            out,
+            "frontier_draw_commands {}",
+            registry.draw_commands_last_frame
+        );
+        header(
+            &mut out,
+            "frontier_draw_commands_culled_total",
+            "counter",
+            "Draw commands skipped for lying entirely outside the window.",
+        );
+        let _ = writeln!(
+            out,
+            "frontier_draw_commands_culled_total {}",
+            registry.draw_commands_culled_total
+        );
+        header(
+            &mut out,
+            "frontier_host_calls_total",
+            "counter",
+            "Calls the guest made to each host import.",
+        );
+        for (function, (calls, _)) in &registry.host_calls {
+            let _ = writeln!(
+                out,
+                "frontier_host_calls_total{{function=\"{function}\"}} {calls}"
+            );
+        }
+        header(
+            &mut out,
+            "frontier_host_call_seconds_total",
+            "counter",
+            "Time spent in each host import.",
+        );
+        for (function, (_, seconds)) in &registry.host_calls {
+            let _ = writeln!(
+                out,
+                "frontier_host_call_seconds_total{{function=\"{function}\"}} {seconds}"
+            );
+        }
+        header(
+            &mut out,
+            "frontier_text_bytes_total",
+            "counter",
+            "Bytes of text the guest passed to draw-text.",
+        );
+        let _ = writeln!(
+            out,
+            "frontier_text_bytes_total {}",
+            registry.text_bytes_total
+        );
+        header(
+            &mut out,
+            "frontier_missed_refreshes_total",
+            "counter",
+            "Display refreshes that passed without a new frame while the guest was animating.",
+        );
+        let _ = writeln!(
+            out,
+            "frontier_missed_refreshes_total {}",
+            registry.missed_refreshes_total
+        );
+        if let Some(bytes) = registry.gpu_memory_bytes {
+            header(
+                &mut out,
+                "frontier_gpu_memory_bytes",
+                "gauge",
+                "GPU memory allocated by the host's device.",
+            );
+            let _ = writeln!(out, "frontier_gpu_memory_bytes {bytes}");
+        }
+        header(
+            &mut out,
+            "frontier_restarts_total",
+            "counter",
+            "Times the component was restarted.",
+        );
+        let _ = writeln!(out, "frontier_restarts_total {}", registry.restarts);
+        out
+    }
+}
+
+/// Serve `metrics` over HTTP on `addr` from a background thread.
+pub fn spawn(addr: &str, metrics: Metrics) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .with_context(|| format!("failed to bind metrics address {addr}"))?;
+    let local = listener.local_addr()?;
+    if !local.ip().is_loopback() {
+        tracing::warn!(%local, "metrics endpoint is reachable from other machines");
+    }
+    tracing::info!(%local, "metrics endpoint listening");
+    thread::Builder::new()
+        .name("frontier-metrics".into())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                let result = stream.and_then(|stream| respond(stream, &metrics));
+                if let Err(err) = result {
+                    tracing::debug!(%err, "metrics request failed");
+                }
+            }
+        })
+        .context("failed to start metrics endpoint thread")?;
+    Ok(())
+}
+
+/// Answer one request, then close the connection. Scrapes are rare enough that handling
+/// them one at a time is fine.
+fn respond(stream: TcpStream, metrics: &Metrics) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain the headers so the client sees an orderly close.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", metrics.render()),
+        (Some("GET"), Some(_)) => ("404 Not Found", "not found; try /metrics\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "only GET is supported\n".to_string(),
+        ),
+    };
+    let mut stream = reader.into_inner().into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::time::Duration;
+
+    use super::{spawn, Metrics};
+    use crate::host::Phase;
+    use crate::stats::{FrameStats, HostCallStats};
+
+    #[test]
+    fn renders_prometheus_text() {
+        let metrics = Metrics::default();
+        metrics.observe_guest_call(Phase::Frame, Duration::from_micros(1500));
+        metrics.observe_guest_call(Phase::Frame, Duration::from_secs(3));
+        metrics.observe_guest_call(Phase::Event, Duration::from_micros(100));
+        metrics.record_frame(
+            &FrameStats {
+                dt_ms: 16.0,
+                render_ms: 2.0,
+                commands: 12,
+                ..FrameStats::default()
+            },
+            None,
+        );
+        metrics.record_frame(
+            &FrameStats {
+                commands: 3,
+                culled: 5,
+                host_calls: vec![HostCallStats {
+                    name: "fill-rect",
+                    calls: 3,
+                    total_ms: 0.5,
+                }],
+                text_bytes: 40,
+                missed: 2,
+                ..FrameStats::default()
+            },
+            Some(4096),
+        );
+        metrics.record_restart();
+
+        let text = metrics.render();
+        for line in [
+            "# TYPE frontier_guest_call_duration_seconds histogram",
+            "frontier_guest_call_duration_seconds_bucket{phase=\"frame\",le=\"0.001\"} 0",
+            "frontier_guest_call_duration_seconds_bucket{phase=\"frame\",le=\"0.002\"} 1",
+            "frontier_guest_call_duration_seconds_bucket{phase=\"frame\",le=\"1\"} 1",
+            "frontier_guest_call_duration_seconds_bucket{phase=\"frame\",le=\"+Inf\"} 2",
+            "frontier_guest_call_duration_seconds_count{phase=\"frame\"} 2",
+            "frontier_guest_call_duration_seconds_count{phase=\"event\"} 1",
+            "frontier_frame_interval_seconds_bucket{le=\"0.016\"} 2",
+            "frontier_frame_interval_seconds_count 2",
+            "frontier_render_duration_seconds_sum 0.002",
+            "frontier_draw_commands_total 15",
+            "frontier_draw_commands 3",
+            "frontier_draw_commands_culled_total 5",
+            "frontier_host_calls_total{function=\"fill-rect\"} 3",
+            "frontier_host_call_seconds_total{function=\"fill-rect\"} 0.0005",
+            "frontier_text_bytes_total 40",
+            "frontier_missed_refreshes_total 2",
+            "frontier_gpu_memory_bytes 4096",
+            "frontier_restarts_total 1",
+        ] {
+            assert!(
+                text.lines().any(|l| l == line),
+                "missing `{line}` in\n{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn serves_metrics_over_http() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let metrics = Metrics::default();
+        metrics.record_restart();
+        spawn(&addr.to_string(), metrics).unwrap();
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: test\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = get("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(response.contains("\r\n\r\n# HELP"), "{response}");
+        assert!(response.contains("frontier_restarts_total 1\n"));
+        assert!(get("/").starts_with("HTTP/1.1 404"));
+    }
+}
@@ -1,26 +1,67 @@
 use std::fmt;
 
-#[derive(Clone, Copy, Debug, Default)]
+use crate::geometry::Vec2;
+
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct LogicalSize {
     pub width: f32,
     pub height: f32,
     pub scale_factor: f32,
 }
 
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Modifiers {
     pub shift: bool,
     pub ctrl: bool,
     pub alt: bool,
     pub meta: bool,
+    pub keys: ModifierKeys,
+    pub locks: LockKeys,
 }
 
+/// Which side's modifier keys are held.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ModifierKeys {
+    pub left_shift: bool,
+    pub right_shift: bool,
+    pub left_ctrl: bool,
+    pub right_ctrl: bool,
+    pub left_alt: bool,
+    pub right_alt: bool,
+    pub left_meta: bool,
+    pub right_meta: bool,
+}
+
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LockKeys {
+    pub caps_lock: bool,
+    /// `None` until a numeric keypad key shows which way NumLock is set.
+    pub num_lock: Option<bool>,
+    pub scroll_lock: bool,
+}
+
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyLocation {
+    #[default]
+    Standard,
+    Left,
+    Right,
+    Numpad,
+}
+
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Clone, Copy, Debug, Default)]
 pub struct PointerButtons {
     pub primary: bool,
     pub secondary: bool,
 }
 
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(dead_code)]
 pub enum PointerKind {
@@ -39,19 +80,52 @@ impl fmt::Display for PointerKind {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointerSample {
+    pub position: Vec2,
+    pub time_ms: f64,
+}
+
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Debug)]
 pub struct PointerEvent {
     pub kind: PointerKind,
-    pub position: [f32; 2],
+    pub position: Vec2,
     pub buttons: PointerButtons,
     pub modifiers: Modifiers,
     pub pointer_id: u64,
+    /// Raw moves folded into this event; 1 when nothing was coalesced.
+    pub coalesced_count: u32,
+    /// Latest raw samples behind a coalesced move, oldest first; empty otherwise.
+    pub history: Vec<PointerSample>,
+}
+
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GesturePhase {
+    Started,
+    Changed,
+    Ended,
+    Cancelled,
+}
+
+/// A pinch or rotation step; `delta` is a scale change for pinches and degrees for rotations.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug)]
+pub struct GestureEvent {
+    pub phase: GesturePhase,
+    pub position: Vec2,
+    pub modifiers: Modifiers,
+    pub delta: f32,
 }
 
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Clone, Debug, Default)]
 pub struct KeyEvent {
     pub key: String,
     pub code: String,
     pub modifiers: Modifiers,
     pub is_repeat: bool,
+    pub location: KeyLocation,
 }
@@ -0,0 +1,123 @@
+//! `navigate`: a guest replacing itself with another component in the same window. Only
+//! allowed with `--allow-navigation`; targets are local paths or `file://` URLs.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Result};
+
+/// Resolve a `navigate` target to an existing component file. Relative paths are taken
+/// from `base`, the directory of the component that asked, when there is one.
+pub fn resolve(target: &str, base: Option<&Path>) -> Result<PathBuf> {
+    let path = parse(target)?;
+    let path = match base {
+        Some(base) if path.is_relative() => base.join(path),
+        _ => path,
+    };
+    if !path.is_file() {
+        bail!("no component at {}", path.display());
+    }
+    Ok(path)
+}
+
+/// Resolve a `spawn-worker` target, which must be a component file inside `base`. A
+/// target outside it fails exactly like a missing one, so a guest cannot probe for files
+/// elsewhere.
+pub fn resolve_within(target: &str, base: Option<&Path>) -> Result<PathBuf> {
+    let target = target.trim();
+    let path = parse(target)?;
+    let Some(base) = base else {
+        bail!("workers can only be loaded by path from a component file");
+    };
+    let path = base.join(path);
+    base.canonicalize()
+        .ok()
+        .zip(path.canonicalize().ok())
+        .filter(|(base, path)| path.starts_with(base) && path.is_file())
+        .map(|(_, path)| path)
+        .ok_or_else(|| anyhow!("no component at '{target}' in this component's directory"))
+}
+
+/// A local path or `file://` URL, not yet checked against the file system.
+fn parse(target: &str) -> Result<PathBuf> {
+    let target = target.trim();
+    if target.is_empty() {
+        bail!("component target is empty");
+    }
+    Ok(match target.split_once("://") {
+        Some((scheme, rest)) if scheme.eq_ignore_ascii_case("file") => {
+            // `file:///abs/path` or `file://localhost/abs/path`.
+            let path = rest.strip_prefix("localhost").unwrap_or(rest);
+            if !path.starts_with('/') {
+                bail!("file URL '{target}' must name an absolute path");
+            }
+            PathBuf::from(percent_decode(path)?)
+        }
+        Some((scheme, _)) => {
+            bail!("{scheme}:// targets are not supported; use a local path instead")
+        }
+        None => PathBuf::from(target),
+    })
+}
+
+pub(crate) fn percent_decode(text: &str) -> Result<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail
+                .get(..2)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            let Some(decoded) = hex else {
+                bail!("invalid percent escape in '{text}'");
+            };
+            bytes.push(decoded);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    Ok(String::from_utf8(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{resolve, resolve_within};
+
+    #[test]
+    fn resolves_paths_and_file_urls() {
+        let dir = std::env::temp_dir().join(format!("frontier-nav test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("next app.wasm");
+        fs::write(&file, b"\0asm").unwrap();
+
+        assert_eq!(resolve("next app.wasm", Some(&dir)).unwrap(), file);
+        assert_eq!(resolve(file.to_str().unwrap(), None).unwrap(), file);
+        let url = format!("file://{}", file.display()).replace(' ', "%20");
+        assert_eq!(resolve(&url, None).unwrap(), file);
+
+        assert!(resolve("missing.wasm", Some(&dir)).is_err());
+        assert!(resolve("https://example.com/app.wasm", None).is_err());
+        assert!(resolve("file://relative/app.wasm", None).is_err());
+        assert!(resolve("file:///bad%zz", None).is_err());
+        assert!(resolve("  ", None).is_err());
+
+        let plugins = dir.join("plugins");
+        fs::create_dir_all(&plugins).unwrap();
+        fs::write(plugins.join("a.wasm"), b"\0asm").unwrap();
+        let inside = resolve_within("a.wasm", Some(&plugins)).unwrap();
+        assert_eq!(inside, plugins.join("a.wasm").canonicalize().unwrap());
+        let outside = resolve_within("../next app.wasm", Some(&plugins)).unwrap_err();
+        let absolute = resolve_within(file.to_str().unwrap(), Some(&plugins)).unwrap_err();
+        let missing = resolve_within("b.wasm", Some(&plugins)).unwrap_err();
+        assert!(format!("{outside:#}").starts_with("no component at"));
+        assert!(format!("{absolute:#}").starts_with("no component at"));
+        assert!(format!("{missing:#}").starts_with("no component at"));
+        assert!(resolve_within("a.wasm", None).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,120 @@
+//! Frame pacing: how many display refreshes went by without a new frame while the guest
+//! was animating, and the 1% low frame time over recent frames, so jank can be measured
+//! rather than eyeballed.
+//!
+//! wgpu does not report when a frame actually reached the screen, so pacing is measured
+//! between `frame` calls, which the window's redraw callbacks keep in step with
+//! presentation. Only frames the previous frame asked for count: a guest that sits idle
+//! until the next input is not janky.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Frames the 1% low is taken over; about ten seconds at 60 Hz.
+pub const WINDOW: usize = 600;
+/// Refresh period assumed when the monitor does not report its rate.
+pub const DEFAULT_PERIOD: Duration = Duration::from_micros(16_667);
+
+#[derive(Debug, Default)]
+pub struct FramePacing {
+    /// Recent intervals between chained frames, in milliseconds, oldest first.
+    intervals: VecDeque<f32>,
+    missed_total: u64,
+    /// When the last frame that asked for another started.
+    chained_from: Option<Instant>,
+}
+
+impl FramePacing {
+    /// A frame is starting at `now`, aiming for one every `period`. Returns the refreshes
+    /// missed since the previous frame, which is zero unless that frame asked for this
+    /// one.
+    pub fn frame_started(&mut self, now: Instant, period: Duration) -> u32 {
+        let Some(previous) = self.chained_from.take() else {
+            return 0;
+        };
+        let interval = now.saturating_duration_since(previous);
+        if self.intervals.len() == WINDOW {
+            self.intervals.pop_front();
+        }
+        self.intervals
+            .push_back(interval.as_micros() as f32 / 1000.0);
+        // Rounding gives half a period of slack for timer and compositor jitter.
+        let refreshes = (interval.as_secs_f64() / period.as_secs_f64().max(1e-6)).round();
+        let missed = (refreshes as u32).saturating_sub(1);
+        self.missed_total += u64::from(missed);
+        missed
+    }
+
+    /// The frame that started at `started` asked for the next one.
+    pub fn frame_chained(&mut self, started: Instant) {
+        self.chained_from = Some(started);
+    }
+
+    /// Frames stopped for a reason that is not jank: a pause, an overlay, a reload or a
+    /// minimized window. The next frame starts a new chain.
+    pub fn interrupt(&mut self) {
+        self.chained_from = None;
+    }
+
+    /// The frame time only 1% of recent chained frames exceeded.
+    pub fn one_percent_low_ms(&self) -> Option<f32> {
+        if self.intervals.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<f32> = self.intervals.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        let index = (sorted.len() * 99).div_ceil(100) - 1;
+        Some(sorted[index])
+    }
+
+    pub fn missed_total(&self) -> u64 {
+        self.missed_total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{FramePacing, DEFAULT_PERIOD};
+
+    #[test]
+    fn counts_missed_refreshes_only_between_chained_frames() {
+        let mut pacing = FramePacing::default();
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert_eq!(pacing.frame_started(at(0), DEFAULT_PERIOD), 0);
+        pacing.frame_chained(at(0));
+        assert_eq!(pacing.frame_started(at(17), DEFAULT_PERIOD), 0);
+        pacing.frame_chained(at(17));
+        assert_eq!(pacing.frame_started(at(67), DEFAULT_PERIOD), 2);
+
+        // The guest stopped animating, then paused mid-chain: neither gap is jank.
+        assert_eq!(pacing.frame_started(at(1000), DEFAULT_PERIOD), 0);
+        pacing.frame_chained(at(1000));
+        pacing.interrupt();
+        assert_eq!(pacing.frame_started(at(2000), DEFAULT_PERIOD), 0);
+
+        assert_eq!(pacing.missed_total(), 2);
+        assert_eq!(pacing.one_percent_low_ms(), Some(50.0));
+    }
+
+    #[test]
+    fn one_percent_low_ignores_the_rarest_spikes() {
+        let mut pacing = FramePacing::default();
+        let start = Instant::now();
+        let mut now = start;
+        for frame in 0..200 {
+            pacing.frame_chained(now);
+            let interval = match frame {
+                0 => 100,
+                1 | 2 => 40,
+                _ => 16,
+            };
+            now += Duration::from_millis(interval);
+            pacing.frame_started(now, DEFAULT_PERIOD);
+        }
+        assert_eq!(pacing.one_percent_low_ms(), Some(40.0));
+    }
+}
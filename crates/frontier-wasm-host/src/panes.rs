@@ -0,0 +1,665 @@
+//! Several components in one window, placed by a manifest's [`Layout`]. Each pane runs
+//! its own component instance and draws at its own logical size; the host clips and
+//! offsets every pane's frame into its rect, then draws the dividers and tab bars on top.
+//!
+//! Input goes to the focused pane, in coordinates relative to it. Pressing another pane
+//! focuses it first, pressing a tab shows and focuses that tab, and pressing a divider
+//! drags it until the button is released. The app holds the focused pane's runtime as
+//! its own, so hotkeys, the error overlay and snapshots act on that pane; the others are
+//! parked here and keep drawing every frame.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+
+use crate::geometry::{Rect, Vec2};
+use crate::host::{Color, DrawCommand, FontFamily, FrameOutput, TextFlow};
+use crate::launch::LaunchConfig;
+use crate::layout::{Arrangement, Divider, Layout};
+use crate::manifest::ComponentManifest;
+use crate::model::LogicalSize;
+use crate::runtime::{CallResult, ComponentRuntime, ComponentSource};
+
+const DIVIDER_COLOR: Color = Color {
+    r: 0.24,
+    g: 0.24,
+    b: 0.27,
+    a: 1.0,
+};
+const TAB_BAR_COLOR: Color = Color {
+    r: 0.13,
+    g: 0.13,
+    b: 0.15,
+    a: 1.0,
+};
+const ACTIVE_TAB_COLOR: Color = Color {
+    r: 0.22,
+    g: 0.22,
+    b: 0.26,
+    a: 1.0,
+};
+const TAB_TEXT_COLOR: Color = Color {
+    r: 0.9,
+    g: 0.9,
+    b: 0.92,
+    a: 1.0,
+};
+const ERROR_TEXT_COLOR: Color = Color {
+    r: 0.95,
+    g: 0.45,
+    b: 0.45,
+    a: 1.0,
+};
+const TAB_TEXT_SIZE: f32 = 13.0;
+const TAB_TEXT_INSET: f32 = 10.0;
+
+/// Start `component`, or every pane its manifest lays out. Returns the runtime the app
+/// holds, plus the panes when there is a layout.
+pub fn load_component(
+    component: ComponentSource,
+    launch: LaunchConfig,
+) -> Result<(ComponentRuntime, Option<Panes>)> {
+    let manifest = ComponentManifest::of(&component)?;
+    let sources = manifest.pane_sources();
+    match manifest.layout {
+        Some(layout) => {
+            let (panes, focused) = Panes::load(layout, sources, &launch)?;
+            Ok((focused, Some(panes)))
+        }
+        None => Ok((ComponentRuntime::with_launch(component, launch)?, None)),
+    }
+}
+
+/// A pane whose component is not focused.
+struct Parked {
+    runtime: ComponentRuntime,
+    /// What it drew last; shown again if a frame fails.
+    frame: Option<FrameOutput>,
+    /// Why it stopped; it is not called again until it is focused and restarted.
+    error: Option<String>,
+}
+
+/// What a press means for the layout, from [`Panes::press`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PanePress {
+    /// It landed in the focused pane, which gets it.
+    Focused,
+    /// It landed in another pane, which should be focused and then get it.
+    Focus(String),
+    /// The host used it to grab a divider or switch tabs; the guest never sees it. A tab
+    /// switch focuses the pane it shows.
+    Host { focus: Option<String> },
+}
+
+pub struct Panes {
+    layout: Layout,
+    window: LogicalSize,
+    arrangement: Arrangement,
+    focused: String,
+    /// The focused pane's last frame, drawn for it once it is parked.
+    focused_frame: Option<FrameOutput>,
+    parked: HashMap<String, Parked>,
+    /// The size each pane's component was last given; hidden tabs keep theirs.
+    sizes: HashMap<String, LogicalSize>,
+    dragging: Option<Divider>,
+}
+
+impl Panes {
+    /// Start every pane's component. The first pane in layout order is focused, and its
+    /// runtime is returned for the app to hold.
+    pub fn load(
+        layout: Layout,
+        sources: Vec<(String, ComponentSource)>,
+        launch: &LaunchConfig,
+    ) -> Result<(Self, ComponentRuntime)> {
+        let mut parked = HashMap::new();
+        for (name, source) in sources {
+            let runtime = ComponentRuntime::with_launch(source, launch.clone())
+                .with_context(|| format!("failed to load pane '{name}'"))?;
+            let pane = Parked {
+                runtime,
+                frame: None,
+                error: None,
+            };
+            parked.insert(name, pane);
+        }
+        let arrangement = layout.arrange(Rect::default());
+        let Some(first) = arrangement.panes.first().map(|slot| slot.name.clone()) else {
+            bail!("the layout shows no panes");
+        };
+        let focused = parked
+            .remove(&first)
+            .with_context(|| format!("pane '{first}' has no component"))?;
+        let panes = Self {
+            layout,
+            window: LogicalSize::default(),
+            arrangement,
+            focused: first,
+            focused_frame: None,
+            parked,
+            sizes: HashMap::new(),
+            dragging: None,
+        };
+        Ok((panes, focused.runtime))
+    }
+
+    pub fn focused(&self) -> &str {
+        &self.focused
+    }
+
+    /// Where the focused pane sits in the window; input is delivered relative to it.
+    pub fn focused_origin(&self) -> Vec2 {
+        self.slot_rect(&self.focused).unwrap_or_default().origin()
+    }
+
+    /// The size the focused pane's component draws at.
+    pub fn focused_size(&self) -> LogicalSize {
+        self.pane_size(&self.focused)
+    }
+
+    /// Every parked pane's runtime, for settings the app applies to all components.
+    pub fn parked_runtimes(&mut self) -> impl Iterator<Item = &mut ComponentRuntime> {
+        self.parked.values_mut().map(|pane| &mut pane.runtime)
+    }
+
+    /// Lay the panes out in a window of `size` and call `init` on the parked ones. The
+    /// app initialises the focused pane itself at [`focused_size`](Self::focused_size).
+    pub fn init(&mut self, size: LogicalSize) {
+        self.window = size;
+        self.arrange();
+        let sizes: Vec<_> = self
+            .parked
+            .keys()
+            .map(|name| (name.clone(), self.pane_size(name)))
+            .collect();
+        for (name, size) in sizes {
+            self.sizes.insert(name.clone(), size);
+            let pane = self.parked.get_mut(&name).expect("listed above");
+            let result = pane.runtime.call_init(size);
+            Self::absorb(&name, pane, result);
+        }
+        self.sizes.insert(self.focused.clone(), self.focused_size());
+    }
+
+    /// Lay the panes out in a window of `size`, resizing the parked panes that changed.
+    /// Returns the focused pane's new size if it changed too, for the app to deliver.
+    pub fn resize(&mut self, size: LogicalSize) -> Option<LogicalSize> {
+        self.window = size;
+        self.relayout()
+    }
+
+    /// Route a press at `point`, in window coordinates.
+    pub fn press(&mut self, point: Vec2) -> PanePress {
+        if let Some(divider) = self.arrangement.divider_at(point) {
+            self.dragging = Some(divider.clone());
+            return PanePress::Host { focus: None };
+        }
+        if let Some((bar, index)) = self.arrangement.tab_at(point) {
+            let tab = [bar.path.as_slice(), &[index]].concat();
+            self.layout.select_tab(&bar.path.clone(), index);
+            self.arrange();
+            // The tab's first visible pane takes focus unless focus is already inside it.
+            let shown: Vec<String> = match self.layout.node(&tab) {
+                Some(node) => node
+                    .pane_names()
+                    .into_iter()
+                    .filter(|name| self.slot_rect(name).is_some())
+                    .map(String::from)
+                    .collect(),
+                None => Vec::new(),
+            };
+            let focus = match shown.first() {
+                Some(first) if !shown.contains(&self.focused) => Some(first.clone()),
+                _ => None,
+            };
+            return PanePress::Host { focus };
+        }
+        match self.arrangement.pane_at(point) {
+            Some(slot) if slot.name != self.focused => PanePress::Focus(slot.name.clone()),
+            Some(_) => PanePress::Focused,
+            // Gaps between panes belong to the dividers, so this is only reached for
+            // points outside the window.
+            None => PanePress::Host { focus: None },
+        }
+    }
+
+    /// Move the grabbed divider to `point`, in window coordinates. `None` when no divider
+    /// is held, so the move is the focused pane's; otherwise the focused pane's new size
+    /// if the drag changed it.
+    pub fn drag(&mut self, point: Vec2) -> Option<Option<LogicalSize>> {
+        let divider = self.dragging.as_ref()?;
+        let ratio = divider.ratio_at(point);
+        let path = divider.path.clone();
+        self.layout.set_ratio(&path, ratio);
+        Some(self.relayout())
+    }
+
+    /// Let go of the grabbed divider; true if one was held, so the release is the host's.
+    pub fn release(&mut self) -> bool {
+        self.dragging.take().is_some()
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.dragging.is_some()
+    }
+
+    /// Make `name` the focused pane. `current` is the runtime of the pane focused so far,
+    /// parked in its place; the newly focused pane's runtime is returned with the error
+    /// it stopped on, if any. Unknown or already focused names give `current` back.
+    pub fn focus(
+        &mut self,
+        name: &str,
+        current: ComponentRuntime,
+    ) -> (ComponentRuntime, Option<String>) {
+        let Some(next) = self.parked.remove(name) else {
+            return (current, None);
+        };
+        let previous = std::mem::replace(&mut self.focused, name.to_string());
+        let parked = Parked {
+            runtime: current,
+            frame: std::mem::replace(&mut self.focused_frame, next.frame),
+            error: None,
+        };
+        self.parked.insert(previous, parked);
+        tracing::debug!(pane = name, "pane focused");
+        (next.runtime, next.error)
+    }
+
+    /// The size the focused pane should be told about, if it differs from the last one
+    /// it was given, e.g. after it was shown by a tab switch.
+    pub fn take_focused_resize(&mut self) -> Option<LogicalSize> {
+        let size = self.focused_size();
+        (self.sizes.insert(self.focused.clone(), size) != Some(size)).then_some(size)
+    }
+
+    /// Run `frame` on every visible parked pane; true if one asked for another frame.
+    pub fn run_frames(&mut self, dt_ms: f32) -> bool {
+        let visible: Vec<_> = self
+            .arrangement
+            .panes
+            .iter()
+            .map(|slot| slot.name.clone())
+            .collect();
+        let mut requested_redraw = false;
+        for name in visible {
+            let Some(pane) = self.parked.get_mut(&name) else {
+                continue;
+            };
+            if pane.error.is_some() {
+                continue;
+            }
+            match pane.runtime.call_frame(dt_ms) {
+                Ok(frame) => {
+                    requested_redraw |= frame.requested_redraw;
+                    pane.frame = Some(frame.frame);
+                }
+                Err(err) => Self::fail(&name, pane, &err),
+            }
+        }
+        requested_redraw
+    }
+
+    /// Deliver background task and worker events to the parked panes; true if one asked
+    /// for a frame or drew.
+    pub fn poll_background(&mut self) -> bool {
+        let mut redraw = false;
+        for (name, pane) in &mut self.parked {
+            let runtime = &mut pane.runtime;
+            if pane.error.is_some()
+                || runtime.tasks_in_flight() == 0 && runtime.workers_running() == 0
+            {
+                continue;
+            }
+            let result = runtime.poll_tasks().and_then(|mut result| {
+                result.merge(runtime.poll_workers()?);
+                Ok(result)
+            });
+            redraw |= Self::absorb(name, pane, result);
+        }
+        redraw
+    }
+
+    /// Whether a parked pane has background work to deliver.
+    pub fn background_pending(&self) -> bool {
+        self.parked.values().any(|pane| {
+            pane.error.is_none()
+                && (pane.runtime.tasks_in_flight() > 0 || pane.runtime.workers_running() > 0)
+        })
+    }
+
+    /// One frame of the whole window: every visible pane's frame clipped to its rect and
+    /// moved to its origin, `focused` standing in for the focused pane's, then the
+    /// dividers and tab bars.
+    pub fn compose(&mut self, focused: &FrameOutput) -> FrameOutput {
+        self.focused_frame = Some(focused.clone());
+        let mut commands = Vec::new();
+        for slot in &self.arrangement.panes {
+            let (frame, error) = match self.parked.get(&slot.name) {
+                Some(pane) => (pane.frame.as_ref(), pane.error.as_deref()),
+                None => (Some(focused), None),
+            };
+            commands.push(DrawCommand::PushClip {
+                origin: slot.rect.origin(),
+                size: Vec2::new(slot.rect.width, slot.rect.height),
+            });
+            if let Some(frame) = frame {
+                if let Some(color) = frame.clear_color {
+                    commands.push(fill(slot.rect, color));
+                }
+                push_translated(&mut commands, &frame.commands, slot.rect.origin());
+            }
+            if let Some(error) = error {
+                commands.push(label(
+                    format!("'{}' stopped: {error}", slot.name),
+                    slot.rect.origin() + Vec2::new(TAB_TEXT_INSET, 2.0 * TAB_TEXT_SIZE),
+                    ERROR_TEXT_COLOR,
+                ));
+            }
+            commands.push(DrawCommand::PopClip);
+        }
+        for divider in &self.arrangement.dividers {
+            commands.push(fill(divider.rect, DIVIDER_COLOR));
+        }
+        for bar in &self.arrangement.tab_bars {
+            commands.push(fill(bar.rect, TAB_BAR_COLOR));
+            for (index, title) in bar.titles.iter().enumerate() {
+                let tab = bar.tab_rect(index);
+                if index == bar.active {
+                    commands.push(fill(tab, ACTIVE_TAB_COLOR));
+                }
+                commands.push(DrawCommand::PushClip {
+                    origin: tab.origin(),
+                    size: Vec2::new(tab.width, tab.height),
+                });
+                let baseline = tab.y + (tab.height + TAB_TEXT_SIZE * 0.7) / 2.0;
+                commands.push(label(
+                    title.clone(),
+                    Vec2::new(tab.x + TAB_TEXT_INSET, baseline),
+                    TAB_TEXT_COLOR,
+                ));
+                commands.push(DrawCommand::PopClip);
+            }
+        }
+        FrameOutput {
+            clear_color: None,
+            commands,
+        }
+    }
+
+    fn arrange(&mut self) {
+        let bounds = Rect::new(0.0, 0.0, self.window.width, self.window.height);
+        self.arrangement = self.layout.arrange(bounds);
+    }
+
+    /// Re-arrange after the window or a divider moved and resize the parked panes whose
+    /// rects changed; the focused pane's new size, if any, is the app's to deliver.
+    fn relayout(&mut self) -> Option<LogicalSize> {
+        self.arrange();
+        let visible: Vec<_> = self
+            .arrangement
+            .panes
+            .iter()
+            .map(|slot| slot.name.clone())
+            .collect();
+        for name in visible {
+            let size = self.pane_size(&name);
+            let Some(pane) = self.parked.get_mut(&name) else {
+                continue;
+            };
+            if self.sizes.insert(name.clone(), size) == Some(size) || pane.error.is_some() {
+                continue;
+            }
+            let result = pane.runtime.call_resize(size);
+            Self::absorb(&name, pane, result);
+        }
+        self.take_focused_resize()
+    }
+
+    fn slot_rect(&self, name: &str) -> Option<Rect> {
+        self.arrangement
+            .panes
+            .iter()
+            .find(|slot| slot.name == name)
+            .map(|slot| slot.rect)
+    }
+
+    /// A visible pane's rect size; hidden tabs keep the size they were last given, or
+    /// the window's before they have one.
+    fn pane_size(&self, name: &str) -> LogicalSize {
+        match self.slot_rect(name) {
+            Some(rect) => LogicalSize {
+                width: rect.width,
+                height: rect.height,
+                scale_factor: self.window.scale_factor,
+            },
+            None => self.sizes.get(name).copied().unwrap_or(self.window),
+        }
+    }
+
+    /// Keep what a parked pane's call drew; true if it asked for a frame or drew.
+    fn absorb(name: &str, pane: &mut Parked, result: Result<CallResult>) -> bool {
+        match result {
+            Ok(result) => {
+                let drew = result.frame.is_some();
+                if let Some(frame) = result.frame {
+                    pane.frame = Some(frame);
+                }
+                result.requested_redraw || drew
+            }
+            Err(err) => {
+                Self::fail(name, pane, &err);
+                true
+            }
+        }
+    }
+
+    fn fail(name: &str, pane: &mut Parked, err: &anyhow::Error) {
+        tracing::warn!(pane = name, error = %format!("{err:#}"), "pane stopped");
+        pane.error = Some(format!("{err:#}"));
+    }
+}
+
+fn fill(rect: Rect, color: Color) -> DrawCommand {
+    DrawCommand::FillRect {
+        origin: rect.origin(),
+        size: Vec2::new(rect.width, rect.height),
+        color,
+    }
+}
+
+fn label(text: String, origin: Vec2, color: Color) -> DrawCommand {
+    DrawCommand::DrawText {
+        text,
+        origin,
+        size: TAB_TEXT_SIZE,
+        color,
+        font: FontFamily::Sans,
+        flow: TextFlow::default(),
+    }
+}
+
+/// Append `commands` moved by `offset`. Clips and shader layers the pane left open are
+/// closed, and stray closes dropped, so nothing leaks out of the pane's own clip.
+fn push_translated(out: &mut Vec<DrawCommand>, commands: &[DrawCommand], offset: Vec2) {
+    let mut open = Vec::new();
+    for command in commands {
+        match command {
+            DrawCommand::PushClip { .. } => open.push(DrawCommand::PopClip),
+            DrawCommand::PushShaderLayer { .. } => open.push(DrawCommand::PopShaderLayer),
+            DrawCommand::PopClip | DrawCommand::PopShaderLayer => {
+                let matches = open.last().is_some_and(|close| {
+                    std::mem::discriminant(close) == std::mem::discriminant(command)
+                });
+                if !matches {
+                    continue;
+                }
+                open.pop();
+            }
+            _ => {}
+        }
+        let mut command = command.clone();
+        command.translate(offset);
+        out.push(command);
+    }
+    out.extend(open.into_iter().rev());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PanePress, Panes};
+    use crate::geometry::Vec2;
+    use crate::host::DrawCommand;
+    use crate::launch::LaunchConfig;
+    use crate::layout::{Layout, DIVIDER_WIDTH, TAB_BAR_HEIGHT};
+    use crate::model::LogicalSize;
+    use crate::runtime::{ComponentRuntime, ComponentSource};
+
+    const COUNTER: &[u8] = include_bytes!("../../../assets/counter-component.wasm");
+
+    const LAYOUT: &str = r#"
+        [split]
+        axis = "horizontal"
+        first = { pane = "left" }
+        second = { tabs = { children = [{ pane = "right" }, { pane = "hidden" }] } }
+    "#;
+
+    const WINDOW: LogicalSize = LogicalSize {
+        width: 404.0,
+        height: 300.0,
+        scale_factor: 1.0,
+    };
+
+    fn load() -> (Panes, ComponentRuntime) {
+        let sources = ["left", "right", "hidden"].map(|name| {
+            (
+                name.to_string(),
+                ComponentSource::embedded("counter", COUNTER),
+            )
+        });
+        let (mut panes, mut focused) = Panes::load(
+            Layout::parse(LAYOUT).unwrap(),
+            sources.into(),
+            &LaunchConfig::default(),
+        )
+        .unwrap();
+        panes.init(WINDOW);
+        focused.call_init(panes.focused_size()).unwrap();
+        (panes, focused)
+    }
+
+    /// The count each visible pane drew, from a composed frame, in layout order.
+    fn counts(panes: &mut Panes, focused: &mut ComponentRuntime) -> Vec<String> {
+        panes.run_frames(16.0);
+        let own = focused.call_frame(16.0).unwrap().frame;
+        let frame = panes.compose(&own);
+        frame
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::DrawText { text, .. } if text.parse::<i32>().is_ok() => {
+                    Some(text.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn presses_focus_panes_and_input_follows_focus() {
+        let (mut panes, mut focused) = load();
+        assert_eq!(panes.focused(), "left");
+        assert_eq!(panes.focused_size().width, 200.0);
+
+        focused.call_key_down(&plus()).unwrap();
+        assert_eq!(counts(&mut panes, &mut focused), ["1", "0"]);
+
+        let press = panes.press(Vec2::new(300.0, 100.0));
+        assert_eq!(press, PanePress::Focus("right".into()));
+        let (next, error) = panes.focus("right", focused);
+        assert!(error.is_none());
+        focused = next;
+        assert_eq!(
+            panes.focused_origin(),
+            Vec2::new(200.0 + DIVIDER_WIDTH, TAB_BAR_HEIGHT)
+        );
+        assert_eq!(panes.press(Vec2::new(300.0, 100.0)), PanePress::Focused);
+
+        focused.call_key_down(&plus()).unwrap();
+        focused.call_key_down(&plus()).unwrap();
+        assert_eq!(counts(&mut panes, &mut focused), ["1", "2"]);
+
+        // The hidden tab's bar entry shows and focuses it.
+        let press = panes.press(Vec2::new(390.0, 10.0));
+        assert_eq!(
+            press,
+            PanePress::Host {
+                focus: Some("hidden".into())
+            }
+        );
+        let (next, _) = panes.focus("hidden", focused);
+        focused = next;
+        assert_eq!(panes.take_focused_resize(), Some(panes.focused_size()));
+        assert_eq!(counts(&mut panes, &mut focused), ["1", "0"]);
+    }
+
+    #[test]
+    fn dragging_a_divider_resizes_both_sides() {
+        let (mut panes, _focused) = load();
+        assert_eq!(panes.drag(Vec2::new(100.0, 50.0)), None, "nothing held yet");
+        assert!(!panes.release());
+
+        let press = panes.press(Vec2::new(201.0, 150.0));
+        assert_eq!(press, PanePress::Host { focus: None });
+        assert!(panes.is_dragging());
+        let resized = panes.drag(Vec2::new(102.0, 150.0)).unwrap().unwrap();
+        assert_eq!(resized.width, 100.0);
+        assert_eq!(panes.sizes["right"].width, 300.0);
+        assert_eq!(panes.drag(Vec2::new(102.0, 10.0)), Some(None), "unchanged");
+        assert!(panes.release());
+        assert!(!panes.is_dragging());
+
+        // The focused pane draws at its own size and lands at its rect.
+        assert_eq!(panes.resize(WINDOW), None);
+        let frame = panes.compose(&crate::host::FrameOutput::default());
+        assert!(matches!(
+            frame.commands[0],
+            DrawCommand::PushClip { size, .. } if size == Vec2::new(100.0, 300.0)
+        ));
+    }
+
+    #[test]
+    fn pane_frames_are_clipped_and_moved_into_place() {
+        let (mut panes, mut focused) = load();
+        panes.run_frames(16.0);
+        let own = focused.call_frame(16.0).unwrap().frame;
+        let frame = panes.compose(&own);
+        let right_origin = Vec2::new(200.0 + DIVIDER_WIDTH, TAB_BAR_HEIGHT);
+        let clips: Vec<_> = frame
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::PushClip { origin, .. } => Some(*origin),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(clips[0], Vec2::ZERO);
+        assert!(clips.contains(&right_origin));
+        let pushes = clips.len();
+        let pops = frame
+            .commands
+            .iter()
+            .filter(|command| matches!(command, DrawCommand::PopClip))
+            .count();
+        assert_eq!(pushes, pops);
+    }
+
+    fn plus() -> crate::model::KeyEvent {
+        crate::model::KeyEvent {
+            key: "+".into(),
+            code: "Equal".into(),
+            location: crate::model::KeyLocation::Standard,
+            modifiers: Default::default(),
+            is_repeat: false,
+        }
+    }
+}
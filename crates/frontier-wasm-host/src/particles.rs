@@ -0,0 +1,1071 @@
+//! Host-simulated particle emitters. A guest configures an emitter once and draws it with a
+//! single call per frame; spawning, motion and fading happen here, so tens of thousands of
+//! particles never cross the component boundary. Emitters advance by the same `dt` the
+//! guest's `frame` receives, so pausing or scaling guest time affects them too.
+//!
+//! The CPU only keeps the books: how many particles each step spawned, when, and with which
+//! settings. A particle's motion follows from those alone, so `draw-emitter` records the
+//! emitter's spawn batches and [`ParticleRenderer`] works out every particle's position,
+//! size and colour in a compute pass, then draws them as instanced quads. Vector export,
+//! the software renderer and shader layers evaluate the same formula on the CPU.
+
+use std::collections::VecDeque;
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::component::vello::canvas::host::EmitterConfig as WitEmitterConfig;
+use crate::geometry::Vec2;
+use crate::host::Color;
+
+/// Largest `max-particles` an emitter may ask for, and the most particles one draw holds.
+pub const MAX_PARTICLES: u32 = 65_536;
+/// Longer steps (after a stall or while unpausing) are clamped so one frame cannot spawn a
+/// whole emitter's worth of particles at the same spot.
+const MAX_STEP_MS: f32 = 250.0;
+
+static NEXT_SEED: AtomicU32 = AtomicU32::new(0x9E37_79B9);
+
+/// How an emitter spawns and animates particles; see `emitter-config` in the WIT.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EmitterConfig {
+    pub position: Vec2,
+    /// Particles spawned per second.
+    pub rate: f32,
+    pub max_particles: u32,
+    pub lifetime_ms: f32,
+    /// Direction of travel in radians; 0 points right and positive angles turn clockwise.
+    pub angle: f32,
+    /// Particles leave up to this many radians either side of `angle`.
+    pub spread: f32,
+    /// Logical pixels per second.
+    pub speed: f32,
+    pub speed_jitter: f32,
+    /// Acceleration in logical pixels per second squared.
+    pub gravity: Vec2,
+    pub start_size: f32,
+    pub end_size: f32,
+    pub start_color: Color,
+    pub end_color: Color,
+}
+
+impl EmitterConfig {
+    pub fn from_wit(config: WitEmitterConfig) -> Self {
+        Self {
+            position: Vec2::from(config.position),
+            rate: config.rate,
+            max_particles: config.max_particles,
+            lifetime_ms: config.lifetime_ms,
+            angle: config.angle,
+            spread: config.spread,
+            speed: config.speed,
+            speed_jitter: config.speed_jitter,
+            gravity: Vec2::from(config.gravity),
+            start_size: config.start_size,
+            end_size: config.end_size,
+            start_color: Color::from_wit(config.start_color),
+            end_color: Color::from_wit(config.end_color),
+        }
+    }
+
+    /// Finite, non-negative rates and sizes, a positive lifetime and a supported capacity.
+    pub fn is_valid(&self) -> bool {
+        let finite = [
+            self.rate,
+            self.lifetime_ms,
+            self.angle,
+            self.spread,
+            self.speed,
+            self.speed_jitter,
+            self.start_size,
+            self.end_size,
+        ]
+        .iter()
+        .all(|value| value.is_finite());
+        finite
+            && self.position.is_finite()
+            && self.gravity.is_finite()
+            && self.rate >= 0.0
+            && self.lifetime_ms > 0.0
+            && self.start_size >= 0.0
+            && self.end_size >= 0.0
+            && (1..=MAX_PARTICLES).contains(&self.max_particles)
+    }
+}
+
+/// One particle as drawn: a filled circle `size` logical pixels across.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ParticleSprite {
+    pub position: Vec2,
+    pub size: f32,
+    pub color: Color,
+}
+
+/// Particles spawned together, numbered `first..first + count`, and `age_ms` old.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Spawn {
+    pub first: u32,
+    pub count: u32,
+    pub age_ms: f32,
+    /// Index of the settings they were spawned with in [`ParticleBatch::configs`].
+    pub config: u32,
+}
+
+/// An emitter's live particles when it was drawn, as the batches they were spawned in.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParticleBatch {
+    /// Picks each particle's direction and speed within the emitter's spread and jitter.
+    pub seed: u32,
+    pub configs: Vec<EmitterConfig>,
+    pub spawns: Vec<Spawn>,
+}
+
+impl ParticleBatch {
+    /// Spawns that can be drawn, with their settings: those naming valid settings and
+    /// still alive, cut off at [`MAX_PARTICLES`] in all. Batches read from a file may
+    /// name anything.
+    pub fn drawable(&self) -> impl Iterator<Item = (Spawn, &EmitterConfig)> {
+        let mut left = MAX_PARTICLES;
+        self.spawns.iter().filter_map(move |spawn| {
+            let config = self
+                .configs
+                .get(spawn.config as usize)
+                .filter(|config| config.is_valid())?;
+            let alive = spawn.age_ms >= 0.0 && spawn.age_ms < config.lifetime_ms;
+            let count = spawn.count.min(left);
+            if !alive || count == 0 {
+                return None;
+            }
+            left -= count;
+            Some((Spawn { count, ..*spawn }, config))
+        })
+    }
+
+    /// Particles that would be drawn.
+    pub fn len(&self) -> usize {
+        self.drawable().map(|(spawn, _)| spawn.count as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every particle as drawn, worked out on the CPU.
+    pub fn sprites(&self) -> impl Iterator<Item = ParticleSprite> + '_ {
+        self.drawable().flat_map(move |(spawn, config)| {
+            (0..spawn.count).map(move |index| {
+                sprite(
+                    self.seed,
+                    config,
+                    spawn.first.wrapping_add(index),
+                    spawn.age_ms,
+                )
+            })
+        })
+    }
+
+    /// Move every particle by `offset`, as content inside a scroll region is.
+    pub fn translate(&mut self, offset: Vec2) {
+        for config in &mut self.configs {
+            config.position = config.position + offset;
+        }
+    }
+}
+
+/// Where particle `number` of an emitter with `seed` is `age_ms` after it was spawned with
+/// `config`. `PARTICLE_WGSL` repeats this for the GPU.
+fn sprite(seed: u32, config: &EmitterConfig, number: u32, age_ms: f32) -> ParticleSprite {
+    let first = hash(number ^ seed);
+    let second = hash(first);
+    let angle = config.angle + config.spread * (unit(first) * 2.0 - 1.0);
+    let speed = config.speed + config.speed_jitter * (unit(second) * 2.0 - 1.0);
+    let t = age_ms / 1000.0;
+    let progress = (age_ms / config.lifetime_ms).clamp(0.0, 1.0);
+    let lerp = |from: f32, to: f32| from + (to - from) * progress;
+    let (from, to) = (config.start_color, config.end_color);
+    ParticleSprite {
+        position: Vec2 {
+            x: config.position.x + angle.cos() * speed * t + 0.5 * config.gravity.x * t * t,
+            y: config.position.y + angle.sin() * speed * t + 0.5 * config.gravity.y * t * t,
+        },
+        size: lerp(config.start_size, config.end_size),
+        color: Color {
+            r: lerp(from.r, to.r),
+            g: lerp(from.g, to.g),
+            b: lerp(from.b, to.b),
+            a: lerp(from.a, to.a),
+        },
+    }
+}
+
+/// The `lowbias32` integer hash, which WGSL computes bit for bit the same.
+fn hash(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^= x >> 16;
+    x
+}
+
+/// The top 24 bits of a hash as 0.0..1.0, exact in `f32`.
+fn unit(hash: u32) -> f32 {
+    (hash >> 8) as f32 / (1u32 << 24) as f32
+}
+
+/// Particles spawned in one step or burst.
+#[derive(Debug)]
+struct Spawned {
+    first: u32,
+    count: u32,
+    /// The emitter's clock when they were spawned.
+    born_ms: f64,
+    config: EmitterConfig,
+}
+
+#[derive(Debug)]
+pub struct Emitter {
+    config: EmitterConfig,
+    spawns: VecDeque<Spawned>,
+    live: u32,
+    /// Milliseconds the emitter has advanced.
+    clock_ms: f64,
+    /// Fraction of a particle owed from earlier steps.
+    pending: f32,
+    seed: u32,
+    /// Number of the next particle to spawn.
+    next: u32,
+    /// What `batch` returned, until particles are spawned or the emitter advances.
+    batch: Option<Arc<ParticleBatch>>,
+}
+
+impl Emitter {
+    /// `None` unless `config.is_valid()`.
+    pub fn new(config: EmitterConfig) -> Option<Self> {
+        if !config.is_valid() {
+            return None;
+        }
+        Some(Self {
+            config,
+            spawns: VecDeque::new(),
+            live: 0,
+            clock_ms: 0.0,
+            pending: 0.0,
+            seed: hash(NEXT_SEED.fetch_add(1, Ordering::Relaxed)),
+            next: 0,
+            batch: None,
+        })
+    }
+
+    /// Replace the configuration for particles spawned from now on; live ones keep the
+    /// settings they were spawned with. `max_particles` keeps the value the emitter was
+    /// created with, since it sets the emitter's memory budget.
+    pub fn configure(&mut self, config: EmitterConfig) -> bool {
+        let config = EmitterConfig {
+            max_particles: self.config.max_particles,
+            ..config
+        };
+        if !config.is_valid() {
+            return false;
+        }
+        self.config = config;
+        true
+    }
+
+    /// Spawn up to `count` particles at once, as capacity allows.
+    pub fn burst(&mut self, count: u32) {
+        self.spawn(count.min(self.capacity_left()));
+    }
+
+    /// Age and expire particles, then spawn what `rate` owes for `dt_ms`.
+    pub fn advance(&mut self, dt_ms: f32) {
+        if !dt_ms.is_finite() || dt_ms <= 0.0 {
+            return;
+        }
+        let dt_ms = dt_ms.min(MAX_STEP_MS);
+        self.clock_ms += f64::from(dt_ms);
+        let clock = self.clock_ms;
+        self.spawns
+            .retain(|spawned| clock - spawned.born_ms < f64::from(spawned.config.lifetime_ms));
+        self.live = self.spawns.iter().map(|spawned| spawned.count).sum();
+        self.batch = None;
+        self.pending += self.config.rate * dt_ms / 1000.0;
+        let owed = self.pending.floor();
+        self.pending -= owed;
+        self.spawn((owed as u32).min(self.capacity_left()));
+    }
+
+    pub fn len(&self) -> usize {
+        self.live as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+
+    /// The live particles for drawing. Repeated draws before the emitter changes share
+    /// one batch.
+    pub fn batch(&mut self) -> Arc<ParticleBatch> {
+        if let Some(batch) = &self.batch {
+            return batch.clone();
+        }
+        let mut configs: Vec<EmitterConfig> = Vec::new();
+        let spawns = self
+            .spawns
+            .iter()
+            .map(|spawned| {
+                if configs.last() != Some(&spawned.config) {
+                    configs.push(spawned.config);
+                }
+                Spawn {
+                    first: spawned.first,
+                    count: spawned.count,
+                    age_ms: (self.clock_ms - spawned.born_ms) as f32,
+                    config: configs.len() as u32 - 1,
+                }
+            })
+            .collect();
+        let batch = Arc::new(ParticleBatch {
+            seed: self.seed,
+            configs,
+            spawns,
+        });
+        self.batch = Some(batch.clone());
+        batch
+    }
+
+    /// GPU memory a full emitter draws into, counted against the image budget.
+    pub fn byte_len(&self) -> usize {
+        self.config.max_particles as usize * INSTANCE_BYTES
+    }
+
+    fn capacity_left(&self) -> u32 {
+        self.config.max_particles.saturating_sub(self.live)
+    }
+
+    fn spawn(&mut self, count: u32) {
+        if count == 0 {
+            return;
+        }
+        let clock = self.clock_ms;
+        let config = self.config;
+        let next = self.next;
+        match self.spawns.back_mut() {
+            // Bursts within one step with the same settings share a batch.
+            Some(last)
+                if last.born_ms == clock
+                    && last.config == config
+                    && last.first.wrapping_add(last.count) == next =>
+            {
+                last.count += count;
+            }
+            _ => self.spawns.push_back(Spawned {
+                first: next,
+                count,
+                born_ms: clock,
+                config,
+            }),
+        }
+        self.next = next.wrapping_add(count);
+        self.live += count;
+        self.batch = None;
+    }
+}
+
+/// Bytes per drawn particle on the GPU: centre, radius and padding, then colour.
+const INSTANCE_BYTES: usize = 32;
+/// Bytes per [`Spawn`] on the GPU: `first`, `count`, the index of its first particle in
+/// the draw, `config`, `age_ms` and padding.
+const SPAWN_BYTES: usize = 32;
+/// Bytes per [`EmitterConfig`] on the GPU: five `vec4`s.
+const CONFIG_BYTES: usize = 80;
+/// `Frame` in `PARTICLE_WGSL`.
+const FRAME_BYTES: usize = 32;
+const WORKGROUP_SIZE: u32 = 64;
+
+const PARTICLE_WGSL: &str = "
+struct Frame {
+    target_size: vec2<f32>,
+    scale: f32,
+    opacity: f32,
+    count: u32,
+    spawn_count: u32,
+    seed: u32,
+    padding: u32,
+}
+
+struct Spawn {
+    first: u32,
+    count: u32,
+    offset: u32,
+    config: u32,
+    age_ms: f32,
+    padding: array<f32, 3>,
+}
+
+struct Config {
+    // Position, then gravity.
+    position: vec4<f32>,
+    // Angle, spread, speed and speed jitter.
+    motion: vec4<f32>,
+    // Lifetime in milliseconds, start size and end size.
+    life: vec4<f32>,
+    start_color: vec4<f32>,
+    end_color: vec4<f32>,
+}
+
+struct Instance {
+    // Centre and radius in logical pixels.
+    shape: vec4<f32>,
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> frame: Frame;
+@group(0) @binding(1) var<storage, read> spawns: array<Spawn>;
+@group(0) @binding(2) var<storage, read> configs: array<Config>;
+@group(0) @binding(3) var<storage, read_write> instances: array<Instance>;
+
+fn hash(value: u32) -> u32 {
+    var x = value;
+    x ^= x >> 16u;
+    x *= 0x7feb352du;
+    x ^= x >> 15u;
+    x *= 0x846ca68bu;
+    x ^= x >> 16u;
+    return x;
+}
+
+fn unit(value: u32) -> f32 {
+    return f32(value >> 8u) / 16777216.0;
+}
+
+fn finite(value: f32) -> bool {
+    return abs(value) <= 3.0e38;
+}
+
+@compute @workgroup_size(64)
+fn simulate(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if index >= frame.count {
+        return;
+    }
+    var low = 0u;
+    var high = frame.spawn_count;
+    while high - low > 1u {
+        let middle = (low + high) / 2u;
+        if spawns[middle].offset <= index {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    let spawn = spawns[low];
+    let config = configs[spawn.config];
+    let first = hash((spawn.first + index - spawn.offset) ^ frame.seed);
+    let second = hash(first);
+    let angle = config.motion.x + config.motion.y * (unit(first) * 2.0 - 1.0);
+    let speed = config.motion.z + config.motion.w * (unit(second) * 2.0 - 1.0);
+    let t = spawn.age_ms / 1000.0;
+    let position = config.position.xy + vec2<f32>(cos(angle), sin(angle)) * speed * t
+        + 0.5 * config.position.zw * t * t;
+    let progress = clamp(spawn.age_ms / config.life.x, 0.0, 1.0);
+    var radius = mix(config.life.y, config.life.z, progress) * 0.5;
+    if !(finite(position.x) && finite(position.y) && finite(radius)) {
+        radius = 0.0;
+    }
+    let color = mix(config.start_color, config.end_color, progress);
+    instances[index] = Instance(vec4<f32>(position, radius, 0.0), color);
+}
+
+struct Fragment {
+    @builtin(position) position: vec4<f32>,
+    // Physical pixels from the centre.
+    @location(0) offset: vec2<f32>,
+    @location(1) radius: f32,
+    @location(2) color: vec4<f32>,
+}
+
+@vertex
+fn vertex(
+    @builtin(vertex_index) corner_index: u32,
+    @location(0) shape: vec4<f32>,
+    @location(1) color: vec4<f32>,
+) -> Fragment {
+    let corner = vec2<f32>(f32(corner_index & 1u), f32(corner_index >> 1u)) * 2.0 - 1.0;
+    let radius = shape.z * frame.scale;
+    // One more pixel leaves room for the antialiased edge.
+    let offset = corner * (radius + 1.0);
+    let clip = (shape.xy * frame.scale + offset) / frame.target_size * 2.0 - 1.0;
+    var out: Fragment;
+    out.position = vec4<f32>(clip.x, -clip.y, 0.0, 1.0);
+    if !(radius > 0.0) {
+        // Every corner on one point outside the target, so nothing is drawn.
+        out.position = vec4<f32>(2.0, 2.0, 0.0, 1.0);
+    }
+    out.offset = offset;
+    out.radius = radius;
+    out.color = vec4<f32>(color.rgb, color.a * frame.opacity);
+    return out;
+}
+
+@fragment
+fn fragment(input: Fragment) -> @location(0) vec4<f32> {
+    let coverage = clamp(input.radius - length(input.offset) + 0.5, 0.0, 1.0);
+    return vec4<f32>(input.color.rgb, input.color.a * coverage);
+}
+";
+
+/// What one [`ParticleRenderer::draw`] draws.
+#[derive(Clone, Copy)]
+pub enum Particles<'a> {
+    /// Sprites worked out on the CPU, such as `draw-points`.
+    Sprites(&'a [ParticleSprite]),
+    /// An emitter's batch, worked out in the compute pass.
+    Batch(&'a ParticleBatch),
+}
+
+/// Where and how [`ParticleRenderer::draw`] draws.
+pub struct ParticleTarget<'a> {
+    pub view: &'a wgpu::TextureView,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f32,
+    /// Multiplies every particle's alpha, for window opacity.
+    pub opacity: f32,
+    /// Physical pixels to draw within: x, y, width and height.
+    pub scissor: [u32; 4],
+}
+
+/// GPU side: a compute pipeline that turns emitter batches into particles and a render
+/// pipeline that draws particles as instanced, antialiased circles over the render target
+/// with straight-alpha blending (Vello's output). Buffers grow to the largest draw seen and
+/// are reused after that.
+pub struct ParticleRenderer {
+    compute_layout: wgpu::BindGroupLayout,
+    render_layout: wgpu::BindGroupLayout,
+    simulate: wgpu::ComputePipeline,
+    draw: wgpu::RenderPipeline,
+    frame: wgpu::Buffer,
+    spawns: Option<wgpu::Buffer>,
+    configs: Option<wgpu::Buffer>,
+    instances: Option<wgpu::Buffer>,
+}
+
+impl ParticleRenderer {
+    pub fn new(device: &wgpu::Device, cache: Option<&wgpu::PipelineCache>) -> Self {
+        let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let uniform = |visibility| wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let compute_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("frontier.particles.simulate"),
+            entries: &[
+                uniform(wgpu::ShaderStages::COMPUTE),
+                storage(1, true),
+                storage(2, true),
+                storage(3, false),
+            ],
+        });
+        let render_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("frontier.particles.draw"),
+            entries: &[uniform(wgpu::ShaderStages::VERTEX)],
+        });
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("frontier.particles"),
+            source: wgpu::ShaderSource::Wgsl(PARTICLE_WGSL.into()),
+        });
+        let simulate = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("frontier.particles.simulate"),
+            layout: Some(
+                &device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("frontier.particles.simulate"),
+                    bind_group_layouts: &[&compute_layout],
+                    push_constant_ranges: &[],
+                }),
+            ),
+            module: &module,
+            entry_point: Some("simulate"),
+            compilation_options: Default::default(),
+            cache,
+        });
+        let draw = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("frontier.particles.draw"),
+            layout: Some(
+                &device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("frontier.particles.draw"),
+                    bind_group_layouts: &[&render_layout],
+                    push_constant_ranges: &[],
+                }),
+            ),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("vertex"),
+                compilation_options: Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: INSTANCE_BYTES as u64,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x4],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some("fragment"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache,
+        });
+        let frame = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frontier.particles.frame"),
+            size: FRAME_BYTES as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Self {
+            compute_layout,
+            render_layout,
+            simulate,
+            draw,
+            frame,
+            spawns: None,
+            configs: None,
+            instances: None,
+        }
+    }
+
+    /// Draw `particles` over `target`, passing every colour through `recolor` first.
+    pub fn draw(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target: &ParticleTarget<'_>,
+        particles: Particles<'_>,
+        recolor: &dyn Fn(Color) -> Color,
+    ) {
+        let [x, y, width, height] = target.scissor;
+        if width == 0 || height == 0 {
+            return;
+        }
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("frontier.particles"),
+        });
+        let (count, spawn_count, seed) = match particles {
+            Particles::Sprites(sprites) => {
+                let limit = device.limits().max_buffer_size as usize / INSTANCE_BYTES;
+                let mut bytes = Vec::with_capacity(sprites.len().min(limit) * INSTANCE_BYTES);
+                for sprite in sprites.iter().take(limit) {
+                    let radius = sprite.size * 0.5;
+                    if !sprite.position.is_finite() || !radius.is_finite() || radius <= 0.0 {
+                        continue;
+                    }
+                    let color = recolor(sprite.color);
+                    put_f32s(
+                        &mut bytes,
+                        &[sprite.position.x, sprite.position.y, radius, 0.0],
+                    );
+                    put_f32s(&mut bytes, &[color.r, color.g, color.b, color.a]);
+                }
+                if bytes.is_empty() {
+                    return;
+                }
+                let instances = grow(device, &mut self.instances, "instances", bytes.len());
+                queue.write_buffer(instances, 0, &bytes);
+                ((bytes.len() / INSTANCE_BYTES) as u32, 0, 0)
+            }
+            Particles::Batch(batch) => {
+                let mut spawns = Vec::new();
+                let mut offset = 0;
+                for (spawn, _) in batch.drawable() {
+                    for value in [spawn.first, spawn.count, offset, spawn.config] {
+                        spawns.extend_from_slice(&value.to_le_bytes());
+                    }
+                    put_f32s(&mut spawns, &[spawn.age_ms, 0.0, 0.0, 0.0]);
+                    offset += spawn.count;
+                }
+                if offset == 0 {
+                    return;
+                }
+                let mut configs = Vec::with_capacity(batch.configs.len() * CONFIG_BYTES);
+                for config in &batch.configs {
+                    let (start, end) = (recolor(config.start_color), recolor(config.end_color));
+                    put_f32s(
+                        &mut configs,
+                        &[
+                            config.position.x,
+                            config.position.y,
+                            config.gravity.x,
+                            config.gravity.y,
+                            config.angle,
+                            config.spread,
+                            config.speed,
+                            config.speed_jitter,
+                            config.lifetime_ms,
+                            config.start_size,
+                            config.end_size,
+                            0.0,
+                            start.r,
+                            start.g,
+                            start.b,
+                            start.a,
+                            end.r,
+                            end.g,
+                            end.b,
+                            end.a,
+                        ],
+                    );
+                }
+                let spawn_count = (spawns.len() / SPAWN_BYTES) as u32;
+                let spawn_buffer = grow(device, &mut self.spawns, "spawns", spawns.len());
+                queue.write_buffer(spawn_buffer, 0, &spawns);
+                let config_buffer = grow(device, &mut self.configs, "configs", configs.len());
+                queue.write_buffer(config_buffer, 0, &configs);
+                let instance_bytes = offset as usize * INSTANCE_BYTES;
+                grow(device, &mut self.instances, "instances", instance_bytes);
+                (offset, spawn_count, batch.seed)
+            }
+        };
+
+        let mut frame = Vec::with_capacity(FRAME_BYTES);
+        put_f32s(
+            &mut frame,
+            &[
+                target.width as f32,
+                target.height as f32,
+                target.scale_factor,
+                target.opacity,
+            ],
+        );
+        for value in [count, spawn_count, seed, 0] {
+            frame.extend_from_slice(&value.to_le_bytes());
+        }
+        queue.write_buffer(&self.frame, 0, &frame);
+        let instances = self.instances.as_ref().expect("grown above");
+        let instance_bytes = u64::from(count) * INSTANCE_BYTES as u64;
+
+        if spawn_count > 0 {
+            let (Some(spawns), Some(configs)) = (&self.spawns, &self.configs) else {
+                return;
+            };
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("frontier.particles.simulate"),
+                layout: &self.compute_layout,
+                entries: &[
+                    binding(0, &self.frame, FRAME_BYTES as u64),
+                    binding(1, spawns, u64::from(spawn_count) * SPAWN_BYTES as u64),
+                    binding(2, configs, (batch_configs(particles) * CONFIG_BYTES) as u64),
+                    binding(3, instances, instance_bytes),
+                ],
+            });
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("frontier.particles.simulate"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.simulate);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(count.div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("frontier.particles.draw"),
+            layout: &self.render_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: self.frame.as_entire_binding(),
+            }],
+        });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("frontier.particles.draw"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target.view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_scissor_rect(x, y, width, height);
+            pass.set_pipeline(&self.draw);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.set_vertex_buffer(0, instances.slice(..instance_bytes));
+            pass.draw(0..4, 0..count);
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// GPU memory held by the particle buffers.
+    pub fn bytes(&self) -> usize {
+        [&self.spawns, &self.configs, &self.instances]
+            .into_iter()
+            .flatten()
+            .map(|buffer| buffer.size() as usize)
+            .sum()
+    }
+
+    /// Free the buffers; the next draw allocates what it needs again.
+    pub fn release(&mut self) {
+        self.spawns = None;
+        self.configs = None;
+        self.instances = None;
+    }
+}
+
+fn batch_configs(particles: Particles<'_>) -> usize {
+    match particles {
+        Particles::Sprites(_) => 0,
+        Particles::Batch(batch) => batch.configs.len(),
+    }
+}
+
+/// The first `size` bytes of `buffer` at `binding`.
+fn binding(binding: u32, buffer: &wgpu::Buffer, size: u64) -> wgpu::BindGroupEntry<'_> {
+    wgpu::BindGroupEntry {
+        binding,
+        resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+            buffer,
+            offset: 0,
+            size: NonZeroU64::new(size),
+        }),
+    }
+}
+
+fn put_f32s(bytes: &mut Vec<u8>, values: &[f32]) {
+    for value in values {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// `slot`, replaced by a buffer of at least `size` bytes when it is smaller.
+fn grow<'a>(
+    device: &wgpu::Device,
+    slot: &'a mut Option<wgpu::Buffer>,
+    name: &str,
+    size: usize,
+) -> &'a wgpu::Buffer {
+    let size = (size as u64).max(256);
+    if slot.as_ref().is_none_or(|buffer| buffer.size() < size) {
+        *slot = Some(device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(&format!("frontier.particles.{name}")),
+            size: size.next_power_of_two(),
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::VERTEX
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+    }
+    slot.as_ref().expect("created above")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+    use std::sync::Arc;
+
+    use super::{Emitter, EmitterConfig, ParticleBatch, Spawn, MAX_PARTICLES};
+    use crate::geometry::Vec2;
+    use crate::host::Color;
+
+    fn config() -> EmitterConfig {
+        EmitterConfig {
+            position: Vec2 { x: 10.0, y: 10.0 },
+            rate: 100.0,
+            max_particles: 8,
+            lifetime_ms: 100.0,
+            angle: 0.0,
+            spread: 0.0,
+            speed: 100.0,
+            speed_jitter: 0.0,
+            gravity: Vec2 { x: 0.0, y: 0.0 },
+            start_size: 4.0,
+            end_size: 0.0,
+            start_color: Color {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+                a: 1.0,
+            },
+            end_color: Color::default(),
+        }
+    }
+
+    #[test]
+    fn spawns_at_rate_moves_and_expires() {
+        let mut emitter = Emitter::new(config()).unwrap();
+        emitter.advance(25.0);
+        assert_eq!(emitter.len(), 2, "100/s for 25ms owes 2.5 particles");
+        emitter.advance(25.0);
+        assert_eq!(emitter.len(), 5);
+
+        let sprites: Vec<_> = emitter.batch().sprites().collect();
+        assert_eq!(sprites.len(), 5);
+        let oldest = sprites[0];
+        assert!((oldest.position.x - 12.5).abs() < 1e-4, "{oldest:?}");
+        assert!(
+            (oldest.size - 3.0).abs() < 1e-4,
+            "a quarter through its life"
+        );
+        assert!((oldest.color.a - 0.75).abs() < 1e-4);
+
+        emitter.burst(100);
+        assert_eq!(emitter.len(), 8, "capped at max_particles");
+        emitter.advance(100.0);
+        assert_eq!(emitter.len(), 8, "all expired, then refilled by rate");
+        emitter.advance(f32::NAN);
+        assert_eq!(emitter.len(), 8);
+    }
+
+    #[test]
+    fn rejects_invalid_configs_and_keeps_capacity() {
+        assert!(Emitter::new(EmitterConfig {
+            lifetime_ms: 0.0,
+            ..config()
+        })
+        .is_none());
+        assert!(Emitter::new(EmitterConfig {
+            max_particles: 0,
+            ..config()
+        })
+        .is_none());
+        let mut emitter = Emitter::new(config()).unwrap();
+        assert!(!emitter.configure(EmitterConfig {
+            rate: f32::NAN,
+            ..config()
+        }));
+        assert!(emitter.configure(EmitterConfig {
+            max_particles: 1_000,
+            spread: PI,
+            ..config()
+        }));
+        emitter.burst(100);
+        assert_eq!(emitter.len(), 8);
+        assert_eq!(
+            emitter.byte_len(),
+            Emitter::new(config()).unwrap().byte_len()
+        );
+    }
+
+    #[test]
+    fn batches_keep_each_spawns_settings_until_the_emitter_changes() {
+        let config = EmitterConfig {
+            rate: 0.0,
+            ..config()
+        };
+        let mut emitter = Emitter::new(config).unwrap();
+        emitter.burst(2);
+        emitter.burst(1);
+        emitter.advance(10.0);
+        assert!(emitter.configure(EmitterConfig {
+            speed: 0.0,
+            ..config
+        }));
+        emitter.burst(1);
+        let batch = emitter.batch();
+        assert!(
+            Arc::ptr_eq(&batch, &emitter.batch()),
+            "cached until changed"
+        );
+        assert_eq!(batch.configs.len(), 2);
+        assert_eq!(
+            batch.spawns,
+            [
+                Spawn {
+                    first: 0,
+                    count: 3,
+                    age_ms: 10.0,
+                    config: 0,
+                },
+                Spawn {
+                    first: 3,
+                    count: 1,
+                    age_ms: 0.0,
+                    config: 1,
+                },
+            ],
+            "the two bursts merge, and the reconfigured one does not"
+        );
+        let sprites: Vec<_> = batch.sprites().collect();
+        assert!((sprites[0].position.x - 11.0).abs() < 1e-4, "{sprites:?}");
+        assert_eq!(sprites[3].position, Vec2 { x: 10.0, y: 10.0 });
+
+        emitter.advance(1.0);
+        assert!(!Arc::ptr_eq(&batch, &emitter.batch()));
+    }
+
+    #[test]
+    fn drawable_skips_bad_spawns_and_caps_the_total() {
+        let batch = ParticleBatch {
+            seed: 7,
+            configs: vec![config()],
+            spawns: vec![
+                Spawn {
+                    first: 0,
+                    count: 4,
+                    age_ms: 100.0,
+                    config: 0,
+                },
+                Spawn {
+                    first: 4,
+                    count: 4,
+                    age_ms: 0.0,
+                    config: 1,
+                },
+                Spawn {
+                    first: 8,
+                    count: u32::MAX,
+                    age_ms: 0.0,
+                    config: 0,
+                },
+                Spawn {
+                    first: 0,
+                    count: 1,
+                    age_ms: 0.0,
+                    config: 0,
+                },
+            ],
+        };
+        assert_eq!(batch.len(), MAX_PARTICLES as usize);
+        assert_eq!(
+            batch.drawable().count(),
+            1,
+            "expired and unknown settings skipped"
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Compiled GPU pipelines kept on disk between runs, so drivers that leave caching to the
+//! application (wgpu supports this on Vulkan only) do not recompile every shader at
+//! startup. Files live under `<cache>/frontier-wasm/pipelines/`, one per adapter.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use crate::gpu::GpuContext;
+
+pub struct PipelineCacheFile {
+    pub cache: wgpu::PipelineCache,
+    path: PathBuf,
+}
+
+impl PipelineCacheFile {
+    /// Load the cache for this adapter, or start an empty one. `None` when the device or
+    /// backend cannot cache pipelines, or there is no platform cache directory.
+    pub fn open(gpu: &GpuContext) -> Option<Self> {
+        if !gpu
+            .device
+            .features()
+            .contains(wgpu::Features::PIPELINE_CACHE)
+        {
+            return None;
+        }
+        let key = wgpu::util::pipeline_cache_key(&gpu.adapter.get_info())?;
+        let path = dirs::cache_dir()?
+            .join("frontier-wasm")
+            .join("pipelines")
+            .join(key);
+        // A missing or unreadable file just means compiling from scratch this time.
+        let data = fs::read(&path).ok();
+        // SAFETY: the data was written by `save` from `PipelineCache::get_data` for an
+        // adapter with the same key, and wgpu validates it against the driver, falling
+        // back to an empty cache when it does not match.
+        let cache = unsafe {
+            gpu.device
+                .create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                    label: Some("frontier.pipelines"),
+                    data: data.as_deref(),
+                    fallback: true,
+                })
+        };
+        tracing::debug!(path = %path.display(), loaded = data.is_some(), "pipeline cache");
+        Some(Self { cache, path })
+    }
+
+    /// Write the cache out, replacing the file atomically so a crash cannot leave half of
+    /// one behind.
+    pub fn save(&self) -> Result<()> {
+        let Some(data) = self.cache.get_data() else {
+            return Ok(());
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let temp = self.path.with_extension("tmp");
+        fs::write(&temp, data).with_context(|| format!("failed to write {}", temp.display()))?;
+        fs::rename(&temp, &self.path)
+            .with_context(|| format!("failed to replace {}", self.path.display()))
+    }
+}
@@ -0,0 +1,110 @@
+//! Host-owned framebuffers for guests that render in software. The component model gives
+//! the host no view into guest linear memory, so the pixels live here instead: the guest
+//! writes only the rectangles that changed with `write-pixel-buffer`, and presenting an
+//! unchanged buffer costs nothing beyond the draw.
+
+use crate::images::{Image, MAX_IMAGE_DIMENSION};
+
+/// A `width` x `height` straight-alpha RGBA8 surface, initially transparent.
+#[derive(Debug)]
+pub struct PixelBuffer {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+    /// Image of the current contents, built on first present after a write.
+    snapshot: Option<Image>,
+}
+
+impl PixelBuffer {
+    /// `None` unless both sides are in `1..=MAX_IMAGE_DIMENSION`.
+    pub fn new(width: u32, height: u32) -> Option<Self> {
+        if !(1..=MAX_IMAGE_DIMENSION).contains(&width)
+            || !(1..=MAX_IMAGE_DIMENSION).contains(&height)
+        {
+            return None;
+        }
+        Some(Self {
+            width,
+            height,
+            rgba: vec![0; width as usize * height as usize * 4],
+            snapshot: None,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.rgba.len()
+    }
+
+    /// Copy whole rows of `width`-pixel-wide RGBA8 into the rectangle at (`x`, `y`). Fails
+    /// without writing anything unless `pixels` holds a non-zero number of complete rows
+    /// and the rectangle fits inside the buffer.
+    pub fn write(&mut self, x: u32, y: u32, width: u32, pixels: &[u8]) -> bool {
+        let row_bytes = width as usize * 4;
+        if row_bytes == 0 || pixels.is_empty() || !pixels.len().is_multiple_of(row_bytes) {
+            return false;
+        }
+        let rows = pixels.len() / row_bytes;
+        let fits = |start: u32, len: usize, limit: u32| {
+            (start as usize)
+                .checked_add(len)
+                .is_some_and(|end| end <= limit as usize)
+        };
+        if !fits(x, width as usize, self.width) || !fits(y, rows, self.height) {
+            return false;
+        }
+        let stride = self.width as usize * 4;
+        for (row, source) in pixels.chunks_exact(row_bytes).enumerate() {
+            let start = (y as usize + row) * stride + x as usize * 4;
+            self.rgba[start..start + row_bytes].copy_from_slice(source);
+        }
+        self.snapshot = None;
+        true
+    }
+
+    /// The current contents; the same image until the next `write`.
+    pub fn image(&mut self) -> Image {
+        self.snapshot
+            .get_or_insert_with(|| {
+                Image::new(self.width, self.height, self.rgba.clone())
+                    .expect("buffer dimensions were validated")
+            })
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PixelBuffer;
+
+    #[test]
+    fn writes_rectangles_and_reuses_snapshots() {
+        let mut buffer = PixelBuffer::new(3, 2).unwrap();
+        let first = buffer.image();
+        assert_eq!(
+            buffer.image().id(),
+            first.id(),
+            "unchanged buffers share pixels"
+        );
+
+        assert!(buffer.write(1, 0, 2, &[7; 16]), "a 2x2 block in the corner");
+        assert!(!buffer.write(2, 0, 2, &[7; 8]), "past the right edge");
+        assert!(!buffer.write(0, 1, 1, &[7; 8]), "past the bottom edge");
+        assert!(!buffer.write(0, 0, 2, &[7; 6]), "partial row");
+        assert!(!buffer.write(0, 0, 0, &[]), "empty");
+
+        let image = buffer.image();
+        assert_ne!(image.id(), first.id());
+        let rgba = image.to_peniko().data;
+        assert_eq!(rgba.data()[..12], [0, 0, 0, 0, 7, 7, 7, 7, 7, 7, 7, 7]);
+        assert_eq!(rgba.data()[12..16], [0; 4]);
+        assert!(PixelBuffer::new(0, 1).is_none());
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use winit::dpi::PhysicalPosition;
+use winit::event::DeviceId;
+
+use crate::model::{PointerButtons, PointerKind, PointerSample};
+
+/// Where a pointer event came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PointerSource {
+    Mouse(DeviceId),
+    /// One finger on a touch device, identified by winit's per-contact id.
+    Touch(DeviceId, u64),
+    /// Input injected by the control endpoint.
+    Synthetic,
+}
+
+impl PointerSource {
+    pub fn kind(self) -> PointerKind {
+        match self {
+            PointerSource::Touch(..) => PointerKind::Touch,
+            PointerSource::Mouse(_) | PointerSource::Synthetic => PointerKind::Mouse,
+        }
+    }
+}
+
+/// Position and button state of one pointer, plus the id the guest sees for it.
+#[derive(Clone, Copy, Debug)]
+pub struct PointerState {
+    pub id: u64,
+    pub position: PhysicalPosition<f64>,
+    pub buttons: PointerButtons,
+}
+
+/// Hands out stable guest pointer ids so two mice, or a touchscreen and a mouse, stay
+/// distinct. The first pointer seen gets id 0; ids are never reused.
+#[derive(Debug)]
+pub struct PointerTracker<K = PointerSource> {
+    pointers: HashMap<K, PointerState>,
+    next_id: u64,
+}
+
+impl<K> Default for PointerTracker<K> {
+    fn default() -> Self {
+        Self {
+            pointers: HashMap::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<K: Eq + Hash> PointerTracker<K> {
+    /// State for `source`, allocating a new pointer id the first time it is seen.
+    pub fn get(&mut self, source: K) -> &mut PointerState {
+        let next_id = &mut self.next_id;
+        self.pointers.entry(source).or_insert_with(|| {
+            let id = *next_id;
+            *next_id += 1;
+            PointerState {
+                id,
+                position: PhysicalPosition::new(0.0, 0.0),
+                buttons: PointerButtons::default(),
+            }
+        })
+    }
+
+    /// Forget a pointer, e.g. a finger that left the screen.
+    pub fn remove(&mut self, source: &K) -> Option<PointerState> {
+        self.pointers.remove(source)
+    }
+}
+
+/// Raw samples kept per pointer between flushes; older ones are dropped but still counted.
+pub const MAX_POINTER_HISTORY: usize = 128;
+
+/// Pointer moves held back by coalescing: one entry per pointer, in the order each pointer
+/// first moved, with how many raw moves it stands for and the latest of their samples.
+#[derive(Debug)]
+pub struct PendingMoves<K = PointerSource> {
+    moves: Vec<(K, u32, VecDeque<PointerSample>)>,
+}
+
+impl<K> Default for PendingMoves<K> {
+    fn default() -> Self {
+        Self { moves: Vec::new() }
+    }
+}
+
+impl<K: PartialEq> PendingMoves<K> {
+    pub fn push(&mut self, source: K, sample: PointerSample) {
+        match self.moves.iter_mut().find(|(s, ..)| *s == source) {
+            Some((_, count, samples)) => {
+                *count = count.saturating_add(1);
+                if samples.len() == MAX_POINTER_HISTORY {
+                    samples.pop_front();
+                }
+                samples.push_back(sample);
+            }
+            None => self.moves.push((source, 1, VecDeque::from([sample]))),
+        }
+    }
+
+    /// Each pending pointer with its raw move count and retained samples, oldest first.
+    pub fn take(&mut self) -> Vec<(K, u32, Vec<PointerSample>)> {
+        std::mem::take(&mut self.moves)
+            .into_iter()
+            .map(|(source, count, samples)| (source, count, samples.into()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PendingMoves, PointerTracker, MAX_POINTER_HISTORY};
+    use crate::geometry::Vec2;
+    use crate::model::PointerSample;
+
+    #[test]
+    fn allocates_stable_ids_per_source() {
+        let mut tracker = PointerTracker::<u32>::default();
+        assert_eq!(tracker.get(7).id, 0);
+        assert_eq!(tracker.get(3).id, 1);
+        tracker.get(7).buttons.primary = true;
+        assert_eq!(tracker.get(7).id, 0);
+        assert!(tracker.get(7).buttons.primary);
+        assert!(!tracker.get(3).buttons.primary);
+
+        tracker.remove(&3);
+        assert_eq!(tracker.get(3).id, 2, "ids are not reused");
+    }
+
+    #[test]
+    fn coalesces_moves_per_source_in_arrival_order() {
+        let sample = |x: f32| PointerSample {
+            position: Vec2::new(x, 0.0),
+            time_ms: x as f64,
+        };
+        let mut pending = PendingMoves::<u32>::default();
+        pending.push(5, sample(1.0));
+        pending.push(2, sample(2.0));
+        pending.push(5, sample(3.0));
+        pending.push(5, sample(4.0));
+        assert_eq!(
+            pending.take(),
+            vec![
+                (5, 3, vec![sample(1.0), sample(3.0), sample(4.0)]),
+                (2, 1, vec![sample(2.0)])
+            ]
+        );
+        assert!(pending.take().is_empty());
+    }
+
+    #[test]
+    fn keeps_only_the_latest_samples_but_counts_every_move() {
+        let sample = |x: f32| PointerSample {
+            position: Vec2::new(x, 0.0),
+            time_ms: x as f64,
+        };
+        let mut pending = PendingMoves::<u32>::default();
+        let moves = MAX_POINTER_HISTORY + 10;
+        for i in 0..moves {
+            pending.push(1, sample(i as f32));
+        }
+        let (_, count, history) = pending.take().remove(0);
+        assert_eq!(count, moves as u32);
+        assert_eq!(history.len(), MAX_POINTER_HISTORY);
+        assert_eq!(history[0], sample(10.0));
+        assert_eq!(history.last(), Some(&sample((moves - 1) as f32)));
+    }
+}
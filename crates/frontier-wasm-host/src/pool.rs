@@ -0,0 +1,141 @@
+//! A fixed set of host threads for work that would otherwise take a thread each: the
+//! background tasks guests start with `spawn-task`, and the components `compile` builds.
+//! `--worker-threads` (or `worker-threads` in the `[engine]` table) caps how many run at
+//! once; the rest wait their turn.
+//!
+//! A task's instance runs entirely on the pool thread that picked it up, but it can never
+//! draw: draw calls are only accepted from the main guest thread during `frame` and event
+//! handlers. Components cannot share linear memory yet, so `wasm32-wasip1-threads` guests
+//! cannot spawn threads of their own; the threads proposal only gives them atomics.
+
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use anyhow::{anyhow, Context, Result};
+
+/// Upper bound on the default size; more threads rarely help the host's own work.
+const MAX_DEFAULT_WORKERS: usize = 8;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+pub struct WorkerPool {
+    sender: mpsc::Sender<Job>,
+    size: NonZeroUsize,
+}
+
+impl WorkerPool {
+    /// One thread per core, at most [`MAX_DEFAULT_WORKERS`].
+    pub fn default_size() -> NonZeroUsize {
+        let cores = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        NonZeroUsize::new(cores.min(MAX_DEFAULT_WORKERS)).unwrap_or(NonZeroUsize::MIN)
+    }
+
+    pub fn new(size: NonZeroUsize) -> Result<Self> {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..size.get() {
+            let receiver = Arc::clone(&receiver);
+            thread::Builder::new()
+                .name(format!("frontier-worker-{index}"))
+                .spawn(move || loop {
+                    // Hold the lock only while taking a job, not while running it.
+                    let job = match receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => return,
+                    };
+                    let Ok(job) = job else { return };
+                    if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+                        tracing::error!("worker job panicked");
+                    }
+                })
+                .context("failed to start worker thread")?;
+        }
+        Ok(Self { sender, size })
+    }
+
+    pub fn size(&self) -> NonZeroUsize {
+        self.size
+    }
+
+    /// Run `job` on the next free thread. A panicking job is logged; the thread survives.
+    /// Dropping the pool lets queued jobs finish without waiting for them.
+    pub fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        // Threads only exit once the sender is dropped, so this cannot fail.
+        let _ = self.sender.send(Box::new(job));
+    }
+
+    /// Apply `f` to every item across the pool and wait for all of them; results keep
+    /// the order of `items`.
+    pub fn map<T, R, F>(&self, items: Vec<T>, f: F) -> Result<Vec<R>>
+    where
+        T: Send + 'static,
+        R: Send + 'static,
+        F: Fn(T) -> R + Send + Sync + 'static,
+    {
+        let count = items.len();
+        let f = Arc::new(f);
+        let (sender, receiver) = mpsc::channel();
+        for (index, item) in items.into_iter().enumerate() {
+            let f = Arc::clone(&f);
+            let sender = sender.clone();
+            self.execute(move || {
+                let _ = sender.send((index, f(item)));
+            });
+        }
+        drop(sender);
+        let mut results: Vec<Option<R>> = (0..count).map(|_| None).collect();
+        for (index, result) in receiver {
+            results[index] = Some(result);
+        }
+        results
+            .into_iter()
+            .map(|result| result.ok_or_else(|| anyhow!("worker job panicked")))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::WorkerPool;
+
+    #[test]
+    fn runs_jobs_on_at_most_size_threads() {
+        let pool = WorkerPool::new(NonZeroUsize::new(2).unwrap()).unwrap();
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let squares = pool
+            .map((0..8u64).collect(), {
+                let running = Arc::clone(&running);
+                let peak = Arc::clone(&peak);
+                move |n| {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(5));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    n * n
+                }
+            })
+            .unwrap();
+        assert_eq!(squares, [0, 1, 4, 9, 16, 25, 36, 49]);
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert!(pool.map(Vec::<u8>::new(), |n| n).unwrap().is_empty());
+    }
+
+    #[test]
+    fn survives_panicking_jobs() {
+        let pool = WorkerPool::new(NonZeroUsize::MIN).unwrap();
+        let result = pool.map(vec![1, 0, 2], |n: u32| {
+            assert!(n != 0, "zero");
+            n
+        });
+        assert!(result.is_err());
+        assert_eq!(pool.map(vec![3], |n: u32| n + 1).unwrap(), [4]);
+    }
+}
@@ -0,0 +1,187 @@
+//! Battery and power-saver state for `power-state`, and the `--power-save` frame cap.
+//! Read from sysfs on Linux; other platforms report nothing.
+
+use std::path::Path;
+use std::time::Duration;
+
+/// Whether this build can read the power state at all.
+pub const SUPPORTED: bool = cfg!(target_os = "linux");
+
+/// How often the window re-reads the power state.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Frame rate cap applied while saving power.
+pub const POWER_SAVE_FPS: u32 = 30;
+
+/// What the host knows about the machine's power supply.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PowerState {
+    /// A system battery is discharging.
+    pub on_battery: bool,
+    /// Charge of the system batteries, averaged when there are several.
+    pub battery_percent: Option<u8>,
+    /// The platform power profile asks for low power.
+    pub power_saver: bool,
+}
+
+impl PowerState {
+    /// The current state, or `None` when the platform does not report one.
+    pub fn read() -> Option<Self> {
+        if SUPPORTED {
+            Self::read_sysfs(Path::new("/sys"))
+        } else {
+            None
+        }
+    }
+
+    /// Read batteries from `sys/class/power_supply` and the ACPI platform profile. Device
+    /// batteries (mice, headsets) are skipped; only system batteries count.
+    fn read_sysfs(sys: &Path) -> Option<Self> {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path)
+                .ok()
+                .map(|text| text.trim().to_string())
+        };
+        let supplies = std::fs::read_dir(sys.join("class/power_supply")).ok()?;
+        let mut on_battery = false;
+        let mut capacities = Vec::new();
+        for supply in supplies.flatten() {
+            let dir = supply.path();
+            if read(&dir.join("type")).as_deref() != Some("Battery")
+                || read(&dir.join("scope")).as_deref() == Some("Device")
+            {
+                continue;
+            }
+            on_battery |= read(&dir.join("status")).as_deref() == Some("Discharging");
+            if let Some(capacity) = read(&dir.join("capacity")).and_then(|text| text.parse().ok()) {
+                capacities.push(u32::min(capacity, 100));
+            }
+        }
+        let battery_percent = (!capacities.is_empty()).then(|| {
+            let total: u32 = capacities.iter().sum();
+            (total as f32 / capacities.len() as f32).round() as u8
+        });
+        let power_saver =
+            read(&sys.join("firmware/acpi/platform_profile")).as_deref() == Some("low-power");
+        Some(Self {
+            on_battery,
+            battery_percent,
+            power_saver,
+        })
+    }
+}
+
+/// When the host caps guest frame rates to save power.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum PowerSave {
+    /// Never cap; the guest's own frame requests decide.
+    #[default]
+    Off,
+    /// Cap to [`POWER_SAVE_FPS`] while on battery or in power-saver mode.
+    Auto,
+    /// Always cap, e.g. to preview how a guest behaves on battery.
+    On,
+}
+
+impl PowerSave {
+    /// Shortest time between guest frames under this policy, or `None` for no cap.
+    pub fn frame_interval(self, state: Option<&PowerState>) -> Option<Duration> {
+        let capped = match self {
+            PowerSave::Off => false,
+            PowerSave::Auto => state.is_some_and(|state| state.on_battery || state.power_saver),
+            PowerSave::On => true,
+        };
+        capped.then(|| Duration::from_secs(1) / POWER_SAVE_FPS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::time::Duration;
+
+    use super::{PowerSave, PowerState};
+
+    fn supply(sys: &Path, name: &str, files: &[(&str, &str)]) {
+        let dir = sys.join("class/power_supply").join(name);
+        fs::create_dir_all(&dir).unwrap();
+        for (file, contents) in files {
+            fs::write(dir.join(file), format!("{contents}\n")).unwrap();
+        }
+    }
+
+    #[test]
+    fn reads_system_batteries_and_platform_profile() {
+        let sys = std::env::temp_dir().join(format!("frontier-power-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&sys);
+        assert_eq!(PowerState::read_sysfs(&sys), None);
+
+        supply(&sys, "AC", &[("type", "Mains"), ("online", "0")]);
+        supply(
+            &sys,
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("status", "Discharging"),
+                ("capacity", "40"),
+            ],
+        );
+        supply(
+            &sys,
+            "BAT1",
+            &[
+                ("type", "Battery"),
+                ("status", "Unknown"),
+                ("capacity", "61"),
+            ],
+        );
+        supply(
+            &sys,
+            "hidpp_battery_0",
+            &[
+                ("type", "Battery"),
+                ("scope", "Device"),
+                ("status", "Discharging"),
+                ("capacity", "5"),
+            ],
+        );
+        assert_eq!(
+            PowerState::read_sysfs(&sys),
+            Some(PowerState {
+                on_battery: true,
+                battery_percent: Some(51),
+                power_saver: false,
+            })
+        );
+
+        supply(&sys, "BAT0", &[("status", "Charging")]);
+        fs::create_dir_all(sys.join("firmware/acpi")).unwrap();
+        fs::write(sys.join("firmware/acpi/platform_profile"), "low-power\n").unwrap();
+        let state = PowerState::read_sysfs(&sys).unwrap();
+        assert!(!state.on_battery);
+        assert!(state.power_saver);
+
+        fs::remove_dir_all(&sys).unwrap();
+    }
+
+    #[test]
+    fn caps_frames_only_when_the_policy_applies() {
+        let cap = Some(Duration::from_secs(1) / 30);
+        let battery = PowerState {
+            on_battery: true,
+            ..PowerState::default()
+        };
+        let saver = PowerState {
+            power_saver: true,
+            ..PowerState::default()
+        };
+        let plugged_in = PowerState::default();
+        assert_eq!(PowerSave::Off.frame_interval(Some(&battery)), None);
+        assert_eq!(PowerSave::Auto.frame_interval(Some(&battery)), cap);
+        assert_eq!(PowerSave::Auto.frame_interval(Some(&saver)), cap);
+        assert_eq!(PowerSave::Auto.frame_interval(Some(&plugged_in)), None);
+        assert_eq!(PowerSave::Auto.frame_interval(None), None);
+        assert_eq!(PowerSave::On.frame_interval(None), cap);
+    }
+}
@@ -0,0 +1,117 @@
+//! Ahead-of-time compilation for `frontier-wasm-host compile`: turns components into
+//! `.cwasm` artifacts the host loads without running Cranelift, so kiosk images and CI
+//! skip JIT time at startup. Several inputs are compiled in parallel on the worker pool.
+//!
+//! An artifact only loads into a host built from the same Wasmtime version, and with
+//! `--target`, only on that target.
+
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use wasmtime::Engine;
+
+use crate::engine::EngineOptions;
+use crate::pool::WorkerPool;
+
+pub const EXTENSION: &str = "cwasm";
+
+/// Whether the host should load `path` as a precompiled artifact.
+pub fn is_precompiled(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == EXTENSION)
+}
+
+/// Compile each of `inputs`, writing `output` when given (only valid for one input) and
+/// `INPUT.cwasm` next to each input otherwise. `target` is a target triple for
+/// cross-compiling, e.g. `aarch64-unknown-linux-gnu`. Returns the files written.
+pub fn compile_all(
+    inputs: &[PathBuf],
+    output: Option<&Path>,
+    target: Option<&str>,
+    engine: &EngineOptions,
+) -> Result<Vec<PathBuf>> {
+    if output.is_some() && inputs.len() > 1 {
+        bail!("-o can only be used with a single input");
+    }
+    let workers = engine.worker_threads();
+    let mut config = engine.config();
+    if let Some(target) = target {
+        config
+            .target(target)
+            .with_context(|| format!("unsupported target '{target}'"))?;
+    }
+    let engine = Engine::new(&config).context("failed to initialise Wasmtime engine")?;
+
+    let jobs: Vec<(PathBuf, PathBuf)> = inputs
+        .iter()
+        .map(|input| {
+            let output = output
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| input.with_extension(EXTENSION));
+            (input.clone(), output)
+        })
+        .collect();
+    // No more threads than inputs, but always one, so an empty list still succeeds.
+    let size = NonZeroUsize::new(jobs.len()).map_or(NonZeroUsize::MIN, |jobs| jobs.min(workers));
+    WorkerPool::new(size)?
+        .map(jobs, move |(input, output)| {
+            compile(&engine, &input, &output).map(|()| output)
+        })?
+        .into_iter()
+        .collect()
+}
+
+fn compile(engine: &Engine, input: &Path, output: &Path) -> Result<()> {
+    if is_precompiled(input) {
+        bail!("{} is already precompiled", input.display());
+    }
+    let bytes =
+        std::fs::read(input).with_context(|| format!("failed to read {}", input.display()))?;
+    let compiled = engine
+        .precompile_component(&bytes)
+        .with_context(|| format!("failed to compile {}", input.display()))?;
+    std::fs::write(output, compiled)
+        .with_context(|| format!("failed to write {}", output.display()))?;
+    tracing::info!(input = %input.display(), output = %output.display(), "precompiled component");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::compile_all;
+    use crate::engine::EngineOptions;
+    use crate::model::LogicalSize;
+    use crate::runtime::{ComponentRuntime, ComponentSource};
+
+    #[test]
+    fn precompiled_components_load_and_run() {
+        let dir = std::env::temp_dir().join(format!("frontier-precompile-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("counter.wasm");
+        std::fs::copy(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../../assets/counter-component.wasm"),
+            &input,
+        )
+        .unwrap();
+
+        let engine = EngineOptions::default();
+        assert!(compile_all(&[input.clone(), input.clone()], Some(&dir), None, &engine).is_err());
+        assert!(compile_all(
+            std::slice::from_ref(&input),
+            None,
+            Some("not-a-target"),
+            &engine
+        )
+        .is_err());
+        let written = compile_all(std::slice::from_ref(&input), None, None, &engine).unwrap();
+        assert_eq!(written, [dir.join("counter.cwasm")]);
+        assert!(compile_all(&written, None, None, &engine).is_err());
+        assert!(compile_all(&[], None, None, &engine).unwrap().is_empty());
+
+        let mut runtime = ComponentRuntime::new(ComponentSource::from_path(&written[0])).unwrap();
+        runtime.call_init(LogicalSize::default()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
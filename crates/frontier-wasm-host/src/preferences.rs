@@ -0,0 +1,186 @@
+//! Display preferences reported by `get-preferences`, and the `--force-colors` palette the
+//! renderer maps guest colours onto so high-contrast users get a readable window even
+//! from guests that ignore the preference.
+//!
+//! High contrast is read once at startup: the `org.gnome.desktop.a11y.interface` setting
+//! or a `HighContrast` GTK theme on Linux, the "Increase contrast" accessibility option
+//! on macOS, and the `HighContrast` flags in the registry on Windows.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::component::vello::canvas::host::Preferences as WitPreferences;
+use crate::geometry::Vec2;
+use crate::host::Color;
+
+/// Fills this thin or thinner, in logical pixels, are lines and borders and take the text
+/// colour under forced colours rather than the background.
+const LINE_THICKNESS: f32 = 2.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Preferences {
+    pub high_contrast: bool,
+    pub forced_colors: bool,
+}
+
+impl Preferences {
+    pub fn detect() -> Self {
+        Self {
+            high_contrast: detect_high_contrast(),
+            forced_colors: false,
+        }
+    }
+
+    pub fn to_wit(self) -> WitPreferences {
+        WitPreferences {
+            high_contrast: self.high_contrast,
+            forced_colors: self.forced_colors,
+        }
+    }
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn detect_high_contrast() -> bool {
+    let gtk_theme = std::env::var("GTK_THEME").unwrap_or_default();
+    gtk_theme.to_ascii_lowercase().contains("highcontrast")
+        || command_output(
+            "gsettings",
+            &["get", "org.gnome.desktop.a11y.interface", "high-contrast"],
+        )
+        .is_some_and(|value| value == "true")
+}
+
+#[cfg(target_os = "macos")]
+fn detect_high_contrast() -> bool {
+    command_output(
+        "defaults",
+        &["read", "com.apple.universalaccess", "increaseContrast"],
+    )
+    .is_some_and(|value| value == "1")
+}
+
+#[cfg(target_os = "windows")]
+fn detect_high_contrast() -> bool {
+    // `Flags    REG_SZ    126`; bit 0 is HCF_HIGHCONTRASTON.
+    command_output(
+        "reg",
+        &[
+            "query",
+            r"HKCU\Control Panel\Accessibility\HighContrast",
+            "/v",
+            "Flags",
+        ],
+    )
+    .and_then(|output| {
+        output
+            .split_whitespace()
+            .last()
+            .and_then(|flags| flags.parse::<u32>().ok())
+    })
+    .is_some_and(|flags| flags & 1 != 0)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn detect_high_contrast() -> bool {
+    let _ = command_output;
+    false
+}
+
+/// The palette guest colours are forced onto. Large fills and the clear colour become
+/// `background`; text, lines, borders and particles become `text`. Images, pictures and
+/// animations are left alone, as browsers do in forced-colors mode. Guest alpha is kept.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ForcedColors {
+    pub background: Color,
+    pub text: Color,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PaletteFile {
+    background: String,
+    text: String,
+}
+
+impl ForcedColors {
+    /// Read a palette file with CSS colours for `background` and `text`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read palette {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid palette {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: PaletteFile = toml::from_str(text)?;
+        Ok(Self {
+            background: Color::parse(&file.background)?,
+            text: Color::parse(&file.text)?,
+        })
+    }
+
+    pub fn clear(&self) -> Color {
+        self.background
+    }
+
+    pub fn fill(&self, color: Color, size: Vec2) -> Color {
+        if size.x.abs().min(size.y.abs()) <= LINE_THICKNESS {
+            Self::with_alpha(self.text, color)
+        } else {
+            Self::with_alpha(self.background, color)
+        }
+    }
+
+    pub fn text(&self, color: Color) -> Color {
+        Self::with_alpha(self.text, color)
+    }
+
+    fn with_alpha(palette: Color, guest: Color) -> Color {
+        Color {
+            a: palette.a * guest.a,
+            ..palette
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ForcedColors;
+    use crate::geometry::Vec2;
+    use crate::host::Color;
+
+    #[test]
+    fn forces_fills_by_shape_and_keeps_guest_alpha() {
+        let palette = ForcedColors::parse(
+            r##"
+            background = "black"
+            text = "#ffff00"
+            "##,
+        )
+        .unwrap();
+        let white = Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 0.5,
+        };
+        let panel = palette.fill(white, Vec2::new(200.0, 80.0));
+        assert_eq!((panel.r, panel.g, panel.b, panel.a), (0.0, 0.0, 0.0, 0.5));
+        let border = palette.fill(white, Vec2::new(200.0, 1.0));
+        assert_eq!((border.r, border.g, border.b), (1.0, 1.0, 0.0));
+        assert_eq!(palette.text(white).a, 0.5);
+
+        assert!(ForcedColors::parse("background = \"black\"").is_err());
+        assert!(ForcedColors::parse("background = \"black\"\ntext = \"nope\"").is_err());
+    }
+}
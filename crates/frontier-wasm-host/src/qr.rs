@@ -0,0 +1,576 @@
+//! QR codes for `draw-qr`, encoded on the host so pairing and login screens need no QR
+//! library of their own, and drawn as one image with a pixel per module rather than a
+//! rectangle per module.
+//!
+//! Data is encoded in byte mode at the smallest version (1 to 40) that holds it with
+//! medium error correction, then at the strongest correction level that still fits that
+//! version. The construction follows ISO/IEC 18004 as laid out in Project Nayuki's
+//! reference encoder.
+
+use crate::images::Image;
+
+/// Light modules around the code, which scanners need to find its edges.
+pub const QUIET_ZONE: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCorrection {
+    Low,
+    Medium,
+    Quartile,
+    High,
+}
+
+impl ErrorCorrection {
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The two bits stored in the format information.
+    fn format_bits(self) -> u32 {
+        match self {
+            Self::Low => 1,
+            Self::Medium => 0,
+            Self::Quartile => 3,
+            Self::High => 2,
+        }
+    }
+}
+
+/// Error correction codewords in each block, by level and version.
+#[rustfmt::skip]
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28, 30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+    [0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28],
+    [0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30, 30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+    [0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+];
+
+/// Error correction blocks, by level and version.
+#[rustfmt::skip]
+const ERROR_CORRECTION_BLOCKS: [[u8; 41]; 4] = [
+    [0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25],
+    [0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49],
+    [0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29, 34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68],
+    [0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32, 35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81],
+];
+
+/// Weights of the mask penalty rules: runs of one colour, 2x2 blocks, finder-like
+/// patterns, and imbalance between dark and light.
+const PENALTY_RUN: i32 = 3;
+const PENALTY_BLOCK: i32 = 3;
+const PENALTY_FINDER: i32 = 40;
+const PENALTY_BALANCE: i32 = 10;
+
+/// A square grid of dark and light modules.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QrCode {
+    size: i32,
+    modules: Vec<bool>,
+    /// Modules of the finder, timing, alignment, format and version patterns, which data
+    /// and masks leave alone.
+    function: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode `data`, or `None` when it is longer than the 2331 bytes a version 40 code
+    /// holds with medium error correction.
+    pub fn encode(data: &[u8]) -> Option<Self> {
+        let (version, count_bits) = (1..=40).find_map(|version| {
+            let count_bits = if version < 10 { 8 } else { 16 };
+            let bits = 4 + count_bits + data.len() * 8;
+            (data.len() < 1 << count_bits
+                && bits <= data_codewords(version, ErrorCorrection::Medium) * 8)
+                .then_some((version, count_bits))
+        })?;
+        let needed = 4 + count_bits + data.len() * 8;
+        let ecl = [ErrorCorrection::High, ErrorCorrection::Quartile]
+            .into_iter()
+            .find(|&ecl| needed <= data_codewords(version, ecl) * 8)
+            .unwrap_or(ErrorCorrection::Medium);
+
+        let mut bits = Bits::default();
+        bits.push(0b0100, 4);
+        bits.push(data.len() as u32, count_bits);
+        for &byte in data {
+            bits.push(byte.into(), 8);
+        }
+        let capacity = data_codewords(version, ecl) * 8;
+        bits.push(0, (capacity - bits.len).min(4));
+        bits.push(0, (8 - bits.len % 8) % 8);
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if bits.len >= capacity {
+                break;
+            }
+            bits.push(pad, 8);
+        }
+
+        let codewords = add_error_correction(&bits.bytes, version, ecl);
+        let mut code = Self::empty(version);
+        code.draw_function_patterns(version);
+        code.draw_codewords(&codewords);
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                code.apply_mask(mask);
+                code.draw_format_bits(ecl, mask);
+                let penalty = code.penalty();
+                code.apply_mask(mask);
+                penalty
+            })
+            .unwrap_or(0);
+        code.apply_mask(mask);
+        code.draw_format_bits(ecl, mask);
+        Some(code)
+    }
+
+    /// Modules along each side, without the quiet zone.
+    pub fn size(&self) -> usize {
+        self.size as usize
+    }
+
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size() + x]
+    }
+
+    /// Black modules on white, one pixel each, inside a [`QUIET_ZONE`] border.
+    pub fn to_image(&self) -> Option<Image> {
+        let side = self.size() + 2 * QUIET_ZONE;
+        let mut rgba = Vec::with_capacity(side * side * 4);
+        for y in 0..side {
+            for x in 0..side {
+                let dark = (QUIET_ZONE..QUIET_ZONE + self.size()).contains(&x)
+                    && (QUIET_ZONE..QUIET_ZONE + self.size()).contains(&y)
+                    && self.is_dark(x - QUIET_ZONE, y - QUIET_ZONE);
+                let value = if dark { 0 } else { 255 };
+                rgba.extend([value, value, value, 255]);
+            }
+        }
+        Image::new(side as u32, side as u32, rgba)
+    }
+
+    fn empty(version: usize) -> Self {
+        let size = version as i32 * 4 + 17;
+        let modules = vec![false; (size * size) as usize];
+        Self {
+            size,
+            function: modules.clone(),
+            modules,
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> usize {
+        (y * self.size + x) as usize
+    }
+
+    fn module(&self, x: i32, y: i32) -> bool {
+        self.modules[self.index(x, y)]
+    }
+
+    fn set_function(&mut self, x: i32, y: i32, dark: bool) {
+        let index = self.index(x, y);
+        self.modules[index] = dark;
+        self.function[index] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4..=4 {
+                for dx in -4..=4 {
+                    let (xx, yy) = (x + dx, y + dy);
+                    if (0..size).contains(&xx) && (0..size).contains(&yy) {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(xx, yy, distance != 2 && distance != 4);
+                    }
+                }
+            }
+        }
+        let positions = alignment_positions(version, size);
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // The three corners already hold finder patterns.
+                if (i, j) == (0, 0) || (i, j) == (0, last) || (i, j) == (last, 0) {
+                    continue;
+                }
+                for dy in -2..=2 {
+                    for dx in -2..=2 {
+                        self.set_function(x + dx, y + dy, dx.abs().max(dy.abs()) != 1);
+                    }
+                }
+            }
+        }
+        // Reserve the format modules now; the real bits go in once the mask is chosen.
+        self.draw_format_bits(ErrorCorrection::Medium, 0);
+        if version >= 7 {
+            let mut remainder = version as u32;
+            for _ in 0..12 {
+                remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1F25);
+            }
+            let bits = (version as u32) << 12 | remainder;
+            for i in 0..18 {
+                let dark = bits >> i & 1 != 0;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, ecl: ErrorCorrection, mask: u32) {
+        let data = ecl.format_bits() << 3 | mask;
+        let mut remainder = data;
+        for _ in 0..10 {
+            remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        }
+        let bits = (data << 10 | remainder) ^ 0x5412;
+        let bit = |i: i32| bits >> i & 1 != 0;
+        let size = self.size;
+        for i in 0..6 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        // Always dark.
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Fill the non-function modules with `codewords` in the zigzag order: pairs of
+    /// columns from the right, alternately upwards and downwards.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let mut bit = 0;
+        let mut right = self.size - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..self.size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward {
+                        self.size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    let index = self.index(x, y);
+                    if !self.function[index] && bit < codewords.len() * 8 {
+                        self.modules[index] = codewords[bit / 8] >> (7 - bit % 8) & 1 != 0;
+                        bit += 1;
+                    }
+                }
+            }
+            right -= 2;
+        }
+    }
+
+    /// XOR the data modules with mask pattern `mask`; applying it twice undoes it.
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = self.index(x, y);
+                self.modules[index] ^= invert && !self.function[index];
+            }
+        }
+    }
+
+    /// How hard the code is to scan, by the standard's rules; the lowest-scoring mask wins.
+    fn penalty(&self) -> i32 {
+        let size = self.size;
+        let mut result = 0;
+        for transposed in [false, true] {
+            for a in 0..size {
+                let mut history = FinderHistory::new(size);
+                let (mut run_dark, mut run) = (false, 0);
+                for b in 0..size {
+                    let dark = match transposed {
+                        false => self.module(b, a),
+                        true => self.module(a, b),
+                    };
+                    if dark == run_dark {
+                        run += 1;
+                        if run == 5 {
+                            result += PENALTY_RUN;
+                        } else if run > 5 {
+                            result += 1;
+                        }
+                    } else {
+                        history.push(run);
+                        if !run_dark {
+                            result += history.count_patterns() * PENALTY_FINDER;
+                        }
+                        run_dark = dark;
+                        run = 1;
+                    }
+                }
+                result += history.finish(run_dark, run) * PENALTY_FINDER;
+            }
+        }
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let dark = self.module(x, y);
+                if dark == self.module(x + 1, y)
+                    && dark == self.module(x, y + 1)
+                    && dark == self.module(x + 1, y + 1)
+                {
+                    result += PENALTY_BLOCK;
+                }
+            }
+        }
+        let dark = self.modules.iter().filter(|&&dark| dark).count() as i32;
+        let total = size * size;
+        // Steps of 5% away from half dark, rounded up.
+        let steps = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        result + steps * PENALTY_BALANCE
+    }
+}
+
+/// The last seven run lengths of a row or column, for spotting the 1:1:3:1:1 ratio of a
+/// finder pattern with light space on one side.
+struct FinderHistory {
+    size: i32,
+    runs: [i32; 7],
+}
+
+impl FinderHistory {
+    fn new(size: i32) -> Self {
+        Self { size, runs: [0; 7] }
+    }
+
+    fn push(&mut self, mut run: i32) {
+        // The light border before the first module counts as part of the first run.
+        if self.runs[0] == 0 {
+            run += self.size;
+        }
+        self.runs.copy_within(0..6, 1);
+        self.runs[0] = run;
+    }
+
+    fn count_patterns(&self) -> i32 {
+        let runs = &self.runs;
+        let n = runs[1];
+        let core = n > 0 && runs[2] == n && runs[3] == n * 3 && runs[4] == n && runs[5] == n;
+        i32::from(core && runs[0] >= n * 4 && runs[6] >= n)
+            + i32::from(core && runs[6] >= n * 4 && runs[0] >= n)
+    }
+
+    /// Close the line, treating the light border after it as one more run.
+    fn finish(mut self, run_dark: bool, mut run: i32) -> i32 {
+        if run_dark {
+            self.push(run);
+            run = 0;
+        }
+        self.push(run + self.size);
+        self.count_patterns()
+    }
+}
+
+/// Bits appended most significant first.
+#[derive(Default)]
+struct Bits {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl Bits {
+    fn push(&mut self, value: u32, count: usize) {
+        for i in (0..count).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if value >> i & 1 != 0 {
+                let last = self.bytes.len() - 1;
+                self.bytes[last] |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+}
+
+/// Centre coordinates of the alignment patterns along each axis.
+fn alignment_positions(version: usize, size: i32) -> Vec<i32> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let count = version as i32 / 7 + 2;
+    let step = (version as i32 * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+    let mut positions: Vec<i32> = (0..count - 1).map(|i| size - 7 - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// Modules left for data and error correction once the function patterns are drawn.
+fn raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        result -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+fn data_codewords(version: usize, ecl: ErrorCorrection) -> usize {
+    raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[ecl.index()][version] as usize
+            * ERROR_CORRECTION_BLOCKS[ecl.index()][version] as usize
+}
+
+/// Split `data` into blocks, append each block's Reed-Solomon codewords, and interleave
+/// the blocks column by column.
+fn add_error_correction(data: &[u8], version: usize, ecl: ErrorCorrection) -> Vec<u8> {
+    let blocks = ERROR_CORRECTION_BLOCKS[ecl.index()][version] as usize;
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[ecl.index()][version] as usize;
+    let raw = raw_data_modules(version) / 8;
+    let short_blocks = blocks - raw % blocks;
+    let short_len = raw / blocks;
+    let divisor = reed_solomon_divisor(ecc_len);
+    let mut rest = data;
+    let filled: Vec<Vec<u8>> = (0..blocks)
+        .map(|i| {
+            let len = short_len - ecc_len + usize::from(i >= short_blocks);
+            let (block_data, tail) = rest.split_at(len);
+            rest = tail;
+            let mut block = block_data.to_vec();
+            // Short blocks get a placeholder so every block is the same length; it is
+            // skipped when interleaving.
+            if i < short_blocks {
+                block.push(0);
+            }
+            block.extend(reed_solomon_remainder(block_data, &divisor));
+            block
+        })
+        .collect();
+    let mut result = Vec::with_capacity(raw);
+    for i in 0..filled[0].len() {
+        for (j, block) in filled.iter().enumerate() {
+            if i != short_len - ecc_len || j >= short_blocks {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+/// Coefficients of the generator polynomial of `degree`, highest power first, leading 1
+/// omitted.
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0; degree];
+    result[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (value, &coefficient) in result.iter_mut().zip(divisor) {
+            *value ^= gf_multiply(coefficient, factor);
+        }
+    }
+    result
+}
+
+/// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1.
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u8 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x1D);
+        z ^= ((y >> i) & 1) * x;
+    }
+    z
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        data_codewords, reed_solomon_divisor, reed_solomon_remainder, ErrorCorrection, QrCode,
+        QUIET_ZONE,
+    };
+
+    #[test]
+    fn error_correction_matches_the_standard() {
+        // "HELLO WORLD" as version 1-M alphanumeric data, from the standard's worked example.
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        assert_eq!(
+            reed_solomon_remainder(&data, &reed_solomon_divisor(10)),
+            [196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+        assert_eq!(data_codewords(1, ErrorCorrection::Medium), 16);
+        assert_eq!(data_codewords(40, ErrorCorrection::Low), 2956);
+        assert_eq!(data_codewords(40, ErrorCorrection::Medium), 2334);
+    }
+
+    #[test]
+    fn encodes_finder_patterns_format_bits_and_size_limits() {
+        let code = QrCode::encode(b"https://example.com/pair?code=1234").unwrap();
+        assert_eq!(code.size(), 29, "version 3");
+        // Finder patterns: a dark ring, a light ring and a dark 3x3 centre.
+        for (x, y) in [(0, 0), (code.size() - 7, 0), (0, code.size() - 7)] {
+            assert!(code.is_dark(x, y) && code.is_dark(x + 6, y + 6));
+            assert!(!code.is_dark(x + 1, y + 1) && code.is_dark(x + 3, y + 3));
+        }
+        // The first copy of the format bits decodes to a valid BCH codeword.
+        let mut format = 0;
+        let positions = (0..6)
+            .map(|i| (8, i))
+            .chain([(8, 7), (8, 8), (7, 8)])
+            .chain((9..15).map(|i| (14 - i, 8)));
+        for (i, (x, y)) in positions.enumerate() {
+            format |= u32::from(code.is_dark(x, y)) << i;
+        }
+        let format = format ^ 0x5412;
+        let mut remainder = format >> 10;
+        for _ in 0..10 {
+            remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        }
+        assert_eq!(remainder & 0x3FF, format & 0x3FF);
+
+        let image = code.to_image().unwrap();
+        assert_eq!(image.width() as usize, code.size() + 2 * QUIET_ZONE);
+        assert_eq!(
+            QrCode::encode(&[0; 2331]).map(|code| code.size()),
+            Some(177)
+        );
+        assert!(QrCode::encode(&[0; 2332]).is_none());
+    }
+}
@@ -1,31 +1,96 @@
+use std::io::Read;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use wasmtime::component::{Component, Linker, ResourceTable};
-use wasmtime::{Config, Engine, Store};
+use wasmtime::{CallHook, Engine, Store, StoreLimits, UpdateDeadline};
 use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiView};
+use winit::dpi::PhysicalPosition;
 
+use crate::camera::CameraAccess;
+use crate::capabilities::DroppedCapabilities;
 use crate::component;
 use crate::component::exports::vello::canvas::app as guest_app;
-use crate::host::{FrameOutput, HostCtx, Phase};
-use crate::model::{KeyEvent, LogicalSize, Modifiers, PointerEvent, PointerKind};
+use crate::database::{self, Database};
+use crate::engine::EngineOptions;
+use crate::export::ExportFormat;
+use crate::geometry::Vec2;
+use crate::gpu::GpuInfo;
+use crate::host::{
+    to_wit_display_info, to_wit_power_status, to_wit_window_position, FrameOutput, HostCtx, Phase,
+};
+use crate::identity::{ComponentIdentity, DataDirs};
+use crate::launch::LaunchConfig;
+use crate::limits::ResourceLimits;
+use crate::manifest::ComponentManifest;
+use crate::memory::MemoryPressure;
+use crate::metrics::Metrics;
+use crate::model::{
+    GestureEvent, GesturePhase, KeyEvent, KeyLocation, LockKeys, LogicalSize, ModifierKeys,
+    Modifiers, PointerButtons, PointerEvent, PointerKind,
+};
+use crate::pool::WorkerPool;
+use crate::power::PowerState;
+use crate::precompile;
+use crate::sandbox::{self, GuestStdio, HostInterfaces, Preopen, Sandbox};
+use crate::scroll::{ScrollEvent, ScrollInput};
+use crate::snapshot::Snapshot;
+use crate::stats::{HostCallStats, PerfMeasure};
+use crate::stdio::GuestOutput;
+use crate::storage::Storage;
+use crate::tasks::{TaskChannel, TaskEvent, TaskReporter, TaskRequest};
+use crate::window::{DisplayInfo, WindowRequest};
+use crate::workers::{WorkerChannel, WorkerEvent, WorkerOutbox, WorkerRequest};
 
 struct StoreState {
     host: HostCtx,
     table: ResourceTable,
     wasi: WasiCtx,
+    output: GuestOutput,
+    limits: StoreLimits,
 }
 
 impl StoreState {
-    fn new() -> Result<Self> {
-        let wasi = WasiCtxBuilder::new().inherit_stdio().build();
+    fn new(label: &str, launch: &LaunchConfig, sandbox: &Sandbox) -> Result<Self> {
+        let mut wasi = WasiCtxBuilder::new();
+        let output = sandbox.configure_wasi(&mut wasi)?;
+        // Blocking WASI file and directory calls block the guest's own thread, main or
+        // pool, rather than going through Tokio's blocking pool, which would add up to 512
+        // threads of its own past `--worker-threads`.
+        let wasi = wasi
+            .args(&launch.wasi_args(label))
+            .envs(&launch.env)
+            .allow_blocking_current_thread(true)
+            .build();
+        let mut host = HostCtx::with_launch_args(launch.args.clone());
+        host.set_locale(launch.locale.clone());
+        host.set_preferences(launch.preferences);
         Ok(Self {
-            host: HostCtx::new(),
+            host,
             table: ResourceTable::new(),
             wasi,
+            output,
+            limits: sandbox.store_limits(),
         })
     }
+
+    /// Collect the side effects of a guest call and return the host to idle.
+    fn finish_call(&mut self) -> CallResult {
+        for (stream, line) in self.output.take_lines() {
+            self.host.record_guest_output(stream, &line);
+        }
+        let result = CallResult {
+            requested_redraw: self.host.take_redraw_request(),
+            window_requests: self.host.take_window_requests(),
+            quit_requested: self.host.take_quit_request(),
+            navigate_to: self.host.take_navigation(),
+            frame: None,
+        };
+        self.host.exit_phase();
+        result
+    }
 }
 
 impl WasiView for StoreState {
@@ -58,51 +123,617 @@ impl ComponentSource {
             label,
         }
     }
+
+    /// Human-readable name for the component, used for window identity and logs.
+    pub fn label(&self) -> String {
+        match self {
+            ComponentSource::Path(path) => path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            ComponentSource::Embedded { label, .. } => (*label).to_string(),
+        }
+    }
+
+    /// Directory of a component loaded from a file; `navigate` resolves relative targets
+    /// against it.
+    pub fn directory(&self) -> Option<PathBuf> {
+        match self {
+            ComponentSource::Path(path) => path.parent().map(PathBuf::from),
+            ComponentSource::Embedded { .. } => None,
+        }
+    }
 }
 
 pub struct ComponentRuntime {
     source: ComponentSource,
+    identity: ComponentIdentity,
+    launch: LaunchConfig,
+    sandbox: Sandbox,
+    limits: ResourceLimits,
+    camera_access: CameraAccess,
+    allow_navigation: bool,
+    display: Option<DisplayInfo>,
+    gpu: Option<GpuInfo>,
+    /// Where `storage-*` calls keep their data; storage is unavailable while unset.
+    data_dirs: Option<DataDirs>,
+    power: Option<PowerState>,
+    last_input: Option<Instant>,
+    window_position: Option<PhysicalPosition<i32>>,
+    /// Size last passed to `init` or `resize`, recorded in snapshots.
+    logical_size: LogicalSize,
+    /// `--metrics`: guest call durations are recorded here.
+    metrics: Option<Metrics>,
+    /// Where running tasks report; replaced on reload, which abandons them.
+    tasks: TaskChannel,
+    /// Runs tasks, `--worker-threads` at a time; started with the first task and kept
+    /// across reloads.
+    pool: Option<WorkerPool>,
+    /// Where workers send messages; replaced on reload, which cuts them off.
+    workers: WorkerChannel,
+    span: tracing::Span,
     engine: Engine,
     component: Component,
     store: Store<StoreState>,
     bindings: component::CanvasApp,
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+/// Side effects requested by the guest during a non-`frame` call.
+#[derive(Debug, Default, Clone)]
 pub struct CallResult {
+    /// The guest called `request-frame`.
     pub requested_redraw: bool,
+    /// Window changes (level, opacity, click-through) in the order they were requested.
+    pub window_requests: Vec<WindowRequest>,
+    /// The guest called `request-quit`.
+    pub quit_requested: bool,
+    /// The guest called `navigate`; the component to load in its place.
+    pub navigate_to: Option<PathBuf>,
+    /// What an event handler drew, to be presented immediately in place of the last frame.
+    pub frame: Option<FrameOutput>,
 }
 
+impl CallResult {
+    /// Fold the side effects of a later call into this one.
+    pub fn merge(&mut self, other: CallResult) {
+        self.requested_redraw |= other.requested_redraw;
+        self.window_requests.extend(other.window_requests);
+        self.quit_requested |= other.quit_requested;
+        if other.navigate_to.is_some() {
+            self.navigate_to = other.navigate_to;
+        }
+        if other.frame.is_some() {
+            self.frame = other.frame;
+        }
+    }
+}
+
+/// Result of a `frame` call: the same side effects as [`CallResult`] plus what was drawn.
 #[derive(Debug)]
 pub struct FrameResult {
+    /// The guest asked for another frame, e.g. to keep an animation running.
     pub requested_redraw: bool,
+    pub window_requests: Vec<WindowRequest>,
+    pub quit_requested: bool,
+    pub navigate_to: Option<PathBuf>,
+    /// The guest called `export-frame` for these formats.
+    pub export_requests: Vec<ExportFormat>,
     pub frame: FrameOutput,
 }
 
-impl ComponentRuntime {
-    pub fn new(source: ComponentSource) -> Result<Self> {
-        let engine = Self::build_engine()?;
-        let component = Self::load_component(&engine, &source)?;
-        let (store, bindings) = Self::instantiate(&engine, &component)?;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseResponse {
+    Allow,
+    Deny,
+}
 
-        Ok(Self {
+/// Magic and version of a core WebAssembly module; components use a different version.
+const CORE_MODULE_HEADER: &[u8] = b"\0asm\x01\0\0\0";
+
+/// The component parser rejects core modules with an unhelpful message; say what to do
+/// instead, including for `wasm32-wasip1-threads` builds, which cannot be components yet.
+fn ensure_component(bytes: &[u8], label: &str) -> Result<()> {
+    if bytes.starts_with(CORE_MODULE_HEADER) {
+        bail!(
+            "{label} is a core WebAssembly module, not a component; build it with \
+             `cargo component` or wrap it with `wasm-tools component new`. Threaded \
+             (wasm32-wasip1-threads) modules cannot be made into components yet"
+        );
+    }
+    Ok(())
+}
+
+/// Configures a [`ComponentRuntime`] before it is instantiated: what the guest is
+/// launched with and the [`Sandbox`] it runs in.
+pub struct ComponentRuntimeBuilder {
+    source: ComponentSource,
+    launch: LaunchConfig,
+    sandbox: Sandbox,
+    limits: ResourceLimits,
+}
+
+impl ComponentRuntimeBuilder {
+    pub fn new(source: ComponentSource) -> Self {
+        Self {
+            source,
+            launch: LaunchConfig::default(),
+            sandbox: Sandbox::default(),
+            limits: ResourceLimits::default(),
+        }
+    }
+
+    /// Replace the arguments, environment, locale and engine options wholesale.
+    pub fn with_launch(mut self, launch: LaunchConfig) -> Self {
+        self.launch = launch;
+        self
+    }
+
+    pub fn with_arg(mut self, arg: impl Into<String>) -> Self {
+        self.launch.args.push(arg.into());
+        self
+    }
+
+    pub fn with_env(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.launch.env.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_stdio(mut self, stdio: GuestStdio) -> Self {
+        self.sandbox.stdio = stdio;
+        self
+    }
+
+    /// Give the guest read-write access to `host_path`, mounted at `guest_path`.
+    pub fn with_preopened_dir(
+        mut self,
+        host_path: impl Into<PathBuf>,
+        guest_path: impl Into<String>,
+    ) -> Self {
+        self.sandbox.preopens.push(Preopen {
+            host_path: host_path.into(),
+            guest_path: guest_path.into(),
+            read_only: false,
+        });
+        self
+    }
+
+    /// Like [`with_preopened_dir`](Self::with_preopened_dir), without write access.
+    pub fn with_read_only_dir(
+        mut self,
+        host_path: impl Into<PathBuf>,
+        guest_path: impl Into<String>,
+    ) -> Self {
+        self.sandbox.preopens.push(Preopen {
+            host_path: host_path.into(),
+            guest_path: guest_path.into(),
+            read_only: true,
+        });
+        self
+    }
+
+    /// Trap any single guest call that runs longer than `timeout`. Enables epoch
+    /// interruption, so components precompiled without it no longer load.
+    pub fn with_call_timeout(mut self, timeout: Duration) -> Self {
+        self.sandbox.call_timeout = Some(timeout);
+        self
+    }
+
+    /// Cap each linear memory at `bytes`; growing past it fails.
+    pub fn with_max_memory(mut self, bytes: usize) -> Self {
+        self.sandbox.max_memory_bytes = Some(bytes);
+        self
+    }
+
+    pub fn with_host_interfaces(mut self, interfaces: HostInterfaces) -> Self {
+        self.sandbox.interfaces = interfaces;
+        self
+    }
+
+    pub fn with_sandbox(mut self, sandbox: Sandbox) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn build(self) -> Result<ComponentRuntime> {
+        let Self {
             source,
+            launch,
+            sandbox,
+            limits,
+        } = self;
+        let engine = ComponentRuntime::build_engine(&launch.engine, &sandbox)?;
+        let component = ComponentRuntime::load_component(&engine, &source)?;
+        let (mut store, bindings) =
+            ComponentRuntime::instantiate(&engine, &component, &source, &launch, &sandbox)?;
+        store.data_mut().host.set_limits(limits);
+        store
+            .data_mut()
+            .host
+            .set_navigation(false, source.directory());
+        let span = tracing::info_span!("component", label = %source.label());
+        let identity = ComponentIdentity::of(&source)?;
+        tracing::debug!(parent: &span, id = %identity.id, hash = %identity.content_hash, "component identity");
+
+        Ok(ComponentRuntime {
+            source,
+            identity,
+            launch,
+            sandbox,
+            limits,
+            camera_access: CameraAccess::default(),
+            allow_navigation: false,
+            display: None,
+            gpu: None,
+            data_dirs: None,
+            power: None,
+            last_input: None,
+            window_position: None,
+            logical_size: LogicalSize::default(),
+            metrics: None,
+            tasks: TaskChannel::default(),
+            pool: None,
+            workers: WorkerChannel::default(),
+            span,
             engine,
             component,
             store,
             bindings,
         })
     }
+}
+
+impl ComponentRuntime {
+    pub fn new(source: ComponentSource) -> Result<Self> {
+        Self::builder(source).build()
+    }
+
+    pub fn with_launch(source: ComponentSource, launch: LaunchConfig) -> Result<Self> {
+        Self::builder(source).with_launch(launch).build()
+    }
+
+    pub fn builder(source: ComponentSource) -> ComponentRuntimeBuilder {
+        ComponentRuntimeBuilder::new(source)
+    }
 
     pub fn reload(&mut self) -> Result<()> {
         self.component = Self::load_component(&self.engine, &self.source)?;
-        let (store, bindings) = Self::instantiate(&self.engine, &self.component)?;
+        self.identity = ComponentIdentity::of(&self.source)?;
+        let (store, bindings) = Self::instantiate(
+            &self.engine,
+            &self.component,
+            &self.source,
+            &self.launch,
+            &self.sandbox,
+        )?;
+        // Tweens run on the host clock, so they carry on in the new instance.
+        let tweens = self.store.data_mut().host.take_tweens();
         self.store = store;
         self.bindings = bindings;
+        self.store.data_mut().host.set_tweens(tweens);
+        self.tasks = TaskChannel::default();
+        self.workers = WorkerChannel::default();
+        self.store.data_mut().host.set_limits(self.limits);
+        self.store
+            .data_mut()
+            .host
+            .set_camera_access(self.camera_access);
+        self.store
+            .data_mut()
+            .host
+            .set_navigation(self.allow_navigation, self.source.directory());
+        self.store.data_mut().host.set_display(self.display.clone());
+        self.store.data_mut().host.set_viewport(self.logical_size);
+        self.store.data_mut().host.set_gpu_info(self.gpu.clone());
+        self.apply_storage();
+        self.store
+            .data_mut()
+            .host
+            .set_power_state(self.power.clone());
+        self.store
+            .data_mut()
+            .host
+            .record_window_position(self.window_position);
+        if let Some(at) = self.last_input {
+            self.store.data_mut().host.set_last_input(at);
+        }
         Ok(())
     }
 
+    /// Which component this is, for filing its data.
+    pub fn identity(&self) -> &ComponentIdentity {
+        &self.identity
+    }
+
+    /// What `get-display-info` reports; kept across reloads.
+    pub fn set_display(&mut self, display: Option<DisplayInfo>) {
+        self.display = display.clone();
+        self.store.data_mut().host.set_display(display);
+    }
+
+    /// The adapter `host-info` reports; kept across reloads.
+    pub fn set_gpu_info(&mut self, gpu: Option<GpuInfo>) {
+        self.gpu = gpu.clone();
+        self.store.data_mut().host.set_gpu_info(gpu);
+    }
+
+    /// Keep guest storage under `dirs`, sharing what the component's manifest grants;
+    /// kept across reloads, which pick up manifest changes.
+    pub fn set_data_dirs(&mut self, dirs: Option<DataDirs>) {
+        self.data_dirs = dirs;
+        self.apply_storage();
+    }
+
+    /// Hand the guest its storage and database, closing any it had open.
+    fn apply_storage(&mut self) {
+        let storage = self.open_storage();
+        let database = database_for(storage.as_ref(), &self.limits, self.sandbox.call_timeout);
+        let host = &mut self.store.data_mut().host;
+        host.set_storage(storage);
+        host.set_database(database);
+    }
+
+    /// Storage for the current identity; `None`, with a warning, when it cannot be opened.
+    fn open_storage(&self) -> Option<Storage> {
+        let dirs = self.data_dirs.as_ref()?;
+        let opened = ComponentManifest::of(&self.source).and_then(|manifest| {
+            Storage::open(dirs, &self.identity, &manifest.shares_storage_with)
+        });
+        match opened {
+            Ok(storage) => Some(storage),
+            Err(err) => {
+                tracing::warn!(parent: &self.span, error = %format!("{err:#}"), "storage unavailable");
+                None
+            }
+        }
+    }
+
+    /// Tasks the guest has spawned whose results it has not yet been given.
+    pub fn tasks_in_flight(&self) -> usize {
+        self.store.data().host.tasks_in_flight()
+    }
+
+    /// Deliver what background tasks reported since the last call, as `task-progress` and
+    /// `task-finished`.
+    pub fn poll_tasks(&mut self) -> Result<CallResult> {
+        let mut result = CallResult::default();
+        for event in self.tasks.drain() {
+            let outcome = match event {
+                TaskEvent::Progress(task, progress) => {
+                    self.invoke(Phase::Event, |bindings, store| {
+                        bindings
+                            .vello_canvas_app()
+                            .call_task_progress(store, task, progress)
+                    })?
+                }
+                TaskEvent::Finished(task, finished) => {
+                    if let Err(err) = &finished {
+                        tracing::warn!(parent: &self.span, task, error = %err, "task failed");
+                    }
+                    self.store.data_mut().host.task_finished();
+                    self.invoke(Phase::Event, |bindings, store| {
+                        bindings.vello_canvas_app().call_task_finished(
+                            store,
+                            task,
+                            finished.as_deref().map_err(String::as_str),
+                        )
+                    })?
+                }
+            };
+            result.merge(outcome);
+        }
+        Ok(result)
+    }
+
+    /// Queue the tasks the guest spawned during the last call on the worker pool. Each
+    /// runs in its own instance on one pool thread; tasks abandoned by a reload before a
+    /// thread is free never start.
+    fn start_tasks(&mut self) {
+        let requests = self.store.data_mut().host.take_task_requests();
+        if requests.is_empty() {
+            return;
+        }
+        if self.pool.is_none() {
+            match WorkerPool::new(self.launch.engine.worker_threads()) {
+                Ok(pool) => self.pool = Some(pool),
+                Err(err) => {
+                    for request in requests {
+                        self.tasks
+                            .reporter(request.id)
+                            .finish(Err(format!("{err:#}")));
+                    }
+                    return;
+                }
+            }
+        }
+        let pool = self.pool.as_ref().expect("pool was just started");
+        for request in requests {
+            tracing::debug!(parent: &self.span, task = request.id, name = %request.name, "starting task");
+            let reporter = self.tasks.reporter(request.id);
+            let instance = TaskInstance {
+                engine: self.engine.clone(),
+                component: self.component.clone(),
+                source: self.source.clone(),
+                launch: self.launch.clone(),
+                sandbox: self.sandbox.clone(),
+                limits: self.limits,
+                storage: self.store.data().host.storage().cloned(),
+                dropped: self.store.data().host.dropped_capabilities().clone(),
+            };
+            pool.execute(move || {
+                if reporter.cancelled() {
+                    return;
+                }
+                let result = instance
+                    .run(&request, &reporter)
+                    .unwrap_or_else(|err| Err(format!("{err:#}")));
+                reporter.finish(result);
+            });
+        }
+    }
+
+    /// Start `source` as a worker of this component, as if the guest had called
+    /// `spawn-worker`, e.g. for an embedder handing the component a plugin. `interfaces`
+    /// is narrowed to what the component itself is linked to.
+    pub fn spawn_worker(
+        &mut self,
+        source: ComponentSource,
+        interfaces: HostInterfaces,
+    ) -> Result<u32> {
+        let id = self
+            .store
+            .data_mut()
+            .host
+            .workers_mut()
+            .spawn(source, interfaces)?;
+        self.start_workers();
+        Ok(id)
+    }
+
+    /// Queue `message` for worker `id`'s `on-message`, as `post-to-worker` does.
+    pub fn post_to_worker(&mut self, id: u32, message: Vec<u8>) -> Result<()> {
+        self.store.data_mut().host.workers_mut().post(id, message)
+    }
+
+    /// Workers the guest has spawned that have not failed or been terminated.
+    pub fn workers_running(&self) -> usize {
+        self.store.data().host.workers_running()
+    }
+
+    /// Deliver what workers sent since the last call, as `worker-message` and
+    /// `worker-failed`.
+    pub fn poll_workers(&mut self) -> Result<CallResult> {
+        let mut result = CallResult::default();
+        for event in self.workers.drain() {
+            let outcome = match event {
+                WorkerEvent::Message(worker, message) => {
+                    self.invoke(Phase::Event, |bindings, store| {
+                        bindings
+                            .vello_canvas_app()
+                            .call_worker_message(store, worker, &message)
+                    })?
+                }
+                WorkerEvent::Failed(worker, error) => {
+                    tracing::warn!(parent: &self.span, worker, %error, "worker failed");
+                    self.store.data_mut().host.workers_mut().terminate(worker);
+                    self.invoke(Phase::Event, |bindings, store| {
+                        bindings
+                            .vello_canvas_app()
+                            .call_worker_failed(store, worker, &error)
+                    })?
+                }
+            };
+            result.merge(outcome);
+        }
+        Ok(result)
+    }
+
+    /// Start the workers the guest spawned during the last call, each on its own thread.
+    fn start_workers(&mut self) {
+        for request in self.store.data_mut().host.take_worker_requests() {
+            let interfaces = request.interfaces.intersect(self.sandbox.interfaces);
+            tracing::debug!(parent: &self.span, worker = request.id, label = %request.source.label(), ?interfaces, "starting worker");
+            let outbox = self.workers.outbox(request.id);
+            let instance = WorkerInstance {
+                engine: self.engine.clone(),
+                // Workers get the parent's locale and limits but none of its arguments,
+                // environment or directories.
+                launch: LaunchConfig {
+                    args: Vec::new(),
+                    env: Vec::new(),
+                    ..self.launch.clone()
+                },
+                sandbox: Sandbox {
+                    preopens: Vec::new(),
+                    interfaces,
+                    ..self.sandbox.clone()
+                },
+                limits: self.limits,
+                data_dirs: self.data_dirs.clone(),
+                dropped: self.store.data().host.dropped_capabilities().clone(),
+                parent: self.identity.clone(),
+            };
+            let thread_outbox = outbox.clone();
+            let spawned = std::thread::Builder::new()
+                .name(format!("worker-{}", request.id))
+                .spawn(move || {
+                    if let Err(err) = instance.run(request, &thread_outbox) {
+                        thread_outbox.fail(format!("{err:#}"));
+                    }
+                });
+            if let Err(err) = spawned {
+                outbox.fail(format!("failed to start the worker thread: {err}"));
+            }
+        }
+    }
+
+    /// When the user last gave the window input, for `seconds-since-input`; kept across
+    /// reloads.
+    pub fn set_last_input(&mut self, at: Instant) {
+        self.last_input = Some(at);
+        self.store.data_mut().host.set_last_input(at);
+    }
+
+    /// What `power-state` reports; kept across reloads.
+    pub fn set_power_state(&mut self, power: Option<PowerState>) {
+        self.power = power.clone();
+        self.store.data_mut().host.set_power_state(power);
+    }
+
+    /// Real time since the previous frame, unclamped; reported by `frame-elapsed-ms`
+    /// during the next `frame`.
+    pub fn set_frame_elapsed(&mut self, elapsed_ms: f64) {
+        self.store.data_mut().host.set_frame_elapsed(elapsed_ms);
+    }
+
+    /// Record guest call durations into `metrics` from now on.
+    pub fn set_metrics(&mut self, metrics: Option<Metrics>) {
+        self.metrics = metrics;
+    }
+
+    /// Replace the resource quotas; they also apply to future reloads.
+    pub fn set_limits(&mut self, limits: ResourceLimits) {
+        self.limits = limits;
+        self.store.data_mut().host.set_limits(limits);
+    }
+
+    /// How `open-camera` is answered; kept across reloads.
+    pub fn set_camera_access(&mut self, access: CameraAccess) {
+        self.camera_access = access;
+        self.store.data_mut().host.set_camera_access(access);
+    }
+
+    /// Whether the guest may `navigate` to another component; kept across reloads.
+    pub fn set_navigation_allowed(&mut self, allowed: bool) {
+        self.allow_navigation = allowed;
+        self.store
+            .data_mut()
+            .host
+            .set_navigation(allowed, self.source.directory());
+    }
+
+    /// Name of a camera waiting on the user's permission.
+    pub fn camera_prompt(&self) -> Option<String> {
+        self.store.data().host.camera_prompt()
+    }
+
+    /// Answer the camera prompt for this session, including after reloads.
+    pub fn answer_camera_prompt(&mut self, allow: bool) {
+        self.camera_access = if allow {
+            CameraAccess::Allow
+        } else {
+            CameraAccess::Deny
+        };
+        self.store.data_mut().host.answer_camera_prompt(allow);
+    }
+
     pub fn call_init(&mut self, size: LogicalSize) -> Result<CallResult> {
+        self.logical_size = size;
+        self.store.data_mut().host.set_viewport(size);
         self.invoke(Phase::Init, |bindings, store| {
             bindings
                 .vello_canvas_app()
@@ -111,6 +742,8 @@ impl ComponentRuntime {
     }
 
     pub fn call_resize(&mut self, size: LogicalSize) -> Result<CallResult> {
+        self.logical_size = size;
+        self.store.data_mut().host.set_viewport(size);
         self.invoke(Phase::Resize, |bindings, store| {
             bindings
                 .vello_canvas_app()
@@ -119,26 +752,23 @@ impl ComponentRuntime {
     }
 
     pub fn call_pointer_down(&mut self, event: &PointerEvent) -> Result<CallResult> {
-        self.invoke(Phase::Event, |bindings, store| {
-            bindings
-                .vello_canvas_app()
-                .call_pointer_down(store, to_wit_pointer_event(event))
+        let evt = to_wit_pointer_event(event);
+        self.invoke(Phase::Event, move |bindings, store| {
+            bindings.vello_canvas_app().call_pointer_down(store, &evt)
         })
     }
 
     pub fn call_pointer_up(&mut self, event: &PointerEvent) -> Result<CallResult> {
-        self.invoke(Phase::Event, |bindings, store| {
-            bindings
-                .vello_canvas_app()
-                .call_pointer_up(store, to_wit_pointer_event(event))
+        let evt = to_wit_pointer_event(event);
+        self.invoke(Phase::Event, move |bindings, store| {
+            bindings.vello_canvas_app().call_pointer_up(store, &evt)
         })
     }
 
     pub fn call_pointer_move(&mut self, event: &PointerEvent) -> Result<CallResult> {
-        self.invoke(Phase::Event, |bindings, store| {
-            bindings
-                .vello_canvas_app()
-                .call_pointer_move(store, to_wit_pointer_event(event))
+        let evt = to_wit_pointer_event(event);
+        self.invoke(Phase::Event, move |bindings, store| {
+            bindings.vello_canvas_app().call_pointer_move(store, &evt)
         })
     }
 
@@ -156,34 +786,329 @@ impl ComponentRuntime {
         })
     }
 
+    pub fn call_text_input(&mut self, text: &str) -> Result<CallResult> {
+        self.invoke(Phase::Event, |bindings, store| {
+            bindings.vello_canvas_app().call_text_input(store, text)
+        })
+    }
+
+    pub fn call_pinch_gesture(&mut self, event: &GestureEvent) -> Result<CallResult> {
+        self.invoke(Phase::Event, |bindings, store| {
+            bindings
+                .vello_canvas_app()
+                .call_pinch_gesture(store, to_wit_gesture_event(event))
+        })
+    }
+
+    pub fn call_rotation_gesture(&mut self, event: &GestureEvent) -> Result<CallResult> {
+        self.invoke(Phase::Event, |bindings, store| {
+            bindings
+                .vello_canvas_app()
+                .call_rotation_gesture(store, to_wit_gesture_event(event))
+        })
+    }
+
+    pub fn call_double_tap_gesture(
+        &mut self,
+        position: Vec2,
+        modifiers: Modifiers,
+    ) -> Result<CallResult> {
+        self.invoke(Phase::Event, |bindings, store| {
+            bindings.vello_canvas_app().call_double_tap_gesture(
+                store,
+                position.into(),
+                to_wit_modifiers(modifiers),
+            )
+        })
+    }
+
     pub fn call_frame(&mut self, dt_ms: f32) -> Result<FrameResult> {
         let phase = Phase::Frame;
+        let _span = self.span.clone().entered();
         {
             let data = self.store.data_mut();
+            data.host.advance_particles(dt_ms);
+            data.host.advance_tweens(dt_ms);
             data.host.enter_phase(phase);
         }
+        if let Some(ticks) = self.sandbox.deadline_ticks() {
+            self.store.set_epoch_deadline(ticks);
+        }
 
+        let started = Instant::now();
         let call_result = self
             .bindings
             .vello_canvas_app()
             .call_frame(&mut self.store, dt_ms);
+        self.observe_call(phase, started);
 
-        let (frame, requested) = {
+        let (frame, export_requests, outcome) = {
             let data = self.store.data_mut();
-            let requested = data.host.take_redraw_request();
             let frame = data.host.take_frame_output();
-            data.host.exit_phase();
-            (frame, requested)
+            let export_requests = data.host.take_export_requests();
+            (frame, export_requests, data.finish_call())
         };
+        self.start_tasks();
+        self.start_workers();
 
         call_result.context("guest frame call failed")?;
 
         Ok(FrameResult {
-            requested_redraw: requested,
+            requested_redraw: outcome.requested_redraw,
+            window_requests: outcome.window_requests,
+            quit_requested: outcome.quit_requested,
+            navigate_to: outcome.navigate_to,
+            export_requests,
             frame,
         })
     }
 
+    /// What `get-window-position` reports; kept across reloads.
+    pub fn set_window_position(&mut self, position: Option<PhysicalPosition<i32>>) {
+        self.window_position = position;
+        self.store.data_mut().host.record_window_position(position);
+    }
+
+    pub fn call_window_moved(&mut self, position: PhysicalPosition<i32>) -> Result<CallResult> {
+        self.invoke(Phase::Event, |bindings, store| {
+            bindings
+                .vello_canvas_app()
+                .call_window_moved(store, to_wit_window_position(position))
+        })
+    }
+
+    pub fn call_minimized_changed(&mut self, minimized: bool) -> Result<CallResult> {
+        self.invoke(Phase::Event, |bindings, store| {
+            bindings
+                .vello_canvas_app()
+                .call_minimized_changed(store, minimized)
+        })
+    }
+
+    pub fn call_frame_missed(&mut self, count: u32) -> Result<CallResult> {
+        self.invoke(Phase::Event, |bindings, store| {
+            bindings.vello_canvas_app().call_frame_missed(store, count)
+        })
+    }
+
+    pub fn call_idle_changed(&mut self, idle: bool) -> Result<CallResult> {
+        self.invoke(Phase::Event, |bindings, store| {
+            bindings.vello_canvas_app().call_idle_changed(store, idle)
+        })
+    }
+
+    pub fn call_display_changed(&mut self, info: &DisplayInfo) -> Result<CallResult> {
+        let info = to_wit_display_info(info);
+        self.invoke(Phase::Event, move |bindings, store| {
+            bindings
+                .vello_canvas_app()
+                .call_display_changed(store, &info)
+        })
+    }
+
+    pub fn call_deep_link_opened(&mut self, url: &str) -> Result<CallResult> {
+        self.invoke(Phase::Event, |bindings, store| {
+            bindings
+                .vello_canvas_app()
+                .call_deep_link_opened(store, url)
+        })
+    }
+
+    /// Route wheel and touch input to the component's scroll regions, returning each region
+    /// it scrolled.
+    pub fn scroll_input(&mut self, input: ScrollInput) -> Vec<ScrollEvent> {
+        self.store.data_mut().host.scroll_input(input)
+    }
+
+    /// Carry scroll flings and bounces forward by the `dt` about to be passed to `frame`.
+    pub fn advance_scrolling(&mut self, dt_ms: f32) -> Vec<ScrollEvent> {
+        self.store.data_mut().host.advance_scrolling(dt_ms)
+    }
+
+    pub fn is_scrolling(&self) -> bool {
+        self.store.data().host.is_scrolling()
+    }
+
+    pub fn call_scroll_changed(&mut self, id: u32, offset: Vec2) -> Result<CallResult> {
+        self.invoke(Phase::Event, move |bindings, store| {
+            bindings
+                .vello_canvas_app()
+                .call_scroll_changed(store, id, offset.into())
+        })
+    }
+
+    pub fn call_kinetic_scrolled(
+        &mut self,
+        id: u32,
+        offset: Vec2,
+        delta: Vec2,
+    ) -> Result<CallResult> {
+        self.invoke(Phase::Event, move |bindings, store| {
+            bindings.vello_canvas_app().call_kinetic_scrolled(
+                store,
+                id,
+                offset.into(),
+                delta.into(),
+            )
+        })
+    }
+
+    pub fn call_instance_activated(&mut self, args: &[String]) -> Result<CallResult> {
+        self.invoke(Phase::Event, |bindings, store| {
+            bindings
+                .vello_canvas_app()
+                .call_instance_activated(store, args)
+        })
+    }
+
+    pub fn call_trim_memory(&mut self, level: MemoryPressure) -> Result<CallResult> {
+        let level = match level {
+            MemoryPressure::Normal => return Ok(CallResult::default()),
+            MemoryPressure::Moderate => guest_app::MemoryPressure::Moderate,
+            MemoryPressure::Critical => guest_app::MemoryPressure::Critical,
+        };
+        self.invoke(Phase::Event, move |bindings, store| {
+            bindings.vello_canvas_app().call_trim_memory(store, level)
+        })
+    }
+
+    /// Evict the guest's least recently drawn assets down to `max_bytes` and tell it which
+    /// went.
+    pub fn evict_assets(&mut self, max_bytes: usize) -> Result<CallResult> {
+        let evicted = self.store.data_mut().host.evict_assets(max_bytes);
+        if evicted.is_empty() {
+            return Ok(CallResult::default());
+        }
+        tracing::info!(
+            assets = evicted.len(),
+            remaining = self.asset_bytes(),
+            "evicted guest assets under memory pressure"
+        );
+        self.invoke(Phase::Event, move |bindings, store| {
+            bindings.vello_canvas_app().call_assets_evicted(
+                store,
+                &evicted.images,
+                &evicted.pictures,
+                &evicted.shaders,
+            )
+        })
+    }
+
+    /// Bytes held by every kind of the component's assets.
+    pub fn asset_bytes(&self) -> usize {
+        self.store.data().host.asset_bytes()
+    }
+
+    pub fn call_power_state_changed(&mut self, state: &PowerState) -> Result<CallResult> {
+        let state = to_wit_power_status(state);
+        self.invoke(Phase::Event, move |bindings, store| {
+            bindings
+                .vello_canvas_app()
+                .call_power_state_changed(store, state)
+        })
+    }
+
+    pub fn call_close_requested(&mut self) -> Result<(CloseResponse, CallResult)> {
+        self.invoke_returning(Phase::Event, |bindings, store| {
+            bindings.vello_canvas_app().call_close_requested(store)
+        })
+        .map(|(response, result)| {
+            let response = match response {
+                guest_app::CloseResponse::Allow => CloseResponse::Allow,
+                guest_app::CloseResponse::Deny => CloseResponse::Deny,
+            };
+            (response, result)
+        })
+    }
+
+    /// Capture the guest's state through `save-state`; see [`snapshot`](crate::snapshot).
+    /// Fails when the guest does not support snapshots.
+    pub fn snapshot(&mut self) -> Result<Vec<u8>> {
+        let (guest, _) = self.invoke_returning(Phase::Event, |bindings, store| {
+            bindings.vello_canvas_app().call_save_state(store)
+        })?;
+        let Some(guest) = guest else {
+            bail!("{} does not support snapshots", self.source.label());
+        };
+        Snapshot {
+            content_hash: self.identity.content_hash.clone(),
+            size: self.logical_size,
+            guest,
+        }
+        .encode()
+    }
+
+    /// Reinstantiate the component and resume it from a [`snapshot`](Self::snapshot)
+    /// taken from the same build. Leaves the guest freshly initialised if its
+    /// `restore-state` fails.
+    pub fn restore(&mut self, bytes: &[u8]) -> Result<CallResult> {
+        let snapshot = Snapshot::decode(bytes)?;
+        // Check against the source as it is now, which is what `reload` will load.
+        if snapshot.content_hash != ComponentIdentity::of(&self.source)?.content_hash {
+            bail!("the snapshot was taken from a different build of this component");
+        }
+        self.reload()?;
+        let mut result = self.call_init(snapshot.size)?;
+        let (restored, restore_result) =
+            self.invoke_returning(Phase::Event, |bindings, store| {
+                bindings
+                    .vello_canvas_app()
+                    .call_restore_state(store, &snapshot.guest)
+            })?;
+        result.merge(restore_result);
+        restored.map_err(|err| anyhow!("the component rejected the snapshot: {err}"))?;
+        Ok(result)
+    }
+
+    /// Move the mouse to `(x, y)` and press and release the primary button there.
+    pub fn simulate_click(&mut self, x: f32, y: f32) -> Result<CallResult> {
+        let position = Vec2::new(x, y);
+        let mut result = self.call_pointer_move(&mouse_event(position, false))?;
+        result.merge(self.call_pointer_down(&mouse_event(position, true))?);
+        result.merge(self.call_pointer_up(&mouse_event(position, false))?);
+        Ok(result)
+    }
+
+    /// Press and release a key. `key` doubles as the physical code (`"Enter"`, `"+"`).
+    pub fn simulate_key(&mut self, key: &str) -> Result<CallResult> {
+        let event = KeyEvent {
+            key: key.to_string(),
+            code: key.to_string(),
+            modifiers: Modifiers::default(),
+            is_repeat: false,
+            location: KeyLocation::Standard,
+        };
+        let mut result = self.call_key_down(&event)?;
+        result.merge(self.call_key_up(&event)?);
+        Ok(result)
+    }
+
+    /// Press the primary button at `from`, move to `to` in `steps` evenly spaced moves with
+    /// the button held, and release it at `to`.
+    pub fn simulate_drag(&mut self, from: Vec2, to: Vec2, steps: u32) -> Result<CallResult> {
+        let mut result = self.call_pointer_move(&mouse_event(from, false))?;
+        result.merge(self.call_pointer_down(&mouse_event(from, true))?);
+        let steps = steps.max(1);
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            let position = from + (to - from) * t;
+            result.merge(self.call_pointer_move(&mouse_event(position, true))?);
+        }
+        result.merge(self.call_pointer_up(&mouse_event(to, false))?);
+        Ok(result)
+    }
+
+    /// Guest `perf-measure` regions recorded since the last call to this method.
+    pub fn take_perf_measures(&mut self) -> Vec<PerfMeasure> {
+        self.store.data_mut().host.take_perf_measures()
+    }
+
+    /// Host import calls since the last call to this method, most time-consuming first,
+    /// and the `draw-text` bytes among them.
+    pub fn take_host_usage(&mut self) -> (Vec<HostCallStats>, usize) {
+        self.store.data_mut().host.take_host_usage()
+    }
+
     pub fn recent_logs(&self) -> Vec<String> {
         self.store.data().host.recent_logs_snapshot()
     }
@@ -192,39 +1117,80 @@ impl ComponentRuntime {
     where
         F: FnOnce(&component::CanvasApp, &mut Store<StoreState>) -> wasmtime::Result<()>,
     {
+        self.invoke_returning(phase, f).map(|((), result)| result)
+    }
+
+    fn invoke_returning<T, F>(&mut self, phase: Phase, f: F) -> Result<(T, CallResult)>
+    where
+        F: FnOnce(&component::CanvasApp, &mut Store<StoreState>) -> wasmtime::Result<T>,
+    {
+        let _span = self.span.clone().entered();
         {
             let data = self.store.data_mut();
             data.host.enter_phase(phase);
         }
 
+        let started = Instant::now();
         let result = f(&self.bindings, &mut self.store);
+        self.observe_call(phase, started);
 
-        let requested = {
+        let outcome = {
             let data = self.store.data_mut();
-            let requested = data.host.take_redraw_request();
-            data.host.exit_phase();
-            requested
+            let frame = (phase == Phase::Event)
+                .then(|| data.host.take_frame_output())
+                .filter(|frame| !frame.is_empty());
+            CallResult {
+                frame,
+                ..data.finish_call()
+            }
         };
+        self.start_tasks();
+        self.start_workers();
 
-        result.context("guest call failed")?;
+        let value = result.context("guest call failed")?;
 
-        Ok(CallResult {
-            requested_redraw: requested,
-        })
+        Ok((value, outcome))
+    }
+
+    fn observe_call(&self, phase: Phase, started: Instant) {
+        if let Some(metrics) = &self.metrics {
+            metrics.observe_guest_call(phase, started.elapsed());
+        }
     }
 
-    fn build_engine() -> Result<Engine> {
-        let mut config = Config::new();
-        config.wasm_component_model(true);
-        config.wasm_backtrace_details(wasmtime::WasmBacktraceDetails::Enable);
-        Engine::new(&config).context("failed to initialise Wasmtime engine")
+    /// Engine settings shared with `compile`; precompiled components only load into an
+    /// engine configured the same way.
+    fn build_engine(options: &EngineOptions, sandbox: &Sandbox) -> Result<Engine> {
+        let mut config = options.config();
+        config.epoch_interruption(sandbox.call_timeout.is_some());
+        let engine = Engine::new(&config).context("failed to initialise Wasmtime engine")?;
+        if sandbox.call_timeout.is_some() {
+            sandbox::spawn_epoch_ticker(&engine)?;
+        }
+        Ok(engine)
     }
 
     fn load_component(engine: &Engine, source: &ComponentSource) -> Result<Component> {
         match source {
-            ComponentSource::Path(path) => Component::from_file(engine, path)
-                .with_context(|| format!("failed to load component from {}", path.display())),
+            ComponentSource::Path(path) if precompile::is_precompiled(path) => {
+                // SAFETY: the file is trusted to be the output of `compile`. Wasmtime checks
+                // that it was built by a compatible version and engine configuration, but
+                // cannot validate the machine code inside.
+                unsafe { Component::deserialize_file(engine, path) }.with_context(|| {
+                    format!("failed to load precompiled component {}", path.display())
+                })
+            }
+            ComponentSource::Path(path) => {
+                let mut header = [0; CORE_MODULE_HEADER.len()];
+                let header = std::fs::File::open(path)
+                    .and_then(|mut file| file.read_exact(&mut header))
+                    .map_or(&[][..], |()| &header[..]);
+                ensure_component(header, &source.label())?;
+                Component::from_file(engine, path)
+                    .with_context(|| format!("failed to load component from {}", path.display()))
+            }
             ComponentSource::Embedded { bytes, label } => {
+                ensure_component(bytes, label)?;
                 Component::from_binary(engine, bytes.as_ref())
                     .with_context(|| format!("failed to load {label} component"))
             }
@@ -234,20 +1200,223 @@ impl ComponentRuntime {
     fn instantiate(
         engine: &Engine,
         component: &Component,
+        source: &ComponentSource,
+        launch: &LaunchConfig,
+        sandbox: &Sandbox,
     ) -> Result<(Store<StoreState>, component::CanvasApp)> {
-        let mut linker = Linker::new(engine);
-        wasmtime_wasi::add_to_linker_sync(&mut linker).context("failed to add WASI to linker")?;
-        component::vello::canvas::host::add_to_linker(&mut linker, |state: &mut StoreState| {
-            &mut state.host
-        })
-        .context("failed to add host bindings to linker")?;
-
-        let store_state = StoreState::new()?;
-        let mut store = Store::new(engine, store_state);
+        let linker = Self::linker(engine, component, sandbox.interfaces)?;
+        let mut store = Self::new_store(engine, &source.label(), launch, sandbox)?;
         let bindings = component::CanvasApp::instantiate(&mut store, component, &linker)
             .context("failed to instantiate component")?;
         Ok((store, bindings))
     }
+
+    /// A linker providing `interfaces` to `component`.
+    fn linker(
+        engine: &Engine,
+        component: &Component,
+        interfaces: HostInterfaces,
+    ) -> Result<Linker<StoreState>> {
+        // Stub every import first so interfaces left out of the sandbox trap when called;
+        // the linked interfaces then shadow their stubs.
+        let mut linker = Linker::new(engine);
+        linker
+            .define_unknown_imports_as_traps(component)
+            .context("failed to stub unlinked imports")?;
+        linker.allow_shadowing(true);
+        if interfaces.wasi {
+            wasmtime_wasi::add_to_linker_sync(&mut linker)
+                .context("failed to add WASI to linker")?;
+        }
+        if interfaces.canvas {
+            component::vello::canvas::host::add_to_linker(&mut linker, |state: &mut StoreState| {
+                &mut state.host
+            })
+            .context("failed to add host bindings to linker")?;
+        }
+        if interfaces.database {
+            component::vello::canvas::database::add_to_linker(
+                &mut linker,
+                |state: &mut StoreState| &mut state.host,
+            )
+            .context("failed to add database bindings to linker")?;
+        }
+        Ok(linker)
+    }
+
+    fn new_store(
+        engine: &Engine,
+        label: &str,
+        launch: &LaunchConfig,
+        sandbox: &Sandbox,
+    ) -> Result<Store<StoreState>> {
+        let store_state = StoreState::new(label, launch, sandbox)?;
+        let mut store = Store::new(engine, store_state);
+        store.limiter(|state| &mut state.limits);
+        if let Some(ticks) = sandbox.deadline_ticks() {
+            store.set_epoch_deadline(ticks);
+        }
+        store.call_hook(|mut store, hook| {
+            match hook {
+                CallHook::CallingHost => store.data_mut().host.begin_host_call(),
+                CallHook::ReturningFromHost => store.data_mut().host.end_host_call(),
+                CallHook::CallingWasm | CallHook::ReturningFromWasm => {}
+            }
+            Ok(())
+        });
+        Ok(store)
+    }
+}
+
+/// What a task thread needs to instantiate its own copy of the component.
+struct TaskInstance {
+    engine: Engine,
+    component: Component,
+    source: ComponentSource,
+    launch: LaunchConfig,
+    sandbox: Sandbox,
+    limits: ResourceLimits,
+    storage: Option<Storage>,
+    dropped: DroppedCapabilities,
+}
+
+impl TaskInstance {
+    /// Instantiate the component and call `run-task`; fails if the instance traps.
+    fn run(
+        self,
+        request: &TaskRequest,
+        reporter: &TaskReporter,
+    ) -> Result<Result<Vec<u8>, String>> {
+        let (mut store, bindings) = ComponentRuntime::instantiate(
+            &self.engine,
+            &self.component,
+            &self.source,
+            &self.launch,
+            &self.sandbox,
+        )?;
+        let host = &mut store.data_mut().host;
+        host.set_limits(self.limits);
+        // Like the rest of the task, its statements run without a time limit.
+        host.set_database(database_for(self.storage.as_ref(), &self.limits, None));
+        host.set_storage(self.storage);
+        host.inherit_dropped_capabilities(&self.dropped);
+        host.set_task_reporter(Some(reporter.clone()));
+        host.enter_phase(Phase::Task);
+        // The call timeout guards the window's responsiveness, which a task cannot hold up;
+        // epoch ticks only check whether the task has been abandoned.
+        let abandoned = reporter.clone();
+        store.epoch_deadline_callback(move |_| {
+            if abandoned.cancelled() {
+                bail!("the task was abandoned");
+            }
+            Ok(UpdateDeadline::Continue(1))
+        });
+        let result =
+            bindings
+                .vello_canvas_app()
+                .call_run_task(&mut store, &request.name, &request.input);
+        store.data_mut().finish_call();
+        result.context("task trapped")
+    }
+}
+
+/// What a worker thread needs to load and run its component.
+struct WorkerInstance {
+    engine: Engine,
+    launch: LaunchConfig,
+    sandbox: Sandbox,
+    limits: ResourceLimits,
+    data_dirs: Option<DataDirs>,
+    dropped: DroppedCapabilities,
+    /// The spawning component, whose id scopes the worker's storage.
+    parent: ComponentIdentity,
+}
+
+impl WorkerInstance {
+    /// Load the worker and hand it messages until its inbox closes; fails if it cannot be
+    /// loaded or traps.
+    fn run(self, request: WorkerRequest, outbox: &WorkerOutbox) -> Result<()> {
+        let source = &request.source;
+        let identity = match source {
+            // Precompiled code is trusted as is, which a guest-chosen file cannot be.
+            ComponentSource::Path(path) if precompile::is_precompiled(path) => {
+                bail!("workers cannot be precompiled components")
+            }
+            ComponentSource::Path(path) => {
+                let bytes = std::fs::read(path)
+                    .with_context(|| format!("failed to read worker {}", path.display()))?;
+                ComponentIdentity::worker(&self.parent, path, &bytes)
+            }
+            ComponentSource::Embedded { bytes, label } => {
+                ComponentIdentity::worker_from_bytes(&self.parent, label, bytes)
+            }
+        };
+        let component = ComponentRuntime::load_component(&self.engine, source)?;
+        let storage = self.data_dirs.as_ref().and_then(|dirs| {
+            Storage::open(dirs, &identity, &[])
+                .inspect_err(|err| {
+                    tracing::warn!(error = %format!("{err:#}"), "worker storage unavailable");
+                })
+                .ok()
+        });
+
+        let mut linker =
+            ComponentRuntime::linker(&self.engine, &component, self.sandbox.interfaces)?;
+        component::worker::vello::canvas::worker::add_to_linker(
+            &mut linker,
+            |state: &mut StoreState| &mut state.host,
+        )
+        .context("failed to add worker bindings to linker")?;
+        let mut store = ComponentRuntime::new_store(
+            &self.engine,
+            &source.label(),
+            &self.launch,
+            &self.sandbox,
+        )?;
+        let host = &mut store.data_mut().host;
+        host.set_limits(self.limits);
+        host.set_database(database_for(
+            storage.as_ref(),
+            &self.limits,
+            self.sandbox.call_timeout,
+        ));
+        host.set_storage(storage);
+        host.inherit_dropped_capabilities(&self.dropped);
+        host.set_worker_outbox(Some(outbox.clone()));
+        let bindings = component::worker::CanvasWorker::instantiate(
+            &mut store, &component, &linker,
+        )
+        .context("failed to instantiate worker; is it built for the canvas-worker world?")?;
+
+        for message in request.inbox {
+            store.data_mut().host.enter_phase(Phase::Worker);
+            if let Some(ticks) = self.sandbox.deadline_ticks() {
+                store.set_epoch_deadline(ticks);
+            }
+            let result = bindings
+                .vello_canvas_worker_app()
+                .call_on_message(&mut store, &message);
+            store.data_mut().finish_call();
+            result.context("worker trapped")?;
+        }
+        Ok(())
+    }
+}
+
+/// The database beside `storage`, or an unavailable one without storage. Statements are
+/// interrupted after `timeout`, the caller's call timeout.
+fn database_for(
+    storage: Option<&Storage>,
+    limits: &ResourceLimits,
+    timeout: Option<Duration>,
+) -> Database {
+    storage.map_or_else(Database::default, |storage| {
+        Database::new(
+            storage.dir().join(database::DATABASE_FILE),
+            limits.max_database_bytes,
+        )
+        .with_timeout(timeout)
+    })
 }
 
 fn to_wit_logical_size(size: LogicalSize) -> guest_app::LogicalSize {
@@ -258,6 +1427,23 @@ fn to_wit_logical_size(size: LogicalSize) -> guest_app::LogicalSize {
     }
 }
 
+/// Synthetic mouse event for the `simulate_*` helpers; `pressed` is the primary button state
+/// after the event.
+fn mouse_event(position: Vec2, pressed: bool) -> PointerEvent {
+    PointerEvent {
+        kind: PointerKind::Mouse,
+        position,
+        buttons: PointerButtons {
+            primary: pressed,
+            secondary: false,
+        },
+        modifiers: Modifiers::default(),
+        pointer_id: 0,
+        coalesced_count: 1,
+        history: Vec::new(),
+    }
+}
+
 fn to_wit_pointer_event(event: &PointerEvent) -> guest_app::PointerEvent {
     guest_app::PointerEvent {
         kind: match event.kind {
@@ -265,16 +1451,36 @@ fn to_wit_pointer_event(event: &PointerEvent) -> guest_app::PointerEvent {
             PointerKind::Touch => guest_app::PointerKind::Touch,
             PointerKind::Pen => guest_app::PointerKind::Pen,
         },
-        position: component::vello::canvas::math::Vec2 {
-            x: event.position[0],
-            y: event.position[1],
-        },
+        position: event.position.into(),
         buttons: guest_app::PointerButton {
             primary: event.buttons.primary,
             secondary: event.buttons.secondary,
         },
         modifiers: to_wit_modifiers(event.modifiers),
         pointer_id: event.pointer_id,
+        coalesced_count: event.coalesced_count,
+        history: event
+            .history
+            .iter()
+            .map(|sample| guest_app::PointerSample {
+                position: sample.position.into(),
+                time_ms: sample.time_ms,
+            })
+            .collect(),
+    }
+}
+
+fn to_wit_gesture_event(event: &GestureEvent) -> guest_app::GestureEvent {
+    guest_app::GestureEvent {
+        phase: match event.phase {
+            GesturePhase::Started => guest_app::GesturePhase::Started,
+            GesturePhase::Changed => guest_app::GesturePhase::Changed,
+            GesturePhase::Ended => guest_app::GesturePhase::Ended,
+            GesturePhase::Cancelled => guest_app::GesturePhase::Cancelled,
+        },
+        position: event.position.into(),
+        modifiers: to_wit_modifiers(event.modifiers),
+        delta: event.delta,
     }
 }
 
@@ -284,6 +1490,12 @@ fn to_wit_key_event(event: &KeyEvent) -> guest_app::KeyEvent {
         code: event.code.clone(),
         modifiers: to_wit_modifiers(event.modifiers),
         is_repeat: event.is_repeat,
+        location: match event.location {
+            KeyLocation::Standard => guest_app::KeyLocation::Standard,
+            KeyLocation::Left => guest_app::KeyLocation::Left,
+            KeyLocation::Right => guest_app::KeyLocation::Right,
+            KeyLocation::Numpad => guest_app::KeyLocation::Numpad,
+        },
     }
 }
 
@@ -293,5 +1505,37 @@ fn to_wit_modifiers(mods: Modifiers) -> guest_app::Modifiers {
         ctrl: mods.ctrl,
         alt: mods.alt,
         meta: mods.meta,
+        keys: to_wit_modifier_keys(mods.keys),
+        locks: to_wit_lock_keys(mods.locks),
+        num_lock_known: mods.locks.num_lock.is_some(),
     }
 }
+
+fn to_wit_modifier_keys(keys: ModifierKeys) -> guest_app::ModifierKeys {
+    use guest_app::ModifierKeys as Keys;
+    [
+        (keys.left_shift, Keys::LEFT_SHIFT),
+        (keys.right_shift, Keys::RIGHT_SHIFT),
+        (keys.left_ctrl, Keys::LEFT_CTRL),
+        (keys.right_ctrl, Keys::RIGHT_CTRL),
+        (keys.left_alt, Keys::LEFT_ALT),
+        (keys.right_alt, Keys::RIGHT_ALT),
+        (keys.left_meta, Keys::LEFT_META),
+        (keys.right_meta, Keys::RIGHT_META),
+    ]
+    .into_iter()
+    .filter(|(held, _)| *held)
+    .fold(Keys::empty(), |acc, (_, flag)| acc | flag)
+}
+
+fn to_wit_lock_keys(locks: LockKeys) -> guest_app::LockKeys {
+    use guest_app::LockKeys as Locks;
+    [
+        (locks.caps_lock, Locks::CAPS_LOCK),
+        (locks.num_lock == Some(true), Locks::NUM_LOCK),
+        (locks.scroll_lock, Locks::SCROLL_LOCK),
+    ]
+    .into_iter()
+    .filter(|(on, _)| *on)
+    .fold(Locks::empty(), |acc, (_, flag)| acc | flag)
+}
@@ -0,0 +1,167 @@
+//! What a component's store is allowed to reach: its stdio, preopened directories, how
+//! long a single guest call may run, how much memory it may grow to and which host
+//! interfaces are linked. The window host uses the defaults; embedders pick their own
+//! through [`ComponentRuntimeBuilder`](crate::runtime::ComponentRuntimeBuilder).
+
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use wasmtime::{Engine, StoreLimits, StoreLimitsBuilder};
+use wasmtime_wasi::{DirPerms, FilePerms, WasiCtxBuilder};
+
+use crate::stdio::{GuestOutput, GuestStream};
+
+/// How often the epoch ticker advances; call timeouts are rounded up to this.
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// Where the guest's WASI stdin, stdout and stderr go.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GuestStdio {
+    /// Stdin is inherited; stdout and stderr are split into lines and surfaced through
+    /// tracing and the recent-log buffer.
+    #[default]
+    Capture,
+    /// All three streams are the host process's own.
+    Inherit,
+    /// Stdin is empty and output is discarded.
+    Null,
+}
+
+/// A host directory the guest sees at `guest_path`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Preopen {
+    pub host_path: PathBuf,
+    pub guest_path: String,
+    pub read_only: bool,
+}
+
+/// Which host interfaces are linked. An import left unlinked still instantiates but
+/// traps when called, so a component that never touches it runs unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HostInterfaces {
+    /// `wasi:cli`, `wasi:filesystem`, `wasi:clocks` and the rest of WASI preview 2.
+    pub wasi: bool,
+    /// `vello:canvas/host`: drawing, input state and window control.
+    pub canvas: bool,
+    /// `vello:canvas/database`: SQL against the component's own SQLite database.
+    pub database: bool,
+}
+
+impl Default for HostInterfaces {
+    fn default() -> Self {
+        Self {
+            wasi: true,
+            canvas: true,
+            database: true,
+        }
+    }
+}
+
+impl HostInterfaces {
+    /// The interfaces linked in both `self` and `other`.
+    pub fn intersect(self, other: Self) -> Self {
+        Self {
+            wasi: self.wasi && other.wasi,
+            canvas: self.canvas && other.canvas,
+            database: self.database && other.database,
+        }
+    }
+}
+
+/// The sandbox a component is instantiated into; kept across reloads.
+#[derive(Clone, Debug, Default)]
+pub struct Sandbox {
+    pub stdio: GuestStdio,
+    pub preopens: Vec<Preopen>,
+    /// Longest a single guest call may run before it traps. `None` lets it run forever.
+    pub call_timeout: Option<Duration>,
+    /// Largest a single linear memory may grow to, in bytes.
+    pub max_memory_bytes: Option<usize>,
+    pub interfaces: HostInterfaces,
+}
+
+impl Sandbox {
+    /// Apply the stdio and preopen settings, returning the sink captured output lands in.
+    pub(crate) fn configure_wasi(&self, builder: &mut WasiCtxBuilder) -> Result<GuestOutput> {
+        let output = GuestOutput::new();
+        match self.stdio {
+            GuestStdio::Capture => {
+                builder
+                    .inherit_stdin()
+                    .stdout(output.stream(GuestStream::Stdout))
+                    .stderr(output.stream(GuestStream::Stderr));
+            }
+            GuestStdio::Inherit => {
+                builder.inherit_stdio();
+            }
+            GuestStdio::Null => {}
+        }
+        for preopen in &self.preopens {
+            let (dir_perms, file_perms) = if preopen.read_only {
+                (DirPerms::READ, FilePerms::READ)
+            } else {
+                (DirPerms::all(), FilePerms::all())
+            };
+            builder
+                .preopened_dir(
+                    &preopen.host_path,
+                    &preopen.guest_path,
+                    dir_perms,
+                    file_perms,
+                )
+                .with_context(|| format!("failed to preopen {}", preopen.host_path.display()))?;
+        }
+        Ok(output)
+    }
+
+    pub(crate) fn store_limits(&self) -> StoreLimits {
+        let mut limits = StoreLimitsBuilder::new();
+        if let Some(bytes) = self.max_memory_bytes {
+            limits = limits.memory_size(bytes);
+        }
+        limits.build()
+    }
+
+    /// Epoch ticks a guest call may run for, when a timeout is set.
+    pub(crate) fn deadline_ticks(&self) -> Option<u64> {
+        self.call_timeout.map(|timeout| {
+            let ticks = timeout.as_nanos().div_ceil(EPOCH_TICK.as_nanos());
+            u64::try_from(ticks).unwrap_or(u64::MAX).max(1)
+        })
+    }
+}
+
+/// Advance `engine`'s epoch every [`EPOCH_TICK`] until the engine is dropped.
+pub(crate) fn spawn_epoch_ticker(engine: &Engine) -> Result<()> {
+    let engine = engine.weak();
+    thread::Builder::new()
+        .name("frontier-epoch".into())
+        .spawn(move || {
+            while let Some(engine) = engine.upgrade() {
+                engine.increment_epoch();
+                drop(engine);
+                thread::sleep(EPOCH_TICK);
+            }
+        })
+        .context("failed to spawn epoch ticker")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Sandbox;
+
+    #[test]
+    fn rounds_call_timeouts_up_to_whole_ticks() {
+        let mut sandbox = Sandbox::default();
+        assert_eq!(sandbox.deadline_ticks(), None);
+        sandbox.call_timeout = Some(Duration::from_millis(25));
+        assert_eq!(sandbox.deadline_ticks(), Some(3));
+        sandbox.call_timeout = Some(Duration::ZERO);
+        assert_eq!(sandbox.deadline_ticks(), Some(1));
+    }
+}
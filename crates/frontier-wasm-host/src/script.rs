@@ -0,0 +1,438 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::geometry::Vec2;
+use crate::graphics::DEFAULT_CLEAR_COLOR;
+use crate::host::{Color, DrawCommand, FrameOutput};
+use crate::launch::LaunchConfig;
+use crate::limits::ResourceLimits;
+use crate::model::{
+    KeyEvent, KeyLocation, LogicalSize, Modifiers, PointerButtons, PointerEvent, PointerKind,
+};
+use crate::runtime::{ComponentRuntime, ComponentSource};
+
+/// dt used for frames rendered implicitly before an assertion.
+const DEFAULT_FRAME_MS: f32 = 1000.0 / 60.0;
+const DEFAULT_PIXEL_TOLERANCE: f32 = 0.02;
+
+/// A headless test script, loaded from YAML:
+///
+/// ```yaml
+/// component: ../target/wasm32-wasip1/debug/counter_component.wasm
+/// size: { width: 800, height: 600 }
+/// steps:
+///   - click: [640, 424]
+///   - expect-text: "1"
+///   - expect-pixel: { x: 10, y: 10, color: "#171a1f" }
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestScript {
+    /// Component to load, relative to the script. Defaults to the one given on the command line.
+    #[serde(default)]
+    pub component: Option<PathBuf>,
+    #[serde(default)]
+    pub size: ScriptSize,
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
+    pub steps: Vec<Step>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptSize {
+    pub width: f32,
+    pub height: f32,
+    #[serde(default = "default_scale_factor")]
+    pub scale_factor: f32,
+}
+
+impl Default for ScriptSize {
+    fn default() -> Self {
+        Self {
+            width: 800.0,
+            height: 600.0,
+            scale_factor: 1.0,
+        }
+    }
+}
+
+impl From<ScriptSize> for LogicalSize {
+    fn from(size: ScriptSize) -> Self {
+        LogicalSize {
+            width: size.width,
+            height: size.height,
+            scale_factor: size.scale_factor,
+        }
+    }
+}
+
+fn default_scale_factor() -> f32 {
+    1.0
+}
+
+/// One scripted event or assertion. Positions are `[x, y]` in logical pixels.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Step {
+    /// Call `frame` with the given dt in milliseconds.
+    Frame(f32),
+    Resize {
+        width: f32,
+        height: f32,
+    },
+    PointerMove([f32; 2]),
+    PointerDown([f32; 2]),
+    PointerUp([f32; 2]),
+    /// Move, press, and release the primary button.
+    Click([f32; 2]),
+    /// Press and release a key (`"Enter"`, `"+"`).
+    Key(String),
+    KeyDown(String),
+    KeyUp(String),
+    /// Exact number of draw commands in the latest frame.
+    ExpectCommands(usize),
+    /// Some `draw-text` command draws exactly this text.
+    ExpectText(String),
+    ExpectNoText(String),
+    ExpectPixel(PixelExpectation),
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PixelExpectation {
+    pub x: f32,
+    pub y: f32,
+    pub color: ScriptColor,
+    /// Maximum per-channel difference, in 0.0-1.0 units.
+    #[serde(default)]
+    pub tolerance: Option<f32>,
+}
+
+/// `"#rrggbb"`, `"#rrggbbaa"`, or `[r, g, b]` / `[r, g, b, a]` with 0.0-1.0 channels.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ScriptColor {
+    Hex(String),
+    Channels(Vec<f32>),
+}
+
+impl ScriptColor {
+    fn to_color(&self) -> Result<Color> {
+        let channels = match self {
+            ScriptColor::Hex(hex) => {
+                let digits = hex.strip_prefix('#').unwrap_or(hex);
+                if !matches!(digits.len(), 6 | 8) || !digits.is_ascii() {
+                    bail!("invalid hex color '{hex}'");
+                }
+                (0..digits.len())
+                    .step_by(2)
+                    .map(|i| {
+                        u8::from_str_radix(&digits[i..i + 2], 16)
+                            .map(|v| v as f32 / 255.0)
+                            .with_context(|| format!("invalid hex color '{hex}'"))
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            }
+            ScriptColor::Channels(channels) => channels.clone(),
+        };
+        match channels[..] {
+            [r, g, b] => Ok(Color { r, g, b, a: 1.0 }),
+            [r, g, b, a] => Ok(Color { r, g, b, a }),
+            _ => bail!("colors need 3 or 4 channels, got {}", channels.len()),
+        }
+    }
+}
+
+impl TestScript {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read test script {}", path.display()))?;
+        Self::from_yaml(&text).with_context(|| format!("invalid test script {}", path.display()))
+    }
+
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(text)?)
+    }
+
+    /// The script's own component, resolved relative to the directory holding the script.
+    pub fn component_source(&self, script_dir: &Path) -> Option<ComponentSource> {
+        self.component
+            .as_ref()
+            .map(|path| ComponentSource::from_path(script_dir.join(path)))
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Frame(dt) => write!(f, "frame {dt}"),
+            Step::Resize { width, height } => write!(f, "resize {width}x{height}"),
+            Step::PointerMove([x, y]) => write!(f, "pointer-move ({x}, {y})"),
+            Step::PointerDown([x, y]) => write!(f, "pointer-down ({x}, {y})"),
+            Step::PointerUp([x, y]) => write!(f, "pointer-up ({x}, {y})"),
+            Step::Click([x, y]) => write!(f, "click ({x}, {y})"),
+            Step::Key(key) => write!(f, "key {key:?}"),
+            Step::KeyDown(key) => write!(f, "key-down {key:?}"),
+            Step::KeyUp(key) => write!(f, "key-up {key:?}"),
+            Step::ExpectCommands(count) => write!(f, "expect-commands {count}"),
+            Step::ExpectText(text) => write!(f, "expect-text {text:?}"),
+            Step::ExpectNoText(text) => write!(f, "expect-no-text {text:?}"),
+            Step::ExpectPixel(pixel) => write!(f, "expect-pixel ({}, {})", pixel.x, pixel.y),
+        }
+    }
+}
+
+/// Drives a component through a [`TestScript`] without a window or GPU.
+pub struct ScriptRunner {
+    runtime: ComponentRuntime,
+    size: LogicalSize,
+    buttons: PointerButtons,
+    frame: Option<FrameOutput>,
+    /// An event was delivered since the last frame, so assertions render a fresh one.
+    stale: bool,
+}
+
+impl ScriptRunner {
+    pub fn new(source: ComponentSource, launch: LaunchConfig, size: ScriptSize) -> Result<Self> {
+        let mut runtime = ComponentRuntime::with_launch(source, launch)?;
+        let size = LogicalSize::from(size);
+        runtime.call_init(size).context("component init failed")?;
+        Ok(Self {
+            runtime,
+            size,
+            buttons: PointerButtons::default(),
+            frame: None,
+            stale: true,
+        })
+    }
+
+    pub fn set_limits(&mut self, limits: ResourceLimits) {
+        self.runtime.set_limits(limits);
+    }
+
+    /// Run every step, stopping at the first failure.
+    pub fn run(&mut self, steps: &[Step]) -> Result<()> {
+        for (index, step) in steps.iter().enumerate() {
+            self.run_step(step)
+                .with_context(|| format!("step {} ({step}) failed", index + 1))?;
+            tracing::debug!(step = index + 1, %step, "script step passed");
+        }
+        Ok(())
+    }
+
+    pub fn run_step(&mut self, step: &Step) -> Result<()> {
+        match step {
+            Step::Frame(dt_ms) => self.render(*dt_ms)?,
+            Step::Resize { width, height } => {
+                self.size.width = *width;
+                self.size.height = *height;
+                self.runtime.call_resize(self.size)?;
+                self.stale = true;
+            }
+            Step::PointerMove(position) => self.pointer_move((*position).into())?,
+            Step::PointerDown(position) => self.pointer_button((*position).into(), true)?,
+            Step::PointerUp(position) => self.pointer_button((*position).into(), false)?,
+            Step::Click([x, y]) => {
+                self.runtime.simulate_click(*x, *y)?;
+                self.buttons.primary = false;
+                self.stale = true;
+            }
+            Step::Key(key) => {
+                self.runtime.simulate_key(key)?;
+                self.stale = true;
+            }
+            Step::KeyDown(key) => self.key(key, true)?,
+            Step::KeyUp(key) => self.key(key, false)?,
+            Step::ExpectCommands(expected) => {
+                let actual = self.current_frame()?.commands.len();
+                if actual != *expected {
+                    bail!("expected {expected} draw commands, found {actual}");
+                }
+            }
+            Step::ExpectText(text) => {
+                let frame = self.current_frame()?;
+                if !frame.contains_text(text) {
+                    let drawn: Vec<_> = frame.texts().collect();
+                    bail!("text {text:?} was not drawn; drawn text: {drawn:?}");
+                }
+            }
+            Step::ExpectNoText(text) => {
+                if self.current_frame()?.contains_text(text) {
+                    bail!("text {text:?} was drawn");
+                }
+            }
+            Step::ExpectPixel(expected) => {
+                let want = expected.color.to_color()?;
+                let got = resolve_pixel(self.current_frame()?, expected.x, expected.y);
+                let tolerance = expected.tolerance.unwrap_or(DEFAULT_PIXEL_TOLERANCE);
+                let channels = [
+                    (want.r, got.r),
+                    (want.g, got.g),
+                    (want.b, got.b),
+                    (want.a, got.a),
+                ];
+                if channels.iter().any(|(w, g)| (w - g).abs() > tolerance) {
+                    bail!(
+                        "expected color [{:.3}, {:.3}, {:.3}, {:.3}], found [{:.3}, {:.3}, {:.3}, {:.3}]",
+                        want.r, want.g, want.b, want.a, got.r, got.g, got.b, got.a
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn render(&mut self, dt_ms: f32) -> Result<()> {
+        let result = self.runtime.call_frame(dt_ms)?;
+        self.frame = Some(result.frame);
+        self.stale = false;
+        Ok(())
+    }
+
+    fn current_frame(&mut self) -> Result<&FrameOutput> {
+        if self.stale || self.frame.is_none() {
+            self.render(DEFAULT_FRAME_MS)?;
+        }
+        Ok(self.frame.as_ref().expect("frame rendered above"))
+    }
+
+    fn pointer_event(&self, position: Vec2) -> PointerEvent {
+        PointerEvent {
+            kind: PointerKind::Mouse,
+            position,
+            buttons: self.buttons,
+            modifiers: Modifiers::default(),
+            pointer_id: 0,
+            coalesced_count: 1,
+            history: Vec::new(),
+        }
+    }
+
+    fn pointer_move(&mut self, position: Vec2) -> Result<()> {
+        self.runtime
+            .call_pointer_move(&self.pointer_event(position))?;
+        self.stale = true;
+        Ok(())
+    }
+
+    fn pointer_button(&mut self, position: Vec2, pressed: bool) -> Result<()> {
+        self.buttons.primary = pressed;
+        let event = self.pointer_event(position);
+        if pressed {
+            self.runtime.call_pointer_down(&event)?;
+        } else {
+            self.runtime.call_pointer_up(&event)?;
+        }
+        self.stale = true;
+        Ok(())
+    }
+
+    fn key(&mut self, key: &str, pressed: bool) -> Result<()> {
+        let event = KeyEvent {
+            key: key.to_string(),
+            code: key.to_string(),
+            modifiers: Modifiers::default(),
+            is_repeat: false,
+            location: KeyLocation::Standard,
+        };
+        if pressed {
+            self.runtime.call_key_down(&event)?;
+        } else {
+            self.runtime.call_key_up(&event)?;
+        }
+        self.stale = true;
+        Ok(())
+    }
+}
+
+/// Colour at a logical position, composited from the clear colour and `fill-rect` commands.
+/// Text is not rasterised, so assertions should sample away from glyphs.
+fn resolve_pixel(frame: &FrameOutput, x: f32, y: f32) -> Color {
+    let mut color = frame.clear_color.unwrap_or(DEFAULT_CLEAR_COLOR);
+    let point = Vec2 { x, y };
+    for command in &frame.commands {
+        let covers = command
+            .bounds()
+            .is_some_and(|bounds| bounds.contains(point));
+        if let (true, DrawCommand::FillRect { color: fill, .. }) = (covers, command) {
+            let a = fill.a.clamp(0.0, 1.0);
+            color = Color {
+                r: fill.r * a + color.r * (1.0 - a),
+                g: fill.g * a + color.g * (1.0 - a),
+                b: fill.b * a + color.b * (1.0 - a),
+                a: a + color.a * (1.0 - a),
+            };
+        }
+    }
+    color
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_pixel, ScriptColor, Step, TestScript};
+    use crate::geometry::Vec2;
+    use crate::host::{Color, DrawCommand, FrameOutput};
+
+    #[test]
+    fn parses_yaml_scripts() {
+        let script = TestScript::from_yaml(
+            r##"
+size: { width: 400, height: 300 }
+steps:
+  - click: [10, 20]
+  - key: Enter
+  - frame: 16
+  - expect-commands: 3
+  - expect-text: "1"
+  - expect-pixel: { x: 1, y: 2, color: "#ff000080", tolerance: 0.1 }
+"##,
+        )
+        .unwrap();
+        assert_eq!(script.size.width, 400.0);
+        assert_eq!(script.size.scale_factor, 1.0);
+        assert_eq!(script.steps[0], Step::Click([10.0, 20.0]));
+        assert_eq!(script.steps[1], Step::Key("Enter".into()));
+        assert_eq!(script.steps[4], Step::ExpectText("1".into()));
+        let Step::ExpectPixel(pixel) = &script.steps[5] else {
+            panic!("expected a pixel step");
+        };
+        let color = pixel.color.to_color().unwrap();
+        assert_eq!((color.r, color.g), (1.0, 0.0));
+        assert!((color.a - 128.0 / 255.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rejects_malformed_colors() {
+        assert!(ScriptColor::Hex("#12345".into()).to_color().is_err());
+        assert!(ScriptColor::Channels(vec![1.0, 0.0]).to_color().is_err());
+    }
+
+    #[test]
+    fn composites_fill_rects_over_the_clear_color() {
+        let fill = |x, w, a| DrawCommand::FillRect {
+            origin: Vec2 { x, y: 0.0 },
+            size: Vec2 { x: w, y: 10.0 },
+            color: Color {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+                a,
+            },
+        };
+        let frame = FrameOutput {
+            clear_color: Some(Color {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0,
+            }),
+            commands: vec![fill(0.0, 10.0, 1.0), fill(5.0, 10.0, 0.5)],
+        };
+        assert_eq!(resolve_pixel(&frame, 2.0, 2.0).r, 1.0);
+        assert_eq!(resolve_pixel(&frame, 12.0, 2.0).r, 0.5);
+        assert_eq!(resolve_pixel(&frame, 30.0, 2.0).r, 0.0);
+    }
+}
@@ -0,0 +1,342 @@
+//! Host-managed scroll regions. A guest declares where a region shows and how large its
+//! content is; wheel turns, touch drags and the fling after a quick drag then move the
+//! region's offset here, with no round trip through the guest per input event, and the
+//! guest hears about each new offset through `scroll-changed`. Drags and flings move with
+//! the physics in `inertia`, so a region pulled past its ends bounces back. Commands drawn between
+//! `push-scroll-region` and `pop-scroll-region` are moved by the offset and clipped to
+//! the region as they are recorded, so a list draws its rows once at content positions.
+
+use crate::geometry::{Rect, Size, Vec2};
+use crate::inertia::Inertia;
+
+/// Regions a guest may have defined at once.
+pub const MAX_SCROLL_REGIONS: usize = 256;
+/// Logical pixels scrolled per line of a line-based wheel delta.
+pub const WHEEL_LINE: f32 = 40.0;
+
+/// Input the host routes to scroll regions, in window logical pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScrollInput {
+    /// A wheel or touchpad scroll at `position`. Positive deltas scroll towards the top
+    /// left, as winit reports them.
+    Wheel { position: Vec2, delta: Vec2 },
+    /// A touch went down at `position`.
+    DragStart {
+        pointer: u64,
+        position: Vec2,
+        time_ms: f64,
+    },
+    DragMove {
+        pointer: u64,
+        position: Vec2,
+        time_ms: f64,
+    },
+    /// The touch lifted or was cancelled.
+    DragEnd { pointer: u64, time_ms: f64 },
+}
+
+/// A scroll the host made that the guest should hear about.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScrollEvent {
+    /// Region `id` moved to `offset`, reported through `scroll-changed`.
+    Region { id: u32, offset: Vec2 },
+    /// Kinetic scroller `id` moved by `delta` to `offset`, reported through
+    /// `kinetic-scrolled`.
+    Kinetic { id: u32, offset: Vec2, delta: Vec2 },
+}
+
+#[derive(Clone, Debug)]
+struct Region {
+    id: u32,
+    content_size: Size,
+    viewport: Rect,
+    /// Where the region was last drawn, in window coordinates; input is hit-tested here.
+    screen: Rect,
+    motion: Inertia,
+    /// The touch dragging the region, if any.
+    pointer: Option<u64>,
+}
+
+/// The largest offset that still fills `viewport` with content.
+fn max_offset(content_size: Size, viewport: Rect) -> Vec2 {
+    Vec2::new(
+        (content_size.width - viewport.width).max(0.0),
+        (content_size.height - viewport.height).max(0.0),
+    )
+}
+
+/// A guest's scroll regions, topmost last.
+#[derive(Debug, Default)]
+pub struct ScrollRegions {
+    regions: Vec<Region>,
+}
+
+impl ScrollRegions {
+    /// Declare region `id`, or update its sizes; an offset at rest is kept within the new
+    /// content and a moving one springs back into it.
+    /// False when a size is negative or not finite, or `MAX_SCROLL_REGIONS` are defined.
+    pub fn define(&mut self, id: u32, content_size: Size, viewport: Rect) -> bool {
+        let valid = [
+            content_size.width,
+            content_size.height,
+            viewport.width,
+            viewport.height,
+        ]
+        .iter()
+        .all(|value| value.is_finite() && *value >= 0.0)
+            && viewport.origin().is_finite();
+        if !valid {
+            return false;
+        }
+        if let Some(region) = self.get_mut(id) {
+            let moved = viewport.origin() - region.viewport.origin();
+            region.content_size = content_size;
+            region.viewport = viewport;
+            region.screen = Rect::new(
+                region.screen.x + moved.x,
+                region.screen.y + moved.y,
+                viewport.width,
+                viewport.height,
+            );
+            region
+                .motion
+                .set_bounds(Vec2::ZERO, max_offset(content_size, viewport));
+            return true;
+        }
+        if self.regions.len() >= MAX_SCROLL_REGIONS {
+            return false;
+        }
+        self.regions.push(Region {
+            id,
+            content_size,
+            viewport,
+            screen: viewport,
+            motion: Inertia::new(Vec2::ZERO, Vec2::ZERO, max_offset(content_size, viewport)),
+            pointer: None,
+        });
+        true
+    }
+
+    pub fn remove(&mut self, id: u32) -> bool {
+        let before = self.regions.len();
+        self.regions.retain(|region| region.id != id);
+        self.regions.len() != before
+    }
+
+    pub fn offset(&self, id: u32) -> Option<Vec2> {
+        self.get(id).map(|region| region.motion.offset())
+    }
+
+    pub fn viewport(&self, id: u32) -> Option<Rect> {
+        self.get(id).map(|region| region.viewport)
+    }
+
+    /// Scroll `id` to `offset` within its content and stop any fling.
+    pub fn set_offset(&mut self, id: u32, offset: Vec2) -> bool {
+        let Some(region) = self.get_mut(id) else {
+            return false;
+        };
+        region.motion.jump_to(offset);
+        true
+    }
+
+    /// Record that `id` was drawn at `screen` in window coordinates, over the regions
+    /// drawn before it.
+    pub fn drawn_at(&mut self, id: u32, screen: Rect) {
+        if let Some(index) = self.regions.iter().position(|region| region.id == id) {
+            let mut region = self.regions.remove(index);
+            region.screen = screen;
+            self.regions.push(region);
+        }
+    }
+
+    /// Some region is still flinging or bouncing back after its drag was released.
+    pub fn is_flinging(&self) -> bool {
+        self.regions.iter().any(|region| region.motion.is_moving())
+    }
+
+    /// Apply `input`, returning each region it scrolled with its new offset.
+    pub fn input(&mut self, input: ScrollInput) -> Vec<(u32, Vec2)> {
+        match input {
+            ScrollInput::Wheel { position, delta } => {
+                if !delta.is_finite() {
+                    return Vec::new();
+                }
+                // The innermost region that can still move takes the whole turn.
+                for region in self.regions.iter_mut().rev() {
+                    if !region.screen.contains(position) {
+                        continue;
+                    }
+                    if region.motion.scroll_by(Vec2::ZERO - delta) {
+                        return vec![(region.id, region.motion.offset())];
+                    }
+                }
+                Vec::new()
+            }
+            ScrollInput::DragStart {
+                pointer,
+                position,
+                time_ms,
+            } => {
+                let hit =
+                    self.regions.iter_mut().rev().find(|region| {
+                        region.screen.contains(position) && region.motion.can_scroll()
+                    });
+                if let Some(region) = hit.filter(|region| region.pointer.is_none()) {
+                    region.pointer = Some(pointer);
+                    region.motion.press(position, time_ms);
+                }
+                Vec::new()
+            }
+            ScrollInput::DragMove {
+                pointer,
+                position,
+                time_ms,
+            } => {
+                let Some(region) = self.dragged_by(pointer) else {
+                    return Vec::new();
+                };
+                if region.motion.drag_to(position, time_ms) {
+                    vec![(region.id, region.motion.offset())]
+                } else {
+                    Vec::new()
+                }
+            }
+            ScrollInput::DragEnd { pointer, time_ms } => {
+                if let Some(region) = self.dragged_by(pointer) {
+                    region.pointer = None;
+                    region.motion.release(time_ms);
+                }
+                Vec::new()
+            }
+        }
+    }
+
+    /// Carry every fling and bounce `dt_ms` forward, returning the regions they moved.
+    pub fn advance(&mut self, dt_ms: f32) -> Vec<(u32, Vec2)> {
+        self.regions
+            .iter_mut()
+            .filter_map(|region| {
+                region
+                    .motion
+                    .advance(dt_ms)
+                    .then(|| (region.id, region.motion.offset()))
+            })
+            .collect()
+    }
+
+    fn get(&self, id: u32) -> Option<&Region> {
+        self.regions.iter().find(|region| region.id == id)
+    }
+
+    fn get_mut(&mut self, id: u32) -> Option<&mut Region> {
+        self.regions.iter_mut().find(|region| region.id == id)
+    }
+
+    fn dragged_by(&mut self, pointer: u64) -> Option<&mut Region> {
+        self.regions
+            .iter_mut()
+            .find(|region| region.pointer == Some(pointer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ScrollInput, ScrollRegions};
+    use crate::geometry::{Rect, Size, Vec2};
+
+    fn list() -> ScrollRegions {
+        let mut regions = ScrollRegions::default();
+        assert!(regions.define(
+            1,
+            Size::new(100.0, 1000.0),
+            Rect::new(0.0, 0.0, 100.0, 200.0)
+        ));
+        regions
+    }
+
+    #[test]
+    fn wheel_scrolls_the_innermost_region_that_can_move() {
+        let mut regions = list();
+        assert!(regions.define(2, Size::new(300.0, 50.0), Rect::new(10.0, 10.0, 50.0, 50.0)));
+        let wheel = |y: f32| ScrollInput::Wheel {
+            position: Vec2::new(20.0, 20.0),
+            delta: Vec2::new(0.0, y),
+        };
+        // Region 2 only scrolls sideways, so vertical turns reach the list around it.
+        assert_eq!(regions.input(wheel(-30.0)), [(1, Vec2::new(0.0, 30.0))]);
+        assert_eq!(regions.input(wheel(-5000.0)), [(1, Vec2::new(0.0, 800.0))]);
+        assert!(regions.input(wheel(-10.0)).is_empty());
+        let sideways = ScrollInput::Wheel {
+            position: Vec2::new(20.0, 20.0),
+            delta: Vec2::new(-40.0, 0.0),
+        };
+        assert_eq!(regions.input(sideways), [(2, Vec2::new(40.0, 0.0))]);
+
+        // Shrinking the content keeps the offset inside it.
+        assert!(regions.define(
+            1,
+            Size::new(100.0, 300.0),
+            Rect::new(0.0, 0.0, 100.0, 200.0)
+        ));
+        assert_eq!(regions.offset(1), Some(Vec2::new(0.0, 100.0)));
+        assert!(!regions.define(3, Size::new(f32::NAN, 0.0), Rect::default()));
+    }
+
+    #[test]
+    fn drags_follow_the_finger_and_fling_until_they_slow_down() {
+        let mut regions = list();
+        let touch = |y: f32, time_ms: f64| ScrollInput::DragMove {
+            pointer: 7,
+            position: Vec2::new(50.0, y),
+            time_ms,
+        };
+        regions.input(ScrollInput::DragStart {
+            pointer: 7,
+            position: Vec2::new(50.0, 150.0),
+            time_ms: 0.0,
+        });
+        assert_eq!(
+            regions.input(touch(140.0, 10.0)),
+            [(1, Vec2::new(0.0, 10.0))]
+        );
+        assert_eq!(
+            regions.input(touch(120.0, 20.0)),
+            [(1, Vec2::new(0.0, 30.0))]
+        );
+        assert!(!regions.is_flinging(), "still held");
+        regions.input(ScrollInput::DragEnd {
+            pointer: 7,
+            time_ms: 25.0,
+        });
+        assert!(regions.is_flinging());
+
+        let mut last = 30.0;
+        for _ in 0..200 {
+            for (id, offset) in regions.advance(16.0) {
+                assert_eq!(id, 1);
+                assert!(offset.y > last);
+                last = offset.y;
+            }
+        }
+        assert!(!regions.is_flinging());
+        assert!(last > 100.0 && last < 800.0, "flung to {last}");
+
+        // A drag that rests before lifting stops where it is.
+        regions.input(ScrollInput::DragStart {
+            pointer: 8,
+            position: Vec2::new(50.0, 100.0),
+            time_ms: 1000.0,
+        });
+        regions.input(ScrollInput::DragMove {
+            pointer: 8,
+            position: Vec2::new(50.0, 80.0),
+            time_ms: 1010.0,
+        });
+        regions.input(ScrollInput::DragEnd {
+            pointer: 8,
+            time_ms: 1200.0,
+        });
+        assert!(!regions.is_flinging());
+    }
+}
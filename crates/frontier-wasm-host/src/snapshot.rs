@@ -0,0 +1,79 @@
+//! Snapshots of a running component, for checkpoints and for restoring the last good
+//! state after the guest fails.
+//!
+//! Wasmtime does not expose a component's core memories to the host, so a snapshot is
+//! the state the guest hands over through `save-state`. The host adds the window size it
+//! last reported and the content hash of the component. Restoring reinstantiates the
+//! component, runs `init` at that size and passes the state to `restore-state`.
+//!
+//! Encoded as [`MAGIC`], a little-endian `u16` [`FORMAT_VERSION`], then a postcard body.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::model::LogicalSize;
+
+/// Leading bytes of every snapshot.
+const MAGIC: &[u8; 8] = b"FSNAP\r\n\0";
+/// Bumped whenever the body encoding changes; snapshots from other versions are rejected.
+pub const FORMAT_VERSION: u16 = 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Content hash of the component the state came from; see
+    /// [`ComponentIdentity`](crate::identity::ComponentIdentity).
+    pub content_hash: String,
+    /// Size passed to `init` on restore.
+    pub size: LogicalSize,
+    /// What the guest returned from `save-state`.
+    pub guest: Vec<u8>,
+}
+
+impl Snapshot {
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        postcard::to_extend(self, bytes).context("failed to encode snapshot")
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let Some(rest) = bytes.strip_prefix(MAGIC.as_slice()) else {
+            bail!("not a component snapshot");
+        };
+        let Some((version, body)) = rest.split_first_chunk::<2>() else {
+            bail!("truncated component snapshot");
+        };
+        let version = u16::from_le_bytes(*version);
+        if version != FORMAT_VERSION {
+            bail!("snapshot format {version} is not supported (expected {FORMAT_VERSION})");
+        }
+        postcard::from_bytes(body).context("corrupt component snapshot")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Snapshot;
+    use crate::model::LogicalSize;
+
+    #[test]
+    fn round_trips_and_rejects_foreign_bytes() {
+        let snapshot = Snapshot {
+            content_hash: "ab".repeat(32),
+            size: LogicalSize {
+                width: 640.0,
+                height: 480.0,
+                scale_factor: 2.0,
+            },
+            guest: vec![7, 0, 0, 0],
+        };
+        let bytes = snapshot.encode().unwrap();
+        assert_eq!(Snapshot::decode(&bytes).unwrap(), snapshot);
+
+        assert!(Snapshot::decode(b"\0asm\x0d\0\x01\0").is_err());
+        assert!(Snapshot::decode(&bytes[..9]).is_err());
+        let mut future = bytes.clone();
+        future[8] = 9;
+        assert!(Snapshot::decode(&future).is_err());
+    }
+}
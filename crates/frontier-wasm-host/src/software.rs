@@ -0,0 +1,601 @@
+//! A CPU [`CanvasBackend`] on tiny-skia. It draws rectangles, text, images, patterns and
+//! particles without wgpu, so tests can check a frame's pixels and the host can run where
+//! no GPU adapter is available. SVG pictures and Lottie animations are Vello scenes and
+//! are skipped.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tiny_skia::{
+    FillRule, FilterQuality, IntSize, Mask, Paint, PathBuilder, Pattern, Pixmap, SpreadMode,
+    Stroke, Transform,
+};
+
+use crate::canvas::CanvasBackend;
+use crate::geometry::{self, Vec2};
+use crate::graphics::{
+    layout_text, placeholder_boxes, FontAssets, DEFAULT_CLEAR_COLOR, PLACEHOLDER_STROKE,
+};
+use crate::host::{Color, FontFamily, FrameOutput, PatternRepeat, TextFlow};
+use crate::images::{FilterCache, Image, ImageStyle};
+use crate::lottie::Animation;
+use crate::particles::ParticleSprite;
+use crate::svg::Picture;
+
+pub struct SoftwareCanvas {
+    pixmap: Pixmap,
+    fonts: FontAssets,
+    scale_factor: f32,
+    filtered_images: FilterCache,
+    /// Premultiplied copies of the images drawn last frame, by [`Image::id`].
+    image_pixmaps: HashMap<u64, Pixmap>,
+    drawn_images: Vec<u64>,
+    /// Device pixels each open clip leaves visible, within the clips around it, and the
+    /// mask covering them; innermost last.
+    clips: Vec<(geometry::Rect, Mask)>,
+}
+
+impl SoftwareCanvas {
+    pub fn new(width: u32, height: u32, scale_factor: f32) -> Result<Self> {
+        Self::with_fonts(width, height, scale_factor, FontAssets::new())
+    }
+
+    pub(crate) fn with_fonts(
+        width: u32,
+        height: u32,
+        scale_factor: f32,
+        fonts: FontAssets,
+    ) -> Result<Self> {
+        Ok(Self {
+            pixmap: Pixmap::new(width, height)
+                .with_context(|| format!("invalid canvas size {width}x{height}"))?,
+            fonts,
+            scale_factor,
+            filtered_images: FilterCache::default(),
+            image_pixmaps: HashMap::new(),
+            drawn_images: Vec::new(),
+            clips: Vec::new(),
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.pixmap.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.pixmap.height()
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    pub fn clear(&mut self, color: Color) {
+        if let Some(color) = to_skia(color) {
+            self.pixmap.fill(color);
+        }
+    }
+
+    /// Replace the canvas contents with `frame`, cleared to its colour or the host default.
+    pub fn render(&mut self, frame: &FrameOutput) {
+        self.clear(frame.clear_color.unwrap_or(DEFAULT_CLEAR_COLOR));
+        self.encode_commands(&frame.commands);
+        let drawn = std::mem::take(&mut self.drawn_images);
+        self.image_pixmaps.retain(|id, _| drawn.contains(id));
+        self.filtered_images.trim();
+    }
+
+    /// Straight-alpha RGBA8 of the pixel at `(x, y)`, in physical pixels.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        let color = self.pixmap.pixel(x, y)?.demultiply();
+        Some([color.red(), color.green(), color.blue(), color.alpha()])
+    }
+
+    /// Straight-alpha RGBA8 of the whole canvas, row-major.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect()
+    }
+
+    /// Save the canvas as a PNG, returning its size.
+    pub fn save_png(&self, path: &Path) -> Result<(u32, u32)> {
+        let (width, height) = (self.width(), self.height());
+        let file = File::create(path)
+            .with_context(|| format!("failed to create screenshot {}", path.display()))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.to_rgba()))
+            .with_context(|| format!("failed to encode screenshot {}", path.display()))?;
+        Ok((width, height))
+    }
+
+    /// Device pixels covered by a logical rectangle, snapped the way the Vello backend
+    /// snaps them.
+    fn physical_rect(&self, origin: Vec2, size: Vec2) -> Option<tiny_skia::Rect> {
+        let snapped = geometry::Rect::new(origin.x, origin.y, size.x, size.y)
+            .snap_to_pixels(self.scale_factor);
+        tiny_skia::Rect::from_xywh(snapped.x, snapped.y, snapped.width, snapped.height)
+    }
+
+    /// Fill the rectangle with `image` scaled so one copy covers `image_size` logical
+    /// pixels from `origin`, sampled like the Vello backend samples it.
+    fn fill_with_image(
+        &mut self,
+        origin: Vec2,
+        size: Vec2,
+        image: &Image,
+        image_size: Vec2,
+        spread_mode: SpreadMode,
+        style: &ImageStyle,
+    ) {
+        let scale_x = image_size.x * self.scale_factor / image.width() as f32;
+        let scale_y = image_size.y * self.scale_factor / image.height() as f32;
+        if !(scale_x.is_finite() && scale_y.is_finite() && scale_x > 0.0 && scale_y > 0.0) {
+            return;
+        }
+        let Some(rect) = self.physical_rect(origin, size) else {
+            return;
+        };
+        let quality = if scale_x > 1.0 || scale_y > 1.0 {
+            FilterQuality::Nearest
+        } else {
+            FilterQuality::Bilinear
+        };
+        let image = self.filtered_images.get(image, style);
+        self.drawn_images.push(image.id());
+        let source = match self.image_pixmaps.entry(image.id()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => match premultiplied_pixmap(&image) {
+                Some(pixmap) => entry.insert(pixmap),
+                None => return,
+            },
+        }
+        .as_ref();
+        let opacity = if style.opacity.is_nan() {
+            0.0
+        } else {
+            style.opacity
+        };
+        let paint = Paint {
+            shader: Pattern::new(
+                source,
+                spread_mode,
+                quality,
+                opacity,
+                Transform::from_row(
+                    scale_x,
+                    0.0,
+                    0.0,
+                    scale_y,
+                    origin.x * self.scale_factor,
+                    origin.y * self.scale_factor,
+                ),
+            ),
+            ..Paint::default()
+        };
+        let mask = self.clips.last().map(|(_, mask)| mask);
+        self.pixmap
+            .fill_rect(rect, &paint, Transform::identity(), mask);
+    }
+}
+
+impl CanvasBackend for SoftwareCanvas {
+    fn fill_rect(&mut self, origin: Vec2, size: Vec2, color: Color) {
+        let (Some(rect), Some(paint)) = (self.physical_rect(origin, size), solid_paint(color))
+        else {
+            return;
+        };
+        let mask = self.clips.last().map(|(_, mask)| mask);
+        self.pixmap
+            .fill_rect(rect, &paint, Transform::identity(), mask);
+    }
+
+    fn draw_text(
+        &mut self,
+        text: &str,
+        origin: Vec2,
+        size: f32,
+        color: Color,
+        font: FontFamily,
+        flow: TextFlow,
+    ) {
+        use ab_glyph::{Font, GlyphId, OutlineCurve, ScaleFont};
+
+        let Some(paint) = solid_paint(color) else {
+            return;
+        };
+        let physical_origin = origin * self.scale_factor;
+        let font_size = size * self.scale_factor;
+        if !(physical_origin.is_finite()
+            && font_size.is_finite()
+            && font_size > 0.0
+            && flow.rotation.is_finite())
+        {
+            return;
+        }
+        // Glyphs are laid out about the origin, then turned and moved onto it.
+        let transform = Transform::from_translate(physical_origin.x, physical_origin.y)
+            .pre_concat(Transform::from_rotate(flow.rotation.to_degrees()));
+        let Some(face) = self.fonts.face(font) else {
+            let mut boxes = PathBuilder::new();
+            for (corner, size) in placeholder_boxes(text, font_size, flow) {
+                if let Some(rect) = tiny_skia::Rect::from_xywh(corner.x, corner.y, size.x, size.y) {
+                    boxes.push_rect(rect);
+                }
+            }
+            let stroke = Stroke {
+                width: (font_size * PLACEHOLDER_STROKE).max(1.0),
+                ..Stroke::default()
+            };
+            if let Some(boxes) = boxes.finish() {
+                let mask = self.clips.last().map(|(_, mask)| mask);
+                self.pixmap
+                    .stroke_path(&boxes, &paint, &stroke, transform, mask);
+            }
+            return;
+        };
+        let font = &face.font_arc;
+        let scale = font.as_scaled(font_size).scale_factor();
+        let mut path = PathBuilder::new();
+        for glyph in layout_text(font, text, font_size, flow) {
+            let Some(outline) = font.outline(GlyphId(glyph.id as u16)) else {
+                continue;
+            };
+            // Outlines are in font units with y up; place them on the glyph's baseline.
+            let (x, y) = (glyph.x, glyph.y);
+            let point = |p: ab_glyph::Point| (x + p.x * scale.horizontal, y - p.y * scale.vertical);
+            let mut last = None;
+            for curve in outline.curves {
+                let start = match curve {
+                    OutlineCurve::Line(p0, _)
+                    | OutlineCurve::Quad(p0, _, _)
+                    | OutlineCurve::Cubic(p0, _, _, _) => point(p0),
+                };
+                if last != Some(start) {
+                    path.move_to(start.0, start.1);
+                }
+                let end = match curve {
+                    OutlineCurve::Line(_, p1) => {
+                        let p1 = point(p1);
+                        path.line_to(p1.0, p1.1);
+                        p1
+                    }
+                    OutlineCurve::Quad(_, p1, p2) => {
+                        let (p1, p2) = (point(p1), point(p2));
+                        path.quad_to(p1.0, p1.1, p2.0, p2.1);
+                        p2
+                    }
+                    OutlineCurve::Cubic(_, p1, p2, p3) => {
+                        let (p1, p2, p3) = (point(p1), point(p2), point(p3));
+                        path.cubic_to(p1.0, p1.1, p2.0, p2.1, p3.0, p3.1);
+                        p3
+                    }
+                };
+                last = Some(end);
+            }
+        }
+        if let Some(path) = path.finish() {
+            let mask = self.clips.last().map(|(_, mask)| mask);
+            self.pixmap
+                .fill_path(&path, &paint, FillRule::Winding, transform, mask);
+        }
+    }
+
+    fn draw_image(&mut self, image: &Image, origin: Vec2, size: Vec2, style: &ImageStyle) {
+        self.fill_with_image(origin, size, image, size, SpreadMode::Pad, style);
+    }
+
+    /// tiny-skia repeats both axes alike, so `RepeatX` tiles vertically within one row;
+    /// the row is cut to the tile height, as with Vello.
+    fn draw_pattern(
+        &mut self,
+        origin: Vec2,
+        size: Vec2,
+        image: &Image,
+        tile_size: Vec2,
+        repeat: PatternRepeat,
+        style: &ImageStyle,
+    ) {
+        let (spread_mode, height) = match repeat {
+            PatternRepeat::Repeat => (SpreadMode::Repeat, size.y),
+            PatternRepeat::RepeatX => (SpreadMode::Repeat, size.y.min(tile_size.y)),
+            PatternRepeat::Mirror => (SpreadMode::Reflect, size.y),
+        };
+        let size = Vec2 {
+            x: size.x,
+            y: height,
+        };
+        self.fill_with_image(origin, size, image, tile_size, spread_mode, style);
+    }
+
+    fn draw_picture(&mut self, _picture: &Picture, _origin: Vec2, _size: Vec2) {
+        tracing::trace!("software canvas skips draw-svg");
+    }
+
+    fn draw_animation(
+        &mut self,
+        _animation: &Animation,
+        _time_ms: f64,
+        _origin: Vec2,
+        _size: Vec2,
+    ) {
+        tracing::trace!("software canvas skips draw-lottie");
+    }
+
+    fn draw_particles(&mut self, particles: &[ParticleSprite]) {
+        for particle in particles {
+            let radius = particle.size * 0.5 * self.scale_factor;
+            let center = particle.position * self.scale_factor;
+            let (Some(circle), Some(paint)) = (
+                PathBuilder::from_circle(center.x, center.y, radius),
+                solid_paint(particle.color),
+            ) else {
+                continue;
+            };
+            self.pixmap.fill_path(
+                &circle,
+                &paint,
+                FillRule::Winding,
+                Transform::identity(),
+                self.clips.last().map(|(_, mask)| mask),
+            );
+        }
+    }
+
+    fn push_clip(&mut self, origin: Vec2, size: Vec2) {
+        let area = geometry::Rect::new(origin.x, origin.y, size.x, size.y)
+            .snap_to_pixels(self.scale_factor);
+        let area = match self.clips.last() {
+            Some((outer, _)) => outer.intersection(&area),
+            None => area,
+        };
+        let mut mask = Mask::new(self.pixmap.width(), self.pixmap.height())
+            .expect("the mask matches the pixmap, which is never empty");
+        if let Some(rect) = tiny_skia::Rect::from_xywh(area.x, area.y, area.width, area.height) {
+            mask.fill_path(
+                &PathBuilder::from_rect(rect),
+                FillRule::Winding,
+                false,
+                Transform::identity(),
+            );
+        }
+        self.clips.push((area, mask));
+    }
+
+    fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    fn close_clips(&mut self) {
+        self.clips.clear();
+    }
+}
+
+fn to_skia(color: Color) -> Option<tiny_skia::Color> {
+    tiny_skia::Color::from_rgba(color.r, color.g, color.b, color.a)
+}
+
+fn solid_paint(color: Color) -> Option<Paint<'static>> {
+    let mut paint = Paint::default();
+    paint.set_color(to_skia(color)?);
+    Some(paint)
+}
+
+fn premultiplied_pixmap(image: &Image) -> Option<Pixmap> {
+    let rgba = image
+        .rgba()
+        .chunks_exact(4)
+        .flat_map(|pixel| {
+            let color =
+                tiny_skia::ColorU8::from_rgba(pixel[0], pixel[1], pixel[2], pixel[3]).premultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    Pixmap::from_vec(rgba, IntSize::from_wh(image.width(), image.height())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SoftwareCanvas;
+    use crate::canvas::CanvasBackend;
+    use crate::geometry::Vec2;
+    use crate::graphics::FontAssets;
+    use crate::host::{Color, DrawCommand, FontFamily, FrameOutput, TextFlow};
+    use crate::images::{Image, ImageStyle};
+
+    const RED: Color = Color {
+        r: 1.0,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    };
+
+    #[test]
+    fn renders_rects_images_and_text_at_the_scale_factor() {
+        let mut canvas = SoftwareCanvas::new(40, 40, 2.0).unwrap();
+        canvas.render(&FrameOutput {
+            clear_color: Some(Color {
+                r: 0.0,
+                g: 0.0,
+                b: 1.0,
+                a: 1.0,
+            }),
+            commands: vec![
+                DrawCommand::FillRect {
+                    origin: Vec2::new(0.0, 0.0),
+                    size: Vec2::new(5.0, 5.0),
+                    color: RED,
+                },
+                DrawCommand::DrawImage {
+                    image: Image::new(1, 1, vec![0, 255, 0, 255]).unwrap(),
+                    origin: Vec2::new(10.0, 0.0),
+                    size: Vec2::new(5.0, 5.0),
+                    style: ImageStyle::default(),
+                },
+            ],
+        });
+        assert_eq!(canvas.pixel(9, 9), Some([255, 0, 0, 255]));
+        assert_eq!(canvas.pixel(10, 10), Some([0, 0, 255, 255]));
+        assert_eq!(canvas.pixel(25, 5), Some([0, 255, 0, 255]));
+
+        let blank = canvas.to_rgba();
+        canvas.draw_text(
+            "W",
+            Vec2::new(0.0, 18.0),
+            16.0,
+            RED,
+            FontFamily::SansBold,
+            TextFlow::default(),
+        );
+        assert_ne!(canvas.to_rgba(), blank);
+    }
+
+    #[test]
+    fn adjacent_rects_leave_no_seams_at_fractional_scales() {
+        let white = Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        };
+        for scale in [1.0, 1.25, 1.5, 1.75, 2.0, 2.25, 2.5, 3.0] {
+            let mut canvas = SoftwareCanvas::new(100, 100, scale).unwrap();
+            // A row of tiles starting off the logical pixel grid.
+            let commands = (0..8)
+                .map(|index| DrawCommand::FillRect {
+                    origin: Vec2::new(0.3 + index as f32 * 3.7, 1.1),
+                    size: Vec2::new(3.7, 3.7),
+                    color: white,
+                })
+                .collect();
+            canvas.render(&FrameOutput {
+                clear_color: Some(Color {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 1.0,
+                }),
+                commands,
+            });
+            let row = (2.5 * scale) as u32;
+            let start = (0.3 * scale).round() as u32;
+            let end = ((0.3 + 8.0 * 3.7) * scale).round() as u32;
+            for x in start..end {
+                assert_eq!(
+                    canvas.pixel(x, row),
+                    Some([255, 255, 255, 255]),
+                    "pixel {x} at scale {scale}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn clips_nest_and_close_at_the_end_of_the_frame() {
+        let mut canvas = SoftwareCanvas::new(20, 20, 2.0).unwrap();
+        let fill = DrawCommand::FillRect {
+            origin: Vec2::new(0.0, 0.0),
+            size: Vec2::new(10.0, 10.0),
+            color: RED,
+        };
+        canvas.render(&FrameOutput {
+            clear_color: Some(Color::default()),
+            commands: vec![
+                DrawCommand::PopClip,
+                DrawCommand::PushClip {
+                    origin: Vec2::new(1.0, 1.0),
+                    size: Vec2::new(4.0, 4.0),
+                },
+                DrawCommand::PushClip {
+                    origin: Vec2::new(3.0, 3.0),
+                    size: Vec2::new(4.0, 4.0),
+                },
+                fill.clone(),
+                DrawCommand::PopClip,
+            ],
+        });
+        assert_eq!(canvas.pixel(5, 5), Some([0; 4]), "outside the inner clip");
+        assert_eq!(canvas.pixel(6, 6), Some([255, 0, 0, 255]));
+        assert_eq!(canvas.pixel(9, 9), Some([255, 0, 0, 255]));
+        assert_eq!(canvas.pixel(10, 10), Some([0; 4]), "outside the outer clip");
+
+        canvas.render(&FrameOutput {
+            clear_color: Some(Color::default()),
+            commands: vec![fill],
+        });
+        assert_eq!(canvas.pixel(0, 0), Some([255, 0, 0, 255]), "unclipped");
+    }
+
+    #[test]
+    fn draws_placeholder_boxes_without_fonts() {
+        let fonts = FontAssets::from_bytes(b"", b"", b"");
+        let mut canvas = SoftwareCanvas::with_fonts(40, 40, 1.0, fonts).unwrap();
+        canvas.fill_rect(Vec2::ZERO, Vec2::new(4.0, 4.0), RED);
+        assert_eq!(canvas.pixel(1, 1), Some([255, 0, 0, 255]));
+        canvas.draw_text(
+            "W",
+            Vec2::new(10.0, 30.0),
+            20.0,
+            RED,
+            FontFamily::Sans,
+            TextFlow::default(),
+        );
+        // The left edge of the box around the glyph.
+        assert_eq!(canvas.pixel(11, 25).map(|pixel| pixel[0]), Some(255));
+
+        // Turned a quarter clockwise, that edge runs along the top.
+        let fonts = FontAssets::from_bytes(b"", b"", b"");
+        let mut canvas = SoftwareCanvas::with_fonts(40, 40, 1.0, fonts).unwrap();
+        canvas.draw_text(
+            "W",
+            Vec2::new(10.0, 10.0),
+            20.0,
+            RED,
+            FontFamily::Sans,
+            TextFlow {
+                rotation: std::f32::consts::FRAC_PI_2,
+                ..TextFlow::default()
+            },
+        );
+        assert_eq!(canvas.pixel(17, 11).map(|pixel| pixel[0]), Some(255));
+        assert_eq!(canvas.pixel(11, 5).map(|pixel| pixel[0]), Some(0));
+    }
+
+    #[test]
+    fn ignores_hostile_geometry() {
+        let mut canvas = SoftwareCanvas::new(8, 8, f32::NAN).unwrap();
+        let nan = Vec2::new(f32::NAN, f32::INFINITY);
+        canvas.fill_rect(nan, nan, RED);
+        canvas.draw_text(
+            "hi",
+            nan,
+            f32::MAX,
+            RED,
+            FontFamily::Sans,
+            TextFlow::default(),
+        );
+        canvas.set_scale_factor(1.0);
+        canvas.draw_text(
+            "hi",
+            Vec2::ZERO,
+            f32::MAX,
+            RED,
+            FontFamily::Sans,
+            TextFlow::default(),
+        );
+        assert!(SoftwareCanvas::new(0, 8, 1.0).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use std::fmt::Write as _;
+
+use crate::gpu::GpuTimings;
+
+/// A guest-defined timing region recorded through `perf-measure`.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct PerfMeasure {
+    pub name: String,
+    pub duration_ms: f32,
+}
+
+/// Calls to one host import since the previous frame.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct HostCallStats {
+    /// WIT name of the import, or `wasi` for WASI calls.
+    pub name: &'static str,
+    pub calls: u32,
+    /// Time spent in the host across those calls.
+    pub total_ms: f32,
+}
+
+/// Host imports listed individually by the stats overlay; the rest are summed.
+const OVERLAY_HOST_CALLS: usize = 6;
+
+/// Timings for the most recent frame, shown by the stats overlay.
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct FrameStats {
+    pub dt_ms: f32,
+    pub guest_ms: f32,
+    pub render_ms: f32,
+    pub commands: usize,
+    /// Commands skipped for lying entirely outside the window.
+    pub culled: usize,
+    pub gpu: Option<GpuTimings>,
+    pub measures: Vec<PerfMeasure>,
+    /// Host imports called since the previous frame, most time-consuming first.
+    pub host_calls: Vec<HostCallStats>,
+    /// Bytes passed to `draw-text` since the previous frame.
+    pub text_bytes: usize,
+    /// Display refreshes missed just before this frame.
+    pub missed: u32,
+    /// Refreshes missed since the host started.
+    pub missed_total: u64,
+    /// The 1% low frame time over recent animated frames; see [`crate::pacing`].
+    pub one_percent_low_ms: Option<f32>,
+}
+
+impl FrameStats {
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::with_capacity(4 + self.measures.len());
+        let fps = if self.dt_ms > 0.0 {
+            1000.0 / self.dt_ms
+        } else {
+            0.0
+        };
+        lines.push(format!("frame {:.1} ms ({fps:.0} fps)", self.dt_ms));
+        if let Some(low_ms) = self.one_percent_low_ms {
+            lines.push(format!(
+                "1% low {low_ms:.1} ms  missed {}",
+                self.missed_total
+            ));
+        }
+        lines.push(format!(
+            "guest {:.2} ms  render {:.2} ms",
+            self.guest_ms, self.render_ms
+        ));
+        if let Some(gpu) = self.gpu {
+            lines.push(format!(
+                "gpu raster {:.2} ms  blit {:.2} ms",
+                gpu.render_ms, gpu.blit_ms
+            ));
+        }
+        if self.culled > 0 {
+            lines.push(format!(
+                "commands {}  culled {}",
+                self.commands, self.culled
+            ));
+        } else {
+            lines.push(format!("commands {}", self.commands));
+        }
+        if !self.host_calls.is_empty() {
+            let calls: u32 = self.host_calls.iter().map(|call| call.calls).sum();
+            let total_ms: f32 = self.host_calls.iter().map(|call| call.total_ms).sum();
+            lines.push(format!(
+                "host calls {calls} ({total_ms:.2} ms)  text {} B",
+                self.text_bytes
+            ));
+            for call in self.host_calls.iter().take(OVERLAY_HOST_CALLS) {
+                lines.push(format!(
+                    "  {} x{} {:.2} ms",
+                    call.name, call.calls, call.total_ms
+                ));
+            }
+            let rest = &self.host_calls[self.host_calls.len().min(OVERLAY_HOST_CALLS)..];
+            if !rest.is_empty() {
+                let calls: u32 = rest.iter().map(|call| call.calls).sum();
+                lines.push(format!("  {} more imports x{calls}", rest.len()));
+            }
+        }
+        if !self.measures.is_empty() {
+            lines.push("measures:".to_string());
+            for measure in &self.measures {
+                let mut line = String::new();
+                let _ = write!(
+                    &mut line,
+                    "  {} {:.2} ms",
+                    measure.name, measure.duration_ms
+                );
+                lines.push(line);
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FrameStats, HostCallStats, PerfMeasure};
+    use crate::gpu::GpuTimings;
+
+    #[test]
+    fn formats_frame_stats_with_measures() {
+        let stats = FrameStats {
+            dt_ms: 20.0,
+            guest_ms: 0.5,
+            render_ms: 1.25,
+            commands: 7,
+            gpu: Some(GpuTimings {
+                render_ms: 2.0,
+                blit_ms: 0.5,
+            }),
+            measures: vec![PerfMeasure {
+                name: "layout".into(),
+                duration_ms: 0.25,
+            }],
+            missed_total: 3,
+            one_percent_low_ms: Some(33.4),
+            ..FrameStats::default()
+        };
+        assert_eq!(
+            stats.lines(),
+            vec![
+                "frame 20.0 ms (50 fps)",
+                "1% low 33.4 ms  missed 3",
+                "guest 0.50 ms  render 1.25 ms",
+                "gpu raster 2.00 ms  blit 0.50 ms",
+                "commands 7",
+                "measures:",
+                "  layout 0.25 ms",
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_the_busiest_host_imports() {
+        let call = |name, calls, total_ms| HostCallStats {
+            name,
+            calls,
+            total_ms,
+        };
+        let stats = FrameStats {
+            commands: 3,
+            culled: 2,
+            host_calls: vec![
+                call("draw-text", 2, 0.5),
+                call("fill-rect", 1, 0.25),
+                call("log", 1, 0.15),
+                call("clear", 1, 0.05),
+                call("wasi", 1, 0.05),
+                call("perf-mark", 1, 0.0),
+                call("perf-measure", 1, 0.0),
+                call("request-frame", 1, 0.0),
+            ],
+            text_bytes: 12,
+            ..FrameStats::default()
+        };
+        assert_eq!(
+            stats.lines()[2..],
+            [
+                "commands 3  culled 2",
+                "host calls 9 (1.00 ms)  text 12 B",
+                "  draw-text x2 0.50 ms",
+                "  fill-rect x1 0.25 ms",
+                "  log x1 0.15 ms",
+                "  clear x1 0.05 ms",
+                "  wasi x1 0.05 ms",
+                "  perf-mark x1 0.00 ms",
+                "  2 more imports x2",
+            ]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use wasmtime_wasi::{HostOutputStream, StdoutStream, StreamResult, Subscribe};
+
+use crate::logging::GUEST_TARGET;
+
+/// Longest partial line buffered before it is flushed without a trailing newline.
+const MAX_PENDING_LINE: usize = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GuestStream {
+    Stdout,
+    Stderr,
+}
+
+impl fmt::Display for GuestStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuestStream::Stdout => f.write_str("stdout"),
+            GuestStream::Stderr => f.write_str("stderr"),
+        }
+    }
+}
+
+/// Collects complete lines written to the guest's WASI stdout/stderr so the host can
+/// surface them in tracing and the recent-log buffer instead of the inherited terminal.
+#[derive(Clone, Default)]
+pub struct GuestOutput {
+    lines: Arc<Mutex<Vec<(GuestStream, String)>>>,
+}
+
+impl GuestOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stream(&self, stream: GuestStream) -> CapturedStdio {
+        CapturedStdio {
+            stream,
+            sink: self.clone(),
+            pending: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Drain the lines captured since the last call.
+    pub fn take_lines(&self) -> Vec<(GuestStream, String)> {
+        std::mem::take(&mut *self.lines.lock().unwrap())
+    }
+
+    fn push_line(&self, stream: GuestStream, bytes: &[u8]) {
+        let line = String::from_utf8_lossy(bytes);
+        let line = line.trim_end_matches('\r');
+        match stream {
+            GuestStream::Stdout => tracing::info!(target: GUEST_TARGET, stream = %stream, "{line}"),
+            GuestStream::Stderr => tracing::warn!(target: GUEST_TARGET, stream = %stream, "{line}"),
+        }
+        self.lines.lock().unwrap().push((stream, line.to_string()));
+    }
+}
+
+/// One WASI output stream feeding a [`GuestOutput`].
+#[derive(Clone)]
+pub struct CapturedStdio {
+    stream: GuestStream,
+    sink: GuestOutput,
+    pending: Arc<Mutex<Vec<u8>>>,
+}
+
+impl CapturedStdio {
+    fn append(&self, bytes: &[u8]) {
+        let mut pending = self.pending.lock().unwrap();
+        pending.extend_from_slice(bytes);
+        while let Some(newline) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=newline).collect();
+            self.sink.push_line(self.stream, &line[..line.len() - 1]);
+        }
+        if pending.len() > MAX_PENDING_LINE {
+            let line = std::mem::take(&mut *pending);
+            self.sink.push_line(self.stream, &line);
+        }
+    }
+}
+
+impl StdoutStream for CapturedStdio {
+    fn stream(&self) -> Box<dyn HostOutputStream> {
+        Box::new(self.clone())
+    }
+
+    fn isatty(&self) -> bool {
+        false
+    }
+}
+
+impl HostOutputStream for CapturedStdio {
+    fn write(&mut self, bytes: Bytes) -> StreamResult<()> {
+        self.append(&bytes);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> StreamResult<()> {
+        Ok(())
+    }
+
+    fn check_write(&mut self) -> StreamResult<usize> {
+        Ok(MAX_PENDING_LINE)
+    }
+}
+
+#[async_trait::async_trait]
+impl Subscribe for CapturedStdio {
+    async fn ready(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GuestOutput, GuestStream};
+
+    #[test]
+    fn splits_writes_into_lines() {
+        let output = GuestOutput::new();
+        let stdout = output.stream(GuestStream::Stdout);
+        let stderr = output.stream(GuestStream::Stderr);
+        stdout.append(b"hello ");
+        stdout.append(b"world\r\nsecond");
+        stderr.append(b"oops\n");
+        assert_eq!(
+            output.take_lines(),
+            vec![
+                (GuestStream::Stdout, "hello world".to_string()),
+                (GuestStream::Stderr, "oops".to_string()),
+            ]
+        );
+        stdout.append(b"\n");
+        assert_eq!(
+            output.take_lines(),
+            vec![(GuestStream::Stdout, "second".to_string())]
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Persistent key-value storage behind `storage-get`, `storage-set` and `storage-delete`.
+//!
+//! Every component has a private namespace that only it can reach, and a shared one that
+//! the components its manifest lists in `shares-storage-with` may also read and write.
+//! That lets a suite (an editor and its previewer) exchange data while everything else
+//! stays isolated. Grants are recorded when the owner runs, so a component that has never
+//! run on this machine shares nothing.
+//!
+//! Each namespace is one file, rewritten on every change, so storage suits settings and
+//! small documents rather than bulk data. Writers in this process (the app and its
+//! background tasks) take a per-namespace lock around that rewrite so none is lost.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{bail, Context, Result};
+
+use crate::identity::{self, ComponentIdentity, DataDirs, DataKind};
+
+/// Longest key accepted, in bytes.
+pub const MAX_KEY_BYTES: usize = 1024;
+/// Most a namespace may hold, counting keys and values.
+pub const MAX_NAMESPACE_BYTES: usize = 5 * 1024 * 1024;
+
+const PRIVATE_FILE: &str = "private.kv";
+const SHARED_FILE: &str = "shared.kv";
+/// Component ids the owner's manifest lets into its shared namespace.
+const GRANTS_FILE: &str = "shares.json";
+
+/// Which namespace a storage call uses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Scope {
+    Private,
+    /// The shared namespace of the component with this id; this component's own when
+    /// `None`.
+    Shared(Option<String>),
+}
+
+type Namespace = BTreeMap<String, Vec<u8>>;
+
+/// A running component's view of storage.
+#[derive(Clone, Debug)]
+pub struct Storage {
+    dirs: DataDirs,
+    id: String,
+    dir: PathBuf,
+}
+
+impl Storage {
+    /// Open `identity`'s storage and record which components may use its shared
+    /// namespace, replacing any earlier grants.
+    pub fn open(
+        dirs: &DataDirs,
+        identity: &ComponentIdentity,
+        shares_with: &[String],
+    ) -> Result<Self> {
+        let dir = identity.dir(dirs, DataKind::Storage)?;
+        let grants: Vec<String> = shares_with
+            .iter()
+            .map(|id| identity::sanitize(id))
+            .collect();
+        write_atomic(&dir.join(GRANTS_FILE), &serde_json::to_vec_pretty(&grants)?)?;
+        Ok(Self {
+            dirs: dirs.clone(),
+            id: identity.id.clone(),
+            dir,
+        })
+    }
+
+    /// This component's storage directory, which also holds its database.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn get(&self, scope: &Scope, key: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.namespace(scope)?;
+        Ok(read_namespace(&path)?.remove(key))
+    }
+
+    pub fn set(&self, scope: &Scope, key: &str, value: Vec<u8>) -> Result<()> {
+        if key.len() > MAX_KEY_BYTES {
+            bail!("keys are limited to {MAX_KEY_BYTES} bytes");
+        }
+        let path = self.namespace(scope)?;
+        let lock = namespace_lock(&path);
+        let _guard = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut namespace = read_namespace(&path)?;
+        namespace.insert(key.to_string(), value);
+        let used: usize = namespace
+            .iter()
+            .map(|(key, value)| key.len() + value.len())
+            .sum();
+        if used > MAX_NAMESPACE_BYTES {
+            bail!("storage quota of {MAX_NAMESPACE_BYTES} bytes exceeded");
+        }
+        write_namespace(&path, &namespace)
+    }
+
+    pub fn delete(&self, scope: &Scope, key: &str) -> Result<()> {
+        let path = self.namespace(scope)?;
+        let lock = namespace_lock(&path);
+        let _guard = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut namespace = read_namespace(&path)?;
+        if namespace.remove(key).is_some() {
+            write_namespace(&path, &namespace)?;
+        }
+        Ok(())
+    }
+
+    /// The file behind `scope`, once this component is known to be allowed in.
+    fn namespace(&self, scope: &Scope) -> Result<PathBuf> {
+        let owner = match scope {
+            Scope::Private => return Ok(self.dir.join(PRIVATE_FILE)),
+            Scope::Shared(None) => return Ok(self.dir.join(SHARED_FILE)),
+            Scope::Shared(Some(owner)) => owner,
+        };
+        let owner_id = identity::sanitize(owner);
+        if owner_id == self.id {
+            return Ok(self.dir.join(SHARED_FILE));
+        }
+        let dir = self.dirs.storage_dir(&owner_id);
+        let grants: Vec<String> = fs::read(dir.join(GRANTS_FILE))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        if !grants.contains(&self.id) {
+            bail!(
+                "component '{owner}' does not share its storage with '{}'",
+                self.id
+            );
+        }
+        Ok(dir.join(SHARED_FILE))
+    }
+}
+
+/// The lock serializing read-modify-write of the namespace file at `path`. It is keyed by
+/// path rather than held by `Storage`, since separately opened handles (another app
+/// sharing the namespace, a task) can reach the same file.
+fn namespace_lock(path: &Path) -> Arc<Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
+    let mut locks = LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    locks.entry(path.to_path_buf()).or_default().clone()
+}
+
+fn read_namespace(path: &Path) -> Result<Namespace> {
+    match fs::read(path) {
+        Ok(bytes) => postcard::from_bytes(&bytes)
+            .with_context(|| format!("corrupt storage file {}", path.display())),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Namespace::new()),
+        Err(err) => Err(err).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+fn write_namespace(path: &Path, namespace: &Namespace) -> Result<()> {
+    write_atomic(path, &postcard::to_stdvec(namespace)?)
+}
+
+/// Replace `path` so readers in other host processes never see a partial file. Every
+/// write gets its own temp file, so concurrent writers never clobber each other's.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let unique = NEXT.fetch_add(1, Ordering::Relaxed);
+    let temp = path.with_extension(format!("{}.{unique}.tmp", std::process::id()));
+    fs::write(&temp, bytes)
+        .and_then(|()| fs::rename(&temp, path))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&temp);
+        })
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{Scope, Storage, MAX_NAMESPACE_BYTES};
+    use crate::identity::{ComponentIdentity, DataDirs};
+
+    fn component(id: &str) -> ComponentIdentity {
+        ComponentIdentity::new(format!("embedded:{id}"), id, b"wasm", Some(id.into()))
+    }
+
+    #[test]
+    fn shared_storage_needs_a_grant_from_its_owner() {
+        let root = std::env::temp_dir().join(format!("frontier-storage-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let dirs = DataDirs {
+            data: root.join("data"),
+            cache: root.join("cache"),
+        };
+        let editor = Storage::open(&dirs, &component("editor"), &["previewer".into()]).unwrap();
+        let previewer = Storage::open(&dirs, &component("previewer"), &[]).unwrap();
+        let other = Storage::open(&dirs, &component("other"), &[]).unwrap();
+
+        let own = Scope::Shared(None);
+        let editors = Scope::Shared(Some("editor".into()));
+        editor.set(&own, "doc", b"# Hi".to_vec()).unwrap();
+        editor.set(&Scope::Private, "draft", b"x".to_vec()).unwrap();
+        assert_eq!(
+            previewer.get(&editors, "doc").unwrap(),
+            Some(b"# Hi".to_vec())
+        );
+        assert_eq!(previewer.get(&Scope::Private, "draft").unwrap(), None);
+        assert!(other.get(&editors, "doc").is_err());
+        assert!(editor
+            .get(&Scope::Shared(Some("previewer".into())), "doc")
+            .is_err());
+
+        previewer.set(&editors, "seen", vec![1]).unwrap();
+        assert_eq!(editor.get(&own, "seen").unwrap(), Some(vec![1]));
+        editor.delete(&own, "seen").unwrap();
+        assert_eq!(previewer.get(&editors, "seen").unwrap(), None);
+
+        // Reopening without the grant revokes it.
+        Storage::open(&dirs, &component("editor"), &[]).unwrap();
+        assert!(previewer.get(&editors, "doc").is_err());
+
+        let big = vec![0; MAX_NAMESPACE_BYTES];
+        assert!(other.set(&Scope::Private, "big", big).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn concurrent_writers_keep_every_key() {
+        let root = std::env::temp_dir().join(format!(
+            "frontier-storage-concurrent-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        let dirs = DataDirs {
+            data: root.join("data"),
+            cache: root.join("cache"),
+        };
+        let storage = Storage::open(&dirs, &component("app"), &[]).unwrap();
+        // A task gets a clone; a second handle opened on the same component shares the file.
+        let reopened = Storage::open(&dirs, &component("app"), &[]).unwrap();
+
+        std::thread::scope(|scope| {
+            for writer in 0..8 {
+                let storage = if writer % 2 == 0 {
+                    storage.clone()
+                } else {
+                    reopened.clone()
+                };
+                scope.spawn(move || {
+                    for key in 0..25 {
+                        let key = format!("{writer}-{key}");
+                        storage
+                            .set(&Scope::Private, &key, key.clone().into_bytes())
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        for writer in 0..8 {
+            for key in 0..25 {
+                let key = format!("{writer}-{key}");
+                assert_eq!(
+                    storage.get(&Scope::Private, &key).unwrap(),
+                    Some(key.into_bytes())
+                );
+            }
+        }
+        let leftovers = fs::read_dir(storage.dir())
+            .unwrap()
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_string_lossy().ends_with(".tmp")
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
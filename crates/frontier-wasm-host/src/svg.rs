@@ -0,0 +1,584 @@
+//! A small SVG renderer behind `load-svg`. It covers the static subset icons and simple
+//! illustrations use: paths and basic shapes with solid fills and strokes, nested groups,
+//! transforms and `viewBox`. The document is turned into a Vello scene fragment once, so
+//! drawing it at any size is a transformed append; the shapes are also kept so frames can
+//! be exported as vector files. Gradients, text, raster images,
+//! `<use>`, filters, masks and stylesheets are skipped (with a warning).
+
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use vello::kurbo::{
+    Affine, BezPath, Cap, Circle, Ellipse, Join, Line, Point, Rect, RoundedRect, Shape, Stroke,
+};
+use vello::peniko::color::{parse_color, Srgb};
+use vello::peniko::Fill;
+use vello::Scene;
+
+use crate::vector::{Recording, VectorSink};
+
+/// Largest document `load-svg` accepts.
+pub const MAX_SVG_BYTES: usize = 4 * 1024 * 1024;
+/// Shapes allowed in one document, to bound the work of encoding it every frame.
+const MAX_SVG_SHAPES: usize = 50_000;
+/// Flattening tolerance for circles and ellipses, in document units.
+const SHAPE_TOLERANCE: f64 = 0.01;
+/// Size of a document that has neither `width`/`height` nor a `viewBox`.
+const DEFAULT_SIZE: f64 = 100.0;
+
+/// Elements whose contents are never drawn directly, or that this renderer does not
+/// support; their whole subtree is skipped.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "defs",
+    "clipPath",
+    "mask",
+    "symbol",
+    "pattern",
+    "marker",
+    "linearGradient",
+    "radialGradient",
+    "filter",
+    "text",
+    "image",
+    "use",
+    "foreignObject",
+    "style",
+    "script",
+];
+/// Skipped without a warning: they never draw anything.
+const METADATA_ELEMENTS: &[&str] = &["title", "desc", "metadata"];
+
+/// A parsed SVG document, ready to draw at any scale. Cloning shares the shapes.
+#[derive(Clone)]
+pub struct Picture {
+    inner: Arc<PictureData>,
+}
+
+struct PictureData {
+    /// Drawn in a `width` x `height` box at the origin, clipped to it.
+    scene: Scene,
+    /// The same drawing as `scene`, for sinks other than Vello.
+    recording: Recording,
+    width: f32,
+    height: f32,
+    /// The document as loaded, kept so frames can be saved and replayed.
+    source: Box<[u8]>,
+}
+
+impl Picture {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() > MAX_SVG_BYTES {
+            bail!("SVG is {} bytes; the limit is {MAX_SVG_BYTES}", bytes.len());
+        }
+        let text = std::str::from_utf8(bytes).context("SVG is not valid UTF-8")?;
+        let mut builder = Builder::default();
+        let mut reader = Reader::from_str(text);
+        loop {
+            match reader.read_event().context("malformed SVG")? {
+                Event::Start(element) => builder.open(&element, false)?,
+                Event::Empty(element) => builder.open(&element, true)?,
+                Event::End(_) => builder.close(),
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        builder.finish(bytes)
+    }
+
+    /// Intrinsic size in CSS pixels, from `width`/`height` or else the `viewBox`.
+    pub fn width(&self) -> f32 {
+        self.inner.width
+    }
+
+    pub fn height(&self) -> f32 {
+        self.inner.height
+    }
+
+    /// Bytes of the SVG source, charged against the guest's image budget.
+    pub fn byte_len(&self) -> usize {
+        self.inner.source.len()
+    }
+
+    pub fn source(&self) -> &[u8] {
+        &self.inner.source
+    }
+
+    /// Whether both are clones of one `parse` result.
+    pub fn ptr_eq(&self, other: &Picture) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    pub fn scene(&self) -> &Scene {
+        &self.inner.scene
+    }
+
+    /// Draw the picture into `sink`, mapping its `width` x `height` box through `transform`.
+    pub fn replay(&self, sink: &mut impl VectorSink, transform: Affine) {
+        self.inner.recording.replay(sink, transform);
+    }
+}
+
+impl fmt::Debug for Picture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Picture({}x{})", self.width(), self.height())
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Picture {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let source: &str = u.arbitrary()?;
+        Ok(Self::parse(source.as_bytes())
+            .unwrap_or_else(|_| Self::parse(b"<svg/>").expect("empty SVG parses")))
+    }
+}
+
+/// Presentation attributes in effect for an element, inherited from its ancestors.
+#[derive(Clone, Copy)]
+struct Style {
+    transform: Affine,
+    fill: Option<vello::peniko::Color>,
+    fill_rule: Fill,
+    fill_opacity: f32,
+    stroke: Option<vello::peniko::Color>,
+    stroke_width: f64,
+    stroke_opacity: f32,
+    cap: Cap,
+    join: Join,
+    miter_limit: f64,
+    /// Product of the `opacity` of this element and its ancestors.
+    opacity: f32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            transform: Affine::IDENTITY,
+            fill: Some(vello::peniko::Color::BLACK),
+            fill_rule: Fill::NonZero,
+            fill_opacity: 1.0,
+            stroke: None,
+            stroke_width: 1.0,
+            stroke_opacity: 1.0,
+            cap: Cap::Butt,
+            join: Join::Miter,
+            miter_limit: 4.0,
+            opacity: 1.0,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Builder {
+    recording: Recording,
+    /// Style of every open element; `None` for elements inside a skipped subtree.
+    stack: Vec<Option<Style>>,
+    /// Document size, set by the outermost `<svg>`.
+    size: Option<(f64, f64)>,
+    shapes: usize,
+    /// Unsupported elements and paint servers, reported once when parsing finishes.
+    skipped: Vec<String>,
+}
+
+impl Builder {
+    fn open(&mut self, element: &BytesStart, empty: bool) -> Result<()> {
+        let name = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+        let parent = match self.stack.last() {
+            Some(None) => {
+                // Inside a skipped subtree.
+                if !empty {
+                    self.stack.push(None);
+                }
+                return Ok(());
+            }
+            Some(Some(style)) => Some(*style),
+            None => None,
+        };
+        if parent.is_none() && name != "svg" {
+            bail!("root element is <{name}>, not <svg>");
+        }
+        if parent.is_none() && self.size.is_some() {
+            bail!("more than one root element");
+        }
+        let skip = SKIPPED_ELEMENTS.contains(&name.as_str());
+        if skip && !self.skipped.contains(&name) {
+            self.skipped.push(name.clone());
+        }
+        let style = if skip || METADATA_ELEMENTS.contains(&name.as_str()) {
+            None
+        } else {
+            let attributes = Attributes::read(element)?;
+            let mut style = self.inherit(parent.unwrap_or_default(), &attributes);
+            if parent.is_none() {
+                let viewport = self.open_root(&attributes)?;
+                style.transform = viewport * style.transform;
+            }
+            self.draw(&name, &attributes, &style)?;
+            Some(style)
+        };
+        if !empty {
+            self.stack.push(style);
+        }
+        Ok(())
+    }
+
+    fn close(&mut self) {
+        self.stack.pop();
+    }
+
+    /// Size the document from the outermost `<svg>` and return the transform from its
+    /// `viewBox` into that size, centred and uniformly scaled unless
+    /// `preserveAspectRatio="none"`.
+    fn open_root(&mut self, attributes: &Attributes) -> Result<Affine> {
+        let view_box = attributes
+            .get("viewBox")
+            .map(|value| {
+                let numbers = parse_numbers(value);
+                match numbers[..] {
+                    [x, y, w, h] if w > 0.0 && h > 0.0 => Ok(Rect::new(x, y, x + w, y + h)),
+                    _ => Err(anyhow!("invalid viewBox `{value}`")),
+                }
+            })
+            .transpose()?;
+        let length = |name| attributes.get(name).and_then(parse_length);
+        let width = length("width")
+            .or(view_box.map(|b| b.width()))
+            .unwrap_or(DEFAULT_SIZE);
+        let height = length("height")
+            .or(view_box.map(|b| b.height()))
+            .unwrap_or(DEFAULT_SIZE);
+        if !(width > 0.0 && height > 0.0 && width.is_finite() && height.is_finite()) {
+            bail!("SVG size {width}x{height} is empty");
+        }
+        self.size = Some((width, height));
+        self.recording
+            .push_clip(Affine::IDENTITY, Rect::new(0.0, 0.0, width, height));
+
+        let Some(view_box) = view_box else {
+            return Ok(Affine::IDENTITY);
+        };
+        let (mut scale_x, mut scale_y) = (width / view_box.width(), height / view_box.height());
+        if attributes.get("preserveAspectRatio") != Some("none") {
+            let scale = scale_x.min(scale_y);
+            (scale_x, scale_y) = (scale, scale);
+        }
+        let offset_x = (width - view_box.width() * scale_x) / 2.0;
+        let offset_y = (height - view_box.height() * scale_y) / 2.0;
+        Ok(Affine::translate((offset_x, offset_y))
+            * Affine::scale_non_uniform(scale_x, scale_y)
+            * Affine::translate((-view_box.x0, -view_box.y0)))
+    }
+
+    fn inherit(&mut self, parent: Style, attributes: &Attributes) -> Style {
+        let mut style = parent;
+        if let Some(transform) = attributes.get("transform") {
+            style.transform = parent.transform * parse_transform(transform);
+        }
+        if let Some(fill) = attributes.get("fill") {
+            style.fill = self.parse_paint(fill, parent.fill);
+        }
+        if let Some(stroke) = attributes.get("stroke") {
+            style.stroke = self.parse_paint(stroke, parent.stroke);
+        }
+        if let Some(rule) = attributes.get("fill-rule") {
+            style.fill_rule = if rule == "evenodd" {
+                Fill::EvenOdd
+            } else {
+                Fill::NonZero
+            };
+        }
+        let opacity = |name| attributes.get(name).and_then(parse_opacity);
+        style.fill_opacity = opacity("fill-opacity").unwrap_or(parent.fill_opacity);
+        style.stroke_opacity = opacity("stroke-opacity").unwrap_or(parent.stroke_opacity);
+        // `opacity` is not inherited, but multiplying it into descendants matches it for
+        // content that does not overlap itself.
+        style.opacity = parent.opacity * opacity("opacity").unwrap_or(1.0);
+        if let Some(width) = attributes.get("stroke-width").and_then(parse_length) {
+            style.stroke_width = width.max(0.0);
+        }
+        if let Some(limit) = attributes.get("stroke-miterlimit").and_then(parse_number) {
+            style.miter_limit = limit.max(1.0);
+        }
+        style.cap = match attributes.get("stroke-linecap") {
+            Some("round") => Cap::Round,
+            Some("square") => Cap::Square,
+            Some("butt") => Cap::Butt,
+            _ => parent.cap,
+        };
+        style.join = match attributes.get("stroke-linejoin") {
+            Some("round") => Join::Round,
+            Some("bevel") => Join::Bevel,
+            Some("miter") => Join::Miter,
+            _ => parent.join,
+        };
+        style
+    }
+
+    fn parse_paint(
+        &mut self,
+        value: &str,
+        parent: Option<vello::peniko::Color>,
+    ) -> Option<vello::peniko::Color> {
+        match value.trim() {
+            "none" => None,
+            "inherit" => parent,
+            "currentColor" => Some(vello::peniko::Color::BLACK),
+            paint if paint.starts_with("url(") => {
+                if !self.skipped.iter().any(|s| s == "paint servers") {
+                    self.skipped.push("paint servers".to_string());
+                }
+                None
+            }
+            paint => parse_color(paint)
+                .map(|color| color.to_alpha_color::<Srgb>())
+                .ok()
+                .or(parent),
+        }
+    }
+
+    fn draw(&mut self, name: &str, attributes: &Attributes, style: &Style) -> Result<()> {
+        let number = |name| attributes.get(name).and_then(parse_length).unwrap_or(0.0);
+        let path = match name {
+            "path" => match attributes.get("d").map(BezPath::from_svg) {
+                Some(Ok(path)) => path,
+                Some(Err(err)) => bail!("invalid path data: {err}"),
+                None => return Ok(()),
+            },
+            "rect" => {
+                let (width, height) = (number("width"), number("height"));
+                if width <= 0.0 || height <= 0.0 {
+                    return Ok(());
+                }
+                let rect = Rect::new(number("x"), number("y"), 0.0, 0.0).with_size((width, height));
+                let radius = match (attributes.get("rx"), attributes.get("ry")) {
+                    (None, None) => 0.0,
+                    (Some(_), _) => number("rx"),
+                    (None, Some(_)) => number("ry"),
+                };
+                let radius = radius.clamp(0.0, width.min(height) / 2.0);
+                RoundedRect::from_rect(rect, radius).to_path(SHAPE_TOLERANCE)
+            }
+            "circle" => Circle::new((number("cx"), number("cy")), number("r").max(0.0))
+                .to_path(SHAPE_TOLERANCE),
+            "ellipse" => Ellipse::new(
+                (number("cx"), number("cy")),
+                (number("rx").max(0.0), number("ry").max(0.0)),
+                0.0,
+            )
+            .to_path(SHAPE_TOLERANCE),
+            "line" => Line::new((number("x1"), number("y1")), (number("x2"), number("y2")))
+                .to_path(SHAPE_TOLERANCE),
+            "polyline" | "polygon" => {
+                let numbers = parse_numbers(attributes.get("points").unwrap_or_default());
+                let mut points = numbers.chunks_exact(2).map(|p| Point::new(p[0], p[1]));
+                let Some(first) = points.next() else {
+                    return Ok(());
+                };
+                let mut path = BezPath::new();
+                path.move_to(first);
+                points.for_each(|point| path.line_to(point));
+                if name == "polygon" {
+                    path.close_path();
+                }
+                path
+            }
+            _ => return Ok(()),
+        };
+        self.shapes += 1;
+        if self.shapes > MAX_SVG_SHAPES {
+            bail!("SVG has more than {MAX_SVG_SHAPES} shapes");
+        }
+        // Lines have no interior.
+        if let (Some(fill), false) = (style.fill, name == "line") {
+            let fill = fill.multiply_alpha(style.fill_opacity * style.opacity);
+            self.recording
+                .fill(style.fill_rule, style.transform, fill, &path);
+        }
+        if let (Some(stroke), true) = (style.stroke, style.stroke_width > 0.0) {
+            let stroke = stroke.multiply_alpha(style.stroke_opacity * style.opacity);
+            let outline = Stroke::new(style.stroke_width)
+                .with_caps(style.cap)
+                .with_join(style.join)
+                .with_miter_limit(style.miter_limit);
+            self.recording
+                .stroke(&outline, style.transform, stroke, &path);
+        }
+        Ok(())
+    }
+
+    fn finish(mut self, source: &[u8]) -> Result<Picture> {
+        let Some((width, height)) = self.size else {
+            bail!("no <svg> element");
+        };
+        self.recording.pop_clip();
+        if !self.skipped.is_empty() {
+            tracing::warn!(
+                skipped = %self.skipped.join(", "),
+                "SVG uses features the host does not render; they were left out"
+            );
+        }
+        let mut scene = Scene::new();
+        self.recording.replay(&mut scene, Affine::IDENTITY);
+        Ok(Picture {
+            inner: Arc::new(PictureData {
+                scene,
+                recording: self.recording,
+                width: width as f32,
+                height: height as f32,
+                source: source.into(),
+            }),
+        })
+    }
+}
+
+/// An element's attributes, with declarations from its `style` attribute taking
+/// precedence as CSS does.
+struct Attributes {
+    pairs: Vec<(String, String)>,
+}
+
+impl Attributes {
+    fn read(element: &BytesStart) -> Result<Self> {
+        let mut pairs = Vec::new();
+        let mut style = None;
+        for attribute in element.attributes() {
+            let attribute = attribute.context("malformed SVG attribute")?;
+            let key = String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned();
+            let value = attribute
+                .unescape_value()
+                .context("malformed SVG attribute")?
+                .into_owned();
+            if key == "style" {
+                style = Some(value);
+            } else {
+                pairs.push((key, value));
+            }
+        }
+        for declaration in style.iter().flat_map(|style| style.split(';')) {
+            if let Some((key, value)) = declaration.split_once(':') {
+                pairs.push((key.trim().to_string(), value.trim().to_string()));
+            }
+        }
+        Ok(Self { pairs })
+    }
+
+    /// The last value given for `name`.
+    fn get(&self, name: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value.trim().parse().ok().filter(|n: &f64| n.is_finite())
+}
+
+/// A length in user units; `px` is accepted, other units are not.
+fn parse_length(value: &str) -> Option<f64> {
+    let value = value.trim();
+    parse_number(value.strip_suffix("px").unwrap_or(value))
+}
+
+/// `0.5` or `50%`, clamped to 0.0-1.0.
+fn parse_opacity(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let opacity = match value.strip_suffix('%') {
+        Some(percent) => parse_number(percent)? / 100.0,
+        None => parse_number(value)?,
+    };
+    Some(opacity.clamp(0.0, 1.0) as f32)
+}
+
+/// Numbers separated by commas and/or whitespace; stops at the first thing that is not a
+/// number.
+fn parse_numbers(value: &str) -> Vec<f64> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map_while(parse_number)
+        .collect()
+}
+
+/// A `transform` list such as `translate(10 20) rotate(45)`. Unknown or malformed
+/// functions are ignored.
+fn parse_transform(value: &str) -> Affine {
+    let mut transform = Affine::IDENTITY;
+    for function in value.split_inclusive(')') {
+        let Some((name, args)) = function.split_once('(') else {
+            continue;
+        };
+        let args = parse_numbers(args.trim_end_matches(')'));
+        let step = match (name.trim().trim_start_matches(',').trim(), &args[..]) {
+            ("matrix", &[a, b, c, d, e, f]) => Affine::new([a, b, c, d, e, f]),
+            ("translate", &[x]) => Affine::translate((x, 0.0)),
+            ("translate", &[x, y]) => Affine::translate((x, y)),
+            ("scale", &[s]) => Affine::scale(s),
+            ("scale", &[x, y]) => Affine::scale_non_uniform(x, y),
+            ("rotate", &[angle]) => Affine::rotate(angle.to_radians()),
+            ("rotate", &[angle, x, y]) => {
+                Affine::rotate_about(angle.to_radians(), Point::new(x, y))
+            }
+            ("skewX", &[angle]) => Affine::skew(angle.to_radians().tan(), 0.0),
+            ("skewY", &[angle]) => Affine::skew(0.0, angle.to_radians().tan()),
+            _ => continue,
+        };
+        transform *= step;
+    }
+    transform
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_transform, Picture};
+    use vello::kurbo::{Affine, Point};
+
+    #[test]
+    fn sizes_from_attributes_or_view_box() {
+        let icon = Picture::parse(
+            br##"<svg xmlns="http://www.w3.org/2000/svg" width="48px" height="24" viewBox="0 0 24 24">
+                <title>icon</title>
+                <g fill="#0af" style="stroke: red; stroke-width: 2">
+                    <path d="M2 2 L22 22 Z"/>
+                    <rect x="4" y="4" width="8" height="8" rx="2"/>
+                    <circle cx="12" cy="12" r="4" fill="none"/>
+                </g>
+                <linearGradient id="g"><stop offset="0"/></linearGradient>
+                <polygon points="1,1 5,1 5,5" fill="url(#g)"/>
+            </svg>"##,
+        )
+        .unwrap();
+        assert_eq!((icon.width(), icon.height()), (48.0, 24.0));
+
+        let bare = Picture::parse(br#"<svg viewBox="-5 -5 10 20"></svg>"#).unwrap();
+        assert_eq!((bare.width(), bare.height()), (10.0, 20.0));
+        let default = Picture::parse(b"<svg/>").unwrap();
+        assert_eq!((default.width(), default.height()), (100.0, 100.0));
+    }
+
+    #[test]
+    fn rejects_documents_it_cannot_draw() {
+        assert!(Picture::parse(b"not xml <").is_err());
+        assert!(Picture::parse(b"<html></html>").is_err());
+        assert!(Picture::parse(b"<svg/><svg/>").is_err());
+        assert!(Picture::parse(b"<svg width=\"0\"/>").is_err());
+        assert!(Picture::parse(br#"<svg><path d="M 0 0 Q"/></svg>"#).is_err());
+        assert!(Picture::parse(&[0xff, 0xfe]).is_err());
+    }
+
+    #[test]
+    fn parses_transform_lists() {
+        let transform = parse_transform("translate(10, 20) scale(2) rotate(90 1 1)");
+        let point = transform * Point::new(2.0, 1.0);
+        assert!((point - Point::new(12.0, 24.0)).hypot() < 1e-9);
+        assert_eq!(
+            parse_transform("bogus(1) translate(1)"),
+            Affine::translate((1.0, 0.0))
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Background tasks behind `spawn-task`, for work (indexing files, processing images) that
+//! would otherwise block `frame` and trip the call timeout.
+//!
+//! Each task runs in a second instance of the component on a thread of the host's worker
+//! pool, which enters it through the `run-task` export. The instance shares nothing with the
+//! one drawing the window except storage, and the call timeout does not apply to it.
+//! Progress it reports and its result reach the main instance as `task-progress` and
+//! `task-finished` between frames.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+
+/// Tasks a component may have spawned and not yet seen finish.
+pub const MAX_TASKS_IN_FLIGHT: usize = 8;
+/// How often the app delivers task and worker events while any are running.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A `spawn-task` call waiting for the runtime to start it.
+#[derive(Debug)]
+pub struct TaskRequest {
+    pub id: u32,
+    pub name: String,
+    pub input: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TaskEvent {
+    /// Fraction done, from 0 to 1.
+    Progress(u32, f32),
+    Finished(u32, Result<Vec<u8>, String>),
+}
+
+/// The main instance's tasks: requests not yet started and how many are running.
+#[derive(Debug, Default)]
+pub struct TaskQueue {
+    next_id: u32,
+    pending: Vec<TaskRequest>,
+    running: usize,
+}
+
+impl TaskQueue {
+    /// Queue a task, returning its id.
+    pub fn spawn(&mut self, name: String, input: Vec<u8>) -> Result<u32> {
+        if self.in_flight() >= MAX_TASKS_IN_FLIGHT {
+            bail!("too many tasks in flight (limit {MAX_TASKS_IN_FLIGHT})");
+        }
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.pending.push(TaskRequest { id, name, input });
+        Ok(id)
+    }
+
+    /// Requests to start; they count as running from here until [`finished`](Self::finished).
+    pub fn take_pending(&mut self) -> Vec<TaskRequest> {
+        self.running += self.pending.len();
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn finished(&mut self) {
+        self.running = self.running.saturating_sub(1);
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.pending.len() + self.running
+    }
+}
+
+/// Where task threads send their events. Dropping it cancels the tasks reporting to it,
+/// which is how a reload abandons the old instance's work.
+pub struct TaskChannel {
+    sender: Sender<TaskEvent>,
+    receiver: Receiver<TaskEvent>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Default for TaskChannel {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            cancelled: Arc::default(),
+        }
+    }
+}
+
+impl TaskChannel {
+    pub fn reporter(&self, id: u32) -> TaskReporter {
+        TaskReporter {
+            id,
+            events: self.sender.clone(),
+            cancelled: self.cancelled.clone(),
+        }
+    }
+
+    /// Events sent since the last call, in order, keeping only each task's latest progress
+    /// and none for tasks that have finished.
+    pub fn drain(&self) -> Vec<TaskEvent> {
+        let mut events: Vec<TaskEvent> = Vec::new();
+        for event in self.receiver.try_iter() {
+            match event {
+                TaskEvent::Progress(id, progress) => {
+                    let earlier = events.iter_mut().find(
+                        |event| matches!(event, TaskEvent::Progress(other, _) if *other == id),
+                    );
+                    match earlier {
+                        Some(earlier) => *earlier = TaskEvent::Progress(id, progress),
+                        None => events.push(event),
+                    }
+                }
+                TaskEvent::Finished(id, _) => {
+                    events.retain(
+                        |event| !matches!(event, TaskEvent::Progress(other, _) if *other == id),
+                    );
+                    events.push(event);
+                }
+            }
+        }
+        events
+    }
+}
+
+impl Drop for TaskChannel {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// A running task's line back to the main instance.
+#[derive(Clone, Debug)]
+pub struct TaskReporter {
+    id: u32,
+    events: Sender<TaskEvent>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TaskReporter {
+    /// Report `progress`, clamped to 0..=1. Fails once the task has been cancelled, so
+    /// the task instance traps and its thread ends.
+    pub fn progress(&self, progress: f32) -> Result<()> {
+        if self.cancelled() {
+            bail!("task {} was cancelled", self.id);
+        }
+        if progress.is_finite() {
+            let _ = self
+                .events
+                .send(TaskEvent::Progress(self.id, progress.clamp(0.0, 1.0)));
+        }
+        Ok(())
+    }
+
+    pub fn finish(self, result: Result<Vec<u8>, String>) {
+        let _ = self.events.send(TaskEvent::Finished(self.id, result));
+    }
+
+    pub fn cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TaskChannel, TaskEvent, TaskQueue, MAX_TASKS_IN_FLIGHT};
+
+    #[test]
+    fn queue_limits_tasks_in_flight() {
+        let mut queue = TaskQueue::default();
+        for id in 0..MAX_TASKS_IN_FLIGHT as u32 {
+            assert_eq!(queue.spawn("index".into(), Vec::new()).unwrap(), id);
+        }
+        assert!(queue.spawn("index".into(), Vec::new()).is_err());
+        assert_eq!(queue.take_pending().len(), MAX_TASKS_IN_FLIGHT);
+        assert!(queue.spawn("index".into(), Vec::new()).is_err());
+        queue.finished();
+        assert!(queue.spawn("index".into(), Vec::new()).is_ok());
+    }
+
+    #[test]
+    fn drain_keeps_the_latest_progress_until_a_task_finishes() {
+        let channel = TaskChannel::default();
+        let (first, second) = (channel.reporter(0), channel.reporter(1));
+        first.progress(0.25).unwrap();
+        second.progress(0.5).unwrap();
+        first.progress(2.0).unwrap();
+        second.progress(f32::NAN).unwrap();
+        second.clone().finish(Ok(vec![1]));
+        assert_eq!(
+            channel.drain(),
+            [
+                TaskEvent::Progress(0, 1.0),
+                TaskEvent::Finished(1, Ok(vec![1]))
+            ]
+        );
+        assert!(channel.drain().is_empty());
+
+        drop(channel);
+        assert!(first.cancelled());
+        assert!(first.progress(0.5).is_err());
+    }
+}
@@ -0,0 +1,539 @@
+//! Text geometry for guests: which character a point falls on and where the caret sits,
+//! measured with the same fonts and advances the renderer lays `draw-text` out with, so an
+//! editor's cursor lands where the glyphs were actually drawn.
+//!
+//! Positions are logical pixels relative to the baseline origin passed to `draw-text`, and
+//! indices count characters (Unicode scalar values), not bytes.
+//!
+//! `draw-rich-text` paragraphs are laid out here too, into runs the host records as plain
+//! text draws, so every renderer draws them without knowing about spans. Each run is text
+//! of one direction, so the renderer's own bidi pass leaves it in the order chosen here.
+//! `draw-text-grid` is split into runs the same way, one fixed cell per character.
+
+use std::fmt;
+
+use crate::bidi::{self, TextDirection};
+use crate::geometry::{Rect, Vec2};
+use crate::graphics::{self, FontAssets, FontFace, LINE_HEIGHT};
+use crate::host::FontFamily;
+
+/// Ascent assumed when no font loaded and text is drawn as placeholder boxes, as a fraction
+/// of the font size.
+const PLACEHOLDER_ASCENT: f32 = 0.9;
+
+/// The bundled fonts, loaded on the first query so instances that never ask pay nothing.
+#[derive(Default)]
+pub struct TextMetrics {
+    fonts: Option<FontAssets>,
+}
+
+impl fmt::Debug for TextMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextMetrics")
+            .field("loaded", &self.fonts.is_some())
+            .finish()
+    }
+}
+
+/// Part of a `draw-rich-text` paragraph drawn in one style.
+#[derive(Clone, Copy, Debug)]
+pub struct Span<'a> {
+    pub text: &'a str,
+    pub size: f32,
+    pub font: FontFamily,
+}
+
+/// Text from one span that lands on one line, placed relative to the paragraph's origin.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Run {
+    /// Index of the span the text came from.
+    pub span: usize,
+    pub text: String,
+    /// Baseline origin of the run: its left end when left-to-right, its right end when
+    /// right-to-left.
+    pub offset: Vec2,
+    pub direction: TextDirection,
+}
+
+/// One laid-out character of a paragraph.
+#[derive(Clone, Copy, Debug)]
+struct Atom {
+    span: usize,
+    ch: char,
+    advance: f32,
+}
+
+/// Text from one `draw-text-grid` cell that lands on one row, with tabs expanded, so each
+/// character covers one column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridRun {
+    /// Index of the cell the text came from.
+    pub cell: usize,
+    pub row: u32,
+    pub column: u32,
+    pub text: String,
+}
+
+/// Widest tab stop `layout_grid` honours, in columns.
+const MAX_TAB_WIDTH: u32 = 64;
+
+/// A place the caret can stop: before a character, or after the last one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CaretStop {
+    line: u32,
+    x: f32,
+}
+
+impl TextMetrics {
+    /// Index of the caret position nearest `point` in `text` drawn in `sans` at `size`.
+    /// Points above the first line or below the last resolve on those lines.
+    pub fn hit_test(
+        &mut self,
+        text: &str,
+        size: f32,
+        point: Vec2,
+        direction: TextDirection,
+    ) -> u32 {
+        let face = self.face(FontFamily::Sans);
+        let stops = caret_stops(face, text, size, direction);
+        let line_height = size * LINE_HEIGHT;
+        let last_line = stops.last().map_or(0, |stop| stop.line);
+        let line = if line_height > 0.0 && point.y.is_finite() {
+            let top = -ascent(face, size);
+            ((point.y - top) / line_height)
+                .floor()
+                .clamp(0.0, last_line as f32) as u32
+        } else {
+            0
+        };
+        stops
+            .iter()
+            .enumerate()
+            .filter(|(_, stop)| stop.line == line)
+            .min_by(|(_, a), (_, b)| (a.x - point.x).abs().total_cmp(&(b.x - point.x).abs()))
+            .map_or(0, |(index, _)| index as u32)
+    }
+
+    /// Zero-width rectangle spanning the line at caret position `index` in `text` drawn in
+    /// `sans` at `size`; indices past the end give the caret after the last character.
+    pub fn caret_rect(
+        &mut self,
+        text: &str,
+        size: f32,
+        index: u32,
+        direction: TextDirection,
+    ) -> Rect {
+        let face = self.face(FontFamily::Sans);
+        let stops = caret_stops(face, text, size, direction);
+        let stop = stops[(index as usize).min(stops.len() - 1)];
+        let line_height = size * LINE_HEIGHT;
+        Rect::new(
+            stop.x,
+            stop.line as f32 * line_height - ascent(face, size),
+            0.0,
+            line_height,
+        )
+    }
+
+    /// Lay `spans` out as one paragraph wrapped at `max_width`: at spaces where possible,
+    /// mid-word for words wider than a line, and always at `\n`. Spaces where a line
+    /// wraps are dropped; those after a `\n` are kept, so indented code stays indented.
+    /// The first baseline is at the origin and each line sits below the previous one by
+    /// the line height of its largest text. Lines are put in visual order for `direction`;
+    /// right-to-left lines end at `max_width`, or at the origin when it is unbounded.
+    pub fn layout_paragraph(
+        &mut self,
+        spans: &[Span],
+        max_width: f32,
+        direction: TextDirection,
+    ) -> Vec<Run> {
+        let fonts = self.fonts.get_or_insert_with(FontAssets::new);
+        let mut wrapper = Wrapper::new(max_width);
+        // The size of the text a line ends in, so blank lines still take up room.
+        let mut end_size = spans.first().map_or(0.0, |span| span.size);
+        for (index, span) in spans.iter().enumerate() {
+            let advance = graphics::advance(fonts.face(span.font), span.size);
+            for ch in span.text.chars() {
+                let atom = Atom {
+                    span: index,
+                    ch,
+                    advance: advance(ch),
+                };
+                match ch {
+                    '\n' => wrapper.end_line(span.size),
+                    ' ' => wrapper.push_space(atom),
+                    _ => wrapper.word.push(atom),
+                }
+            }
+            end_size = span.size;
+        }
+        wrapper.end_line(end_size);
+
+        let mut runs: Vec<Run> = Vec::new();
+        let mut baseline = 0.0;
+        for (number, (atoms, end_size)) in wrapper.lines.into_iter().enumerate() {
+            let size = atoms
+                .iter()
+                .map(|atom| spans[atom.span].size)
+                .reduce(f32::max)
+                .unwrap_or(end_size);
+            if number > 0 {
+                baseline += size * LINE_HEIGHT;
+            }
+            let chars: Vec<char> = atoms.iter().map(|atom| atom.ch).collect();
+            let width: f32 = atoms.iter().map(|atom| atom.advance).sum();
+            let mut x = match direction {
+                TextDirection::Ltr => 0.0,
+                TextDirection::Rtl if max_width.is_finite() => max_width - width,
+                TextDirection::Rtl => -width,
+            };
+            let mut previous = None;
+            for visual in bidi::visual_order(&chars, direction) {
+                let atom = atoms[visual.index];
+                let run_direction = match visual.rtl {
+                    false => TextDirection::Ltr,
+                    true => TextDirection::Rtl,
+                };
+                x += atom.advance;
+                match runs.last_mut() {
+                    // Right-to-left runs are stored in logical order, anchored at their
+                    // right end, for the renderer to reverse again.
+                    Some(run) if previous == Some((atom.span, run_direction)) => {
+                        match run_direction {
+                            TextDirection::Ltr => run.text.push(atom.ch),
+                            TextDirection::Rtl => {
+                                run.text.insert(0, atom.ch);
+                                run.offset.x = x;
+                            }
+                        }
+                    }
+                    _ => runs.push(Run {
+                        span: atom.span,
+                        text: atom.ch.to_string(),
+                        offset: match run_direction {
+                            TextDirection::Ltr => Vec2::new(x - atom.advance, baseline),
+                            TextDirection::Rtl => Vec2::new(x, baseline),
+                        },
+                        direction: run_direction,
+                    }),
+                }
+                previous = Some((atom.span, run_direction));
+            }
+        }
+        runs.retain(|run| !run.text.trim().is_empty());
+        runs
+    }
+
+    /// Size of one `draw-text-grid` cell at `size`: the `monospace` advance by the line
+    /// height. Also returns how far the baseline sits below the cell's top.
+    pub fn grid_cell(&mut self, size: f32) -> (Vec2, f32) {
+        let face = self.face(FontFamily::Monospace);
+        let width = graphics::advance(face, size)('M');
+        (Vec2::new(width, size * LINE_HEIGHT), ascent(face, size))
+    }
+
+    fn face(&mut self, font: FontFamily) -> Option<&FontFace> {
+        self.fonts.get_or_insert_with(FontAssets::new).face(font)
+    }
+}
+
+/// Split the text of `draw-text-grid` cells into runs by row, breaking rows at `\n` and
+/// expanding each `\t` to spaces up to the next multiple of `tab_width` columns. Every
+/// other character takes one column. Right-to-left characters get runs of their own, so
+/// the renderer's bidi pass cannot move them out of their columns: terminals leave
+/// reordering to the program.
+pub fn layout_grid(cells: &[&str], tab_width: u32) -> Vec<GridRun> {
+    let tab_width = tab_width.clamp(1, MAX_TAB_WIDTH);
+    let mut runs: Vec<GridRun> = Vec::new();
+    let (mut row, mut column) = (0, 0);
+    for (index, text) in cells.iter().enumerate() {
+        // Whether the next character may join the last run.
+        let mut open = false;
+        for ch in text.chars() {
+            if ch == '\n' {
+                row += 1;
+                column = 0;
+                open = false;
+                continue;
+            }
+            let isolated = bidi::is_reordered(ch);
+            if !open || isolated {
+                runs.push(GridRun {
+                    cell: index,
+                    row,
+                    column,
+                    text: String::new(),
+                });
+            }
+            let Some(run) = runs.last_mut() else {
+                continue;
+            };
+            if ch == '\t' {
+                let width = tab_width - column % tab_width;
+                run.text.extend(std::iter::repeat_n(' ', width as usize));
+                column += width;
+            } else {
+                run.text.push(ch);
+                column += 1;
+            }
+            open = !isolated;
+        }
+    }
+    runs
+}
+
+fn ascent(face: Option<&FontFace>, size: f32) -> f32 {
+    face.map_or(size * PLACEHOLDER_ASCENT, |face| face.ascent(size))
+}
+
+/// Greedy line breaking for `layout_paragraph`.
+struct Wrapper {
+    max_width: f32,
+    /// Finished lines, each with the size of the text it ended in.
+    lines: Vec<(Vec<Atom>, f32)>,
+    line: Vec<Atom>,
+    width: f32,
+    /// Spaces since the last word, placed only if the next word fits after them.
+    spaces: Vec<Atom>,
+    word: Vec<Atom>,
+}
+
+impl Wrapper {
+    fn new(max_width: f32) -> Self {
+        Self {
+            max_width,
+            lines: Vec::new(),
+            line: Vec::new(),
+            width: 0.0,
+            spaces: Vec::new(),
+            word: Vec::new(),
+        }
+    }
+
+    fn push_space(&mut self, atom: Atom) {
+        self.place_word();
+        self.spaces.push(atom);
+    }
+
+    fn end_line(&mut self, size: f32) {
+        self.place_word();
+        self.line.append(&mut self.spaces);
+        self.lines.push((std::mem::take(&mut self.line), size));
+        self.width = 0.0;
+    }
+
+    /// Wrap before the pending word if it does not fit after the pending spaces, and
+    /// within it if it does not fit on a line of its own.
+    fn place_word(&mut self) {
+        if self.word.is_empty() {
+            return;
+        }
+        let space_width: f32 = self.spaces.iter().map(|atom| atom.advance).sum();
+        let word_width: f32 = self.word.iter().map(|atom| atom.advance).sum();
+        if !self.line.is_empty() && self.width + space_width + word_width > self.max_width {
+            self.wrap();
+            self.spaces.clear();
+        } else {
+            self.width += space_width;
+            self.line.append(&mut self.spaces);
+        }
+        for atom in std::mem::take(&mut self.word) {
+            if !self.line.is_empty() && self.width + atom.advance > self.max_width {
+                self.wrap();
+            }
+            self.width += atom.advance;
+            self.line.push(atom);
+        }
+    }
+
+    fn wrap(&mut self) {
+        self.lines.push((std::mem::take(&mut self.line), 0.0));
+        self.width = 0.0;
+    }
+}
+
+/// The caret stop before each character of `text` and after the last, where the renderer
+/// places them: at the leading edge of each character, which is its right edge in
+/// right-to-left runs, and at the end of the line for line breaks and the end of the text.
+fn caret_stops(
+    face: Option<&FontFace>,
+    text: &str,
+    size: f32,
+    direction: TextDirection,
+) -> Vec<CaretStop> {
+    let placement = graphics::place_text(text, direction, graphics::advance(face, size));
+    let line_end = |line: u32| CaretStop {
+        line,
+        x: placement.line_ends[line as usize],
+    };
+    let mut stops = Vec::with_capacity(text.len() + 1);
+    let mut line = 0;
+    for ch in text.chars() {
+        stops.push(line_end(line));
+        if ch == '\n' {
+            line += 1;
+        }
+    }
+    stops.push(line_end(line));
+    for placed in placement.chars {
+        stops[placed.index] = CaretStop {
+            line: placed.line,
+            x: if placed.rtl {
+                placed.x + placed.advance
+            } else {
+                placed.x
+            },
+        };
+    }
+    stops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{layout_grid, GridRun, Span, TextMetrics};
+    use crate::bidi::TextDirection::{Ltr, Rtl};
+    use crate::geometry::Vec2;
+    use crate::graphics::SceneEncoder;
+    use crate::host::FontFamily;
+
+    #[test]
+    fn hit_testing_and_carets_agree_with_the_renderer() {
+        let mut metrics = TextMetrics::default();
+        let encoder = SceneEncoder::new(1.0);
+        let ab = encoder.text_width("ab", 20.0);
+        let abc = encoder.text_width("abc", 20.0);
+
+        let caret = metrics.caret_rect("abc\nde", 20.0, 2, Ltr);
+        assert_eq!(caret.x, ab);
+        assert_eq!(caret.width, 0.0);
+        assert!(caret.y < 0.0 && caret.y + caret.height > 0.0);
+        // Just short of the middle of `c` is still before it; past the end is after it.
+        let middle = (ab + abc) / 2.0;
+        assert_eq!(
+            metrics.hit_test("abc\nde", 20.0, Vec2::new(middle - 0.5, 0.0), Ltr),
+            2
+        );
+        assert_eq!(
+            metrics.hit_test("abc\nde", 20.0, Vec2::new(middle + 0.5, 0.0), Ltr),
+            3
+        );
+        assert_eq!(
+            metrics.hit_test("abc\nde", 20.0, Vec2::new(500.0, -100.0), Ltr),
+            3
+        );
+
+        // The second line starts after the newline and catches everything below it.
+        assert_eq!(metrics.caret_rect("abc\nde", 20.0, 4, Ltr).x, 0.0);
+        assert_eq!(
+            metrics.hit_test("abc\nde", 20.0, Vec2::new(-5.0, 24.0), Ltr),
+            4
+        );
+        assert_eq!(
+            metrics.hit_test("abc\nde", 20.0, Vec2::new(500.0, 500.0), Ltr),
+            6
+        );
+        assert_eq!(
+            metrics.caret_rect("abc\nde", 20.0, 99, Ltr),
+            metrics.caret_rect("abc\nde", 20.0, 6, Ltr)
+        );
+
+        assert_eq!(metrics.hit_test("", 20.0, Vec2::new(10.0, 0.0), Ltr), 0);
+        assert_eq!(
+            metrics.hit_test("abc", f32::NAN, Vec2::new(10.0, 0.0), Ltr),
+            0
+        );
+    }
+
+    #[test]
+    fn paragraphs_wrap_across_spans() {
+        let mut metrics = TextMetrics::default();
+        let encoder = SceneEncoder::new(1.0);
+        let span = |text, size| Span {
+            text,
+            size,
+            font: FontFamily::Sans,
+        };
+        let spans = [
+            span("let ", 10.0),
+            span("answer", 20.0),
+            span(" = 42;\n  done", 10.0),
+        ];
+        let max_width = encoder.text_width("let ", 10.0) + encoder.text_width("answer", 20.0);
+        let runs = metrics.layout_paragraph(&spans, max_width, Ltr);
+        let texts: Vec<_> = runs
+            .iter()
+            .map(|run| (run.span, run.text.as_str()))
+            .collect();
+        assert_eq!(
+            texts,
+            [(0, "let "), (1, "answer"), (2, "= 42;"), (2, "  done")]
+        );
+
+        // Each line sits below the last by the line height of its own largest text.
+        assert_eq!(runs[0].offset, Vec2::ZERO);
+        assert_eq!(
+            runs[1].offset,
+            Vec2::new(encoder.text_width("let ", 10.0), 0.0)
+        );
+        assert_eq!(runs[2].offset, Vec2::new(0.0, 12.0));
+        assert_eq!(runs[3].offset, Vec2::new(0.0, 24.0));
+
+        assert!(metrics.layout_paragraph(&[], 100.0, Ltr).is_empty());
+    }
+
+    #[test]
+    fn right_to_left_text_ends_at_the_origin() {
+        let mut metrics = TextMetrics::default();
+        let encoder = SceneEncoder::new(1.0);
+        let width = encoder.text_width("שלום", 20.0);
+        let shin = encoder.text_width("ש", 20.0);
+
+        // The first letter is drawn rightmost, so the caret before it is at the origin.
+        let caret_x = |metrics: &mut TextMetrics, index, direction| {
+            metrics.caret_rect("שלום", 20.0, index, direction).x
+        };
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-3;
+        assert!(close(caret_x(&mut metrics, 0, Rtl), 0.0));
+        assert!(close(caret_x(&mut metrics, 1, Rtl), -shin));
+        assert!(close(caret_x(&mut metrics, 4, Rtl), -width));
+        assert_eq!(
+            metrics.hit_test("שלום", 20.0, Vec2::new(-width - 5.0, 0.0), Rtl),
+            4
+        );
+        // In a left-to-right paragraph the same word starts at the origin, still reversed.
+        assert!(close(caret_x(&mut metrics, 0, Ltr), width));
+
+        let span = Span {
+            text: "שלום 42",
+            size: 20.0,
+            font: FontFamily::Sans,
+        };
+        let runs = metrics.layout_paragraph(&[span], 200.0, Rtl);
+        let texts: Vec<_> = runs
+            .iter()
+            .map(|run| (run.text.as_str(), run.direction))
+            .collect();
+        assert_eq!(texts, [("42", Ltr), ("שלום ", Rtl)]);
+        assert!(close(runs[1].offset.x, 200.0));
+    }
+
+    #[test]
+    fn grid_text_expands_tabs_and_keeps_every_character_in_its_column() {
+        let run = |cell, row, column, text: &str| GridRun {
+            cell,
+            row,
+            column,
+            text: text.to_string(),
+        };
+        assert_eq!(
+            layout_grid(&["ab\tc", "d\nשx", "\t"], 4),
+            [
+                run(0, 0, 0, "ab  c"),
+                run(1, 0, 5, "d"),
+                run(1, 1, 0, "ש"),
+                run(1, 1, 1, "x"),
+                run(2, 1, 2, "  "),
+            ]
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! The host's time zone for `local-timezone` and `utc-offset-at`. Offsets come from the
+//! system time zone database through chrono, which honours `TZ`, so a guest clock agrees
+//! with `format-datetime`.
+
+use chrono::{DateTime, Local, Offset};
+
+/// IANA name of the host time zone, such as `Europe/Berlin`. A `TZ` naming a zone wins
+/// over the system setting, matching how offsets are computed.
+pub fn local_timezone() -> Option<String> {
+    if let Some(name) = std::env::var("TZ").ok().as_deref().and_then(zone_name) {
+        return Some(name.to_string());
+    }
+    match iana_time_zone::get_timezone() {
+        Ok(name) => Some(name),
+        Err(err) => {
+            tracing::debug!(error = %err, "host time zone unknown");
+            None
+        }
+    }
+}
+
+/// Seconds east of UTC in the host time zone at `unix_ms`, daylight saving included.
+/// `None` when the timestamp is outside what chrono can represent.
+pub fn utc_offset_at(unix_ms: i64) -> Option<i32> {
+    let time = DateTime::from_timestamp_millis(unix_ms)?;
+    Some(time.with_timezone(&Local).offset().fix().local_minus_utc())
+}
+
+/// The zone named by a `TZ` value: `Europe/Paris` or `:Europe/Paris`. Absolute paths and
+/// POSIX rules such as `EST5EDT` have no IANA name.
+fn zone_name(tz: &str) -> Option<&str> {
+    let name = tz.strip_prefix(':').unwrap_or(tz);
+    let is_rule = name
+        .bytes()
+        .any(|byte| byte.is_ascii_digit() || byte == b',');
+    let valid = !name.is_empty()
+        && !name.starts_with('/')
+        && !name.contains("..")
+        && name
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"/_-+".contains(&byte));
+    (valid && (!is_rule || name.starts_with("Etc/"))).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{utc_offset_at, zone_name};
+
+    #[test]
+    fn names_only_tz_values_that_are_zones() {
+        assert_eq!(zone_name("Europe/Berlin"), Some("Europe/Berlin"));
+        assert_eq!(zone_name(":America/New_York"), Some("America/New_York"));
+        assert_eq!(zone_name("UTC"), Some("UTC"));
+        assert_eq!(zone_name("Etc/GMT+5"), Some("Etc/GMT+5"));
+        assert_eq!(zone_name("EST5EDT,M3.2.0,M11.1.0"), None);
+        assert_eq!(zone_name("/usr/share/zoneinfo/Europe/Berlin"), None);
+        assert_eq!(zone_name(":"), None);
+        assert_eq!(zone_name("../etc/passwd"), None);
+    }
+
+    #[test]
+    fn offsets_stay_within_a_day_and_reject_out_of_range_times() {
+        for unix_ms in [0, 1_780_000_000_000, -2_208_988_800_000] {
+            let offset = utc_offset_at(unix_ms).unwrap();
+            assert!(offset.abs() < 24 * 3600, "{offset}");
+        }
+        assert_eq!(utc_offset_at(i64::MAX), None);
+    }
+}
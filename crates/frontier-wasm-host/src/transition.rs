@@ -0,0 +1,88 @@
+//! Host-drawn transitions shown when the component is replaced, e.g. on restart: the
+//! outgoing component's last frame is blended over the incoming one for a short time.
+
+use std::time::{Duration, Instant};
+
+use crate::host::FrameOutput;
+
+/// Default length of a transition.
+pub const DEFAULT_DURATION: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TransitionKind {
+    /// Switch straight to the new component.
+    #[default]
+    None,
+    /// Fade the old frame out over the new one.
+    Crossfade,
+    /// Slide the old frame off to the left, uncovering the new one.
+    Slide,
+}
+
+/// A transition in flight.
+#[derive(Debug)]
+pub struct Transition {
+    pub kind: TransitionKind,
+    /// The last frame of the component being replaced. Shader layers in it are drawn
+    /// without their effect.
+    pub outgoing: FrameOutput,
+    started: Instant,
+    duration: Duration,
+}
+
+impl Transition {
+    /// `None` for [`TransitionKind::None`], which has nothing to draw.
+    pub fn start(
+        kind: TransitionKind,
+        outgoing: FrameOutput,
+        duration: Duration,
+        now: Instant,
+    ) -> Option<Self> {
+        (kind != TransitionKind::None && !duration.is_zero()).then_some(Self {
+            kind,
+            outgoing,
+            started: now,
+            duration,
+        })
+    }
+
+    /// Eased progress from 0 (all old frame) to 1, or `None` once finished.
+    pub fn progress(&self, now: Instant) -> Option<f32> {
+        let elapsed = now.saturating_duration_since(self.started);
+        if elapsed >= self.duration {
+            return None;
+        }
+        let t = elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        Some(t * t * (3.0 - 2.0 * t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Transition, TransitionKind};
+    use crate::host::FrameOutput;
+
+    #[test]
+    fn eases_from_start_to_end() {
+        let now = Instant::now();
+        let duration = Duration::from_millis(200);
+        assert!(
+            Transition::start(TransitionKind::None, FrameOutput::default(), duration, now)
+                .is_none()
+        );
+        let transition = Transition::start(
+            TransitionKind::Crossfade,
+            FrameOutput::default(),
+            duration,
+            now,
+        )
+        .unwrap();
+        assert_eq!(transition.progress(now), Some(0.0));
+        assert_eq!(transition.progress(now + duration / 2), Some(0.5));
+        let early = transition.progress(now + duration / 10).unwrap();
+        assert!(early > 0.0 && early < 0.1, "{early}");
+        assert_eq!(transition.progress(now + duration), None);
+    }
+}
@@ -0,0 +1,390 @@
+//! Value animations behind `animate` and `spring`. The host steps every animation by the
+//! `dt` it is about to pass to `frame`, so a guest reads the same value for the whole frame
+//! and motion follows the virtual clock (pausing, slow motion and single steps included)
+//! however late the guest's own callbacks run. Animations are owned by the runtime across
+//! hot reloads.
+
+use crate::component::vello::canvas::host::Easing as WitEasing;
+
+/// Animations a guest may have at once, finished ones included.
+pub const MAX_TWEENS: usize = 256;
+/// A spring this close to its target, and slower than this per second, comes to rest.
+const SPRING_REST: f64 = 1e-3;
+
+/// Timing curves, matching the CSS keywords of the same names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn from_wit(easing: WitEasing) -> Self {
+        match easing {
+            WitEasing::Linear => Easing::Linear,
+            WitEasing::Ease => Easing::Ease,
+            WitEasing::EaseIn => Easing::EaseIn,
+            WitEasing::EaseOut => Easing::EaseOut,
+            WitEasing::EaseInOut => Easing::EaseInOut,
+        }
+    }
+
+    /// Eased progress for linear progress `t`, both from 0 to 1.
+    pub fn apply(self, t: f32) -> f32 {
+        let (x1, y1, x2, y2) = match self {
+            Easing::Linear => return t,
+            Easing::Ease => (0.25, 0.1, 0.25, 1.0),
+            Easing::EaseIn => (0.42, 0.0, 1.0, 1.0),
+            Easing::EaseOut => (0.0, 0.0, 0.58, 1.0),
+            Easing::EaseInOut => (0.42, 0.0, 0.58, 1.0),
+        };
+        // The cubic Bézier from (0, 0) to (1, 1): find where it reaches `t` across, by
+        // bisection since x grows steadily along it, and read off how far up it is there.
+        let along = |a: f32, b: f32, s: f32| {
+            let rest = 1.0 - s;
+            3.0 * a * s * rest * rest + 3.0 * b * s * s * rest + s * s * s
+        };
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..24 {
+            let mid = (low + high) / 2.0;
+            if along(x1, x2, mid) < t {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        along(y1, y2, (low + high) / 2.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Tween {
+    from: f32,
+    to: f32,
+    duration_ms: f32,
+    elapsed_ms: f32,
+    easing: Easing,
+}
+
+impl Tween {
+    fn value(&self) -> f32 {
+        if self.is_finished() {
+            return self.to;
+        }
+        let progress = self.easing.apply(self.elapsed_ms / self.duration_ms);
+        self.from + (self.to - self.from) * progress
+    }
+
+    /// Rate of change per second, measured over the next millisecond.
+    fn velocity(&self) -> f32 {
+        if self.is_finished() {
+            return 0.0;
+        }
+        let later = Tween {
+            elapsed_ms: (self.elapsed_ms + 1.0).min(self.duration_ms),
+            ..*self
+        };
+        (later.value() - self.value()) * 1000.0
+    }
+
+    fn is_finished(&self) -> bool {
+        self.elapsed_ms >= self.duration_ms
+    }
+}
+
+/// A unit mass on a damped spring pulling it towards `target`. Stepped in closed form, so
+/// any `dt` lands exactly where the spring would be and stiff springs cannot blow up.
+#[derive(Clone, Copy, Debug)]
+struct Spring {
+    value: f64,
+    /// Per second.
+    velocity: f64,
+    target: f64,
+    stiffness: f64,
+    damping: f64,
+}
+
+impl Spring {
+    fn is_at_rest(&self) -> bool {
+        self.value == self.target && self.velocity == 0.0
+    }
+
+    fn advance(&mut self, dt_ms: f32) {
+        if self.is_at_rest() {
+            return;
+        }
+        let t = f64::from(dt_ms) / 1000.0;
+        let (x, v) = (self.value - self.target, self.velocity);
+        let natural = self.stiffness.sqrt();
+        let ratio = self.damping / (2.0 * natural);
+        let (x, v) = if (ratio - 1.0).abs() < 1e-6 {
+            // Critically damped: the quickest return that never overshoots from rest.
+            let decay = (-natural * t).exp();
+            let rate = v + natural * x;
+            ((x + rate * t) * decay, (v - rate * natural * t) * decay)
+        } else if ratio < 1.0 {
+            // Underdamped: overshoots and rings down.
+            let ringing = natural * (1.0 - ratio * ratio).sqrt();
+            let decay = (-ratio * natural * t).exp();
+            let (sin, cos) = (ringing * t).sin_cos();
+            let b = (v + ratio * natural * x) / ringing;
+            (
+                decay * (x * cos + b * sin),
+                decay
+                    * ((b * ringing - ratio * natural * x) * cos
+                        - (x * ringing + ratio * natural * b) * sin),
+            )
+        } else {
+            // Overdamped: two exponentials, creeping in without overshoot.
+            let spread = natural * (ratio * ratio - 1.0).sqrt();
+            let (fast, slow) = (-ratio * natural - spread, -ratio * natural + spread);
+            let a = (v - slow * x) / (fast - slow);
+            let b = x - a;
+            let (fast_decay, slow_decay) = ((fast * t).exp(), (slow * t).exp());
+            (
+                a * fast_decay + b * slow_decay,
+                a * fast * fast_decay + b * slow * slow_decay,
+            )
+        };
+        if x.abs() < SPRING_REST && v.abs() < SPRING_REST {
+            self.value = self.target;
+            self.velocity = 0.0;
+        } else {
+            self.value = self.target + x;
+            self.velocity = v;
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Motion {
+    Tween(Tween),
+    Spring(Spring),
+}
+
+impl Motion {
+    fn value(&self) -> f32 {
+        match self {
+            Motion::Tween(tween) => tween.value(),
+            Motion::Spring(spring) => spring.value as f32,
+        }
+    }
+
+    fn velocity(&self) -> f32 {
+        match self {
+            Motion::Tween(tween) => tween.velocity(),
+            Motion::Spring(spring) => spring.velocity as f32,
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        match self {
+            Motion::Tween(tween) => !tween.is_finished(),
+            Motion::Spring(spring) => !spring.is_at_rest(),
+        }
+    }
+}
+
+/// A guest's tweens and springs by id. Finished ones hold their end value until stopped or
+/// replaced.
+#[derive(Debug, Default)]
+pub struct Tweens {
+    tweens: Vec<(u32, Motion)>,
+}
+
+impl Tweens {
+    /// Tween `id` from `from` to `to` over `duration_ms`, replacing any tween with that id.
+    /// False when a value is not finite, the duration is negative, or `MAX_TWEENS` exist.
+    pub fn start(&mut self, id: u32, from: f32, to: f32, duration_ms: f32, easing: Easing) -> bool {
+        let valid =
+            from.is_finite() && to.is_finite() && duration_ms.is_finite() && duration_ms >= 0.0;
+        if !valid {
+            return false;
+        }
+        self.set(
+            id,
+            Motion::Tween(Tween {
+                from,
+                to,
+                duration_ms,
+                elapsed_ms: 0.0,
+                easing,
+            }),
+        )
+    }
+
+    /// Spring `id` towards `target`, from the value and speed its current animation has
+    /// reached, or from rest at `target` for a new id. False when a value is not finite,
+    /// `stiffness` or `damping` is not positive, or `MAX_TWEENS` exist.
+    pub fn spring(&mut self, id: u32, target: f32, stiffness: f32, damping: f32) -> bool {
+        let valid = target.is_finite()
+            && stiffness.is_finite()
+            && stiffness > 0.0
+            && damping.is_finite()
+            && damping > 0.0;
+        if !valid {
+            return false;
+        }
+        let (value, velocity) = self
+            .get(id)
+            .map_or((target, 0.0), |motion| (motion.value(), motion.velocity()));
+        self.set(
+            id,
+            Motion::Spring(Spring {
+                value: value.into(),
+                velocity: velocity.into(),
+                target: target.into(),
+                stiffness: stiffness.into(),
+                damping: damping.into(),
+            }),
+        )
+    }
+
+    /// Animation `id`'s current value.
+    pub fn value(&self, id: u32) -> Option<f32> {
+        self.get(id).map(Motion::value)
+    }
+
+    /// Forget animation `id`, returning the value it reached.
+    pub fn stop(&mut self, id: u32) -> Option<f32> {
+        let index = self.tweens.iter().position(|(other, _)| *other == id)?;
+        Some(self.tweens.remove(index).1.value())
+    }
+
+    /// Some tween has not reached its end value yet, or some spring is not at rest.
+    pub fn is_running(&self) -> bool {
+        self.tweens.iter().any(|(_, motion)| motion.is_running())
+    }
+
+    /// Step every animation by `dt_ms`.
+    pub fn advance(&mut self, dt_ms: f32) {
+        if !(dt_ms.is_finite() && dt_ms > 0.0) {
+            return;
+        }
+        for (_, motion) in &mut self.tweens {
+            match motion {
+                Motion::Tween(tween) => {
+                    tween.elapsed_ms = (tween.elapsed_ms + dt_ms).min(tween.duration_ms);
+                }
+                Motion::Spring(spring) => spring.advance(dt_ms),
+            }
+        }
+    }
+
+    fn get(&self, id: u32) -> Option<&Motion> {
+        self.tweens
+            .iter()
+            .find(|(other, _)| *other == id)
+            .map(|(_, motion)| motion)
+    }
+
+    /// Put `motion` under `id`, replacing what was there; false when that would make more
+    /// than `MAX_TWEENS`.
+    fn set(&mut self, id: u32, motion: Motion) -> bool {
+        if let Some((_, existing)) = self.tweens.iter_mut().find(|(other, _)| *other == id) {
+            *existing = motion;
+            return true;
+        }
+        if self.tweens.len() >= MAX_TWEENS {
+            return false;
+        }
+        self.tweens.push((id, motion));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Easing, Tweens, MAX_TWEENS};
+
+    #[test]
+    fn easings_start_and_end_in_place_and_bend_in_between() {
+        for easing in [
+            Easing::Linear,
+            Easing::Ease,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert!(easing.apply(0.0).abs() < 1e-4, "{easing:?}");
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-4, "{easing:?}");
+        }
+        assert_eq!(Easing::Linear.apply(0.25), 0.25);
+        assert!(Easing::EaseIn.apply(0.25) < 0.25);
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+        assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-4);
+        // CSS `ease` is about 80% of the way there halfway through.
+        assert!((Easing::Ease.apply(0.5) - 0.8024).abs() < 1e-3);
+    }
+
+    #[test]
+    fn tweens_follow_the_frames_they_are_stepped_by() {
+        let mut tweens = Tweens::default();
+        assert!(tweens.start(1, 10.0, 20.0, 100.0, Easing::Linear));
+        assert!(!tweens.start(2, 0.0, f32::INFINITY, 100.0, Easing::Linear));
+        assert!(!tweens.start(2, 0.0, 1.0, -1.0, Easing::Linear));
+        assert_eq!(tweens.value(1), Some(10.0));
+        assert!(tweens.is_running());
+
+        tweens.advance(25.0);
+        assert_eq!(tweens.value(1), Some(12.5));
+        tweens.advance(f32::NAN);
+        tweens.advance(1000.0);
+        assert_eq!(tweens.value(1), Some(20.0), "holds its end value");
+        assert!(!tweens.is_running());
+
+        // Restarting an id replaces its tween; zero-length ones finish at once.
+        assert!(tweens.start(1, 5.0, 0.0, 0.0, Easing::EaseIn));
+        assert_eq!(tweens.value(1), Some(0.0));
+        assert_eq!(tweens.stop(1), Some(0.0));
+        assert_eq!(tweens.value(1), None);
+
+        for id in 0..MAX_TWEENS as u32 {
+            assert!(tweens.start(id, 0.0, 1.0, 10.0, Easing::Linear));
+        }
+        assert!(!tweens.start(MAX_TWEENS as u32, 0.0, 1.0, 10.0, Easing::Linear));
+        assert!(
+            tweens.start(0, 1.0, 0.0, 10.0, Easing::Linear),
+            "replacing fits"
+        );
+    }
+
+    #[test]
+    fn springs_settle_on_their_target_and_pick_up_running_motion() {
+        let mut tweens = Tweens::default();
+        assert!(!tweens.spring(1, 1.0, 0.0, 10.0));
+        assert!(!tweens.spring(1, 1.0, 100.0, f32::NAN));
+        assert!(tweens.spring(1, 5.0, 100.0, 20.0));
+        assert_eq!(tweens.value(1), Some(5.0), "new ids start at rest");
+        assert!(!tweens.is_running());
+
+        // Critically damped (damping 2√stiffness) and overdamped springs never overshoot;
+        // underdamped ones do, and all of them settle.
+        for (damping, overshoots) in [(20.0, false), (40.0, false), (5.0, true)] {
+            assert!(tweens.start(1, 0.0, 0.0, 0.0, Easing::Linear));
+            assert!(tweens.spring(1, 100.0, 100.0, damping));
+            let mut furthest: f32 = 0.0;
+            let mut frames = 0;
+            while tweens.is_running() {
+                tweens.advance(16.0);
+                furthest = furthest.max(tweens.value(1).unwrap());
+                frames += 1;
+                assert!(frames < 1000, "damping {damping} never settled");
+            }
+            assert_eq!(tweens.value(1), Some(100.0));
+            assert_eq!(furthest > 100.0, overshoots, "damping {damping}");
+        }
+
+        // Retargeting mid-tween carries its speed on, so the value keeps moving forwards
+        // before turning back.
+        assert!(tweens.start(2, 0.0, 100.0, 100.0, Easing::Linear));
+        tweens.advance(50.0);
+        assert!(tweens.spring(2, 0.0, 100.0, 20.0));
+        tweens.advance(16.0);
+        assert!(tweens.value(2).unwrap() > 50.0);
+    }
+}
@@ -0,0 +1,119 @@
+//! The drawing vocabulary shared by the SVG and Lottie renderers. They draw through
+//! [`VectorSink`], which a Vello scene implements for the window and the exporters in
+//! [`crate::export`] implement for SVG and PDF files.
+
+use vello::kurbo::{Affine, BezPath, Rect, Stroke};
+use vello::peniko::{Color, Fill};
+use vello::Scene;
+
+/// Somewhere paths can be filled, stroked and clipped.
+pub trait VectorSink {
+    fn fill(&mut self, rule: Fill, transform: Affine, color: Color, path: &BezPath);
+
+    fn stroke(&mut self, stroke: &Stroke, transform: Affine, color: Color, path: &BezPath);
+
+    /// Clip everything up to the matching `pop_clip` to `rect` mapped through `transform`.
+    fn push_clip(&mut self, transform: Affine, rect: Rect);
+
+    fn pop_clip(&mut self);
+}
+
+impl VectorSink for Scene {
+    fn fill(&mut self, rule: Fill, transform: Affine, color: Color, path: &BezPath) {
+        Scene::fill(self, rule, transform, color, None, path);
+    }
+
+    fn stroke(&mut self, stroke: &Stroke, transform: Affine, color: Color, path: &BezPath) {
+        Scene::stroke(self, stroke, transform, color, None, path);
+    }
+
+    fn push_clip(&mut self, transform: Affine, rect: Rect) {
+        self.push_clip_layer(transform, &rect);
+    }
+
+    fn pop_clip(&mut self) {
+        self.pop_layer();
+    }
+}
+
+#[derive(Clone, Debug)]
+enum VectorOp {
+    Fill {
+        rule: Fill,
+        transform: Affine,
+        color: Color,
+        path: BezPath,
+    },
+    Stroke {
+        stroke: Stroke,
+        transform: Affine,
+        color: Color,
+        path: BezPath,
+    },
+    PushClip {
+        transform: Affine,
+        rect: Rect,
+    },
+    PopClip,
+}
+
+/// Drawing operations kept so they can be replayed into any sink.
+#[derive(Clone, Debug, Default)]
+pub struct Recording {
+    ops: Vec<VectorOp>,
+}
+
+impl Recording {
+    /// Draw everything recorded into `sink`, with `transform` applied on top.
+    pub fn replay(&self, sink: &mut impl VectorSink, transform: Affine) {
+        for op in &self.ops {
+            match op {
+                VectorOp::Fill {
+                    rule,
+                    transform: local,
+                    color,
+                    path,
+                } => sink.fill(*rule, transform * *local, *color, path),
+                VectorOp::Stroke {
+                    stroke,
+                    transform: local,
+                    color,
+                    path,
+                } => sink.stroke(stroke, transform * *local, *color, path),
+                VectorOp::PushClip {
+                    transform: local,
+                    rect,
+                } => sink.push_clip(transform * *local, *rect),
+                VectorOp::PopClip => sink.pop_clip(),
+            }
+        }
+    }
+}
+
+impl VectorSink for Recording {
+    fn fill(&mut self, rule: Fill, transform: Affine, color: Color, path: &BezPath) {
+        self.ops.push(VectorOp::Fill {
+            rule,
+            transform,
+            color,
+            path: path.clone(),
+        });
+    }
+
+    fn stroke(&mut self, stroke: &Stroke, transform: Affine, color: Color, path: &BezPath) {
+        self.ops.push(VectorOp::Stroke {
+            stroke: stroke.clone(),
+            transform,
+            color,
+            path: path.clone(),
+        });
+    }
+
+    fn push_clip(&mut self, transform: Affine, rect: Rect) {
+        self.ops.push(VectorOp::PushClip { transform, rect });
+    }
+
+    fn pop_clip(&mut self) {
+        self.ops.push(VectorOp::PopClip);
+    }
+}
@@ -0,0 +1,285 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::graphics::DEFAULT_CLEAR_COLOR;
+use crate::host::Color;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::monitor::MonitorHandle;
+use winit::window::{
+    Icon, Theme as WinitTheme, Window, WindowAttributes, WindowLevel as WinitWindowLevel,
+};
+
+pub const DEFAULT_WINDOW_TITLE: &str = "Frontier Canvas Prototype";
+pub const DEFAULT_APP_ID: &str = "frontier-wasm-host";
+/// Smallest inner size, in logical pixels, the window can be resized to. Some platforms
+/// ignore it, and minimizing still reports a zero size.
+pub const MIN_WINDOW_SIZE: (f64, f64) = (160.0, 120.0);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum WindowLevel {
+    #[default]
+    Normal,
+    AlwaysOnTop,
+    AlwaysOnBottom,
+}
+
+impl WindowLevel {
+    fn to_winit(self) -> WinitWindowLevel {
+        match self {
+            WindowLevel::Normal => WinitWindowLevel::Normal,
+            WindowLevel::AlwaysOnTop => WinitWindowLevel::AlwaysOnTop,
+            WindowLevel::AlwaysOnBottom => WinitWindowLevel::AlwaysOnBottom,
+        }
+    }
+}
+
+/// Light or dark window decorations; `system` follows the desktop setting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    fn to_winit(self) -> Option<WinitTheme> {
+        match self {
+            Theme::System => None,
+            Theme::Light => Some(WinitTheme::Light),
+            Theme::Dark => Some(WinitTheme::Dark),
+        }
+    }
+}
+
+/// Window changes requested by the guest, applied by the app after each call.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowRequest {
+    SetLevel(WindowLevel),
+    SetOpacity(f32),
+    SetClickThrough(bool),
+    /// Outer top-left corner in physical pixels.
+    SetPosition(PhysicalPosition<i32>),
+    /// Colour shown behind frames that do not clear.
+    SetBackground(Color),
+}
+
+/// Window identity and chrome options applied when the host window is created.
+#[derive(Clone, Debug)]
+pub struct WindowOptions {
+    pub title: String,
+    /// PNG image used for the window and taskbar icon.
+    pub icon: Option<PathBuf>,
+    /// Application id (Wayland) / WM_CLASS (X11) hint.
+    pub app_id: String,
+    pub level: WindowLevel,
+    /// Overall window opacity; values below 1.0 create a transparent window.
+    pub opacity: f32,
+    /// Let pointer input pass through the window.
+    pub click_through: bool,
+    pub theme: Theme,
+    /// Colour shown behind frames that do not clear, until the guest picks its own.
+    pub background: Color,
+}
+
+impl Default for WindowOptions {
+    fn default() -> Self {
+        Self {
+            title: DEFAULT_WINDOW_TITLE.to_string(),
+            icon: None,
+            app_id: DEFAULT_APP_ID.to_string(),
+            level: WindowLevel::Normal,
+            opacity: 1.0,
+            click_through: false,
+            theme: Theme::System,
+            background: DEFAULT_CLEAR_COLOR,
+        }
+    }
+}
+
+impl WindowOptions {
+    pub fn attributes(&self) -> WindowAttributes {
+        let mut attributes = WindowAttributes::default()
+            .with_title(self.title.clone())
+            .with_inner_size(PhysicalSize::new(900, 600))
+            .with_min_inner_size(winit::dpi::LogicalSize::new(
+                MIN_WINDOW_SIZE.0,
+                MIN_WINDOW_SIZE.1,
+            ))
+            .with_window_level(self.level.to_winit())
+            .with_theme(self.theme.to_winit())
+            .with_transparent(self.opacity < 1.0);
+
+        if let Some(path) = &self.icon {
+            match load_icon(path) {
+                Ok(icon) => attributes = attributes.with_window_icon(Some(icon)),
+                Err(err) => tracing::warn!(error = %format!("{err:#}"), "ignoring window icon"),
+            }
+        }
+
+        apply_app_id(attributes, &self.app_id)
+    }
+
+    /// Apply settings that can only be changed once the window exists.
+    pub fn apply_to(&self, window: &Window) {
+        if self.click_through {
+            set_click_through(window, true);
+        }
+    }
+}
+
+/// Properties of the monitor a window is on, as reported to guests.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DisplayInfo {
+    pub name: Option<String>,
+    /// Physical pixels.
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f32,
+    pub refresh_rate_millihertz: Option<u32>,
+}
+
+impl DisplayInfo {
+    pub fn from_monitor(monitor: &MonitorHandle) -> Self {
+        let size = monitor.size();
+        Self {
+            name: monitor.name(),
+            width: size.width,
+            height: size.height,
+            scale_factor: monitor.scale_factor() as f32,
+            refresh_rate_millihertz: monitor.refresh_rate_millihertz(),
+        }
+    }
+
+    /// The display `window` is on, if the platform can say.
+    pub fn current(window: &Window) -> Option<Self> {
+        window
+            .current_monitor()
+            .map(|monitor| Self::from_monitor(&monitor))
+    }
+}
+
+pub fn clamp_opacity(opacity: f32) -> f32 {
+    if opacity.is_finite() {
+        opacity.clamp(0.0, 1.0)
+    } else {
+        1.0
+    }
+}
+
+pub fn set_window_level(window: &Window, level: WindowLevel) {
+    window.set_window_level(level.to_winit());
+}
+
+pub fn set_click_through(window: &Window, enabled: bool) {
+    if let Err(err) = window.set_cursor_hittest(!enabled) {
+        tracing::warn!(%err, "click-through is not supported on this platform");
+    }
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+))]
+fn apply_app_id(attributes: WindowAttributes, app_id: &str) -> WindowAttributes {
+    use winit::platform::wayland::WindowAttributesExtWayland;
+
+    attributes.with_name(app_id, app_id)
+}
+
+#[cfg(not(all(
+    unix,
+    not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+)))]
+fn apply_app_id(attributes: WindowAttributes, _app_id: &str) -> WindowAttributes {
+    attributes
+}
+
+/// Turn an arbitrary label (usually a component file stem) into a desktop-friendly app id.
+pub fn sanitize_app_id(label: &str) -> String {
+    let mut id = String::with_capacity(label.len());
+    for ch in label.chars() {
+        if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+            id.push(ch.to_ascii_lowercase());
+        } else if !id.ends_with('-') {
+            id.push('-');
+        }
+    }
+    let id = id.trim_matches('-').to_string();
+    if id.is_empty() {
+        DEFAULT_APP_ID.to_string()
+    } else {
+        id
+    }
+}
+
+pub fn load_icon(path: &Path) -> Result<Icon> {
+    let file =
+        File::open(path).with_context(|| format!("failed to open icon {}", path.display()))?;
+    let (rgba, width, height) = decode_png_rgba(BufReader::new(file))
+        .with_context(|| format!("failed to decode icon {}", path.display()))?;
+    Icon::from_rgba(rgba, width, height).context("icon rejected by windowing system")
+}
+
+fn decode_png_rgba<R: std::io::Read>(reader: R) -> Result<(Vec<u8>, u32, u32)> {
+    let mut decoder = png::Decoder::new(reader);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf)?;
+    buf.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|px| [px[0], px[1], px[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|px| [px[0], px[0], px[0], px[1]])
+            .collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+        png::ColorType::Indexed => bail!("indexed PNG was not expanded"),
+    };
+    Ok((rgba, info.width, info.height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clamp_opacity, decode_png_rgba, sanitize_app_id};
+
+    #[test]
+    fn sanitizes_component_labels_into_app_ids() {
+        assert_eq!(sanitize_app_id("counter_component"), "counter_component");
+        assert_eq!(sanitize_app_id("My Cool App!"), "my-cool-app");
+        assert_eq!(sanitize_app_id("   "), "frontier-wasm-host");
+    }
+
+    #[test]
+    fn clamps_opacity_into_unit_range() {
+        assert_eq!(clamp_opacity(1.5), 1.0);
+        assert_eq!(clamp_opacity(-0.2), 0.0);
+        assert_eq!(clamp_opacity(f32::NAN), 1.0);
+        assert_eq!(clamp_opacity(0.25), 0.25);
+    }
+
+    #[test]
+    fn expands_rgb_png_to_rgba() {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, 2, 1);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(&[255, 0, 0, 0, 0, 255]).unwrap();
+        }
+        let (rgba, width, height) = decode_png_rgba(bytes.as_slice()).unwrap();
+        assert_eq!((width, height), (2, 1));
+        assert_eq!(rgba, vec![255, 0, 0, 255, 0, 0, 255, 255]);
+    }
+}
@@ -0,0 +1,211 @@
+//! Worker components behind `spawn-worker`, so an app can load plugins at runtime without
+//! handing them its own access.
+//!
+//! A worker is a separate `canvas-worker` component on its own thread. It is linked only
+//! to the host interfaces its parent granted (and the parent has). It keeps its own
+//! storage, sees none of the parent's preopened directories, and exchanges nothing with
+//! the parent but byte messages: `post-to-worker` queues them for its `on-message`, and its
+//! `post-message` calls reach the parent as `worker-message` between frames.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::runtime::ComponentSource;
+use crate::sandbox::HostInterfaces;
+
+/// Workers a component may have running at once.
+pub const MAX_WORKERS: usize = 8;
+/// Messages that may wait in either direction before senders are refused or held up.
+pub const MAX_QUEUED_MESSAGES: usize = 256;
+/// Largest message either side may send, in bytes.
+pub const MAX_MESSAGE_BYTES: usize = 1024 * 1024;
+
+/// A `spawn-worker` call waiting for the runtime to start it.
+pub struct WorkerRequest {
+    pub id: u32,
+    pub source: ComponentSource,
+    /// What the worker was granted; the runtime narrows it to what the parent has.
+    pub interfaces: HostInterfaces,
+    pub inbox: Receiver<Vec<u8>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WorkerEvent {
+    Message(u32, Vec<u8>),
+    Failed(u32, String),
+}
+
+/// The parent's workers: requests not yet started and the inbox of each one running.
+#[derive(Default)]
+pub struct Workers {
+    next_id: u32,
+    pending: Vec<WorkerRequest>,
+    inboxes: HashMap<u32, SyncSender<Vec<u8>>>,
+}
+
+impl std::fmt::Debug for Workers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Workers")
+            .field("pending", &self.pending.len())
+            .field("running", &self.inboxes.len())
+            .finish()
+    }
+}
+
+impl Workers {
+    /// Queue a worker, returning its id.
+    pub fn spawn(&mut self, source: ComponentSource, interfaces: HostInterfaces) -> Result<u32> {
+        if self.inboxes.len() >= MAX_WORKERS {
+            bail!("too many workers running (limit {MAX_WORKERS})");
+        }
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        let (sender, inbox) = mpsc::sync_channel(MAX_QUEUED_MESSAGES);
+        self.inboxes.insert(id, sender);
+        self.pending.push(WorkerRequest {
+            id,
+            source,
+            interfaces,
+            inbox,
+        });
+        Ok(id)
+    }
+
+    pub fn post(&self, id: u32, message: Vec<u8>) -> Result<()> {
+        check_size(&message)?;
+        let inbox = self
+            .inboxes
+            .get(&id)
+            .ok_or_else(|| anyhow!("no worker {id}"))?;
+        inbox.try_send(message).map_err(|err| match err {
+            TrySendError::Full(_) => {
+                anyhow!("worker {id} has {MAX_QUEUED_MESSAGES} messages waiting")
+            }
+            TrySendError::Disconnected(_) => anyhow!("worker {id} has stopped"),
+        })
+    }
+
+    /// Drop the worker's inbox, which ends it once the message in progress is handled.
+    pub fn terminate(&mut self, id: u32) -> bool {
+        self.pending.retain(|request| request.id != id);
+        self.inboxes.remove(&id).is_some()
+    }
+
+    pub fn take_pending(&mut self) -> Vec<WorkerRequest> {
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn running(&self) -> usize {
+        self.inboxes.len()
+    }
+}
+
+/// Where workers send their messages. Dropping it ends the parent's side of every
+/// channel, which is how a reload cuts off the old instance's workers.
+pub struct WorkerChannel {
+    sender: SyncSender<WorkerEvent>,
+    receiver: Receiver<WorkerEvent>,
+}
+
+impl Default for WorkerChannel {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::sync_channel(MAX_QUEUED_MESSAGES);
+        Self { sender, receiver }
+    }
+}
+
+impl WorkerChannel {
+    pub fn outbox(&self, id: u32) -> WorkerOutbox {
+        WorkerOutbox {
+            id,
+            events: self.sender.clone(),
+        }
+    }
+
+    /// Events waiting, oldest first. Stops after a channel's worth so a chatty worker
+    /// cannot keep the caller here.
+    pub fn drain(&self) -> Vec<WorkerEvent> {
+        self.receiver.try_iter().take(MAX_QUEUED_MESSAGES).collect()
+    }
+}
+
+/// A worker's line back to its parent.
+#[derive(Clone, Debug)]
+pub struct WorkerOutbox {
+    id: u32,
+    events: SyncSender<WorkerEvent>,
+}
+
+impl WorkerOutbox {
+    /// Send `message`, waiting while the parent is behind.
+    pub fn post(&self, message: Vec<u8>) -> Result<()> {
+        check_size(&message)?;
+        self.events
+            .send(WorkerEvent::Message(self.id, message))
+            .map_err(|_| anyhow!("the parent is no longer listening"))
+    }
+
+    pub fn fail(&self, error: String) {
+        let _ = self.events.send(WorkerEvent::Failed(self.id, error));
+    }
+}
+
+fn check_size(message: &[u8]) -> Result<()> {
+    if message.len() > MAX_MESSAGE_BYTES {
+        bail!("messages are limited to {MAX_MESSAGE_BYTES} bytes");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WorkerChannel, WorkerEvent, Workers, MAX_MESSAGE_BYTES, MAX_WORKERS};
+    use crate::runtime::ComponentSource;
+    use crate::sandbox::HostInterfaces;
+
+    #[test]
+    fn messages_reach_running_workers_until_they_are_terminated() {
+        let mut workers = Workers::default();
+        let source = ComponentSource::embedded("plugin", b"");
+        let ids: Vec<u32> = (0..MAX_WORKERS)
+            .map(|_| {
+                workers
+                    .spawn(source.clone(), HostInterfaces::default())
+                    .unwrap()
+            })
+            .collect();
+        assert!(workers
+            .spawn(source.clone(), HostInterfaces::default())
+            .is_err());
+
+        let pending = workers.take_pending();
+        assert_eq!(pending.len(), MAX_WORKERS);
+        workers.post(ids[0], b"hi".to_vec()).unwrap();
+        assert_eq!(pending[0].inbox.try_recv().unwrap(), b"hi");
+        assert!(workers
+            .post(ids[0], vec![0; MAX_MESSAGE_BYTES + 1])
+            .is_err());
+
+        assert!(workers.terminate(ids[0]));
+        assert!(!workers.terminate(ids[0]));
+        assert!(workers.post(ids[0], Vec::new()).is_err());
+        assert!(pending[0].inbox.recv().is_err());
+        assert_eq!(workers.running(), MAX_WORKERS - 1);
+
+        let channel = WorkerChannel::default();
+        let outbox = channel.outbox(ids[1]);
+        outbox.post(b"back".to_vec()).unwrap();
+        outbox.fail("trapped".into());
+        assert_eq!(
+            channel.drain(),
+            [
+                WorkerEvent::Message(ids[1], b"back".to_vec()),
+                WorkerEvent::Failed(ids[1], "trapped".into())
+            ]
+        );
+        drop(channel);
+        assert!(outbox.post(Vec::new()).is_err());
+    }
+}
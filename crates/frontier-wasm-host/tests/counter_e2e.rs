@@ -1,7 +1,14 @@
 use std::path::PathBuf;
 use std::process::Command;
+use std::time::{Duration, Instant};
 
-use frontier_wasm_host::{ComponentRuntime, ComponentSource, LogicalSize};
+use frontier_wasm_host::model::{GestureEvent, GesturePhase, Modifiers};
+use frontier_wasm_host::script::{ScriptRunner, Step, TestScript};
+use frontier_wasm_host::software::SoftwareCanvas;
+use frontier_wasm_host::{
+    CloseResponse, ComponentRuntime, ComponentSource, GuestStdio, HostInterfaces, LaunchConfig,
+    LogicalSize, Rect, Vec2,
+};
 
 fn workspace_root() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -64,7 +71,336 @@ fn counter_component_lifecycle() {
 
     let frame = runtime.call_frame(16.0).expect("call frame");
     assert!(
-        !frame.frame.commands.is_empty(),
-        "frame should contain drawing commands"
+        frame.frame.contains_text("0"),
+        "counter should start at zero"
+    );
+    assert!(
+        frame.frame.contains_text("+") && frame.frame.contains_text("-"),
+        "both buttons should be labelled"
+    );
+    assert!(
+        frame
+            .frame
+            .rects_intersecting(Rect::new(0.0, 0.0, 800.0, 600.0))
+            .count()
+            >= 3,
+        "panel and buttons should be filled"
+    );
+
+    let (response, _) = runtime
+        .call_close_requested()
+        .expect("call close-requested");
+    assert_eq!(response, CloseResponse::Allow);
+}
+
+#[test]
+fn embedded_counter_component_matches_host_interface() {
+    let bytes: &'static [u8] = include_bytes!("../../../assets/counter-component.wasm");
+    let source = ComponentSource::embedded("embedded counter demo", bytes);
+    let mut runtime = ComponentRuntime::new(source).expect("instantiate embedded component");
+    runtime
+        .call_init(LogicalSize {
+            width: 800.0,
+            height: 600.0,
+            scale_factor: 1.0,
+        })
+        .expect("call init");
+}
+
+#[test]
+fn counter_runs_in_a_locked_down_sandbox() {
+    let bytes: &'static [u8] = include_bytes!("../../../assets/counter-component.wasm");
+    let source = ComponentSource::embedded("embedded counter demo", bytes);
+    let mut runtime = ComponentRuntime::builder(source)
+        .with_stdio(GuestStdio::Null)
+        .with_call_timeout(Duration::from_secs(5))
+        .with_max_memory(16 * 1024 * 1024)
+        .with_host_interfaces(HostInterfaces {
+            wasi: false,
+            canvas: true,
+            database: false,
+        })
+        .build()
+        .expect("instantiate sandboxed component");
+    runtime
+        .call_init(LogicalSize {
+            width: 800.0,
+            height: 600.0,
+            scale_factor: 1.0,
+        })
+        .expect("call init");
+    let frame = runtime.call_frame(16.0).expect("call frame");
+    assert!(!frame.frame.is_empty());
+}
+
+#[test]
+fn memory_limits_below_the_initial_size_fail_to_instantiate() {
+    let bytes: &'static [u8] = include_bytes!("../../../assets/counter-component.wasm");
+    let source = ComponentSource::embedded("embedded counter demo", bytes);
+    assert!(ComponentRuntime::builder(source)
+        .with_max_memory(64 * 1024)
+        .build()
+        .is_err());
+}
+
+#[test]
+fn counter_frame_renders_without_a_gpu() {
+    let bytes: &'static [u8] = include_bytes!("../../../assets/counter-component.wasm");
+    let source = ComponentSource::embedded("embedded counter demo", bytes);
+    let mut runtime = ComponentRuntime::new(source).expect("instantiate embedded component");
+    runtime
+        .call_init(LogicalSize {
+            width: 400.0,
+            height: 300.0,
+            scale_factor: 1.0,
+        })
+        .expect("call init");
+    let frame = runtime.call_frame(16.0).expect("call frame").frame;
+
+    let mut canvas = SoftwareCanvas::new(400, 300, 1.0).expect("create canvas");
+    canvas.render(&frame);
+    // The counter clears to a dark grey and draws a lighter panel inset from the edges.
+    let background = canvas.pixel(2, 2).expect("corner pixel");
+    let panel = canvas.pixel(200, 60).expect("panel pixel");
+    assert!(
+        background[..3].iter().all(|&channel| channel < 40),
+        "{background:?}"
+    );
+    assert_ne!(background, panel);
+}
+
+#[test]
+fn core_modules_are_rejected_with_a_hint() {
+    // An empty core module: magic, version 1.
+    let source = ComponentSource::embedded("core module", b"\0asm\x01\0\0\0");
+    let Err(err) = ComponentRuntime::new(source) else {
+        panic!("core module loaded as a component");
+    };
+    assert!(
+        format!("{err:#}").contains("core WebAssembly module"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn snapshots_restore_the_count() {
+    let bytes: &'static [u8] = include_bytes!("../../../assets/counter-component.wasm");
+    let source = ComponentSource::embedded("embedded counter demo", bytes);
+    let mut runtime = ComponentRuntime::new(source).expect("instantiate embedded component");
+    let size = LogicalSize {
+        width: 800.0,
+        height: 600.0,
+        scale_factor: 1.0,
+    };
+    runtime.call_init(size).expect("call init");
+    runtime.simulate_click(640.0, 424.0).expect("click plus");
+    runtime.simulate_click(640.0, 424.0).expect("click plus");
+    let snapshot = runtime.snapshot().expect("snapshot");
+
+    runtime.simulate_key("Enter").expect("reset");
+    let frame = runtime.call_frame(16.0).expect("call frame").frame;
+    assert!(frame.contains_text("0"), "enter should reset the count");
+
+    let restored = runtime.restore(&snapshot).expect("restore");
+    assert!(
+        restored.requested_redraw,
+        "restoring should request a redraw"
+    );
+    let frame = runtime.call_frame(16.0).expect("call frame").frame;
+    assert!(
+        frame.contains_text("2"),
+        "restore should bring the count back"
+    );
+
+    assert!(runtime.restore(b"not a snapshot").is_err());
+}
+
+#[test]
+fn counter_test_script_passes() {
+    let bytes: &'static [u8] = include_bytes!("../../../assets/counter-component.wasm");
+    let source = ComponentSource::embedded("embedded counter demo", bytes);
+    let script_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/scripts/counter.yaml");
+    let script = TestScript::load(&script_path).expect("load test script");
+
+    let mut runner =
+        ScriptRunner::new(source, LaunchConfig::default(), script.size).expect("start runner");
+    runner.run(&script.steps).expect("script should pass");
+
+    let failure = runner
+        .run(&[Step::ExpectText("42".into())])
+        .expect_err("missing text should fail");
+    assert!(format!("{failure:#}").contains("\"42\" was not drawn"));
+}
+
+#[test]
+fn counter_responds_to_simulated_input() {
+    let bytes: &'static [u8] = include_bytes!("../../../assets/counter-component.wasm");
+    let source = ComponentSource::embedded("embedded counter demo", bytes);
+    let mut runtime = ComponentRuntime::new(source).expect("instantiate embedded component");
+    runtime
+        .call_init(LogicalSize {
+            width: 800.0,
+            height: 600.0,
+            scale_factor: 1.0,
+        })
+        .expect("call init");
+    let plus = Vec2::new(640.0, 424.0);
+
+    let clicked = runtime.simulate_click(plus.x, plus.y).expect("click plus");
+    assert!(clicked.requested_redraw, "clicking should request a redraw");
+    let frame = runtime.call_frame(16.0).expect("call frame").frame;
+    assert!(frame.contains_text("1"), "click on + should increment");
+    let (host_calls, text_bytes) = runtime.take_host_usage();
+    let fill_rects = host_calls.iter().find(|call| call.name == "fill-rect");
+    assert!(
+        fill_rects.is_some_and(|call| call.calls > 0),
+        "{host_calls:?}"
+    );
+    assert!(
+        text_bytes > 0,
+        "drawing the count should be counted as text"
+    );
+
+    runtime
+        .simulate_drag(plus, Vec2::new(400.0, 100.0), 4)
+        .expect("drag off plus");
+    let frame = runtime.call_frame(16.0).expect("call frame").frame;
+    assert!(
+        frame.contains_text("1"),
+        "dragging off + should cancel the press"
+    );
+
+    runtime.simulate_key("Enter").expect("press enter");
+    let frame = runtime.call_frame(16.0).expect("call frame").frame;
+    assert!(frame.contains_text("0"), "enter should reset the count");
+
+    runtime.call_text_input("é").expect("text input");
+
+    let pinch = GestureEvent {
+        phase: GesturePhase::Changed,
+        position: plus,
+        modifiers: Modifiers::default(),
+        delta: 0.1,
+    };
+    runtime.call_pinch_gesture(&pinch).expect("pinch gesture");
+    runtime
+        .call_rotation_gesture(&pinch)
+        .expect("rotation gesture");
+    runtime
+        .call_double_tap_gesture(plus, Modifiers::default())
+        .expect("double tap gesture");
+}
+
+#[test]
+fn background_tasks_report_back_between_calls() {
+    let bytes: &'static [u8] = include_bytes!("../../../assets/counter-component.wasm");
+    let source = ComponentSource::embedded("embedded counter demo", bytes);
+    let mut runtime = ComponentRuntime::builder(source)
+        .with_call_timeout(Duration::from_millis(500))
+        .build()
+        .expect("instantiate embedded component");
+    runtime
+        .call_init(LogicalSize {
+            width: 800.0,
+            height: 600.0,
+            scale_factor: 1.0,
+        })
+        .expect("call init");
+    runtime.simulate_key("+").expect("increment");
+    runtime.simulate_key("+").expect("increment");
+    runtime.simulate_key("+").expect("increment");
+
+    runtime.simulate_key("d").expect("start doubling");
+    assert_eq!(runtime.tasks_in_flight(), 1);
+    let frame = runtime.call_frame(16.0).expect("call frame").frame;
+    assert!(
+        frame.contains_text("Doubling... 0%"),
+        "the hint should show progress"
+    );
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while runtime.tasks_in_flight() > 0 {
+        assert!(Instant::now() < deadline, "the task never finished");
+        runtime.poll_tasks().expect("deliver task events");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let frame = runtime.call_frame(16.0).expect("call frame").frame;
+    assert!(frame.contains_text("6"), "the task should double the count");
+    assert!(frame.contains_text("Use +/- keys or Space/Enter"));
+}
+
+/// A `canvas-worker` that posts every message straight back.
+const ECHO_WORKER: &str = r#"
+(component
+  (import "vello:canvas/worker@0.1.0" (instance $channel
+    (export "post-message" (func (param "message" (list u8)) (result (result (error string)))))))
+  (core module $alloc
+    (memory (export "memory") 1)
+    (global $heap (mut i32) (i32.const 1024))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      (local.set $ptr (global.get $heap))
+      (global.set $heap (i32.add (global.get $heap) (local.get 3)))
+      (local.get $ptr)))
+  (core instance $alloc (instantiate $alloc))
+  (alias core export $alloc "memory" (core memory $mem))
+  (alias core export $alloc "realloc" (core func $realloc))
+  (alias export $channel "post-message" (func $post))
+  (core func $post (canon lower (func $post) (memory $mem) (realloc $realloc)))
+  (core module $worker
+    (import "env" "post-message" (func $post (param i32 i32 i32)))
+    (func (export "on-message") (param $ptr i32) (param $len i32)
+      (call $post (local.get $ptr) (local.get $len) (i32.const 0))))
+  (core instance $env (export "post-message" (func $post)))
+  (core instance $worker (instantiate $worker (with "env" (instance $env))))
+  (func $on-message (param "message" (list u8))
+    (canon lift (core func $worker "on-message") (memory $mem) (realloc $realloc)))
+  (instance $app (export "on-message" (func $on-message)))
+  (export "vello:canvas/worker-app@0.1.0" (instance $app)))
+"#;
+
+#[test]
+fn workers_exchange_messages_with_their_parent() {
+    let bytes: &'static [u8] = include_bytes!("../../../assets/counter-component.wasm");
+    let source = ComponentSource::embedded("embedded counter demo", bytes);
+    let mut runtime = ComponentRuntime::new(source).expect("instantiate embedded component");
+    runtime
+        .call_init(LogicalSize {
+            width: 800.0,
+            height: 600.0,
+            scale_factor: 1.0,
+        })
+        .expect("call init");
+
+    let echo: &'static [u8] = wat::parse_str(ECHO_WORKER).expect("parse worker").leak();
+    let no_access = HostInterfaces {
+        wasi: false,
+        canvas: false,
+        database: false,
+    };
+    let echo = runtime
+        .spawn_worker(ComponentSource::embedded("echo", echo), no_access)
+        .expect("spawn worker");
+    let broken = runtime
+        .spawn_worker(ComponentSource::embedded("counter", bytes), no_access)
+        .expect("spawn worker");
+    assert_eq!(runtime.workers_running(), 2);
+    runtime
+        .post_to_worker(echo, 7i32.to_le_bytes().to_vec())
+        .expect("post to worker");
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    loop {
+        runtime.poll_workers().expect("deliver worker events");
+        let frame = runtime.call_frame(16.0).expect("call frame").frame;
+        if frame.contains_text("7") && runtime.workers_running() == 1 {
+            break;
+        }
+        assert!(Instant::now() < deadline, "the worker never replied");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(
+        runtime.post_to_worker(broken, Vec::new()).is_err(),
+        "a component without worker exports should fail to load"
     );
 }
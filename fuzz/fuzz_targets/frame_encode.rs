@@ -0,0 +1,31 @@
+//! Encodes arbitrary, unsanitised frames, including NaN geometry and giant strings that
+//! `HostCtx` would normally reject, to check the scene encoder and frame queries never panic.
+#![no_main]
+
+use std::cell::RefCell;
+
+use frontier_wasm_host::canvas::CanvasBackend;
+use frontier_wasm_host::graphics::SceneEncoder;
+use frontier_wasm_host::{FrameOutput, Rect};
+use libfuzzer_sys::fuzz_target;
+
+thread_local! {
+    static ENCODER: RefCell<SceneEncoder> =
+        RefCell::new(SceneEncoder::new(1.0));
+}
+
+fuzz_target!(|input: (FrameOutput, f32, [f32; 4])| {
+    let (frame, scale_factor, [x, y, width, height]) = input;
+    let _ = frame.command_count();
+    let _ = frame.texts().count();
+    let _ = frame
+        .rects_intersecting(Rect::new(x, y, width, height))
+        .count();
+
+    ENCODER.with(|encoder| {
+        let mut encoder = encoder.borrow_mut();
+        encoder.set_scale_factor(scale_factor);
+        encoder.reset();
+        encoder.encode_commands(&frame.commands);
+    });
+});
@@ -0,0 +1,10 @@
+//! Decodes arbitrary bytes as a `.fframe` file, to check corrupt or hostile dumps are
+//! rejected with an error rather than a panic.
+#![no_main]
+
+use frontier_wasm_host::frame_file;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| {
+    let _ = frame_file::decode(bytes);
+});
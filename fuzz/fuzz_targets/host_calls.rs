@@ -0,0 +1,523 @@
+//! Feeds arbitrary sequences of guest host calls through `HostCtx`, then encodes whatever
+//! frame survives into a Vello scene. A hostile component controls every argument here.
+#![no_main]
+
+use std::cell::RefCell;
+
+use arbitrary::Arbitrary;
+use frontier_wasm_host::component::vello::canvas::host::{
+    BarStyle, DatetimeStyle, Easing, EmitterConfig, ExportFormat, FontFamily, GridCell, Heatmap, Host, ImageFilter, ImageStyle, LogLevel, PatternRepeat, Rect, StyledSpan, TextDirection, WindowPosition, WritingMode,
+};
+use frontier_wasm_host::component::vello::canvas::math::{Color, Vec2};
+use frontier_wasm_host::canvas::CanvasBackend;
+use frontier_wasm_host::geometry;
+use frontier_wasm_host::graphics::SceneEncoder;
+use frontier_wasm_host::host::{HostCtx, Phase};
+use frontier_wasm_host::model::LogicalSize;
+use frontier_wasm_host::scroll::ScrollInput;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    EnterFrame,
+    EnterEvent,
+    ExitPhase,
+    Clear([f32; 4]),
+    FillRect([f32; 2], [f32; 2], [f32; 4]),
+    DrawText(String, [f32; 2], f32, [f32; 4]),
+    DrawTextWithFont(String, [f32; 2], f32, [f32; 4], u8),
+    DrawTextWithDirection(String, [f32; 2], f32, [f32; 4], u8, u8),
+    DrawTextOriented(String, [f32; 2], f32, [f32; 4], u8, u8, bool, f32),
+    DrawRichText(Vec<(String, [f32; 4], f32, u8)>, [f32; 2], f32, u8),
+    DrawTextGrid(Vec<(String, [f32; 4], Option<[f32; 4]>)>, [f32; 2], f32, u32),
+    TextGridCell(f32),
+    DrawMarkdown(String, [f32; 2], [f32; 2], f32, [f32; 4]),
+    DrawQr(String, [f32; 2], [f32; 2]),
+    DrawBars(Vec<f32>, [f32; 2], [f32; 2], [f32; 4], [f32; 4]),
+    DrawPoints(Vec<[f32; 2]>, f32, [f32; 4]),
+    DrawHeatmap(u32, Vec<f32>, [f32; 2], [f32; 2], [f32; 2], Vec<[f32; 4]>),
+    SetViewport(f32, f32),
+    DefineScrollRegion(u8, [f32; 2], [f32; 2], [f32; 2]),
+    RemoveScrollRegion(u8),
+    ScrollOffset(u8),
+    SetScrollOffset(u8, [f32; 2]),
+    PushScrollRegion(u8),
+    PopScrollRegion,
+    Wheel([f32; 2], [f32; 2]),
+    Drag(u8, u8, [f32; 2], f64),
+    AdvanceScrolling(f32),
+    KineticPress(u8, [f32; 2], [f32; 2], [f32; 2], [f32; 2]),
+    KineticDrag(u8, [f32; 2]),
+    KineticRelease(u8),
+    KineticStop(u8),
+    Animate(u8, f32, f32, f32, u8),
+    Spring(u8, f32, f32, f32),
+    AnimatedValue(u8),
+    StopAnimating(u8),
+    AdvanceTweens(f32),
+    HitTestText(String, f32, [f32; 2], u8),
+    CaretRect(String, f32, u32, u8),
+    CreateImage(u8, u8, Vec<u8>),
+    DropImage(u32),
+    LoadSvg(String),
+    PictureSize(u32),
+    DropPicture(u32),
+    DrawPicture(u32, [f32; 2], [f32; 2]),
+    LoadLottie(String),
+    AnimationInfo(u32),
+    DropAnimation(u32),
+    DrawAnimation(u32, f64, [f32; 2], [f32; 2]),
+    DecodeImage(Vec<u8>),
+    AnimatedImageInfo(u32),
+    DropAnimatedImage(u32),
+    DrawAnimatedImage(u32, u32, [f32; 2], [f32; 2], [f32; 4], u8, f32),
+    CreatePixelBuffer(u16, u16),
+    WritePixelBuffer(u32, u32, u32, u32, Vec<u8>),
+    PresentPixelBuffer(u32, [f32; 2], [f32; 2]),
+    DropPixelBuffer(u32),
+    CreateEmitter([f32; 2], [f32; 8], u32, [f32; 2], [f32; 4], [f32; 4]),
+    ConfigureEmitter(u32, [f32; 2], [f32; 8], [f32; 2], [f32; 4], [f32; 4]),
+    BurstEmitter(u32, u32),
+    EmitterParticleCount(u32),
+    DrawEmitter(u32),
+    DropEmitter(u32),
+    AdvanceParticles(f32),
+    RegisterShader(String),
+    DropShader(u32),
+    PushShaderLayer(u32, Vec<f32>),
+    PopShaderLayer,
+    ListCameras,
+    OpenCamera(u32),
+    CameraStatus(u32),
+    CameraFrameInfo(u32),
+    DrawCamera(u32, [f32; 2], [f32; 2], [f32; 4], u8, f32),
+    CloseCamera(u32),
+    DrawImage(u32, [f32; 2], [f32; 2], [f32; 4], u8, f32),
+    FillPattern([f32; 2], [f32; 2], u32, [f32; 2], u8, [f32; 4], u8, f32),
+    ExportFrame(bool),
+    RequestFrame,
+    Log(String),
+    SetWindowOpacity(f32),
+    SetClickThrough(bool),
+    SetWindowPosition(i32, i32),
+    RequestQuit,
+    FormatNumber(f64, u8),
+    FormatCurrency(f64, String),
+    FormatDatetime(i64, u8),
+    UtcOffsetAt(i64),
+    PerfMark(String),
+    PerfMeasure(String, String),
+    TakeFrame,
+}
+
+fn vec2([x, y]: [f32; 2]) -> Vec2 {
+    Vec2 { x, y }
+}
+
+fn font(font: u8) -> FontFamily {
+    match font % 3 {
+        0 => FontFamily::Sans,
+        1 => FontFamily::SansBold,
+        _ => FontFamily::Monospace,
+    }
+}
+
+fn direction(direction: u8) -> TextDirection {
+    match direction % 3 {
+        0 => TextDirection::Auto,
+        1 => TextDirection::Ltr,
+        _ => TextDirection::Rtl,
+    }
+}
+
+fn easing(easing: u8) -> Easing {
+    match easing % 5 {
+        0 => Easing::Linear,
+        1 => Easing::Ease,
+        2 => Easing::EaseIn,
+        3 => Easing::EaseOut,
+        _ => Easing::EaseInOut,
+    }
+}
+
+fn color([r, g, b, a]: [f32; 4]) -> Color {
+    Color { r, g, b, a }
+}
+
+fn style(tint: [f32; 4], filter: u8, opacity: f32) -> ImageStyle {
+    let filter = match filter % 3 {
+        0 => ImageFilter::None,
+        1 => ImageFilter::Grayscale,
+        _ => ImageFilter::Sepia,
+    };
+    ImageStyle {
+        tint: color(tint),
+        filter,
+        opacity,
+    }
+}
+
+fn emitter(
+    position: [f32; 2],
+    [rate, lifetime_ms, angle, spread, speed, speed_jitter, start_size, end_size]: [f32; 8],
+    max_particles: u32,
+    gravity: [f32; 2],
+    start_color: [f32; 4],
+    end_color: [f32; 4],
+) -> EmitterConfig {
+    EmitterConfig {
+        position: vec2(position),
+        rate,
+        // Keep the simulation small enough for the fuzzer to stay fast.
+        max_particles: max_particles % 1024,
+        lifetime_ms,
+        angle,
+        spread,
+        speed,
+        speed_jitter,
+        gravity: vec2(gravity),
+        start_size,
+        end_size,
+        start_color: color(start_color),
+        end_color: color(end_color),
+    }
+}
+
+thread_local! {
+    static ENCODER: RefCell<SceneEncoder> =
+        RefCell::new(SceneEncoder::new(1.0));
+}
+
+fn encode(host: &mut HostCtx) {
+    let frame = host.take_frame_output();
+    let _ = frame.contains_text("");
+    ENCODER.with(|encoder| {
+        let mut encoder = encoder.borrow_mut();
+        encoder.reset();
+        encoder.encode_commands(&frame.commands);
+    });
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut host = HostCtx::new();
+    for op in ops {
+        let result = match op {
+            Op::EnterFrame => {
+                host.enter_phase(Phase::Frame);
+                Ok(())
+            }
+            Op::EnterEvent => {
+                host.enter_phase(Phase::Event);
+                Ok(())
+            }
+            Op::ExitPhase => {
+                host.exit_phase();
+                Ok(())
+            }
+            Op::Clear(c) => host.clear(color(c)),
+            Op::FillRect(origin, size, c) => host.fill_rect(vec2(origin), vec2(size), color(c)),
+            Op::DrawText(text, origin, size, c) => {
+                host.draw_text(text, vec2(origin), size, color(c))
+            }
+            Op::DrawTextWithFont(text, origin, size, c, family) => {
+                host.draw_text_with_font(text, vec2(origin), size, color(c), font(family))
+            }
+            Op::DrawTextWithDirection(text, origin, size, c, family, dir) => host
+                .draw_text_with_direction(
+                    text,
+                    vec2(origin),
+                    size,
+                    color(c),
+                    font(family),
+                    direction(dir),
+                ),
+            Op::DrawTextOriented(text, origin, size, c, family, dir, vertical, rotation) => {
+                let mode = if vertical {
+                    WritingMode::VerticalRl
+                } else {
+                    WritingMode::HorizontalTb
+                };
+                host.draw_text_oriented(
+                    text,
+                    vec2(origin),
+                    size,
+                    color(c),
+                    font(family),
+                    direction(dir),
+                    mode,
+                    rotation,
+                )
+            }
+            Op::DrawRichText(spans, origin, max_width, dir) => {
+                let spans = spans
+                    .into_iter()
+                    .map(|(text, c, size, family)| StyledSpan {
+                        text,
+                        color: color(c),
+                        size,
+                        font: font(family),
+                    })
+                    .collect();
+                host.draw_rich_text(spans, vec2(origin), max_width, direction(dir))
+            }
+            Op::DrawTextGrid(cells, origin, size, tab_width) => {
+                let cells = cells
+                    .into_iter()
+                    .map(|(text, fg, bg)| GridCell {
+                        text,
+                        fg: color(fg),
+                        bg: bg.map(color),
+                    })
+                    .collect();
+                host.draw_text_grid(cells, vec2(origin), size, tab_width)
+            }
+            Op::TextGridCell(size) => host.text_grid_cell(size).map(drop),
+            Op::DrawMarkdown(source, origin, size, base_size, c) => host.draw_markdown(
+                source,
+                Rect {
+                    origin: vec2(origin),
+                    size: vec2(size),
+                },
+                base_size,
+                color(c),
+            ),
+            Op::DrawQr(data, origin, size) => host.draw_qr(
+                data,
+                Rect {
+                    origin: vec2(origin),
+                    size: vec2(size),
+                },
+            ),
+            Op::DrawBars(values, origin, size, c, [min, max, baseline, gap]) => host.draw_bars(
+                values,
+                Rect {
+                    origin: vec2(origin),
+                    size: vec2(size),
+                },
+                BarStyle {
+                    color: color(c),
+                    min,
+                    max,
+                    baseline,
+                    gap,
+                },
+            ),
+            Op::DrawPoints(points, radius, c) => {
+                host.draw_points(points.into_iter().map(vec2).collect(), radius, color(c))
+            }
+            Op::DrawHeatmap(columns, values, [min, max], origin, size, colormap) => host
+                .draw_heatmap(
+                    Heatmap {
+                        columns,
+                        values,
+                        min,
+                        max,
+                    },
+                    Rect {
+                        origin: vec2(origin),
+                        size: vec2(size),
+                    },
+                    colormap.into_iter().map(color).collect(),
+                ),
+            Op::SetViewport(width, height) => {
+                host.set_viewport(LogicalSize {
+                    width,
+                    height,
+                    scale_factor: 1.0,
+                });
+                Ok(())
+            }
+            Op::DefineScrollRegion(id, content_size, origin, size) => host
+                .define_scroll_region(
+                    id.into(),
+                    vec2(content_size),
+                    Rect {
+                        origin: vec2(origin),
+                        size: vec2(size),
+                    },
+                )
+                .map(drop),
+            Op::RemoveScrollRegion(id) => host.remove_scroll_region(id.into()),
+            Op::ScrollOffset(id) => host.scroll_offset(id.into()).map(drop),
+            Op::SetScrollOffset(id, offset) => host.set_scroll_offset(id.into(), vec2(offset)),
+            Op::PushScrollRegion(id) => host.push_scroll_region(id.into()),
+            Op::PopScrollRegion => host.pop_scroll_region(),
+            Op::Wheel(position, delta) => {
+                host.scroll_input(ScrollInput::Wheel {
+                    position: geometry::Vec2::from(position),
+                    delta: geometry::Vec2::from(delta),
+                });
+                Ok(())
+            }
+            Op::Drag(kind, pointer, position, time_ms) => {
+                let (pointer, position) = (pointer.into(), geometry::Vec2::from(position));
+                host.scroll_input(match kind % 3 {
+                    0 => ScrollInput::DragStart {
+                        pointer,
+                        position,
+                        time_ms,
+                    },
+                    1 => ScrollInput::DragMove {
+                        pointer,
+                        position,
+                        time_ms,
+                    },
+                    _ => ScrollInput::DragEnd { pointer, time_ms },
+                });
+                Ok(())
+            }
+            Op::AdvanceScrolling(dt_ms) => {
+                host.advance_scrolling(dt_ms);
+                Ok(())
+            }
+            Op::KineticPress(id, position, offset, min, max) => host
+                .kinetic_press(id.into(), vec2(position), vec2(offset), vec2(min), vec2(max))
+                .map(drop),
+            Op::KineticDrag(id, position) => host.kinetic_drag(id.into(), vec2(position)).map(drop),
+            Op::KineticRelease(id) => host.kinetic_release(id.into()),
+            Op::KineticStop(id) => host.kinetic_stop(id.into()).map(drop),
+            Op::Animate(id, from, to, duration_ms, curve) => host
+                .animate(id.into(), from, to, duration_ms, easing(curve))
+                .map(drop),
+            Op::Spring(id, target, stiffness, damping) => host
+                .spring(id.into(), target, stiffness, damping)
+                .map(drop),
+            Op::AnimatedValue(id) => host.animated_value(id.into()).map(drop),
+            Op::StopAnimating(id) => host.stop_animating(id.into()).map(drop),
+            Op::AdvanceTweens(dt_ms) => {
+                host.advance_tweens(dt_ms);
+                Ok(())
+            }
+            Op::HitTestText(text, size, point, dir) => host
+                .hit_test_text(text, size, vec2(point), direction(dir))
+                .map(drop),
+            Op::CaretRect(text, size, index, dir) => host
+                .caret_rect(text, size, index, direction(dir))
+                .map(drop),
+            Op::CreateImage(width, height, rgba) => host
+                .create_image(width.into(), height.into(), rgba)
+                .map(drop),
+            Op::DropImage(image) => host.drop_image(image),
+            Op::LoadSvg(source) => host.load_svg(source.into_bytes()).map(drop),
+            Op::PictureSize(picture) => host.picture_size(picture).map(drop),
+            Op::DropPicture(picture) => host.drop_picture(picture),
+            Op::DrawPicture(picture, origin, size) => {
+                host.draw_picture(picture, vec2(origin), vec2(size))
+            }
+            Op::LoadLottie(source) => host.load_lottie(source.into_bytes()).map(drop),
+            Op::AnimationInfo(animation) => host.animation_info(animation).map(drop),
+            Op::DropAnimation(animation) => host.drop_animation(animation),
+            Op::DrawAnimation(animation, time_ms, origin, size) => {
+                host.draw_animation(animation, time_ms, vec2(origin), vec2(size))
+            }
+            Op::DecodeImage(bytes) => host.decode_image(bytes).map(drop),
+            Op::AnimatedImageInfo(image) => host.animated_image_info(image).map(drop),
+            Op::DropAnimatedImage(image) => host.drop_animated_image(image),
+            Op::DrawAnimatedImage(image, frame, origin, size, tint, filter, opacity) => host
+                .draw_animated_image(
+                    image,
+                    frame,
+                    vec2(origin),
+                    vec2(size),
+                    style(tint, filter, opacity),
+                ),
+            Op::CreatePixelBuffer(width, height) => host
+                .create_pixel_buffer(width.into(), height.into())
+                .map(drop),
+            Op::WritePixelBuffer(buffer, x, y, width, rgba) => host
+                .write_pixel_buffer(buffer, x, y, width, rgba)
+                .map(drop),
+            Op::PresentPixelBuffer(buffer, origin, size) => {
+                host.present_pixel_buffer(buffer, vec2(origin), vec2(size))
+            }
+            Op::DropPixelBuffer(buffer) => host.drop_pixel_buffer(buffer),
+            Op::CreateEmitter(position, values, max, gravity, start, end) => host
+                .create_emitter(emitter(position, values, max, gravity, start, end))
+                .map(drop),
+            Op::ConfigureEmitter(id, position, values, gravity, start, end) => host
+                .configure_emitter(id, emitter(position, values, 1, gravity, start, end))
+                .map(drop),
+            Op::BurstEmitter(id, count) => host.burst_emitter(id, count),
+            Op::EmitterParticleCount(id) => host.emitter_particle_count(id).map(drop),
+            Op::DrawEmitter(id) => host.draw_emitter(id),
+            Op::DropEmitter(id) => host.drop_emitter(id),
+            Op::AdvanceParticles(dt_ms) => {
+                host.advance_particles(dt_ms);
+                Ok(())
+            }
+            Op::RegisterShader(source) => host.register_shader(source).map(drop),
+            Op::DropShader(shader) => host.drop_shader(shader),
+            Op::PushShaderLayer(shader, params) => host.push_shader_layer(shader, params),
+            Op::PopShaderLayer => host.pop_shader_layer(),
+            Op::ListCameras => host.list_cameras().map(drop),
+            Op::OpenCamera(device) => host.open_camera(device).map(drop),
+            Op::CameraStatus(camera) => host.camera_status(camera).map(drop),
+            Op::CameraFrameInfo(camera) => host.camera_frame_info(camera).map(drop),
+            Op::DrawCamera(camera, origin, size, tint, filter, opacity) => host.draw_camera(
+                camera,
+                vec2(origin),
+                vec2(size),
+                style(tint, filter, opacity),
+            ),
+            Op::CloseCamera(camera) => host.close_camera(camera),
+            Op::DrawImage(image, origin, size, tint, filter, opacity) => host.draw_image(
+                image,
+                vec2(origin),
+                vec2(size),
+                style(tint, filter, opacity),
+            ),
+            Op::FillPattern(origin, size, image, tile, repeat, tint, filter, opacity) => {
+                let repeat = match repeat % 3 {
+                    0 => PatternRepeat::Repeat,
+                    1 => PatternRepeat::RepeatX,
+                    _ => PatternRepeat::Mirror,
+                };
+                host.fill_pattern(
+                    vec2(origin),
+                    vec2(size),
+                    image,
+                    vec2(tile),
+                    repeat,
+                    style(tint, filter, opacity),
+                )
+            }
+            Op::ExportFrame(pdf) => host.export_frame(if pdf {
+                ExportFormat::Pdf
+            } else {
+                ExportFormat::Svg
+            }),
+            Op::RequestFrame => host.request_frame(),
+            Op::Log(message) => host.log(LogLevel::Debug, message),
+            Op::SetWindowOpacity(opacity) => host.set_window_opacity(opacity),
+            Op::SetClickThrough(enabled) => host.set_click_through(enabled),
+            Op::SetWindowPosition(x, y) => host.set_window_position(WindowPosition { x, y }),
+            Op::RequestQuit => host.request_quit(),
+            Op::FormatNumber(value, digits) => host.format_number(value, digits).map(drop),
+            Op::FormatCurrency(value, currency) => {
+                host.format_currency(value, currency).map(drop)
+            }
+            Op::FormatDatetime(unix_ms, style) => {
+                let style = match style % 3 {
+                    0 => DatetimeStyle::Date,
+                    1 => DatetimeStyle::Time,
+                    _ => DatetimeStyle::DateTime,
+                };
+                host.format_datetime(unix_ms, style).map(drop)
+            }
+            Op::UtcOffsetAt(unix_ms) => host.utc_offset_at(unix_ms).map(drop),
+            Op::PerfMark(name) => host.perf_mark(name),
+            Op::PerfMeasure(name, start) => host.perf_measure(name, start),
+            Op::TakeFrame => {
+                encode(&mut host);
+                Ok(())
+            }
+        };
+        // A host call error traps the guest, so nothing after it would run.
+        if result.is_err() {
+            break;
+        }
+    }
+    encode(&mut host);
+    let _ = host.take_window_requests();
+    let _ = host.take_perf_measures();
+});